@@ -81,7 +81,7 @@ async fn main() {
 	for item in &expanded_doc {
 		if let Object::Node(item) = item.as_ref() {
 			for entries in item.get(Vocab::Entries) {
-				if let Object::List(entries) = entries.as_ref() {
+				if let Object::List(entries, _) = entries.as_ref() {
 					for entry in entries {
 						if let Object::Node(entry) = entry.as_ref() {
 							generate_test(entry);
@@ -128,12 +128,17 @@ fn generate_test(entry: &Node<Value, Id>) {
 
 			for spec_version in option.get(Vocab::SpecVersion) {
 				if let Some(spec_version) = spec_version.as_str() {
-					if spec_version != "json-ld-1.1" {
-						info!(
-							"skipping test {} (unsupported json-ld version {})",
-							url, spec_version
-						);
-						return;
+					match spec_version {
+						"json-ld-1.1" => (),
+						// JSON-LD 1.0 tests are run in json-ld-1.0 processing mode.
+						"json-ld-1.0" => processing_mode = ProcessingMode::JsonLd1_0,
+						_ => {
+							info!(
+								"skipping test {} (unsupported json-ld version {})",
+								url, spec_version
+							);
+							return;
+						}
 					}
 				}
 			}
@@ -54,7 +54,7 @@ async fn main() {
 	for object in expanded_doc {
 		if let Object::Node(node) = object.as_ref() {
 			for entries in node.get(Vocab::Entries) {
-				if let Object::List(entries) = entries.as_ref() {
+				if let Object::List(entries, _) = entries.as_ref() {
 					for entry in entries {
 						if let Object::Node(entry) = entry.as_ref() {
 							let name = entry.get(Vocab::Name).next().unwrap().as_str().unwrap();
@@ -0,0 +1,5 @@
+#![no_main]
+use json_ld_fuzz::Input;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: Input| json_ld_fuzz::context(&input));
@@ -0,0 +1,332 @@
+//! Structured fuzzing harness.
+//!
+//! Fuzzing inputs are not random bytes but JSON-LD documents and contexts
+//! built from a vocabulary of keywords, terms, IRIs and language tags (see [`Input`]),
+//! so that the fuzzer quickly reaches the interesting parts of the algorithms.
+//! Remote contexts are served from memory by a [`Loader`].
+//!
+//! Each fuzz target calls one of the [`expand`], [`compact`] and [`context`] functions,
+//! also used to replay the minimized crashers of the `regressions` directory.
+use arbitrary::Arbitrary;
+use futures::executor::block_on;
+use futures::future::{BoxFuture, FutureExt};
+use iref::{Iri, IriBuf};
+use json_ld::{
+	compaction,
+	context::{self, Local, ProcessingOptions},
+	expansion, loader, Document, Error, ErrorCode, ExpandedDocument, Limits, ProcessingMode,
+	RemoteDocument,
+};
+use serde_json::Value;
+
+/// Maximum nesting depth of the generated JSON values.
+const MAX_DEPTH: usize = 16;
+
+/// Maximum number of remote contexts.
+const MAX_CONTEXTS: usize = 4;
+
+/// Prefix of the URLs of the remote contexts.
+const CONTEXT_URL: &str = "http://context.example/";
+
+/// Base IRI of the document, when enabled.
+const BASE: &str = "http://example.org/base/doc";
+
+const KEYWORDS: &[&str] = &[
+	"@base",
+	"@container",
+	"@context",
+	"@direction",
+	"@graph",
+	"@id",
+	"@import",
+	"@included",
+	"@index",
+	"@json",
+	"@language",
+	"@list",
+	"@nest",
+	"@none",
+	"@prefix",
+	"@propagate",
+	"@protected",
+	"@reverse",
+	"@set",
+	"@type",
+	"@value",
+	"@version",
+	"@vocab",
+	"@ignoreMe",
+];
+
+const TERMS: &[&str] = &[
+	"a", "b", "c", "name", "knows", "ex", "ex:a", "ex:b", "_:b0", "",
+];
+
+const IRIS: &[&str] = &[
+	"http://example.org/",
+	"http://example.org/a",
+	"http://example.org/b",
+	"http://example.org/vocab#",
+	"http://www.w3.org/2001/XMLSchema#integer",
+	"http://www.w3.org/1999/02/22-rdf-syntax-ns#JSON",
+	"relative",
+	"#fragment",
+	"../parent",
+	"//authority",
+];
+
+const LANGUAGES: &[&str] = &["en", "en-US", "fr", "invalid tag", "ltr", "rtl", "1.1"];
+
+fn pick(items: &[&str], i: u8) -> String {
+	items[i as usize % items.len()].to_string()
+}
+
+/// Generated string, either a key or a value.
+#[derive(Arbitrary, Debug)]
+pub enum Str {
+	Keyword(u8),
+	Term(u8),
+	Iri(u8),
+	RemoteContext(u8),
+	Language(u8),
+	Any(String),
+}
+
+impl Str {
+	pub fn build(&self) -> String {
+		match self {
+			Self::Keyword(i) => pick(KEYWORDS, *i),
+			Self::Term(i) => pick(TERMS, *i),
+			Self::Iri(i) => pick(IRIS, *i),
+			Self::RemoteContext(i) => format!("{}{}", CONTEXT_URL, *i as usize % MAX_CONTEXTS),
+			Self::Language(i) => pick(LANGUAGES, *i),
+			Self::Any(s) => s.clone(),
+		}
+	}
+}
+
+/// Generated JSON value.
+#[derive(Arbitrary, Debug)]
+pub enum Json {
+	Null,
+	Boolean(bool),
+	Integer(i64),
+	Float(f64),
+	String(Str),
+	Array(Vec<Json>),
+	Object(Vec<(Str, Json)>),
+}
+
+impl Json {
+	/// Builds the JSON value, replacing values nested too deep by `null`.
+	pub fn build(&self) -> Value {
+		self.build_nested(0)
+	}
+
+	fn build_nested(&self, depth: usize) -> Value {
+		if depth > MAX_DEPTH {
+			return Value::Null;
+		}
+
+		match self {
+			Self::Null => Value::Null,
+			Self::Boolean(b) => Value::Bool(*b),
+			Self::Integer(i) => Value::from(*i),
+			Self::Float(f) => serde_json::Number::from_f64(*f).map_or(Value::Null, Value::Number),
+			Self::String(s) => Value::String(s.build()),
+			Self::Array(items) => {
+				Value::Array(items.iter().map(|i| i.build_nested(depth + 1)).collect())
+			}
+			Self::Object(entries) => Value::Object(
+				entries
+					.iter()
+					.map(|(key, value)| (key.build(), value.build_nested(depth + 1)))
+					.collect(),
+			),
+		}
+	}
+}
+
+/// Fuzzing input.
+#[derive(Arbitrary, Debug)]
+pub struct Input {
+	/// Processed document, or context for the `context` target.
+	pub document: Json,
+
+	/// Remote contexts, served at `http://context.example/{index}`.
+	pub contexts: Vec<Json>,
+
+	/// Context used by the `compact` target.
+	pub compaction_context: Json,
+
+	/// Use the json-ld-1.0 processing mode.
+	pub json_ld_1_0: bool,
+
+	/// Resolve relative IRIs against a base IRI.
+	pub base: bool,
+
+	/// Compaction `compactArrays` option.
+	pub compact_arrays: bool,
+
+	/// Compaction `compactToRelative` option.
+	pub compact_to_relative: bool,
+
+	/// `ordered` option.
+	pub ordered: bool,
+}
+
+impl Input {
+	fn processing_mode(&self) -> ProcessingMode {
+		if self.json_ld_1_0 {
+			ProcessingMode::JsonLd1_0
+		} else {
+			ProcessingMode::JsonLd1_1
+		}
+	}
+
+	fn base(&self) -> Option<Iri<'static>> {
+		if self.base {
+			Some(Iri::new(BASE).unwrap())
+		} else {
+			None
+		}
+	}
+
+	fn limits(&self) -> Limits {
+		Limits {
+			max_depth: Some(MAX_DEPTH + 1),
+			max_values: Some(10_000),
+			max_string_length: Some(4096),
+			max_remote_contexts: Some(MAX_CONTEXTS * 2),
+		}
+	}
+
+	fn processing_options(&self) -> ProcessingOptions {
+		ProcessingOptions {
+			processing_mode: self.processing_mode(),
+			limits: self.limits(),
+			..ProcessingOptions::default()
+		}
+	}
+
+	/// Returns the loader serving the remote contexts of this input.
+	pub fn loader(&self) -> Loader {
+		Loader {
+			documents: self
+				.contexts
+				.iter()
+				.take(MAX_CONTEXTS)
+				.enumerate()
+				.map(|(i, context)| {
+					let url = IriBuf::new(&format!("{}{}", CONTEXT_URL, i)).unwrap();
+					(url, serde_json::json!({ "@context": context.build() }))
+				})
+				.collect(),
+		}
+	}
+}
+
+/// In-memory loader.
+pub struct Loader {
+	documents: Vec<(IriBuf, Value)>,
+}
+
+impl loader::NonBlocking for Loader {}
+
+impl json_ld::Loader for Loader {
+	type Document = Value;
+
+	fn id(&self, iri: Iri<'_>) -> Option<loader::Id> {
+		self.documents
+			.iter()
+			.position(|(url, _)| url.as_iri() == iri)
+			.map(loader::Id::new)
+	}
+
+	fn iri(&self, id: loader::Id) -> Option<Iri<'_>> {
+		self.documents.get(id.unwrap()).map(|(url, _)| url.as_iri())
+	}
+
+	fn load<'a>(&'a mut self, url: Iri<'_>) -> BoxFuture<'a, Result<RemoteDocument<Value>, Error>> {
+		let result = match self.id(url) {
+			Some(id) => {
+				let (url, doc) = &self.documents[id.unwrap()];
+				Ok(RemoteDocument::new(doc.clone(), url.clone(), id))
+			}
+			None => Err(ErrorCode::LoadingDocumentFailed.into()),
+		};
+
+		async move { result }.boxed()
+	}
+}
+
+fn no_metadata<M>(_: Option<&M>) {}
+
+fn expand_document(input: &Input, loader: &mut Loader) -> Option<ExpandedDocument<Value, IriBuf>> {
+	let options = expansion::Options {
+		processing_mode: input.processing_mode(),
+		ordered: input.ordered,
+		limits: input.limits(),
+		..expansion::Options::default()
+	};
+
+	let document = input.document.build();
+	block_on(
+		document.expand_with_base::<context::Json<Value, IriBuf>, _>(input.base(), loader, options),
+	)
+	.ok()
+}
+
+/// Expands the input document.
+pub fn expand(input: &Input) {
+	let mut loader = input.loader();
+	expand_document(input, &mut loader);
+}
+
+/// Expands the input document and compacts it with the compaction context.
+pub fn compact(input: &Input) {
+	let mut loader = input.loader();
+	let expanded = match expand_document(input, &mut loader) {
+		Some(expanded) => expanded,
+		None => return,
+	};
+
+	let local_context = input.compaction_context.build();
+	let context = match block_on(local_context.process_with(
+		&context::Json::<Value, IriBuf>::new(input.base()),
+		&mut loader,
+		input.base(),
+		input.processing_options(),
+	)) {
+		Ok(context) => context.owned(),
+		Err(_) => return,
+	};
+
+	let options = compaction::Options {
+		processing_mode: input.processing_mode(),
+		compact_arrays: input.compact_arrays,
+		compact_to_relative: input.compact_to_relative,
+		ordered: input.ordered,
+		..compaction::Options::default()
+	};
+
+	let _: Result<Value, Error> = block_on(expanded.compact_document(
+		&context,
+		&mut loader,
+		options,
+		no_metadata,
+		no_metadata,
+	));
+}
+
+/// Processes the input document as a context.
+pub fn context(input: &Input) {
+	let mut loader = input.loader();
+	let local_context = input.document.build();
+	let _ = block_on(local_context.process_with(
+		&context::Json::<Value, IriBuf>::new(input.base()),
+		&mut loader,
+		input.base(),
+		input.processing_options(),
+	));
+}
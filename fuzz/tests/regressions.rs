@@ -0,0 +1,39 @@
+//! Replays the minimized crashers of the `regressions` directory.
+use arbitrary::{Arbitrary, Unstructured};
+use json_ld_fuzz::Input;
+use std::{fs, path::Path};
+
+fn replay(target: &str, run: fn(&Input)) {
+	let dir = Path::new(env!("CARGO_MANIFEST_DIR"))
+		.join("regressions")
+		.join(target);
+
+	for entry in fs::read_dir(dir).unwrap() {
+		let path = entry.unwrap().path();
+		let hidden = path
+			.file_name()
+			.map_or(true, |name| name.to_string_lossy().starts_with('.'));
+
+		if !hidden {
+			let data = fs::read(&path).unwrap();
+			if let Ok(input) = Input::arbitrary_take_rest(Unstructured::new(&data)) {
+				run(&input)
+			}
+		}
+	}
+}
+
+#[test]
+fn expand() {
+	replay("expand", json_ld_fuzz::expand)
+}
+
+#[test]
+fn compact() {
+	replay("compact", json_ld_fuzz::compact)
+}
+
+#[test]
+fn context() {
+	replay("context", json_ld_fuzz::context)
+}
@@ -0,0 +1,171 @@
+use crate::util::collections::HashMap;
+use crate::{object, ExpandedDocument, Id, Indexed, Node, Object, Reference};
+use generic_json::JsonHash;
+
+/// Edge between two identified nodes of an [`Adjacency`] index.
+pub struct Edge<'a, T: Id> {
+	/// Source node identifier.
+	pub subject: &'a Reference<T>,
+
+	/// Property.
+	pub property: &'a Reference<T>,
+
+	/// Target node identifier.
+	pub object: &'a Reference<T>,
+}
+
+impl<'a, T: Id> Clone for Edge<'a, T> {
+	#[inline(always)]
+	fn clone(&self) -> Self {
+		*self
+	}
+}
+
+impl<'a, T: Id> Copy for Edge<'a, T> {}
+
+/// Adjacency index of an expanded document.
+///
+/// Indexes the edges between the identified nodes of a document
+/// (including nested nodes), in both directions,
+/// so that the incoming edges of a node can be found without scanning the whole document.
+/// Reverse properties are indexed as edges in the forward direction:
+/// a node `a` with a reverse property `p` pointing to a node `b` produces the edge
+/// `b -p-> a`.
+/// List items are followed, and nodes without identifier are ignored.
+///
+/// It is created with [`ExpandedDocument::adjacency`].
+pub struct Adjacency<'a, J: JsonHash, T: Id> {
+	nodes: HashMap<&'a Reference<T>, Vec<&'a Node<J, T>>>,
+	outgoing: HashMap<&'a Reference<T>, Vec<Edge<'a, T>>>,
+	incoming: HashMap<&'a Reference<T>, Vec<Edge<'a, T>>>,
+}
+
+impl<'a, J: JsonHash, T: Id> Adjacency<'a, J, T> {
+	/// Builds the adjacency index of the given objects, and their nested nodes.
+	pub fn new<I: IntoIterator<Item = &'a Indexed<Object<J, T>>>>(objects: I) -> Self {
+		let mut result = Self {
+			nodes: HashMap::new(),
+			outgoing: HashMap::new(),
+			incoming: HashMap::new(),
+		};
+
+		for node in object::Traverse::new(objects) {
+			if let Some(id) = node.id() {
+				result.nodes.entry(id).or_default().push(node);
+
+				for (property, objects) in node.properties() {
+					for object in objects {
+						result.add_object(id, property, object)
+					}
+				}
+
+				for (property, subjects) in node.reverse_properties() {
+					for subject in subjects {
+						if let Some(subject) = subject.id() {
+							result.add(subject, property, id)
+						}
+					}
+				}
+			}
+		}
+
+		result
+	}
+
+	fn add_object(
+		&mut self,
+		subject: &'a Reference<T>,
+		property: &'a Reference<T>,
+		object: &'a Object<J, T>,
+	) {
+		match object {
+			Object::Node(node) => {
+				if let Some(id) = node.id() {
+					self.add(subject, property, id)
+				}
+			}
+			Object::List(items, _) => {
+				for item in items {
+					self.add_object(subject, property, item)
+				}
+			}
+			Object::Value(..) => (),
+		}
+	}
+
+	fn add(
+		&mut self,
+		subject: &'a Reference<T>,
+		property: &'a Reference<T>,
+		object: &'a Reference<T>,
+	) {
+		let edge = Edge {
+			subject,
+			property,
+			object,
+		};
+
+		self.outgoing.entry(subject).or_default().push(edge);
+		self.incoming.entry(object).or_default().push(edge);
+	}
+
+	/// Returns the node objects with the given identifier.
+	///
+	/// The same node may be described by multiple node objects across the document.
+	#[inline(always)]
+	pub fn nodes(&self, id: &Reference<T>) -> &[&'a Node<J, T>] {
+		self.nodes.get(id).map(Vec::as_slice).unwrap_or_default()
+	}
+
+	/// Returns an iterator over the identifiers of the indexed nodes.
+	///
+	/// Nodes only appearing as the target of an edge are not included.
+	#[inline(always)]
+	pub fn ids(&self) -> impl Iterator<Item = &'a Reference<T>> + '_ {
+		self.nodes.keys().cloned()
+	}
+
+	/// Returns the edges starting from the given node.
+	#[inline(always)]
+	pub fn outgoing_edges(&self, id: &Reference<T>) -> &[Edge<'a, T>] {
+		self.outgoing.get(id).map(Vec::as_slice).unwrap_or_default()
+	}
+
+	/// Returns the edges pointing to the given node.
+	#[inline(always)]
+	pub fn incoming_edges(&self, id: &Reference<T>) -> &[Edge<'a, T>] {
+		self.incoming.get(id).map(Vec::as_slice).unwrap_or_default()
+	}
+
+	/// Returns an iterator over the nodes pointing to the given node through the given property.
+	pub fn subjects<'s>(
+		&'s self,
+		property: &'s Reference<T>,
+		object: &Reference<T>,
+	) -> impl 's + Iterator<Item = &'a Reference<T>> {
+		self.incoming_edges(object)
+			.iter()
+			.filter(move |edge| edge.property == property)
+			.map(|edge| edge.subject)
+	}
+
+	/// Returns an iterator over the nodes the given node points to through the given property.
+	pub fn objects<'s>(
+		&'s self,
+		subject: &Reference<T>,
+		property: &'s Reference<T>,
+	) -> impl 's + Iterator<Item = &'a Reference<T>> {
+		self.outgoing_edges(subject)
+			.iter()
+			.filter(move |edge| edge.property == property)
+			.map(|edge| edge.object)
+	}
+}
+
+impl<J: JsonHash, T: Id> ExpandedDocument<J, T> {
+	/// Builds the adjacency index of this document.
+	#[inline(always)]
+	pub fn adjacency(&self) -> Adjacency<'_, J, T> {
+		Adjacency::new(self.iter())
+	}
+}
@@ -0,0 +1,444 @@
+//! Command line interface to the JSON-LD processing algorithms.
+//!
+//! Reads a JSON-LD document from a file (or the standard input)
+//! and writes the result on the standard output.
+//! Run `json-ld --help` for the list of commands and options.
+
+use futures::executor::block_on;
+use iref::{Iri, IriBuf};
+use json_ld::{
+	compaction,
+	context::{self, Local, ProcessingOptions},
+	expansion,
+	flattening::NodeMapGraph,
+	rdf,
+	util::AsJson,
+	BlankIdSequence, Document, ExpandedDocument, FsLoader, Indexed, Limits, Node, Object,
+	ProcessingMode,
+};
+use serde_json::Value;
+use std::convert::TryFrom;
+use std::io::Read;
+use std::path::PathBuf;
+use std::{fmt, fs, io, process};
+
+type Context = context::Json<Value, IriBuf>;
+
+const USAGE: &str = "Usage: json-ld <COMMAND> [OPTIONS] [FILE]
+
+Reads the input JSON-LD document from FILE, or the standard input if FILE is
+omitted or `-`, and writes the result on the standard output.
+
+Commands:
+  expand                      Expand the document.
+  compact                     Compact the document (requires --context).
+  flatten                     Flatten the document, then compact it if
+                              --context is given.
+  to-rdf                      Convert the document into N-Quads.
+  frame                       Not supported: this implementation does not
+                              provide the framing algorithm.
+
+Options:
+  -c, --context <FILE>        Context used to compact the document.
+      --expand-context <FILE> Context used to initialize the expansion.
+  -b, --base <IRI>            Base IRI of the document.
+  -m, --mount <IRI>=<DIR>     Load remote documents starting with IRI from DIR.
+                              May be repeated.
+      --processing-mode <MODE>
+                              `json-ld-1.0` or `json-ld-1.1` (default).
+      --ordered               Process entries in lexicographical order.
+      --no-compact-arrays     Keep arrays with a single item when compacting.
+      --no-compact-to-relative
+                              Do not compact IRIs relatively to the base IRI.
+      --lenient               Drop invalid entries instead of failing.
+      --limits                Apply the resource limits suitable for untrusted documents.
+  -p, --pretty                Pretty-print the output.
+  -h, --help                  Print this message.";
+
+enum Command {
+	Expand,
+	Compact,
+	Flatten,
+	ToRdf,
+}
+
+/// Command line arguments.
+struct Args {
+	command: Command,
+	input: Option<PathBuf>,
+	context: Option<PathBuf>,
+	expand_context: Option<PathBuf>,
+	base: Option<IriBuf>,
+	mount_points: Vec<(IriBuf, PathBuf)>,
+	processing_mode: ProcessingMode,
+	ordered: bool,
+	compact_arrays: bool,
+	compact_to_relative: bool,
+	lenient: bool,
+	limits: Limits,
+	pretty: bool,
+}
+
+/// Command line error.
+struct CliError(String);
+
+impl<E: fmt::Display> From<E> for CliError {
+	fn from(e: E) -> Self {
+		Self(e.to_string())
+	}
+}
+
+fn parse_iri(value: &str) -> Result<IriBuf, CliError> {
+	IriBuf::new(value).map_err(|_| CliError(format!("invalid IRI `{}`", value)))
+}
+
+impl Args {
+	fn parse(mut args: impl Iterator<Item = String>) -> Result<Self, CliError> {
+		let command = match args.next().as_deref() {
+			Some("expand") => Command::Expand,
+			Some("compact") => Command::Compact,
+			Some("flatten") => Command::Flatten,
+			Some("to-rdf") => Command::ToRdf,
+			Some("-h") | Some("--help") | None => {
+				println!("{}", USAGE);
+				process::exit(0)
+			}
+			Some("frame") => {
+				return Err(CliError(
+					"the `frame` command is not supported: \
+					this implementation does not provide the framing algorithm"
+						.to_string(),
+				))
+			}
+			Some(cmd) => return Err(CliError(format!("unknown command `{}`", cmd))),
+		};
+
+		let mut result = Self {
+			command,
+			input: None,
+			context: None,
+			expand_context: None,
+			base: None,
+			mount_points: Vec::new(),
+			processing_mode: ProcessingMode::default(),
+			ordered: false,
+			compact_arrays: true,
+			compact_to_relative: true,
+			lenient: false,
+			limits: Limits::none(),
+			pretty: false,
+		};
+
+		while let Some(arg) = args.next() {
+			let mut value = |name: &str| {
+				args.next()
+					.ok_or_else(|| CliError(format!("missing value for `{}`", name)))
+			};
+
+			match arg.as_str() {
+				"-c" | "--context" => result.context = Some(value(&arg)?.into()),
+				"--expand-context" => result.expand_context = Some(value(&arg)?.into()),
+				"-b" | "--base" => result.base = Some(parse_iri(&value(&arg)?)?),
+				"-m" | "--mount" => {
+					let mount = value(&arg)?;
+					match mount.split_once('=') {
+						Some((iri, dir)) => result.mount_points.push((parse_iri(iri)?, dir.into())),
+						None => return Err(CliError(format!("invalid mount point `{}`", mount))),
+					}
+				}
+				"--processing-mode" => {
+					let mode = value(&arg)?;
+					result.processing_mode = ProcessingMode::try_from(mode.as_str())
+						.map_err(|_| CliError(format!("unknown processing mode `{}`", mode)))?
+				}
+				"--ordered" => result.ordered = true,
+				"--no-compact-arrays" => result.compact_arrays = false,
+				"--no-compact-to-relative" => result.compact_to_relative = false,
+				"--lenient" => result.lenient = true,
+				"--limits" => result.limits = Limits::server(),
+				"-p" | "--pretty" => result.pretty = true,
+				"-h" | "--help" => {
+					println!("{}", USAGE);
+					process::exit(0)
+				}
+				"-" if result.input.is_none() => (),
+				_ if !arg.starts_with('-') && result.input.is_none() => {
+					result.input = Some(PathBuf::from(&arg))
+				}
+				_ => return Err(CliError(format!("unexpected argument `{}`", arg))),
+			}
+		}
+
+		Ok(result)
+	}
+
+	fn expansion_options(&self) -> expansion::Options {
+		expansion::Options {
+			processing_mode: self.processing_mode,
+			ordered: self.ordered,
+			lenient: self.lenient,
+			limits: self.limits,
+			..expansion::Options::default()
+		}
+	}
+
+	fn compaction_options(&self) -> compaction::Options {
+		compaction::Options {
+			processing_mode: self.processing_mode,
+			ordered: self.ordered,
+			compact_arrays: self.compact_arrays,
+			compact_to_relative: self.compact_to_relative,
+			..compaction::Options::default()
+		}
+	}
+
+	fn processing_options(&self) -> ProcessingOptions {
+		ProcessingOptions::default()
+			.with_processing_mode(self.processing_mode)
+			.with_limits(self.limits)
+	}
+}
+
+fn read_json(path: Option<&PathBuf>) -> Result<Value, CliError> {
+	let mut content = String::new();
+	match path {
+		Some(path) => content = fs::read_to_string(path)?,
+		None => {
+			io::stdin().read_to_string(&mut content)?;
+		}
+	}
+
+	Ok(serde_json::from_str(&content)?)
+}
+
+/// Reads a context file, unwrapping its `@context` entry if any.
+fn read_context(path: &PathBuf) -> Result<Value, CliError> {
+	let mut json = read_json(Some(path))?;
+	match json.get_mut("@context") {
+		Some(context) => Ok(context.take()),
+		None => Ok(json),
+	}
+}
+
+fn process(
+	local: &Value,
+	base: Option<Iri>,
+	loader: &mut FsLoader<Value>,
+	options: ProcessingOptions,
+) -> Result<context::ProcessedOwned<Value, Context>, CliError> {
+	let processed = block_on(local.process_with(&Context::new(base), loader, base, options))?;
+	Ok(processed.owned())
+}
+
+fn expand(
+	input: &Value,
+	base: Option<Iri>,
+	expand_context: Option<&Context>,
+	loader: &mut FsLoader<Value>,
+	options: expansion::Options,
+) -> Result<ExpandedDocument<Value, IriBuf>, CliError> {
+	let expanded = match expand_context {
+		Some(context) => block_on(input.expand_with(base, context, loader, options)),
+		None => block_on(input.expand_with(base, &Context::new(base), loader, options)),
+	}?;
+
+	Ok(expanded)
+}
+
+/// Flattens the expanded document, following the
+/// [Flattening algorithm](https://www.w3.org/TR/json-ld11-api/#flattening-algorithm).
+///
+/// The result is in expanded form.
+fn flatten(expanded: ExpandedDocument<Value, IriBuf>, ordered: bool) -> Result<Value, CliError> {
+	let mut graphs = expanded
+		.generate_node_map(&mut BlankIdSequence::default())?
+		.into_graphs();
+	let mut default_graph = graphs
+		.remove(&None)
+		.map(NodeMapGraph::into_nodes)
+		.unwrap_or_default();
+
+	for (name, graph) in graphs {
+		if let Some(name) = name {
+			let node = default_graph
+				.entry(name.clone())
+				.or_insert_with(|| Node::with_id(name).into());
+			let graph = graph
+				.into_nodes()
+				.into_values()
+				.filter(|node| !is_node_reference(node))
+				.map(|node| node.map_inner(Object::Node))
+				.collect();
+			node.set_graph(Some(graph))
+		}
+	}
+
+	let mut nodes: Vec<_> = default_graph
+		.into_values()
+		.filter(|node| !is_node_reference(node))
+		.collect();
+	if ordered {
+		nodes.sort_by(|a, b| {
+			a.id()
+				.map(|id| id.as_str())
+				.cmp(&b.id().map(|id| id.as_str()))
+		})
+	}
+
+	Ok(Value::Array(
+		nodes.iter().map(|node| node.as_json()).collect(),
+	))
+}
+
+/// Checks if the given node has nothing but an `@id` entry.
+fn is_node_reference(node: &Indexed<Node<Value, IriBuf>>) -> bool {
+	node.index().is_none() && node.graph().is_none() && node.is_empty()
+}
+
+/// Puts the nodes of a compacted flattened document in a top-level `@graph` entry,
+/// as compaction leaves a single node unwrapped.
+fn into_graph(compacted: Value) -> Value {
+	match compacted {
+		Value::Object(mut node) if !node.contains_key("@graph") => {
+			let mut result = serde_json::Map::new();
+			if let Some(context) = node.remove("@context") {
+				result.insert("@context".to_string(), context);
+			}
+
+			let graph = if node.is_empty() {
+				Vec::new()
+			} else {
+				vec![Value::Object(node)]
+			};
+			result.insert("@graph".to_string(), Value::Array(graph));
+			Value::Object(result)
+		}
+		compacted => compacted,
+	}
+}
+
+/// Converts the expanded document into N-Quads, one statement per line.
+///
+/// Statements are sorted so that the output is stable.
+fn to_nquads(expanded: &ExpandedDocument<Value, IriBuf>) -> Result<String, CliError> {
+	let quads = expanded.to_rdf(&mut BlankIdSequence::default(), &rdf::Options::default())?;
+	let mut lines: Vec<_> = quads.iter().map(ToString::to_string).collect();
+	lines.sort();
+	Ok(lines.join("\n"))
+}
+
+fn print_json(value: &Value, pretty: bool) -> Result<String, CliError> {
+	if pretty {
+		Ok(serde_json::to_string_pretty(value)?)
+	} else {
+		Ok(serde_json::to_string(value)?)
+	}
+}
+
+fn run(args: Args) -> Result<String, CliError> {
+	let mut loader = FsLoader::<Value>::new(|s| serde_json::from_str(s));
+	for (iri, dir) in &args.mount_points {
+		loader.mount(iri.as_iri(), dir)
+	}
+
+	let input = read_json(args.input.as_ref())?;
+	let base = args.base.as_ref().map(|base| base.as_iri());
+
+	let expand_context = match &args.expand_context {
+		Some(path) => {
+			let local = read_context(path)?;
+			Some(process(
+				&local,
+				base,
+				&mut loader,
+				args.processing_options(),
+			)?)
+		}
+		None => None,
+	};
+
+	match args.command {
+		Command::Expand => {
+			let expanded = expand(
+				&input,
+				base,
+				expand_context.as_deref(),
+				&mut loader,
+				args.expansion_options(),
+			)?;
+
+			print_json(&expanded.as_json(), args.pretty)
+		}
+		Command::Compact => {
+			let path = args
+				.context
+				.as_ref()
+				.ok_or_else(|| CliError("missing `--context` option".to_string()))?;
+			let local = read_context(path)?;
+			let context = process(&local, base, &mut loader, args.processing_options())?;
+
+			let compacted: Value = block_on(input.compact_with_expand_context(
+				base,
+				expand_context.as_deref(),
+				&context,
+				&mut loader,
+				args.compaction_options(),
+				|_| (),
+				|_| (),
+			))?;
+
+			print_json(&compacted, args.pretty)
+		}
+		Command::Flatten => {
+			let expanded = expand(
+				&input,
+				base,
+				expand_context.as_deref(),
+				&mut loader,
+				args.expansion_options(),
+			)?;
+			let flattened = flatten(expanded, args.ordered)?;
+
+			match &args.context {
+				Some(path) => {
+					let local = read_context(path)?;
+					let context = process(&local, base, &mut loader, args.processing_options())?;
+					let compacted: Value = block_on(flattened.compact_with(
+						base,
+						&context,
+						&mut loader,
+						args.compaction_options(),
+						|_| (),
+						|_| (),
+					))?;
+
+					print_json(&into_graph(compacted), args.pretty)
+				}
+				None => print_json(&flattened, args.pretty),
+			}
+		}
+		Command::ToRdf => {
+			let expanded = expand(
+				&input,
+				base,
+				expand_context.as_deref(),
+				&mut loader,
+				args.expansion_options(),
+			)?;
+
+			to_nquads(&expanded)
+		}
+	}
+}
+
+fn main() {
+	let result = Args::parse(std::env::args().skip(1)).and_then(run);
+
+	match result {
+		Ok(output) => println!("{}", output),
+		Err(CliError(message)) => {
+			eprintln!("error: {}", message);
+			process::exit(1)
+		}
+	}
+}
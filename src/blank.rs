@@ -1,7 +1,10 @@
+#[cfg(not(feature = "std"))]
+use crate::prelude::*;
 use crate::util;
+use core::convert::TryFrom;
+use core::fmt::{self, Write};
 use generic_json::JsonBuild;
-use std::convert::TryFrom;
-use std::fmt;
+use iref::{Iri, IriBuf, IriRef};
 
 /// Blank node identifier.
 ///
@@ -46,6 +49,37 @@ impl BlankId {
 	pub fn name(&self) -> &str {
 		&self.0[2..self.0.len()]
 	}
+
+	/// Returns the skolem IRI replacing this blank node identifier.
+	///
+	/// The skolem IRI is the well-known `/.well-known/genid/name` path
+	/// resolved against the `base` IRI, as recommended by RDF 1.1.
+	/// Characters of the name that are not unreserved IRI characters are percent-encoded.
+	///
+	/// ```
+	/// # use json_ld::BlankId;
+	/// # use iref::Iri;
+	/// let base = Iri::new("https://example.com/data/doc.jsonld").unwrap();
+	/// assert_eq!(
+	///   BlankId::new("b0").skolem_iri(base).as_str(),
+	///   "https://example.com/.well-known/genid/b0"
+	/// );
+	/// ```
+	pub fn skolem_iri(&self, base: Iri) -> IriBuf {
+		let mut path = "/.well-known/genid/".to_string();
+		for c in self.name().chars() {
+			if c.is_alphanumeric() || matches!(c, '-' | '.' | '_' | '~') {
+				path.push(c)
+			} else {
+				let mut buffer = [0; 4];
+				for byte in c.encode_utf8(&mut buffer).bytes() {
+					write!(path, "%{:02X}", byte).unwrap()
+				}
+			}
+		}
+
+		IriRef::new(&path).unwrap().resolved(base)
+	}
 }
 
 impl<'a> TryFrom<&'a str> for BlankId {
@@ -75,3 +109,62 @@ impl fmt::Display for BlankId {
 		self.0.fmt(f)
 	}
 }
+
+/// Blank node identifier generator.
+///
+/// Used by the algorithms that need to create or rename blank nodes.
+/// It is implemented by any `FnMut() -> BlankId` closure.
+pub trait BlankIdGenerator {
+	/// Generates a new blank node identifier.
+	///
+	/// The generator must never return the same identifier twice.
+	fn next_blank_id(&mut self) -> BlankId;
+}
+
+impl<F: FnMut() -> BlankId> BlankIdGenerator for F {
+	#[inline(always)]
+	fn next_blank_id(&mut self) -> BlankId {
+		self()
+	}
+}
+
+/// Blank node identifier generator producing the sequence `_:prefix0`, `_:prefix1`, etc.
+///
+/// The default prefix is `b`, as in the JSON-LD specification.
+#[derive(Clone, Debug)]
+pub struct BlankIdSequence {
+	prefix: String,
+	count: usize,
+}
+
+impl BlankIdSequence {
+	/// Creates a new generator using the given prefix.
+	#[inline(always)]
+	pub fn new(prefix: impl Into<String>) -> Self {
+		Self {
+			prefix: prefix.into(),
+			count: 0,
+		}
+	}
+
+	/// Number of identifiers generated so far.
+	#[inline(always)]
+	pub fn count(&self) -> usize {
+		self.count
+	}
+}
+
+impl Default for BlankIdSequence {
+	#[inline(always)]
+	fn default() -> Self {
+		Self::new("b")
+	}
+}
+
+impl BlankIdGenerator for BlankIdSequence {
+	fn next_blank_id(&mut self) -> BlankId {
+		let id = BlankId::new(&format!("{}{}", self.prefix, self.count));
+		self.count += 1;
+		id
+	}
+}
@@ -0,0 +1,494 @@
+//! Generation of Rust types from a JSON-LD context.
+//!
+//! A [`Generator`] turns the [term bindings](crate::context::Context::bindings)
+//! of a context into the source code of a Rust structure,
+//! with one field per term, along with conversions from and into [`Node`]s.
+//! The generated code is meant to be written by a build script and included in the
+//! application, giving compile-time checked access to documents using a known context.
+//!
+//! The type of each field is derived from the type coercion of the term:
+//!   - `@id` and `@vocab` coercions give [`Reference`] fields;
+//!   - `xsd:boolean` gives `bool` fields, integer datatypes `i64` fields,
+//!     and other numeric datatypes `f64` fields;
+//!   - any other coercion, or no coercion at all, gives `String` fields.
+//!
+//! Terms with no container mapping give `Option` fields (only one value is kept),
+//! terms with a `@list` container mapping give list fields,
+//! and other terms give `Vec` fields.
+//! Reverse properties, terms coerced to `@json`, terms with a `@graph` container mapping
+//! and terms mapped to blank node identifiers are ignored.
+//!
+//! Values not matching the type of their field are ignored when converting from a node.
+//!
+//! ```
+//! use iref::IriBuf;
+//! use json_ld::{codegen::Generator, context::{self, Local}, NoLoader};
+//! use serde_json::{json, Value};
+//!
+//! # async_std::task::block_on(async {
+//! let mut loader = NoLoader::<Value>::new();
+//! let context = json!({
+//!   "name": "http://xmlns.com/foaf/0.1/name",
+//!   "knows": {
+//!     "@id": "http://xmlns.com/foaf/0.1/knows",
+//!     "@type": "@id",
+//!     "@container": "@set"
+//!   }
+//! })
+//! .process::<context::Json<Value, IriBuf>, _>(&mut loader, None)
+//! .await
+//! .unwrap()
+//! .into_inner();
+//!
+//! let code = Generator::from_context("Person", &context).generate();
+//! assert!(code.contains("pub struct Person {"));
+//! assert!(code.contains("pub name: Option<String>,"));
+//! assert!(code.contains("pub knows: Vec<::json_ld::Reference>,"));
+//! # })
+//! ```
+use crate::util::collections::HashSet;
+use crate::{
+	context::{Context, TermBinding},
+	object::value::{xsd, Literal, LiteralString},
+	syntax::{ContainerType, Type},
+	Id, Indexed, Node, Object, Reference, Value,
+};
+use iref::Iri;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+/// JSON type bound of the generated conversions.
+pub use generic_json::JsonHash;
+
+/// Value of a generated field.
+pub trait FieldValue<J: JsonHash, T: Id>: Sized {
+	/// Extracts a field value from the given object, if it is of the expected kind.
+	fn from_object(object: &Object<J, T>) -> Option<Self>;
+
+	/// Converts the field value into an object, typed with the given datatype if any.
+	fn into_object(self, datatype: Option<&str>) -> Object<J, T>;
+}
+
+fn literal<J: JsonHash, T: Id>(s: String, datatype: Option<&str>) -> Object<J, T> {
+	let datatype = datatype.map(|ty| T::from_iri(Iri::new(ty).expect("invalid datatype IRI")));
+	Object::Value(
+		Value::Literal(Literal::String(LiteralString::Inferred(s)), datatype),
+		None,
+	)
+}
+
+impl<J: JsonHash, T: Id> FieldValue<J, T> for String {
+	fn from_object(object: &Object<J, T>) -> Option<Self> {
+		object.as_value()?.as_str().map(ToString::to_string)
+	}
+
+	fn into_object(self, datatype: Option<&str>) -> Object<J, T> {
+		literal(self, datatype)
+	}
+}
+
+/// The datatype is ignored: booleans are converted into native values.
+impl<J: JsonHash, T: Id> FieldValue<J, T> for bool {
+	fn from_object(object: &Object<J, T>) -> Option<Self> {
+		object.as_value()?.as_bool()
+	}
+
+	fn into_object(self, _datatype: Option<&str>) -> Object<J, T> {
+		Object::Value(Value::Literal(Literal::Boolean(self), None), None)
+	}
+}
+
+/// Integers are converted into typed literals, `xsd:integer` by default.
+impl<J: JsonHash, T: Id> FieldValue<J, T> for i64 {
+	fn from_object(object: &Object<J, T>) -> Option<Self> {
+		object.as_value()?.as_i64()
+	}
+
+	fn into_object(self, datatype: Option<&str>) -> Object<J, T> {
+		let integer = xsd::Datatype::Integer.iri();
+		literal(self.to_string(), Some(datatype.unwrap_or(&integer)))
+	}
+}
+
+/// Floating point numbers are converted into typed literals, `xsd:double` by default.
+impl<J: JsonHash, T: Id> FieldValue<J, T> for f64 {
+	fn from_object(object: &Object<J, T>) -> Option<Self> {
+		object.as_value()?.as_f64()
+	}
+
+	fn into_object(self, datatype: Option<&str>) -> Object<J, T> {
+		let double = xsd::Datatype::Double.iri();
+		let datatype = datatype.unwrap_or(&double);
+		let s = match xsd::Datatype::from_iri(datatype) {
+			Some(xsd::Datatype::Decimal) => xsd::canonical_decimal(self),
+			_ => xsd::canonical_double(self),
+		};
+
+		literal(s, Some(datatype))
+	}
+}
+
+/// The datatype is ignored: references are converted into node objects.
+impl<J: JsonHash, T: Id> FieldValue<J, T> for Reference<T> {
+	fn from_object(object: &Object<J, T>) -> Option<Self> {
+		object.as_node()?.id().cloned()
+	}
+
+	fn into_object(self, _datatype: Option<&str>) -> Object<J, T> {
+		Object::Node(Node::with_id(self))
+	}
+}
+
+fn property<T: Id>(iri: &str) -> Reference<T> {
+	Reference::Id(T::from_iri(Iri::new(iri).expect("invalid property IRI")))
+}
+
+/// Returns one of the values of the given property matching the field type, if any.
+pub fn single<J: JsonHash, T: Id, V: FieldValue<J, T>>(node: &Node<J, T>, iri: &str) -> Option<V> {
+	node.get(&property(iri))
+		.find_map(|object| V::from_object(object))
+}
+
+/// Returns the values of the given property matching the field type.
+pub fn set<J: JsonHash, T: Id, V: FieldValue<J, T>>(node: &Node<J, T>, iri: &str) -> Vec<V> {
+	node.get(&property(iri))
+		.filter_map(|object| V::from_object(object))
+		.collect()
+}
+
+/// Returns the items of the lists associated to the given property
+/// matching the field type.
+pub fn list<J: JsonHash, T: Id, V: FieldValue<J, T>>(node: &Node<J, T>, iri: &str) -> Vec<V> {
+	node.get(&property(iri))
+		.filter_map(|object| object.as_list())
+		.flatten()
+		.filter_map(|item| V::from_object(item))
+		.collect()
+}
+
+/// Associates the given values to the node through the given property.
+pub fn insert<J: JsonHash, T: Id, V: FieldValue<J, T>, I: IntoIterator<Item = V>>(
+	node: &mut Node<J, T>,
+	iri: &str,
+	values: I,
+	datatype: Option<&str>,
+) {
+	let prop = property(iri);
+	for value in values {
+		node.insert(
+			prop.clone(),
+			Indexed::new(value.into_object(datatype), None),
+		)
+	}
+}
+
+/// Associates a list of the given items to the node through the given property,
+/// unless there are no items.
+pub fn insert_list<J: JsonHash, T: Id, V: FieldValue<J, T>>(
+	node: &mut Node<J, T>,
+	iri: &str,
+	items: Vec<V>,
+	datatype: Option<&str>,
+) {
+	if !items.is_empty() {
+		let items = items
+			.into_iter()
+			.map(|item| Indexed::new(item.into_object(datatype), None))
+			.collect();
+		node.insert(property(iri), Indexed::new(Object::List(items, None), None))
+	}
+}
+
+/// Rust type of a generated field value.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum FieldKind {
+	/// `String`.
+	String,
+
+	/// `bool`.
+	Boolean,
+
+	/// `i64`.
+	Integer,
+
+	/// `f64`.
+	Float,
+
+	/// [`Reference`].
+	Reference,
+}
+
+impl FieldKind {
+	/// Returns the Rust type of the field values.
+	pub fn rust_type(&self) -> &'static str {
+		match self {
+			Self::String => "String",
+			Self::Boolean => "bool",
+			Self::Integer => "i64",
+			Self::Float => "f64",
+			Self::Reference => "::json_ld::Reference",
+		}
+	}
+}
+
+/// Number of values of a generated field.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Multiplicity {
+	/// At most one value (`Option`).
+	Single,
+
+	/// Any number of values (`Vec`).
+	Set,
+
+	/// List items (`Vec`).
+	List,
+}
+
+/// Generated field.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Field {
+	/// Name of the field.
+	pub name: String,
+
+	/// Term the field is generated from.
+	pub term: String,
+
+	/// IRI of the property.
+	pub iri: String,
+
+	/// Type of the values.
+	pub kind: FieldKind,
+
+	/// Number of values.
+	pub multiplicity: Multiplicity,
+
+	/// Datatype of the values, if any.
+	pub datatype: Option<String>,
+}
+
+impl Field {
+	/// Creates the field of the given term, if the binding can be represented.
+	fn new<T: Id>(term: &str, binding: &TermBinding<T>) -> Option<Self> {
+		if binding.reverse || binding.container.contains(ContainerType::Graph) {
+			return None;
+		}
+
+		let iri = match &binding.iri {
+			Reference::Id(id) => id.as_iri().as_str().to_string(),
+			_ => return None,
+		};
+
+		let (kind, datatype) = match &binding.typ {
+			Some(Type::Id) | Some(Type::Vocab) => (FieldKind::Reference, None),
+			Some(Type::Json) => return None,
+			Some(Type::Ref(ty)) => {
+				let ty = ty.as_iri().as_str().to_string();
+				let kind = match xsd::Datatype::from_iri(&ty) {
+					Some(xsd::Datatype::Boolean) => FieldKind::Boolean,
+					Some(d) if d.is_integer() => FieldKind::Integer,
+					Some(d) if d.is_numeric() => FieldKind::Float,
+					_ => FieldKind::String,
+				};
+				(kind, Some(ty))
+			}
+			Some(Type::None) | None => (FieldKind::String, None),
+		};
+
+		let multiplicity = if binding.container.contains(ContainerType::List) {
+			Multiplicity::List
+		} else if binding.container.is_empty() {
+			Multiplicity::Single
+		} else {
+			Multiplicity::Set
+		};
+
+		Some(Self {
+			name: field_name(term),
+			term: term.to_string(),
+			iri,
+			kind,
+			multiplicity,
+			datatype,
+		})
+	}
+
+	/// Returns the Rust type of the field.
+	pub fn rust_type(&self) -> String {
+		match self.multiplicity {
+			Multiplicity::Single => format!("Option<{}>", self.kind.rust_type()),
+			_ => format!("Vec<{}>", self.kind.rust_type()),
+		}
+	}
+}
+
+const RUST_KEYWORDS: &[&str] = &[
+	"abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "crate",
+	"do", "dyn", "else", "enum", "extern", "false", "final", "fn", "for", "if", "impl", "in",
+	"let", "loop", "macro", "match", "mod", "move", "mut", "override", "priv", "pub", "ref",
+	"return", "self", "static", "struct", "super", "trait", "true", "try", "type", "typeof",
+	"unsafe", "unsized", "use", "virtual", "where", "while", "yield",
+];
+
+/// Turns the given term into a snake case Rust identifier.
+fn field_name(term: &str) -> String {
+	let mut name = String::new();
+	let mut previous_lowercase = false;
+	for c in term.chars() {
+		if c.is_ascii_alphanumeric() {
+			if c.is_ascii_uppercase() && previous_lowercase {
+				name.push('_')
+			}
+
+			name.push(c.to_ascii_lowercase());
+			previous_lowercase = c.is_ascii_lowercase() || c.is_ascii_digit();
+		} else {
+			if !name.ends_with('_') {
+				name.push('_')
+			}
+
+			previous_lowercase = false
+		}
+	}
+
+	let name = name.trim_matches('_');
+	if name.is_empty() || name.starts_with(|c: char| c.is_ascii_digit()) {
+		format!("_{}", name)
+	} else if RUST_KEYWORDS.contains(&name) {
+		format!("{}_", name)
+	} else {
+		name.to_string()
+	}
+}
+
+/// Rust structure generator.
+pub struct Generator {
+	/// Name of the structure.
+	name: String,
+
+	/// Fields of the structure, except for the node identifier.
+	fields: Vec<Field>,
+}
+
+impl Generator {
+	/// Creates a generator for a structure with the given name and term bindings.
+	///
+	/// Field names are derived from the terms, converted into snake case.
+	/// The `id` field is reserved to the node identifier:
+	/// conflicting field names are suffixed with a number.
+	pub fn new<T: Id>(name: &str, bindings: &BTreeMap<String, TermBinding<T>>) -> Self {
+		let mut names = HashSet::new();
+		names.insert("id".to_string());
+
+		let mut fields = Vec::new();
+		for (term, binding) in bindings {
+			if let Some(mut field) = Field::new(term, binding) {
+				let mut i = 1;
+				let base = field.name.clone();
+				while !names.insert(field.name.clone()) {
+					field.name = format!("{}_{}", base, i);
+					i += 1
+				}
+
+				fields.push(field)
+			}
+		}
+
+		Self {
+			name: name.to_string(),
+			fields,
+		}
+	}
+
+	/// Creates a generator for a structure with the given name,
+	/// using the term bindings of the given context.
+	pub fn from_context<T: Id, C: Context<T>>(name: &str, context: &C) -> Self {
+		Self::new(name, &context.bindings())
+	}
+
+	/// Name of the structure.
+	#[inline(always)]
+	pub fn name(&self) -> &str {
+		&self.name
+	}
+
+	/// Fields of the structure, except for the node identifier.
+	#[inline(always)]
+	pub fn fields(&self) -> &[Field] {
+		&self.fields
+	}
+
+	/// Generates the source code of the structure definition and its conversions.
+	pub fn generate(&self) -> String {
+		let mut code = String::new();
+		self.write(&mut code).unwrap();
+		code
+	}
+
+	fn write(&self, f: &mut String) -> std::fmt::Result {
+		writeln!(f, "/// Generated from a JSON-LD context.")?;
+		writeln!(f, "#[derive(Clone, Default, PartialEq, Debug)]")?;
+		writeln!(f, "pub struct {} {{", self.name)?;
+		writeln!(f, "\t/// Node identifier.")?;
+		writeln!(f, "\tpub id: Option<::json_ld::Reference>,")?;
+		for field in &self.fields {
+			writeln!(f)?;
+			writeln!(f, "\t/// `{}`: `{}`.", field.term, field.iri)?;
+			writeln!(f, "\tpub {}: {},", field.name, field.rust_type())?;
+		}
+		writeln!(f, "}}")?;
+		writeln!(f)?;
+
+		writeln!(
+			f,
+			"impl<'a, J: ::json_ld::codegen::JsonHash> From<&'a ::json_ld::Node<J>> for {} {{",
+			self.name
+		)?;
+		writeln!(f, "\tfn from(node: &'a ::json_ld::Node<J>) -> Self {{")?;
+		writeln!(f, "\t\tSelf {{")?;
+		writeln!(f, "\t\t\tid: node.id().cloned(),")?;
+		for field in &self.fields {
+			let function = match field.multiplicity {
+				Multiplicity::Single => "single",
+				Multiplicity::Set => "set",
+				Multiplicity::List => "list",
+			};
+			writeln!(
+				f,
+				"\t\t\t{}: ::json_ld::codegen::{}(node, {:?}),",
+				field.name, function, field.iri
+			)?;
+		}
+		writeln!(f, "\t\t}}")?;
+		writeln!(f, "\t}}")?;
+		writeln!(f, "}}")?;
+		writeln!(f)?;
+
+		writeln!(
+			f,
+			"impl<J: ::json_ld::codegen::JsonHash> From<{}> for ::json_ld::Node<J> {{",
+			self.name
+		)?;
+		writeln!(f, "\tfn from(value: {}) -> Self {{", self.name)?;
+		writeln!(f, "\t\tlet mut node = match value.id {{")?;
+		writeln!(f, "\t\t\tSome(id) => ::json_ld::Node::with_id(id),")?;
+		writeln!(f, "\t\t\tNone => ::json_ld::Node::new(),")?;
+		writeln!(f, "\t\t}};")?;
+		for field in &self.fields {
+			let function = match field.multiplicity {
+				Multiplicity::List => "insert_list",
+				_ => "insert",
+			};
+			writeln!(
+				f,
+				"\t\t::json_ld::codegen::{}(&mut node, {:?}, value.{}, {:?});",
+				function,
+				field.iri,
+				field.name,
+				field.datatype.as_deref()
+			)?;
+		}
+		writeln!(f, "\t\tnode")?;
+		writeln!(f, "\t}}")?;
+		writeln!(f, "}}")
+	}
+}
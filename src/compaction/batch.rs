@@ -0,0 +1,63 @@
+use super::{JsonSrc, Options};
+use crate::{
+	context::{Inversible, Loader},
+	util::{AsJson, JsonFrom},
+	Context, ContextMutProxy, Error, ExpandedDocument, Id,
+};
+use generic_json::Json;
+
+/// Compacts every document of a batch into a top-level JSON-LD document using the same context.
+///
+/// The inverse context, needed by the compaction algorithm, is computed only once for the
+/// whole batch, instead of once per document as it would with
+/// [`ExpandedDocument::compact_document`].
+/// This is well suited to compact many small documents against a single context.
+///
+/// The compacted documents are returned in the order of the input documents.
+/// Compaction stops at the first error.
+/// See [`compact_document`](ExpandedDocument::compact_document) for the other parameters.
+pub async fn compact_all<'a, J, T, K, C: ContextMutProxy<T>, L: Loader, M1, M2, I>(
+	documents: I,
+	context: &'a C,
+	loader: &'a mut L,
+	options: Options,
+	meta_context: M1,
+	meta_document: M2,
+) -> Result<Vec<K>, Error>
+where
+	J: 'a + JsonSrc,
+	T: 'a + Sync + Send + Id,
+	I: IntoIterator<Item = &'a ExpandedDocument<J, T>>,
+	K: JsonFrom<J> + JsonFrom<<C::Target as Context<T>>::LocalContext>,
+	C: AsJson<<C::Target as Context<T>>::LocalContext, K> + Send + Sync,
+	<C::Target as Context<T>>::LocalContext: JsonSrc + From<L::Output>,
+	C::Target: Send + Sync,
+	L: Send + Sync,
+	M1: 'a
+		+ Clone
+		+ Send
+		+ Sync
+		+ Fn(Option<&<<C::Target as Context<T>>::LocalContext as Json>::MetaData>) -> K::MetaData,
+	M2: 'a + Clone + Send + Sync + Fn(Option<&J::MetaData>) -> K::MetaData,
+{
+	let inversible = Inversible::new(context.deref());
+
+	let documents = documents.into_iter();
+	let mut results = Vec::with_capacity(documents.size_hint().0);
+	for document in documents {
+		let json_context = context.as_json_with(meta_context.clone());
+		let compacted = document
+			.compact_document_inversible(
+				json_context,
+				inversible.clone(),
+				&mut *loader,
+				options.clone(),
+				meta_document.clone(),
+			)
+			.await?;
+
+		results.push(compacted)
+	}
+
+	Ok(results)
+}
@@ -0,0 +1,59 @@
+use cc_traits::{Iter, MapIter};
+use generic_json::{Json, ValueRef};
+
+/// Document compacted against one of several candidate contexts.
+///
+/// See [`ExpandedDocument::compact_candidates`](crate::ExpandedDocument::compact_candidates).
+pub struct Candidate<K> {
+	/// Index of the context in the candidates list.
+	pub index: usize,
+
+	/// Compacted document.
+	pub output: K,
+
+	/// Size of the compacted document, as computed by [`json_size`].
+	pub size: usize,
+}
+
+impl<K: Json> Candidate<K> {
+	/// Creates a new candidate output for the context at the given index.
+	pub fn new(index: usize, output: K) -> Self {
+		let size = json_size(&output);
+		Self {
+			index,
+			output,
+			size,
+		}
+	}
+}
+
+/// Computes the size of a JSON value.
+///
+/// The size is the number of bytes of every string and object key,
+/// plus one for each value.
+/// It approximates the serialized size of the value independently of the JSON
+/// implementation and serialization format.
+pub fn json_size<K: Json>(value: &K) -> usize {
+	let mut size = 1;
+
+	match value.as_value_ref() {
+		ValueRef::String(s) => {
+			let s: &str = s.as_ref();
+			size += s.len()
+		}
+		ValueRef::Array(ary) => {
+			for item in ary.iter() {
+				size += json_size(&*item)
+			}
+		}
+		ValueRef::Object(obj) => {
+			for (key, item) in obj.iter() {
+				let key: &str = key.as_ref();
+				size += key.len() + json_size(&*item)
+			}
+		}
+		_ => (),
+	}
+
+	size
+}
@@ -0,0 +1,128 @@
+use super::{JsonSrc, Options};
+use crate::{
+	context::{Loader, Local, ProcessedOwned, ProcessingOptions},
+	expansion,
+	util::JsonFrom,
+	Context, ContextMut, Document, Error, ExpandedDocument, Id, Loc,
+};
+use cc_traits::MapIter;
+use generic_json::Json;
+use iref::{Iri, IriBuf};
+
+/// Compacted document that can be edited through its expanded form.
+///
+/// The document is expanded when loaded, so that it can be edited using the
+/// expanded object model (see [`document_mut`](EditableDocument::document_mut)),
+/// regardless of how each property has been compacted.
+/// It is then compacted back using its original `@context` entry,
+/// inserted in the output exactly as written (preserving term order and aliases),
+/// so that edits produce minimal differences with the original document.
+pub struct EditableDocument<J: JsonSrc, T: Id, C> {
+	/// Base URL of the document.
+	base_url: Option<IriBuf>,
+
+	/// Original context of the document.
+	context: ProcessedOwned<J, C>,
+
+	/// Expanded document.
+	document: ExpandedDocument<J, T>,
+}
+
+impl<J, T, C> EditableDocument<J, T, C>
+where
+	J: JsonSrc + expansion::JsonExpand + JsonFrom<J>,
+	T: Id + Send + Sync,
+	C: ContextMut<T> + Context<T, LocalContext = J> + Send + Sync,
+{
+	/// Loads the given compacted document.
+	///
+	/// The `@context` entry of the document is processed and kept aside to
+	/// compact the document back, and the document is expanded.
+	pub async fn load<L: Loader + Send + Sync>(
+		json: &J,
+		base_url: Option<Iri<'_>>,
+		loader: &mut L,
+		options: expansion::Options,
+	) -> Result<Self, Loc<Error, J::MetaData>>
+	where
+		J: From<L::Output>,
+		L::Output: Into<J>,
+	{
+		let local = json
+			.as_object()
+			.and_then(|obj| {
+				obj.iter()
+					.find(|(key, _)| {
+						let key: &str = key.as_ref();
+						key == "@context"
+					})
+					.map(|(_, value)| (*value).clone())
+			})
+			.unwrap_or_else(|| J::null(json.metadata().clone()));
+
+		let context = local
+			.process_with(
+				&C::new(base_url),
+				loader,
+				base_url,
+				ProcessingOptions::default(),
+			)
+			.await?
+			.owned();
+
+		let document =
+			Document::<T>::expand_with(json, base_url, &C::new(base_url), loader, options).await?;
+
+		Ok(Self {
+			base_url: base_url.map(IriBuf::from),
+			context,
+			document,
+		})
+	}
+
+	/// Returns the base URL of the document, if any.
+	pub fn base_url(&self) -> Option<Iri> {
+		self.base_url.as_ref().map(|url| url.as_iri())
+	}
+
+	/// Returns the original context of the document.
+	pub fn context(&self) -> &ProcessedOwned<J, C> {
+		&self.context
+	}
+
+	/// Returns the expanded document.
+	pub fn document(&self) -> &ExpandedDocument<J, T> {
+		&self.document
+	}
+
+	/// Returns a mutable reference to the expanded document, to edit it.
+	pub fn document_mut(&mut self) -> &mut ExpandedDocument<J, T> {
+		&mut self.document
+	}
+
+	/// Consumes the editable document and returns the expanded document.
+	pub fn into_document(self) -> ExpandedDocument<J, T> {
+		self.document
+	}
+
+	/// Compacts the (edited) document back using its original context.
+	pub async fn compact<L: Loader + Send + Sync>(
+		&self,
+		loader: &mut L,
+		options: Options,
+	) -> Result<J, Error>
+	where
+		J::MetaData: Default,
+		J: From<L::Output>,
+	{
+		self.document
+			.compact_document(
+				&self.context,
+				loader,
+				options,
+				|m| m.cloned().unwrap_or_default(),
+				|m| m.cloned().unwrap_or_default(),
+			)
+			.await
+	}
+}
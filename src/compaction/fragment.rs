@@ -0,0 +1,146 @@
+use super::{Compact, CompactIndexed, JsonSrc, Options};
+use crate::util::future::BoxFuture;
+use crate::{
+	context::{Inversible, Loader},
+	util::JsonFrom,
+	Context, ContextMut, ContextMutProxy, Error, Id, Node, Object,
+};
+
+/// Compacts the object as if it had no `@index`.
+impl<J: JsonSrc, T: Sync + Send + Id> Compact<J, T> for Object<J, T> {
+	#[inline(always)]
+	fn compact_full<'a, K: JsonFrom<J>, C: ContextMut<T>, L: Loader, M>(
+		&'a self,
+		active_context: Inversible<T, &'a C>,
+		type_scoped_context: Inversible<T, &'a C>,
+		active_property: Option<&'a str>,
+		loader: &'a mut L,
+		options: Options,
+		meta: M,
+	) -> BoxFuture<'a, Result<K, Error>>
+	where
+		J: 'a,
+		T: 'a,
+		C: Sync + Send,
+		C::LocalContext: Send + Sync + From<L::Output>,
+		L: Sync + Send,
+		M: 'a + Send + Sync + Clone + Fn(Option<&J::MetaData>) -> K::MetaData,
+	{
+		self.compact_indexed(
+			None,
+			active_context,
+			type_scoped_context,
+			active_property,
+			loader,
+			options,
+			meta,
+		)
+	}
+}
+
+/// Compacts the node as if it had no `@index`.
+impl<J: JsonSrc, T: Sync + Send + Id> Compact<J, T> for Node<J, T> {
+	#[inline(always)]
+	fn compact_full<'a, K: JsonFrom<J>, C: ContextMut<T>, L: Loader, M>(
+		&'a self,
+		active_context: Inversible<T, &'a C>,
+		type_scoped_context: Inversible<T, &'a C>,
+		active_property: Option<&'a str>,
+		loader: &'a mut L,
+		options: Options,
+		meta: M,
+	) -> BoxFuture<'a, Result<K, Error>>
+	where
+		J: 'a,
+		T: 'a,
+		C: Sync + Send,
+		C::LocalContext: Send + Sync + From<L::Output>,
+		L: Sync + Send,
+		M: 'a + Send + Sync + Clone + Fn(Option<&J::MetaData>) -> K::MetaData,
+	{
+		self.compact_indexed(
+			None,
+			active_context,
+			type_scoped_context,
+			active_property,
+			loader,
+			options,
+			meta,
+		)
+	}
+}
+
+/// Compacts a fragment of an expanded document, such as a single object or node,
+/// using the given context.
+///
+/// Unlike [`ExpandedDocument::compact_document`](crate::ExpandedDocument::compact_document),
+/// the result is not a top-level JSON-LD document: it has no `@context` entry,
+/// and is meant to be embedded in a larger JSON value
+/// where the context is known by other means.
+/// The fragment is compacted as the value of `active_property`, if any,
+/// so that the containers and scoped context of this property are honored.
+///
+/// ```
+/// use iref::IriBuf;
+/// use json_ld::{compaction, context::{self, Local}, Document, NoLoader};
+/// use serde_json::{json, Value};
+///
+/// # async_std::task::block_on(async {
+/// let mut loader = NoLoader::<Value>::new();
+/// let doc = json!({
+///   "@id": "http://example.org/alice",
+///   "http://xmlns.com/foaf/0.1/name": "Alice"
+/// });
+/// let expanded = doc.expand::<context::Json<Value, IriBuf>, _>(&mut loader).await.unwrap();
+/// let node = expanded.iter().next().unwrap();
+///
+/// let context = json!({ "name": "http://xmlns.com/foaf/0.1/name" });
+/// let context = context
+///   .process::<context::Json<Value, IriBuf>, _>(&mut loader, None)
+///   .await
+///   .unwrap();
+///
+/// let fragment: Value = compaction::compact_fragment(
+///   node,
+///   &context,
+///   None,
+///   &mut loader,
+///   compaction::Options::default(),
+///   |_| (),
+/// )
+/// .await
+/// .unwrap();
+///
+/// assert_eq!(fragment, json!({ "@id": "http://example.org/alice", "name": "Alice" }));
+/// # })
+/// ```
+pub async fn compact_fragment<'a, J, T, K, F, C: ContextMutProxy<T>, L: Loader, M>(
+	fragment: &'a F,
+	context: &'a C,
+	active_property: Option<&'a str>,
+	loader: &'a mut L,
+	options: Options,
+	meta: M,
+) -> Result<K, Error>
+where
+	J: 'a + JsonSrc,
+	T: 'a + Sync + Send + Id,
+	F: Compact<J, T> + Sync,
+	K: JsonFrom<J>,
+	C::Target: Send + Sync,
+	<C::Target as Context<T>>::LocalContext: Send + Sync + From<L::Output>,
+	L: Send + Sync,
+	M: 'a + Clone + Send + Sync + Fn(Option<&J::MetaData>) -> K::MetaData,
+{
+	let context = Inversible::new(context.deref());
+	fragment
+		.compact_full(
+			context.clone(),
+			context,
+			active_property,
+			loader,
+			options,
+			meta,
+		)
+		.await
+}
@@ -1,4 +1,6 @@
 //! Compaction algorithm and related types.
+use crate::util::collections::HashSet;
+use crate::util::future::{BoxFuture, FutureExt};
 use crate::{
 	context::{
 		self,
@@ -8,24 +10,39 @@ use crate::{
 	object,
 	syntax::{ContainerType, Keyword, Term},
 	util::{AsAnyJson, AsJson, JsonFrom},
-	ContextMut, Error, Id, Indexed, Loc, Object, ProcessingMode, Value,
+	ContextMut, Error, Id, Indexed, Loc, Monitor, Object, ProcessingMode, Value,
 };
-use futures::future::{BoxFuture, FutureExt};
 use generic_json::{JsonBuild, JsonClone, JsonHash, JsonMut, JsonSendSync};
-use std::collections::HashSet;
+use std::sync::Arc;
 
 /// JSON type that can be used by the compaction algorithm.
 pub trait JsonSrc = JsonClone + JsonHash + JsonSendSync;
 
+mod batch;
+mod candidate;
+mod editable;
+mod fragment;
 mod iri;
 mod node;
+mod owned;
+mod prefix;
 mod property;
+mod selection;
 mod value;
+mod view;
 
+pub use batch::*;
+pub use candidate::*;
+pub use editable::*;
+pub use fragment::*;
 pub(crate) use iri::*;
 use node::*;
+pub use owned::*;
+pub use prefix::*;
 use property::*;
+pub use selection::*;
 use value::*;
+pub use view::*;
 
 fn optional_string<K: JsonBuild>(s: Option<String>, meta: K::MetaData) -> K {
 	match s {
@@ -34,26 +51,82 @@ fn optional_string<K: JsonBuild>(s: Option<String>, meta: K::MetaData) -> K {
 	}
 }
 
+/// Returns the local context (property-scoped or type-scoped context) of the given term
+/// definition, unless scoped contexts are not supported (json-ld-1.0).
+fn scoped_context<T: Id, C: context::Context<T>>(
+	definition: &context::TermDefinition<T, C>,
+	options: &Options,
+) -> Option<&C::LocalContext> {
+	if options.processing_mode == ProcessingMode::JsonLd1_0 {
+		None
+	} else {
+		definition.context.as_ref()
+	}
+}
+
 /// Compaction options.
-#[derive(Clone, Copy)]
+#[derive(Clone)]
 pub struct Options {
 	/// JSON-LD processing mode.
 	pub processing_mode: ProcessingMode,
 
 	/// Determines if IRIs are compacted relative to the provided base IRI or document location when compacting.
+	///
+	/// This is the `compactToRelative` option of the JSON-LD 1.1 API.
+	/// If set to `false`, node identifiers are always kept absolute.
+	///
+	/// Default is `true`.
 	pub compact_to_relative: bool,
 
 	/// If set to `true`, arrays with just one element are replaced with that element during compaction.
 	/// If set to `false`, all arrays will remain arrays even if they have just one element.
+	///
+	/// This is the `compactArrays` option of the JSON-LD 1.1 API.
+	/// Even when set to `true`, single-element arrays are kept for the values of terms
+	/// whose container mapping includes `@set` or `@list`, and for the `@type` entry
+	/// (or its aliases) when the context defines `@type` with a `@set` container
+	/// (in JSON-LD 1.1 processing mode), so that the shape of those entries is stable.
+	///
+	/// Default is `true`.
 	pub compact_arrays: bool,
 
 	/// If set to `true`, properties are processed by lexical order.
 	/// If `false`, order is not considered in processing.
 	pub ordered: bool,
+
+	/// If set to `true`, typed literals are compacted into native JSON values when
+	/// it can be done without loss.
+	///
+	/// This is the compaction analog of the `useNativeTypes` RDF deserialization option.
+	/// Only `xsd:boolean` literals with the lexical form `true` or `false`,
+	/// and `xsd:integer` literals in canonical lexical form whose value can be
+	/// represented exactly by a double precision floating point number are converted.
+	/// Other literals (including `xsd:double` literals) are left untouched.
+	pub use_native_types: bool,
+
+	/// If set to `false`, keywords are never compacted into the aliases defined by the
+	/// context: `@id`, `@type`, etc. are always spelled literally.
+	///
+	/// See [`Context::keyword_aliases`](crate::Context::keyword_aliases) to list the aliases
+	/// defined by a context.
+	pub use_keyword_aliases: bool,
+
+	/// If set to `true`, the annotations of
+	/// [JSON-LD-star](https://json-ld.github.io/json-ld-star/) are compacted
+	/// into `@annotation` entries.
+	/// If `false`, annotations are dropped.
+	pub annotation: bool,
+
+	/// Progress monitor, also used to cancel the compaction.
+	///
+	/// The monitor is advanced for every compacted node, value and list object,
+	/// and the compaction fails with a [`Cancelled`](crate::ErrorCode::Cancelled) error
+	/// once it is cancelled.
+	pub monitor: Option<Arc<Monitor>>,
 }
 
-impl From<Options> for context::ProcessingOptions {
-	fn from(options: Options) -> context::ProcessingOptions {
+impl<'a> From<&'a Options> for context::ProcessingOptions {
+	fn from(options: &'a Options) -> context::ProcessingOptions {
 		context::ProcessingOptions {
 			processing_mode: options.processing_mode,
 			..Default::default()
@@ -61,11 +134,20 @@ impl From<Options> for context::ProcessingOptions {
 	}
 }
 
+impl From<Options> for context::ProcessingOptions {
+	#[inline(always)]
+	fn from(options: Options) -> context::ProcessingOptions {
+		Self::from(&options)
+	}
+}
+
 impl From<crate::expansion::Options> for Options {
 	fn from(options: crate::expansion::Options) -> Options {
 		Options {
 			processing_mode: options.processing_mode,
 			ordered: options.ordered,
+			annotation: options.annotation,
+			monitor: options.monitor,
 			..Options::default()
 		}
 	}
@@ -78,6 +160,10 @@ impl Default for Options {
 			compact_to_relative: true,
 			compact_arrays: true,
 			ordered: false,
+			use_native_types: false,
+			use_keyword_aliases: true,
+			annotation: false,
+			monitor: None,
 		}
 	}
 }
@@ -166,7 +252,7 @@ enum TypeLangValue<'a, T: Id> {
 }
 
 /// Type that can be compacted with an index.
-pub trait CompactIndexed<J: JsonSrc, T: Id> {
+pub trait CompactIndexed<J: JsonSrc, T: Id>: Sized {
 	/// Compact with the given optional index.
 	fn compact_indexed<'a, K: JsonFrom<J>, C: ContextMut<T>, L: Loader, M>(
 		&'a self,
@@ -178,6 +264,39 @@ pub trait CompactIndexed<J: JsonSrc, T: Id> {
 		options: Options,
 		meta: M,
 	) -> BoxFuture<'a, Result<K, Error>>
+	where
+		J: 'a,
+		T: 'a,
+		C: Sync + Send,
+		C::LocalContext: Send + Sync + From<L::Output>,
+		L: Sync + Send,
+		M: 'a + Send + Sync + Clone + Fn(Option<&J::MetaData>) -> K::MetaData,
+	{
+		self.compact_annotated(
+			index,
+			None,
+			active_context,
+			type_scoped_context,
+			active_property,
+			loader,
+			options,
+			meta,
+		)
+	}
+
+	/// Compact with the given optional index and annotation
+	/// (see [`Indexed::annotation`]).
+	fn compact_annotated<'a, K: JsonFrom<J>, C: ContextMut<T>, L: Loader, M>(
+		&'a self,
+		index: Option<&'a str>,
+		annotation: Option<&'a [Indexed<Self>]>,
+		active_context: Inversible<T, &'a C>,
+		type_scoped_context: Inversible<T, &'a C>,
+		active_property: Option<&'a str>,
+		loader: &'a mut L,
+		options: Options,
+		meta: M,
+	) -> BoxFuture<'a, Result<K, Error>>
 	where
 		J: 'a,
 		T: 'a,
@@ -207,8 +326,9 @@ impl<J: JsonSrc, T: Sync + Send + Id, V: Sync + Send + CompactIndexed<J, T>> Com
 		L: Sync + Send,
 		M: 'a + Send + Sync + Clone + Fn(Option<&J::MetaData>) -> K::MetaData,
 	{
-		self.inner().compact_indexed(
+		self.inner().compact_annotated(
 			self.index(),
+			self.annotation(),
 			active_context,
 			type_scoped_context,
 			active_property,
@@ -222,9 +342,10 @@ impl<J: JsonSrc, T: Sync + Send + Id, V: Sync + Send + CompactIndexed<J, T>> Com
 impl<J: JsonSrc, T: Sync + Send + Id, N: object::Any<J, T> + Sync + Send> CompactIndexed<J, T>
 	for N
 {
-	fn compact_indexed<'a, K: JsonFrom<J>, C: ContextMut<T>, L: Loader, M>(
+	fn compact_annotated<'a, K: JsonFrom<J>, C: ContextMut<T>, L: Loader, M>(
 		&'a self,
 		index: Option<&'a str>,
+		annotation: Option<&'a [Indexed<Self>]>,
 		active_context: Inversible<T, &'a C>,
 		type_scoped_context: Inversible<T, &'a C>,
 		active_property: Option<&'a str>,
@@ -240,10 +361,17 @@ impl<J: JsonSrc, T: Sync + Send + Id, N: object::Any<J, T> + Sync + Send> Compac
 		L: Sync + Send,
 		M: 'a + Send + Sync + Clone + Fn(Option<&J::MetaData>) -> K::MetaData,
 	{
+		if let Some(monitor) = &options.monitor {
+			if let Err(e) = monitor.advance(0) {
+				return futures::future::ready(Err(e)).boxed();
+			}
+		}
+
 		match self.as_ref() {
 			object::Ref::Value(value) => async move {
 				compact_indexed_value_with(
 					value,
+					annotation,
 					index,
 					active_context,
 					active_property,
@@ -258,6 +386,7 @@ impl<J: JsonSrc, T: Sync + Send + Id, N: object::Any<J, T> + Sync + Send> Compac
 				compact_indexed_node_with(
 					node,
 					index,
+					annotation,
 					active_context,
 					type_scoped_context,
 					active_property,
@@ -287,14 +416,16 @@ impl<J: JsonSrc, T: Sync + Send + Id, N: object::Any<J, T> + Sync + Send> Compac
 					if let Some(active_property_definition) =
 						type_scoped_context.get(active_property)
 					{
-						if let Some(local_context) = &active_property_definition.context {
+						if let Some(local_context) =
+							scoped_context(active_property_definition, &options)
+						{
 							active_context = Inversible::new(
 								local_context
 									.process_with(
 										*active_context.as_ref(),
 										loader,
 										active_property_definition.base_url(),
-										context::ProcessingOptions::from(options).with_override(),
+										context::ProcessingOptions::from(&options).with_override(),
 									)
 									.await
 									.map_err(Loc::unwrap)?
@@ -329,7 +460,7 @@ impl<J: JsonSrc, T: Sync + Send + Id, N: object::Any<J, T> + Sync + Send> Compac
 						active_context.as_ref(),
 						loader,
 						false,
-						options,
+						options.clone(),
 						meta.clone(),
 					)
 					.await?;
@@ -473,7 +604,7 @@ where
 					type_scoped_context.clone(),
 					active_property,
 					loader,
-					options,
+					options.clone(),
 					meta.clone(),
 				)
 				.await?;
@@ -1,9 +1,12 @@
-use super::{add_value, compact_iri, compact_property, optional_string, JsonSrc, Options};
+use super::{
+	add_value, compact_iri, compact_property, optional_string, scoped_context, JsonSrc, Options,
+};
 use crate::{
 	context::{self, Inversible, Loader, Local},
+	object,
 	syntax::{Container, ContainerType, Keyword, Term, Type},
 	util::{AsAnyJson, JsonFrom},
-	ContextMut, Error, Id, Loc, Node, ProcessingMode, Reference,
+	ContextMut, Error, Id, Indexed, Loc, Node, ProcessingMode, Reference,
 };
 use cc_traits::Len;
 
@@ -14,10 +17,12 @@ pub async fn compact_indexed_node_with<
 	T: Sync + Send + Id,
 	C: ContextMut<T>,
 	L: Loader,
+	N: object::Any<J, T> + Sync + Send,
 	M,
 >(
 	node: &Node<J, T>,
 	index: Option<&str>,
+	annotation: Option<&[Indexed<N>]>,
 	mut active_context: Inversible<T, &C>,
 	type_scoped_context: Inversible<T, &C>,
 	active_property: Option<&str>,
@@ -48,14 +53,14 @@ where
 	let mut active_context = active_context.into_borrowed();
 	if let Some(active_property) = active_property {
 		if let Some(active_property_definition) = type_scoped_context.get(active_property) {
-			if let Some(local_context) = &active_property_definition.context {
+			if let Some(local_context) = scoped_context(active_property_definition, &options) {
 				active_context = Inversible::new(
 					local_context
 						.process_with(
 							*active_context.as_ref(),
 							loader,
 							active_property_definition.base_url(),
-							context::ProcessingOptions::from(options).with_override(),
+							context::ProcessingOptions::from(&options).with_override(),
 						)
 						.await
 						.map_err(Loc::unwrap)?
@@ -81,7 +86,7 @@ where
 				&ty.clone().into_term(),
 				true,
 				false,
-				options,
+				options.clone(),
 			)?;
 			compacted_types.push(compacted_ty)
 		}
@@ -91,9 +96,9 @@ where
 		for term in &compacted_types {
 			if let Some(term_definition) = type_scoped_context.get(term.as_ref().unwrap().as_str())
 			{
-				if let Some(local_context) = &term_definition.context {
+				if let Some(local_context) = scoped_context(term_definition, &options) {
 					let processing_options =
-						context::ProcessingOptions::from(options).without_propagation();
+						context::ProcessingOptions::from(&options).without_propagation();
 					active_context = Inversible::new(
 						local_context
 							.process_with(
@@ -165,7 +170,7 @@ where
 		// If expanded value is a string, then initialize compacted value by IRI
 		// compacting expanded value with vocab set to false.
 		let compacted_value =
-			compact_iri::<J, _, _>(active_context.as_ref(), &id, false, false, options)?;
+			compact_iri::<J, _, _>(active_context.as_ref(), &id, false, false, options.clone())?;
 
 		// Initialize alias by IRI compacting expanded property.
 		let alias = compact_iri::<J, _, _>(
@@ -173,7 +178,7 @@ where
 			&Term::Keyword(Keyword::Id),
 			true,
 			false,
-			options,
+			options.clone(),
 		)?;
 
 		// Add an entry alias to result whose value is set to compacted value and continue
@@ -191,7 +196,7 @@ where
 		&node.types,
 		active_context.as_ref(),
 		type_scoped_context.clone(),
-		options,
+		options.clone(),
 		meta.clone(),
 	)?;
 
@@ -202,14 +207,14 @@ where
 		// expanded value for element, and the compactArrays and ordered flags.
 		let active_property = "@reverse";
 		if let Some(active_property_definition) = active_context.get(active_property) {
-			if let Some(local_context) = &active_property_definition.context {
+			if let Some(local_context) = scoped_context(active_property_definition, &options) {
 				active_context = Inversible::new(
 					local_context
 						.process_with(
 							*active_context.as_ref(),
 							loader,
 							active_property_definition.base_url(),
-							context::ProcessingOptions::from(options).with_override(),
+							context::ProcessingOptions::from(&options).with_override(),
 						)
 						.await
 						.map_err(Loc::unwrap)?
@@ -228,7 +233,7 @@ where
 				active_context.as_ref(),
 				loader,
 				true,
-				options,
+				options.clone(),
 				meta.clone(),
 			)
 			.await?;
@@ -267,7 +272,7 @@ where
 				&Term::Keyword(Keyword::Reverse),
 				true,
 				false,
-				options,
+				options.clone(),
 			)?;
 
 			// Set the value of the alias entry of result to compacted value.
@@ -302,7 +307,7 @@ where
 				&Term::Keyword(Keyword::Index),
 				true,
 				false,
-				options,
+				options.clone(),
 			)?;
 
 			// Add an entry alias to result whose value is set to expanded value and continue with the next expanded property.
@@ -321,7 +326,7 @@ where
 			active_context.as_ref(),
 			loader,
 			false,
-			options,
+			options.clone(),
 			meta.clone(),
 		)
 		.await?
@@ -335,7 +340,7 @@ where
 			active_context.as_ref(),
 			loader,
 			false,
-			options,
+			options.clone(),
 			meta.clone(),
 		)
 		.await?
@@ -349,6 +354,20 @@ where
 			active_context.as_ref(),
 			loader,
 			false,
+			options.clone(),
+			meta.clone(),
+		)
+		.await?
+	}
+
+	if let Some(annotation) = annotation.filter(|_| options.annotation) {
+		compact_property::<J, K, _, _, _, _, _, _>(
+			&mut result,
+			Term::Keyword(Keyword::Annotation),
+			annotation,
+			active_context.as_ref(),
+			loader,
+			false,
 			options,
 			meta.clone(),
 		)
@@ -385,7 +404,7 @@ fn compact_types<
 					&types[0].clone().into_term(),
 					true,
 					false,
-					options,
+					options.clone(),
 				)?,
 				meta(None),
 			)
@@ -399,8 +418,13 @@ fn compact_types<
 				let ty = ty.clone().into_term();
 
 				// Set term by IRI compacting expanded type using type-scoped context for active context.
-				let compacted_ty =
-					compact_iri::<J, _, _>(type_scoped_context.clone(), &ty, true, false, options)?;
+				let compacted_ty = compact_iri::<J, _, _>(
+					type_scoped_context.clone(),
+					&ty,
+					true,
+					false,
+					options.clone(),
+				)?;
 
 				// Append term, to compacted value.
 				compacted_value.push(optional_string(compacted_ty, meta(None)))
@@ -415,7 +439,7 @@ fn compact_types<
 			&Term::Keyword(Keyword::Type),
 			true,
 			false,
-			options,
+			options.clone(),
 		)?
 		.unwrap();
 
@@ -0,0 +1,243 @@
+use super::{CompactedDocument, JsonSrc, Options};
+use crate::util::collections::HashMap;
+use crate::{
+	context::Loader,
+	expansion::expand_iri,
+	syntax::{Keyword, Term},
+	util::{AsJson, JsonFrom},
+	Context, ContextMutProxy, Error, ExpandedDocument, Id,
+};
+use cc_traits::{Iter, MapIter};
+use generic_json::{Json, ValueRef};
+
+/// Compacted document, attached to the context and options used to compact it.
+///
+/// Unlike the bare JSON value returned by
+/// [`ExpandedDocument::compact_document`], it remembers how the document has been
+/// compacted, so that it can be compacted again after the expanded document
+/// has been modified (see [`recompact`](Self::recompact)),
+/// and so that each key of the compacted document can be explained
+/// (see [`explain`](Self::explain)).
+///
+/// ```
+/// use iref::IriBuf;
+/// use json_ld::{compaction::{self, CompactedOwned}, context::{self, Local}, syntax::Term};
+/// use json_ld::{Document, NoLoader, Reference};
+/// use serde_json::{json, Value};
+///
+/// # async_std::task::block_on(async {
+/// let mut loader = NoLoader::<Value>::new();
+/// let doc = json!({ "http://xmlns.com/foaf/0.1/name": "Alice" });
+/// let expanded = doc.expand::<context::Json<Value, IriBuf>, _>(&mut loader).await.unwrap();
+///
+/// let context = json!({ "name": "http://xmlns.com/foaf/0.1/name" });
+/// let context = context
+///   .process::<context::Json<Value, IriBuf>, _>(&mut loader, None)
+///   .await
+///   .unwrap()
+///   .owned();
+///
+/// let compacted: CompactedOwned<Value, _, _> = CompactedOwned::compact(
+///   &expanded,
+///   context,
+///   &mut loader,
+///   compaction::Options::default(),
+///   |_| (),
+///   |_| (),
+/// )
+/// .await
+/// .unwrap();
+///
+/// let name = IriBuf::new("http://xmlns.com/foaf/0.1/name").unwrap();
+/// assert_eq!(compacted.explain("name"), Some(&Term::Ref(Reference::Id(name))));
+/// # })
+/// ```
+pub struct CompactedOwned<K: Json, T: Id, C> {
+	/// Compacted document.
+	json: K,
+
+	/// Context used to compact the document.
+	context: C,
+
+	/// Options used to compact the document.
+	options: Options,
+
+	/// Expansion of every key of the compacted document.
+	keys: HashMap<String, Term<T>>,
+}
+
+impl<K: Json, T: Id, C: ContextMutProxy<T>> CompactedOwned<K, T, C> {
+	/// Compacts the given document using the given context and options.
+	///
+	/// See [`ExpandedDocument::compact_document`] for the details.
+	pub async fn compact<'a, J: JsonSrc, L: Loader, M1, M2>(
+		document: &'a ExpandedDocument<J, T>,
+		context: C,
+		loader: &'a mut L,
+		options: Options,
+		meta_context: M1,
+		meta_document: M2,
+	) -> Result<Self, Error>
+	where
+		T: 'a + Sync + Send,
+		K: JsonFrom<J> + JsonFrom<<C::Target as Context<T>>::LocalContext>,
+		C: AsJson<<C::Target as Context<T>>::LocalContext, K> + Send + Sync,
+		<C::Target as Context<T>>::LocalContext: JsonSrc + From<L::Output>,
+		C::Target: Send + Sync,
+		L: Send + Sync,
+		M1: 'a
+			+ Clone
+			+ Send
+			+ Sync
+			+ Fn(Option<&<<C::Target as Context<T>>::LocalContext as Json>::MetaData>) -> K::MetaData,
+		M2: 'a + Clone + Send + Sync + Fn(Option<&J::MetaData>) -> K::MetaData,
+	{
+		let json = document
+			.compact_document(
+				&context,
+				loader,
+				options.clone(),
+				meta_context,
+				meta_document,
+			)
+			.await?;
+		Ok(Self::new(json, context, options))
+	}
+
+	/// Attaches the given compacted document to the context and options used to compact it.
+	pub fn new(json: K, context: C, options: Options) -> Self {
+		let mut keys = HashMap::new();
+		collect_keys(&json, context.deref(), &mut keys);
+
+		Self {
+			json,
+			context,
+			options,
+			keys,
+		}
+	}
+
+	/// Returns the compacted document.
+	#[inline(always)]
+	pub fn as_json(&self) -> &K {
+		&self.json
+	}
+
+	/// Consumes this wrapper and returns the compacted document.
+	#[inline(always)]
+	pub fn into_json(self) -> K {
+		self.json
+	}
+
+	/// Returns the context used to compact the document.
+	#[inline(always)]
+	pub fn context(&self) -> &C {
+		&self.context
+	}
+
+	/// Returns the options used to compact the document.
+	#[inline(always)]
+	pub fn options(&self) -> &Options {
+		&self.options
+	}
+
+	/// Returns the expansion of every key appearing in the compacted document.
+	///
+	/// Keys are expanded using the context the document has been compacted with.
+	/// Scoped contexts are not taken into account.
+	#[inline(always)]
+	pub fn keys(&self) -> &HashMap<String, Term<T>> {
+		&self.keys
+	}
+
+	/// Returns the IRI, blank node identifier or keyword the given key
+	/// of the compacted document stands for.
+	///
+	/// Returns `None` if the key does not appear in the compacted document.
+	#[inline(always)]
+	pub fn explain(&self, key: &str) -> Option<&Term<T>> {
+		self.keys.get(key)
+	}
+
+	/// Returns a read-only view over the compacted document.
+	#[inline(always)]
+	pub fn view(&self) -> CompactedDocument<K, T, C::Target> {
+		CompactedDocument::new(&self.json, self.context.deref())
+	}
+
+	/// Compacts the given document again with the same context and options,
+	/// typically after the expanded document this document has been compacted from
+	/// has been edited.
+	pub async fn recompact<'a, J: JsonSrc, L: Loader, M1, M2>(
+		&'a mut self,
+		document: &'a ExpandedDocument<J, T>,
+		loader: &'a mut L,
+		meta_context: M1,
+		meta_document: M2,
+	) -> Result<(), Error>
+	where
+		T: 'a + Sync + Send,
+		K: JsonFrom<J> + JsonFrom<<C::Target as Context<T>>::LocalContext>,
+		C: AsJson<<C::Target as Context<T>>::LocalContext, K> + Send + Sync,
+		<C::Target as Context<T>>::LocalContext: JsonSrc + From<L::Output>,
+		C::Target: Send + Sync,
+		L: Send + Sync,
+		M1: 'a
+			+ Clone
+			+ Send
+			+ Sync
+			+ Fn(Option<&<<C::Target as Context<T>>::LocalContext as Json>::MetaData>) -> K::MetaData,
+		M2: 'a + Clone + Send + Sync + Fn(Option<&J::MetaData>) -> K::MetaData,
+	{
+		let json = document
+			.compact_document(
+				&self.context,
+				loader,
+				self.options.clone(),
+				meta_context,
+				meta_document,
+			)
+			.await?;
+
+		self.keys.clear();
+		collect_keys(&json, self.context.deref(), &mut self.keys);
+		self.json = json;
+		Ok(())
+	}
+}
+
+/// Expands every key of the given JSON value, recursively,
+/// except for the keys of the `@context` entries.
+fn collect_keys<K: Json, T: Id, C: Context<T>>(
+	json: &K,
+	context: &C,
+	keys: &mut HashMap<String, Term<T>>,
+) {
+	match json.as_value_ref() {
+		ValueRef::Array(ary) => {
+			for item in ary.iter() {
+				collect_keys(&*item, context, keys)
+			}
+		}
+		ValueRef::Object(obj) => {
+			for (key, value) in obj.iter() {
+				let key: &str = key.as_ref();
+				let expanded = match keys.get(key) {
+					Some(expanded) => expanded.clone(),
+					None => {
+						let mut warnings = Vec::new();
+						let expanded =
+							expand_iri(None, context, key, &(), false, true, &mut warnings);
+						keys.insert(key.to_string(), expanded.clone());
+						expanded
+					}
+				};
+
+				if expanded != Term::Keyword(Keyword::Context) {
+					collect_keys(&*value, context, keys)
+				}
+			}
+		}
+		_ => (),
+	}
+}
@@ -0,0 +1,214 @@
+use crate::util::collections::{HashMap, HashSet};
+use crate::{
+	syntax::ContextDefinition,
+	util::{AsAnyJson, AsJson, JsonFrom},
+};
+use cc_traits::{Get, Iter, MapIter};
+use generic_json::{Json, JsonClone, Key, ValueRef};
+use iref::IriBuf;
+use std::collections::BTreeSet;
+
+/// Compacts the given expanded document using only a prefix map.
+///
+/// This is a lightweight alternative to the compaction algorithm
+/// (see [`ExpandedDocument::compact_document`](crate::ExpandedDocument::compact_document))
+/// when only the CURIE-style shortening of IRIs is wanted, like the prefixes of Turtle.
+/// Properties, reverse properties, types, datatypes and node identifiers are rewritten into
+/// compact IRIs `prefix:suffix`, using the longest matching IRI of `prefixes`.
+/// The rest of the document is left in expanded form.
+///
+/// The output is a JSON-LD document whose `@context` only defines the used prefixes,
+/// and whose `@graph` entry holds the rewritten objects.
+/// Prefixes that are not valid terms (empty, `_`, containing a `:` or starting with `@`)
+/// are ignored, as well as prefixes that could be confused with the scheme of
+/// an IRI left uncompacted.
+///
+/// The input is assumed to be in expanded form.
+pub fn compact_with_prefixes<K: JsonClone + JsonFrom<K>>(
+	json: &K,
+	prefixes: &HashMap<String, IriBuf>,
+) -> K {
+	let mut candidates: Vec<_> = prefixes
+		.iter()
+		.filter(|(prefix, _)| is_valid_prefix(prefix))
+		.map(|(prefix, iri)| (prefix.as_str(), iri.as_str()))
+		.collect();
+
+	// Longest IRIs first, so that the most specific prefix is selected.
+	candidates.sort_by(|(pa, a), (pb, b)| b.len().cmp(&a.len()).then(pa.cmp(pb)));
+
+	loop {
+		let mut rewriter = Rewriter::new(&candidates);
+		let graph = rewriter.array(json);
+
+		if rewriter
+			.used
+			.iter()
+			.any(|prefix| rewriter.schemes.contains(*prefix))
+		{
+			// Some uncompacted IRI would be read as a compact IRI.
+			let schemes = rewriter.schemes;
+			candidates.retain(|(prefix, _)| !schemes.contains(*prefix));
+			continue;
+		}
+
+		let mut context = ContextDefinition::new();
+		for prefix in &rewriter.used {
+			let (_, iri) = candidates.iter().find(|(p, _)| p == prefix).unwrap();
+			context = context.with_prefix(*prefix, *iri)
+		}
+
+		let meta = json.metadata().clone();
+		return K::object(
+			vec![
+				(
+					K::new_key("@context", meta.clone()),
+					AsAnyJson::<K>::as_json_with(&context, meta.clone()),
+				),
+				(K::new_key("@graph", meta.clone()), graph),
+			]
+			.into_iter()
+			.collect(),
+			meta,
+		);
+	}
+}
+
+fn is_valid_prefix(prefix: &str) -> bool {
+	!prefix.is_empty() && prefix != "_" && !prefix.contains(':') && !prefix.starts_with('@')
+}
+
+/// Copies the given JSON value.
+fn copy<K: JsonClone + JsonFrom<K>>(json: &K) -> K {
+	let meta = json.metadata().clone();
+	AsJson::<K, K>::as_json_with(json, move |m: Option<&K::MetaData>| {
+		m.cloned().unwrap_or_else(|| meta.clone())
+	})
+}
+
+struct Rewriter<'p> {
+	/// Candidate prefixes and their IRI, longest IRI first.
+	prefixes: &'p [(&'p str, &'p str)],
+
+	/// Used prefixes.
+	used: BTreeSet<&'p str>,
+
+	/// Schemes of the IRIs left uncompacted.
+	schemes: HashSet<String>,
+}
+
+impl<'p> Rewriter<'p> {
+	fn new(prefixes: &'p [(&'p str, &'p str)]) -> Self {
+		Self {
+			prefixes,
+			used: BTreeSet::new(),
+			schemes: HashSet::new(),
+		}
+	}
+
+	/// Compacts the given IRI with the longest matching prefix, if any.
+	fn compact_iri(&mut self, iri: &str) -> String {
+		if iri.starts_with("_:") {
+			return iri.to_string();
+		}
+
+		for &(prefix, prefix_iri) in self.prefixes {
+			if let Some(suffix) = iri.strip_prefix(prefix_iri) {
+				if !suffix.is_empty() && !suffix.starts_with("//") {
+					self.used.insert(prefix);
+					return format!("{}:{}", prefix, suffix);
+				}
+			}
+		}
+
+		if let Some((scheme, rest)) = iri.split_once(':') {
+			if !rest.starts_with("//") {
+				self.schemes.insert(scheme.to_string());
+			}
+		}
+
+		iri.to_string()
+	}
+
+	/// Compacts the given IRI value (or array of IRIs).
+	fn iris<K: JsonClone + JsonFrom<K>>(&mut self, json: &K) -> K {
+		let meta = json.metadata().clone();
+		match json.as_value_ref() {
+			ValueRef::String(iri) => {
+				let iri: &str = iri.as_ref();
+				K::string(self.compact_iri(iri).as_str().into(), meta)
+			}
+			ValueRef::Array(ary) => K::array(ary.iter().map(|i| self.iris(&*i)).collect(), meta),
+			_ => copy(json),
+		}
+	}
+
+	fn array<K: JsonClone + JsonFrom<K>>(&mut self, json: &K) -> K {
+		match json.as_value_ref() {
+			ValueRef::Array(ary) => K::array(
+				ary.iter().map(|i| self.object(&*i)).collect(),
+				json.metadata().clone(),
+			),
+			_ => self.object(json),
+		}
+	}
+
+	/// Rewrites a node, value or list object.
+	fn object<K: JsonClone + JsonFrom<K>>(&mut self, json: &K) -> K {
+		match json.as_value_ref() {
+			ValueRef::Object(obj) => {
+				let is_value = obj.get("@value").is_some();
+				let entries = obj
+					.iter()
+					.map(|(key, value)| {
+						let key_meta = key.metadata().clone();
+						match &**key {
+							"@type" if is_value => {
+								let datatype = match value.as_value_ref() {
+									ValueRef::String(ty) if ty.as_ref() == "@json" => copy(&*value),
+									_ => self.iris(&*value),
+								};
+								(K::new_key("@type", key_meta), datatype)
+							}
+							"@id" | "@type" => (K::new_key(&**key, key_meta), self.iris(&*value)),
+							"@list" | "@set" | "@graph" | "@included" | "@annotation" => {
+								(K::new_key(&**key, key_meta), self.array(&*value))
+							}
+							"@reverse" => (K::new_key(&**key, key_meta), self.properties(&*value)),
+							key if key.starts_with('@') || is_value => {
+								(K::new_key(key, key_meta), copy(&*value))
+							}
+							prop => (
+								K::new_key(&self.compact_iri(prop), key_meta),
+								self.array(&*value),
+							),
+						}
+					})
+					.collect::<Vec<_>>();
+
+				K::object(entries.into_iter().collect(), json.metadata().clone())
+			}
+			_ => copy(json),
+		}
+	}
+
+	/// Rewrites the properties of a `@reverse` entry.
+	fn properties<K: JsonClone + JsonFrom<K>>(&mut self, json: &K) -> K {
+		match json.as_value_ref() {
+			ValueRef::Object(obj) => {
+				let entries = obj
+					.iter()
+					.map(|(key, value)| {
+						(
+							K::new_key(&self.compact_iri(&**key), key.metadata().clone()),
+							self.array(&*value),
+						)
+					})
+					.collect::<Vec<_>>();
+
+				K::object(entries.into_iter().collect(), json.metadata().clone())
+			}
+			_ => copy(json),
+		}
+	}
+}
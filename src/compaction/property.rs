@@ -7,7 +7,7 @@ use crate::{
 	object,
 	syntax::{Container, ContainerType, Keyword, Term},
 	util::JsonFrom,
-	ContextMut, Error, ErrorCode, Id, Indexed, Node, Object, Reference,
+	ContextMut, Error, ErrorCode, Id, Indexed, Node, Object, ProcessingMode, Reference,
 };
 use cc_traits::Len;
 use generic_json::{JsonBuild, JsonClone, JsonHash, JsonIntoMut, JsonMut, ValueMut};
@@ -45,7 +45,7 @@ where
 		active_context.clone(),
 		Some(item_active_property),
 		loader,
-		options,
+		options.clone(),
 		meta.clone(),
 	)
 	.await?;
@@ -69,7 +69,7 @@ where
 			&Term::Keyword(Keyword::List),
 			true,
 			false,
-			options,
+			options.clone(),
 		)?;
 		let mut compacted_item_list_object = K::Object::default();
 		compacted_item_list_object.insert(
@@ -148,7 +148,7 @@ where
 			active_context.clone(),
 			Some(item_active_property),
 			loader,
-			options,
+			options.clone(),
 			meta.clone(),
 		)
 		.await?;
@@ -178,7 +178,8 @@ where
 		};
 
 		let map_key =
-			compact_iri::<J, _, _>(active_context, &id_value, vocab, false, options)?.unwrap();
+			compact_iri::<J, _, _>(active_context, &id_value, vocab, false, options.clone())?
+				.unwrap();
 
 		// Use `add_value` to add `compacted_item` to
 		// the `map_key` entry in `map_object` using `as_array`.
@@ -231,7 +232,7 @@ where
 					&Term::Keyword(Keyword::Included),
 					true,
 					false,
-					options,
+					options.clone(),
 				)?
 				.unwrap();
 				let mut map = K::Object::default();
@@ -264,7 +265,7 @@ where
 			&Term::Keyword(Keyword::Graph),
 			true,
 			false,
-			options,
+			options.clone(),
 		)?
 		.unwrap();
 		let mut map = K::Object::default();
@@ -281,7 +282,7 @@ where
 				&Term::Keyword(Keyword::Id),
 				false,
 				false,
-				options,
+				options.clone(),
 			)?
 			.unwrap();
 			let value = compact_iri::<J, _, _>(
@@ -289,7 +290,7 @@ where
 				&id.clone().into_term(),
 				false,
 				false,
-				options,
+				options.clone(),
 			)?;
 			map.insert(
 				K::new_key(key.as_str(), meta(None)),
@@ -362,7 +363,7 @@ fn select_nest_result<'a, K: 'a + JsonBuild + JsonMut + JsonIntoMut, T: Id, C: C
 	result: &'a mut K::Object,
 	active_context: Inversible<T, &C>,
 	item_active_property: &str,
-	compact_arrays: bool,
+	options: Options,
 	meta: M,
 ) -> Result<(&'a mut K::Object, Container, bool), Error>
 where
@@ -370,7 +371,12 @@ where
 {
 	let (nest_result, container) = match active_context.get(item_active_property) {
 		Some(term_definition) => {
-			let nest_result = match &term_definition.nest {
+			// `@nest` is not supported in json-ld-1.0.
+			let nest = term_definition
+				.nest
+				.as_ref()
+				.filter(|_| options.processing_mode != ProcessingMode::JsonLd1_0);
+			let nest_result = match nest {
 				Some(nest_term) => {
 					// If nest term is not @nest,
 					// or a term in the active context that expands to @nest,
@@ -424,7 +430,7 @@ where
 	{
 		true
 	} else {
-		!compact_arrays
+		!options.compact_arrays
 	};
 
 	Ok((nest_result, container, as_array))
@@ -469,7 +475,7 @@ where
 			expanded_item,
 			true,
 			inside_reverse,
-			options,
+			options.clone(),
 		)?;
 
 		// If the term definition for `item_active_property` in the active context
@@ -480,7 +486,7 @@ where
 					result,
 					active_context.clone(),
 					item_active_property.as_str(),
-					options.compact_arrays,
+					options.clone(),
 					|| meta(None),
 				)?;
 
@@ -502,7 +508,7 @@ where
 						item_active_property.as_str(),
 						active_context.clone(),
 						loader,
-						options,
+						options.clone(),
 						meta.clone(),
 					)
 					.await?
@@ -517,7 +523,7 @@ where
 						item_active_property.as_str(),
 						active_context.clone(),
 						loader,
-						options,
+						options.clone(),
 						meta.clone(),
 					)
 					.await?
@@ -529,7 +535,7 @@ where
 							active_context.clone(),
 							Some(item_active_property.as_str()),
 							loader,
-							options,
+							options.clone(),
 							meta.clone(),
 						)
 						.await?;
@@ -573,15 +579,15 @@ where
 							&Term::Keyword(container_type.into()),
 							true,
 							false,
-							options,
+							options.clone(),
 						)?;
 
 						// Initialize `index_key` to the value of index mapping in
 						// the term definition associated with `item_active_property`
 						// in active context, or @index, if no such value exists.
 						let index_key = match active_context.get(item_active_property.as_str()) {
-							Some(def) if def.index.is_some() => def.index.as_ref().unwrap(),
-							_ => "@index",
+							Some(def) => def.index_key(),
+							None => "@index",
 						};
 
 						// If `container` includes @language and `expanded_item`
@@ -614,7 +620,7 @@ where
 									&Term::Ref(Reference::Invalid(index_key.to_string())),
 									true,
 									false,
-									options,
+									options.clone(),
 								)?;
 
 								// Set `map_key` to the first value of
@@ -752,7 +758,7 @@ where
 											active_context.clone(),
 											Some(item_active_property.as_str()),
 											loader,
-											options,
+											options.clone(),
 											meta.clone(),
 										)
 										.await?
@@ -772,7 +778,7 @@ where
 									&Term::Keyword(Keyword::None),
 									true,
 									false,
-									options,
+									options.clone(),
 								)?;
 								key.unwrap()
 							}
@@ -810,7 +816,7 @@ where
 			&Indexed::new(Object::Node(Node::new()), None),
 			true,
 			inside_reverse,
-			options,
+			options.clone(),
 		)?;
 
 		// If the term definition for `item_active_property` in the active context
@@ -820,7 +826,7 @@ where
 				result,
 				active_context.clone(),
 				item_active_property.as_str(),
-				options.compact_arrays,
+				options,
 				|| meta(None),
 			)?;
 
@@ -0,0 +1,98 @@
+use super::{compact_iri_with, term_preferences, Options};
+use crate::{
+	context::inverse::Inversible,
+	object,
+	syntax::{Container, Term},
+	Context, Error, Id, Indexed,
+};
+use generic_json::JsonHash;
+
+/// Term of the active context selected by the compaction algorithm.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct SelectedTerm {
+	/// Term.
+	pub term: String,
+
+	/// Container mapping of the term definition.
+	pub container: Container,
+}
+
+/// Outcome of the compaction of a property value, as decided by the compaction algorithm.
+///
+/// See [`select_property`].
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct PropertySelection {
+	/// Compacted property, as it would appear as an entry key of the compacted document.
+	pub key: String,
+
+	/// Term selected for the value, if any.
+	///
+	/// If `None`, no term definition is compatible with the value,
+	/// and the property is compacted into a vocabulary-relative IRI, a compact IRI,
+	/// or left as is.
+	pub term: Option<SelectedTerm>,
+
+	/// Containers compatible with the value, from the most to the least preferred.
+	///
+	/// The selected term is the first term defined with one of those containers and
+	/// a compatible type or language mapping.
+	/// Empty if no term definition maps to the property.
+	pub preferred_containers: Vec<Container>,
+}
+
+/// Explains how the given property would be compacted with the given value.
+///
+/// This runs the
+/// [IRI Compaction algorithm](https://www.w3.org/TR/json-ld11-api/#iri-compaction)
+/// on the property, and returns the selected term along with its container mapping,
+/// which decides how the value is compacted (into a list, a language map, an index map, etc.).
+/// The value only needs to have the shape of the actual value:
+/// its type, language, direction, index, identifier and kind of object (node, value, list, graph).
+///
+/// If `reverse` is `true`, the value is the value of a reverse property.
+///
+/// Returns `None` if the property is `null`.
+pub fn select_property<J: JsonHash, T: Id, C: Context<T>, N: object::Any<J, T>>(
+	active_context: &C,
+	property: &Term<T>,
+	value: &Indexed<N>,
+	reverse: bool,
+	options: Options,
+) -> Result<Option<PropertySelection>, Error> {
+	let active_context = Inversible::new(active_context);
+
+	let key = match compact_iri_with::<J, _, _, _>(
+		active_context.clone(),
+		property,
+		value,
+		true,
+		reverse,
+		options.clone(),
+	)? {
+		Some(key) => key,
+		None => return Ok(None),
+	};
+
+	let mut term = None;
+	let mut preferred_containers = Vec::new();
+	if let Some(entry) = active_context.inverse().get(property) {
+		let (containers, selection) =
+			term_preferences::<J, T, C, N>(active_context.clone(), Some(value), reverse, options)?;
+
+		// Keywords may be spelled literally (see `Options::use_keyword_aliases`).
+		term = entry
+			.select_with_container(&containers, &selection)
+			.filter(|(_, term)| *term == key)
+			.map(|(container, term)| SelectedTerm {
+				term: term.to_string(),
+				container,
+			});
+		preferred_containers = containers;
+	}
+
+	Ok(Some(PropertySelection {
+		key,
+		term,
+		preferred_containers,
+	}))
+}
@@ -1,21 +1,26 @@
-use super::{compact_iri, JsonSrc, Options};
+use super::{compact_iri, compact_property, scoped_context, JsonSrc, Options};
 use crate::{
 	context::{self, Inversible, Loader, Local},
+	object,
 	syntax::{Container, ContainerType, Keyword, Term, Type},
 	util::{AsAnyJson, AsJson, JsonFrom},
-	ContextMut, Error, Id, Loc, Reference, Value,
+	ContextMut, Error, ErrorCode, Id, Indexed, Loc, ProcessingMode, Reference, Value,
 };
 
 /// Compact the given indexed value.
+///
+/// An annotated value is never compacted into a scalar.
 pub async fn compact_indexed_value_with<
 	J: JsonSrc,
 	K: JsonFrom<J>,
 	T: Sync + Send + Id,
 	C: ContextMut<T>,
 	L: Loader,
+	N: object::Any<J, T> + Sync + Send,
 	M,
 >(
 	value: &Value<J, T>,
+	annotation: Option<&[Indexed<N>]>,
 	index: Option<&str>,
 	active_context: Inversible<T, &C>,
 	active_property: Option<&str>,
@@ -33,14 +38,14 @@ where
 	let mut active_context = active_context.into_borrowed();
 	if let Some(active_property) = active_property {
 		if let Some(active_property_definition) = active_context.get(active_property) {
-			if let Some(local_context) = &active_property_definition.context {
+			if let Some(local_context) = scoped_context(active_property_definition, &options) {
 				active_context = Inversible::new(
 					local_context
 						.process_with(
 							*active_context.as_ref(),
 							loader,
 							active_property_definition.base_url(),
-							context::ProcessingOptions::from(options).with_override(),
+							context::ProcessingOptions::from(&options).with_override(),
 						)
 						.await
 						.map_err(Loc::unwrap)?
@@ -112,10 +117,19 @@ where
 	let remove_index =
 		(index.is_some() && container_mapping.contains(ContainerType::Index)) || index.is_none();
 
+	// Annotations are only kept if enabled.
+	let annotation = annotation.filter(|_| options.annotation);
+
+	if options.use_native_types && index.is_none() && annotation.is_none() {
+		if let Some(native) = native_value(value, meta(None)) {
+			return Ok(native);
+		}
+	}
+
 	match value {
 		Value::Literal(lit, ty) => {
 			use crate::object::value::Literal;
-			if ty.as_ref().map(Type::Ref) == type_mapping && remove_index {
+			if ty.as_ref().map(Type::Ref) == type_mapping && remove_index && annotation.is_none() {
 				match lit {
 					Literal::Null => return Ok(K::null(meta(None))),
 					Literal::Boolean(b) => return Ok(b.as_json_with(meta(None))),
@@ -129,7 +143,7 @@ where
 								&Term::Keyword(Keyword::Value),
 								true,
 								false,
-								options,
+								options.clone(),
 							)?;
 							result.insert(
 								K::new_key(compact_key.as_ref().unwrap().as_str(), meta(None)),
@@ -144,7 +158,7 @@ where
 					&Term::Keyword(Keyword::Value),
 					true,
 					false,
-					options,
+					options.clone(),
 				)?;
 				match lit {
 					Literal::Null => {
@@ -179,14 +193,14 @@ where
 						&Term::Keyword(Keyword::Type),
 						true,
 						false,
-						options,
+						options.clone(),
 					)?;
 					let compact_ty = compact_iri::<J, _, _>(
 						active_context.as_ref(),
 						&Term::Ref(Reference::Id(ty.clone())),
 						true,
 						false,
-						options,
+						options.clone(),
 					)?;
 					result.insert(
 						K::new_key(compact_key.as_ref().unwrap().as_str(), meta(None)),
@@ -202,7 +216,7 @@ where
 			let ls_language = ls.language(); //.map(|l| Nullable::Some(l));
 			let ls_direction = ls.direction(); //.map(|d| Nullable::Some(d));
 
-			if remove_index
+			if remove_index && annotation.is_none()
 			&& (ls_language.is_none() || language == ls_language) // || (ls.language().is_none() && language.is_none()))
 			&& (ls_direction.is_none() || direction == ls_direction)
 			{
@@ -214,7 +228,7 @@ where
 					&Term::Keyword(Keyword::Value),
 					true,
 					false,
-					options,
+					options.clone(),
 				)?;
 				result.insert(
 					K::new_key(compact_key.as_ref().unwrap().as_str(), meta(None)),
@@ -227,7 +241,7 @@ where
 						&Term::Keyword(Keyword::Language),
 						true,
 						false,
-						options,
+						options.clone(),
 					)?;
 					result.insert(
 						K::new_key(compact_key.as_ref().unwrap().as_str(), meta(None)),
@@ -241,7 +255,7 @@ where
 						&Term::Keyword(Keyword::Direction),
 						true,
 						false,
-						options,
+						options.clone(),
 					)?;
 					result.insert(
 						K::new_key(compact_key.as_ref().unwrap().as_str(), meta(None)),
@@ -250,8 +264,12 @@ where
 				}
 			}
 		}
+		Value::Json(_) if options.processing_mode == ProcessingMode::JsonLd1_0 => {
+			// JSON literals are not supported in json-ld-1.0.
+			return Err(ErrorCode::InvalidValueObjectValue.into());
+		}
 		Value::Json(value) => {
-			if type_mapping == Some(Type::Json) && remove_index {
+			if type_mapping == Some(Type::Json) && remove_index && annotation.is_none() {
 				return Ok(value.as_json_with(meta));
 			} else {
 				let compact_key = compact_iri::<J, _, _>(
@@ -259,7 +277,7 @@ where
 					&Term::Keyword(Keyword::Value),
 					true,
 					false,
-					options,
+					options.clone(),
 				)?;
 				result.insert(
 					K::new_key(compact_key.as_ref().unwrap().as_str(), meta(None)),
@@ -271,14 +289,14 @@ where
 					&Term::Keyword(Keyword::Type),
 					true,
 					false,
-					options,
+					options.clone(),
 				)?;
 				let compact_ty = compact_iri::<J, _, _>(
 					active_context.as_ref(),
 					&Term::Keyword(Keyword::Json),
 					true,
 					false,
-					options,
+					options.clone(),
 				)?;
 				result.insert(
 					K::new_key(compact_key.as_ref().unwrap().as_str(), meta(None)),
@@ -298,7 +316,7 @@ where
 				&Term::Keyword(Keyword::Index),
 				true,
 				false,
-				options,
+				options.clone(),
 			)?;
 			result.insert(
 				K::new_key(compact_key.as_ref().unwrap().as_str(), meta(None)),
@@ -307,5 +325,52 @@ where
 		}
 	}
 
+	if let Some(annotation) = annotation {
+		compact_property::<J, K, _, _, _, _, _, _>(
+			&mut result,
+			Term::Keyword(Keyword::Annotation),
+			annotation,
+			active_context.as_ref(),
+			loader,
+			false,
+			options,
+			meta.clone(),
+		)
+		.await?
+	}
+
 	Ok(K::object(result, meta(None)))
 }
+
+/// Largest integer such that every integer of smaller magnitude can be represented
+/// exactly by a double precision floating point number.
+const MAX_SAFE_INTEGER: i64 = (1 << 53) - 1;
+
+/// Converts the given typed literal into a native JSON value, if it can be done
+/// without loss.
+fn native_value<J: JsonSrc, K: JsonFrom<J>, T: Id>(
+	value: &Value<J, T>,
+	meta: K::MetaData,
+) -> Option<K> {
+	use crate::object::value::{xsd, Literal};
+	match value {
+		Value::Literal(Literal::String(s), Some(_)) => match value.xsd_datatype()? {
+			xsd::Datatype::Boolean => match s.as_str() {
+				"true" => Some(K::boolean(true, meta)),
+				"false" => Some(K::boolean(false, meta)),
+				_ => None,
+			},
+			xsd::Datatype::Integer => {
+				let i: i64 = s.as_str().parse().ok()?;
+				let safe = (-MAX_SAFE_INTEGER..=MAX_SAFE_INTEGER).contains(&i);
+				if safe && i.to_string() == s.as_str() {
+					Some(K::number(i.into(), meta))
+				} else {
+					None
+				}
+			}
+			_ => None,
+		},
+		_ => None,
+	}
+}
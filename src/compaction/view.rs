@@ -0,0 +1,209 @@
+use crate::{
+	expansion::expand_iri,
+	syntax::{Keyword, Term},
+	util::as_array,
+	Context, Id,
+};
+use cc_traits::{CollectionRef, MapIter};
+use generic_json::{Json, JsonClone, ValueRef};
+use std::marker::PhantomData;
+
+/// Read-only view over a compacted document.
+///
+/// Entries are looked up by term, compact IRI, IRI or keyword using the context
+/// the document has been compacted with, so that aliases are honored and
+/// the lookup does not depend on how each property has been compacted.
+/// Properties nested in nest groups (by terms defined with `@nest`) are found
+/// as if they were top-level entries.
+///
+/// ```
+/// # fn main() -> Result<(), json_ld::Loc<json_ld::Error, ()>> {
+/// use async_std::task;
+/// use json_ld::{compaction::CompactedDocument, context::{self, Local}, Document, NoLoader};
+/// use serde_json::Value;
+///
+/// let input: Value = serde_json::from_str(r#"
+///   {
+///     "@id": "https://example.com/",
+///     "http://xmlns.com/foaf/0.1/name": "Example"
+///   }
+/// "#).unwrap();
+///
+/// let context: Value = serde_json::from_str(r#"
+///   {
+///     "id": "@id",
+///     "label": "http://xmlns.com/foaf/0.1/name"
+///   }
+/// "#).unwrap();
+///
+/// let mut loader = NoLoader::<Value>::new();
+/// let processed_context =
+///   task::block_on(context.process::<context::Json<Value>, _>(&mut loader, None))?;
+/// let output = task::block_on(input.compact(&processed_context, &mut loader)).unwrap();
+///
+/// let doc = CompactedDocument::new(&output, &*processed_context);
+/// assert_eq!(doc.get_str("@id").as_deref(), Some("https://example.com/"));
+/// assert_eq!(doc.get_str("http://xmlns.com/foaf/0.1/name").as_deref(), Some("Example"));
+/// # Ok(())
+/// # }
+/// ```
+pub struct CompactedDocument<'a, K: Json, T: Id, C: Context<T>> {
+	json: &'a K,
+	context: &'a C,
+	t: PhantomData<T>,
+}
+
+impl<'a, K: Json, T: Id, C: Context<T>> CompactedDocument<'a, K, T, C> {
+	/// Creates a view over the given compacted document,
+	/// compacted using the given context.
+	pub fn new(json: &'a K, context: &'a C) -> Self {
+		Self {
+			json,
+			context,
+			t: PhantomData,
+		}
+	}
+
+	/// Returns the underlying JSON document.
+	#[inline(always)]
+	pub fn as_json(&self) -> &'a K {
+		self.json
+	}
+
+	/// Returns the context used to resolve keys.
+	#[inline(always)]
+	pub fn context(&self) -> &'a C {
+		self.context
+	}
+
+	/// Expands the given key.
+	fn expand(&self, key: &str) -> Term<T> {
+		let mut warnings = Vec::new();
+		expand_iri(None, self.context, key, &(), false, true, &mut warnings)
+	}
+
+	/// Returns the value of the entry matching the given key, if any.
+	///
+	/// An entry matches if its key is equal to the given key,
+	/// or if both keys expand to the same IRI or keyword.
+	/// Only the top-level entries are considered:
+	/// use the other accessors to also look into nest groups.
+	pub fn get(&self, key: &str) -> Option<<K::Object as CollectionRef>::ItemRef<'a>> {
+		let obj = self.json.as_object()?;
+		let expanded = self.expand(key);
+
+		obj.iter()
+			.find(|(entry_key, _)| {
+				let entry_key: &str = entry_key.as_ref();
+				entry_key == key || (!expanded.is_null() && self.expand(entry_key) == expanded)
+			})
+			.map(|(_, value)| value)
+	}
+
+	/// Checks if the document has an entry matching the given key,
+	/// at the top level or in a nest group.
+	pub fn contains(&self, key: &str) -> bool {
+		let mut found = false;
+		self.for_each_entry(self.json, key, &self.expand(key), &mut |_| found = true);
+		found
+	}
+
+	/// Calls `f` on the value of every entry of `json` matching the given key,
+	/// including entries of nest groups (entries whose key expands to `@nest`).
+	fn for_each_entry(&self, json: &K, key: &str, expanded: &Term<T>, f: &mut impl FnMut(&K)) {
+		if let Some(obj) = json.as_object() {
+			for (entry_key, value) in obj.iter() {
+				let entry_key: &str = entry_key.as_ref();
+				let entry_expanded = self.expand(entry_key);
+				if entry_key == key || (!expanded.is_null() && entry_expanded == *expanded) {
+					f(&*value)
+				} else if entry_expanded == Term::Keyword(Keyword::Nest) {
+					let (items, _) = as_array(&*value);
+					for item in items {
+						self.for_each_entry(&*item, key, expanded, f)
+					}
+				}
+			}
+		}
+	}
+
+	/// Returns the string value of the entry matching the given key.
+	///
+	/// If the value is an array, its first item is used.
+	/// Value objects are unwrapped.
+	pub fn get_str(&self, key: &str) -> Option<String> {
+		self.get_all_with(key, &|value| value.as_str().map(ToString::to_string))
+			.into_iter()
+			.next()
+	}
+
+	/// Returns the boolean value of the entry matching the given key.
+	///
+	/// If the value is an array, its first item is used.
+	/// Value objects are unwrapped.
+	pub fn get_bool(&self, key: &str) -> Option<bool> {
+		self.get_all_with(key, &|value| value.as_bool())
+			.into_iter()
+			.next()
+	}
+
+	/// Returns the numeric value of the entry matching the given key.
+	///
+	/// If the value is an array, its first item is used.
+	/// Value objects are unwrapped.
+	pub fn get_f64(&self, key: &str) -> Option<f64> {
+		self.get_all_with(key, &|value| value.as_f64())
+			.into_iter()
+			.next()
+	}
+
+	/// Returns every string value of the entry matching the given key.
+	///
+	/// Value objects are unwrapped. Values that are not strings are ignored.
+	pub fn get_all_str(&self, key: &str) -> Vec<String> {
+		self.get_all_with(key, &|value| value.as_str().map(ToString::to_string))
+	}
+
+	/// Returns every value of the entries matching the given key,
+	/// at the top level or in a nest group.
+	///
+	/// If a value is not an array, it is returned as a single item.
+	pub fn get_all(&self, key: &str) -> Vec<K>
+	where
+		K: JsonClone,
+	{
+		let mut result = Vec::new();
+		self.for_each_entry(self.json, key, &self.expand(key), &mut |value| {
+			let (items, _) = as_array(value);
+			result.extend(items.map(|item| (*item).clone()))
+		});
+		result
+	}
+
+	/// Applies `f` on every (unwrapped) value of the entries matching the given key.
+	fn get_all_with<U>(&self, key: &str, f: &impl Fn(&K) -> Option<U>) -> Vec<U> {
+		let mut result = Vec::new();
+		self.for_each_entry(self.json, key, &self.expand(key), &mut |value| {
+			let (items, _) = as_array(value);
+			result.extend(items.filter_map(|item| self.literal(&*item, f)))
+		});
+		result
+	}
+
+	/// Applies `f` on the given value, unwrapping value objects.
+	fn literal<U>(&self, value: &K, f: &impl Fn(&K) -> Option<U>) -> Option<U> {
+		match value.as_value_ref() {
+			ValueRef::Object(obj) => {
+				for (key, item) in obj.iter() {
+					let key: &str = key.as_ref();
+					if self.expand(key) == Term::Keyword(Keyword::Value) {
+						return f(&*item);
+					}
+				}
+
+				None
+			}
+			_ => f(value),
+		}
+	}
+}
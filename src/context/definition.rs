@@ -1,10 +1,14 @@
-use super::Context;
+use super::{Context, ContextMut, Loader, Local, ProcessingOptions};
+use crate::util::future::{BoxFuture, FutureExt};
 use crate::{
 	lang::LenientLanguageTagBuf,
 	syntax::{Container, Term, Type},
-	Direction, Id, Nullable,
+	Direction, Error, Id, Loc, Nullable, Reference,
 };
+use generic_json::Json;
 use iref::{Iri, IriBuf};
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
 // use langtag::LanguageTagBuf;
 
 // A term definition.
@@ -45,12 +49,123 @@ pub struct TermDefinition<T: Id, C: Context<T>> {
 
 	// Optional type mapping.
 	pub typ: Option<Type<T>>,
+
+	// Processed versions of the optional context.
+	pub scoped_contexts: ScopedContextCache<C>,
 }
 
 impl<T: Id, C: Context<T>> TermDefinition<T, C> {
 	pub fn base_url(&self) -> Option<Iri> {
 		self.base_url.as_ref().map(|iri| iri.as_iri())
 	}
+
+	/// Returns the key used to index the values of an index map (`@container: @index`).
+	///
+	/// This is the index mapping of the term if any (`"@index": "prop"`), in which case
+	/// the index map keys are values of the `prop` property, or `@index` otherwise.
+	pub fn index_key(&self) -> &str {
+		self.index.as_deref().unwrap_or("@index")
+	}
+
+	/// Returns the binding of this term definition,
+	/// or `None` if the term is not mapped to an IRI or blank node identifier
+	/// (keyword aliases and terms explicitly mapped to `null`).
+	pub fn binding(&self) -> Option<TermBinding<T>> {
+		match &self.value {
+			Some(Term::Ref(iri)) => Some(TermBinding {
+				iri: iri.clone(),
+				typ: self.typ.clone(),
+				container: self.container,
+				reverse: self.reverse_property,
+				language: self.language.clone(),
+				direction: self.direction,
+				scoped: self.context.is_some(),
+			}),
+			_ => None,
+		}
+	}
+}
+
+/// Binding of a term to a property, as exported by [`Context::bindings`].
+///
+/// This is the part of a term definition that describes the values of the property,
+/// suitable to generate language bindings (such as structure or type definitions)
+/// from a context.
+#[derive(Clone, PartialEq, Eq)]
+pub struct TermBinding<T: Id> {
+	/// IRI (or blank node identifier) of the property.
+	pub iri: Reference<T>,
+
+	/// Type coercion (`@type` entry of the term definition), if any.
+	pub typ: Option<Type<T>>,
+
+	/// Container mapping.
+	pub container: Container,
+
+	/// Reverse property flag.
+	pub reverse: bool,
+
+	/// Language mapping, if any.
+	pub language: Option<Nullable<LenientLanguageTagBuf>>,
+
+	/// Direction mapping, if any.
+	pub direction: Option<Nullable<Direction>>,
+
+	/// Whether the term defines a scoped context,
+	/// in which case the values of the property may use other bindings.
+	pub scoped: bool,
+}
+
+impl<T: Id + Send + Sync, C: ContextMut<T> + Send + Sync> TermDefinition<T, C> {
+	/// Processes the local context of this term definition (property-scoped or type-scoped
+	/// context) with the given active context, using the base URL of the term definition.
+	///
+	/// The result is cached in [`scoped_contexts`](Self::scoped_contexts):
+	/// applying the local context to the same active context (with the same
+	/// [generation](Context::generation)) with the same options returns the cached context
+	/// without processing it again.
+	/// If this term definition has no local context, the active context is returned.
+	pub fn process_scoped_context<'a, L: Loader + Send + Sync>(
+		&'a self,
+		active_context: &'a C,
+		loader: &'a mut L,
+		options: ProcessingOptions,
+	) -> BoxFuture<'a, Result<C, Loc<Error, <C::LocalContext as Json>::MetaData>>>
+	where
+		C::LocalContext: From<L::Output>,
+	{
+		async move {
+			let local_context = match &self.context {
+				Some(local_context) => local_context,
+				None => return Ok(active_context.clone()),
+			};
+
+			let base_url = self.base_url();
+			let generation = active_context.generation();
+			if let Some(generation) = generation {
+				if let Some(processed) = self.scoped_contexts.get(generation, base_url, options) {
+					return Ok(processed);
+				}
+			}
+
+			let processed = local_context
+				.process_with(active_context, loader, base_url, options)
+				.await?
+				.into_inner();
+
+			if let Some(generation) = generation {
+				self.scoped_contexts.insert(ProcessedScopedContext {
+					active_context: generation,
+					base_url: base_url.map(IriBuf::from),
+					options,
+					processed: processed.clone(),
+				})
+			}
+
+			Ok(processed)
+		}
+		.boxed()
+	}
 }
 
 impl<T: Id, C: Context<T>> Default for TermDefinition<T, C> {
@@ -68,13 +183,14 @@ impl<T: Id, C: Context<T>> Default for TermDefinition<T, C> {
 			nest: None,
 			index: None,
 			container: Container::new(),
+			scoped_contexts: ScopedContextCache::default(),
 		}
 	}
 }
 
 impl<T: Id, C: Context<T>> PartialEq for TermDefinition<T, C> {
 	fn eq(&self, other: &TermDefinition<T, C>) -> bool {
-		// NOTE we ignore the `protected` flag.
+		// NOTE we ignore the `protected` flag and the scoped contexts cache.
 		self.prefix == other.prefix
 			&& self.reverse_property == other.reverse_property
 			&& self.language == other.language
@@ -90,3 +206,108 @@ impl<T: Id, C: Context<T>> PartialEq for TermDefinition<T, C> {
 }
 
 impl<T: Id, C: Context<T>> Eq for TermDefinition<T, C> {}
+
+/// Maximum number of processed contexts kept by a [`ScopedContextCache`].
+const SCOPED_CONTEXT_CACHE_CAPACITY: usize = 8;
+
+/// Scoped context processed with a given active context.
+pub struct ProcessedScopedContext<C> {
+	/// Generation of the active context the local context has been applied to.
+	pub active_context: u64,
+
+	/// Base URL used to process the local context.
+	pub base_url: Option<IriBuf>,
+
+	/// Processing options, including the `propagate` and `override_protected` flags.
+	pub options: ProcessingOptions,
+
+	/// Resulting context.
+	pub processed: C,
+}
+
+/// Cache of the processed versions of the local context of a term definition.
+///
+/// Property-scoped and type-scoped contexts are processed each time they are applied,
+/// which is costly for documents relying heavily on them.
+/// This cache keeps the last processed contexts, keyed by the
+/// [generation](Context::generation) of the active context they have been applied to,
+/// the base URL and the processing options.
+/// The local context of the term definition is not part of the key:
+/// the cache must be [cleared](Self::clear) if it is modified.
+///
+/// Cloning a cache shares its entries, but not the cache itself.
+/// Since cached contexts are never modified, they only reference entries older than
+/// themselves and no reference cycle can occur.
+pub struct ScopedContextCache<C> {
+	entries: Mutex<VecDeque<Arc<ProcessedScopedContext<C>>>>,
+}
+
+impl<C> ScopedContextCache<C> {
+	/// Returns the number of cached contexts.
+	pub fn len(&self) -> usize {
+		self.entries.lock().unwrap().len()
+	}
+
+	/// Checks if the cache is empty.
+	pub fn is_empty(&self) -> bool {
+		self.len() == 0
+	}
+
+	/// Returns the cached contexts, from the least to the most recently inserted.
+	pub fn entries(&self) -> Vec<Arc<ProcessedScopedContext<C>>> {
+		self.entries.lock().unwrap().iter().cloned().collect()
+	}
+
+	/// Removes every cached context.
+	pub fn clear(&self) {
+		self.entries.lock().unwrap().clear()
+	}
+
+	/// Adds a processed context to the cache, evicting the oldest entry if the cache is full.
+	pub fn insert(&self, entry: ProcessedScopedContext<C>) {
+		let mut entries = self.entries.lock().unwrap();
+		if entries.len() >= SCOPED_CONTEXT_CACHE_CAPACITY {
+			entries.pop_front();
+		}
+
+		entries.push_back(Arc::new(entry))
+	}
+}
+
+impl<C: Clone> ScopedContextCache<C> {
+	/// Returns the cached result of the processing of the local context
+	/// with the active context of the given generation, base URL and options, if any.
+	pub fn get(
+		&self,
+		active_context: u64,
+		base_url: Option<Iri>,
+		options: ProcessingOptions,
+	) -> Option<C> {
+		self.entries
+			.lock()
+			.unwrap()
+			.iter()
+			.find(|entry| {
+				entry.active_context == active_context
+					&& entry.options == options
+					&& entry.base_url.as_ref().map(|url| url.as_iri()) == base_url
+			})
+			.map(|entry| entry.processed.clone())
+	}
+}
+
+impl<C> Default for ScopedContextCache<C> {
+	fn default() -> Self {
+		Self {
+			entries: Mutex::new(VecDeque::new()),
+		}
+	}
+}
+
+impl<C> Clone for ScopedContextCache<C> {
+	fn clone(&self) -> Self {
+		Self {
+			entries: Mutex::new(self.entries.lock().unwrap().clone()),
+		}
+	}
+}
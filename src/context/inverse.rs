@@ -1,4 +1,5 @@
 use super::Context;
+use crate::util::collections::HashMap;
 use crate::{
 	lang::{LenientLanguageTag, LenientLanguageTagBuf},
 	syntax::{Container, Term, Type},
@@ -7,7 +8,7 @@ use crate::{
 use mown::Mown;
 use once_cell::sync::OnceCell;
 use std::sync::Arc;
-use std::{cmp::Ordering, collections::HashMap, fmt};
+use std::{cmp::Ordering, fmt};
 
 /// Context that can be inverted.
 ///
@@ -246,21 +247,32 @@ impl<T: Id> InverseDefinition<T> {
 	}
 
 	pub fn select(&self, containers: &[Container], selection: &Selection<T>) -> Option<&str> {
+		self.select_with_container(containers, selection)
+			.map(|(_, term)| term)
+	}
+
+	/// Selects a term like [`select`](Self::select),
+	/// also returning the container mapping of the selected term.
+	pub fn select_with_container(
+		&self,
+		containers: &[Container],
+		selection: &Selection<T>,
+	) -> Option<(Container, &str)> {
 		for container in containers {
 			if let Some(type_lang_map) = self.get(container) {
 				match selection {
-					Selection::Any => return Some(type_lang_map.any.none.as_str()),
+					Selection::Any => return Some((*container, type_lang_map.any.none.as_str())),
 					Selection::Type(preferred_values) => {
 						for item in preferred_values {
 							if let Some(term) = type_lang_map.typ.select(item.clone()) {
-								return Some(term);
+								return Some((*container, term));
 							}
 						}
 					}
 					Selection::Lang(preferred_values) => {
 						for item in preferred_values {
 							if let Some(term) = type_lang_map.language.select(*item) {
-								return Some(term);
+								return Some((*container, term));
 							}
 						}
 					}
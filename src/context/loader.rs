@@ -1,5 +1,5 @@
-use crate::{loader, Error, ErrorCode};
-use futures::future::{BoxFuture, FutureExt};
+use crate::util::future::{BoxFuture, FutureExt};
+use crate::{loader, Error, ErrorCode, ErrorDetails};
 use generic_json::Json;
 use iref::{Iri, IriBuf};
 
@@ -7,6 +7,7 @@ pub struct RemoteContext<C> {
 	url: IriBuf,
 	source: loader::Id,
 	context: C,
+	version: Option<loader::Version>,
 }
 
 impl<C> RemoteContext<C> {
@@ -15,6 +16,7 @@ impl<C> RemoteContext<C> {
 			url: IriBuf::from(url),
 			source,
 			context,
+			version: None,
 		}
 	}
 
@@ -23,9 +25,22 @@ impl<C> RemoteContext<C> {
 			url,
 			source,
 			context,
+			version: None,
 		}
 	}
 
+	/// Sets the version information of the context document.
+	#[must_use]
+	pub fn with_version(mut self, version: Option<loader::Version>) -> Self {
+		self.version = version;
+		self
+	}
+
+	/// Returns the version information of the context document, if known.
+	pub fn version(&self) -> Option<&loader::Version> {
+		self.version.as_ref()
+	}
+
 	pub fn context(&self) -> &C {
 		&self.context
 	}
@@ -50,6 +65,30 @@ impl<C> RemoteContext<C> {
 			url: self.url,
 			source: self.source,
 			context: self.context.into(),
+			version: self.version,
+		}
+	}
+}
+
+/// Remote context loaded during context processing.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct LoadedContext {
+	/// URL of the context document.
+	pub url: IriBuf,
+
+	/// Identifier of the context document in the loader.
+	pub source: loader::Id,
+
+	/// Version information of the context document, if known.
+	pub version: Option<loader::Version>,
+}
+
+impl<C> From<&RemoteContext<C>> for LoadedContext {
+	fn from(context: &RemoteContext<C>) -> Self {
+		Self {
+			url: context.url.clone(),
+			source: context.source,
+			version: context.version.clone(),
 		}
 	}
 }
@@ -99,18 +138,23 @@ where
 		async move {
 			match self.load(url.as_iri()).await {
 				Ok(remote_doc) => {
+					let version = remote_doc.version().cloned();
 					let (doc, source, url) = remote_doc.into_parts();
 					if let generic_json::Value::Object(obj) = doc.into() {
 						for (key, value) in obj {
 							if &*key == "@context" {
-								return Ok(RemoteContext::from_parts(url, source, value));
+								return Ok(RemoteContext::from_parts(url, source, value)
+									.with_version(version));
 							}
 						}
 					}
 
 					Err(ErrorCode::InvalidRemoteContext.into())
 				}
-				Err(_) => Err(ErrorCode::LoadingRemoteContextFailed.into()),
+				Err(e) => Err(Error::with_source(ErrorCode::LoadingRemoteContextFailed, e)
+					.set_details(ErrorDetails::Iri {
+						iri: url.as_str().to_string(),
+					})),
 			}
 		}
 		.boxed()
@@ -4,40 +4,103 @@ mod definition;
 pub mod inverse;
 mod loader;
 mod processing;
+mod protection;
+mod registry;
 
+use crate::util::future::{BoxFuture, FutureExt};
 use crate::{
 	lang::{LenientLanguageTag, LenientLanguageTagBuf},
-	syntax::Term,
+	syntax::{Keyword, Term},
 	util::{AsJson, JsonFrom},
-	Direction, Error, Id, Loc, ProcessingMode, Warning,
+	Direction, Error, Id, Limits, Loc, ProcessingMode, Warning,
 };
-use futures::{future::BoxFuture, FutureExt};
 use generic_json::{JsonClone, JsonSendSync};
 use iref::{Iri, IriBuf};
 // use langtag::{LanguageTag, LanguageTagBuf};
-use std::collections::HashMap;
+use crate::util::collections::HashMap;
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicU64, Ordering};
 
 pub use definition::*;
 pub use inverse::{InverseContext, Inversible};
 pub use loader::*;
 use processing::*;
+pub use protection::*;
+pub use registry::*;
 
 pub trait JsonContext = JsonSendSync + JsonClone;
 
 /// Options of the Context Processing Algorithm.
-#[derive(Clone, Copy, PartialEq, Eq)]
+///
+/// The default options are the ones used to process the top-level context of a document:
+/// protected term definitions cannot be overridden, and the processed context is propagated
+/// to nested node objects.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub struct ProcessingOptions {
 	/// The processing mode
 	pub processing_mode: ProcessingMode,
 
 	/// Override protected definitions.
+	///
+	/// If `true`, protected term definitions of the active context can be redefined
+	/// by the processed context without raising a
+	/// [`ProtectedTermRedefinition`](crate::ErrorCode::ProtectedTermRedefinition) error.
+	/// This is used by the expansion algorithm when processing property-scoped contexts.
+	///
+	/// Default is `false`.
 	pub override_protected: bool,
 
 	/// Propagate the processed context.
+	///
+	/// If `false`, the processed context only applies to the current node object:
+	/// nested node objects revert to the previous context.
+	/// This is equivalent to a `"@propagate": false` entry in the processed context,
+	/// and is used by the expansion algorithm for type-scoped contexts.
+	///
+	/// Default is `true`.
 	pub propagate: bool,
+
+	/// Resource limits applied to remote contexts.
+	///
+	/// Every loaded remote context is checked against those limits,
+	/// and the remote context chain length is bounded by
+	/// [`Limits::max_remote_contexts`].
+	///
+	/// Default is no limit.
+	pub limits: Limits,
 }
 
 impl ProcessingOptions {
+	/// Options enforcing protected term definitions.
+	///
+	/// Uses the JSON-LD 1.1 processing mode (required for `@protected`),
+	/// does not allow protected definitions to be overridden,
+	/// and propagates the processed context.
+	pub fn strict_protected() -> ProcessingOptions {
+		ProcessingOptions {
+			processing_mode: ProcessingMode::JsonLd1_1,
+			override_protected: false,
+			propagate: true,
+			limits: Limits::default(),
+		}
+	}
+
+	/// Return the same set of options, but with the given resource limits.
+	#[must_use]
+	pub fn with_limits(&self, limits: Limits) -> ProcessingOptions {
+		let mut opt = *self;
+		opt.limits = limits;
+		opt
+	}
+
+	/// Return the same set of options, but with the given processing mode.
+	#[must_use]
+	pub fn with_processing_mode(&self, processing_mode: ProcessingMode) -> ProcessingOptions {
+		let mut opt = *self;
+		opt.processing_mode = processing_mode;
+		opt
+	}
+
 	/// Return the same set of options, but with `override_protected` set to `true`.
 	#[must_use]
 	pub fn with_override(&self) -> ProcessingOptions {
@@ -61,6 +124,14 @@ impl ProcessingOptions {
 		opt.propagate = false;
 		opt
 	}
+
+	/// Return the same set of options, but with `propagate` set to `true`.
+	#[must_use]
+	pub fn with_propagation(&self) -> ProcessingOptions {
+		let mut opt = *self;
+		opt.propagate = true;
+		opt
+	}
 }
 
 impl Default for ProcessingOptions {
@@ -69,6 +140,7 @@ impl Default for ProcessingOptions {
 			processing_mode: ProcessingMode::default(),
 			override_protected: false,
 			propagate: true,
+			limits: Limits::default(),
 		}
 	}
 }
@@ -120,9 +192,91 @@ pub trait Context<T: Id = IriBuf>: Clone {
 	/// Get the previous context.
 	fn previous_context(&self) -> Option<&Self>;
 
+	/// Generation of this context, if it is tracked.
+	///
+	/// Two contexts with the same generation are clones of each other,
+	/// untouched since: they hold the same definitions.
+	/// It is used to cache the processing of scoped contexts
+	/// (see [`TermDefinition::process_scoped_context`]) without comparing contexts.
+	/// Contexts returning `None` (the default) are never cached.
+	fn generation(&self) -> Option<u64> {
+		None
+	}
+
 	fn definitions<'a>(
 		&'a self,
 	) -> Box<dyn 'a + Iterator<Item = (&'a String, &'a TermDefinition<T, Self>)>>;
+
+	/// Returns the keyword aliases defined by this context,
+	/// that is every term defined as a keyword (such as `"id": "@id"`),
+	/// sorted by term.
+	fn keyword_aliases(&self) -> Vec<(&str, Keyword)> {
+		let mut aliases: Vec<_> = self
+			.definitions()
+			.filter_map(|(term, definition)| match &definition.value {
+				Some(Term::Keyword(keyword)) => Some((term.as_str(), *keyword)),
+				_ => None,
+			})
+			.collect();
+		aliases.sort_by(|(a, _), (b, _)| a.cmp(b));
+		aliases
+	}
+
+	/// Returns the protected terms of this context, sorted.
+	///
+	/// See [`verify_protected`] to check that a document only uses protected terms.
+	fn protected_terms(&self) -> Vec<&str> {
+		let mut terms: Vec<_> = self
+			.definitions()
+			.filter(|(_, definition)| definition.protected)
+			.map(|(term, _)| term.as_str())
+			.collect();
+		terms.sort_unstable();
+		terms
+	}
+
+	/// Returns the binding of every term of this context mapped to a property,
+	/// indexed by term.
+	///
+	/// This is a typed export of the term definitions for code generation tools:
+	/// each term is associated to its IRI, type coercion and container mapping.
+	/// Keyword aliases and terms mapped to `null` are ignored,
+	/// as well as scoped contexts (see [`TermBinding::scoped`]).
+	///
+	/// ```
+	/// use json_ld::{context::{self, Context, Local}, syntax::{Container, ContainerType, Type}};
+	/// use json_ld::NoLoader;
+	/// use iref::IriBuf;
+	/// use serde_json::{json, Value};
+	///
+	/// # async_std::task::block_on(async {
+	/// let mut loader = NoLoader::<Value>::new();
+	/// let context = json!({
+	///   "id": "@id",
+	///   "knows": {
+	///     "@id": "http://xmlns.com/foaf/0.1/knows",
+	///     "@type": "@id",
+	///     "@container": "@set"
+	///   }
+	/// })
+	/// .process::<context::Json<Value, IriBuf>, _>(&mut loader, None)
+	/// .await
+	/// .unwrap()
+	/// .into_inner();
+	///
+	/// let bindings = context.bindings();
+	/// assert_eq!(bindings.len(), 1);
+	/// let knows = &bindings["knows"];
+	/// assert_eq!(knows.iri.as_str(), "http://xmlns.com/foaf/0.1/knows");
+	/// assert!(knows.typ == Some(Type::Id));
+	/// assert_eq!(knows.container, Container::from(&[ContainerType::Set]).unwrap());
+	/// # })
+	/// ```
+	fn bindings(&self) -> BTreeMap<String, TermBinding<T>> {
+		self.definitions()
+			.filter_map(|(term, definition)| Some((term.clone(), definition.binding()?)))
+			.collect()
+	}
 }
 
 /// Mutable JSON-LD context.
@@ -162,9 +316,35 @@ pub trait ContextMutProxy<T: Id = IriBuf> {
 }
 
 /// Context processing result.
+///
+/// On success, the [`Processed`] context references the unprocessed local context
+/// and holds the warnings emitted during processing.
+///
+/// ```
+/// use json_ld::{context::{self, Local}, NoLoader};
+/// use serde_json::Value;
+///
+/// let local: Value = serde_json::from_str(r#"{ "name": "http://xmlns.com/foaf/0.1/name" }"#).unwrap();
+/// let mut loader = NoLoader::<Value>::new();
+///
+/// let rt = tokio::runtime::Runtime::new().unwrap();
+/// let processed = rt.block_on(local.process::<context::Json<Value>, _>(&mut loader, None)).unwrap();
+///
+/// let (unprocessed, context, warnings) = processed.into_parts();
+/// assert_eq!(unprocessed, &local);
+/// assert!(warnings.is_empty());
+/// # let _ = context;
+/// ```
 pub type ProcessingResult<'s, J, C> =
 	Result<Processed<'s, J, C>, Loc<Error, <J as generic_json::Json>::MetaData>>;
 
+/// Context processing result owning the unprocessed local context.
+///
+/// Unlike [`ProcessingResult`], it does not borrow the local context,
+/// which makes it easier to return it from functions or store it.
+pub type ProcessingOwnedResult<J, C> =
+	Result<ProcessedOwned<J, C>, Loc<Error, <J as generic_json::Json>::MetaData>>;
+
 /// Local context used for context expansion.
 ///
 /// Local contexts can be seen as "abstract contexts" that can be processed to enrich an
@@ -231,12 +411,101 @@ pub trait Local<T: Id = IriBuf>: JsonSendSync {
 		}
 		.boxed()
 	}
+
+	/// Process the local context with specific options,
+	/// attaching the result to a copy of the local context.
+	///
+	/// Unlike [`process_with`](`Local::process_with`), the result does not borrow `self`,
+	/// so it can be returned from a function or stored in the state of an application,
+	/// and then given to the compaction functions as any other context.
+	fn process_owned_with<'a, C: ContextMut<T> + Send + Sync, L: Loader + Send + Sync>(
+		&'a self,
+		active_context: &'a C,
+		loader: &'a mut L,
+		base_url: Option<Iri<'a>>,
+		options: ProcessingOptions,
+	) -> BoxFuture<'a, ProcessingOwnedResult<Self, C>>
+	where
+		C::LocalContext: From<L::Output> + From<Self>,
+		L::Output: Into<Self>,
+		T: Send + Sync,
+	{
+		async move {
+			self.process_with(active_context, loader, base_url, options)
+				.await
+				.map(Processed::owned)
+		}
+		.boxed()
+	}
+
+	/// Process the local context with the default options,
+	/// attaching the result to a copy of the local context.
+	///
+	/// This is the owning counterpart of [`process`](`Local::process`).
+	///
+	/// ```
+	/// use json_ld::{context::{self, Context, Local, ProcessedOwned}, NoLoader};
+	/// use serde_json::Value;
+	///
+	/// async fn load_context() -> ProcessedOwned<Value, context::Json<Value>> {
+	///   let local: Value =
+	///     serde_json::from_str(r#"{ "name": "http://xmlns.com/foaf/0.1/name" }"#).unwrap();
+	///   let mut loader = NoLoader::<Value>::new();
+	///   local.process_owned(&mut loader, None).await.unwrap()
+	/// }
+	///
+	/// let context = async_std::task::block_on(load_context());
+	/// assert!(context.get("name").is_some());
+	/// ```
+	fn process_owned<'a, C: ContextMut<T> + Default + Send + Sync, L: Loader + Send + Sync>(
+		&'a self,
+		loader: &'a mut L,
+		base_url: Option<Iri<'a>>,
+	) -> BoxFuture<'a, ProcessingOwnedResult<Self, C>>
+	where
+		C::LocalContext: From<L::Output> + From<Self>,
+		L::Output: Into<Self>,
+		T: Send + Sync,
+	{
+		self.process(loader, base_url)
+			.map(|result| result.map(Processed::owned))
+			.boxed()
+	}
+
+	/// Process the local context with the default options,
+	/// blocking the current thread until the processing is complete.
+	///
+	/// This is the synchronous counterpart of [`process`](`Local::process`).
+	/// The processing future is executed by a minimal internal executor,
+	/// which is why the loader must be [`NonBlocking`](crate::loader::NonBlocking).
+	fn process_blocking<
+		'a,
+		's: 'a,
+		C: ContextMut<T> + Default + Send + Sync,
+		L: Loader + crate::loader::NonBlocking + Send + Sync,
+	>(
+		&'s self,
+		loader: &'a mut L,
+		base_url: Option<Iri<'a>>,
+	) -> ProcessingResult<'s, Self, C>
+	where
+		C::LocalContext: From<L::Output> + From<Self>,
+		L::Output: Into<Self>,
+		T: Send + Sync,
+	{
+		futures::executor::block_on(self.process(loader, base_url))
+	}
 }
 
 /// Processed context attached to its original unprocessed local context.
 ///
 /// This is usefull for instance to attach a processed context to its original JSON form,
 /// which is then used by the compaction algorithm to put the context in the compacted document.
+///
+/// Unlike [`Processed`], it owns the local context and has no lifetime parameter.
+/// It is returned by [`Local::process_owned`], or can be obtained from a [`Processed`]
+/// context with [`Processed::owned`] (or `into`).
+/// Both implement [`ContextMutProxy`], so they are accepted alike by the compaction functions.
 #[derive(Clone)]
 pub struct ProcessedOwned<L: generic_json::Json, C> {
 	/// Original unprocessed context.
@@ -247,6 +516,9 @@ pub struct ProcessedOwned<L: generic_json::Json, C> {
 
 	/// Warnings collected during processing.
 	warnings: Vec<Loc<Warning, L::MetaData>>,
+
+	/// Remote contexts loaded during processing.
+	remote_contexts: Vec<LoadedContext>,
 }
 
 impl<L: generic_json::Json, C> ProcessedOwned<L, C> {
@@ -261,18 +533,58 @@ impl<L: generic_json::Json, C> ProcessedOwned<L, C> {
 			local,
 			processed,
 			warnings,
+			remote_contexts: Vec::new(),
 		}
 	}
 
+	/// Sets the list of remote contexts loaded during processing.
+	#[must_use]
+	pub fn with_remote_contexts(mut self, remote_contexts: Vec<LoadedContext>) -> Self {
+		self.remote_contexts = remote_contexts;
+		self
+	}
+
 	/// Returns a reference to the warnings emitted during processing.
 	pub fn warnings(&self) -> &[Loc<Warning, L::MetaData>] {
 		&self.warnings
 	}
 
+	/// Returns the remote contexts loaded during processing, in load order.
+	///
+	/// Each entry gives the URL of the context along with its version information
+	/// (HTTP validators and final URL) when the loader provides it,
+	/// which can be used to record the provenance of processed documents.
+	pub fn remote_contexts(&self) -> &[LoadedContext] {
+		&self.remote_contexts
+	}
+
+	/// Returns a reference to the original unprocessed local context.
+	pub fn local(&self) -> &L {
+		&self.local
+	}
+
+	/// Returns a reference to the processed context.
+	pub fn processed(&self) -> &C {
+		&self.processed
+	}
+
 	/// Consumes the wrapper and returns the processed context.
 	pub fn into_inner(self) -> C {
 		self.processed
 	}
+
+	/// Consumes the wrapper and returns the original unprocessed local context,
+	/// the processed context and the warnings emitted during processing.
+	pub fn into_parts(self) -> (L, C, Vec<Loc<Warning, L::MetaData>>) {
+		(self.local, self.processed, self.warnings)
+	}
+}
+
+impl<'a, L: generic_json::Json + Clone, C> From<Processed<'a, L, C>> for ProcessedOwned<L, C> {
+	#[inline(always)]
+	fn from(processed: Processed<'a, L, C>) -> Self {
+		processed.owned()
+	}
 }
 
 impl<T: Id, L: generic_json::Json, C: ContextMut<T>> ContextMutProxy<T> for ProcessedOwned<L, C> {
@@ -319,6 +631,9 @@ pub struct Processed<'a, L: generic_json::Json, C> {
 
 	/// Warnings collected during processing.
 	warnings: Vec<Loc<Warning, L::MetaData>>,
+
+	/// Remote contexts loaded during processing.
+	remote_contexts: Vec<LoadedContext>,
 }
 
 impl<'a, L: generic_json::Json, C> Processed<'a, L, C> {
@@ -338,19 +653,52 @@ impl<'a, L: generic_json::Json, C> Processed<'a, L, C> {
 			local,
 			processed,
 			warnings,
+			remote_contexts: Vec::new(),
 		}
 	}
 
+	/// Sets the list of remote contexts loaded during processing.
+	#[must_use]
+	pub fn with_remote_contexts(mut self, remote_contexts: Vec<LoadedContext>) -> Self {
+		self.remote_contexts = remote_contexts;
+		self
+	}
+
 	/// Returns a reference to the warnings emitted during processing.
 	pub fn warnings(&self) -> &[Loc<Warning, L::MetaData>] {
 		&self.warnings
 	}
 
+	/// Returns the remote contexts loaded during processing, in load order.
+	///
+	/// Each entry gives the URL of the context along with its version information
+	/// (HTTP validators and final URL) when the loader provides it,
+	/// which can be used to record the provenance of processed documents.
+	pub fn remote_contexts(&self) -> &[LoadedContext] {
+		&self.remote_contexts
+	}
+
+	/// Returns a reference to the original unprocessed local context.
+	pub fn local(&self) -> &'a L {
+		self.local
+	}
+
+	/// Returns a reference to the processed context.
+	pub fn processed(&self) -> &C {
+		&self.processed
+	}
+
 	/// Consumes the wrapper and returns the processed context.
 	pub fn into_inner(self) -> C {
 		self.processed
 	}
 
+	/// Consumes the wrapper and returns the original unprocessed local context,
+	/// the processed context and the warnings emitted during processing.
+	pub fn into_parts(self) -> (&'a L, C, Vec<Loc<Warning, L::MetaData>>) {
+		(self.local, self.processed, self.warnings)
+	}
+
 	/// Clone the referenced local context and return
 	/// a `Processed` context that owns the original local context.
 	pub fn owned(self) -> ProcessedOwned<L, C>
@@ -361,6 +709,7 @@ impl<'a, L: generic_json::Json, C> Processed<'a, L, C> {
 			local: L::clone(self.local),
 			processed: self.processed,
 			warnings: self.warnings,
+			remote_contexts: self.remote_contexts,
 		}
 	}
 }
@@ -397,8 +746,15 @@ impl<'a, J: JsonClone, K: JsonFrom<J>, L: generic_json::Json + AsJson<J, K>, C>
 	}
 }
 
-#[derive(Clone, PartialEq, Eq)]
+/// Returns a new context generation (see [`Context::generation`]).
+fn next_generation() -> u64 {
+	static GENERATION: AtomicU64 = AtomicU64::new(0);
+	GENERATION.fetch_add(1, Ordering::Relaxed)
+}
+
+#[derive(Clone)]
 pub struct Json<J: JsonContext, T: Id = IriBuf> {
+	generation: u64,
 	original_base_url: Option<IriBuf>,
 	base_iri: Option<IriBuf>,
 	vocabulary: Option<Term<T>>,
@@ -411,6 +767,7 @@ pub struct Json<J: JsonContext, T: Id = IriBuf> {
 impl<J: JsonContext, T: Id> Json<J, T> {
 	pub fn new(base_iri: Option<Iri>) -> Self {
 		Self {
+			generation: next_generation(),
 			original_base_url: base_iri.map(|iri| iri.into()),
 			base_iri: base_iri.map(|iri| iri.into()),
 			vocabulary: None,
@@ -422,6 +779,21 @@ impl<J: JsonContext, T: Id> Json<J, T> {
 	}
 }
 
+impl<J: JsonContext, T: Id> PartialEq for Json<J, T> {
+	fn eq(&self, other: &Self) -> bool {
+		// NOTE we ignore the generation.
+		self.original_base_url == other.original_base_url
+			&& self.base_iri == other.base_iri
+			&& self.vocabulary == other.vocabulary
+			&& self.default_language == other.default_language
+			&& self.default_base_direction == other.default_base_direction
+			&& self.previous_context == other.previous_context
+			&& self.definitions == other.definitions
+	}
+}
+
+impl<J: JsonContext, T: Id> Eq for Json<J, T> {}
+
 impl<J: JsonContext, T: Id> ContextMutProxy<T> for Json<J, T> {
 	type Target = Self;
 
@@ -433,6 +805,7 @@ impl<J: JsonContext, T: Id> ContextMutProxy<T> for Json<J, T> {
 impl<J: JsonContext, T: Id> Default for Json<J, T> {
 	fn default() -> Self {
 		Self {
+			generation: next_generation(),
 			original_base_url: None,
 			base_iri: None,
 			vocabulary: None,
@@ -490,6 +863,10 @@ impl<J: JsonContext, T: Id> Context<T> for Json<J, T> {
 	) -> Box<dyn 'a + Iterator<Item = (&'a String, &'a TermDefinition<T, Self>)>> {
 		Box::new(self.definitions.iter())
 	}
+
+	fn generation(&self) -> Option<u64> {
+		Some(self.generation)
+	}
 }
 
 impl<J: JsonContext, T: Id> ContextMut<T> for Json<J, T> {
@@ -498,6 +875,7 @@ impl<J: JsonContext, T: Id> ContextMut<T> for Json<J, T> {
 		term: &str,
 		definition: Option<TermDefinition<T, Self>>,
 	) -> Option<TermDefinition<T, Self>> {
+		self.generation = next_generation();
 		match definition {
 			Some(def) => self.definitions.insert(term.to_string(), def),
 			None => self.definitions.remove(term),
@@ -505,6 +883,7 @@ impl<J: JsonContext, T: Id> ContextMut<T> for Json<J, T> {
 	}
 
 	fn set_base_iri(&mut self, iri: Option<Iri>) {
+		self.generation = next_generation();
 		self.base_iri = match iri {
 			Some(iri) => {
 				let iri_buf: IriBuf = iri.into();
@@ -515,18 +894,22 @@ impl<J: JsonContext, T: Id> ContextMut<T> for Json<J, T> {
 	}
 
 	fn set_vocabulary(&mut self, vocab: Option<Term<T>>) {
+		self.generation = next_generation();
 		self.vocabulary = vocab;
 	}
 
 	fn set_default_language(&mut self, lang: Option<LenientLanguageTagBuf>) {
+		self.generation = next_generation();
 		self.default_language = lang;
 	}
 
 	fn set_default_base_direction(&mut self, dir: Option<Direction>) {
+		self.generation = next_generation();
 		self.default_base_direction = dir;
 	}
 
 	fn set_previous_context(&mut self, previous: Self) {
+		self.generation = next_generation();
 		self.previous_context = Some(Box::new(previous))
 	}
 }
@@ -1,23 +1,26 @@
 use super::{
-	Context, ContextMut, JsonContext, Loader, Local, Processed, ProcessingOptions,
+	Context, ContextMut, JsonContext, LoadedContext, Loader, Local, Processed, ProcessingOptions,
 	ProcessingResult, TermDefinition,
 };
+use crate::util::collections::HashMap;
+use crate::util::future::{BoxFuture, FutureExt};
 use crate::{
 	expansion, loader,
-	syntax::{is_keyword, is_keyword_like, ContainerType, Keyword, Term, Type},
+	syntax::{
+		check_context_definition, is_keyword, is_keyword_like, ContainerType, Keyword, Term, Type,
+	},
 	util::as_array,
-	BlankId, Direction, Error, ErrorCode, Id, Loc, Nullable, ProcessingMode, Reference, Warning,
+	BlankId, Direction, Error, ErrorCode, ErrorDetails, Id, Inclusion, Loc, Nullable,
+	ProcessingMode, Reference, Warning,
 };
 use cc_traits::{Get, GetKeyValue, Len, MapIter};
-use futures::future::{BoxFuture, FutureExt};
 use generic_json::{Json, Key, ValueRef};
 use iref::{Iri, IriBuf, IriRef};
 use langtag::LanguageTagBuf;
 use mown::Mown;
-use std::collections::HashMap;
 use std::convert::{TryFrom, TryInto};
 use std::future::Future;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 
 /// Local JSON-LD context.
 pub struct LocalContextObject<'o, O> {
@@ -251,14 +254,15 @@ impl<J: JsonContext, T: Id> Local<T> for J {
 			let processed = process_context(
 				active_context,
 				self,
-				stack,
+				stack.clone(),
 				loader,
 				base_url,
 				options,
 				&mut warnings,
 			)
 			.await?;
-			Ok(Processed::with_warnings(self, processed, warnings))
+			Ok(Processed::with_warnings(self, processed, warnings)
+				.with_remote_contexts(stack.loaded_contexts()))
 		}
 		.boxed()
 	}
@@ -317,15 +321,34 @@ impl StackNode {
 /// Context processing stack.
 ///
 /// Contains the list of the loaded contexts to detect loops.
+/// Every remote context loaded while processing is also recorded in a log
+/// shared by all the clones of the stack.
 #[derive(Clone)]
 pub struct ProcessingStack {
 	head: Option<Arc<StackNode>>,
+	loaded: Arc<Mutex<Vec<LoadedContext>>>,
 }
 
 impl ProcessingStack {
 	/// Creates a new empty processing stack.
 	pub fn new() -> ProcessingStack {
-		ProcessingStack { head: None }
+		ProcessingStack {
+			head: None,
+			loaded: Arc::new(Mutex::new(Vec::new())),
+		}
+	}
+
+	/// Records the load of the given remote context, unless it is already recorded.
+	pub fn record(&self, context: LoadedContext) {
+		let mut loaded = self.loaded.lock().unwrap();
+		if !loaded.iter().any(|c| c.url == context.url) {
+			loaded.push(context)
+		}
+	}
+
+	/// Returns the remote contexts loaded so far, in load order.
+	pub fn loaded_contexts(&self) -> Vec<LoadedContext> {
+		self.loaded.lock().unwrap().clone()
 	}
 
 	/// Checks if the stack is empty.
@@ -333,6 +356,18 @@ impl ProcessingStack {
 		self.head.is_none()
 	}
 
+	/// Returns the number of contexts in the stack.
+	pub fn len(&self) -> usize {
+		let mut len = 0;
+		let mut node = self.head.as_ref();
+		while let Some(n) = node {
+			len += 1;
+			node = n.previous.as_ref();
+		}
+
+		len
+	}
+
 	/// Checks if the given URL is already in the stack.
 	///
 	/// This is used for loop detection.
@@ -492,12 +527,28 @@ where
 					// context has been detected and processing is aborted.
 					// Set loaded context to the value of that entry.
 					if remote_contexts.push(context_iri.as_iri()) {
+						options
+							.limits
+							.check_remote_contexts(remote_contexts.len())
+							.map_err(|e| e.located(source, context.metadata().clone()))?;
+
 						let context_document = loader
 							.load_context(context_iri.as_iri())
 							.await
 							.map_err(|e| e.located(source, context.metadata().clone()))?
 							.cast::<J>();
+						remote_contexts.record(LoadedContext::from(&context_document));
+						let inclusion = Inclusion {
+							url: context_iri.clone(),
+							source,
+							import: false,
+						};
+
 						let loaded_context = context_document.context();
+						options
+							.limits
+							.check(loaded_context, Some(context_document.source()))
+							.map_err(|e| e.included_from(inclusion.clone()))?;
 
 						// Set result to the result of recursively calling this algorithm, passing result
 						// for active context, loaded context for local context, the documentUrl of context
@@ -506,6 +557,7 @@ where
 							processing_mode: options.processing_mode,
 							override_protected: false,
 							propagate: true,
+							limits: options.limits,
 						};
 
 						result = loaded_context
@@ -516,7 +568,8 @@ where
 								Some(context_document.url()),
 								new_options,
 							)
-							.await?
+							.await
+							.map_err(|e| e.included_from(inclusion))?
 							.into_inner();
 						// result = process_context(&result, loaded_context, remote_contexts, loader, Some(context_document.url()), new_options).await?
 					}
@@ -590,10 +643,13 @@ where
 								if let Some((import_key, _)) =
 									import_context_obj.get_key_value(Keyword::Import.into())
 								{
-									return Err(ErrorCode::InvalidContextEntry.located(
-										Some(import_source),
-										import_key.metadata().clone(),
-									));
+									return Err(ErrorCode::InvalidContextEntry
+										.located(Some(import_source), import_key.metadata().clone())
+										.included_from(Inclusion {
+											url: import,
+											source,
+											import: true,
+										}));
 								}
 
 								// Set `context` to the result of merging context into
@@ -606,7 +662,12 @@ where
 								merged_context
 							} else {
 								return Err(ErrorCode::InvalidRemoteContext
-									.located(Some(import_source), import_context_metadata));
+									.located(Some(import_source), import_context_metadata)
+									.included_from(Inclusion {
+										url: import,
+										source,
+										import: true,
+									}));
 							}
 						} else {
 							// 5.6.2) If the value of @import is not a string, an invalid
@@ -757,6 +818,17 @@ where
 						}
 					}
 
+					// Before running the (asynchronous) create term definition algorithm,
+					// check the structure of every term definition at once so that grammar
+					// violations are reported with their exact position.
+					if options.processing_mode == ProcessingMode::JsonLd1_1 {
+						let mut violations = Vec::new();
+						check_context_definition::<J>(context, &mut violations);
+						if let Some(violation) = violations.into_iter().next() {
+							return Err(violation.code.located(source, violation.metadata));
+						}
+					}
+
 					// 5.12) Create a map `defined` to keep track of whether or not a term
 					// has already been defined or is currently being defined during recursion.
 					let mut defined = HashMap::new();
@@ -1083,7 +1155,15 @@ where
 								Term::Ref(mapping) if mapping.is_valid() => {
 									definition.value = Some(Term::Ref(mapping))
 								}
-								_ => return Err(ErrorCode::InvalidIriMapping.into()),
+								_ => {
+									return Err(Error::with_details(
+										ErrorCode::InvalidIriMapping,
+										ErrorDetails::InvalidIriMapping {
+											term: term.to_string(),
+											value: reverse_value.to_string(),
+										},
+									))
+								}
 							}
 
 							// If `value` contains an `@container` entry, set the `container`
@@ -1184,7 +1264,13 @@ where
 											// nor an IRI, nor a blank node identifier, an
 											// invalid IRI mapping error has been detected and processing
 											// is aborted;
-											return Err(ErrorCode::InvalidIriMapping.into());
+											return Err(Error::with_details(
+												ErrorCode::InvalidIriMapping,
+												ErrorDetails::InvalidIriMapping {
+													term: term.to_string(),
+													value: id_value.to_string(),
+												},
+											));
 										}
 										value => Some(value),
 									};
@@ -1218,7 +1304,13 @@ where
 										)
 										.await?;
 										if definition.value != Some(expanded_term) {
-											return Err(ErrorCode::InvalidIriMapping.into());
+											return Err(Error::with_details(
+												ErrorCode::InvalidIriMapping,
+												ErrorDetails::InvalidIriMapping {
+													term: term.to_string(),
+													value: id_value.to_string(),
+												},
+											));
 										}
 									}
 
@@ -1285,7 +1377,13 @@ where
 									if let Ok(iri) = Iri::new(result.as_str()) {
 										definition.value = Some(Term::<T>::from(T::from_iri(iri)))
 									} else {
-										return Err(ErrorCode::InvalidIriMapping.into());
+										return Err(Error::with_details(
+											ErrorCode::InvalidIriMapping,
+											ErrorDetails::InvalidIriMapping {
+												term: term.to_string(),
+												value: result.clone(),
+											},
+										));
 									}
 								} else {
 									// Otherwise, `term` is an IRI or blank node identifier.
@@ -1296,7 +1394,13 @@ where
 									} else if let Ok(iri) = Iri::new(term) {
 										definition.value = Some(Term::<T>::from(T::from_iri(iri)))
 									} else {
-										return Err(ErrorCode::InvalidIriMapping.into());
+										return Err(Error::with_details(
+											ErrorCode::InvalidIriMapping,
+											ErrorDetails::InvalidIriMapping {
+												term: term.to_string(),
+												value: term.to_string(),
+											},
+										));
 									}
 								}
 							} else if term.contains('/') {
@@ -1317,7 +1421,15 @@ where
 									}
 									// If the resulting IRI mapping is not an IRI, an invalid IRI mapping
 									// error has been detected and processing is aborted.
-									_ => return Err(ErrorCode::InvalidIriMapping.into()),
+									_ => {
+										return Err(Error::with_details(
+											ErrorCode::InvalidIriMapping,
+											ErrorDetails::InvalidIriMapping {
+												term: term.to_string(),
+												value: term.to_string(),
+											},
+										))
+									}
 								}
 							} else if term == "@type" {
 								// Otherwise, if `term` is ``@type`, set the IRI mapping of definition to
@@ -1335,7 +1447,13 @@ where
 									if let Ok(iri) = Iri::new(result.as_str()) {
 										definition.value = Some(Term::<T>::from(T::from_iri(iri)))
 									} else {
-										return Err(ErrorCode::InvalidIriMapping.into());
+										return Err(Error::with_details(
+											ErrorCode::InvalidIriMapping,
+											ErrorDetails::InvalidIriMapping {
+												term: term.to_string(),
+												value: result.clone(),
+											},
+										));
 									}
 								} else {
 									return Err(ErrorCode::InvalidIriMapping.into());
@@ -1627,6 +1745,7 @@ where
 			}
 		}
 	}
+	.map(move |result| result.map_err(|e| e.with_term(term)))
 	.boxed()
 }
 
@@ -0,0 +1,157 @@
+use super::Context;
+use crate::{
+	syntax::{is_keyword_like, Keyword, Term, Type},
+	util::as_array,
+	Id,
+};
+use cc_traits::MapIter;
+use generic_json::{Json, Key, ValueRef};
+use std::convert::TryFrom;
+
+/// Use of a term that is not protected by the context.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct UnprotectedTerm<M> {
+	/// Term, compact IRI or IRI, as found in the document.
+	pub term: String,
+
+	/// Metadata of the offending key or value.
+	pub metadata: M,
+}
+
+/// Checks that the given document only uses terms protected by the given context.
+///
+/// A term is protected if it cannot be redefined by another context,
+/// in which case its meaning does not depend on the context the document is
+/// eventually processed with: the document survives the substitution of a
+/// (remote) context by a malicious one, provided the protected definitions stay the same.
+/// This is what verifiable credential implementations rely on.
+///
+/// The given context is typically the result of processing the trusted context of the
+/// document. Every key of the document, every `@type` value and every value of a
+/// term with a `@vocab` type mapping is checked. Keywords, absolute IRIs with an
+/// authority (`scheme://...`) and blank node identifiers are always protected.
+/// Other terms must be defined and protected, and compact IRIs must use a protected
+/// prefix (or be defined themselves by a protected definition).
+/// In particular, terms only expanded using the vocabulary mapping are unprotected.
+///
+/// Scoped contexts and contexts embedded in the document are not taken into account:
+/// the terms they define are reported unless they are also protected by the given context.
+/// JSON literals and the content of `@context` entries are not checked.
+///
+/// Returns the unprotected terms used by the document, in document order.
+///
+/// ```
+/// use iref::IriBuf;
+/// use json_ld::{context::{self, verify_protected, Context, Local}, NoLoader};
+/// use serde_json::{json, Value};
+///
+/// # async_std::task::block_on(async {
+/// let mut loader = NoLoader::<Value>::new();
+/// let context = json!({
+///   "@protected": true,
+///   "name": "http://xmlns.com/foaf/0.1/name"
+/// })
+/// .process::<context::Json<Value, IriBuf>, _>(&mut loader, None)
+/// .await
+/// .unwrap()
+/// .into_inner();
+///
+/// assert_eq!(context.protected_terms(), vec!["name"]);
+///
+/// let doc = json!({ "name": "Alice", "age": 42, "http://schema.org/email": "a@b.c" });
+/// let unprotected = verify_protected(&context, &doc);
+/// assert_eq!(unprotected.len(), 1);
+/// assert_eq!(unprotected[0].term, "age");
+/// # })
+/// ```
+pub fn verify_protected<J: Json, T: Id, C: Context<T>>(
+	context: &C,
+	document: &J,
+) -> Vec<UnprotectedTerm<J::MetaData>> {
+	let mut unprotected = Vec::new();
+	visit(context, document, &mut unprotected);
+	unprotected
+}
+
+/// Checks if the given term, compact IRI or IRI cannot be redefined by another context.
+fn is_protected<T: Id, C: Context<T>>(context: &C, term: &str) -> bool {
+	match context.get(term) {
+		Some(definition) => definition.protected,
+		None if is_keyword_like(term) => true,
+		None => match term.find(':') {
+			Some(i) => {
+				let (prefix, suffix) = (&term[..i], &term[(i + 1)..]);
+				prefix == "_"
+					|| suffix.starts_with("//")
+					|| context.get(prefix).map_or(false, |d| d.protected)
+			}
+			None => false,
+		},
+	}
+}
+
+fn visit<J: Json, T: Id, C: Context<T>>(
+	context: &C,
+	value: &J,
+	unprotected: &mut Vec<UnprotectedTerm<J::MetaData>>,
+) {
+	match value.as_value_ref() {
+		ValueRef::Array(_) => {
+			let (items, _) = as_array(value);
+			for item in items {
+				visit(context, &*item, unprotected)
+			}
+		}
+		ValueRef::Object(obj) => {
+			for (key, value) in obj.iter() {
+				let term: &str = key.as_ref();
+				if !is_protected(context, term) {
+					unprotected.push(UnprotectedTerm {
+						term: term.to_string(),
+						metadata: key.metadata().clone(),
+					})
+				}
+
+				let definition = context.get(term);
+				let keyword = match definition.and_then(|d| d.value.as_ref()) {
+					Some(Term::Keyword(keyword)) => Some(*keyword),
+					_ => Keyword::try_from(term).ok(),
+				};
+
+				match keyword {
+					Some(Keyword::Context) | Some(Keyword::Value) => (),
+					Some(Keyword::Type) => visit_vocab_values(context, &*value, unprotected),
+					Some(_) => visit(context, &*value, unprotected),
+					None => match definition.and_then(|d| d.typ.as_ref()) {
+						Some(Type::Json) => (),
+						Some(Type::Vocab) => visit_vocab_values(context, &*value, unprotected),
+						_ => visit(context, &*value, unprotected),
+					},
+				}
+			}
+		}
+		_ => (),
+	}
+}
+
+/// Checks the vocabulary-relative string values of the given value.
+fn visit_vocab_values<J: Json, T: Id, C: Context<T>>(
+	context: &C,
+	value: &J,
+	unprotected: &mut Vec<UnprotectedTerm<J::MetaData>>,
+) {
+	let (items, _) = as_array(value);
+	for item in items {
+		match item.as_str() {
+			Some(term) => {
+				if !is_protected(context, term) {
+					unprotected.push(UnprotectedTerm {
+						term: term.to_string(),
+						metadata: item.metadata().clone(),
+					})
+				}
+			}
+			None => visit(context, &*item, unprotected),
+		}
+	}
+}
@@ -0,0 +1,235 @@
+use super::{ContextMut, JsonContext, Loader, Local, ProcessingOptions};
+use crate::util::collections::HashMap;
+use crate::{loader, Error, Id, Loc};
+use iref::{Iri, IriBuf};
+use std::sync::{Arc, PoisonError, RwLock};
+
+/// Processed context registered in a [`ContextRegistry`].
+pub struct RegisteredContext<C> {
+	/// Version of the context document, if known.
+	pub version: Option<loader::Version>,
+
+	/// Processed context.
+	pub context: Arc<C>,
+}
+
+impl<C> Clone for RegisteredContext<C> {
+	fn clone(&self) -> Self {
+		Self {
+			version: self.version.clone(),
+			context: self.context.clone(),
+		}
+	}
+}
+
+/// Thread-safe registry of processed contexts, keyed by IRI.
+///
+/// The registry is cheap to clone: every clone shares the same underlying storage,
+/// so it can be handed to every worker of a multi-threaded service in order to
+/// process each remote context only once.
+/// Lookups can happen concurrently, insertions are serialized.
+///
+/// Several versions of the same context can be registered, identified by the
+/// [`Version`](loader::Version) returned by the loader.
+/// The most recently registered version is the one returned by [`get`](Self::get).
+///
+/// Registered contexts are processed on top of an empty active context
+/// (with the context IRI as base IRI).
+///
+/// ```
+/// use iref::{Iri, IriBuf};
+/// use json_ld::context::{self, Context, ContextRegistry};
+/// use serde_json::Value;
+///
+/// let registry = ContextRegistry::new();
+/// let url = Iri::new("https://example.com/context.jsonld").unwrap();
+///
+/// let worker_registry = registry.clone();
+/// std::thread::spawn(move || {
+///   worker_registry.insert(url, None, context::Json::<Value, IriBuf>::new(Some(url)));
+/// })
+/// .join()
+/// .unwrap();
+///
+/// assert!(registry.get(url).is_some());
+/// ```
+pub struct ContextRegistry<C> {
+	contexts: Arc<RwLock<HashMap<IriBuf, Vec<RegisteredContext<C>>>>>,
+}
+
+impl<C> Clone for ContextRegistry<C> {
+	fn clone(&self) -> Self {
+		Self {
+			contexts: self.contexts.clone(),
+		}
+	}
+}
+
+impl<C> Default for ContextRegistry<C> {
+	fn default() -> Self {
+		Self {
+			contexts: Arc::new(RwLock::new(HashMap::new())),
+		}
+	}
+}
+
+impl<C> ContextRegistry<C> {
+	/// Creates a new empty registry.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Calls `f` with the registered contexts.
+	///
+	/// The map is never left in an inconsistent state,
+	/// so a poisoned lock can safely be recovered.
+	fn read<U>(&self, f: impl FnOnce(&HashMap<IriBuf, Vec<RegisteredContext<C>>>) -> U) -> U {
+		f(&self.contexts.read().unwrap_or_else(PoisonError::into_inner))
+	}
+
+	fn write<U>(&self, f: impl FnOnce(&mut HashMap<IriBuf, Vec<RegisteredContext<C>>>) -> U) -> U {
+		f(&mut self
+			.contexts
+			.write()
+			.unwrap_or_else(PoisonError::into_inner))
+	}
+
+	/// Returns the latest registered version of the given context, if any.
+	pub fn get(&self, url: Iri) -> Option<Arc<C>> {
+		self.read(|contexts| {
+			contexts
+				.get(&IriBuf::from(url))
+				.and_then(|versions| versions.last())
+				.map(|entry| entry.context.clone())
+		})
+	}
+
+	/// Returns the given version of the given context, if registered.
+	pub fn get_version(&self, url: Iri, version: &loader::Version) -> Option<Arc<C>> {
+		self.read(|contexts| {
+			contexts.get(&IriBuf::from(url)).and_then(|versions| {
+				versions
+					.iter()
+					.find(|entry| entry.version.as_ref() == Some(version))
+					.map(|entry| entry.context.clone())
+			})
+		})
+	}
+
+	/// Returns every registered version of the given context,
+	/// from the oldest to the latest.
+	pub fn versions(&self, url: Iri) -> Vec<RegisteredContext<C>> {
+		self.read(|contexts| {
+			contexts
+				.get(&IriBuf::from(url))
+				.cloned()
+				.unwrap_or_default()
+		})
+	}
+
+	/// Checks if at least one version of the given context is registered.
+	pub fn contains(&self, url: Iri) -> bool {
+		self.read(|contexts| contexts.contains_key(&IriBuf::from(url)))
+	}
+
+	/// Registers a new version of the given context, that becomes its latest version.
+	///
+	/// If a context with the same version is already registered, it is kept and
+	/// returned instead, so that concurrent processing of the same context ends up
+	/// sharing a single instance.
+	/// Contexts without version always replace the latest unversioned one.
+	pub fn insert(&self, url: Iri, version: Option<loader::Version>, context: C) -> Arc<C> {
+		self.write(|contexts| {
+			let versions = contexts.entry(url.into()).or_insert_with(Vec::new);
+
+			if let Some(i) = versions.iter().position(|entry| entry.version == version) {
+				if version.is_some() {
+					return versions[i].context.clone();
+				}
+
+				versions.remove(i);
+			}
+
+			let context = Arc::new(context);
+			versions.push(RegisteredContext {
+				version,
+				context: context.clone(),
+			});
+			context
+		})
+	}
+
+	/// Removes every version of the given context.
+	///
+	/// Returns `true` if the context was registered.
+	pub fn remove(&self, url: Iri) -> bool {
+		self.write(|contexts| contexts.remove(&IriBuf::from(url)).is_some())
+	}
+
+	/// Removes every registered context.
+	pub fn clear(&self) {
+		self.write(|contexts| contexts.clear())
+	}
+
+	/// Returns the processed context for the given IRI,
+	/// loading and processing it with `loader` if it is not yet registered.
+	///
+	/// The registry is not locked while the context is loaded and processed:
+	/// two threads may process the same context at the same time,
+	/// in which case the first registered instance is shared (see [`insert`](Self::insert)).
+	pub async fn process<T: Id, L: Loader>(
+		&self,
+		url: Iri<'_>,
+		loader: &mut L,
+		options: ProcessingOptions,
+	) -> Result<Arc<C>, Error>
+	where
+		C: ContextMut<T> + Send + Sync,
+		C::LocalContext: From<L::Output>,
+		L: Send + Sync,
+		L::Output: JsonContext,
+		T: Send + Sync,
+	{
+		match self.get(url) {
+			Some(context) => Ok(context),
+			None => self.reload::<T, L>(url, loader, options).await,
+		}
+	}
+
+	/// Loads the given context with `loader` and registers it,
+	/// even if it is already registered.
+	///
+	/// The context is only processed again if the loader returns a new version of it.
+	pub async fn reload<T: Id, L: Loader>(
+		&self,
+		url: Iri<'_>,
+		loader: &mut L,
+		options: ProcessingOptions,
+	) -> Result<Arc<C>, Error>
+	where
+		C: ContextMut<T> + Send + Sync,
+		C::LocalContext: From<L::Output>,
+		L: Send + Sync,
+		L::Output: JsonContext,
+		T: Send + Sync,
+	{
+		let remote_context = loader.load_context(url).await?;
+		let version = remote_context.version().cloned();
+
+		if let Some(version) = &version {
+			if let Some(context) = self.get_version(url, version) {
+				return Ok(context);
+			}
+		}
+
+		let local_context = remote_context.into_context();
+		let active_context = C::new(Some(url));
+		let context =
+			Local::<T>::process_with(&local_context, &active_context, loader, Some(url), options)
+				.await
+				.map_err(Loc::unwrap)?
+				.into_inner();
+
+		Ok(self.insert(url, version, context))
+	}
+}
@@ -0,0 +1,155 @@
+use crate::util::collections::{HashMap, HashSet};
+use crate::{loader, BlankId, ExpandedDocument, Id, Indexed, Loc, Object, Reference, Warning};
+use generic_json::JsonHash;
+
+/// Blank node labels renamed in a document added to a [`DatasetBuilder`].
+#[derive(Clone, Debug)]
+pub struct Provenance {
+	/// Source document.
+	source: Option<loader::Id>,
+
+	/// Renamed labels, associated to their new label.
+	renamed: HashMap<BlankId, BlankId>,
+}
+
+impl Provenance {
+	/// Source document, if known.
+	#[inline(always)]
+	pub fn source(&self) -> Option<loader::Id> {
+		self.source
+	}
+
+	/// Number of blank node labels that have been rewritten.
+	#[inline(always)]
+	pub fn renamed_count(&self) -> usize {
+		self.renamed.len()
+	}
+
+	/// Returns the new label of the given original blank node label, if it has been renamed.
+	#[inline(always)]
+	pub fn renamed(&self, id: &BlankId) -> Option<&BlankId> {
+		self.renamed.get(id)
+	}
+
+	/// Returns an iterator over the renamed labels, with their new label.
+	#[inline(always)]
+	pub fn iter(&self) -> impl Iterator<Item = (&BlankId, &BlankId)> {
+		self.renamed.iter()
+	}
+}
+
+/// Dataset builder.
+///
+/// Aggregates multiple expanded documents into a single one.
+/// Blank node labels are local to a document: when the same label is used by two
+/// documents, it is renamed in the last added document so that the two blank nodes
+/// are not merged.
+/// Renamings are recorded for each added document in a [`Provenance`] report.
+pub struct DatasetBuilder<J: JsonHash, T: Id> {
+	objects: HashSet<Indexed<Object<J, T>>>,
+	warnings: Vec<Loc<Warning, J::MetaData>>,
+	labels: HashSet<BlankId>,
+	provenance: Vec<Provenance>,
+	count: usize,
+}
+
+impl<J: JsonHash, T: Id> Default for DatasetBuilder<J, T> {
+	#[inline(always)]
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl<J: JsonHash, T: Id> DatasetBuilder<J, T> {
+	/// Creates a new empty builder.
+	#[inline(always)]
+	pub fn new() -> Self {
+		Self {
+			objects: HashSet::new(),
+			warnings: Vec::new(),
+			labels: HashSet::new(),
+			provenance: Vec::new(),
+			count: 0,
+		}
+	}
+
+	/// Adds the given expanded document, loaded from `source`.
+	///
+	/// Blank node labels already used by a previously added document are renamed.
+	/// Returns the provenance report of the document.
+	pub fn add(&mut self, doc: ExpandedDocument<J, T>, source: Option<loader::Id>) -> &Provenance {
+		let (objects, warnings) = doc.into_parts();
+
+		let mut used = HashSet::new();
+		for object in &objects {
+			object.for_each_reference(&mut |r| {
+				if let Reference::Blank(id) = r {
+					used.insert(id.clone());
+				}
+			})
+		}
+
+		// Sort the labels so that the renaming is deterministic.
+		let mut reused: Vec<_> = used.iter().filter(|id| self.labels.contains(id)).collect();
+		reused.sort_by(|a, b| a.as_str().cmp(b.as_str()));
+
+		let mut renamed = HashMap::new();
+		for id in reused {
+			let fresh = self.fresh_label(&used);
+			renamed.insert(id.clone(), fresh);
+		}
+
+		for id in used {
+			if !renamed.contains_key(&id) {
+				self.labels.insert(id);
+			}
+		}
+
+		if renamed.is_empty() {
+			self.objects.extend(objects)
+		} else {
+			let mut rename = |r: Reference<T>| match r {
+				Reference::Blank(id) => match renamed.get(&id) {
+					Some(fresh) => Reference::Blank(fresh.clone()),
+					None => Reference::Blank(id),
+				},
+				r => r,
+			};
+
+			self.objects.extend(
+				objects
+					.into_iter()
+					.map(|object| object.map_inner(|object| object.map_references(&mut rename))),
+			)
+		}
+
+		self.warnings.extend(warnings);
+		self.provenance.push(Provenance { source, renamed });
+		self.provenance.last().unwrap()
+	}
+
+	/// Generates a blank node label unused by any added document and
+	/// by the given set of labels.
+	fn fresh_label(&mut self, used: &HashSet<BlankId>) -> BlankId {
+		loop {
+			let id = BlankId::new(&format!("d{}", self.count));
+			self.count += 1;
+			if !self.labels.contains(&id) && !used.contains(&id) {
+				self.labels.insert(id.clone());
+				break id;
+			}
+		}
+	}
+
+	/// Provenance reports of the added documents, in insertion order.
+	#[inline(always)]
+	pub fn provenance(&self) -> &[Provenance] {
+		&self.provenance
+	}
+
+	/// Builds the aggregated expanded document.
+	#[inline(always)]
+	pub fn build(self) -> ExpandedDocument<J, T> {
+		ExpandedDocument::new(self.objects, self.warnings)
+	}
+}
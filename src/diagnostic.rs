@@ -0,0 +1,172 @@
+//! LSP-style diagnostics.
+//!
+//! Processing errors and warnings can be turned into [`Diagnostic`]s
+//! modeled after the diagnostics of the
+//! [Language Server Protocol](https://microsoft.github.io/language-server-protocol/),
+//! so that they can be consumed by editors and CI tools.
+//! With the `serde` feature, diagnostics serialize to LSP `Diagnostic` JSON objects.
+//!
+//! Since the metadata attached to located values is generic,
+//! the caller provides the function computing the text range of some metadata.
+//!
+//! ```
+//! use json_ld::{diagnostic::{Diagnostic, Severity}, Loc, Warning};
+//!
+//! let warning = Loc::new(Warning::EmptyTerm, None, ());
+//! let diagnostic = Diagnostic::from_warning(&warning, |_| None);
+//! assert_eq!(diagnostic.severity, Severity::Warning);
+//! assert_eq!(diagnostic.to_string(), "warning[empty term]: empty term");
+//! ```
+use crate::{loader, Error, Loc, Warning};
+use std::fmt;
+
+/// Name of the diagnostics source.
+pub const SOURCE: &str = "json-ld";
+
+/// Position in a text document.
+///
+/// Both the line and character offsets are zero-based.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Position {
+	/// Line offset.
+	pub line: u32,
+
+	/// Character offset in the line.
+	pub character: u32,
+}
+
+/// Range in a text document.
+///
+/// The end position is exclusive.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Default, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Range {
+	/// Start position.
+	pub start: Position,
+
+	/// End position.
+	pub end: Position,
+}
+
+/// Diagnostic severity.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub enum Severity {
+	Error = 1,
+	Warning = 2,
+	Information = 3,
+	Hint = 4,
+}
+
+impl Severity {
+	/// Returns the name of the severity.
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			Self::Error => "error",
+			Self::Warning => "warning",
+			Self::Information => "info",
+			Self::Hint => "hint",
+		}
+	}
+}
+
+impl fmt::Display for Severity {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		self.as_str().fmt(f)
+	}
+}
+
+/// Serializes the severity as its LSP numeric value.
+#[cfg(feature = "serde")]
+impl serde::Serialize for Severity {
+	#[inline(always)]
+	fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		serializer.serialize_u8(*self as u8)
+	}
+}
+
+/// Diagnostic.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Diagnostic {
+	/// Range of the offending value, if known.
+	pub range: Option<Range>,
+
+	/// Severity.
+	pub severity: Severity,
+
+	/// Error or warning code.
+	pub code: &'static str,
+
+	/// Human readable message.
+	pub message: String,
+
+	/// Document in which the diagnostic occurred, if known.
+	///
+	/// This is not part of the serialized diagnostic:
+	/// LSP diagnostics are published for a given document.
+	pub document: Option<loader::Id>,
+}
+
+impl Diagnostic {
+	/// Creates a diagnostic from the given warning.
+	///
+	/// The `range` function computes the text range of the warning metadata.
+	pub fn from_warning<M>(
+		warning: &Loc<Warning, M>,
+		range: impl FnOnce(&M) -> Option<Range>,
+	) -> Self {
+		Self {
+			range: range(warning.metadata()),
+			severity: Severity::Warning,
+			code: warning.code().as_str(),
+			message: warning.to_string(),
+			document: warning.source(),
+		}
+	}
+
+	/// Creates a diagnostic from the given error.
+	///
+	/// The `range` function computes the text range of the error metadata.
+	pub fn from_error<M>(error: &Loc<Error, M>, range: impl FnOnce(&M) -> Option<Range>) -> Self {
+		Self {
+			range: range(error.metadata()),
+			severity: Severity::Error,
+			code: error.code().as_str(),
+			message: error.to_string(),
+			document: error.source(),
+		}
+	}
+}
+
+/// Formats the diagnostic on a single line, prefixed with its one-based position if known.
+impl fmt::Display for Diagnostic {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		if let Some(range) = &self.range {
+			write!(
+				f,
+				"{}:{}: ",
+				range.start.line + 1,
+				range.start.character + 1
+			)?;
+		}
+
+		write!(f, "{}[{}]: {}", self.severity, self.code, self.message)
+	}
+}
+
+/// Serializes the diagnostic as an LSP `Diagnostic` object.
+///
+/// An unknown range is serialized as the empty range at the beginning of the document.
+#[cfg(feature = "serde")]
+impl serde::Serialize for Diagnostic {
+	fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		use serde::ser::SerializeStruct;
+		let mut s = serializer.serialize_struct("Diagnostic", 5)?;
+		s.serialize_field("range", &self.range.unwrap_or_default())?;
+		s.serialize_field("severity", &self.severity)?;
+		s.serialize_field("code", self.code)?;
+		s.serialize_field("source", SOURCE)?;
+		s.serialize_field("message", &self.message)?;
+		s.end()
+	}
+}
@@ -1,7 +1,7 @@
 use crate::util::AsAnyJson;
+use core::convert::TryFrom;
+use core::fmt;
 use generic_json::JsonBuild;
-use std::convert::TryFrom;
-use std::fmt;
 
 /// Internationalized string direction.
 ///
@@ -51,3 +51,52 @@ impl<K: JsonBuild> AsAnyJson<K> for Direction {
 		}
 	}
 }
+
+/// Base direction handling when converting JSON-LD to/from RDF.
+///
+/// RDF literals have no base direction, so the direction of a language-tagged string
+/// must be encoded using one of the following strategies, selected with the
+/// `rdfDirection` option of the JSON-LD API.
+/// If no strategy is selected, the direction is dropped.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum RdfDirection {
+	/// `i18n-datatype`.
+	///
+	/// The string is encoded as a literal whose datatype is
+	/// `https://www.w3.org/ns/i18n#` followed by the lowercased language tag (if any),
+	/// an underscore and the direction, such as `https://www.w3.org/ns/i18n#ar-eg_rtl`.
+	/// See [`LangString::i18n_datatype`](crate::LangString::i18n_datatype) and
+	/// [`LangString::from_i18n_datatype`](crate::LangString::from_i18n_datatype).
+	I18nDatatype,
+
+	/// `compound-literal`.
+	///
+	/// The string is encoded as a blank node with an `rdf:value` property
+	/// for the string value, an `rdf:language` property for the language tag (if any),
+	/// and an `rdf:direction` property for the direction.
+	CompoundLiteral,
+}
+
+impl<'a> TryFrom<&'a str> for RdfDirection {
+	type Error = &'a str;
+
+	/// Convert the strings `"i18n-datatype"` and `"compound-literal"` into a `RdfDirection`.
+	#[inline(always)]
+	fn try_from(name: &'a str) -> Result<RdfDirection, &'a str> {
+		match name {
+			"i18n-datatype" => Ok(RdfDirection::I18nDatatype),
+			"compound-literal" => Ok(RdfDirection::CompoundLiteral),
+			_ => Err(name),
+		}
+	}
+}
+
+impl fmt::Display for RdfDirection {
+	#[inline(always)]
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			RdfDirection::I18nDatatype => write!(f, "i18n-datatype"),
+			RdfDirection::CompoundLiteral => write!(f, "compound-literal"),
+		}
+	}
+}
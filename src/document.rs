@@ -1,25 +1,48 @@
+use crate::util::collections::{self, HashMap, HashSet};
+use crate::util::future::{BoxFuture, FutureExt};
 use crate::{
 	compaction,
-	context::{self, Loader},
-	expansion, loader,
+	context::{self, Loader, Local},
+	expansion, flattening, loader,
+	object::{self, node},
+	shape,
 	util::{AsJson, JsonFrom},
-	Context, ContextMut, ContextMutProxy, Error, Id, Indexed, Loc, Object, Warning,
+	BlankId, BlankIdGenerator, Context, ContextMut, ContextMutProxy, DatasetBuilder, Error, Id,
+	Indexed, Loc, Node, Object, Reference, ToReference, Warning,
 };
 use cc_traits::Len;
-use futures::future::{BoxFuture, FutureExt};
 use generic_json::{Json, JsonClone, JsonHash};
 use iref::{Iri, IriBuf};
-use std::collections::HashSet;
-use std::ops::{Deref, DerefMut};
+use std::iter::FromIterator;
+use std::ops::{Deref, DerefMut, Range};
+use std::sync::Arc;
+
+#[cfg(feature = "rayon")]
+mod parallel;
 
 /// Result of the document expansion algorithm.
 ///
 /// It is just an alias for a set of (indexed) objects.
+///
+/// An expanded document owns all its data and does not use any interior mutability,
+/// so it is [`Send`] and [`Sync`] as long as the JSON type `J`,
+/// its metadata and the identifier type `T` are.
+/// It can be frozen into a [`FrozenDocument`] to be cheaply shared between threads.
 pub struct ExpandedDocument<J: JsonHash, T: Id> {
 	objects: HashSet<Indexed<Object<J, T>>>,
 	warnings: Vec<Loc<Warning, J::MetaData>>,
 }
 
+impl<J: JsonHash + JsonClone, T: Id> Clone for ExpandedDocument<J, T> {
+	#[inline(always)]
+	fn clone(&self) -> Self {
+		Self {
+			objects: self.objects.clone(),
+			warnings: self.warnings.clone(),
+		}
+	}
+}
+
 impl<J: JsonHash, T: Id> ExpandedDocument<J, T> {
 	#[inline(always)]
 	pub fn new(
@@ -45,9 +68,764 @@ impl<J: JsonHash, T: Id> ExpandedDocument<J, T> {
 	}
 
 	#[inline(always)]
-	pub fn iter(&self) -> std::collections::hash_set::Iter<'_, Indexed<Object<J, T>>> {
+	pub fn iter(&self) -> collections::hash_set::Iter<'_, Indexed<Object<J, T>>> {
 		self.objects.iter()
 	}
+
+	/// Returns an iterator over all the node objects of the document,
+	/// including nested nodes.
+	#[inline(always)]
+	pub fn nodes(&self) -> object::Traverse<'_, J, T> {
+		object::Traverse::new(&self.objects)
+	}
+
+	/// Returns an iterator over every IRI appearing in the document.
+	///
+	/// Every position holding an IRI is visited once, in no particular order:
+	/// node identifiers (including graph names), types, properties, reverse properties
+	/// and datatypes of typed literals.
+	/// The same IRI is hence returned as many times as it is used.
+	pub fn iris(&self) -> impl '_ + Iterator<Item = &T> {
+		let mut iris = Vec::new();
+		self.for_each_identifier(|reference| {
+			if let Reference::Id(iri) = reference {
+				iris.push(iri)
+			}
+		});
+		self.for_each_datatype(|iri| iris.push(iri));
+		iris.into_iter()
+	}
+
+	/// Returns an iterator over every blank node identifier appearing in the document.
+	///
+	/// Every position holding a blank node identifier is visited once, in no particular order:
+	/// node identifiers (including graph names), types, properties and reverse properties.
+	pub fn blank_ids(&self) -> impl '_ + Iterator<Item = &BlankId> {
+		let mut blank_ids = Vec::new();
+		self.for_each_identifier(|reference| {
+			if let Reference::Blank(id) = reference {
+				blank_ids.push(id)
+			}
+		});
+		blank_ids.into_iter()
+	}
+
+	/// Calls `f` on every node identifier, type, property and reverse property of the document.
+	fn for_each_identifier<'a>(&'a self, mut f: impl FnMut(&'a Reference<T>)) {
+		for node in self.nodes() {
+			if let Some(id) = node.id() {
+				f(id)
+			}
+
+			for ty in node.types() {
+				f(ty)
+			}
+
+			for (prop, _) in node.properties() {
+				f(prop)
+			}
+
+			for (prop, _) in node.reverse_properties() {
+				f(prop)
+			}
+		}
+	}
+
+	/// Calls `f` on the datatype of every typed literal of the document.
+	fn for_each_datatype<'a>(&'a self, mut f: impl FnMut(&'a T)) {
+		fn visit<'a, J: JsonHash, T: Id>(object: &'a Object<J, T>, f: &mut impl FnMut(&'a T)) {
+			match object {
+				Object::Value(object::Value::Literal(_, Some(ty)), _) => f(ty),
+				Object::List(items, _) => {
+					for item in items {
+						visit(item.inner(), f)
+					}
+				}
+				_ => (),
+			}
+		}
+
+		for node in self.nodes() {
+			for (_, objects) in node.properties() {
+				for object in objects {
+					visit(object.inner(), &mut f)
+				}
+			}
+		}
+	}
+
+	/// Returns an iterator over the nodes of the document (including nested nodes)
+	/// having the given type.
+	///
+	/// This scans the whole document on each call.
+	/// When filtering the nodes of a document by type repeatedly,
+	/// prefer [`NodeMapGraph::nodes_with_type`](crate::flattening::NodeMapGraph::nodes_with_type)
+	/// on the node map of the document (see [`generate_node_map`](Self::generate_node_map)),
+	/// which uses a type index.
+	pub fn nodes_with_type<'a>(
+		&'a self,
+		ty: &'a Reference<T>,
+	) -> impl 'a + Iterator<Item = &'a Node<J, T>> {
+		self.nodes().filter(move |node| node.has_type(ty))
+	}
+
+	/// Returns an iterator over the nodes of the document (including nested nodes)
+	/// having the given type or one of its subclasses,
+	/// according to the given type hierarchy.
+	///
+	/// ```
+	/// # use json_ld::{Document, ExpandedDocument, Reference, context, NoLoader};
+	/// # use iref::IriBuf;
+	/// # use serde_json::Value;
+	/// # let doc: Value = serde_json::from_str(r#"{
+	/// #   "@id": "https://example.com/rex",
+	/// #   "@type": "https://example.com/Dog"
+	/// # }"#).unwrap();
+	/// # let mut loader = NoLoader::<Value>::new();
+	/// # let expanded: ExpandedDocument<Value, IriBuf> = async_std::task::block_on(
+	/// #   doc.expand::<context::Json<Value>, _>(&mut loader)
+	/// # ).unwrap();
+	/// let animal = Reference::Id(IriBuf::new("https://example.com/Animal").unwrap());
+	/// let dog = Reference::Id(IriBuf::new("https://example.com/Dog").unwrap());
+	/// let hierarchy = |ty: &Reference<IriBuf>| {
+	///   if *ty == animal { vec![dog.clone()] } else { vec![] }
+	/// };
+	///
+	/// assert_eq!(expanded.nodes_of_type_transitive(&animal, &hierarchy).count(), 1);
+	/// ```
+	pub fn nodes_of_type_transitive<'a, H: node::TypeHierarchy<T>>(
+		&'a self,
+		ty: &Reference<T>,
+		hierarchy: &H,
+	) -> impl 'a + Iterator<Item = &'a Node<J, T>> {
+		let types = hierarchy.sub_classes_transitive(ty);
+		self.nodes().filter(move |node| node.has_type_in(&types))
+	}
+
+	/// Selects the objects reached from the top-level objects of the document
+	/// by following the given property path.
+	///
+	/// See [`Node::select`] for the details.
+	/// With an empty path, the top-level objects are selected.
+	pub fn select<'a, Q: ToReference<T>>(&'a self, path: &[Q]) -> Vec<&'a Indexed<Object<J, T>>> {
+		let mut selection = Vec::new();
+		for object in &self.objects {
+			node::select(object, path, &mut selection)
+		}
+
+		selection
+	}
+
+	/// Returns an iterator over the triples of the document matching the given pattern.
+	///
+	/// Each triple is given by a node (subject), one of its properties (predicate)
+	/// and one of the associated objects.
+	/// A `None` pattern matches anything, while a `Some` object pattern only matches
+	/// node objects with the given identifier.
+	/// Only identified nodes (including blank node identifiers) are considered as subjects.
+	/// Types (`@type`) and reverse properties are not reported as triples.
+	pub fn match_<'a>(
+		&'a self,
+		subject: Option<&'a Reference<T>>,
+		predicate: Option<&'a Reference<T>>,
+		object: Option<&'a Reference<T>>,
+	) -> impl 'a + Iterator<Item = Triple<'a, J, T>> {
+		self.nodes()
+			.filter_map(|node| node.id().map(|id| (id, node)))
+			.filter(move |(id, _)| subject.map(|s| s == *id).unwrap_or(true))
+			.flat_map(move |(id, node)| {
+				node.properties()
+					.iter()
+					.filter(move |(p, _)| predicate.map(|q| q == *p).unwrap_or(true))
+					.flat_map(move |(p, objects)| {
+						objects
+							.iter()
+							.filter(move |o| {
+								object.map(|r| o.inner().id() == Some(r)).unwrap_or(true)
+							})
+							.map(move |o| Triple {
+								subject: id,
+								predicate: p,
+								object: o,
+							})
+					})
+			})
+	}
+
+	/// Returns the entities of the document whose source spans the given offset.
+	///
+	/// An entity is always listed before the entities it contains,
+	/// so that the innermost entity is the last one.
+	/// Since the metadata attached to the document is generic,
+	/// the caller provides the `span` function computing the byte range of some metadata
+	/// in the source document.
+	/// Synthetic entities, without metadata, are not listed, but their content is searched.
+	///
+	/// ```
+	/// use json_ld::{context, Document, NoLoader, SourceEntity};
+	/// use serde_json::{json, Value};
+	///
+	/// # async_std::task::block_on(async {
+	/// let mut loader = NoLoader::<Value>::new();
+	/// let doc = json!({ "http://xmlns.com/foaf/0.1/name": "Alice" })
+	///   .expand::<context::Json<Value>, _>(&mut loader)
+	///   .await
+	///   .unwrap();
+	///
+	/// // `serde_json` does not record positions: every entity spans the whole document.
+	/// let entities = doc.entities_at(0, |_| Some(0..1));
+	/// assert!(matches!(entities[0], SourceEntity::Object(object) if object.is_node()));
+	/// assert!(matches!(entities[1], SourceEntity::Property(_, prop) if prop == "http://xmlns.com/foaf/0.1/name"));
+	/// assert!(matches!(entities[2], SourceEntity::Object(object) if object.as_str() == Some("Alice")));
+	/// assert!(doc.entities_at(1, |_| Some(0..1)).is_empty());
+	/// # })
+	/// ```
+	pub fn entities_at<'a>(
+		&'a self,
+		offset: usize,
+		span: impl Fn(&J::MetaData) -> Option<Range<usize>>,
+	) -> Vec<SourceEntity<'a, J, T>> {
+		let mut entities = Vec::new();
+		for object in &self.objects {
+			object_entities_at(object, offset, &span, &mut entities)
+		}
+
+		entities
+	}
+
+	/// Validates the nodes of the document (including nested nodes) against the given shapes.
+	///
+	/// Each node is validated against every shape targeting one of its types.
+	/// See the [`shape`](crate::shape) module for more details.
+	#[inline(always)]
+	pub fn validate(&self, shapes: &[shape::Shape<T>]) -> Vec<shape::Violation<T, J::MetaData>> {
+		shape::validate(self.nodes(), shapes)
+	}
+
+	/// Adds an object to the document.
+	///
+	/// Returns `false` if the document already contained this object.
+	#[inline(always)]
+	pub fn insert(&mut self, object: Indexed<Object<J, T>>) -> bool {
+		self.objects.insert(object)
+	}
+
+	/// Removes an object from the document.
+	///
+	/// Returns `true` if the object was in the document.
+	#[inline(always)]
+	pub fn remove(&mut self, object: &Indexed<Object<J, T>>) -> bool {
+		self.objects.remove(object)
+	}
+
+	/// Applies `f` on the top-level node with the given identifier.
+	///
+	/// Returns `false` if there is no such node in the document.
+	pub fn update_node(&mut self, id: &Reference<T>, f: impl FnOnce(&mut Node<J, T>)) -> bool {
+		let mut f = Some(f);
+		let objects = std::mem::take(&mut self.objects);
+		self.objects = objects
+			.into_iter()
+			.map(|mut object| {
+				if let Object::Node(node) = &mut *object {
+					if node.id.as_ref() == Some(id) {
+						if let Some(f) = f.take() {
+							f(node)
+						}
+					}
+				}
+
+				object
+			})
+			.collect();
+
+		f.is_none()
+	}
+
+	/// Retains only the objects specified by the predicate.
+	#[inline(always)]
+	pub fn retain(&mut self, f: impl FnMut(&Indexed<Object<J, T>>) -> bool) {
+		self.objects.retain(f)
+	}
+
+	/// Normalizes every value of the document.
+	///
+	/// Literals are canonicalized (see [`Value::normalized`](crate::Value::normalized))
+	/// and equivalent values of a same property are collapsed,
+	/// making equality and hashing of documents more predictable.
+	pub fn normalize(&mut self) {
+		self.objects = std::mem::take(&mut self.objects)
+			.into_iter()
+			.map(|object| object.map_inner(Object::normalized))
+			.collect()
+	}
+
+	/// Rewrites every IRI of the document with `f`.
+	///
+	/// Node identifiers (including graph names), types, properties, reverse properties
+	/// and datatypes of typed literals are all mapped, which can be used to migrate
+	/// a document from one vocabulary namespace to another.
+	/// Blank node identifiers are left untouched.
+	///
+	/// ```
+	/// # use json_ld::{context, Document, ExpandedDocument, NoLoader};
+	/// # use iref::IriBuf;
+	/// # use serde_json::Value;
+	/// # let doc: Value = serde_json::from_str(r#"{
+	/// #   "@type": "http://schema.org/Person",
+	/// #   "http://schema.org/name": "Timothée"
+	/// # }"#).unwrap();
+	/// # let mut loader = NoLoader::<Value>::new();
+	/// # let expanded: ExpandedDocument<Value, IriBuf> = async_std::task::block_on(
+	/// #   doc.expand::<context::Json<Value>, _>(&mut loader)
+	/// # ).unwrap();
+	/// let migrated = expanded.map_iris(|iri: IriBuf| {
+	///   let suffix = iri.as_str().strip_prefix("http://schema.org/").map(str::to_string);
+	///   match suffix {
+	///     Some(suffix) => IriBuf::new(&format!("https://schema.org/{}", suffix)).unwrap(),
+	///     None => iri,
+	///   }
+	/// });
+	///
+	/// assert!(migrated.iris().all(|iri| iri.as_str().starts_with("https://schema.org/")));
+	/// ```
+	pub fn map_iris<F: FnMut(T) -> T>(self, mut f: F) -> Self {
+		Self {
+			objects: self
+				.objects
+				.into_iter()
+				.map(|object| object.map_inner(|object| object.map_iris(&mut f)))
+				.collect(),
+			warnings: self.warnings,
+		}
+	}
+
+	/// Merges the given document into this one.
+	///
+	/// See the [`FromIterator`] implementation for details.
+	pub fn merge(&mut self, other: ExpandedDocument<J, T>) {
+		let this = std::mem::replace(self, Self::new(HashSet::new(), Vec::new()));
+		*self = std::iter::once(this)
+			.chain(std::iter::once(other))
+			.collect()
+	}
+
+	/// Relabels every blank node identifier of the document with the given generator.
+	///
+	/// Every occurrence of a blank node identifier, in any graph, is replaced by
+	/// the same fresh identifier.
+	/// Returns the new identifier of each relabeled blank node.
+	pub fn relabel_blank_nodes<G: BlankIdGenerator>(
+		&mut self,
+		generator: &mut G,
+	) -> HashMap<BlankId, BlankId> {
+		let mut labels = HashMap::new();
+		self.map_blank_ids(|id| {
+			Reference::Blank(
+				labels
+					.entry(id)
+					.or_insert_with(|| generator.next_blank_id())
+					.clone(),
+			)
+		});
+		labels
+	}
+
+	/// Replaces every blank node identifier of the document with a skolem IRI.
+	///
+	/// Each blank node identifier is replaced by its well-known `genid` IRI
+	/// relative to `base` (see [`BlankId::skolem_iri`]), in every graph.
+	/// Returns the skolem IRI of each replaced blank node.
+	pub fn skolemize(&mut self, base: Iri) -> HashMap<BlankId, T> {
+		let mut iris = HashMap::new();
+		self.map_blank_ids(|id| {
+			let iri = iris
+				.entry(id)
+				.or_insert_with_key(|id: &BlankId| T::from_iri(id.skolem_iri(base).as_iri()));
+			Reference::Id(iri.clone())
+		});
+		iris
+	}
+
+	/// Replaces every blank node identifier of the document using `f`.
+	fn map_blank_ids(&mut self, mut f: impl FnMut(BlankId) -> Reference<T>) {
+		let mut f = |r: Reference<T>| match r {
+			Reference::Blank(id) => f(id),
+			r => r,
+		};
+
+		self.objects = std::mem::take(&mut self.objects)
+			.into_iter()
+			.map(|object| object.map_inner(|object| object.map_references(&mut f)))
+			.collect()
+	}
+
+	/// Generates the node map of this document.
+	///
+	/// See [`flattening::generate_node_map`] for details.
+	#[inline(always)]
+	pub fn generate_node_map<G: BlankIdGenerator>(
+		self,
+		generator: &mut G,
+	) -> Result<flattening::NodeMap<J, T>, Error> {
+		flattening::generate_node_map(self, generator)
+	}
+
+	/// Generates the node map of this document, recovering from conflicting indexes.
+	///
+	/// See [`flattening::generate_node_map_with_warnings`] for details.
+	#[inline(always)]
+	pub fn generate_node_map_with_warnings<G: BlankIdGenerator>(
+		self,
+		generator: &mut G,
+		warnings: &mut Vec<Loc<Warning, Option<J::MetaData>>>,
+	) -> Result<flattening::NodeMap<J, T>, Error> {
+		flattening::generate_node_map_with_warnings(self, generator, warnings)
+	}
+
+	/// Returns the names of the named graphs of the document.
+	///
+	/// A named graph is defined by the `@graph` entry of a top-level node object,
+	/// and named after the node identifier.
+	/// Graph objects nested in property values are not considered.
+	pub fn graph_names(&self) -> HashSet<&Reference<T>> {
+		self.objects
+			.iter()
+			.filter_map(|object| object.as_node())
+			.filter(|node| node.graph.is_some())
+			.filter_map(Node::id)
+			.collect()
+	}
+
+	/// Returns a copy of the given named graph as its own document,
+	/// or `None` if there is no such graph.
+	///
+	/// The content of every top-level node object defining the graph is collected
+	/// (see [`graph_names`](Self::graph_names)).
+	pub fn graph(&self, name: &Reference<T>) -> Option<Self>
+	where
+		J: JsonClone,
+	{
+		let mut graph: Option<HashSet<_>> = None;
+		for node in self.objects.iter().filter_map(|object| object.as_node()) {
+			if node.id.as_ref() == Some(name) {
+				if let Some(objects) = &node.graph {
+					graph
+						.get_or_insert_with(HashSet::new)
+						.extend(objects.iter().cloned())
+				}
+			}
+		}
+
+		graph.map(|objects| Self::new(objects, Vec::new()))
+	}
+
+	/// Removes the given named graph from the document, and returns it as its own document.
+	///
+	/// The `@graph` entry of every top-level node object defining the graph is removed.
+	/// Node objects left without any other entry than `@id` are removed.
+	pub fn take_graph(&mut self, name: &Reference<T>) -> Option<Self> {
+		let mut graph: Option<HashSet<_>> = None;
+		self.objects = std::mem::take(&mut self.objects)
+			.into_iter()
+			.filter_map(|mut object| {
+				if let Object::Node(node) = &mut *object {
+					if node.id.as_ref() == Some(name) {
+						if let Some(objects) = node.graph.take() {
+							graph.get_or_insert_with(HashSet::new).extend(objects);
+							if node.is_empty() {
+								return None;
+							}
+						}
+					}
+				}
+
+				Some(object)
+			})
+			.collect();
+
+		graph.map(|objects| Self::new(objects, Vec::new()))
+	}
+
+	/// Splits this document into its default graph and its named graphs.
+	///
+	/// The default graph keeps the warnings of the document.
+	/// Top-level node objects left without any other entry than `@id`
+	/// once their `@graph` entry is removed are not part of the default graph.
+	pub fn into_graphs(self) -> (Self, HashMap<Reference<T>, Self>) {
+		let mut default_graph = HashSet::new();
+		let mut graphs: HashMap<Reference<T>, HashSet<_>> = HashMap::new();
+
+		for mut object in self.objects {
+			if let Object::Node(node) = &mut *object {
+				if let (Some(id), Some(objects)) = (&node.id, &mut node.graph) {
+					graphs
+						.entry(id.clone())
+						.or_default()
+						.extend(std::mem::take(objects));
+					node.graph = None;
+
+					if node.is_empty() {
+						continue;
+					}
+				}
+			}
+
+			default_graph.insert(object);
+		}
+
+		let graphs = graphs
+			.into_iter()
+			.map(|(name, objects)| (name, Self::new(objects, Vec::new())))
+			.collect();
+
+		(Self::new(default_graph, self.warnings), graphs)
+	}
+
+	/// Moves the content of the named graph `from` into the graph `to`
+	/// (the default graph if `None`).
+	///
+	/// The content is added to the existing content of the target graph, if any:
+	/// node objects with the same identifier are not merged.
+	/// Returns `false` if there is no graph named `from`.
+	pub fn move_graph(&mut self, from: &Reference<T>, to: Option<&Reference<T>>) -> bool {
+		let objects = match self.take_graph(from) {
+			Some(graph) => graph.objects,
+			None => return false,
+		};
+
+		match to {
+			Some(to) => {
+				let mut objects = Some(objects);
+				self.update_node(to, |node| {
+					node.graph
+						.get_or_insert_with(HashSet::new)
+						.extend(objects.take().unwrap())
+				});
+
+				if let Some(objects) = objects {
+					let mut node = Node::with_id(to.clone());
+					node.graph = Some(objects);
+					self.objects.insert(Indexed::new(Object::Node(node), None));
+				}
+			}
+			None => self.objects.extend(objects),
+		}
+
+		true
+	}
+
+	/// Freezes this document into a cheaply clonable, thread-safe snapshot.
+	#[inline(always)]
+	pub fn freeze(self) -> FrozenDocument<J, T> {
+		FrozenDocument(Arc::new(self))
+	}
+
+	/// Returns the main node of the document, if it can be identified.
+	///
+	/// Many documents describe one primary entity along with auxiliary nodes.
+	/// The main node is, by order of preference:
+	///   - the top-level node identified by the given document `url`;
+	///   - the only top-level node identified by `url` with a fragment;
+	///   - the only top-level node identified by an IRI;
+	///   - the only top-level node.
+	///
+	/// Returns `None` if the main node is ambiguous.
+	pub fn main_node(&self, url: Option<Iri>) -> Option<&Node<J, T>> {
+		let nodes = || self.objects.iter().filter_map(|object| object.as_node());
+
+		if let Some(url) = url {
+			if let Some(node) = nodes().find(|node| node.as_iri() == Some(url)) {
+				return Some(node);
+			}
+
+			let mut fragments = nodes().filter(|node| match node.as_str() {
+				Some(id) => id.split('#').next() == Some(url.as_str()),
+				None => false,
+			});
+
+			if let (Some(node), None) = (fragments.next(), fragments.next()) {
+				return Some(node);
+			}
+		}
+
+		let mut named = nodes().filter(|node| node.as_iri().is_some());
+		match (named.next(), named.next()) {
+			(Some(node), None) => Some(node),
+			(Some(_), Some(_)) => None,
+			(None, _) => {
+				let mut nodes = nodes();
+				match (nodes.next(), nodes.next()) {
+					(Some(node), None) => Some(node),
+					_ => None,
+				}
+			}
+		}
+	}
+
+	/// Turns this document into its objects and warnings.
+	#[inline(always)]
+	pub fn into_parts(
+		self,
+	) -> (
+		HashSet<Indexed<Object<J, T>>>,
+		Vec<Loc<Warning, J::MetaData>>,
+	) {
+		(self.objects, self.warnings)
+	}
+}
+
+/// Triple of an expanded document, returned by [`ExpandedDocument::match_`].
+pub struct Triple<'a, J: JsonHash, T: Id> {
+	/// Subject node identifier.
+	pub subject: &'a Reference<T>,
+
+	/// Property.
+	pub predicate: &'a Reference<T>,
+
+	/// Object.
+	pub object: &'a Indexed<Object<J, T>>,
+}
+
+/// Entity of an expanded document located in the source document,
+/// returned by [`ExpandedDocument::entities_at`].
+pub enum SourceEntity<'a, J: JsonHash, T: Id> {
+	/// Node, value or list object.
+	Object(&'a Indexed<Object<J, T>>),
+
+	/// Node reached through a reverse property or an `@included` entry.
+	Node(&'a Indexed<Node<J, T>>),
+
+	/// Property entry of a node.
+	Property(&'a Node<J, T>, &'a Reference<T>),
+}
+
+fn spans<M>(
+	metadata: Option<&M>,
+	offset: usize,
+	span: &impl Fn(&M) -> Option<Range<usize>>,
+) -> bool {
+	metadata
+		.and_then(span)
+		.map_or(false, |range| range.contains(&offset))
+}
+
+fn object_entities_at<'a, J: JsonHash, T: Id>(
+	object: &'a Indexed<Object<J, T>>,
+	offset: usize,
+	span: &impl Fn(&J::MetaData) -> Option<Range<usize>>,
+	entities: &mut Vec<SourceEntity<'a, J, T>>,
+) {
+	if spans(object.metadata(), offset, span) {
+		entities.push(SourceEntity::Object(object))
+	}
+
+	match object.inner() {
+		Object::Value(..) => (),
+		Object::List(items, _) => {
+			for item in items {
+				object_entities_at(item, offset, span, entities)
+			}
+		}
+		Object::Node(node) => node_entities_at(node, offset, span, entities),
+	}
+}
+
+fn node_entities_at<'a, J: JsonHash, T: Id>(
+	node: &'a Node<J, T>,
+	offset: usize,
+	span: &impl Fn(&J::MetaData) -> Option<Range<usize>>,
+	entities: &mut Vec<SourceEntity<'a, J, T>>,
+) {
+	for (prop, objects) in node.properties() {
+		if spans(node.properties().entry_metadata(prop), offset, span) {
+			entities.push(SourceEntity::Property(node, prop))
+		}
+
+		for object in objects {
+			object_entities_at(object, offset, span, entities)
+		}
+	}
+
+	let reverse_nodes = node
+		.reverse_properties()
+		.iter()
+		.flat_map(|(_, nodes)| nodes);
+	for reverse_node in reverse_nodes.chain(node.included().into_iter().flatten()) {
+		if spans(reverse_node.metadata(), offset, span) {
+			entities.push(SourceEntity::Node(reverse_node))
+		}
+
+		node_entities_at(reverse_node.inner(), offset, span, entities)
+	}
+
+	if let Some(graph) = node.graph() {
+		for object in graph {
+			object_entities_at(object, offset, span, entities)
+		}
+	}
+}
+
+/// Immutable snapshot of an expanded document.
+///
+/// A frozen document is reference-counted:
+/// cloning it is cheap and does not copy the underlying document,
+/// so that it can be shared by multiple threads for concurrent reads.
+/// Updates are performed with copy-on-write semantics using
+/// [`make_mut`](`FrozenDocument::make_mut`):
+/// the document is only copied if it is currently shared,
+/// leaving other snapshots unchanged.
+pub struct FrozenDocument<J: JsonHash, T: Id>(Arc<ExpandedDocument<J, T>>);
+
+impl<J: JsonHash, T: Id> FrozenDocument<J, T> {
+	/// Returns a mutable reference to the document,
+	/// copying it first if it is shared with other snapshots.
+	#[inline(always)]
+	pub fn make_mut(&mut self) -> &mut ExpandedDocument<J, T>
+	where
+		J: JsonClone,
+	{
+		Arc::make_mut(&mut self.0)
+	}
+
+	/// Checks if the two snapshots share the same document.
+	#[inline(always)]
+	pub fn ptr_eq(&self, other: &Self) -> bool {
+		Arc::ptr_eq(&self.0, &other.0)
+	}
+
+	/// Turns this snapshot back into an expanded document,
+	/// copying it if it is shared with other snapshots.
+	#[inline(always)]
+	pub fn into_document(self) -> ExpandedDocument<J, T>
+	where
+		J: JsonClone,
+	{
+		Arc::try_unwrap(self.0).unwrap_or_else(|doc| (*doc).clone())
+	}
+}
+
+impl<J: JsonHash, T: Id> Clone for FrozenDocument<J, T> {
+	#[inline(always)]
+	fn clone(&self) -> Self {
+		Self(self.0.clone())
+	}
+}
+
+impl<J: JsonHash, T: Id> Deref for FrozenDocument<J, T> {
+	type Target = ExpandedDocument<J, T>;
+
+	#[inline(always)]
+	fn deref(&self) -> &ExpandedDocument<J, T> {
+		&self.0
+	}
+}
+
+impl<J: JsonHash, T: Id> From<ExpandedDocument<J, T>> for FrozenDocument<J, T> {
+	#[inline(always)]
+	fn from(doc: ExpandedDocument<J, T>) -> Self {
+		doc.freeze()
+	}
 }
 
 impl<J: compaction::JsonSrc, T: Sync + Send + Id> compaction::Compact<J, T>
@@ -69,19 +847,216 @@ impl<J: compaction::JsonSrc, T: Sync + Send + Id> compaction::Compact<J, T>
 		L: Sync + Send,
 		M: 'a + Send + Sync + Clone + Fn(Option<&J::MetaData>) -> K::MetaData,
 	{
-		self.objects.compact_full(
-			active_context,
-			type_scoped_context,
-			active_property,
-			loader,
-			options,
-			meta,
-		)
+		self.objects.compact_full(
+			active_context,
+			type_scoped_context,
+			active_property,
+			loader,
+			options,
+			meta,
+		)
+	}
+}
+
+impl<J: compaction::JsonSrc, T: Sync + Send + Id> ExpandedDocument<J, T> {
+	/// Compact this document into a top-level JSON-LD document using the given context.
+	///
+	/// The JSON representation of the context is inserted as the `@context` entry
+	/// of the output document, exactly as written.
+	/// The `meta_context` and `meta_document` functions convert the metadata of the
+	/// context and of this document to `K::MetaData`.
+	pub async fn compact_document<'a, K, C: ContextMutProxy<T>, L: Loader, M1, M2>(
+		&'a self,
+		context: &'a C,
+		loader: &'a mut L,
+		options: compaction::Options,
+		meta_context: M1,
+		meta_document: M2,
+	) -> Result<K, Error>
+	where
+		K: JsonFrom<J> + JsonFrom<<C::Target as Context<T>>::LocalContext>,
+		C: AsJson<<C::Target as Context<T>>::LocalContext, K> + Send + Sync,
+		<C::Target as Context<T>>::LocalContext: compaction::JsonSrc + From<L::Output>,
+		C::Target: Send + Sync,
+		L: Send + Sync,
+		M1: 'a
+			+ Clone
+			+ Send
+			+ Sync
+			+ Fn(Option<&<<C::Target as Context<T>>::LocalContext as Json>::MetaData>) -> K::MetaData,
+		M2: 'a + Clone + Send + Sync + Fn(Option<&J::MetaData>) -> K::MetaData,
+	{
+		let json_context = context.as_json_with(meta_context);
+		let context = context::Inversible::new(context.deref());
+		self.compact_document_inversible(json_context, context, loader, options, meta_document)
+			.await
+	}
+
+	/// Compact this document into a top-level JSON-LD document using the given
+	/// (already inversible) context and its JSON representation.
+	///
+	/// The inverse context is shared with any clone of `context`,
+	/// so it is computed at most once for every document compacted with it.
+	pub(crate) async fn compact_document_inversible<'a, K, C: ContextMut<T>, L: Loader, M>(
+		&'a self,
+		json_context: K,
+		context: context::Inversible<T, &'a C>,
+		loader: &'a mut L,
+		options: compaction::Options,
+		meta_document: M,
+	) -> Result<K, Error>
+	where
+		K: JsonFrom<J>,
+		C: Send + Sync,
+		C::LocalContext: compaction::JsonSrc + From<L::Output>,
+		L: Send + Sync,
+		M: 'a + Clone + Send + Sync + Fn(Option<&J::MetaData>) -> K::MetaData,
+	{
+		use compaction::Compact;
+
+		let compacted: K = if self.len() == 1 && options.compact_arrays {
+			self.iter()
+				.next()
+				.unwrap()
+				.compact_full(
+					context.clone(),
+					context.clone(),
+					None,
+					loader,
+					options.clone(),
+					meta_document.clone(),
+				)
+				.await?
+		} else {
+			self.compact_full(
+				context.clone(),
+				context.clone(),
+				None,
+				loader,
+				options.clone(),
+				meta_document.clone(),
+			)
+			.await?
+		};
+
+		let (mut map, metadata) = match compacted.into_parts() {
+			(generic_json::Value::Array(items), metadata) => {
+				let mut map = K::Object::default();
+				if !items.is_empty() {
+					use crate::syntax::{Keyword, Term};
+					let key = crate::compaction::compact_iri::<J, _, _>(
+						context.clone(),
+						&Term::Keyword(Keyword::Graph),
+						true,
+						false,
+						options,
+					)?;
+					map.insert(
+						K::new_key(&key.unwrap(), meta_document(None)),
+						K::array(items, metadata),
+					);
+				}
+
+				(map, meta_document(None))
+			}
+			(generic_json::Value::Object(map), metadata) => (map, metadata),
+			_ => {
+				// This should never be triggered unless some user
+				// uses a custom faulty `Compact` implementation.
+				panic!("invalid compact document")
+			}
+		};
+
+		if !map.is_empty() && !json_context.is_null() && !json_context.is_empty_array_or_object() {
+			map.insert(K::new_key("@context", meta_document(None)), json_context);
+		}
+
+		Ok(K::object(map, metadata))
+	}
+
+	/// Compact this document against each of the given candidate contexts.
+	///
+	/// Returns the compacted documents along with their size,
+	/// in the order of the given contexts.
+	/// See [`compact_document`](ExpandedDocument::compact_document) for the other parameters.
+	pub async fn compact_candidates<'a, K, C: ContextMutProxy<T>, L: Loader, M1, M2>(
+		&'a self,
+		contexts: &'a [C],
+		loader: &'a mut L,
+		options: compaction::Options,
+		meta_context: M1,
+		meta_document: M2,
+	) -> Result<Vec<compaction::Candidate<K>>, Error>
+	where
+		K: JsonFrom<J> + JsonFrom<<C::Target as Context<T>>::LocalContext>,
+		C: AsJson<<C::Target as Context<T>>::LocalContext, K> + Send + Sync,
+		<C::Target as Context<T>>::LocalContext: compaction::JsonSrc + From<L::Output>,
+		C::Target: Send + Sync,
+		L: Send + Sync,
+		M1: 'a
+			+ Clone
+			+ Send
+			+ Sync
+			+ Fn(Option<&<<C::Target as Context<T>>::LocalContext as Json>::MetaData>) -> K::MetaData,
+		M2: 'a + Clone + Send + Sync + Fn(Option<&J::MetaData>) -> K::MetaData,
+	{
+		let mut candidates = Vec::with_capacity(contexts.len());
+		for (index, context) in contexts.iter().enumerate() {
+			let output = self
+				.compact_document(
+					context,
+					loader,
+					options.clone(),
+					meta_context.clone(),
+					meta_document.clone(),
+				)
+				.await?;
+			candidates.push(compaction::Candidate::new(index, output))
+		}
+
+		Ok(candidates)
+	}
+
+	/// Compact this document against each of the given candidate contexts,
+	/// and returns the smallest output.
+	///
+	/// The size of the outputs (including their `@context` entry)
+	/// is computed using [`compaction::json_size`].
+	/// If several outputs have the same size, the first one is returned.
+	/// Returns `None` if no context is given.
+	pub async fn compact_best<'a, K, C: ContextMutProxy<T>, L: Loader, M1, M2>(
+		&'a self,
+		contexts: &'a [C],
+		loader: &'a mut L,
+		options: compaction::Options,
+		meta_context: M1,
+		meta_document: M2,
+	) -> Result<Option<compaction::Candidate<K>>, Error>
+	where
+		K: JsonFrom<J> + JsonFrom<<C::Target as Context<T>>::LocalContext>,
+		C: AsJson<<C::Target as Context<T>>::LocalContext, K> + Send + Sync,
+		<C::Target as Context<T>>::LocalContext: compaction::JsonSrc + From<L::Output>,
+		C::Target: Send + Sync,
+		L: Send + Sync,
+		M1: 'a
+			+ Clone
+			+ Send
+			+ Sync
+			+ Fn(Option<&<<C::Target as Context<T>>::LocalContext as Json>::MetaData>) -> K::MetaData,
+		M2: 'a + Clone + Send + Sync + Fn(Option<&J::MetaData>) -> K::MetaData,
+	{
+		let candidates = self
+			.compact_candidates(contexts, loader, options, meta_context, meta_document)
+			.await?;
+
+		Ok(candidates
+			.into_iter()
+			.min_by_key(|candidate| candidate.size))
 	}
 }
 
 impl<J: JsonHash, T: Id> IntoIterator for ExpandedDocument<J, T> {
-	type IntoIter = std::collections::hash_set::IntoIter<Indexed<Object<J, T>>>;
+	type IntoIter = collections::hash_set::IntoIter<Indexed<Object<J, T>>>;
 	type Item = Indexed<Object<J, T>>;
 
 	#[inline(always)]
@@ -90,8 +1065,91 @@ impl<J: JsonHash, T: Id> IntoIterator for ExpandedDocument<J, T> {
 	}
 }
 
+/// Merges expanded documents into a single dataset.
+///
+/// Blank node identifiers are local to each document:
+/// identifiers used by more than one document are renamed as done by the [`DatasetBuilder`],
+/// so that distinct blank nodes are not merged.
+/// Top-level node objects sharing the same identifier are then merged into one node
+/// holding the union of their types, properties, reverse properties,
+/// `@graph` and `@included` entries.
+/// Nested nodes are left untouched, so the graph structure of each document is preserved.
+/// When merged nodes have different `@index` values, the first one is kept.
+/// Warnings are concatenated.
+impl<J: JsonHash, T: Id> FromIterator<ExpandedDocument<J, T>> for ExpandedDocument<J, T> {
+	fn from_iter<I: IntoIterator<Item = ExpandedDocument<J, T>>>(iter: I) -> Self {
+		let mut builder = DatasetBuilder::new();
+		for doc in iter {
+			builder.add(doc, None);
+		}
+
+		let (objects, warnings) = builder.build().into_parts();
+		let mut nodes: HashMap<Reference<T>, Indexed<Node<J, T>>> = HashMap::new();
+		let mut merged = HashSet::new();
+
+		for object in objects {
+			match object.id().cloned() {
+				Some(id) => {
+					let node = object.into_indexed_node().unwrap();
+					match nodes.get_mut(&id) {
+						Some(current) => {
+							if current.index().is_none() {
+								current.set_index(node.index().map(ToString::to_string))
+							}
+
+							merge_nodes(current, node.into_inner())
+						}
+						None => {
+							nodes.insert(id, node);
+						}
+					}
+				}
+				None => {
+					merged.insert(object);
+				}
+			}
+		}
+
+		merged.extend(nodes.into_values().map(|node| node.map_inner(Object::Node)));
+		Self::new(merged, warnings)
+	}
+}
+
+/// Merges the entries of node `b` into node `a`, without duplicate values.
+fn merge_nodes<J: JsonHash, T: Id>(a: &mut Node<J, T>, b: Node<J, T>) {
+	for ty in b.types {
+		if !a.types.contains(&ty) {
+			a.types.push(ty)
+		}
+	}
+
+	for (prop, objects) in b.properties {
+		for object in objects {
+			if object.is_list() || !a.get(&prop).any(|o| *o == object) {
+				a.insert(prop.clone(), object)
+			}
+		}
+	}
+
+	for (prop, nodes) in b.reverse_properties {
+		for node in nodes {
+			if !a.reverse_properties.get(&prop).any(|n| *n == node) {
+				a.insert_reverse(prop.clone(), node)
+			}
+		}
+	}
+
+	if let Some(graph) = b.graph {
+		a.graph.get_or_insert_with(HashSet::new).extend(graph)
+	}
+
+	if let Some(included) = b.included {
+		a.included.get_or_insert_with(HashSet::new).extend(included)
+	}
+}
+
 impl<'a, J: JsonHash, T: Id> IntoIterator for &'a ExpandedDocument<J, T> {
-	type IntoIter = std::collections::hash_set::Iter<'a, Indexed<Object<J, T>>>;
+	type IntoIter = collections::hash_set::Iter<'a, Indexed<Object<J, T>>>;
 	type Item = &'a Indexed<Object<J, T>>;
 
 	#[inline(always)]
@@ -100,6 +1158,87 @@ impl<'a, J: JsonHash, T: Id> IntoIterator for &'a ExpandedDocument<J, T> {
 	}
 }
 
+impl<J: JsonHash + JsonClone, T: Id> ExpandedDocument<J, T> {
+	/// Converts this document into a `K` JSON value in canonical expanded document form,
+	/// using the given `meta` function to convert `J::MetaData` into `K::MetaData`.
+	///
+	/// See [`expansion::canonical_form`] for the guarantees of this form.
+	pub fn as_canonical_json_with<K: JsonFrom<J> + JsonFrom<K> + JsonClone>(
+		&self,
+		meta: impl Clone + Fn(Option<&J::MetaData>) -> K::MetaData,
+	) -> K {
+		expansion::canonical_form(&self.as_json_with(meta))
+	}
+
+	/// Compacts this document using only a prefix map, without a full context.
+	///
+	/// See [`compaction::compact_with_prefixes`] for details.
+	pub fn compact_with_prefixes<K: JsonFrom<J> + JsonFrom<K> + JsonClone>(
+		&self,
+		prefixes: &HashMap<String, IriBuf>,
+		meta: impl Clone + Fn(Option<&J::MetaData>) -> K::MetaData,
+	) -> K {
+		compaction::compact_with_prefixes(&self.as_json_with(meta), prefixes)
+	}
+
+	/// Converts this document into a `K` JSON value in canonical expanded document form.
+	///
+	/// The `K` value is annotated with the default value of `K::MetaData`.
+	pub fn as_canonical_json<K: JsonFrom<J> + JsonFrom<K> + JsonClone>(&self) -> K
+	where
+		K::MetaData: Default,
+	{
+		self.as_canonical_json_with(|_| K::MetaData::default())
+	}
+
+	/// Converts this document into a document of another JSON type `K`,
+	/// using the given `meta` function to convert `J::MetaData` into `K::MetaData`.
+	///
+	/// The metadata of the warnings is converted as well.
+	/// This can be used to merge documents parsed with different metadata types
+	/// (see also [`strip`](Self::strip)).
+	pub fn map_metadata<K: JsonFrom<J> + JsonHash>(
+		self,
+		meta: impl Clone + Fn(Option<&J::MetaData>) -> K::MetaData,
+	) -> ExpandedDocument<K, T> {
+		let objects = self
+			.objects
+			.into_iter()
+			.map(|object| object.map_inner(|object| object.map_metadata(meta.clone())))
+			.collect();
+		let warnings = self
+			.warnings
+			.into_iter()
+			.map(|warning| warning.map_metadata(|m| meta(Some(&m))))
+			.collect();
+
+		ExpandedDocument::new(objects, warnings)
+	}
+
+	/// Converts this document into a document of another JSON type `K`
+	/// without metadata.
+	///
+	/// ```
+	/// # use json_ld::{context, Document, NoLoader};
+	/// # use serde_json::Value;
+	/// let doc: Value = serde_json::from_str(r#"{ "http://example.org/p": "v" }"#).unwrap();
+	///
+	/// let mut loader = NoLoader::<Value>::new();
+	/// let expanded =
+	///   async_std::task::block_on(doc.expand::<context::Json<Value>, _>(&mut loader)).unwrap();
+	///
+	/// let stripped: json_ld::ExpandedDocument<Value, _> = expanded.clone().strip();
+	/// assert_eq!(stripped.len(), expanded.len());
+	/// ```
+	#[inline(always)]
+	pub fn strip<K: JsonFrom<J> + JsonHash>(self) -> ExpandedDocument<K, T>
+	where
+		K: Json<MetaData = ()>,
+	{
+		self.map_metadata(|_| ())
+	}
+}
+
 impl<J: JsonHash + JsonClone, K: JsonFrom<J>, T: Id> AsJson<J, K> for ExpandedDocument<J, T> {
 	fn as_json_with(&self, meta: impl Clone + Fn(Option<&J::MetaData>) -> K::MetaData) -> K {
 		self.objects.as_json_with(meta)
@@ -122,6 +1261,18 @@ pub trait Document<T: Id> {
 	/// Document location, if any.
 	fn base_url(&self) -> Option<Iri>;
 
+	/// Metadata attached to the root of the document, if known.
+	///
+	/// Used to locate the errors that are not caused by a particular part of the document,
+	/// such as the failure to load the context given by the HTTP `Link` header of a
+	/// [`RemoteDocument`].
+	///
+	/// The default implementation returns `None`.
+	#[inline(always)]
+	fn root_metadata(&self) -> Option<&<Self::Json as Json>::MetaData> {
+		None
+	}
+
 	/// Expand the document with a custom base URL, initial context, document loader and
 	/// expansion options.
 	///
@@ -207,12 +1358,88 @@ pub trait Document<T: Id> {
 		.boxed()
 	}
 
+	/// Expand the document, blocking the current thread until the expansion is complete.
+	///
+	/// This is the synchronous counterpart of [`expand`](`Document::expand`),
+	/// for codebases that do not use an asynchronous runtime.
+	/// The expansion future is executed by a minimal internal executor,
+	/// which is why the loader must be [`NonBlocking`](loader::NonBlocking).
+	///
+	/// # Example
+	/// ```
+	/// # fn main() -> Result<(), json_ld::Loc<json_ld::Error, ()>> {
+	/// use json_ld::{Document, context, NoLoader};
+	/// use serde_json::Value;
+	///
+	/// let doc: Value = serde_json::from_str(r#"
+	///   {
+	///     "@context": { "name": "http://xmlns.com/foaf/0.1/name" },
+	///     "name": "Timothée Haudebourg"
+	///   }
+	/// "#).unwrap();
+	/// let mut loader = NoLoader::<Value>::new();
+	/// let expanded_doc = doc.expand_blocking::<context::Json<Value>, _>(&mut loader)?;
+	/// assert_eq!(expanded_doc.len(), 1);
+	/// # Ok(())
+	/// # }
+	/// ```
+	#[inline(always)]
+	fn expand_blocking<'a, C: 'a + ContextMut<T>, L: Loader + loader::NonBlocking>(
+		&'a self,
+		loader: &'a mut L,
+	) -> ExpansionResult<T, Self::Json>
+	where
+		Self: Send + Sync,
+		Self::Json: expansion::JsonExpand,
+		C: Send + Sync,
+		C::LocalContext: From<L::Output> + From<Self::Json>,
+		L: Send + Sync,
+		L::Output: Into<Self::Json>,
+		T: 'a + Send + Sync,
+	{
+		futures::executor::block_on(self.expand::<C, L>(loader))
+	}
+
+	/// Expand the document using the given `base` IRI instead of the document location.
+	///
+	/// This is the equivalent of the `base` option of the JSON-LD API:
+	/// when defined, `base` takes precedence over the document [`base_url`](`Document::base_url`)
+	/// to resolve relative IRI references and remote contexts.
+	/// Relative IRI resolution can be disabled altogether using the
+	/// [`forbid_relative_iris`](`expansion::Options::forbid_relative_iris`) option.
+	#[inline(always)]
+	fn expand_with_base<'a, C: 'a + ContextMut<T>, L: Loader>(
+		&'a self,
+		base: Option<Iri<'a>>,
+		loader: &'a mut L,
+		options: expansion::Options,
+	) -> BoxFuture<'a, ExpansionResult<T, Self::Json>>
+	where
+		Self: Send + Sync,
+		Self::Json: expansion::JsonExpand,
+		C: Send + Sync,
+		C::LocalContext: From<L::Output> + From<Self::Json>,
+		L: Send + Sync,
+		L::Output: Into<Self::Json>,
+		T: 'a + Send + Sync,
+	{
+		async move {
+			let base = base.or_else(|| self.base_url());
+			let context = C::new(base);
+			self.expand_with(base, &context, loader, options).await
+		}
+		.boxed()
+	}
+
 	/// Compact the document with a custom base URL, context, document loader and options.
 	///
+	/// The given `base_url` takes precedence over the document location.
+	///
 	/// The `meta_context` parameter is a function to convert the metadata
 	/// associated to the input context (JSON representation) to `K::MetaData`.
 	/// The `meta_document` parameter is another conversion function for the
 	/// metadata attached to the document.
+	#[inline(always)]
 	fn compact_with<'a, K: JsonFrom<Self::Json>, C: ContextMutProxy<T>, L: Loader, M1, M2>(
 		&'a self,
 		base_url: Option<Iri<'a>>,
@@ -240,83 +1467,117 @@ pub trait Document<T: Id> {
 		M2: 'a + Clone + Send + Sync + Fn(Option<&<Self::Json as Json>::MetaData>) -> K::MetaData,
 		L::Output: Into<Self::Json>,
 	{
-		use compaction::Compact;
-		async move {
-			let json_context = context.as_json_with(meta_context);
-			let context = context::Inversible::new(context.deref());
-			let expanded = self
-				.expand_with(base_url, &C::Target::new(base_url), loader, options.into())
-				.await
-				.map_err(Loc::unwrap)?;
-
-			let compacted: K = if expanded.len() == 1 && options.compact_arrays {
-				expanded
-					.into_iter()
-					.next()
-					.unwrap()
-					.compact_full(
-						context.clone(),
-						context.clone(),
-						None,
-						loader,
-						options,
-						meta_document.clone(),
-					)
-					.await?
-			} else {
-				expanded
-					.compact_full(
-						context.clone(),
-						context.clone(),
-						None,
-						loader,
-						options,
-						meta_document.clone(),
-					)
-					.await?
-			};
-
-			let (mut map, metadata) = match compacted.into_parts() {
-				(generic_json::Value::Array(items), metadata) => {
-					let mut map = K::Object::default();
-					if !items.is_empty() {
-						use crate::syntax::{Keyword, Term};
-						let key = crate::compaction::compact_iri::<Self::Json, _, _>(
-							context.clone(),
-							&Term::Keyword(Keyword::Graph),
-							true,
-							false,
-							options,
-						)?;
-						map.insert(
-							K::new_key(&key.unwrap(), meta_document(None)),
-							K::array(items, metadata),
-						);
-					}
+		self.compact_with_expand_context(
+			base_url,
+			None,
+			context,
+			loader,
+			options,
+			meta_context,
+			meta_document,
+		)
+	}
 
-					(map, meta_document(None))
-				}
-				(generic_json::Value::Object(map), metadata) => (map, metadata),
-				_ => {
-					// This should never be triggered unless some user
-					// uses a custom faulty `Compact` implementation.
-					panic!("invalid compact document")
+	/// Compact the document with a custom base URL, expansion context, context,
+	/// document loader and options.
+	///
+	/// The document is first expanded using the given `expand_context` as initial
+	/// active context, following the `expandContext` option of the JSON-LD API.
+	/// If `expand_context` is `None`, an empty context is used.
+	/// The expanded document is then compacted using `context`.
+	///
+	/// See [`compact_with`](`Document::compact_with`) for the other parameters.
+	fn compact_with_expand_context<
+		'a,
+		K: JsonFrom<Self::Json>,
+		C: ContextMutProxy<T>,
+		L: Loader,
+		M1,
+		M2,
+	>(
+		&'a self,
+		base_url: Option<Iri<'a>>,
+		expand_context: Option<&'a C::Target>,
+		context: &'a C,
+		loader: &'a mut L,
+		options: compaction::Options,
+		meta_context: M1,
+		meta_document: M2,
+	) -> BoxFuture<'a, Result<K, Error>>
+	where
+		Self: Sync,
+		Self::Json: expansion::JsonExpand + compaction::JsonSrc,
+		T: 'a + Send + Sync,
+		K: JsonFrom<<C::Target as Context<T>>::LocalContext>,
+		C: AsJson<<C::Target as Context<T>>::LocalContext, K> + Send + Sync,
+		<C::Target as Context<T>>::LocalContext:
+			compaction::JsonSrc + From<L::Output> + From<Self::Json>,
+		C::Target: Send + Sync,
+		L: 'a + Send + Sync,
+		M1: 'a
+			+ Clone
+			+ Send
+			+ Sync
+			+ Fn(Option<&<<C::Target as Context<T>>::LocalContext as Json>::MetaData>) -> K::MetaData,
+		M2: 'a + Clone + Send + Sync + Fn(Option<&<Self::Json as Json>::MetaData>) -> K::MetaData,
+		L::Output: Into<Self::Json>,
+	{
+		async move {
+			let initial_context;
+			let expand_context = match expand_context {
+				Some(expand_context) => expand_context,
+				None => {
+					initial_context = C::Target::new(base_url);
+					&initial_context
 				}
 			};
 
-			if !map.is_empty()
-				&& !json_context.is_null()
-				&& !json_context.is_empty_array_or_object()
-			{
-				map.insert(K::new_key("@context", meta_document(None)), json_context);
-			}
+			let expanded = self
+				.expand_with(base_url, expand_context, loader, options.clone().into())
+				.await
+				.map_err(Loc::unwrap)?;
 
-			Ok(K::object(map, metadata))
+			expanded
+				.compact_document(context, loader, options, meta_context, meta_document)
+				.await
 		}
 		.boxed()
 	}
 
 	/// Compact the document.
+	///
+	/// The document does not need to be expanded first:
+	/// it is expanded then compacted using the given context,
+	/// as specified by the `compact` method of the JSON-LD API.
+	/// Use [`compact_with_expand_context`](`Document::compact_with_expand_context`)
+	/// to provide an initial context to the expansion (`expandContext` option).
+	///
+	/// # Example
+	/// ```
+	/// # fn main() -> Result<(), json_ld::Loc<json_ld::Error, ()>> {
+	/// use async_std::task;
+	/// use json_ld::{context::{self, Local}, Document, NoLoader};
+	/// use serde_json::Value;
+	///
+	/// let input: Value = serde_json::from_str(r#"
+	///   {
+	///     "@id": "https://example.com/",
+	///     "http://xmlns.com/foaf/0.1/name": "Example"
+	///   }
+	/// "#).unwrap();
+	///
+	/// let context: Value = serde_json::from_str(r#"
+	///   { "name": "http://xmlns.com/foaf/0.1/name" }
+	/// "#).unwrap();
+	///
+	/// let mut loader = NoLoader::<Value>::new();
+	/// let processed_context =
+	///   task::block_on(context.process::<context::Json<Value>, _>(&mut loader, None))?;
+	/// let output = task::block_on(input.compact(&processed_context, &mut loader)).unwrap();
+	/// assert_eq!(output["name"], "Example");
+	/// # Ok(())
+	/// # }
+	/// ```
 	#[inline(always)]
 	fn compact<'a, C: ContextMutProxy<T> + AsJson<Self::Json, Self::Json>, L: Loader>(
 		&'a self,
@@ -344,6 +1605,35 @@ pub trait Document<T: Id> {
 			|m| m.cloned().unwrap_or_default(),
 		)
 	}
+
+	/// Compact the document, blocking the current thread until the compaction is complete.
+	///
+	/// This is the synchronous counterpart of [`compact`](`Document::compact`).
+	/// See [`expand_blocking`](`Document::expand_blocking`) for more details.
+	#[inline(always)]
+	fn compact_blocking<
+		'a,
+		C: ContextMutProxy<T> + AsJson<Self::Json, Self::Json>,
+		L: Loader + loader::NonBlocking,
+	>(
+		&'a self,
+		context: &'a C,
+		loader: &'a mut L,
+	) -> Result<Self::Json, Error>
+	where
+		Self: Sync,
+		Self::Json:
+			JsonFrom<Self::Json> + expansion::JsonExpand + compaction::JsonSrc + From<L::Output>,
+		<Self::Json as Json>::MetaData: Default,
+		T: 'a + Send + Sync,
+		C::Target: Context<T, LocalContext = Self::Json>,
+		C: Send + Sync,
+		C::Target: Send + Sync,
+		L: 'a + Send + Sync,
+		L::Output: Into<Self::Json>,
+	{
+		futures::executor::block_on(self.compact(context, loader))
+	}
 }
 
 /// Default JSON document implementation.
@@ -358,6 +1648,11 @@ impl<J: Json, T: Id> Document<T> for J {
 		None
 	}
 
+	#[inline(always)]
+	fn root_metadata(&self) -> Option<&J::MetaData> {
+		Some(Json::metadata(self))
+	}
+
 	#[inline(always)]
 	fn expand_with<'a, C: ContextMut<T>, L: Loader>(
 		&'a self,
@@ -391,7 +1686,12 @@ impl<J: Json, T: Id> Document<T> for J {
 /// Represent a document located at a given base URL.
 /// This is the result of loading a document with [`Loader::load`](`crate::Loader::load`).
 /// It is a simple wrapper that [`Deref`] to the underlying document while remembering its
-/// base URL.
+/// base URL, along with the [information](loader::DocumentInfo) given by the loader
+/// (such as its media type).
+///
+/// When the loader found a context URL (given by an HTTP `Link` header),
+/// this context is processed on top of the initial context when expanding the document,
+/// as specified by the JSON-LD API.
 ///
 /// # Example
 /// ```
@@ -423,6 +1723,12 @@ pub struct RemoteDocument<D> {
 
 	/// Document contents.
 	doc: D,
+
+	/// Document version, if known.
+	version: Option<loader::Version>,
+
+	/// Information given by the loader.
+	info: loader::DocumentInfo,
 }
 
 impl<D> RemoteDocument<D> {
@@ -433,13 +1739,74 @@ impl<D> RemoteDocument<D> {
 			base_url,
 			source,
 			doc,
+			version: None,
+			info: loader::DocumentInfo::default(),
 		}
 	}
 
+	/// Sets the version information of the document.
+	#[must_use]
+	pub fn with_version(mut self, version: Option<loader::Version>) -> Self {
+		self.version = version;
+		self
+	}
+
+	/// Sets the information given by the loader about the document.
+	#[must_use]
+	pub fn with_info(mut self, info: loader::DocumentInfo) -> Self {
+		self.info = info;
+		self
+	}
+
 	pub fn source(&self) -> loader::Id {
 		self.source
 	}
 
+	/// Returns the final URL of the document, after redirects.
+	///
+	/// This is the URL of the [version](Self::version) of the document, if known,
+	/// and its base URL otherwise.
+	#[inline(always)]
+	pub fn document_url(&self) -> Iri {
+		match &self.version {
+			Some(version) => version.url.as_iri(),
+			None => self.base_url.as_iri(),
+		}
+	}
+
+	/// Returns the information given by the loader about the document.
+	#[inline(always)]
+	pub fn info(&self) -> &loader::DocumentInfo {
+		&self.info
+	}
+
+	/// Returns the media type of the document, if known.
+	#[inline(always)]
+	pub fn content_type(&self) -> Option<&str> {
+		self.info.content_type.as_deref()
+	}
+
+	/// Returns the `profile` parameter of the media type of the document, if any.
+	#[inline(always)]
+	pub fn profile(&self) -> Option<&str> {
+		self.info.profile.as_deref()
+	}
+
+	/// Returns the URL of the context given by an HTTP `Link` header, if any.
+	#[inline(always)]
+	pub fn context_url(&self) -> Option<Iri> {
+		self.info.context_url.as_ref().map(|url| url.as_iri())
+	}
+
+	/// Returns the version information of the document, if known.
+	///
+	/// This includes the HTTP validators (`ETag`, `Last-Modified`) and final URL
+	/// of documents fetched over HTTP.
+	#[inline(always)]
+	pub fn version(&self) -> Option<&loader::Version> {
+		self.version.as_ref()
+	}
+
 	/// Consume the remote document and return the inner document.
 	#[inline(always)]
 	pub fn into_document(self) -> D {
@@ -454,15 +1821,32 @@ impl<D> RemoteDocument<D> {
 }
 
 /// A Remote document is a document.
-impl<T: Id, D: Document<T>> Document<T> for RemoteDocument<D> {
+impl<T: Id, D: Document<T> + Sync> Document<T> for RemoteDocument<D> {
 	type Json = D::Json;
 
+	/// Returns the [document URL](RemoteDocument::document_url).
 	#[inline(always)]
 	fn base_url(&self) -> Option<Iri> {
-		Some(self.base_url.as_iri())
+		Some(self.document_url())
 	}
 
 	#[inline(always)]
+	fn root_metadata(&self) -> Option<&<D::Json as Json>::MetaData> {
+		Document::<T>::root_metadata(&self.doc)
+	}
+
+	/// Expands the inner document.
+	///
+	/// If the document has a [context URL](RemoteDocument::context_url), given by an
+	/// HTTP `Link` header, the context is loaded and processed on top of the given
+	/// `context` before expansion.
+	/// Following the JSON-LD API, the `expandContext` option must then already be processed
+	/// in `context` (see [`ExpandContext::process`](expansion::ExpandContext::process)),
+	/// so that the context URL is applied after it.
+	///
+	/// The context URL is ignored if the [root metadata](Document::root_metadata) of the inner
+	/// document is unknown, since a loading failure could not be located.
+	/// It is always known for JSON values.
 	fn expand_with<'a, C: 'a + ContextMut<T> + Send + Sync, L: 'a + Loader + Send + Sync>(
 		&'a self,
 		base_url: Option<Iri>,
@@ -476,7 +1860,40 @@ impl<T: Id, D: Document<T>> Document<T> for RemoteDocument<D> {
 		L::Output: Into<Self::Json>,
 		T: 'a + Send + Sync,
 	{
-		self.doc.expand_with(base_url, context, loader, options)
+		let base_url = base_url.map(IriBuf::from);
+
+		async move {
+			let base_url = base_url.as_ref().map(|url| url.as_iri());
+			match (self.context_url(), Document::<T>::root_metadata(&self.doc)) {
+				(Some(context_url), Some(meta)) => {
+					let local_context: D::Json = loader
+						.load_context(context_url)
+						.await
+						.map_err(|e| e.located(Some(self.source), meta.clone()))?
+						.into_context()
+						.into();
+					let context = local_context
+						.process_with(
+							context,
+							loader,
+							Some(context_url),
+							context::ProcessingOptions::from(&options),
+						)
+						.await?
+						.into_inner();
+
+					self.doc
+						.expand_with(base_url, &context, loader, options)
+						.await
+				}
+				_ => {
+					self.doc
+						.expand_with(base_url, context, loader, options)
+						.await
+				}
+			}
+		}
+		.boxed()
 	}
 }
 
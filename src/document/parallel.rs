@@ -0,0 +1,93 @@
+//! Parallel iteration over expanded documents, using [`rayon`].
+use super::ExpandedDocument;
+use crate::{Id, Indexed, Object};
+use generic_json::JsonHash;
+#[cfg(feature = "hashbrown")]
+use hashbrown::hash_set::rayon::{IntoParIter, ParIter};
+#[cfg(not(feature = "hashbrown"))]
+use rayon::collections::hash_set::{IntoIter as IntoParIter, Iter as ParIter};
+use rayon::iter::{IntoParallelIterator, IntoParallelRefIterator, ParallelIterator};
+
+impl<J: JsonHash, T: Id> ExpandedDocument<J, T>
+where
+	Indexed<Object<J, T>>: Send + Sync,
+{
+	/// Returns a parallel iterator over the top-level objects of the document.
+	///
+	/// ```
+	/// # use json_ld::{context, Document, NoLoader};
+	/// # use iref::IriBuf;
+	/// # use serde_json::Value;
+	/// use rayon::iter::ParallelIterator;
+	///
+	/// let doc: Value = serde_json::from_str(r#"[
+	///   { "@id": "http://example.org/a", "http://example.org/p": 1 },
+	///   { "@id": "http://example.org/b", "http://example.org/p": 2 }
+	/// ]"#).unwrap();
+	/// # let mut loader = NoLoader::<Value>::new();
+	/// let expanded = async_std::task::block_on(
+	///   doc.expand::<context::Json<Value, IriBuf>, _>(&mut loader)
+	/// ).unwrap();
+	///
+	/// assert_eq!(expanded.par_iter().filter(|object| object.is_node()).count(), 2);
+	/// ```
+	#[inline(always)]
+	pub fn par_iter(&self) -> ParIter<'_, Indexed<Object<J, T>>> {
+		self.objects.par_iter()
+	}
+
+	/// Transforms every top-level object of the document in parallel.
+	///
+	/// The metadata held by the objects is kept as long as `f` does not drop it,
+	/// and the warnings of the document are preserved.
+	/// As with any set, objects mapped to equal objects are collapsed.
+	pub fn par_map<F>(self, f: F) -> Self
+	where
+		F: Fn(Indexed<Object<J, T>>) -> Indexed<Object<J, T>> + Send + Sync,
+	{
+		Self {
+			objects: self.objects.into_par_iter().map(f).collect(),
+			warnings: self.warnings,
+		}
+	}
+
+	/// Transforms the top-level objects of the document in parallel,
+	/// removing the objects for which `f` returns `None`.
+	///
+	/// See [`par_map`](Self::par_map) for the details.
+	pub fn par_filter_map<F>(self, f: F) -> Self
+	where
+		F: Fn(Indexed<Object<J, T>>) -> Option<Indexed<Object<J, T>>> + Send + Sync,
+	{
+		Self {
+			objects: self.objects.into_par_iter().filter_map(f).collect(),
+			warnings: self.warnings,
+		}
+	}
+}
+
+impl<J: JsonHash, T: Id> IntoParallelIterator for ExpandedDocument<J, T>
+where
+	Indexed<Object<J, T>>: Send,
+{
+	type Iter = IntoParIter<Indexed<Object<J, T>>>;
+	type Item = Indexed<Object<J, T>>;
+
+	#[inline(always)]
+	fn into_par_iter(self) -> Self::Iter {
+		self.objects.into_par_iter()
+	}
+}
+
+impl<'a, J: JsonHash, T: Id> IntoParallelIterator for &'a ExpandedDocument<J, T>
+where
+	Indexed<Object<J, T>>: Sync,
+{
+	type Iter = ParIter<'a, Indexed<Object<J, T>>>;
+	type Item = &'a Indexed<Object<J, T>>;
+
+	#[inline(always)]
+	fn into_par_iter(self) -> Self::Iter {
+		self.objects.par_iter()
+	}
+}
@@ -0,0 +1,236 @@
+use crate::util::collections::HashSet;
+use crate::{ExpandedDocument, Id, Indexed, Node, Object, Value};
+use generic_json::{JsonClone, JsonHash};
+
+/// Options of the [`ExpandedDocument::equivalent_modulo`] comparison.
+///
+/// By default, every option is disabled and the comparison only ignores the order of
+/// property values, types, and top-level objects.
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub struct EquivalenceOptions {
+	/// Consider a list (`@list`) equivalent to the repeated values of its items.
+	///
+	/// List items order is then ignored.
+	pub lists_as_sets: bool,
+
+	/// Consider numeric literals equivalent if they denote the same number,
+	/// whatever their representation (`1`, `1.0` or `"1"^^xsd:integer`).
+	pub numeric_literals: bool,
+
+	/// Ignore in which graph the nodes are defined.
+	///
+	/// The content of every named graph is merged into the default graph,
+	/// and node objects with the same identifier are merged.
+	pub graph_partitioning: bool,
+}
+
+impl EquivalenceOptions {
+	/// Ignore every difference supported by the options.
+	pub fn all() -> Self {
+		Self {
+			lists_as_sets: true,
+			numeric_literals: true,
+			graph_partitioning: true,
+		}
+	}
+}
+
+impl<J: JsonHash + JsonClone, T: Id> ExpandedDocument<J, T> {
+	/// Checks if this document is equivalent to the given one,
+	/// modulo the differences enabled in `options`.
+	///
+	/// Property values, types and top-level objects are compared as sets.
+	/// Blank node identifiers are compared as is.
+	pub fn equivalent_modulo(&self, other: &Self, options: EquivalenceOptions) -> bool {
+		if options.graph_partitioning {
+			let a = merge_graphs(self);
+			let b = merge_graphs(other);
+			objects_eq(a.iter(), b.iter(), options)
+		} else {
+			objects_eq(self.iter(), other.iter(), options)
+		}
+	}
+}
+
+/// Moves the content of every graph to the top level,
+/// and merges nodes with the same identifier.
+fn merge_graphs<J: JsonHash + JsonClone, T: Id>(
+	doc: &ExpandedDocument<J, T>,
+) -> ExpandedDocument<J, T> {
+	fn lift<J: JsonHash + JsonClone, T: Id>(
+		object: &Indexed<Object<J, T>>,
+		objects: &mut HashSet<Indexed<Object<J, T>>>,
+	) {
+		let mut object = object.clone();
+		if let Object::Node(node) = &mut *object {
+			if let Some(graph) = node.graph.take() {
+				for item in &graph {
+					lift(item, objects)
+				}
+
+				if node.is_empty() {
+					return;
+				}
+			}
+		}
+
+		objects.insert(object);
+	}
+
+	let mut objects = HashSet::new();
+	for object in doc {
+		lift(object, &mut objects)
+	}
+
+	std::iter::once(ExpandedDocument::new(objects, Vec::new())).collect()
+}
+
+/// Compares two sets of objects.
+fn objects_eq<'a, J: 'a + JsonHash, T: 'a + Id>(
+	a: impl Iterator<Item = &'a Indexed<Object<J, T>>>,
+	b: impl Iterator<Item = &'a Indexed<Object<J, T>>>,
+	options: EquivalenceOptions,
+) -> bool {
+	let a = values(a, options);
+	let b = values(b, options);
+	a.iter()
+		.all(|x| b.iter().any(|y| indexed_object_eq(x, y, options)))
+		&& b.iter()
+			.all(|y| a.iter().any(|x| indexed_object_eq(x, y, options)))
+}
+
+/// Collects the given property values, replacing lists by their items if
+/// [`EquivalenceOptions::lists_as_sets`] is enabled.
+fn values<'a, J: JsonHash, T: Id>(
+	objects: impl Iterator<Item = &'a Indexed<Object<J, T>>>,
+	options: EquivalenceOptions,
+) -> Vec<&'a Indexed<Object<J, T>>> {
+	let mut result = Vec::new();
+	for object in objects {
+		match object.inner() {
+			Object::List(items, _) if options.lists_as_sets => {
+				result.extend(values(items.iter(), options))
+			}
+			_ => result.push(object),
+		}
+	}
+
+	result
+}
+
+fn indexed_object_eq<J: JsonHash, T: Id>(
+	a: &Indexed<Object<J, T>>,
+	b: &Indexed<Object<J, T>>,
+	options: EquivalenceOptions,
+) -> bool {
+	let annotation_eq = match (a.annotation(), b.annotation()) {
+		(Some(a), Some(b)) => objects_eq(a.iter(), b.iter(), options),
+		(None, None) => true,
+		_ => false,
+	};
+
+	a.index() == b.index() && object_eq(a, b, options) && annotation_eq
+}
+
+fn object_eq<J: JsonHash, T: Id>(
+	a: &Object<J, T>,
+	b: &Object<J, T>,
+	options: EquivalenceOptions,
+) -> bool {
+	match (a, b) {
+		(Object::Value(a, _), Object::Value(b, _)) => value_eq(a, b, options),
+		(Object::Node(a), Object::Node(b)) => node_eq(a, b, options),
+		(Object::List(a, _), Object::List(b, _)) => {
+			a.len() == b.len()
+				&& a.iter()
+					.zip(b)
+					.all(|(a, b)| indexed_object_eq(a, b, options))
+		}
+		_ => false,
+	}
+}
+
+fn value_eq<J: JsonHash, T: Id>(
+	a: &Value<J, T>,
+	b: &Value<J, T>,
+	options: EquivalenceOptions,
+) -> bool {
+	if options.numeric_literals {
+		if let (Some(x), Some(y)) = (numeric(a), numeric(b)) {
+			return x == y || (x.is_nan() && y.is_nan());
+		}
+	}
+
+	a == b
+}
+
+/// Returns the number denoted by the given value, if it is a numeric literal.
+fn numeric<J: JsonHash, T: Id>(value: &Value<J, T>) -> Option<f64> {
+	match value {
+		Value::Literal(crate::object::Literal::Number(_), None) => value.as_f64(),
+		Value::Literal(_, Some(_)) => match value.xsd_datatype() {
+			Some(ty) if ty.is_numeric() => value.as_f64(),
+			_ => None,
+		},
+		_ => None,
+	}
+}
+
+/// Compares optional sets of nodes, such as included nodes.
+fn node_set_eq<J: JsonHash, T: Id>(
+	a: &Option<HashSet<Indexed<Node<J, T>>>>,
+	b: &Option<HashSet<Indexed<Node<J, T>>>>,
+	options: EquivalenceOptions,
+) -> bool {
+	match (a, b) {
+		(Some(a), Some(b)) => {
+			a.iter().all(|x| {
+				b.iter()
+					.any(|y| x.index() == y.index() && node_eq(x, y, options))
+			}) && b.iter().all(|y| {
+				a.iter()
+					.any(|x| x.index() == y.index() && node_eq(x, y, options))
+			})
+		}
+		(None, None) => true,
+		_ => false,
+	}
+}
+
+fn node_eq<J: JsonHash, T: Id>(
+	a: &Node<J, T>,
+	b: &Node<J, T>,
+	options: EquivalenceOptions,
+) -> bool {
+	if a.id != b.id
+		|| !a.types.iter().all(|ty| b.types.contains(ty))
+		|| !b.types.iter().all(|ty| a.types.contains(ty))
+	{
+		return false;
+	}
+
+	let graph_eq = match (&a.graph, &b.graph) {
+		(Some(a), Some(b)) => objects_eq(a.iter(), b.iter(), options),
+		(None, None) => true,
+		_ => false,
+	};
+
+	graph_eq
+		&& node_set_eq(&a.included, &b.included, options)
+		&& a.properties.len() == b.properties.len()
+		&& a.properties.iter().all(|(prop, a_values)| {
+			b.properties.contains(prop) && objects_eq(a_values.iter(), b.get(prop), options)
+		}) && a.reverse_properties.len() == b.reverse_properties.len()
+		&& a.reverse_properties.iter().all(|(prop, a_nodes)| {
+			let b_nodes: Vec<_> = b.reverse_properties.get(prop).collect();
+			a_nodes.iter().all(|x| {
+				b_nodes
+					.iter()
+					.any(|y| x.index() == y.index() && node_eq(x, y, options))
+			}) && b_nodes.iter().all(|y| {
+				a_nodes
+					.iter()
+					.any(|x| x.index() == y.index() && node_eq(x, y, options))
+			})
+		})
+}
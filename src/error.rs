@@ -1,26 +1,45 @@
-use crate::{loader, Loc};
-use std::convert::TryFrom;
-use std::fmt;
+#[cfg(not(feature = "std"))]
+use crate::prelude::*;
+#[cfg(feature = "std")]
+use crate::{context, loader, Loc};
+use core::convert::TryFrom;
+use core::fmt;
+#[cfg(feature = "std")]
+use iref::IriBuf;
 
 /// Error type.
 ///
 /// This is the type of all the errors that may occur during a JSON-LD document processing.
-/// Each error is described by an error code.
+/// Each error is described by an error code,
+/// optionally completed with structured [`ErrorDetails`] about its cause.
 /// See [`ErrorCode`] for more informations about all the different possible errors.
+#[cfg(feature = "std")]
 #[derive(Debug)]
 pub struct Error {
 	/// Error code.
 	code: ErrorCode,
 
+	/// Details about the error, if any.
+	details: Option<ErrorDetails>,
+
 	/// The lower-level source of this error, if any.
 	source: Option<Box<dyn std::error::Error + 'static>>,
+
+	/// Remote context inclusions leading to the error, innermost first.
+	trace: Vec<Inclusion>,
 }
 
+#[cfg(feature = "std")]
 impl Error {
 	/// Create a new error.
 	#[inline(always)]
 	pub fn new(code: ErrorCode) -> Error {
-		Error { code, source: None }
+		Error {
+			code,
+			details: None,
+			source: None,
+			trace: Vec::new(),
+		}
 	}
 
 	/// Create a new error with a given error source.
@@ -28,7 +47,20 @@ impl Error {
 	pub fn with_source<S: std::error::Error + 'static>(code: ErrorCode, source: S) -> Error {
 		Error {
 			code,
+			details: None,
 			source: Some(Box::new(source)),
+			trace: Vec::new(),
+		}
+	}
+
+	/// Create a new error with the given details.
+	#[inline(always)]
+	pub fn with_details(code: ErrorCode, details: ErrorDetails) -> Error {
+		Error {
+			code,
+			details: Some(details),
+			source: None,
+			trace: Vec::new(),
 		}
 	}
 
@@ -38,12 +70,144 @@ impl Error {
 		self.code
 	}
 
+	/// Get the details of the error, if any.
+	#[inline(always)]
+	pub fn details(&self) -> Option<&ErrorDetails> {
+		self.details.as_ref()
+	}
+
+	/// Sets the details of the error, replacing any previous details.
+	#[must_use]
+	pub fn set_details(mut self, details: ErrorDetails) -> Self {
+		self.details = Some(details);
+		self
+	}
+
+	/// Attach the term being defined when the error occurred.
+	///
+	/// Does nothing if the error already has details,
+	/// so that the details closest to the cause of the error are kept.
+	#[must_use]
+	pub fn with_term(mut self, term: &str) -> Self {
+		if self.details.is_none() {
+			self.details = Some(ErrorDetails::Term {
+				term: term.to_string(),
+			})
+		}
+
+		self
+	}
+
+	/// Returns the chain of remote context inclusions leading to the error,
+	/// innermost first.
+	///
+	/// The error is located in the context included by the first inclusion (if any),
+	/// itself included by the document of the second inclusion, etc.
+	#[inline(always)]
+	pub fn trace(&self) -> &[Inclusion] {
+		&self.trace
+	}
+
+	/// Records that the error occurred in (or below) the given included context.
+	pub fn push_inclusion(&mut self, inclusion: Inclusion) {
+		self.trace.push(inclusion)
+	}
+
+	/// Returns a value displaying this error followed by its inclusion trace,
+	/// one inclusion per line.
+	///
+	/// The given loader is used to find the URL of the including documents.
+	///
+	/// ```
+	/// use iref::IriBuf;
+	/// use json_ld::{loader::Id, Error, ErrorCode, Inclusion, NoLoader};
+	/// use serde_json::Value;
+	///
+	/// let mut error = Error::new(ErrorCode::InvalidTermDefinition);
+	/// error.push_inclusion(Inclusion {
+	///   url: IriBuf::new("https://example.com/b.jsonld").unwrap(),
+	///   source: None,
+	///   import: true,
+	/// });
+	///
+	/// let loader = NoLoader::<Value>::new();
+	/// assert_eq!(
+	///   error.display_trace(&loader).to_string(),
+	///   "invalid term definition\n  in `https://example.com/b.jsonld`, imported by the document"
+	/// );
+	/// ```
+	#[inline(always)]
+	pub fn display_trace<'a, L: ?Sized + context::Loader>(&'a self, loader: &'a L) -> Trace<'a, L> {
+		Trace {
+			error: self,
+			loader,
+		}
+	}
+
 	/// Turns this error into a located error attached with the given `metadata`.
 	pub fn located<M>(self, source: Option<loader::Id>, metadata: M) -> Loc<Error, M> {
 		Loc::new(self, source, metadata)
 	}
 }
 
+#[cfg(feature = "std")]
+impl<M> Loc<Error, M> {
+	/// Records that the error occurred in (or below) the given included context.
+	#[must_use]
+	pub fn included_from(mut self, inclusion: Inclusion) -> Self {
+		self.push_inclusion(inclusion);
+		self
+	}
+}
+
+/// Inclusion of a remote context by a document,
+/// recorded in the [trace](Error::trace) of errors.
+#[cfg(feature = "std")]
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Inclusion {
+	/// URL of the included context.
+	pub url: IriBuf,
+
+	/// Including document, if known.
+	pub source: Option<loader::Id>,
+
+	/// Whether the context is included through an `@import` entry,
+	/// rather than referenced as a remote context.
+	pub import: bool,
+}
+
+/// Error displayed with its inclusion trace.
+///
+/// See [`Error::display_trace`].
+#[cfg(feature = "std")]
+pub struct Trace<'a, L: ?Sized> {
+	error: &'a Error,
+	loader: &'a L,
+}
+
+#[cfg(feature = "std")]
+impl<'a, L: ?Sized + context::Loader> fmt::Display for Trace<'a, L> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		self.error.fmt(f)?;
+		for inclusion in &self.error.trace {
+			let verb = if inclusion.import {
+				"imported"
+			} else {
+				"loaded"
+			};
+
+			write!(f, "\n  in `{}`, {} by ", inclusion.url, verb)?;
+			match inclusion.source.and_then(|id| self.loader.iri(id)) {
+				Some(iri) => write!(f, "`{}`", iri)?,
+				None => write!(f, "the document")?,
+			}
+		}
+
+		Ok(())
+	}
+}
+
+#[cfg(feature = "std")]
 impl std::error::Error for Error {
 	#[inline(always)]
 	fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
@@ -54,22 +218,99 @@ impl std::error::Error for Error {
 	}
 }
 
+#[cfg(feature = "std")]
 impl fmt::Display for Error {
-	#[inline(always)]
 	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-		write!(f, "{}", self.code.as_str())
+		match &self.details {
+			Some(details) => write!(f, "{} ({})", self.code.as_str(), details),
+			None => write!(f, "{}", self.code.as_str()),
+		}
 	}
 }
 
+#[cfg(feature = "std")]
 impl From<ErrorCode> for Error {
 	fn from(c: ErrorCode) -> Self {
 		Self::new(c)
 	}
 }
 
+/// Structured details about an error.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum ErrorDetails {
+	/// The error occurred while defining the given term.
+	Term {
+		/// Term being defined.
+		term: String,
+	},
+
+	/// The given term is mapped to an invalid IRI.
+	InvalidIriMapping {
+		/// Term being defined.
+		term: String,
+
+		/// Offending IRI mapping.
+		value: String,
+	},
+
+	/// The given IRI could not be handled.
+	Iri {
+		/// Offending IRI.
+		iri: String,
+	},
+
+	/// The given resource limit has been exceeded.
+	Limit {
+		/// Name of the limit (the corresponding [`Limits`](crate::Limits) field).
+		name: String,
+
+		/// Value of the limit.
+		max: usize,
+	},
+
+	/// A value does not match what was expected.
+	UnexpectedValue {
+		/// Description of the expected value.
+		expected: String,
+
+		/// Value found instead.
+		found: String,
+	},
+}
+
+impl ErrorDetails {
+	/// Returns the term concerned by the error, if any.
+	pub fn term(&self) -> Option<&str> {
+		match self {
+			Self::Term { term } | Self::InvalidIriMapping { term, .. } => Some(term),
+			_ => None,
+		}
+	}
+}
+
+impl fmt::Display for ErrorDetails {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Self::Term { term } => write!(f, "term `{}`", term),
+			Self::InvalidIriMapping { term, value } => {
+				write!(f, "term `{}` mapped to `{}`", term, value)
+			}
+			Self::Iri { iri } => write!(f, "IRI `{}`", iri),
+			Self::Limit { name, max } => write!(f, "`{}` limit of {} exceeded", name, max),
+			Self::UnexpectedValue { expected, found } => {
+				write!(f, "expected {}, found {}", expected, found)
+			}
+		}
+	}
+}
+
 /// Error code.
 #[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Debug)]
 pub enum ErrorCode {
+	/// The processing has been cancelled through its [`Monitor`](crate::Monitor).
+	/// Note: this error is not defined in the JSON-LD API specification.
+	Cancelled,
+
 	/// Two properties which expand to the same keyword have been detected.
 	/// This might occur if a keyword and an alias thereof are used at the same time.
 	CollidingKeywords,
@@ -83,6 +324,19 @@ pub enum ErrorCode {
 	/// A cycle in IRI mappings has been detected.
 	CyclicIriMapping,
 
+	/// A relative IRI reference was found while relative IRI resolution is forbidden.
+	ForbiddenRelativeIri,
+
+	/// The loading of a remote document has been forbidden by the
+	/// [`LoadPolicy`](crate::loader::LoadPolicy) of the loader.
+	/// Note: this error is not defined in the JSON-LD API specification.
+	ForbiddenRemoteDocument,
+
+	/// A number cannot be converted into an RDF literal without loss of precision.
+	/// Only raised in the lossless [numbers conversion mode](crate::rdf::NumberOptions::lossless).
+	/// Note: this error is not defined in the JSON-LD API specification.
+	InexactNumber,
+
 	/// An `@id` entry was encountered whose value was not a string.
 	InvalidIdValue,
 
@@ -113,6 +367,11 @@ pub enum ErrorCode {
 	/// The `@version` entry was used in a context with an out of range value.
 	InvalidVersionValue,
 
+	/// An `@annotation` entry was found where annotations are not allowed,
+	/// or its value is not a (set of) node object(s).
+	/// Note: this error is defined by the JSON-LD-star draft.
+	InvalidAnnotation,
+
 	/// The value of `@direction` is not "ltr", "rtl", or null and thus invalid.
 	InvalidBaseDirection,
 
@@ -210,6 +469,9 @@ pub enum ErrorCode {
 	/// (because its IRI scheme matches a term definition and it has no IRI authority).
 	IriConfusedWithPrefix,
 
+	/// A JSON literal value exceeds the configured depth or size limits.
+	JsonLiteralLimitExceeded,
+
 	/// Unable to expand a key into a IRI, blank node identifier or keyword
 	/// using the current [key expansion policy](crate::expansion::Policy).
 	/// Note: this error is not defined in the JSON-LD API specification.
@@ -218,6 +480,10 @@ pub enum ErrorCode {
 	/// A keyword redefinition has been detected.
 	KeywordRedefinition,
 
+	/// A document exceeds the configured resource [`Limits`](crate::Limits).
+	/// Note: this error is not defined in the JSON-LD API specification.
+	LimitExceeded,
+
 	/// The document could not be loaded or parsed as JSON.
 	LoadingDocumentFailed,
 
@@ -238,14 +504,18 @@ pub enum ErrorCode {
 
 impl ErrorCode {
 	/// Get the error message corresponding to the error code.
-	pub fn as_str(&self) -> &str {
+	pub fn as_str(&self) -> &'static str {
 		use ErrorCode::*;
 
 		match self {
+			Cancelled => "cancelled",
 			CollidingKeywords => "colliding keywords",
 			ConflictingIndexes => "conflicting indexes",
 			ContextOverflow => "context overflow",
 			CyclicIriMapping => "cyclic IRI mapping",
+			ForbiddenRelativeIri => "forbidden relative IRI",
+			ForbiddenRemoteDocument => "forbidden remote document",
+			InexactNumber => "inexact number",
 			InvalidIdValue => "invalid @id value",
 			InvalidImportValue => "invalid @import value",
 			InvalidIncludedValue => "invalid @included value",
@@ -256,6 +526,7 @@ impl ErrorCode {
 			InvalidProtectedValue => "invalid @protected value",
 			InvalidReverseValue => "invalid @reverse value",
 			InvalidVersionValue => "invalid @version value",
+			InvalidAnnotation => "invalid annotation",
 			InvalidBaseDirection => "invalid base direction",
 			InvalidBaseIri => "invalid base IRI",
 			InvalidContainerMapping => "invalid container mapping",
@@ -285,8 +556,10 @@ impl ErrorCode {
 			InvalidValueObjectValue => "invalid value object value",
 			InvalidVocabMapping => "invalid vocab mapping",
 			IriConfusedWithPrefix => "IRI confused with prefix",
+			JsonLiteralLimitExceeded => "JSON literal limit exceeded",
 			KeyExpansionFailed => "key expansion failed",
 			KeywordRedefinition => "keyword redefinition",
+			LimitExceeded => "limit exceeded",
 			LoadingDocumentFailed => "loading document failed",
 			LoadingRemoteContextFailed => "loading remote context failed",
 			MultipleContextLinkHeaders => "multiple context link headers",
@@ -296,6 +569,7 @@ impl ErrorCode {
 	}
 
 	/// Turns this error code into an actual located error attached with the given `metadata`.
+	#[cfg(feature = "std")]
 	pub fn located<M>(self, source: Option<loader::Id>, metadata: M) -> Loc<Error, M> {
 		Error::from(self).located(source, metadata)
 	}
@@ -307,10 +581,14 @@ impl<'a> TryFrom<&'a str> for ErrorCode {
 	fn try_from(name: &'a str) -> Result<ErrorCode, ()> {
 		use ErrorCode::*;
 		match name {
+			"cancelled" => Ok(Cancelled),
 			"colliding keywords" => Ok(CollidingKeywords),
 			"conflicting indexes" => Ok(ConflictingIndexes),
 			"context overflow" => Ok(ContextOverflow),
 			"cyclic IRI mapping" => Ok(CyclicIriMapping),
+			"forbidden relative IRI" => Ok(ForbiddenRelativeIri),
+			"forbidden remote document" => Ok(ForbiddenRemoteDocument),
+			"inexact number" => Ok(InexactNumber),
 			"invalid @id value" => Ok(InvalidIdValue),
 			"invalid @import value" => Ok(InvalidImportValue),
 			"invalid @included value" => Ok(InvalidIncludedValue),
@@ -321,6 +599,7 @@ impl<'a> TryFrom<&'a str> for ErrorCode {
 			"invalid @protected value" => Ok(InvalidProtectedValue),
 			"invalid @reverse value" => Ok(InvalidReverseValue),
 			"invalid @version value" => Ok(InvalidVersionValue),
+			"invalid annotation" => Ok(InvalidAnnotation),
 			"invalid base direction" => Ok(InvalidBaseDirection),
 			"invalid base IRI" => Ok(InvalidBaseIri),
 			"invalid container mapping" => Ok(InvalidContainerMapping),
@@ -350,8 +629,10 @@ impl<'a> TryFrom<&'a str> for ErrorCode {
 			"invalid value object value" => Ok(InvalidValueObjectValue),
 			"invalid vocab mapping" => Ok(InvalidVocabMapping),
 			"IRI confused with prefix" => Ok(IriConfusedWithPrefix),
+			"JSON literal limit exceeded" => Ok(JsonLiteralLimitExceeded),
 			"key expansion failed" => Ok(KeyExpansionFailed),
 			"keyword redefinition" => Ok(KeywordRedefinition),
+			"limit exceeded" => Ok(LimitExceeded),
 			"loading document failed" => Ok(LoadingDocumentFailed),
 			"loading remote context failed" => Ok(LoadingRemoteContextFailed),
 			"multiple context link headers" => Ok(MultipleContextLinkHeaders),
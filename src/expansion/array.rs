@@ -18,6 +18,7 @@ pub async fn expand_array<
 	active_property: ActiveProperty<'_, J>,
 	active_property_definition: Option<&TermDefinition<T, C>>,
 	element: &J::Array,
+	metadata: &J::MetaData,
 	base_url: Option<Iri<'_>>,
 	loader: &mut L,
 	options: Options,
@@ -51,7 +52,7 @@ where
 				&*item,
 				base_url,
 				loader,
-				options,
+				options.clone(),
 				from_map,
 				warnings,
 			)
@@ -60,7 +61,9 @@ where
 	}
 
 	if is_list {
-		return Ok(Expanded::Object(Object::List(result).into()));
+		return Ok(Expanded::Object(
+			Object::List(result, Some(metadata.clone())).into(),
+		));
 	}
 
 	// Return result.
@@ -0,0 +1,76 @@
+use crate::util::{AsJson, JsonFrom};
+use cc_traits::{Get, Iter, MapIter};
+use generic_json::{Json, JsonClone, Key, ValueRef};
+
+/// Puts the given expanded document in canonical expanded document form.
+///
+/// In this form the document is an array, and the `@type`, `@graph`, `@included`
+/// and `@list` entries of every object, as well as every property value
+/// (including reverse properties), are arrays, even when they hold a single item.
+///
+/// The input is assumed to be in expanded form.
+/// Value objects are left untouched.
+pub fn canonical_form<K: JsonClone + JsonFrom<K>>(json: &K) -> K {
+	canonical_array(json, canonical_object)
+}
+
+/// Copies the given JSON value.
+fn copy<K: JsonClone + JsonFrom<K>>(json: &K) -> K {
+	let meta = json.metadata().clone();
+	AsJson::<K, K>::as_json_with(json, move |m: Option<&K::MetaData>| {
+		m.cloned().unwrap_or_else(|| meta.clone())
+	})
+}
+
+/// Converts the given value into an array, if it is not already,
+/// applying `item` on each item.
+fn canonical_array<K: JsonClone + JsonFrom<K>>(json: &K, item: fn(&K) -> K) -> K {
+	let meta = json.metadata().clone();
+	match json.as_value_ref() {
+		ValueRef::Array(ary) => K::array(ary.iter().map(|i| item(&*i)).collect(), meta),
+		_ => K::array(std::iter::once(item(json)).collect(), meta),
+	}
+}
+
+/// Puts the given node, value or list object in canonical form.
+fn canonical_object<K: JsonClone + JsonFrom<K>>(json: &K) -> K {
+	match json.as_value_ref() {
+		ValueRef::Object(obj) if obj.get("@value").is_none() => K::object(
+			obj.iter()
+				.map(|(key, value)| {
+					let value = match &**key {
+						"@type" => canonical_array(&*value, copy),
+						"@list" | "@graph" | "@included" | "@annotation" => {
+							canonical_array(&*value, canonical_object)
+						}
+						"@reverse" => canonical_reverse(&*value),
+						key if key.starts_with('@') => copy(&*value),
+						_ => canonical_array(&*value, canonical_object),
+					};
+
+					(K::new_key(&**key, key.metadata().clone()), value)
+				})
+				.collect(),
+			json.metadata().clone(),
+		),
+		_ => copy(json),
+	}
+}
+
+/// Puts the given `@reverse` entry value in canonical form.
+fn canonical_reverse<K: JsonClone + JsonFrom<K>>(json: &K) -> K {
+	match json.as_value_ref() {
+		ValueRef::Object(obj) => K::object(
+			obj.iter()
+				.map(|(key, value)| {
+					(
+						K::new_key(&**key, key.metadata().clone()),
+						canonical_array(&*value, canonical_object),
+					)
+				})
+				.collect(),
+			json.metadata().clone(),
+		),
+		_ => copy(json),
+	}
+}
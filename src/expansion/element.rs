@@ -1,8 +1,9 @@
 use super::{
 	expand_array, expand_iri, expand_literal, expand_node, expand_value, ActiveProperty, Entry,
-	Expanded, ExpandedEntry, JsonExpand, LiteralValue, Options,
+	Expanded, ExpandedEntry, JsonExpand, KeywordAction, LiteralValue, Options,
 };
 use crate::util::as_array;
+use crate::util::future::{BoxFuture, FutureExt};
 use crate::{
 	context::{ContextMut, Loader, Local, ProcessingOptions},
 	object::*,
@@ -10,7 +11,6 @@ use crate::{
 	Error, ErrorCode, Id, Indexed, Loc, Reference, Warning,
 };
 use cc_traits::{CollectionRef, Get, KeyedRef, Len, MapIter};
-use futures::future::{BoxFuture, FutureExt};
 use generic_json::{Json, Key, ValueRef};
 use iref::Iri;
 use mown::Mown;
@@ -48,29 +48,29 @@ where
 			return Ok(Expanded::Null);
 		}
 
+		if let Some(monitor) = &options.monitor {
+			monitor
+				.advance_value(element)
+				.map_err(|e| e.located(source, element.metadata().clone()))?
+		}
+
 		let active_property_definition = active_context.get_opt(active_property.id());
 
 		// If `active_property` has a term definition in `active_context` with a local context,
 		// initialize property-scoped context to that local context.
-		let mut property_scoped_base_url = None;
-		let property_scoped_context = if let Some(definition) = active_property_definition {
-			if let Some(base_url) = &definition.base_url {
-				property_scoped_base_url = Some(base_url.as_iri());
-			}
-
-			definition.context.as_ref()
-		} else {
-			None
-		};
+		// The local context is processed by its term definition, which caches the result.
+		let property_scoped_definition =
+			active_property_definition.filter(|definition| definition.context.is_some());
 
 		match element.as_value_ref() {
 			ValueRef::Null => unreachable!(),
-			ValueRef::Array(element) => {
+			ValueRef::Array(array) => {
 				expand_array(
 					active_context,
 					active_property,
 					active_property_definition,
-					element,
+					array,
+					element.metadata(),
 					base_url,
 					loader,
 					options,
@@ -133,21 +133,19 @@ where
 				// `property_scoped_context` as `local_context`, `base_url` from the term
 				// definition for `active_property`, in `active_context` and `true` for
 				// `override_protected`.
-				if let Some(property_scoped_context) = property_scoped_context {
-					let options: ProcessingOptions = options.into();
+				if let Some(property_scoped_definition) = property_scoped_definition {
+					let options = ProcessingOptions::from(&options);
 					active_context = Mown::Owned(
-						property_scoped_context
-							.process_with(
+						property_scoped_definition
+							.process_scoped_context(
 								active_context.as_ref(),
 								loader,
-								property_scoped_base_url,
 								options.with_override(),
 							)
 							.await
 							.map_err(|e| {
 								e.with_metadata(active_property.metadata().unwrap().clone())
-							})?
-							.into_inner(),
+							})?,
 					);
 				}
 
@@ -157,7 +155,12 @@ where
 				if let Some(local_context) = element.get("@context") {
 					active_context = Mown::Owned(
 						local_context
-							.process_with(active_context.as_ref(), loader, base_url, options.into())
+							.process_with(
+								active_context.as_ref(),
+								loader,
+								base_url,
+								ProcessingOptions::from(&options),
+							)
 							.await?
 							.into_inner(),
 					);
@@ -207,25 +210,21 @@ where
 					for term in sorted_value {
 						let term_str = term.as_str().unwrap();
 						if let Some(term_definition) = type_scoped_context.get(term_str) {
-							if let Some(local_context) = &term_definition.context {
+							if term_definition.context.is_some() {
 								// set `active_context` to the result of
 								// Context Processing algorithm, passing `active_context`, the value of the
 								// `term`'s local context as `local_context`, `base_url` from the term
 								// definition for value in `active_context`, and `false` for `propagate`.
-								let base_url =
-									term_definition.base_url.as_ref().map(|url| url.as_iri());
-								let options: ProcessingOptions = options.into();
+								let options = ProcessingOptions::from(&options);
 								active_context = Mown::Owned(
-									local_context
-										.process_with(
+									term_definition
+										.process_scoped_context(
 											active_context.as_ref(),
 											loader,
-											base_url,
 											options.without_propagation(),
 										)
 										.await
-										.map_err(|e| e.with_metadata(term.metadata().clone()))?
-										.into_inner(),
+										.map_err(|e| e.with_metadata(term.metadata().clone()))?,
 								);
 							}
 						}
@@ -262,11 +261,26 @@ where
 				let mut list_entry = None;
 				let mut set_entry = None;
 				let mut value_entry = None;
+				let mut annotation_entry = None;
+				let mut custom_keywords = Vec::new();
 				for Entry(key, value) in entries {
 					if key.is_empty() {
 						warnings.push(Loc::new(Warning::EmptyTerm, source, key.metadata().clone()));
 					}
 
+					match options.keyword_action(key.as_ref()) {
+						KeywordAction::Default => (),
+						KeywordAction::Ignore => continue,
+						KeywordAction::Keep => {
+							custom_keywords.push((
+								key.as_ref().to_string(),
+								key.metadata().clone(),
+								(*value).clone(),
+							));
+							continue;
+						}
+					}
+
 					let expanded_key = expand_iri(
 						source,
 						active_context.as_ref(),
@@ -284,6 +298,20 @@ where
 							list_entry = Some(value.clone())
 						}
 						Term::Keyword(Keyword::Set) => set_entry = Some(value.clone()),
+						Term::Keyword(Keyword::Annotation) => {
+							if options.annotation {
+								annotation_entry = Some(value.clone());
+							} else {
+								// Annotations are disabled: `@annotation` is an unknown keyword.
+								warnings.push(Loc::new(
+									Warning::KeywordLikeValue(key.as_ref().to_string()),
+									source,
+									key.metadata().clone(),
+								));
+							}
+
+							continue;
+						}
 						Term::Ref(Reference::Blank(id)) => {
 							warnings.push(Loc::new(
 								Warning::BlankNodeIdProperty(id.clone()),
@@ -301,6 +329,56 @@ where
 					))
 				}
 
+				// If `element` has an `@annotation` entry (JSON-LD-star), expand its value
+				// into a set of node objects.
+				let annotation = match annotation_entry {
+					Some(annotation_entry) => {
+						// Only property values can be annotated.
+						if active_property.is_none()
+							|| active_property == Some("@graph")
+							|| list_entry.is_some()
+							|| set_entry.is_some()
+						{
+							return Err(ErrorCode::InvalidAnnotation
+								.located(source, annotation_entry.metadata().clone()));
+						}
+
+						let expanded = expand_element(
+							active_context.as_ref(),
+							ActiveProperty::None,
+							&*annotation_entry,
+							base_url,
+							loader,
+							options.clone(),
+							false,
+							warnings,
+						)
+						.await?;
+
+						// Annotations are node objects without identifier.
+						let mut annotation = Vec::new();
+						for object in expanded {
+							if !matches!(object.inner(), Object::Node(node) if node.id().is_none())
+							{
+								return Err(ErrorCode::InvalidAnnotation
+									.located(source, annotation_entry.metadata().clone()));
+							}
+
+							annotation.push(object)
+						}
+
+						annotation
+					}
+					None => Vec::new(),
+				};
+
+				// Custom keyword entries are only kept on node objects.
+				if list_entry.is_some() || set_entry.is_some() || value_entry.is_some() {
+					for (key, metadata, _) in custom_keywords.drain(..) {
+						warnings.push(Loc::new(Warning::KeywordLikeValue(key), source, metadata));
+					}
+				}
+
 				if let Some(list_entry) = list_entry {
 					// List objects.
 					let mut index = None;
@@ -308,6 +386,12 @@ where
 						match expanded_key {
 							Term::Keyword(Keyword::Index) => match value.as_str() {
 								Some(value) => index = Some(value.to_string()),
+								None if options.recovers(ErrorCode::InvalidIndexValue) => warnings
+									.push(Loc::new(
+										Warning::DroppedEntry(ErrorCode::InvalidIndexValue),
+										source,
+										value.metadata().clone(),
+									)),
 								None => {
 									return Err(ErrorCode::InvalidIndexValue
 										.located(source, value.metadata().clone()))
@@ -335,7 +419,7 @@ where
 								&*item,
 								base_url,
 								loader,
-								options,
+								options.clone(),
 								false,
 								warnings,
 							)
@@ -343,7 +427,16 @@ where
 						)
 					}
 
-					Ok(Expanded::Object(Indexed::new(Object::List(result), index)))
+					// List items cannot be annotated.
+					if result.iter().any(|item| item.annotation().is_some()) {
+						return Err(ErrorCode::InvalidAnnotation
+							.located(source, element.metadata().clone()));
+					}
+
+					Ok(Expanded::Object(Indexed::new(
+						Object::List(result, Some(element.metadata().clone())),
+						index,
+					)))
 				} else if let Some(set_entry) = set_entry {
 					// Set objects.
 					for ExpandedEntry(key, expanded_key, _) in expanded_entries {
@@ -376,23 +469,39 @@ where
 					.await
 				} else if let Some(value_entry) = value_entry {
 					// Value objects.
-					if let Some(value) = expand_value(
+					match expand_value(
 						source,
 						input_type,
 						type_scoped_context,
 						expanded_entries,
 						&*value_entry,
+						&options,
 						warnings,
-					)
-					.map_err(|e| e.located(source, value_entry.metadata().clone()))?
-					{
-						Ok(Expanded::Object(value))
-					} else {
-						Ok(Expanded::Null)
+					) {
+						Ok(Some(mut value)) => {
+							value.set_metadata(Some(element.metadata().clone()));
+							value.set_annotation(annotation);
+
+							match options.language.apply(value) {
+								Some(value) => Ok(Expanded::Object(value)),
+								None => Ok(Expanded::Null),
+							}
+						}
+						Ok(None) => Ok(Expanded::Null),
+						Err(e) if options.recovers(e.code()) => {
+							// In lenient mode, drop the invalid value object.
+							warnings.push(Loc::new(
+								Warning::DroppedEntry(e.code()),
+								source,
+								value_entry.metadata().clone(),
+							));
+							Ok(Expanded::Null)
+						}
+						Err(e) => Err(e.located(source, value_entry.metadata().clone())),
 					}
 				} else {
 					// Node objects.
-					if let Some(result) = expand_node(
+					if let Some(mut result) = expand_node(
 						active_context.as_ref(),
 						type_scoped_context,
 						active_property,
@@ -404,7 +513,14 @@ where
 					)
 					.await?
 					{
-						Ok(result.cast::<Object<J, T>>().into())
+						result.metadata = Some(element.metadata().clone());
+						for (key, _, value) in custom_keywords {
+							result.insert_custom_keyword(key, value);
+						}
+
+						let mut result = result.cast::<Object<J, T>>();
+						result.set_annotation(annotation);
+						Ok(result.into())
 					} else {
 						Ok(Expanded::Null)
 					}
@@ -425,41 +541,39 @@ where
 				// Context Processing algorithm, passing `active_context`, `property_scoped_context` as
 				// local context, and `base_url` from the term definition for `active_property` in
 				// `active context`.
-				let active_context = if let Some(property_scoped_context) = property_scoped_context
-				{
-					// FIXME it is unclear what we should use as `base_url` if there is no term definition for `active_context`.
-					let base_url =
-						active_context
-							.get_opt(active_property.id())
-							.and_then(|definition| {
-								definition
-									.base_url
-									.as_ref()
-									.map(|base_url| base_url.as_iri())
-							});
-
-					let result = property_scoped_context
-						.process_with(active_context, loader, base_url, options.into())
-						.await
-						.map_err(|e| e.with_metadata(active_property.metadata().unwrap().clone()))?
-						.into_inner();
-					Mown::Owned(result)
-				} else {
-					Mown::Borrowed(active_context)
-				};
+				let active_context =
+					if let Some(property_scoped_definition) = property_scoped_definition {
+						let result = property_scoped_definition
+							.process_scoped_context(
+								active_context,
+								loader,
+								ProcessingOptions::from(&options),
+							)
+							.await
+							.map_err(|e| {
+								e.with_metadata(active_property.metadata().unwrap().clone())
+							})?;
+						Mown::Owned(result)
+					} else {
+						Mown::Borrowed(active_context)
+					};
 
 				// Return the result of the Value Expansion algorithm, passing the `active_context`,
 				// `active_property`, and `element` as value.
-				return Ok(Expanded::Object(
-					expand_literal(
-						source,
-						active_context.as_ref(),
-						active_property,
-						LiteralValue::Given(element),
-						warnings,
-					)
-					.map_err(|e| e.located(source, element.metadata().clone()))?,
-				));
+				let result = expand_literal(
+					source,
+					active_context.as_ref(),
+					active_property,
+					LiteralValue::Given(element),
+					&options,
+					warnings,
+				)
+				.map_err(|e| e.located(source, element.metadata().clone()))?;
+
+				return match options.language.apply(result) {
+					Some(result) => Ok(Expanded::Object(result)),
+					None => Ok(Expanded::Null),
+				};
 			}
 		}
 	}
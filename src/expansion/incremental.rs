@@ -0,0 +1,261 @@
+use super::{
+	expand_element, filter_top_level_item, into_top_level, ActiveProperty, JsonExpand, Options,
+};
+use crate::util::collections::HashSet;
+use crate::{
+	context::{Loader, Local, ProcessingOptions},
+	ContextMut, Error, ExpandedDocument, Id, Indexed, Loc, Object, Warning,
+};
+use cc_traits::{Get, Len};
+use generic_json::{Json, ValueRef};
+use iref::IriBuf;
+
+/// Layout of the expanded document.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Layout {
+	/// The document is an array of top-level items.
+	Array,
+
+	/// The document is an object with only a `@context` and a `@graph` entry,
+	/// whose items are the top-level items.
+	Graph,
+
+	/// Any other document, considered as a single top-level item.
+	Whole,
+}
+
+/// Incremental expansion state.
+///
+/// Remembers the active context and options used to expand a document,
+/// so that the expansion of a modified document can be updated by only
+/// expanding again the top-level items that changed,
+/// typically after applying a [JSON patch](https://tools.ietf.org/html/rfc6902).
+///
+/// The top-level items of a document are the items of the document if it is an array,
+/// the items of its `@graph` entry if it is an object with only a `@context` and a `@graph`
+/// entry (keyword aliases are not recognized here), or else the document itself.
+///
+/// The result of an update is the same as expanding the whole modified document, except that:
+///   - a single unnamed graph is only unwrapped (as done at the top level of the document)
+///     when the whole document is one item;
+///   - identical objects expanded from different items are the same element of the
+///     expanded document, so removing one removes the other;
+///   - the metadata of unchanged objects is not updated, even if their position
+///     in the modified document has changed.
+///
+/// A change to the `@context` entry of the document, or to the document layout itself,
+/// requires creating a new state and expanding the whole document again.
+///
+/// ```
+/// use iref::IriBuf;
+/// use json_ld::{context, expansion::{Incremental, Options}, Document, NoLoader};
+/// use serde_json::{json, Value};
+///
+/// # async_std::task::block_on(async {
+/// let mut loader = NoLoader::<Value>::new();
+/// let doc = json!([
+///   { "@id": "http://example.org/alice", "http://xmlns.com/foaf/0.1/name": "Alice" },
+///   { "@id": "http://example.org/bob", "http://xmlns.com/foaf/0.1/name": "Bob" }
+/// ]);
+/// let mut expanded = doc.expand::<context::Json<Value, IriBuf>, _>(&mut loader).await.unwrap();
+///
+/// let context = context::Json::<Value, IriBuf>::new(None);
+/// let incremental = Incremental::new(&context, &doc, None, &mut loader, Options::default())
+///   .await
+///   .unwrap();
+///
+/// // Replace the name of Bob.
+/// assert_eq!(incremental.affected_item("/1/http:~1~1xmlns.com~1foaf~10.1~1name"), Some(1));
+/// let bob = json!({
+///   "@id": "http://example.org/bob",
+///   "http://xmlns.com/foaf/0.1/name": "Robert"
+/// });
+/// incremental
+///   .update(&mut expanded, Some(&doc[1]), Some(&bob), &mut loader, &mut Vec::new())
+///   .await
+///   .unwrap();
+///
+/// let patched = json!([doc[0].clone(), bob]);
+/// let expected = patched.expand::<context::Json<Value, IriBuf>, _>(&mut loader).await.unwrap();
+/// assert_eq!(expanded.len(), expected.len());
+/// assert!(expected.iter().all(|object| expanded.iter().any(|o| o == object)));
+/// # })
+/// ```
+pub struct Incremental<C> {
+	/// Active context of the top-level items.
+	context: C,
+
+	/// Base URL of the document.
+	base_url: Option<IriBuf>,
+
+	/// Expansion options.
+	options: Options,
+
+	/// Layout of the document.
+	layout: Layout,
+}
+
+impl<C> Incremental<C> {
+	/// Prepares the incremental expansion of the given document.
+	///
+	/// The active context, base URL and options must be the ones used to expand
+	/// the whole document in the first place.
+	/// The top-level `@context` entry of the document, if any,
+	/// is processed once and for all.
+	pub async fn new<'a, J: JsonExpand, T: Id, L: Loader>(
+		active_context: &'a C,
+		document: &'a J,
+		base_url: Option<IriBuf>,
+		loader: &'a mut L,
+		options: Options,
+	) -> Result<Self, Loc<Error, J::MetaData>>
+	where
+		T: Send + Sync,
+		C: ContextMut<T> + Send + Sync,
+		C::LocalContext: From<L::Output> + From<J>,
+		L: Send + Sync,
+		L::Output: Into<J>,
+	{
+		let base = base_url.as_ref().map(|url| url.as_iri());
+		let mut context = active_context.clone();
+
+		let layout = match document.as_value_ref() {
+			ValueRef::Array(_) => Layout::Array,
+			ValueRef::Object(obj) if obj.len() == 2 && obj.get("@graph").is_some() => {
+				match obj.get("@context") {
+					Some(local_context) => {
+						context = local_context
+							.process_with(&context, loader, base, ProcessingOptions::from(&options))
+							.await?
+							.into_inner();
+						Layout::Graph
+					}
+					None => Layout::Whole,
+				}
+			}
+			_ => Layout::Whole,
+		};
+
+		Ok(Self {
+			context,
+			base_url,
+			options,
+			layout,
+		})
+	}
+
+	/// Returns the active context used to expand the top-level items.
+	#[inline(always)]
+	pub fn context(&self) -> &C {
+		&self.context
+	}
+
+	/// Returns the expansion options.
+	#[inline(always)]
+	pub fn options(&self) -> &Options {
+		&self.options
+	}
+
+	/// Returns the index of the top-level item affected by a change at the given
+	/// [JSON pointer](https://tools.ietf.org/html/rfc6901),
+	/// such as the `path` of a JSON patch operation.
+	///
+	/// If the whole document is a single item, its index is `0`.
+	/// Returns `None` if the change affects the document layout or its `@context` entry,
+	/// or if the pointer designates the end of the items array (`-`),
+	/// in which case the whole document must be expanded again
+	/// (or the new item added with [`update`](Self::update)).
+	pub fn affected_item(&self, pointer: &str) -> Option<usize> {
+		let mut segments = pointer.strip_prefix('/')?.split('/');
+		match self.layout {
+			Layout::Array => segments.next()?.parse().ok(),
+			Layout::Graph => match segments.next()? {
+				"@graph" => segments.next()?.parse().ok(),
+				_ => None,
+			},
+			Layout::Whole => Some(0),
+		}
+	}
+
+	/// Updates the given expanded document after a top-level item has been replaced.
+	///
+	/// The objects expanded from the `old` item, if any, are removed from the document,
+	/// and the objects expanded from the `new` item, if any, are inserted.
+	/// Adding (resp. removing) an item is done by passing `None` as `old` (resp. `new`).
+	///
+	/// The [resource limits](Options::limits) apply to each item individually.
+	/// The document is left untouched if the expansion of the new item fails.
+	/// Only the warnings of the new item are reported.
+	pub async fn update<J: JsonExpand, T: Id, L: Loader>(
+		&self,
+		expanded: &mut ExpandedDocument<J, T>,
+		old: Option<&J>,
+		new: Option<&J>,
+		loader: &mut L,
+		warnings: &mut Vec<Loc<Warning, J::MetaData>>,
+	) -> Result<(), Loc<Error, J::MetaData>>
+	where
+		T: Send + Sync,
+		C: ContextMut<T> + Send + Sync,
+		C::LocalContext: From<L::Output> + From<J>,
+		L: Send + Sync,
+		L::Output: Into<J>,
+	{
+		let new = match new {
+			Some(item) => self.expand_item(item, loader, warnings).await?,
+			None => HashSet::new(),
+		};
+
+		if let Some(item) = old {
+			for object in self.expand_item(item, loader, &mut Vec::new()).await? {
+				expanded.remove(&object);
+			}
+		}
+
+		for object in new {
+			expanded.insert(object);
+		}
+
+		Ok(())
+	}
+
+	/// Expands the given top-level item.
+	async fn expand_item<'a, J: JsonExpand, T: Id, L: Loader>(
+		&'a self,
+		item: &'a J,
+		loader: &'a mut L,
+		warnings: &mut Vec<Loc<Warning, J::MetaData>>,
+	) -> Result<HashSet<Indexed<Object<J, T>>>, Loc<Error, J::MetaData>>
+	where
+		T: Send + Sync,
+		C: ContextMut<T> + Send + Sync,
+		C::LocalContext: From<L::Output> + From<J>,
+		L: Send + Sync,
+		L::Output: Into<J>,
+	{
+		let base_url = self.base_url.as_ref().map(|url| url.as_iri());
+		self.options.limits.check(item, loader.id_opt(base_url))?;
+
+		let active_property = match self.layout {
+			Layout::Graph => ActiveProperty::Some("@graph", item.metadata()),
+			_ => ActiveProperty::None,
+		};
+
+		let expanded = expand_element(
+			&self.context,
+			active_property,
+			item,
+			base_url,
+			loader,
+			self.options.clone(),
+			false,
+			warnings,
+		)
+		.await?;
+
+		match self.layout {
+			Layout::Whole => Ok(into_top_level(expanded)),
+			_ => Ok(expanded.into_iter().filter(filter_top_level_item).collect()),
+		}
+	}
+}
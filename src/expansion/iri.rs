@@ -1,7 +1,8 @@
+use super::Options;
 use crate::{
 	loader,
 	syntax::{is_keyword_like, Keyword, Term},
-	BlankId, Context, Id, Loc, Reference, Warning,
+	BlankId, Context, ErrorCode, Id, Loc, Reference, Warning,
 };
 use iref::{Iri, IriRef};
 use std::convert::TryFrom;
@@ -144,6 +145,41 @@ pub fn expand_iri<T: Id, C: Context<T>, M: Clone>(
 	}
 }
 
+/// Expand the given IRI using `true` for `document_relative`,
+/// unless relative IRI resolution is forbidden by the given `options`.
+///
+/// In this case, relative IRI references are not resolved against the base IRI
+/// and a `ForbiddenRelativeIri` error is returned instead.
+pub fn expand_document_relative_iri<T: Id, C: Context<T>, M: Clone>(
+	source: Option<loader::Id>,
+	active_context: &C,
+	value: &str,
+	metadata: &M,
+	vocab: bool,
+	options: &Options,
+	warnings: &mut Vec<Loc<Warning, M>>,
+) -> Result<Term<T>, ErrorCode> {
+	let term = expand_iri(
+		source,
+		active_context,
+		value,
+		metadata,
+		!options.forbid_relative_iris,
+		vocab,
+		warnings,
+	);
+
+	if options.forbid_relative_iris {
+		if let Term::Ref(Reference::Invalid(_)) = &term {
+			if IriRef::new(value).is_ok() {
+				return Err(ErrorCode::ForbiddenRelativeIri);
+			}
+		}
+	}
+
+	Ok(term)
+}
+
 /// Build an invalid reference and emit a warning.
 fn invalid<T: Id, M: Clone>(
 	value: String,
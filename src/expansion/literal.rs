@@ -1,4 +1,4 @@
-use super::{expand_iri, node_id_of_term, ActiveProperty};
+use super::{expand_document_relative_iri, node_id_of_term, ActiveProperty, Options};
 use crate::{
 	loader, object::*, syntax::Type, Context, Error, ErrorCode, Id, Indexed, LangString, Loc,
 	Warning,
@@ -40,6 +40,7 @@ pub fn expand_literal<J: JsonHash + JsonClone, T: Id, C: Context<T>>(
 	active_context: &C,
 	active_property: ActiveProperty<J>,
 	value: LiteralValue<J>,
+	options: &Options,
 	warnings: &mut Vec<Loc<Warning, J::MetaData>>,
 ) -> Result<Indexed<Object<J, T>>, Error> {
 	let active_property_definition = active_context.get_opt(active_property.id());
@@ -58,15 +59,16 @@ pub fn expand_literal<J: JsonHash + JsonClone, T: Id, C: Context<T>>(
 		// `false` for vocab.
 		Some(Type::Id) if value.is_string() => {
 			let mut node = Node::new();
-			node.id = node_id_of_term(expand_iri(
+			node.id = node_id_of_term(expand_document_relative_iri(
 				source,
 				active_context,
 				value.as_str().unwrap(),
 				value.metadata(),
-				true,
 				false,
+				options,
 				warnings,
-			));
+			)?);
+			node.metadata = Some(value.metadata().clone());
 			Ok(Object::Node(node).into())
 		}
 
@@ -76,19 +78,22 @@ pub fn expand_literal<J: JsonHash + JsonClone, T: Id, C: Context<T>>(
 		// document relative.
 		Some(Type::Vocab) if value.is_string() => {
 			let mut node = Node::new();
-			node.id = node_id_of_term(expand_iri(
+			node.id = node_id_of_term(expand_document_relative_iri(
 				source,
 				active_context,
 				value.as_str().unwrap(),
 				value.metadata(),
 				true,
-				true,
+				options,
 				warnings,
-			));
+			)?);
+			node.metadata = Some(value.metadata().clone());
 			Ok(Object::Node(node).into())
 		}
 
 		_ => {
+			let metadata = Some(value.metadata().clone());
+
 			// Otherwise, initialize `result` to a map with an `@value` entry whose value is set to
 			// `value`.
 			let result: Literal<J> = match value {
@@ -143,11 +148,13 @@ pub fn expand_literal<J: JsonHash + JsonClone, T: Id, C: Context<T>>(
 						// If `direction` is not null, add `@direction` to result with the
 						// value `direction`.
 						return match LangString::new(str, language, direction) {
-							Ok(lang_str) => Ok(Object::Value(Value::LangString(lang_str)).into()),
-							Err(str) => Ok(Object::Value(Value::Literal(
-								Literal::String(str),
-								None,
-							))
+							Ok(lang_str) => {
+								Ok(Object::Value(Value::LangString(lang_str), metadata).into())
+							}
+							Err(str) => Ok(Object::Value(
+								Value::Literal(Literal::String(str), None),
+								metadata,
+							)
 							.into()),
 						};
 					}
@@ -162,7 +169,7 @@ pub fn expand_literal<J: JsonHash + JsonClone, T: Id, C: Context<T>>(
 				}
 			}
 
-			Ok(Object::Value(Value::Literal(result, ty)).into())
+			Ok(Object::Value(Value::Literal(result, ty), metadata).into())
 		}
 	}
 }
@@ -1,36 +1,49 @@
 //! Expansion algorithm and related types.
+use crate::util::collections::HashSet;
 use crate::{
-	context::{Loader, ProcessingOptions},
-	ContextMut, Error, Id, Indexed, Loc, Object, ProcessingMode, Warning,
+	context::{JsonContext, Loader, Local, ProcessingOptions},
+	object::{Literal, Value},
+	syntax::{is_keyword_like, ContextDefinition, Keyword},
+	util::AsAnyJson,
+	ContextMut, Error, ErrorCode, Id, Indexed, LangString, Limits, Loc, Monitor, Object,
+	ProcessingMode, Warning,
 };
 use cc_traits::{CollectionRef, KeyedRef};
 use derivative::Derivative;
-use generic_json::{Json, JsonClone, JsonHash, JsonLft, JsonSendSync};
-use iref::IriBuf;
+use generic_json::{Json, JsonBuild, JsonClone, JsonHash, JsonLft, JsonSendSync};
+use iref::{Iri, IriBuf};
+use langtag::LanguageTagBuf;
 use std::cmp::{Ord, Ordering};
-use std::collections::HashSet;
+use std::convert::TryFrom;
+use std::sync::Arc;
 
 mod array;
+mod canonical;
 mod element;
 mod expanded;
+mod incremental;
 mod iri;
 mod literal;
 mod node;
+mod stream;
 mod value;
 
 use array::*;
+pub use canonical::canonical_form;
 use element::*;
 use expanded::*;
+pub use incremental::*;
 pub(crate) use iri::*;
 use literal::*;
 use node::*;
+pub use stream::*;
 use value::*;
 
 /// JSON document that can be expanded.
 pub trait JsonExpand = JsonSendSync + JsonHash + JsonClone + JsonLft<'static>;
 
 /// Expansion options.
-#[derive(Clone, Copy, Default)]
+#[derive(Clone, Default)]
 pub struct Options {
 	/// Sets the processing mode.
 	pub processing_mode: ProcessingMode,
@@ -43,6 +56,457 @@ pub struct Options {
 	/// If set to true, input document entries are processed lexicographically.
 	/// If false, order is not considered in processing.
 	pub ordered: bool,
+
+	/// Lenient mode.
+	///
+	/// If set to true, recoverable errors (such as invalid language-tagged strings,
+	/// invalid `@id`, `@type` or `@index` values) do not abort the expansion.
+	/// Instead the offending entry is dropped and a
+	/// [`Warning::DroppedEntry`](crate::Warning::DroppedEntry) is emitted.
+	/// Conflicting indexes are detected by the node map generation, which can recover from
+	/// them with [`generate_node_map_with_warnings`](crate::flattening::generate_node_map_with_warnings).
+	///
+	/// Default is `false`.
+	pub lenient: bool,
+
+	/// Limits applied to JSON literal values (`@json`).
+	///
+	/// Default is no limit.
+	pub json_literal_limits: JsonLiteralLimits,
+
+	/// Language tag handling applied to the expanded string values.
+	///
+	/// Default is to keep language tags as they are.
+	pub language: LanguageOptions,
+
+	/// Forbids the resolution of relative IRI references against the base IRI.
+	///
+	/// If set to true, any relative IRI reference used as a node identifier
+	/// or type raises a `ForbiddenRelativeIri` error, instead of being resolved
+	/// (or kept as an invalid reference if there is no base IRI).
+	///
+	/// Default is `false`.
+	pub forbid_relative_iris: bool,
+
+	/// Resource limits applied to the input document and the remote contexts it loads.
+	///
+	/// A [`LimitExceeded`](ErrorCode::LimitExceeded) error is raised before expansion
+	/// if the input document exceeds them.
+	///
+	/// Default is no limit.
+	pub limits: Limits,
+
+	/// Extension handling the entries whose key has the form of a keyword
+	/// without being one (such as `@preserve`).
+	///
+	/// By default, those entries are dropped with a
+	/// [`KeywordLikeValue`](crate::Warning::KeywordLikeValue) warning.
+	pub keyword_extension: Option<Arc<dyn KeywordExtension>>,
+
+	/// Enables the `@annotation` keyword of
+	/// [JSON-LD-star](https://json-ld.github.io/json-ld-star/).
+	///
+	/// If set to `false`, `@annotation` entries are dropped with a
+	/// [`KeywordLikeValue`](crate::Warning::KeywordLikeValue) warning.
+	///
+	/// Default is `false`.
+	pub annotation: bool,
+
+	/// Progress monitor, also used to cancel the expansion.
+	///
+	/// The monitor is advanced for every expanded JSON value,
+	/// and the expansion fails with a [`Cancelled`](ErrorCode::Cancelled) error
+	/// once it is cancelled.
+	///
+	/// Default is `None`.
+	pub monitor: Option<Arc<Monitor>>,
+}
+
+/// Context applied before expansion (`expandContext` option of the JSON-LD API).
+///
+/// It is processed with [`ExpandContext::process`] into the initial active context
+/// given to [`Document::expand_with`](crate::Document::expand_with)
+/// or [`Document::compact_with_expand_context`](crate::Document::compact_with_expand_context).
+///
+/// ```
+/// use iref::IriBuf;
+/// use json_ld::{context, expansion::ExpandContext, syntax::ContextDefinition, Document, NoLoader};
+/// use serde_json::Value;
+///
+/// # async_std::task::block_on(async {
+/// let mut loader = NoLoader::<Value>::new();
+/// let expand_context = ExpandContext::Definition(
+///   ContextDefinition::new().with_vocab("http://schema.org/")
+/// );
+/// let context: context::Json<Value, IriBuf> = expand_context
+///   .process::<Value, _, _, _>(&context::Json::new(None), &mut loader, None, (), Default::default())
+///   .await
+///   .unwrap();
+///
+/// let doc: Value = serde_json::from_str(r#"{ "name": "Alice" }"#).unwrap();
+/// let expanded = doc
+///   .expand_with(None, &context, &mut loader, Default::default())
+///   .await
+///   .unwrap();
+/// assert_eq!(expanded.len(), 1);
+/// # })
+/// ```
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum ExpandContext {
+	/// IRI of a context document, loaded with the document loader.
+	///
+	/// As for any remote context, the `@context` entry of the document is used.
+	Iri(IriBuf),
+
+	/// Context definition.
+	Definition(ContextDefinition),
+}
+
+impl<K: JsonBuild> AsAnyJson<K> for ExpandContext {
+	fn as_json_with(&self, meta: K::MetaData) -> K {
+		match self {
+			Self::Iri(iri) => iri.as_str().as_json_with(meta),
+			Self::Definition(definition) => definition.as_json_with(meta),
+		}
+	}
+}
+
+impl ExpandContext {
+	/// Processes this context on top of the given active context.
+	///
+	/// The context is first converted into a local context of type `J`
+	/// with the given metadata.
+	pub async fn process<'a, J, T: Id, C: ContextMut<T>, L: Loader>(
+		&self,
+		active_context: &'a C,
+		loader: &'a mut L,
+		base_url: Option<Iri<'a>>,
+		meta: J::MetaData,
+		options: ProcessingOptions,
+	) -> Result<C, Loc<Error, J::MetaData>>
+	where
+		J: JsonContext + JsonBuild,
+		T: Send + Sync,
+		C: Send + Sync,
+		C::LocalContext: From<L::Output> + From<J>,
+		L: Send + Sync,
+		L::Output: Into<J>,
+	{
+		let local_context: J = self.as_json_with(meta);
+		let processed = local_context
+			.process_with(active_context, loader, base_url, options)
+			.await?;
+		Ok(processed.into_inner())
+	}
+}
+
+/// Action taken by a [`KeywordExtension`] on a keyword-like entry.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum KeywordAction {
+	/// Default behavior: the entry is dropped with a warning.
+	Default,
+
+	/// The entry is silently dropped.
+	Ignore,
+
+	/// The entry is kept as is in the expanded node object,
+	/// as a custom keyword entry (see [`Node::custom_keywords`](crate::Node::custom_keywords)).
+	///
+	/// Custom keyword entries are not part of the graph described by the document.
+	/// On value, list and set objects, the entry is dropped with a warning.
+	Keep,
+}
+
+/// Expansion extension point for custom keywords.
+///
+/// Invoked by the expansion algorithm on every entry whose key has the form of a keyword
+/// (`@` followed by letters) without being a JSON-LD keyword.
+/// It is implemented by any `Fn(&str) -> KeywordAction` function.
+///
+/// ```
+/// use iref::IriBuf;
+/// use json_ld::{context, expansion::{KeywordAction, Options}, Document, NoLoader};
+/// use serde_json::{json, Value};
+/// use std::sync::Arc;
+///
+/// # async_std::task::block_on(async {
+/// let options = Options {
+///   keyword_extension: Some(Arc::new(|keyword: &str| match keyword {
+///     "@preserve" => KeywordAction::Keep,
+///     _ => KeywordAction::Default,
+///   })),
+///   ..Options::default()
+/// };
+///
+/// let doc = json!({
+///   "@id": "https://example.com/alice",
+///   "@preserve": { "name": "Alice" },
+///   "https://schema.org/name": "Alice"
+/// });
+///
+/// let mut loader = NoLoader::<Value>::new();
+/// let expanded = doc
+///   .expand_with(None, &context::Json::<Value, IriBuf>::new(None), &mut loader, options)
+///   .await
+///   .unwrap();
+///
+/// let node = expanded.iter().next().unwrap().as_node().unwrap();
+/// assert_eq!(node.custom_keyword("@preserve"), Some(&json!({ "name": "Alice" })));
+/// assert_eq!(node.properties().len(), 1);
+/// # })
+/// ```
+pub trait KeywordExtension: Send + Sync {
+	/// Returns the action to take on an entry with the given keyword-like key.
+	fn keyword_action(&self, keyword: &str) -> KeywordAction;
+}
+
+impl<F: Send + Sync + Fn(&str) -> KeywordAction> KeywordExtension for F {
+	#[inline(always)]
+	fn keyword_action(&self, keyword: &str) -> KeywordAction {
+		self(keyword)
+	}
+}
+
+impl Options {
+	/// Returns the action to take on the entry with the given key.
+	///
+	/// This is [`KeywordAction::Default`] unless the key has the form of a keyword without
+	/// being one, and the keyword extension handles it.
+	pub(crate) fn keyword_action(&self, key: &str) -> KeywordAction {
+		match &self.keyword_extension {
+			Some(extension) if is_keyword_like(key) && Keyword::try_from(key).is_err() => {
+				extension.keyword_action(key)
+			}
+			_ => KeywordAction::Default,
+		}
+	}
+
+	/// Checks if the given error can be recovered from in lenient mode.
+	pub(crate) fn recovers(&self, code: ErrorCode) -> bool {
+		self.lenient
+			&& matches!(
+				code,
+				ErrorCode::InvalidLanguageTaggedString
+					| ErrorCode::InvalidLanguageTaggedValue
+					| ErrorCode::InvalidLanguageMapValue
+					| ErrorCode::InvalidBaseDirection
+					| ErrorCode::InvalidTypedValue
+					| ErrorCode::InvalidTypeValue
+					| ErrorCode::InvalidIdValue
+					| ErrorCode::InvalidIndexValue
+			)
+	}
+}
+
+/// Resource limits applied to JSON literal values.
+///
+/// JSON literals (values typed with `@json`) embed arbitrary JSON
+/// that is copied as is in the expanded document.
+/// Those limits can be used to reject unreasonably large literals.
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub struct JsonLiteralLimits {
+	/// Maximum nesting depth of arrays and objects.
+	///
+	/// A scalar value has depth 0.
+	pub max_depth: Option<usize>,
+
+	/// Maximum size of the literal.
+	///
+	/// The size is the number of bytes of every string and object key,
+	/// plus one for each value.
+	pub max_size: Option<usize>,
+}
+
+impl JsonLiteralLimits {
+	/// Checks that the given JSON literal value respects the limits.
+	///
+	/// Returns a `JsonLiteralLimitExceeded` error otherwise.
+	pub fn check<J: Json>(&self, value: &J) -> Result<(), ErrorCode> {
+		if self.max_depth.is_some() || self.max_size.is_some() {
+			let mut size = 0;
+			self.check_value(value, 0, &mut size)
+		} else {
+			Ok(())
+		}
+	}
+
+	fn check_value<J: Json>(
+		&self,
+		value: &J,
+		depth: usize,
+		size: &mut usize,
+	) -> Result<(), ErrorCode> {
+		use cc_traits::{Iter, MapIter};
+		use generic_json::ValueRef;
+
+		*size += 1;
+		match value.as_value_ref() {
+			ValueRef::String(s) => {
+				let s: &str = s.as_ref();
+				*size += s.len()
+			}
+			ValueRef::Array(ary) => {
+				self.check_depth(depth + 1)?;
+				for item in ary.iter() {
+					self.check_value(&*item, depth + 1, size)?
+				}
+			}
+			ValueRef::Object(obj) => {
+				self.check_depth(depth + 1)?;
+				for (key, item) in obj.iter() {
+					let key: &str = key.as_ref();
+					*size += key.len();
+					self.check_value(&*item, depth + 1, size)?
+				}
+			}
+			_ => (),
+		}
+
+		match self.max_size {
+			Some(max_size) if *size > max_size => Err(ErrorCode::JsonLiteralLimitExceeded),
+			_ => Ok(()),
+		}
+	}
+
+	fn check_depth(&self, depth: usize) -> Result<(), ErrorCode> {
+		match self.max_depth {
+			Some(max_depth) if depth > max_depth => Err(ErrorCode::JsonLiteralLimitExceeded),
+			_ => Ok(()),
+		}
+	}
+}
+
+/// Language tag handling options.
+///
+/// Those options are applied to every string value produced by the expansion,
+/// whether it comes from a value object, a plain string or a language map,
+/// so that consumers of multilingual datasets can extract a single language slice.
+///
+/// ```
+/// use json_ld::expansion::{LanguageOptions, Options};
+///
+/// let options = Options {
+///   language: LanguageOptions {
+///     lowercase: true,
+///     allowed: Some(&["en", "fr"]),
+///     default: Some("en"),
+///   },
+///   ..Options::default()
+/// };
+///
+/// assert!(options.language.allows("en-US"));
+/// assert!(!options.language.allows("de"));
+/// ```
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub struct LanguageOptions {
+	/// Lowercases language tags.
+	///
+	/// Language tags are case-insensitive.
+	///
+	/// Default is `false`.
+	pub lowercase: bool,
+
+	/// Language ranges of the values to keep.
+	///
+	/// Language-tagged strings whose language tag does not match any of those ranges
+	/// are dropped.
+	/// Matching follows the basic filtering scheme of
+	/// [RFC 4647](https://www.rfc-editor.org/rfc/rfc4647):
+	/// a range matches a tag if, ignoring case, it is equal to the tag or to a prefix of the tag
+	/// followed by `-`. The `*` range matches any tag.
+	/// Strings without language tag are always kept.
+	///
+	/// Default is `None`, keeping every value.
+	pub allowed: Option<&'static [&'static str]>,
+
+	/// Language tag given to the strings without language tag.
+	///
+	/// Unlike the default language of a context, it also applies to the strings
+	/// of value objects and to the strings explicitly left untagged with `"@language": null`.
+	/// Typed values are left unchanged.
+	///
+	/// Default is `None`.
+	pub default: Option<&'static str>,
+}
+
+impl LanguageOptions {
+	/// Checks if the given language tag matches one of the [`allowed`](Self::allowed) ranges.
+	pub fn allows(&self, language: &str) -> bool {
+		match self.allowed {
+			Some(ranges) => ranges.iter().any(|range| {
+				*range == "*"
+					|| match language.get(..range.len()) {
+						Some(prefix) => {
+							prefix.eq_ignore_ascii_case(range)
+								&& matches!(language.as_bytes().get(range.len()), None | Some(b'-'))
+						}
+						None => false,
+					}
+			}),
+			None => true,
+		}
+	}
+
+	/// Applies the options to the given expanded object.
+	///
+	/// Returns `None` if the object must be dropped.
+	pub(crate) fn apply<J: JsonHash, T: Id>(
+		&self,
+		mut object: Indexed<Object<J, T>>,
+	) -> Option<Indexed<Object<J, T>>> {
+		if *self == Self::default() {
+			return Some(object);
+		}
+
+		let annotation = object.take_annotation();
+		let mut result = match object.into_parts() {
+			(Object::Value(value, metadata), index) => {
+				let value = self.apply_value(value)?;
+				Indexed::new(Object::Value(value, metadata), index)
+			}
+			(object, index) => Indexed::new(object, index),
+		};
+
+		result.set_annotation(annotation);
+		Some(result)
+	}
+
+	fn apply_value<J: JsonHash, T: Id>(&self, value: Value<J, T>) -> Option<Value<J, T>> {
+		let value = match (self.default, value) {
+			(Some(default), Value::Literal(Literal::String(s), None)) => {
+				let language = match LanguageTagBuf::parse_copy(default) {
+					Ok(tag) => tag.into(),
+					Err(_) => default.to_string().into(),
+				};
+
+				match LangString::new(s, Some(language), None) {
+					Ok(s) => Value::LangString(s),
+					Err(s) => Value::Literal(Literal::String(s), None),
+				}
+			}
+			(_, value) => value,
+		};
+
+		match value {
+			Value::LangString(mut s) => {
+				if self.lowercase {
+					s.normalize_language()
+				}
+
+				let allowed = match s.language() {
+					Some(language) => self.allows(language.as_str()),
+					None => true,
+				};
+
+				if allowed {
+					Some(Value::LangString(s))
+				} else {
+					None
+				}
+			}
+			value => Some(value),
+		}
+	}
 }
 
 /// Key expansion policy.
@@ -98,20 +562,29 @@ impl Default for Policy {
 	}
 }
 
-impl From<Options> for ProcessingOptions {
-	fn from(options: Options) -> ProcessingOptions {
+impl<'a> From<&'a Options> for ProcessingOptions {
+	fn from(options: &'a Options) -> ProcessingOptions {
 		ProcessingOptions {
 			processing_mode: options.processing_mode,
+			limits: options.limits,
 			..Default::default()
 		}
 	}
 }
 
+impl From<Options> for ProcessingOptions {
+	#[inline(always)]
+	fn from(options: Options) -> ProcessingOptions {
+		Self::from(&options)
+	}
+}
+
 impl From<crate::compaction::Options> for Options {
 	fn from(options: crate::compaction::Options) -> Options {
 		Options {
 			processing_mode: options.processing_mode,
 			ordered: options.ordered,
+			annotation: options.annotation,
 			..Options::default()
 		}
 	}
@@ -196,7 +669,7 @@ where
 
 fn filter_top_level_item<J: JsonHash, T: Id>(item: &Indexed<Object<J, T>>) -> bool {
 	// Remove dangling values.
-	!matches!(item.inner(), Object::Value(_))
+	!matches!(item.inner(), Object::Value(..))
 }
 
 pub enum ActiveProperty<'a, J: Json> {
@@ -270,6 +743,8 @@ where
 	L::Output: Into<J>,
 {
 	let base_url = base_url.as_ref().map(|url| url.as_iri());
+	options.limits.check(document, loader.id_opt(base_url))?;
+
 	let expanded = expand_element(
 		active_context,
 		ActiveProperty::None,
@@ -281,18 +756,25 @@ where
 		warnings,
 	)
 	.await?;
+	Ok(into_top_level(expanded))
+}
+
+/// Turns the expansion result of a whole document into its top-level objects.
+///
+/// A single unnamed graph is unwrapped, and free-floating values are dropped.
+fn into_top_level<J: JsonHash, T: Id>(expanded: Expanded<J, T>) -> HashSet<Indexed<Object<J, T>>> {
 	if expanded.len() == 1 {
 		match expanded.into_iter().next().unwrap().into_unnamed_graph() {
-			Ok(graph) => Ok(graph),
+			Ok(graph) => graph,
 			Err(obj) => {
 				let mut set = HashSet::new();
 				if filter_top_level_item(&obj) {
 					set.insert(obj);
 				}
-				Ok(set)
+				set
 			}
 		}
 	} else {
-		Ok(expanded.into_iter().filter(filter_top_level_item).collect())
+		expanded.into_iter().filter(filter_top_level_item).collect()
 	}
 }
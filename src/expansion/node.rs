@@ -1,21 +1,23 @@
 use super::{
-	expand_element, expand_iri, expand_literal, filter_top_level_item, ActiveProperty, Entry,
-	Expanded, ExpandedEntry, JsonExpand, LiteralValue, Options, Policy,
+	expand_document_relative_iri, expand_element, expand_iri, expand_literal,
+	filter_top_level_item, ActiveProperty, Entry, Expanded, ExpandedEntry, JsonExpand,
+	LiteralValue, Options, Policy,
 };
 use crate::util::as_array;
+use crate::util::collections::HashSet;
+use crate::util::future::{BoxFuture, FutureExt};
 use crate::{
-	context::{ContextMut, Loader, Local, ProcessingOptions},
+	context::{ContextMut, Loader, ProcessingOptions},
 	object::*,
 	syntax::{Container, ContainerType, Keyword, Term, Type},
 	Error, ErrorCode, Id, Indexed, LangString, Loc, ProcessingMode, Reference, Warning,
 };
 use cc_traits::{Len, MapIter};
-use futures::future::{BoxFuture, FutureExt};
 use generic_json::{Json, Key, ValueRef};
 use iref::Iri;
 use langtag::LanguageTagBuf;
 use mown::Mown;
-use std::{collections::HashSet, convert::TryInto};
+use std::convert::TryInto;
 
 /// Convert a term to a node id, if possible.
 /// Return `None` if the term is `null`.
@@ -179,14 +181,23 @@ where
 								// Otherwise, set `expanded_value` to the result of IRI
 								// expanding value using true for document relative and
 								// false for vocab.
-								result.id = node_id_of_term(expand_iri(
+								result.id = node_id_of_term(
+									expand_document_relative_iri(
+										source,
+										active_context,
+										str_value,
+										value.metadata(),
+										false,
+										&options,
+										warnings,
+									)
+									.map_err(|e| e.located(source, value.metadata().clone()))?,
+								)
+							} else if options.recovers(ErrorCode::InvalidIdValue) {
+								warnings.push(Loc::new(
+									Warning::DroppedEntry(ErrorCode::InvalidIdValue),
 									source,
-									active_context,
-									str_value,
-									value.metadata(),
-									true,
-									false,
-									warnings,
+									value.metadata().clone(),
 								))
 							} else {
 								return Err(ErrorCode::InvalidIdValue
@@ -204,22 +215,35 @@ where
 							// context, and true for document relative.
 							for ty in value {
 								if let Some(str_ty) = ty.as_str() {
-									if let Ok(ty) = expand_iri(
+									let expanded_ty = expand_document_relative_iri(
 										source,
 										type_scoped_context,
 										str_ty,
 										ty.metadata(),
 										true,
-										true,
+										&options,
 										warnings,
 									)
-									.try_into()
-									{
+									.map_err(|e| e.located(source, ty.metadata().clone()))?;
+
+									if let Ok(ty) = expanded_ty.try_into() {
 										result.types.push(ty)
+									} else if options.recovers(ErrorCode::InvalidTypeValue) {
+										warnings.push(Loc::new(
+											Warning::DroppedEntry(ErrorCode::InvalidTypeValue),
+											source,
+											ty.metadata().clone(),
+										))
 									} else {
 										return Err(ErrorCode::InvalidTypeValue
 											.located(source, ty.metadata().clone()));
 									}
+								} else if options.recovers(ErrorCode::InvalidTypeValue) {
+									warnings.push(Loc::new(
+										Warning::DroppedEntry(ErrorCode::InvalidTypeValue),
+										source,
+										ty.metadata().clone(),
+									))
 								} else {
 									return Err(ErrorCode::InvalidTypeValue
 										.located(source, ty.metadata().clone()));
@@ -239,7 +263,7 @@ where
 								&*value,
 								base_url,
 								loader,
-								options,
+								options.clone(),
 								false,
 								warnings,
 							)
@@ -269,7 +293,7 @@ where
 								&*value,
 								base_url,
 								loader,
-								options,
+								options.clone(),
 								false,
 								warnings,
 							)
@@ -301,6 +325,12 @@ where
 						Keyword::Index => {
 							if let Some(value) = value.as_str() {
 								result.set_index(Some(value.to_string()))
+							} else if options.recovers(ErrorCode::InvalidIndexValue) {
+								warnings.push(Loc::new(
+									Warning::DroppedEntry(ErrorCode::InvalidIndexValue),
+									source,
+									value.metadata().clone(),
+								))
 							} else {
 								// If value is not a string, an invalid @index value
 								// error has been detected and processing is aborted.
@@ -356,7 +386,7 @@ where
 												&*reverse_value,
 												base_url,
 												loader,
-												options,
+												options.clone(),
 												false,
 												warnings,
 											)
@@ -419,42 +449,37 @@ where
 						}
 						// If expanded property is @nest
 						Keyword::Nest => {
+							// `@nest` is not a keyword in json-ld-1.0,
+							// continue with the next key from element.
+							if options.processing_mode == ProcessingMode::JsonLd1_0 {
+								continue;
+							}
+
 							let nesting_key = key;
 							// Recursively repeat steps 3, 8, 13, and 14 using `nesting_key` for active property,
 							// and nested value for element.
 							let (value, _) = as_array(&*value);
 							for nested_value in value {
 								// Step 3 again.
-								let mut property_scoped_base_url = None;
-								let property_scoped_context =
-									match active_context.get(nesting_key.as_ref()) {
-										Some(definition) => {
-											if let Some(base_url) = &definition.base_url {
-												property_scoped_base_url = Some(base_url.as_iri());
-											}
-
-											definition.context.as_ref()
-										}
-										None => None,
-									};
+								let property_scoped_definition = active_context
+									.get(nesting_key.as_ref())
+									.filter(|definition| definition.context.is_some());
 
 								// Step 8 again.
-								let active_context = match property_scoped_context {
-									Some(property_scoped_context) => {
-										let options: ProcessingOptions = options.into();
+								let active_context = match property_scoped_definition {
+									Some(property_scoped_definition) => {
+										let options = ProcessingOptions::from(&options);
 										Mown::Owned(
-											property_scoped_context
-												.process_with(
+											property_scoped_definition
+												.process_scoped_context(
 													active_context,
 													loader,
-													property_scoped_base_url,
 													options.with_override(),
 												)
 												.await
 												.map_err(|e| {
 													e.with_metadata(nesting_key.metadata().clone())
-												})?
-												.into_inner(),
+												})?,
 										)
 									}
 									None => Mown::Borrowed(active_context),
@@ -496,7 +521,7 @@ where
 											nested_expanded_entries.collect(),
 											base_url,
 											loader,
-											options,
+											options.clone(),
 											warnings,
 										)
 										.await?;
@@ -548,7 +573,17 @@ where
 					}
 
 					let mut expanded_value = if is_json {
-						Expanded::Object(Object::Value(Value::Json((*value).clone())).into())
+						if let Err(e) = options.json_literal_limits.check(&*value) {
+							return Err(e.located(source, value.metadata().clone()));
+						}
+
+						Expanded::Object(
+							Object::Value(
+								Value::Json((*value).clone()),
+								Some(value.metadata().clone()),
+							)
+							.into(),
+						)
 					} else {
 						match value.as_object() {
 							Some(value) if container_mapping.contains(ContainerType::Language) => {
@@ -589,6 +624,7 @@ where
 
 									// For each item in language value:
 									for item in language_value {
+										let item_metadata = item.metadata();
 										match item.as_value_ref() {
 											// If item is null, continue to the next entry in
 											// language value.
@@ -627,7 +663,7 @@ where
 												// initialize a new map v consisting of two
 												// key-value pairs: (@value-item) and
 												// (@language-language).
-												if let Ok(v) = LangString::new(
+												let v = match LangString::new(
 													LiteralString::Expanded(item.clone()),
 													language,
 													direction,
@@ -636,31 +672,38 @@ where
 													// according to section 2.2.9 of [BCP47],
 													// processors SHOULD issue a warning.
 													// TODO warning
+													Ok(v) => Value::LangString(v),
+													Err(v) => {
+														Value::Literal(Literal::String(v), None)
+													}
+												};
 
-													// Append v to expanded value.
-													expanded_value.push(
-														Object::Value(Value::LangString(v)).into(),
-													)
-												} else {
-													expanded_value.push(
-														Object::Value(Value::Literal(
-															Literal::String(
-																LiteralString::Expanded(
-																	item.clone(),
-																),
-															),
-															None,
-														))
+												// Append v to expanded value.
+												if let Some(v) = options.language.apply(
+													Object::Value(v, Some(item_metadata.clone()))
 														.into(),
-													)
+												) {
+													expanded_value.push(v)
 												}
 											}
 											_ => {
 												// item must be a string, otherwise an
 												// invalid language map value error has
 												// been detected and processing is aborted.
-												return Err(ErrorCode::InvalidLanguageMapValue
-													.located(source, item.metadata().clone()));
+												if options
+													.recovers(ErrorCode::InvalidLanguageMapValue)
+												{
+													warnings.push(Loc::new(
+														Warning::DroppedEntry(
+															ErrorCode::InvalidLanguageMapValue,
+														),
+														source,
+														item.metadata().clone(),
+													))
+												} else {
+													return Err(ErrorCode::InvalidLanguageMapValue
+														.located(source, item.metadata().clone()));
+												}
 											}
 										}
 									}
@@ -681,15 +724,9 @@ where
 
 								// Initialize `index_key` to the key's index mapping in
 								// `active_context`, or @index, if it does not exist.
-								let index_key = if let Some(key_definition) = key_definition {
-									if let Some(index) = &key_definition.index {
-										index.as_str()
-									} else {
-										"@index"
-									}
-								} else {
-									"@index"
-								};
+								let index_key = key_definition
+									.map(|key_definition| key_definition.index_key())
+									.unwrap_or("@index");
 
 								// For each key-value pair index-index value in value,
 								// ordered lexicographically by index if ordered is true:
@@ -729,26 +766,20 @@ where
 										if let Some(index_definition) =
 											map_context.get(index.as_ref())
 										{
-											if let Some(local_context) = &index_definition.context {
-												let base_url = index_definition
-													.base_url
-													.as_ref()
-													.map(|url| url.as_iri());
+											if index_definition.context.is_some() {
 												map_context = Mown::Owned(
-													local_context
-														.process_with(
+													index_definition
+														.process_scoped_context(
 															map_context.as_ref(),
 															loader,
-															base_url,
-															options.into(),
+															ProcessingOptions::from(&options),
 														)
 														.await
 														.map_err(|e| {
 															e.with_metadata(
 																index.metadata().clone(),
 															)
-														})?
-														.into_inner(),
+														})?,
 												)
 											}
 										}
@@ -788,7 +819,7 @@ where
 										&*index_value,
 										base_url,
 										loader,
-										options,
+										options.clone(),
 										true,
 										warnings,
 									)
@@ -814,7 +845,6 @@ where
 											// If `container_mapping` includes @index,
 											// index key is not @index, and expanded index is
 											// not @none:
-											// TODO the @none part.
 											if container_mapping.contains(ContainerType::Index)
 												&& index_key != "@index"
 											{
@@ -833,6 +863,7 @@ where
 														(&**index).into(),
 														index.metadata().clone(),
 													),
+													&options,
 													warnings,
 												)
 												.map_err(|e| {
@@ -854,10 +885,13 @@ where
 													_ => continue,
 												};
 
-												// Add the key-value pair (expanded index
-												// key-index property values) to item.
+												// Initialize index property values to the
+												// concatenation of re-expanded index with any
+												// existing values of expanded index key in
+												// item, and add the key-value pair (expanded
+												// index key-index property values) to item.
 												if let Object::Node(ref mut node) = *item {
-													node.insert(
+													node.properties.insert_front(
 														expanded_index_key,
 														re_expanded_index,
 													);
@@ -893,15 +927,23 @@ where
 												// result of IRI expanding index using true for
 												// document relative and false for vocab.
 												if let Object::Node(ref mut node) = *item {
-													node.id = node_id_of_term(expand_iri(
-														source,
-														active_context,
-														index.as_ref(),
-														index.metadata(),
-														true,
-														false,
-														warnings,
-													));
+													node.id = node_id_of_term(
+														expand_document_relative_iri(
+															source,
+															active_context,
+															index.as_ref(),
+															index.metadata(),
+															false,
+															&options,
+															warnings,
+														)
+														.map_err(|e| {
+															e.located(
+																source,
+																index.metadata().clone(),
+															)
+														})?,
+													);
 												}
 											} else if container_mapping
 												.contains(ContainerType::Type)
@@ -945,7 +987,7 @@ where
 									&*value,
 									base_url,
 									loader,
-									options,
+									options.clone(),
 									false,
 									warnings,
 								)
@@ -963,7 +1005,11 @@ where
 					if container_mapping.contains(ContainerType::List) && !expanded_value.is_list()
 					{
 						expanded_value = Expanded::Object(
-							Object::List(expanded_value.into_iter().collect()).into(),
+							Object::List(
+								expanded_value.into_iter().collect(),
+								Some(value.metadata().clone()),
+							)
+							.into(),
 						);
 					}
 
@@ -1010,7 +1056,8 @@ where
 							// Otherwise, key is not a reverse property use add value
 							// to add expanded value to the expanded property entry in
 							// result using true for as array.
-							result.insert_all(prop, expanded_value.into_iter());
+							result.insert_all(prop.clone(), expanded_value.into_iter());
+							result.properties.set_entry_metadata(&prop, key.metadata());
 						}
 					}
 				}
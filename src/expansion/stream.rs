@@ -0,0 +1,233 @@
+use super::{
+	expand, expand_element, into_top_level, ActiveProperty, Expanded, JsonExpand, Options,
+};
+use crate::util::collections::HashSet;
+use crate::{context::Loader, ContextMut, Error, Id, Indexed, Loc, Object, Warning};
+use generic_json::{Json, JsonBuild};
+use iref::IriBuf;
+
+/// JSON parsing event.
+///
+/// A well-formed JSON document is described by a sequence of events
+/// where each `BeginArray` (resp. `BeginObject`) is matched by an `EndArray`
+/// (resp. `EndObject`), and each object entry value is preceded by a `Key` event.
+pub enum Event<J: Json> {
+	/// `null` value.
+	Null(J::MetaData),
+
+	/// Boolean value.
+	Boolean(bool, J::MetaData),
+
+	/// Number value.
+	Number(J::Number, J::MetaData),
+
+	/// String value.
+	String(String, J::MetaData),
+
+	/// Beginning of an array.
+	BeginArray(J::MetaData),
+
+	/// End of the current array.
+	EndArray,
+
+	/// Beginning of an object.
+	BeginObject(J::MetaData),
+
+	/// Key of the next entry of the current object.
+	Key(String, J::MetaData),
+
+	/// End of the current object.
+	EndObject,
+}
+
+/// Source of JSON parsing events, such as a pull parser.
+pub trait EventSource<J: Json> {
+	/// Parsing error.
+	type Error;
+
+	/// Returns the next event, or `None` at the end of the document.
+	fn next_event(&mut self) -> Result<Option<Event<J>>, Self::Error>;
+}
+
+impl<J: Json, E, I: Iterator<Item = Result<Event<J>, E>>> EventSource<J> for I {
+	type Error = E;
+
+	fn next_event(&mut self) -> Result<Option<Event<J>>, E> {
+		self.next().transpose()
+	}
+}
+
+/// Streaming expansion error.
+#[derive(Debug)]
+pub enum StreamError<E, M> {
+	/// The event source failed.
+	Source(E),
+
+	/// The events do not describe a well-formed JSON document.
+	Malformed,
+
+	/// The expansion failed.
+	Expansion(Loc<Error, M>),
+}
+
+impl<E, M> From<Loc<Error, M>> for StreamError<E, M> {
+	fn from(e: Loc<Error, M>) -> Self {
+		Self::Expansion(e)
+	}
+}
+
+type StreamResult<T, J, S> =
+	Result<T, StreamError<<S as EventSource<J>>::Error, <J as Json>::MetaData>>;
+
+fn next_event<J: Json, S: EventSource<J>>(source: &mut S) -> StreamResult<Option<Event<J>>, J, S> {
+	source.next_event().map_err(StreamError::Source)
+}
+
+/// Value being built from events.
+enum Frame<J: Json> {
+	Array(Vec<J>, J::MetaData),
+	Object(Vec<(J::Key, J)>, J::MetaData, Option<J::Key>),
+}
+
+/// Builds the JSON value starting with the given `first` event,
+/// consuming the events of the source up to the end of this value.
+fn build_value<J: JsonBuild, S: EventSource<J>>(
+	source: &mut S,
+	first: Event<J>,
+) -> StreamResult<J, J, S> {
+	let mut stack: Vec<Frame<J>> = Vec::new();
+	let mut event = first;
+
+	loop {
+		let value = match event {
+			Event::Null(meta) => Some(J::null(meta)),
+			Event::Boolean(b, meta) => Some(J::boolean(b, meta)),
+			Event::Number(n, meta) => Some(J::number(n, meta)),
+			Event::String(s, meta) => Some(J::string(s.as_str().into(), meta)),
+			Event::BeginArray(meta) => {
+				stack.push(Frame::Array(Vec::new(), meta));
+				None
+			}
+			Event::BeginObject(meta) => {
+				stack.push(Frame::Object(Vec::new(), meta, None));
+				None
+			}
+			Event::Key(key, meta) => match stack.last_mut() {
+				Some(Frame::Object(_, _, pending @ None)) => {
+					*pending = Some(J::new_key(&key, meta));
+					None
+				}
+				_ => return Err(StreamError::Malformed),
+			},
+			Event::EndArray => match stack.pop() {
+				Some(Frame::Array(items, meta)) => {
+					Some(J::array(items.into_iter().collect(), meta))
+				}
+				_ => return Err(StreamError::Malformed),
+			},
+			Event::EndObject => match stack.pop() {
+				Some(Frame::Object(entries, meta, None)) => {
+					Some(J::object(entries.into_iter().collect(), meta))
+				}
+				_ => return Err(StreamError::Malformed),
+			},
+		};
+
+		if let Some(value) = value {
+			match stack.last_mut() {
+				None => return Ok(value),
+				Some(Frame::Array(items, _)) => items.push(value),
+				Some(Frame::Object(entries, _, pending)) => match pending.take() {
+					Some(key) => entries.push((key, value)),
+					None => return Err(StreamError::Malformed),
+				},
+			}
+		}
+
+		event = next_event(source)?.ok_or(StreamError::Malformed)?;
+	}
+}
+
+/// Expand the JSON-LD document described by the given event source.
+///
+/// When the top-level value of the document is an array,
+/// each item is built and expanded as soon as its events are received,
+/// and then discarded, so that the whole document is never held in memory.
+///
+/// The result is the same as calling [`expand`] on the document,
+/// except that the [resource limits](Options::limits) apply to each
+/// top-level array item individually.
+///
+/// # Limitations
+///
+/// Only the items of a top-level array are streamed.
+/// Since the `@context` and `@type` entries of an object may appear after the
+/// entries they apply to, the members of an object cannot be expanded as they are
+/// received: a top-level object (including one with a large `@graph` entry)
+/// and each top-level array item are entirely built before being expanded.
+/// Documents that must be streamed should hence be given as a top-level array.
+pub async fn expand_stream<'a, J, T: Id, C: ContextMut<T>, L: Loader, S: EventSource<J>>(
+	active_context: &'a C,
+	source: &mut S,
+	base_url: Option<IriBuf>,
+	loader: &'a mut L,
+	options: Options,
+	warnings: &mut Vec<Loc<Warning, J::MetaData>>,
+) -> StreamResult<HashSet<Indexed<Object<J, T>>>, J, S>
+where
+	J: JsonExpand + JsonBuild,
+	T: Send + Sync,
+	C: Send + Sync,
+	C::LocalContext: From<L::Output> + From<J>,
+	L: Send + Sync,
+	L::Output: Into<J>,
+{
+	let result = match next_event(source)?.ok_or(StreamError::Malformed)? {
+		Event::BeginArray(_) => {
+			let base_url = base_url.as_ref().map(|url| url.as_iri());
+			let mut expanded = Vec::new();
+
+			loop {
+				match next_event(source)?.ok_or(StreamError::Malformed)? {
+					Event::EndArray => break,
+					event => {
+						let item = build_value(source, event)?;
+						options.limits.check(&item, loader.id_opt(base_url))?;
+						expanded.extend(
+							expand_element(
+								active_context,
+								ActiveProperty::None,
+								&item,
+								base_url,
+								loader,
+								options.clone(),
+								false,
+								warnings,
+							)
+							.await?,
+						)
+					}
+				}
+			}
+
+			into_top_level(Expanded::Array(expanded))
+		}
+		event => {
+			let document = build_value(source, event)?;
+			expand(
+				active_context,
+				&document,
+				base_url,
+				loader,
+				options,
+				warnings,
+			)
+			.await?
+		}
+	};
+
+	match next_event(source)? {
+		None => Ok(result),
+		Some(_) => Err(StreamError::Malformed),
+	}
+}
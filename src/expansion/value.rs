@@ -1,9 +1,10 @@
-use super::{expand_iri, ExpandedEntry};
+use super::{expand_document_relative_iri, ExpandedEntry, Options};
 use crate::{
 	loader,
 	object::*,
 	syntax::{Keyword, Term},
-	ContextMut, Direction, Error, ErrorCode, Id, Indexed, LangString, Loc, Reference, Warning,
+	ContextMut, Direction, Error, ErrorCode, Id, Indexed, LangString, Loc, ProcessingMode,
+	Reference, Warning,
 };
 use generic_json::{JsonClone, JsonHash, ValueRef};
 use langtag::LanguageTagBuf;
@@ -15,6 +16,7 @@ pub(crate) fn expand_value<'e, J: JsonHash + JsonClone, T: Id, C: ContextMut<T>>
 	type_scoped_context: &C,
 	expanded_entries: Vec<ExpandedEntry<'e, J, Term<T>>>,
 	value_entry: &J,
+	options: &Options,
 	warnings: &mut Vec<Loc<Warning, J::MetaData>>,
 ) -> Result<Option<Indexed<Object<J, T>>>, Error>
 where
@@ -50,7 +52,9 @@ where
 			Term::Keyword(Keyword::Direction) => {
 				// If processing mode is json-ld-1.0, continue with the next key
 				// from element.
-				// TODO processing mode.
+				if options.processing_mode == ProcessingMode::JsonLd1_0 {
+					continue;
+				}
 
 				// If value is neither "ltr" nor "rtl", an invalid base direction
 				// error has been detected and processing is aborted.
@@ -77,15 +81,15 @@ where
 			// If expanded ...
 			Term::Keyword(Keyword::Type) => {
 				if let Some(ty_value) = value.as_str() {
-					let expanded_ty = expand_iri(
+					let expanded_ty = expand_document_relative_iri(
 						source,
 						type_scoped_context,
 						ty_value,
 						value.metadata(),
 						true,
-						true,
+						options,
 						warnings,
-					);
+					)?;
 
 					match expanded_ty {
 						Term::Keyword(Keyword::Json) => {
@@ -112,11 +116,15 @@ where
 	// If processing mode is json-ld-1.0, an invalid value object value error has
 	// been detected and processing is aborted.
 	if is_json {
+		if options.processing_mode == ProcessingMode::JsonLd1_0 {
+			return Err(ErrorCode::InvalidValueObjectValue.into());
+		}
 		if language.is_some() || direction.is_some() {
 			return Err(ErrorCode::InvalidValueObject.into());
 		}
+		options.json_literal_limits.check(value_entry)?;
 		return Ok(Some(Indexed::new(
-			Object::Value(Value::Json(value_entry.clone())),
+			Object::Value(Value::Json(value_entry.clone()), None),
 			index,
 		)));
 	}
@@ -173,7 +181,7 @@ where
 
 			return match LangString::new(str, lang, direction) {
 				Ok(result) => Ok(Some(Indexed::new(
-					Object::Value(Value::LangString(result)),
+					Object::Value(Value::LangString(result), None),
 					index,
 				))),
 				Err(_) => Err(ErrorCode::InvalidLanguageTaggedValue.into()),
@@ -189,7 +197,7 @@ where
 	// TODO
 
 	Ok(Some(Indexed::new(
-		Object::Value(Value::Literal(result, ty)),
+		Object::Value(Value::Literal(result, ty), None),
 		index,
 	)))
 }
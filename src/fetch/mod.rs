@@ -0,0 +1,270 @@
+//! Document and context loader based on the
+//! [Fetch API](https://developer.mozilla.org/en-US/docs/Web/API/Fetch_API),
+//! to run the processing algorithms in a browser (or web worker)
+//! on the `wasm32-unknown-unknown` target.
+//!
+//! JavaScript promises are not `Send`.
+//! The `wasm` feature enables the `local-futures` feature, so that the futures
+//! of the processing algorithms and loaders are not required to be `Send`
+//! (see [`util::future`](crate::util::future)),
+//! and the futures of this loader can be used without any wrapper.
+
+use crate::util::future::{BoxFuture, FutureExt};
+use crate::{
+	loader::{self, is_json_media_type},
+	Error, ErrorCode, ErrorDetails, RemoteDocument,
+};
+use generic_json::Json;
+use iref::{Iri, IriBuf};
+use std::collections::HashMap;
+use std::fmt;
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{Request, RequestInit, RequestMode, Response};
+
+/// Error raised by the Fetch API.
+#[derive(Debug)]
+pub struct FetchError(String);
+
+impl FetchError {
+	fn new(value: JsValue) -> Self {
+		match value.as_string() {
+			Some(message) => Self(message),
+			None => Self(format!("{:?}", value)),
+		}
+	}
+}
+
+impl fmt::Display for FetchError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		self.0.fmt(f)
+	}
+}
+
+impl std::error::Error for FetchError {}
+
+fn fetch_error(value: JsValue) -> Error {
+	Error::with_source(ErrorCode::LoadingDocumentFailed, FetchError::new(value))
+}
+
+/// Calls the `fetch` function of the global scope (window or worker).
+fn fetch(request: &Request) -> Result<js_sys::Promise, Error> {
+	let global = js_sys::global();
+	if let Some(window) = global.dyn_ref::<web_sys::Window>() {
+		Ok(window.fetch_with_request(request))
+	} else if let Some(worker) = global.dyn_ref::<web_sys::WorkerGlobalScope>() {
+		Ok(worker.fetch_with_request(request))
+	} else {
+		Err(fetch_error(JsValue::from_str(
+			"the Fetch API is not available in this global scope",
+		)))
+	}
+}
+
+/// Loads the remote JSON-LD document at the given URL.
+///
+/// The document is parsed with the given `parser`.
+/// Since it awaits JavaScript promises, the returned future is not `Send`.
+pub async fn load_remote_json_ld_document<J, P>(url: Iri<'_>, parser: &mut P) -> Result<J, Error>
+where
+	P: FnMut(&str) -> Result<J, Error>,
+{
+	load_remote_json_ld_document_with_version(url, parser)
+		.await
+		.map(|(doc, _)| doc)
+}
+
+/// Loads the remote JSON-LD document at the given URL,
+/// along with its version information.
+///
+/// Note that the `ETag` and `Last-Modified` headers of cross-origin responses
+/// are only visible if the server exposes them
+/// (with the `Access-Control-Expose-Headers` header).
+pub async fn load_remote_json_ld_document_with_version<J, P>(
+	url: Iri<'_>,
+	parser: &mut P,
+) -> Result<(J, loader::Version), Error>
+where
+	P: FnMut(&str) -> Result<J, Error>,
+{
+	load_remote_document(url, parser)
+		.await
+		.map(|(doc, version, _)| (doc, version))
+}
+
+/// Loads the remote JSON-LD document at the given URL,
+/// along with its version information and the information given by the
+/// HTTP headers of the response (see [`loader::DocumentInfo::from_headers`]).
+///
+/// As for the `ETag` and `Last-Modified` headers,
+/// the `Link` header of cross-origin responses must be exposed by the server.
+pub async fn load_remote_document<J, P>(
+	url: Iri<'_>,
+	parser: &mut P,
+) -> Result<(J, loader::Version, loader::DocumentInfo), Error>
+where
+	P: FnMut(&str) -> Result<J, Error>,
+{
+	log::info!("loading remote document `{}'", url);
+
+	let mut init = RequestInit::new();
+	init.method("GET").mode(RequestMode::Cors);
+	let request = Request::new_with_str_and_init(url.as_str(), &init).map_err(fetch_error)?;
+	request
+		.headers()
+		.set("Accept", "application/ld+json, application/json")
+		.map_err(fetch_error)?;
+
+	let response: Response = JsFuture::from(fetch(&request)?)
+		.await
+		.map_err(fetch_error)?
+		.unchecked_into();
+
+	if !response.ok() {
+		return Err(Error::with_details(
+			ErrorCode::LoadingDocumentFailed,
+			ErrorDetails::UnexpectedValue {
+				expected: "a successful HTTP status".to_string(),
+				found: response.status().to_string(),
+			},
+		));
+	}
+
+	let headers = response.headers();
+	let header = |name: &str| headers.get(name).ok().flatten();
+
+	let content_type = header("Content-Type");
+	let is_json = content_type.as_deref().map_or(false, |value| {
+		value
+			.split(';')
+			.next()
+			.map_or(false, |ty| is_json_media_type(ty.trim()))
+	});
+
+	if is_json {
+		let version = loader::Version {
+			url: IriBuf::new(response.url().as_str()).unwrap_or_else(|_| url.into()),
+			etag: header("ETag"),
+			last_modified: header("Last-Modified"),
+			max_age: header("Cache-Control")
+				.as_deref()
+				.and_then(loader::cache_max_age),
+		};
+
+		// The Fetch API combines the values of repeated headers with `, `.
+		let links = header("Link");
+		let info = loader::DocumentInfo::from_headers(
+			content_type.as_deref(),
+			links.as_deref(),
+			version.url.as_iri(),
+		)?;
+
+		let body = JsFuture::from(response.text().map_err(fetch_error)?)
+			.await
+			.map_err(fetch_error)?
+			.as_string()
+			.unwrap_or_default();
+		let doc = (*parser)(body.as_str())?;
+		Ok((doc, version, info))
+	} else {
+		Err(Error::with_details(
+			ErrorCode::LoadingDocumentFailed,
+			ErrorDetails::UnexpectedValue {
+				expected: "a JSON media type".to_string(),
+				found: content_type.unwrap_or_else(|| "no content type".to_string()),
+			},
+		))
+	}
+}
+
+/// Loader fetching remote documents with the Fetch API.
+///
+/// Loaded documents are cached.
+/// The futures returned by the [`crate::Loader`] implementation are not `Send`
+/// (see the [module documentation](self)).
+pub struct Loader<J> {
+	namespace: HashMap<IriBuf, loader::Id>,
+	cache: Vec<(J, IriBuf, Option<loader::Version>, loader::DocumentInfo)>,
+	parser: Box<dyn 'static + Send + Sync + FnMut(&str) -> Result<J, Error>>,
+}
+
+impl<J: Clone + Send> Loader<J> {
+	pub fn new<E: 'static + std::error::Error>(
+		mut parser: impl 'static + Send + Sync + FnMut(&str) -> Result<J, E>,
+	) -> Self {
+		Self {
+			namespace: HashMap::new(),
+			cache: Vec::new(),
+			parser: Box::new(move |s| {
+				parser(s).map_err(|e| Error::with_source(ErrorCode::LoadingDocumentFailed, e))
+			}),
+		}
+	}
+
+	/// Allocate a identifier to the given IRI.
+	fn allocate(
+		&mut self,
+		iri: IriBuf,
+		doc: J,
+		version: Option<loader::Version>,
+		info: loader::DocumentInfo,
+	) -> loader::Id {
+		let id = loader::Id::new(self.cache.len());
+		self.namespace.insert(iri.clone(), id);
+		self.cache.push((doc, iri, version, info));
+		id
+	}
+
+	/// Returns the version information of the document with the given identifier, if any.
+	pub fn version(&self, id: loader::Id) -> Option<&loader::Version> {
+		self.cache
+			.get(id.unwrap())
+			.and_then(|(_, _, version, _)| version.as_ref())
+	}
+
+	pub async fn load(&mut self, url: Iri<'_>) -> Result<RemoteDocument<J>, Error> {
+		let url = IriBuf::from(url);
+		match self.namespace.get(&url) {
+			Some(id) => {
+				let (doc, _, version, info) = &self.cache[id.unwrap()];
+				Ok(RemoteDocument::new(doc.clone(), url, *id)
+					.with_version(version.clone())
+					.with_info(info.clone()))
+			}
+			None => {
+				let (doc, version, info) =
+					load_remote_document(url.as_iri(), &mut self.parser).await?;
+				let id = self.allocate(
+					url.clone(),
+					doc.clone(),
+					Some(version.clone()),
+					info.clone(),
+				);
+				Ok(RemoteDocument::new(doc, url, id)
+					.with_version(Some(version))
+					.with_info(info))
+			}
+		}
+	}
+}
+
+impl<J: Json + Clone + Send + Sync> crate::Loader for Loader<J> {
+	type Document = J;
+
+	#[inline(always)]
+	fn id(&self, iri: Iri<'_>) -> Option<loader::Id> {
+		self.namespace.get(&IriBuf::from(iri)).cloned()
+	}
+
+	#[inline(always)]
+	fn iri(&self, id: loader::Id) -> Option<Iri<'_>> {
+		self.cache
+			.get(id.unwrap())
+			.map(|(_, iri, _, _)| iri.as_iri())
+	}
+
+	fn load<'a>(&'a mut self, url: Iri<'_>) -> BoxFuture<'a, Result<RemoteDocument<J>, Error>> {
+		let url: IriBuf = url.into();
+		async move { Loader::load(self, url.as_iri()).await }.boxed()
+	}
+}
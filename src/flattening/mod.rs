@@ -0,0 +1,4 @@
+//! Flattening algorithm and related types.
+mod node_map;
+
+pub use node_map::*;
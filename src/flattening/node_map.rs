@@ -0,0 +1,443 @@
+use crate::util::collections::HashMap;
+use crate::{
+	BlankId, BlankIdGenerator, Error, ErrorCode, ErrorDetails, ExpandedDocument, Id, Indexed, Loc,
+	Node, Object, Reference, Warning,
+};
+use generic_json::JsonHash;
+use once_cell::sync::OnceCell;
+
+/// Node map, result of the
+/// [Node Map Generation algorithm](https://www.w3.org/TR/json-ld11-api/#node-map-generation).
+///
+/// It associates each graph name to the nodes of the graph, indexed by their identifier.
+/// The default graph has no name (`None`).
+pub struct NodeMap<J: JsonHash, T: Id> {
+	graphs: HashMap<Option<Reference<T>>, NodeMapGraph<J, T>>,
+}
+
+impl<J: JsonHash, T: Id> NodeMap<J, T> {
+	/// Creates a new node map with an empty default graph.
+	pub fn new() -> Self {
+		let mut graphs = HashMap::new();
+		graphs.insert(None, NodeMapGraph::new());
+		Self { graphs }
+	}
+
+	/// Returns the graph with the given name (`None` for the default graph), if any.
+	#[inline(always)]
+	pub fn graph(&self, name: Option<&Reference<T>>) -> Option<&NodeMapGraph<J, T>> {
+		self.graphs.get(&name.cloned())
+	}
+
+	/// Returns the default graph.
+	#[inline(always)]
+	pub fn default_graph(&self) -> &NodeMapGraph<J, T> {
+		&self.graphs[&None]
+	}
+
+	/// Returns an iterator over the graphs of the map, with their name.
+	#[inline(always)]
+	pub fn graphs(&self) -> impl Iterator<Item = (Option<&Reference<T>>, &NodeMapGraph<J, T>)> {
+		self.graphs
+			.iter()
+			.map(|(name, graph)| (name.as_ref(), graph))
+	}
+
+	/// Returns the graphs of the map with their name, the default graph first,
+	/// then the named graphs sorted by name.
+	pub fn graphs_sorted(&self) -> Vec<(Option<&Reference<T>>, &NodeMapGraph<J, T>)> {
+		let mut graphs: Vec<_> = self.graphs().collect();
+		graphs.sort_by(|(a, _), (b, _)| a.map(Reference::as_str).cmp(&b.map(Reference::as_str)));
+		graphs
+	}
+
+	/// Returns an iterator over the names of the named graphs.
+	#[inline(always)]
+	pub fn graph_names(&self) -> impl Iterator<Item = &Reference<T>> {
+		self.graphs.keys().filter_map(Option::as_ref)
+	}
+
+	/// Turns this node map into its graphs.
+	#[inline(always)]
+	pub fn into_graphs(self) -> HashMap<Option<Reference<T>>, NodeMapGraph<J, T>> {
+		self.graphs
+	}
+
+	fn graph_mut(&mut self, name: &Option<Reference<T>>) -> &mut NodeMapGraph<J, T> {
+		if !self.graphs.contains_key(name) {
+			self.graphs.insert(name.clone(), NodeMapGraph::new());
+		}
+
+		self.graphs.get_mut(name).unwrap()
+	}
+}
+
+impl<J: JsonHash, T: Id> Default for NodeMap<J, T> {
+	#[inline(always)]
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+/// Graph of a [`NodeMap`].
+///
+/// Nodes are flat: they have no `@graph`, `@included` or reverse properties,
+/// and objects referring to other nodes are node references (node objects with only an `@id`).
+pub struct NodeMapGraph<J: JsonHash, T: Id> {
+	nodes: HashMap<Reference<T>, Indexed<Node<J, T>>>,
+
+	/// Identifiers of the nodes of each type.
+	///
+	/// Built on first use, once the graph is complete.
+	types: OnceCell<HashMap<Reference<T>, Vec<Reference<T>>>>,
+}
+
+impl<J: JsonHash, T: Id> NodeMapGraph<J, T> {
+	/// Creates a new empty graph.
+	#[inline(always)]
+	pub fn new() -> Self {
+		Self {
+			nodes: HashMap::new(),
+			types: OnceCell::new(),
+		}
+	}
+
+	/// Number of nodes in the graph.
+	#[inline(always)]
+	pub fn len(&self) -> usize {
+		self.nodes.len()
+	}
+
+	/// Checks if the graph is empty.
+	#[inline(always)]
+	pub fn is_empty(&self) -> bool {
+		self.nodes.is_empty()
+	}
+
+	/// Returns the node with the given identifier, if any.
+	#[inline(always)]
+	pub fn get(&self, id: &Reference<T>) -> Option<&Indexed<Node<J, T>>> {
+		self.nodes.get(id)
+	}
+
+	/// Returns an iterator over the nodes of the graph, with their identifier.
+	#[inline(always)]
+	pub fn iter(&self) -> impl Iterator<Item = (&Reference<T>, &Indexed<Node<J, T>>)> {
+		self.nodes.iter()
+	}
+
+	/// Returns the nodes of the graph with their identifier, sorted by identifier.
+	///
+	/// Unlike [`iter`](Self::iter), the order does not depend on the hash of the identifiers.
+	pub fn iter_sorted(&self) -> std::vec::IntoIter<(&Reference<T>, &Indexed<Node<J, T>>)> {
+		let mut nodes: Vec<_> = self.nodes.iter().collect();
+		nodes.sort_by(|(a, _), (b, _)| a.as_str().cmp(b.as_str()));
+		nodes.into_iter()
+	}
+
+	/// Returns an iterator over the nodes of the graph having the given type.
+	///
+	/// The nodes are found using an index of the node types,
+	/// built the first time this function is called.
+	pub fn nodes_with_type<'a>(
+		&'a self,
+		ty: &Reference<T>,
+	) -> impl 'a + Iterator<Item = &'a Indexed<Node<J, T>>> {
+		let types = self.types.get_or_init(|| {
+			let mut types: HashMap<_, Vec<_>> = HashMap::new();
+			for (id, node) in &self.nodes {
+				for ty in node.types() {
+					types.entry(ty.clone()).or_default().push(id.clone())
+				}
+			}
+
+			types
+		});
+
+		let ids = types.get(ty).map(Vec::as_slice).unwrap_or(&[]);
+		ids.iter().map(move |id| &self.nodes[id])
+	}
+
+	/// Returns an iterator over the nodes of the graph having the given `@index` value.
+	pub fn nodes_with_index<'a>(
+		&'a self,
+		index: &'a str,
+	) -> impl 'a + Iterator<Item = &'a Indexed<Node<J, T>>> {
+		self.nodes
+			.values()
+			.filter(move |node| node.index() == Some(index))
+	}
+
+	/// Turns this graph into its nodes, indexed by their identifier.
+	#[inline(always)]
+	pub fn into_nodes(self) -> HashMap<Reference<T>, Indexed<Node<J, T>>> {
+		self.nodes
+	}
+}
+
+impl<J: JsonHash, T: Id> Default for NodeMapGraph<J, T> {
+	#[inline(always)]
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+/// Generates the node map of the given expanded document.
+///
+/// This follows the
+/// [Node Map Generation algorithm](https://www.w3.org/TR/json-ld11-api/#node-map-generation)
+/// of the JSON-LD 1.1 API:
+///   - every blank node identifier is relabeled with an identifier provided by `generator`
+///     (consistently across the whole document), and nodes without identifier are given
+///     a fresh blank node identifier;
+///   - nested node objects are replaced by node references and added to the map;
+///   - the nodes of a `@graph` entry are added to the graph named after the
+///     enclosing node identifier, and `@included` nodes to the current graph;
+///   - reverse properties are turned into properties of the referenced nodes;
+///   - the types and properties of nodes sharing the same identifier are merged,
+///     without duplicate values (lists are never merged).
+///
+/// The `@index` of a nested node object is kept by the node added to the map,
+/// but also by the node reference replacing it,
+/// so that the values of a property using an `@index` container can still be queried by index
+/// (see [`Node::get_index_map`]).
+///
+/// Two nodes with the same identifier and different `@index` values raise a
+/// [`ConflictingIndexes`](ErrorCode::ConflictingIndexes) error
+/// (see [`generate_node_map_with_warnings`] to recover from it).
+///
+/// Since the expanded document is not ordered, the generated identifiers may differ between
+/// two runs on equal documents. Top-level values are ignored, as in the specification.
+pub fn generate_node_map<J: JsonHash, T: Id, G: BlankIdGenerator>(
+	document: ExpandedDocument<J, T>,
+	generator: &mut G,
+) -> Result<NodeMap<J, T>, Error> {
+	build_node_map(document, generator, None)
+}
+
+/// Generates the node map of the given document, recovering from conflicting indexes.
+///
+/// This is the same as [`generate_node_map`], except that a node whose `@index` conflicts with
+/// the index of a previous node with the same identifier keeps the previous index.
+/// The conflicting index is dropped and a
+/// [`Warning::DroppedEntry`](crate::Warning::DroppedEntry) is emitted, located at the node.
+pub fn generate_node_map_with_warnings<J: JsonHash, T: Id, G: BlankIdGenerator>(
+	document: ExpandedDocument<J, T>,
+	generator: &mut G,
+	warnings: &mut Vec<Loc<Warning, Option<J::MetaData>>>,
+) -> Result<NodeMap<J, T>, Error> {
+	build_node_map(document, generator, Some(warnings))
+}
+
+fn build_node_map<J: JsonHash, T: Id, G: BlankIdGenerator>(
+	document: ExpandedDocument<J, T>,
+	generator: &mut G,
+	warnings: Option<&mut Vec<Loc<Warning, Option<J::MetaData>>>>,
+) -> Result<NodeMap<J, T>, Error> {
+	let (objects, _) = document.into_parts();
+	let mut builder = Builder {
+		map: NodeMap::new(),
+		labels: HashMap::new(),
+		generator,
+		warnings,
+	};
+
+	for object in objects {
+		builder.object(&None, object)?;
+	}
+
+	Ok(builder.map)
+}
+
+struct Builder<'g, J: JsonHash, T: Id, G> {
+	map: NodeMap<J, T>,
+	labels: HashMap<BlankId, BlankId>,
+	generator: &'g mut G,
+
+	/// Warnings, if conflicting indexes are recovered from.
+	warnings: Option<&'g mut Vec<Loc<Warning, Option<J::MetaData>>>>,
+}
+
+impl<'g, J: JsonHash, T: Id, G: BlankIdGenerator> Builder<'g, J, T, G> {
+	/// Relabels the given reference if it is a blank node identifier.
+	fn relabel(&mut self, r: Reference<T>) -> Reference<T> {
+		match r {
+			Reference::Blank(id) => {
+				let generator = &mut self.generator;
+				Reference::Blank(
+					self.labels
+						.entry(id)
+						.or_insert_with(|| generator.next_blank_id())
+						.clone(),
+				)
+			}
+			r => r,
+		}
+	}
+
+	/// Adds the given object to the graph, and returns its flattened version.
+	fn object(
+		&mut self,
+		graph: &Option<Reference<T>>,
+		mut object: Indexed<Object<J, T>>,
+	) -> Result<Indexed<Object<J, T>>, Error> {
+		let annotation = self.annotation(object.take_annotation());
+		let (object, index) = object.into_parts();
+		let mut flat_object = match object {
+			Object::Value(value, meta) => Indexed::new(Object::Value(value, meta), index),
+			Object::Node(node) => {
+				let metadata = node.metadata.clone();
+				let id = self.node(graph, Indexed::new(node, index.clone()))?;
+				let mut reference = Node::with_id(id);
+				reference.metadata = metadata;
+				Indexed::new(Object::Node(reference), index)
+			}
+			Object::List(items, meta) => {
+				let mut flat_items = Vec::with_capacity(items.len());
+				for item in items {
+					flat_items.push(self.object(graph, item)?)
+				}
+
+				Indexed::new(Object::List(flat_items, meta), index)
+			}
+		};
+
+		flat_object.set_annotation(annotation);
+		Ok(flat_object)
+	}
+
+	/// Relabels the blank node identifiers of the given annotation.
+	///
+	/// Annotations are not added to the graph: they stay embedded in the annotated object.
+	fn annotation(&mut self, annotation: Vec<Indexed<Object<J, T>>>) -> Vec<Indexed<Object<J, T>>> {
+		annotation
+			.into_iter()
+			.map(|object| {
+				object.map_inner(|object| object.map_references(&mut |r| self.relabel(r)))
+			})
+			.collect()
+	}
+
+	/// Adds the given node to the graph, and returns its identifier.
+	fn node(
+		&mut self,
+		graph: &Option<Reference<T>>,
+		node: Indexed<Node<J, T>>,
+	) -> Result<Reference<T>, Error> {
+		let (node, index) = node.into_parts();
+		let id = match node.id {
+			Some(id) => self.relabel(id),
+			None => Reference::Blank(self.generator.next_blank_id()),
+		};
+
+		let types: Vec<_> = node.types.into_iter().map(|ty| self.relabel(ty)).collect();
+
+		if let Some(index) = index {
+			let entry = self.entry(graph, &id);
+			match entry.index() {
+				Some(current) if current != index => {
+					let expected = current.to_string();
+					self.conflicting_indexes(expected, index, node.metadata.as_ref())?
+				}
+				_ => entry.set_index(Some(index)),
+			}
+		}
+
+		{
+			let entry = self.entry(graph, &id);
+
+			for ty in types {
+				if !entry.types.contains(&ty) {
+					entry.types.push(ty)
+				}
+			}
+
+			if entry.metadata.is_none() {
+				entry.metadata = node.metadata
+			}
+		}
+
+		for (prop, objects) in node.properties {
+			let prop = self.relabel(prop);
+			for object in objects {
+				let object = self.object(graph, object)?;
+				self.add_property(graph, &id, prop.clone(), object)
+			}
+		}
+
+		for (prop, nodes) in node.reverse_properties {
+			let prop = self.relabel(prop);
+			for subject in nodes {
+				let subject = self.node(graph, subject)?;
+				let reference = Indexed::new(Object::Node(Node::with_id(id.clone())), None);
+				self.add_property(graph, &subject, prop.clone(), reference)
+			}
+		}
+
+		if let Some(objects) = node.graph {
+			let name = Some(id.clone());
+			self.map.graph_mut(&name);
+			for object in objects {
+				self.object(&name, object)?;
+			}
+		}
+
+		if let Some(included) = node.included {
+			for node in included {
+				self.node(graph, node)?;
+			}
+		}
+
+		Ok(id)
+	}
+
+	/// Fails with a [`ConflictingIndexes`](ErrorCode::ConflictingIndexes) error,
+	/// unless conflicting indexes are recovered from.
+	fn conflicting_indexes(
+		&mut self,
+		expected: String,
+		found: String,
+		metadata: Option<&J::MetaData>,
+	) -> Result<(), Error> {
+		match &mut self.warnings {
+			Some(warnings) => {
+				warnings.push(Loc::new(
+					Warning::DroppedEntry(ErrorCode::ConflictingIndexes),
+					None,
+					metadata.cloned(),
+				));
+				Ok(())
+			}
+			None => Err(Error::with_details(
+				ErrorCode::ConflictingIndexes,
+				ErrorDetails::UnexpectedValue { expected, found },
+			)),
+		}
+	}
+
+	/// Returns the node of the graph with the given identifier, creating it if necessary.
+	fn entry(
+		&mut self,
+		graph: &Option<Reference<T>>,
+		id: &Reference<T>,
+	) -> &mut Indexed<Node<J, T>> {
+		self.map
+			.graph_mut(graph)
+			.nodes
+			.entry(id.clone())
+			.or_insert_with(|| Indexed::new(Node::with_id(id.clone()), None))
+	}
+
+	fn add_property(
+		&mut self,
+		graph: &Option<Reference<T>>,
+		id: &Reference<T>,
+		prop: Reference<T>,
+		object: Indexed<Object<J, T>>,
+	) {
+		let entry = self.entry(graph, id);
+		if object.is_list() || !entry.get(&prop).any(|o| *o == object) {
+			entry.insert(prop, object)
+		}
+	}
+}
@@ -0,0 +1,355 @@
+//! Extraction of JSON-LD documents embedded in HTML documents.
+//!
+//! This implements the
+//! [Process HTML](https://www.w3.org/TR/json-ld11-api/#process-html) algorithm
+//! of the JSON-LD 1.1 API: JSON-LD documents are found in `script` elements
+//! of type `application/ld+json`.
+//!
+//! ```
+//! use iref::Iri;
+//! use json_ld::{context, html, Document, NoLoader};
+//! use serde_json::Value;
+//!
+//! let page = r#"<html>
+//!   <head>
+//!     <script type="application/ld+json">
+//!       { "@id": "rust", "http://xmlns.com/foaf/0.1/name": "Rust" }
+//!     </script>
+//!   </head>
+//! </html>"#;
+//!
+//! let url = Iri::new("https://www.rust-lang.org/index.html").unwrap();
+//! let doc = html::extract(page, url, false, |s| serde_json::from_str::<Value>(s)).unwrap();
+//!
+//! let base_url = Some(doc.base_url.as_iri());
+//! let mut loader = NoLoader::<Value>::new();
+//! let expanded = async_std::task::block_on(doc.document.expand_with(
+//!   base_url,
+//!   &context::Json::<Value>::new(base_url),
+//!   &mut loader,
+//!   json_ld::expansion::Options::default(),
+//! ))
+//! .unwrap();
+//!
+//! let node = expanded.main_node(None).unwrap();
+//! assert_eq!(node.id().unwrap().as_str(), "https://www.rust-lang.org/rust");
+//! ```
+use crate::{Error, ErrorCode};
+use cc_traits::Iter;
+use generic_json::{JsonBuild, JsonClone, ValueRef};
+use iref::{Iri, IriBuf, IriRef};
+
+/// JSON-LD media type.
+const JSON_LD_MEDIA_TYPE: &str = "application/ld+json";
+
+/// JSON-LD document extracted from an HTML document.
+pub struct HtmlDocument<J> {
+	/// Extracted document.
+	pub document: J,
+
+	/// Base URL of the document.
+	///
+	/// This is the URL of the HTML document, unless it has a `base` element.
+	pub base_url: IriBuf,
+}
+
+/// `script` element of type `application/ld+json`.
+pub struct Script<'a> {
+	/// Value of the `id` attribute, if any.
+	pub id: Option<String>,
+
+	/// Content of the element.
+	pub content: &'a str,
+}
+
+/// Extracts the JSON-LD document embedded in the given HTML document.
+///
+/// The `url` of the HTML document is used to resolve its `base` element, if any.
+/// If `url` has a fragment, the `script` element with this fragment as identifier is selected.
+/// Otherwise, the first JSON-LD `script` element is selected,
+/// unless `extract_all_scripts` is `true`,
+/// in which case every JSON-LD `script` element is extracted and the result is an array
+/// of the extracted documents (arrays being concatenated).
+///
+/// The content of the selected elements is parsed with `parse`.
+/// Fails with a [`LoadingDocumentFailed`](ErrorCode::LoadingDocumentFailed) error if no
+/// suitable `script` element is found, and with an
+/// [`InvalidScriptElement`](ErrorCode::InvalidScriptElement) error if the content of a
+/// `script` element cannot be parsed.
+pub fn extract<J: JsonClone + JsonBuild, E: 'static + std::error::Error>(
+	html: &str,
+	url: Iri,
+	extract_all_scripts: bool,
+	mut parse: impl FnMut(&str) -> Result<J, E>,
+) -> Result<HtmlDocument<J>, Error>
+where
+	J::MetaData: Default,
+{
+	let base_url = base_href(html)
+		.and_then(|href| IriRef::new(&href).ok().map(|r| r.resolved(url)))
+		.unwrap_or_else(|| url.into());
+
+	let mut parse = |script: &Script| {
+		parse(script.content).map_err(|e| Error::with_source(ErrorCode::InvalidScriptElement, e))
+	};
+
+	let scripts = scripts(html);
+	let document = match url.fragment() {
+		Some(fragment) => {
+			match scripts
+				.iter()
+				.find(|script| script.id.as_deref() == Some(fragment.as_str()))
+			{
+				Some(script) => parse(script)?,
+				None => return Err(ErrorCode::LoadingDocumentFailed.into()),
+			}
+		}
+		None if extract_all_scripts => {
+			let mut documents = Vec::new();
+			for script in &scripts {
+				let document = parse(script)?;
+				let items: Option<Vec<J>> = match document.as_value_ref() {
+					ValueRef::Array(items) => Some(items.iter().map(|i| (*i).clone()).collect()),
+					_ => None,
+				};
+
+				match items {
+					Some(items) => documents.extend(items),
+					None => documents.push(document),
+				}
+			}
+
+			J::array(documents.into_iter().collect(), J::MetaData::default())
+		}
+		None => match scripts.first() {
+			Some(script) => parse(script)?,
+			None => return Err(ErrorCode::LoadingDocumentFailed.into()),
+		},
+	};
+
+	Ok(HtmlDocument { document, base_url })
+}
+
+/// Returns every `script` element of type `application/ld+json` in the given HTML document,
+/// in document order.
+///
+/// The type may have parameters, such as a `profile`.
+pub fn scripts(html: &str) -> Vec<Script> {
+	let mut scripts = Vec::new();
+	for element in elements(html) {
+		if element.name == "script" {
+			let is_json_ld = element.attribute("type").map_or(false, |ty| {
+				let essence = ty.split(';').next().unwrap().trim();
+				essence.eq_ignore_ascii_case(JSON_LD_MEDIA_TYPE)
+			});
+
+			if is_json_ld {
+				scripts.push(Script {
+					id: element.attribute("id").map(str::to_string),
+					content: element.content.unwrap_or(""),
+				})
+			}
+		}
+	}
+
+	scripts
+}
+
+/// Returns the `href` attribute of the first `base` element of the given HTML document.
+pub fn base_href(html: &str) -> Option<String> {
+	elements(html)
+		.into_iter()
+		.filter(|element| element.name == "base")
+		.find_map(|element| element.attribute("href").map(str::to_string))
+}
+
+/// HTML start tag.
+struct Element<'a> {
+	/// Lowercase tag name.
+	name: String,
+
+	/// Attributes, with lowercase names and decoded values.
+	attributes: Vec<(String, String)>,
+
+	/// Raw text content, for `script` elements.
+	content: Option<&'a str>,
+}
+
+impl<'a> Element<'a> {
+	fn attribute(&self, name: &str) -> Option<&str> {
+		self.attributes
+			.iter()
+			.find(|(n, _)| n == name)
+			.map(|(_, value)| value.as_str())
+	}
+}
+
+/// Lists the start tags of the given HTML document.
+///
+/// This is not a complete HTML parser: it only finds start tags and their attributes,
+/// skipping comments and the content of raw text elements (`script` and `style`),
+/// which is enough to find `script` and `base` elements.
+fn elements(html: &str) -> Vec<Element> {
+	let mut elements = Vec::new();
+	let mut i = 0;
+
+	while let Some(offset) = html[i..].find('<') {
+		let start = i + offset;
+		let rest = &html[start..];
+
+		if rest.starts_with("<!--") {
+			i = match rest.find("-->") {
+				Some(end) => start + end + 3,
+				None => html.len(),
+			};
+			continue;
+		}
+
+		let name_len = rest[1..]
+			.find(|c: char| !c.is_ascii_alphanumeric())
+			.unwrap_or(rest.len() - 1);
+		if name_len == 0 || !rest[1..].starts_with(|c: char| c.is_ascii_alphabetic()) {
+			i = start + 1;
+			continue;
+		}
+
+		let name = rest[1..=name_len].to_ascii_lowercase();
+		let (attributes, len) = attributes(&rest[1 + name_len..]);
+		i = start + 1 + name_len + len;
+
+		let content = if name == "script" || name == "style" {
+			let end =
+				find_ignore_case(&html[i..], &format!("</{}", name)).unwrap_or(html.len() - i);
+			let content = &html[i..i + end];
+			i += end;
+			Some(content)
+		} else {
+			None
+		};
+
+		elements.push(Element {
+			name,
+			attributes,
+			content,
+		})
+	}
+
+	elements
+}
+
+/// Parses the attributes of a start tag, up to the closing `>`.
+///
+/// Returns the attributes and the length of the parsed input.
+fn attributes(input: &str) -> (Vec<(String, String)>, usize) {
+	let mut attributes = Vec::new();
+	let mut i = 0;
+
+	loop {
+		i += input[i..]
+			.find(|c: char| !c.is_ascii_whitespace() && c != '/')
+			.unwrap_or(input.len() - i);
+
+		if i >= input.len() {
+			return (attributes, i);
+		}
+
+		if input[i..].starts_with('>') {
+			return (attributes, i + 1);
+		}
+
+		let name_len = input[i..]
+			.find(|c: char| c.is_ascii_whitespace() || c == '=' || c == '>' || c == '/')
+			.unwrap_or(input.len() - i)
+			.max(1);
+		let name = input[i..i + name_len].to_ascii_lowercase();
+		i += name_len;
+
+		let after_name = i + input[i..]
+			.find(|c: char| !c.is_ascii_whitespace())
+			.unwrap_or(input.len() - i);
+
+		let value = if input[after_name..].starts_with('=') {
+			i = after_name + 1;
+			i += input[i..]
+				.find(|c: char| !c.is_ascii_whitespace())
+				.unwrap_or(input.len() - i);
+
+			let value = match input[i..].chars().next() {
+				Some(quote) if quote == '"' || quote == '\'' => {
+					let len = input[i + 1..].find(quote).unwrap_or(input.len() - i - 1);
+					let value = &input[i + 1..i + 1 + len];
+					i = (i + len + 2).min(input.len());
+					value
+				}
+				_ => {
+					let len = input[i..]
+						.find(|c: char| c.is_ascii_whitespace() || c == '>')
+						.unwrap_or(input.len() - i);
+					let value = &input[i..i + len];
+					i += len;
+					value
+				}
+			};
+
+			decode_character_references(value)
+		} else {
+			String::new()
+		};
+
+		attributes.push((name, value))
+	}
+}
+
+/// Finds the given ASCII pattern in the input, ignoring case.
+fn find_ignore_case(input: &str, pattern: &str) -> Option<usize> {
+	let pattern = pattern.as_bytes();
+	input
+		.as_bytes()
+		.windows(pattern.len())
+		.position(|window| window.eq_ignore_ascii_case(pattern))
+}
+
+/// Decodes the most common character references of an attribute value.
+fn decode_character_references(value: &str) -> String {
+	let mut result = String::new();
+	let mut rest = value;
+
+	while let Some(start) = rest.find('&') {
+		result.push_str(&rest[..start]);
+		rest = &rest[start..];
+
+		let decoded = rest.find(';').and_then(|end| {
+			let c = match &rest[1..end] {
+				"amp" => Some('&'),
+				"lt" => Some('<'),
+				"gt" => Some('>'),
+				"quot" => Some('"'),
+				"apos" => Some('\''),
+				reference => match reference.strip_prefix('#') {
+					Some(code) => match code.strip_prefix(|c: char| c == 'x' || c == 'X') {
+						Some(hex) => u32::from_str_radix(hex, 16).ok(),
+						None => code.parse().ok(),
+					}
+					.and_then(std::char::from_u32),
+					None => None,
+				},
+			};
+
+			c.map(|c| (c, end + 1))
+		});
+
+		match decoded {
+			Some((c, len)) => {
+				result.push(c);
+				rest = &rest[len..]
+			}
+			None => {
+				result.push('&');
+				rest = &rest[1..]
+			}
+		}
+	}
+
+	result.push_str(rest);
+	result
+}
@@ -1,7 +1,7 @@
 use crate::syntax::TermLike;
+use core::hash::Hash;
 use generic_json::JsonBuild;
 use iref::{AsIri, Iri, IriBuf};
-use std::hash::Hash;
 
 /// Unique identifier types.
 ///
@@ -1,17 +1,23 @@
+#[cfg(not(feature = "std"))]
+use crate::prelude::*;
 use crate::{
 	syntax::Keyword,
 	util::{AsAnyJson, AsJson, JsonFrom},
 };
+use core::convert::{TryFrom, TryInto};
+use core::hash::{Hash, Hasher};
+use core::ops::{Deref, DerefMut};
 use generic_json::JsonClone;
-use std::convert::{TryFrom, TryInto};
-use std::hash::{Hash, Hasher};
-use std::ops::{Deref, DerefMut};
 
 /// Indexed objects.
 ///
 /// Nodes and value objects may be indexed by a string in JSON-LD.
 /// This type is a wrapper around any kind of indexable data.
 ///
+/// Nodes and value objects appearing as property values may also be annotated
+/// (`@annotation` entry of [JSON-LD-star](https://json-ld.github.io/json-ld-star/)):
+/// the annotation is stored beside the index.
+///
 /// It is a pointer type that `Deref` into the underlying value.
 pub struct Indexed<T> {
 	/// Index.
@@ -19,13 +25,22 @@ pub struct Indexed<T> {
 
 	/// Value.
 	value: T,
+
+	/// Annotation.
+	///
+	/// Empty if the value is not annotated.
+	annotation: Vec<Indexed<T>>,
 }
 
 impl<T> Indexed<T> {
 	/// Create a new (maybe) indexed value.
 	#[inline(always)]
 	pub fn new(value: T, index: Option<String>) -> Indexed<T> {
-		Indexed { value, index }
+		Indexed {
+			value,
+			index,
+			annotation: Vec::new(),
+		}
 	}
 
 	/// Get a reference to the inner value.
@@ -55,23 +70,83 @@ impl<T> Indexed<T> {
 		self.index = index
 	}
 
+	/// Get the annotation, if any.
+	///
+	/// The annotation is a set of node objects without identifier, describing the statement
+	/// relating this value to the node holding it as property value.
+	#[inline(always)]
+	pub fn annotation(&self) -> Option<&[Indexed<T>]> {
+		if self.annotation.is_empty() {
+			None
+		} else {
+			Some(&self.annotation)
+		}
+	}
+
+	/// Set the value annotation.
+	///
+	/// An empty annotation removes it.
+	#[inline(always)]
+	pub fn set_annotation(&mut self, annotation: Vec<Indexed<T>>) {
+		self.annotation = annotation
+	}
+
+	/// Removes the annotation and returns it.
+	#[inline(always)]
+	pub fn take_annotation(&mut self) -> Vec<Indexed<T>> {
+		core::mem::take(&mut self.annotation)
+	}
+
 	/// Turn this indexed value into its components: inner value and index.
+	///
+	/// The annotation, if any, is dropped.
 	#[inline(always)]
 	pub fn into_parts(self) -> (T, Option<String>) {
 		(self.value, self.index)
 	}
 
+	/// Maps the inner value, preserving the index.
+	///
+	/// The values of the annotation, if any, are mapped with the same function.
+	#[inline(always)]
+	pub fn map_inner<U, F: FnMut(T) -> U>(self, mut f: F) -> Indexed<U> {
+		self.map_inner_with(&mut f)
+	}
+
+	fn map_inner_with<U, F: FnMut(T) -> U>(self, f: &mut F) -> Indexed<U> {
+		Indexed {
+			value: f(self.value),
+			index: self.index,
+			annotation: self
+				.annotation
+				.into_iter()
+				.map(|a| a.map_inner_with(f))
+				.collect(),
+		}
+	}
+
 	/// Cast the inner value.
 	#[inline(always)]
 	pub fn cast<U: From<T>>(self) -> Indexed<U> {
-		Indexed::new(self.value.into(), self.index)
+		self.map_inner(U::from)
 	}
 
 	/// Try to cast the inner value.
+	///
+	/// The annotation values that cannot be cast are dropped,
+	/// and so is the whole annotation if the inner value cannot be cast.
 	#[inline(always)]
 	pub fn try_cast<U: TryFrom<T>>(self) -> Result<Indexed<U>, Indexed<U::Error>> {
 		match self.value.try_into() {
-			Ok(value) => Ok(Indexed::new(value, self.index)),
+			Ok(value) => Ok(Indexed {
+				value,
+				index: self.index,
+				annotation: self
+					.annotation
+					.into_iter()
+					.filter_map(|a| a.try_cast().ok())
+					.collect(),
+			}),
 			Err(e) => Err(Indexed::new(e, self.index)),
 		}
 	}
@@ -88,8 +163,28 @@ impl<T: Hash> Hash for Indexed<T> {
 impl<T: PartialEq> PartialEq for Indexed<T> {
 	#[inline(always)]
 	fn eq(&self, other: &Self) -> bool {
-		self.index == other.index && self.value == other.value
+		self.index == other.index
+			&& self.value == other.value
+			&& same_annotation(&self.annotation, &other.annotation)
+	}
+}
+
+/// Compares two annotations as multisets.
+fn same_annotation<T: PartialEq>(a: &[Indexed<T>], b: &[Indexed<T>]) -> bool {
+	if a.len() != b.len() {
+		return false;
 	}
+
+	let mut matched = vec![false; b.len()];
+	a.iter().all(
+		|x| match (0..b.len()).find(|&i| !matched[i] && b[i] == *x) {
+			Some(i) => {
+				matched[i] = true;
+				true
+			}
+			None => false,
+		},
+	)
 }
 
 impl<T: Eq> Eq for Indexed<T> {}
@@ -97,7 +192,11 @@ impl<T: Eq> Eq for Indexed<T> {}
 impl<T: Clone> Clone for Indexed<T> {
 	#[inline(always)]
 	fn clone(&self) -> Self {
-		Indexed::new(self.value.clone(), self.index.clone())
+		Indexed {
+			value: self.value.clone(),
+			index: self.index.clone(),
+			annotation: self.annotation.clone(),
+		}
 	}
 }
 
@@ -149,6 +248,18 @@ impl<J: JsonClone, K: JsonFrom<J>, T: AsJson<J, K>> AsJson<J, K> for Indexed<T>
 					index.as_json_with(meta(None)),
 				);
 			}
+
+			if !self.annotation.is_empty() {
+				let annotation = self
+					.annotation
+					.iter()
+					.map(|a| a.as_json_with(meta.clone()))
+					.collect();
+				obj.insert(
+					K::new_key(Keyword::Annotation.into_str(), meta(None)),
+					K::array(annotation, meta(None)),
+				);
+			}
 		}
 
 		json
@@ -0,0 +1,177 @@
+use crate::util::collections::HashMap;
+use crate::Id;
+use iref::{AsIri, Iri, IriBuf};
+use once_cell::sync::Lazy;
+use std::fmt;
+use std::sync::RwLock;
+
+/// Global IRI table.
+struct Table {
+	iris: Vec<Iri<'static>>,
+	indexes: HashMap<&'static str, u32>,
+}
+
+impl Table {
+	fn get(&self, iri: &str) -> Option<IriIndex> {
+		self.indexes.get(iri).copied().map(IriIndex)
+	}
+
+	fn insert(&mut self, iri: Iri) -> IriIndex {
+		match self.get(iri.as_str()) {
+			Some(index) => index,
+			None => {
+				let buf: &'static IriBuf = Box::leak(Box::new(IriBuf::from(iri)));
+				let index = self.iris.len() as u32;
+				self.iris.push(buf.as_iri());
+				self.indexes.insert(buf.as_str(), index);
+				IriIndex(index)
+			}
+		}
+	}
+}
+
+static TABLE: Lazy<RwLock<Table>> = Lazy::new(|| {
+	RwLock::new(Table {
+		iris: Vec::new(),
+		indexes: HashMap::new(),
+	})
+});
+
+/// Interned IRI.
+///
+/// Unlike the [`Lexicon`](crate::Lexicon) type, which requires the vocabulary to be known
+/// in advance, this identifier type assigns an index to each IRI on the fly,
+/// the first time it is encountered.
+/// Identifiers are then cheap to copy, compare and hash.
+///
+/// IRIs are stored in a process-wide table and are never freed,
+/// so this type should only be used when the set of IRIs manipulated by the application
+/// is bounded.
+/// Indexes are assigned in order of first appearance: well-known vocabularies can be
+/// interned first with [`IriIndex::reserve`] to get stable indexes,
+/// and the table can be saved with [`IriIndex::dump`] and restored with
+/// [`IriIndex::restore`] in another process.
+///
+/// ```
+/// use iref::Iri;
+/// use json_ld::IriIndex;
+///
+/// let name = IriIndex::new(Iri::new("http://xmlns.com/foaf/0.1/name").unwrap());
+/// assert_eq!(IriIndex::get(Iri::new("http://xmlns.com/foaf/0.1/name").unwrap()), Some(name));
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct IriIndex(u32);
+
+impl IriIndex {
+	/// Interns the given IRI, and returns its index.
+	pub fn new(iri: Iri) -> Self {
+		if let Some(index) = Self::get(iri) {
+			return index;
+		}
+
+		TABLE.write().unwrap().insert(iri)
+	}
+
+	/// Returns the index of the given IRI, if it has already been interned.
+	pub fn get(iri: Iri) -> Option<Self> {
+		TABLE.read().unwrap().get(iri.as_str())
+	}
+
+	/// Returns the index of this IRI in the table.
+	#[inline(always)]
+	pub fn index(&self) -> usize {
+		self.0 as usize
+	}
+
+	/// Interns the given IRIs, in order.
+	///
+	/// When called before any other IRI is interned, the interned IRIs are given
+	/// the indexes `0`, `1`, etc.
+	pub fn reserve<'a>(iris: impl IntoIterator<Item = Iri<'a>>) -> Vec<Self> {
+		let mut table = TABLE.write().unwrap();
+		iris.into_iter().map(|iri| table.insert(iri)).collect()
+	}
+
+	/// Number of interned IRIs.
+	pub fn count() -> usize {
+		TABLE.read().unwrap().iris.len()
+	}
+
+	/// Returns a copy of the IRI table, where the IRI at position `i` has index `i`.
+	pub fn dump() -> Vec<IriBuf> {
+		TABLE
+			.read()
+			.unwrap()
+			.iris
+			.iter()
+			.map(|iri| IriBuf::from(*iri))
+			.collect()
+	}
+
+	/// Restores a table dumped with [`IriIndex::dump`],
+	/// so that every IRI of the table is given the same index.
+	///
+	/// Fails if an IRI of the table has already been given another index,
+	/// in which case the IRIs preceding it have been interned.
+	pub fn restore<'a>(iris: impl IntoIterator<Item = Iri<'a>>) -> Result<(), RestoreError> {
+		let mut table = TABLE.write().unwrap();
+		for (i, iri) in iris.into_iter().enumerate() {
+			let index = table.insert(iri);
+			if index.index() != i {
+				return Err(RestoreError {
+					iri: iri.into(),
+					expected: i,
+					found: index.index(),
+				});
+			}
+		}
+
+		Ok(())
+	}
+}
+
+impl AsIri for IriIndex {
+	#[inline]
+	fn as_iri(&self) -> Iri {
+		TABLE.read().unwrap().iris[self.index()]
+	}
+}
+
+impl Id for IriIndex {
+	#[inline]
+	fn from_iri(iri: Iri) -> Self {
+		Self::new(iri)
+	}
+}
+
+impl fmt::Display for IriIndex {
+	#[inline]
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		self.as_iri().fmt(f)
+	}
+}
+
+/// Error raised by [`IriIndex::restore`] when an IRI has already been given another index.
+#[derive(Clone, Debug)]
+pub struct RestoreError {
+	/// Conflicting IRI.
+	pub iri: IriBuf,
+
+	/// Index of the IRI in the restored table.
+	pub expected: usize,
+
+	/// Index already given to the IRI.
+	pub found: usize,
+}
+
+impl fmt::Display for RestoreError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(
+			f,
+			"IRI `{}` has index {} instead of {}",
+			self.iri, self.found, self.expected
+		)
+	}
+}
+
+impl std::error::Error for RestoreError {}
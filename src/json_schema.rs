@@ -0,0 +1,503 @@
+//! Generation of JSON Schemas describing compacted documents.
+//!
+//! A [`JsonSchema`] describes the node objects of a document compacted with a given context,
+//! allowing incoming compacted documents to be validated by any
+//! [JSON Schema](https://json-schema.org/) validator without expanding them first.
+//! The schema is serialized using the [`AsAnyJson`] trait.
+//!
+//! Each term of the context gives a property whose values are described by the
+//! type coercion and container mapping of the term:
+//!   - `@id` and `@vocab` coercions, and reverse properties, accept strings and node objects;
+//!   - `xsd:boolean` accepts strings and booleans, integer datatypes strings and integers,
+//!     other numeric datatypes strings and numbers, and other datatypes strings only;
+//!   - `@json` accepts any value;
+//!   - terms without coercion accept strings, numbers, booleans and objects.
+//!
+//! Terms with a `@set` or `@list` container mapping accept arrays,
+//! terms with an `@index`, `@id`, `@type` or `@language` container mapping accept maps
+//! and terms with no container mapping accept a single value or an array of values.
+//! Terms with a `@nest` mapping are described under their nesting key.
+//! The `@context`, `@id` and `@type` keywords, and their aliases, are also described.
+//!
+//! Only node objects are described: embedded node objects and value objects are accepted
+//! as any object, and documents with a top-level `@graph` entry are not described.
+//!
+//! ```
+//! use iref::IriBuf;
+//! use json_ld::{context::{self, Local}, json_schema::JsonSchema, util::AsAnyJson};
+//! use json_ld::{NoLoader, Reference};
+//! use serde_json::{json, Value};
+//!
+//! # async_std::task::block_on(async {
+//! let mut loader = NoLoader::<Value>::new();
+//! let context = json!({
+//!   "@vocab": "http://schema.org/",
+//!   "type": "@type",
+//!   "name": "http://schema.org/name",
+//!   "knows": { "@id": "http://schema.org/knows", "@type": "@id", "@container": "@set" }
+//! })
+//! .process::<context::Json<Value, IriBuf>, _>(&mut loader, None)
+//! .await
+//! .unwrap()
+//! .into_inner();
+//!
+//! let person = Reference::Id(IriBuf::new("http://schema.org/Person").unwrap());
+//! let schema: Value = JsonSchema::with_types(&context, &[person]).strict().as_json();
+//!
+//! assert_eq!(schema["required"], json!(["type"]));
+//! assert_eq!(schema["additionalProperties"], json!(false));
+//! assert_eq!(
+//!   schema["properties"]["type"]["anyOf"][0],
+//!   json!({ "enum": ["Person", "http://schema.org/Person"] })
+//! );
+//! assert_eq!(
+//!   schema["properties"]["knows"],
+//!   json!({ "type": "array", "items": { "type": ["string", "object"] } })
+//! );
+//! # })
+//! ```
+use crate::{
+	context::{Context, TermDefinition},
+	object::value::xsd,
+	syntax::{ContainerType, Keyword, Term, Type},
+	util::AsAnyJson,
+	Id, Reference,
+};
+use generic_json::JsonBuild;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// URI of the JSON Schema dialect of the generated schemas.
+pub const DIALECT: &str = "https://json-schema.org/draft/2020-12/schema";
+
+/// JSON type.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub enum JsonType {
+	Boolean,
+	Integer,
+	Number,
+	String,
+	Array,
+	Object,
+}
+
+impl JsonType {
+	/// Returns the name of this type in JSON Schema.
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			Self::Boolean => "boolean",
+			Self::Integer => "integer",
+			Self::Number => "number",
+			Self::String => "string",
+			Self::Array => "array",
+			Self::Object => "object",
+		}
+	}
+}
+
+/// JSON Schema of a JSON value.
+///
+/// Only the constructs needed to describe compacted documents are supported.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum Schema {
+	/// Accepts any value (`true`).
+	Any,
+
+	/// Rejects any value (`false`).
+	Never,
+
+	/// Accepts values of the given types.
+	Type(Vec<JsonType>),
+
+	/// Accepts the given strings (`enum`).
+	Enum(Vec<String>),
+
+	/// Accepts arrays whose items match the given schema (`items`).
+	Array(Box<Self>),
+
+	/// Accepts arrays containing at least one item matching the given schema (`contains`).
+	Contains(Box<Self>),
+
+	/// Accepts objects.
+	Object(ObjectSchema),
+
+	/// Accepts values matching at least one of the given schemas (`anyOf`).
+	AnyOf(Vec<Self>),
+}
+
+impl Schema {
+	/// Accepts objects whose entries all match the given schema.
+	pub fn map(entry: Self) -> Self {
+		Self::Object(ObjectSchema {
+			additional_properties: Box::new(entry),
+			..ObjectSchema::default()
+		})
+	}
+
+	/// Accepts a value matching the given schema, or an array of such values.
+	pub fn one_or_many(item: Self) -> Self {
+		Self::AnyOf(vec![item.clone(), Self::Array(Box::new(item))])
+	}
+}
+
+/// JSON Schema of a JSON object.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct ObjectSchema {
+	/// Schema of the known entries (`properties`).
+	pub properties: BTreeMap<String, Schema>,
+
+	/// Required entries (`required`).
+	pub required: Vec<String>,
+
+	/// Schema of the other entries (`additionalProperties`).
+	pub additional_properties: Box<Schema>,
+}
+
+impl Default for ObjectSchema {
+	fn default() -> Self {
+		Self {
+			properties: BTreeMap::new(),
+			required: Vec::new(),
+			additional_properties: Box::new(Schema::Any),
+		}
+	}
+}
+
+/// JSON Schema of the node objects of the documents compacted with a given context.
+///
+/// See the [module documentation](self) for the details.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct JsonSchema {
+	/// Title of the schema.
+	title: Option<String>,
+
+	/// Schema of the node objects.
+	node: ObjectSchema,
+
+	/// Nesting keys.
+	nests: Vec<String>,
+}
+
+impl JsonSchema {
+	/// Generates the schema of the node objects compacted with the given context.
+	pub fn new<T: Id, C: Context<T>>(context: &C) -> Self {
+		Self::with_types(context, &[])
+	}
+
+	/// Generates the schema of the node objects compacted with the given context,
+	/// having at least one of the given types.
+	///
+	/// Types may be given as any term defined as the type, as relative to the vocabulary
+	/// mapping, as a compact IRI or as an absolute IRI.
+	/// If no type is given, the type of the node objects is not checked.
+	///
+	/// If `@type` has aliases, the type is required under the alias a compaction algorithm
+	/// would select (the shortest one, then the first in lexicographical order).
+	pub fn with_types<T: Id, C: Context<T>>(context: &C, types: &[Reference<T>]) -> Self {
+		let mut id_keys = vec![Keyword::Id.into_str().to_string()];
+		let mut type_keys = vec![Keyword::Type.into_str().to_string()];
+		let mut nests: BTreeMap<String, ObjectSchema> = BTreeMap::new();
+		let mut properties = BTreeMap::new();
+
+		for (term, definition) in context.definitions() {
+			match &definition.value {
+				Some(Term::Keyword(Keyword::Id)) => id_keys.push(term.clone()),
+				Some(Term::Keyword(Keyword::Type)) => type_keys.push(term.clone()),
+				Some(Term::Ref(_)) => {
+					let schema = property_schema(definition);
+					match &definition.nest {
+						Some(nest) => nests
+							.entry(nest.clone())
+							.or_insert_with(ObjectSchema::default)
+							.properties
+							.insert(term.clone(), schema),
+						None => properties.insert(term.clone(), schema),
+					};
+				}
+				_ => (),
+			}
+		}
+
+		let mut node = ObjectSchema::default();
+		node.properties
+			.insert(Keyword::Context.into_str().to_string(), Schema::Any);
+
+		for key in id_keys {
+			node.properties
+				.insert(key, Schema::Type(vec![JsonType::String]));
+		}
+
+		let type_schema = if types.is_empty() {
+			Schema::one_or_many(Schema::Type(vec![JsonType::String]))
+		} else {
+			let names = Schema::Enum(type_names(context, types).into_iter().collect());
+			Schema::AnyOf(vec![names.clone(), Schema::Contains(Box::new(names))])
+		};
+
+		if !types.is_empty() {
+			let key = type_keys
+				.iter()
+				.filter(|key| type_keys.len() == 1 || *key != Keyword::Type.into_str())
+				.min_by(|a, b| a.len().cmp(&b.len()).then_with(|| a.cmp(b)))
+				.unwrap();
+			node.required.push(key.clone())
+		}
+
+		for key in type_keys {
+			node.properties.insert(key, type_schema.clone());
+		}
+
+		let nest_keys = nests.keys().cloned().collect();
+		for (nest, schema) in nests {
+			node.properties.insert(nest, Schema::Object(schema));
+		}
+
+		node.properties.extend(properties);
+
+		Self {
+			title: None,
+			node,
+			nests: nest_keys,
+		}
+	}
+
+	/// Sets the title of the schema.
+	pub fn with_title(mut self, title: &str) -> Self {
+		self.title = Some(title.to_string());
+		self
+	}
+
+	/// Rejects node objects with entries not described by the context.
+	pub fn strict(mut self) -> Self {
+		self.node.additional_properties = Box::new(Schema::Never);
+		for nest in &self.nests {
+			if let Some(Schema::Object(nested)) = self.node.properties.get_mut(nest) {
+				nested.additional_properties = Box::new(Schema::Never)
+			}
+		}
+
+		self
+	}
+
+	/// Title of the schema.
+	#[inline(always)]
+	pub fn title(&self) -> Option<&str> {
+		self.title.as_deref()
+	}
+
+	/// Schema of the node objects.
+	#[inline(always)]
+	pub fn node(&self) -> &ObjectSchema {
+		&self.node
+	}
+}
+
+/// Schema of the values of a property defined by the given definition.
+fn property_schema<T: Id, C: Context<T>>(definition: &TermDefinition<T, C>) -> Schema {
+	let container = definition.container;
+
+	let item = if definition.reverse_property || container.contains(ContainerType::Graph) {
+		Schema::Type(vec![JsonType::String, JsonType::Object])
+	} else if container.contains(ContainerType::Language) {
+		Schema::Type(vec![JsonType::String])
+	} else {
+		match &definition.typ {
+			Some(Type::Id) | Some(Type::Vocab) => {
+				Schema::Type(vec![JsonType::String, JsonType::Object])
+			}
+			Some(Type::Json) => Schema::Any,
+			Some(Type::Ref(ty)) => match xsd::Datatype::from_iri(ty.as_iri().as_str()) {
+				Some(xsd::Datatype::Boolean) => {
+					Schema::Type(vec![JsonType::String, JsonType::Boolean])
+				}
+				Some(d) if d.is_integer() => {
+					Schema::Type(vec![JsonType::String, JsonType::Integer])
+				}
+				Some(d) if d.is_numeric() => Schema::Type(vec![JsonType::String, JsonType::Number]),
+				_ => Schema::Type(vec![JsonType::String]),
+			},
+			Some(Type::None) | None => Schema::Type(vec![
+				JsonType::String,
+				JsonType::Number,
+				JsonType::Boolean,
+				JsonType::Object,
+			]),
+		}
+	};
+
+	let values =
+		if container.contains(ContainerType::List) || container.contains(ContainerType::Set) {
+			Schema::Array(Box::new(item))
+		} else {
+			Schema::one_or_many(item)
+		};
+
+	if container.contains(ContainerType::Index)
+		|| container.contains(ContainerType::Id)
+		|| container.contains(ContainerType::Type)
+		|| container.contains(ContainerType::Language)
+	{
+		Schema::map(values)
+	} else {
+		values
+	}
+}
+
+/// Returns every string a compacted document may use to refer to the given types.
+fn type_names<T: Id, C: Context<T>>(context: &C, types: &[Reference<T>]) -> BTreeSet<String> {
+	let mut names = BTreeSet::new();
+	for ty in types {
+		let iri = ty.as_str();
+		names.insert(iri.to_string());
+
+		if let Some(Term::Ref(vocab)) = context.vocabulary() {
+			if let Some(suffix) = iri.strip_prefix(vocab.as_str()) {
+				if !suffix.is_empty() {
+					names.insert(suffix.to_string());
+				}
+			}
+		}
+
+		for (term, definition) in context.definitions() {
+			if let Some(Term::Ref(value)) = &definition.value {
+				if value == ty && !definition.reverse_property {
+					names.insert(term.clone());
+				} else if definition.prefix {
+					if let Some(suffix) = iri.strip_prefix(value.as_str()) {
+						if !suffix.is_empty() {
+							names.insert(format!("{}:{}", term, suffix));
+						}
+					}
+				}
+			}
+		}
+	}
+
+	names
+}
+
+impl<K: JsonBuild> AsAnyJson<K> for JsonType {
+	fn as_json_with(&self, meta: K::MetaData) -> K {
+		self.as_str().as_json_with(meta)
+	}
+}
+
+impl<K: JsonBuild> AsAnyJson<K> for Schema {
+	fn as_json_with(&self, meta: K::MetaData) -> K {
+		let entry = |key: &str, value: K| (K::new_key(key, meta.clone()), value);
+		let array = |items: Vec<K>| K::array(items.into_iter().collect(), meta.clone());
+
+		let entries = match self {
+			Self::Any => return true.as_json_with(meta),
+			Self::Never => return false.as_json_with(meta),
+			Self::Type(types) => {
+				let types = match types.as_slice() {
+					[ty] => ty.as_json_with(meta.clone()),
+					types => array(
+						types
+							.iter()
+							.map(|ty| ty.as_json_with(meta.clone()))
+							.collect(),
+					),
+				};
+				vec![entry("type", types)]
+			}
+			Self::Enum(values) => {
+				let values = values
+					.iter()
+					.map(|v| v.as_json_with(meta.clone()))
+					.collect();
+				vec![entry("enum", array(values))]
+			}
+			Self::Array(items) => vec![
+				entry("type", JsonType::Array.as_json_with(meta.clone())),
+				entry("items", items.as_json_with(meta.clone())),
+			],
+			Self::Contains(item) => vec![
+				entry("type", JsonType::Array.as_json_with(meta.clone())),
+				entry("contains", item.as_json_with(meta.clone())),
+			],
+			Self::Object(object) => return object.as_json_with(meta),
+			Self::AnyOf(schemas) => {
+				let schemas = schemas
+					.iter()
+					.map(|s| s.as_json_with(meta.clone()))
+					.collect();
+				vec![entry("anyOf", array(schemas))]
+			}
+		};
+
+		K::object(entries.into_iter().collect(), meta)
+	}
+}
+
+impl ObjectSchema {
+	fn entries<K: JsonBuild>(&self, meta: &K::MetaData) -> Vec<(K::Key, K)> {
+		let mut entries = vec![(
+			K::new_key("type", meta.clone()),
+			JsonType::Object.as_json_with(meta.clone()),
+		)];
+
+		if !self.properties.is_empty() {
+			let properties = self
+				.properties
+				.iter()
+				.map(|(key, schema)| {
+					(
+						K::new_key(key, meta.clone()),
+						schema.as_json_with(meta.clone()),
+					)
+				})
+				.collect();
+			entries.push((
+				K::new_key("properties", meta.clone()),
+				K::object(properties, meta.clone()),
+			))
+		}
+
+		if !self.required.is_empty() {
+			let required = self
+				.required
+				.iter()
+				.map(|key| key.as_json_with(meta.clone()))
+				.collect();
+			entries.push((
+				K::new_key("required", meta.clone()),
+				K::array(required, meta.clone()),
+			))
+		}
+
+		if *self.additional_properties != Schema::Any {
+			entries.push((
+				K::new_key("additionalProperties", meta.clone()),
+				self.additional_properties.as_json_with(meta.clone()),
+			))
+		}
+
+		entries
+	}
+}
+
+impl<K: JsonBuild> AsAnyJson<K> for ObjectSchema {
+	fn as_json_with(&self, meta: K::MetaData) -> K {
+		K::object(self.entries::<K>(&meta).into_iter().collect(), meta)
+	}
+}
+
+impl<K: JsonBuild> AsAnyJson<K> for JsonSchema {
+	fn as_json_with(&self, meta: K::MetaData) -> K {
+		let mut entries = vec![(
+			K::new_key("$schema", meta.clone()),
+			DIALECT.as_json_with(meta.clone()),
+		)];
+
+		if let Some(title) = &self.title {
+			entries.push((
+				K::new_key("title", meta.clone()),
+				title.as_json_with(meta.clone()),
+			))
+		}
+
+		entries.extend(self.node.entries::<K>(&meta));
+		K::object(entries.into_iter().collect(), meta)
+	}
+}
@@ -1,8 +1,19 @@
-use crate::{object::LiteralString, util::AsAnyJson, Direction};
+#[cfg(not(feature = "std"))]
+use crate::prelude::*;
+use crate::{
+	object::LiteralString,
+	util::{AsAnyJson, JsonFrom},
+	Direction,
+};
+use core::convert::TryFrom;
+use core::fmt;
 use derivative::Derivative;
 use generic_json::{Json, JsonBuild};
+use iref::IriBuf;
 use langtag::{LanguageTag, LanguageTagBuf};
-use std::fmt;
+
+/// Namespace of the datatypes used to encode the direction of strings in RDF literals.
+const I18N_NAMESPACE: &str = "https://www.w3.org/ns/i18n#";
 
 /// Language tag buffer that may not be well-formed.
 #[derive(Clone, PartialEq, Eq, Hash, Debug)]
@@ -147,6 +158,16 @@ impl<J: Json> LangString<J> {
 		}
 	}
 
+	/// Converts this string into a string of another JSON type.
+	#[inline(always)]
+	pub fn cast<K: JsonFrom<J>>(self) -> LangString<K> {
+		LangString {
+			data: self.data.cast(),
+			language: self.language,
+			direction: self.direction,
+		}
+	}
+
 	/// Reference to the underlying `str`.
 	#[inline(always)]
 	pub fn as_string(&self) -> &LiteralString<J> {
@@ -181,6 +202,21 @@ impl<J: Json> LangString<J> {
 		}
 	}
 
+	/// Lowercases the language tag, if any.
+	///
+	/// Language tags are case-insensitive.
+	pub fn normalize_language(&mut self) {
+		if let Some(language) = &self.language {
+			let lowercase = language.as_str().to_lowercase();
+			if lowercase != language.as_str() {
+				self.language = Some(match LanguageTagBuf::parse_copy(lowercase.as_str()) {
+					Ok(tag) => tag.into(),
+					Err(_) => lowercase.into(),
+				})
+			}
+		}
+	}
+
 	/// Gets the associated direction, if any.
 	#[inline(always)]
 	pub fn direction(&self) -> Option<Direction> {
@@ -200,6 +236,58 @@ impl<J: Json> LangString<J> {
 		}
 	}
 
+	/// Returns the datatype IRI encoding the language tag and direction of this string
+	/// following the `i18n-datatype` [`RdfDirection`](crate::RdfDirection) strategy.
+	///
+	/// Returns `None` if the string has no direction, in which case it must be
+	/// encoded as a regular language-tagged RDF literal.
+	pub fn i18n_datatype(&self) -> Option<IriBuf> {
+		self.direction.map(|direction| {
+			let language = self
+				.language
+				.as_ref()
+				.map(|tag| tag.as_str().to_lowercase())
+				.unwrap_or_default();
+			IriBuf::new(&format!("{}{}_{}", I18N_NAMESPACE, language, direction)).unwrap()
+		})
+	}
+
+	/// Decodes a string encoded as an RDF literal with the given datatype
+	/// following the `i18n-datatype` [`RdfDirection`](crate::RdfDirection) strategy.
+	///
+	/// This is the inverse of [`i18n_datatype`](LangString::i18n_datatype).
+	/// Returns the input string if the datatype is not an `i18n` datatype.
+	pub fn from_i18n_datatype(
+		str: LiteralString<J>,
+		datatype: &str,
+	) -> Result<Self, LiteralString<J>> {
+		let suffix = match datatype.strip_prefix(I18N_NAMESPACE) {
+			Some(suffix) => suffix,
+			None => return Err(str),
+		};
+
+		let (language, direction) = match suffix.rfind('_') {
+			Some(i) => (&suffix[..i], &suffix[(i + 1)..]),
+			None => return Err(str),
+		};
+
+		let direction = match Direction::try_from(direction) {
+			Ok(direction) => direction,
+			Err(_) => return Err(str),
+		};
+
+		let language = if language.is_empty() {
+			None
+		} else {
+			Some(match LanguageTagBuf::parse_copy(language) {
+				Ok(tag) => tag.into(),
+				Err(_) => language.to_string().into(),
+			})
+		};
+
+		Self::new(str, language, Some(direction))
+	}
+
 	/// Set both the language tag and direction.
 	///
 	/// If both `language` and `direction` are `None`,
@@ -78,6 +78,15 @@
 //!     mount point system.
 //!   - `reqwest::Loader` provided by the `reqwest-loader` feature that uses the
 //!     [`reqwest`](https://crates.io/crates/reqwest) crate to load remote documents.
+//!     Its `reqwest::ContextLoader` variant only fetches the context of remote documents
+//!     when the server supports the `http://www.w3.org/ns/json-ld#context` profile.
+//!   - `fetch::Loader` provided by the `wasm` feature that uses the browser
+//!     [Fetch API](https://developer.mozilla.org/en-US/docs/Web/API/Fetch_API)
+//!     to load remote documents on the `wasm32-unknown-unknown` target.
+//!     This feature enables the `local-futures` feature: the futures of the processing
+//!     algorithms and loaders are then not required to be `Send`.
+//!   - `loader::DiskCache` provided by the `disk-cache` feature that caches the documents
+//!     loaded by another loader on disk, so that they are available to subsequent runs.
 //!   Note that `reqwest` requires the
 //!   [`tokio`](https://crates.io/crates/tokio) runtime to work.
 //!
@@ -124,7 +133,83 @@
 //!
 //! ### Flattening
 //!
-//! Flattening is not yet implemented, but will be in the future.
+//! The [`flattening`] module implements the
+//! [Node Map Generation](https://www.w3.org/TR/json-ld11-api/#node-map-generation)
+//! subroutine of the flattening algorithm.
+//! An expanded document can be turned into a [`NodeMap`](flattening::NodeMap),
+//! collecting every node object of the document by graph and identifier,
+//! with [`ExpandedDocument::generate_node_map`]:
+//! ```
+//! use json_ld::{context, BlankIdSequence, Document, Loc, NoLoader};
+//! use serde_json::Value;
+//!
+//! #[async_std::main]
+//! async fn main() -> Result<(), json_ld::Error> {
+//!   let doc: Value = serde_json::from_str(r#"
+//!     {
+//!       "@id": "https://www.rust-lang.org",
+//!       "http://xmlns.com/foaf/0.1/maker": { "http://xmlns.com/foaf/0.1/name": "Rust Team" }
+//!     }
+//!   "#).unwrap();
+//!
+//!   let mut loader = NoLoader::<Value>::new();
+//!   let expanded_doc = doc
+//!     .expand::<context::Json<Value>, _>(&mut loader)
+//!     .await
+//!     .map_err(Loc::unwrap)?;
+//!
+//!   // Generate the node map, naming the anonymous maker with a fresh blank node identifier.
+//!   let node_map = expanded_doc.generate_node_map(&mut BlankIdSequence::default())?;
+//!   for (id, _node) in node_map.default_graph().iter() {
+//!     println!("node: {}", id)
+//!   }
+//!
+//!   assert_eq!(node_map.default_graph().len(), 2);
+//!   Ok(())
+//! }
+//! ```
+//!
+//! Producing the final flattened document (and its compacted form)
+//! is not yet implemented.
+//!
+//! ### Command line interface
+//!
+//! The `cli` feature provides a `json-ld` binary to expand and compact documents
+//! from shell pipelines:
+//! ```sh
+//! $ cargo install json-ld --features cli
+//! $ json-ld compact --context context.jsonld --pretty < input.jsonld
+//! ```
+//! Run `json-ld --help` for the complete list of options.
+//!
+//! ### Iteration order
+//!
+//! Node properties are iterated in insertion order as long as a node has only a few of them.
+//! The `ordered` feature preserves the insertion order regardless of the number of properties,
+//! so that expanded documents are serialized in a reproducible way.
+//! Properties and node map graphs can also be iterated in lexicographical order with
+//! their `iter_sorted` methods.
+//!
+//! ### Parallel processing
+//!
+//! The `rayon` feature provides parallel iteration over the top-level objects of an
+//! [`ExpandedDocument`] with [`rayon`](https://crates.io/crates/rayon), and parallel
+//! transformations of documents (`ExpandedDocument::par_map` and `par_filter_map`).
+//!
+//! ### `no_std` support
+//!
+//! The hash sets and maps of the object model, re-exported in [`util::collections`],
+//! are the ones of the standard library.
+//! The `hashbrown` feature replaces them with the ones of
+//! [`hashbrown`](https://crates.io/crates/hashbrown).
+//!
+//! The `std` feature is enabled by default.
+//! Without it, the crate is `no_std` and only requires `alloc`: it then only provides
+//! the [`syntax`] module and the object model ([`object`], [`Indexed`], [`Reference`], etc.),
+//! and the `hashbrown` feature must be enabled.
+//! The loaders, the processing algorithms and the other modules require `std`,
+//! as do the features providing them.
+//! Note that the `iref`, `langtag` and `generic-json` dependencies still use `std` themselves.
 //!
 //! ## Custom identifiers
 //!
@@ -164,55 +249,140 @@
 //! crate that provides the `IriEnum` derive macro which automatically generate
 //! conversions between the `MyVocab` and `iref::Iri` types.
 //!
+//! The `vocabularies` feature provides such vocabularies for the common terms of
+//! schema.org, rdf, rdfs, xsd, skos and foaf in the `vocabularies` module.
+//!
 //! ## RDF Serialization/Deserialization
 //!
-//! This is not directly handled by this crate.
+//! Expanded documents can be converted into RDF quads with
+//! [`ExpandedDocument::to_rdf`], and RDF datasets canonicalized with the
+//! [`rdf`] module. Parsing and serializing RDF syntaxes is not handled by this crate.
+#![cfg_attr(not(feature = "std"), no_std)]
 #![allow(clippy::derive_hash_xor_eq)]
 #![feature(generic_associated_types)]
 #![feature(trait_alias)]
 
+#[cfg(not(any(feature = "std", feature = "hashbrown")))]
+compile_error!("the `hashbrown` feature is required without the `std` feature");
+
+#[cfg_attr(not(feature = "std"), macro_use)]
+extern crate alloc;
 extern crate iref;
 extern crate log;
 
+/// Items of the standard prelude that are not in the `core` prelude,
+/// imported by the modules supporting `no_std`.
+#[cfg(not(feature = "std"))]
+mod prelude {
+	pub use alloc::borrow::ToOwned;
+	pub use alloc::boxed::Box;
+	pub use alloc::string::{String, ToString};
+	pub use alloc::vec::Vec;
+}
+
+#[cfg(feature = "std")]
+mod adjacency;
 mod blank;
+#[cfg(feature = "std")]
 pub mod compaction;
+#[cfg(feature = "std")]
 pub mod context;
+#[cfg(feature = "std")]
+mod dataset;
+#[cfg(feature = "std")]
+pub mod diagnostic;
 mod direction;
+#[cfg(feature = "std")]
 mod document;
+#[cfg(feature = "std")]
+mod equivalence;
 mod error;
+#[cfg(feature = "std")]
 pub mod expansion;
+#[cfg(feature = "std")]
+pub mod flattening;
 mod id;
 mod indexed;
+#[cfg(feature = "std")]
+mod iri_index;
 mod lang;
+#[cfg(feature = "std")]
+mod limits;
+#[cfg(feature = "std")]
+pub mod lint;
+#[cfg(feature = "std")]
 pub mod loader;
+#[cfg(feature = "std")]
 mod loc;
 mod mode;
+#[cfg(feature = "std")]
+mod monitor;
 mod null;
 pub mod object;
+#[cfg(feature = "std")]
+pub mod print;
+#[cfg(feature = "std")]
+pub mod rdf;
 mod reference;
+#[cfg(feature = "std")]
+pub mod shape;
 pub mod syntax;
 pub mod util;
 mod vocab;
+#[cfg(feature = "std")]
 mod warning;
 
 #[cfg(feature = "reqwest-loader")]
 pub mod reqwest;
 
+#[cfg(feature = "wasm")]
+pub mod fetch;
+
+#[cfg(feature = "html")]
+pub mod html;
+
+#[cfg(feature = "codegen")]
+pub mod codegen;
+
+#[cfg(feature = "json-schema")]
+pub mod json_schema;
+
+#[cfg(feature = "vocabularies")]
+pub mod vocabularies;
+
+#[cfg(feature = "std")]
+pub use adjacency::*;
 pub use blank::*;
+#[cfg(feature = "std")]
 pub use compaction::Compact;
+#[cfg(feature = "std")]
+pub use dataset::*;
 pub use direction::*;
+#[cfg(feature = "std")]
 pub use document::*;
+#[cfg(feature = "std")]
+pub use equivalence::*;
 pub use error::*;
 pub use id::*;
 pub use indexed::*;
+#[cfg(feature = "std")]
+pub use iri_index::*;
 pub use lang::*;
+#[cfg(feature = "std")]
+pub use limits::*;
+#[cfg(feature = "std")]
 pub use loader::{FsLoader, Loader, NoLoader};
+#[cfg(feature = "std")]
 pub use loc::Loc;
 pub use mode::*;
+#[cfg(feature = "std")]
+pub use monitor::*;
 pub use null::*;
 pub use reference::*;
 pub use vocab::*;
+#[cfg(feature = "std")]
 pub use warning::*;
 
+#[cfg(feature = "std")]
 pub use context::{Context, ContextMut, ContextMutProxy, JsonContext};
 pub use object::{Node, Nodes, Object, Objects, Value};
@@ -0,0 +1,169 @@
+use crate::{loader, Error, ErrorCode, ErrorDetails, Loc};
+use cc_traits::{Iter, MapIter};
+use generic_json::{Json, ValueRef};
+
+/// Resource limits applied to processed documents.
+///
+/// Deeply nested or unreasonably large documents and contexts can exhaust
+/// the stack or memory of the processor.
+/// Those limits are checked on the input document before expansion,
+/// and on every remote context before it is processed,
+/// raising a [`LimitExceeded`](ErrorCode::LimitExceeded) error when exceeded.
+///
+/// Contexts directly provided by the application are trusted and not checked.
+///
+/// The default is no limit.
+/// See [`Limits::server`] for limits suitable to process untrusted documents.
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub struct Limits {
+	/// Maximum nesting depth of arrays and objects.
+	///
+	/// A scalar value has depth 0.
+	pub max_depth: Option<usize>,
+
+	/// Maximum number of JSON values (including arrays and objects) in a document.
+	///
+	/// Since the expansion algorithm creates at most one object per value of
+	/// the input document, this also bounds the size of the expanded document.
+	pub max_values: Option<usize>,
+
+	/// Maximum length of strings and object keys, in bytes.
+	pub max_string_length: Option<usize>,
+
+	/// Maximum length of a remote context chain,
+	/// that is the number of nested remote contexts loaded to process a context.
+	///
+	/// As required by the JSON-LD specification, exceeding this limit
+	/// raises a [`ContextOverflow`](ErrorCode::ContextOverflow) error.
+	pub max_remote_contexts: Option<usize>,
+}
+
+impl Limits {
+	/// Bound on the nesting depth of the documents checked by [`Limits::check`],
+	/// whatever the value of [`max_depth`](Limits::max_depth).
+	///
+	/// Documents are checked (and processed) recursively:
+	/// this keeps the check from exhausting the stack on a deeply nested document.
+	pub const MAX_DEPTH: usize = 1024;
+
+	/// No limit.
+	pub fn none() -> Self {
+		Self::default()
+	}
+
+	/// Limits suitable to process untrusted documents on a server.
+	///
+	/// Documents may be nested up to 64 levels, have up to one million values
+	/// and strings up to 1MiB long. Remote contexts chains are limited to 16 contexts.
+	pub fn server() -> Self {
+		Self {
+			max_depth: Some(64),
+			max_values: Some(1_000_000),
+			max_string_length: Some(1024 * 1024),
+			max_remote_contexts: Some(16),
+		}
+	}
+
+	/// Checks that no limit is set.
+	pub fn is_none(&self) -> bool {
+		*self == Self::none()
+	}
+
+	/// Checks that the given document respects the depth, values and string length limits.
+	///
+	/// The returned error is located at the value exceeding the limit.
+	/// Unless no limit is set, in which case the document is not checked at all,
+	/// the depth of the document is also bounded by [`Limits::MAX_DEPTH`].
+	///
+	/// ```
+	/// use json_ld::{ErrorCode, Limits};
+	/// use serde_json::json;
+	///
+	/// let limits = Limits { max_depth: Some(2), ..Limits::none() };
+	/// assert!(limits.check(&json!({ "a": [1] }), None).is_ok());
+	/// let error = limits.check(&json!({ "a": [[1]] }), None).unwrap_err();
+	/// assert_eq!(error.code(), ErrorCode::LimitExceeded);
+	/// ```
+	pub fn check<J: Json>(
+		&self,
+		value: &J,
+		source: Option<loader::Id>,
+	) -> Result<(), Loc<Error, J::MetaData>> {
+		if self.max_depth.is_none() && self.max_values.is_none() && self.max_string_length.is_none()
+		{
+			return Ok(());
+		}
+
+		let max_depth = self
+			.max_depth
+			.map_or(Self::MAX_DEPTH, |max| max.min(Self::MAX_DEPTH));
+		let mut count = 0;
+		self.check_value(value, source, 0, max_depth, &mut count)
+	}
+
+	fn check_value<J: Json>(
+		&self,
+		value: &J,
+		source: Option<loader::Id>,
+		depth: usize,
+		max_depth: usize,
+		count: &mut usize,
+	) -> Result<(), Loc<Error, J::MetaData>> {
+		let located = |e: Error| e.located(source, value.metadata().clone());
+
+		*count += 1;
+		check(self.max_values, *count, "max_values").map_err(located)?;
+
+		match value.as_value_ref() {
+			ValueRef::String(s) => {
+				let s: &str = s.as_ref();
+				check(self.max_string_length, s.len(), "max_string_length").map_err(located)?
+			}
+			ValueRef::Array(ary) => {
+				check(Some(max_depth), depth + 1, "max_depth").map_err(located)?;
+				for item in ary.iter() {
+					self.check_value(&*item, source, depth + 1, max_depth, count)?
+				}
+			}
+			ValueRef::Object(obj) => {
+				check(Some(max_depth), depth + 1, "max_depth").map_err(located)?;
+				for (key, item) in obj.iter() {
+					let key_str: &str = key.as_ref();
+					check(self.max_string_length, key_str.len(), "max_string_length")
+						.map_err(|e| e.located(source, key.metadata().clone()))?;
+					self.check_value(&*item, source, depth + 1, max_depth, count)?
+				}
+			}
+			_ => (),
+		}
+
+		Ok(())
+	}
+
+	/// Checks that a remote context chain of the given length respects the limits.
+	pub(crate) fn check_remote_contexts(&self, len: usize) -> Result<(), Error> {
+		match self.max_remote_contexts {
+			Some(max) if len > max => Err(Error::with_details(
+				ErrorCode::ContextOverflow,
+				ErrorDetails::Limit {
+					name: "max_remote_contexts".to_string(),
+					max,
+				},
+			)),
+			_ => Ok(()),
+		}
+	}
+}
+
+fn check(limit: Option<usize>, value: usize, name: &str) -> Result<(), Error> {
+	match limit {
+		Some(max) if value > max => Err(Error::with_details(
+			ErrorCode::LimitExceeded,
+			ErrorDetails::Limit {
+				name: name.to_string(),
+				max,
+			},
+		)),
+		_ => Ok(()),
+	}
+}
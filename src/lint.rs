@@ -0,0 +1,372 @@
+//! Context linter.
+//!
+//! Statically analyzes a context document (without loading any remote context)
+//! and reports suspicious constructs that are not necessarily processing errors.
+//!
+//! ```
+//! use json_ld::lint::{lint, Issue};
+//! use serde_json::Value;
+//!
+//! let context: Value = serde_json::from_str(r#"
+//!   {
+//!     "name": "http://xmlns.com/foaf/0.1/name",
+//!     "fullName": "http://xmlns.com/foaf/0.1/name"
+//!   }
+//! "#).unwrap();
+//!
+//! let lints = lint(&context);
+//! assert!(matches!(lints[0].issue, Issue::DuplicateIri { .. }))
+//! ```
+use crate::util::collections::{HashMap, HashSet};
+use crate::{
+	syntax::{is_keyword, is_keyword_like},
+	util::as_array,
+};
+use cc_traits::{Get, MapIter};
+use generic_json::{Json, Key, ValueRef};
+use std::fmt;
+
+/// IRI schemes that are never reported as undefined prefixes.
+const KNOWN_SCHEMES: &[&str] = &[
+	"http", "https", "urn", "mailto", "tag", "file", "data", "did", "ftp",
+];
+
+/// Issue reported by the linter.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum Issue {
+	/// The term is a keyword or has the form of a keyword.
+	///
+	/// Such terms are ignored by processors.
+	KeywordShadowing(String),
+
+	/// The prefix is never used by the other definitions of the context.
+	UnusedPrefix(String),
+
+	/// The term expands to the same IRI as a previously defined term.
+	DuplicateIri {
+		term: String,
+		other: String,
+		iri: String,
+	},
+
+	/// The term redefines a protected term with a different IRI.
+	ProtectedRedefinition(String),
+
+	/// The value is a compact IRI whose prefix is not defined.
+	UndefinedPrefix { term: String, prefix: String },
+
+	/// The construct is not supported by JSON-LD 1.0 processors.
+	Compatibility(String),
+}
+
+impl fmt::Display for Issue {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Self::KeywordShadowing(term) => {
+				write!(f, "term `{}` is or has the form of a keyword", term)
+			}
+			Self::UnusedPrefix(prefix) => write!(f, "prefix `{}` is never used", prefix),
+			Self::DuplicateIri { term, other, iri } => write!(
+				f,
+				"term `{}` expands to `{}` like term `{}`",
+				term, iri, other
+			),
+			Self::ProtectedRedefinition(term) => {
+				write!(f, "protected term `{}` is redefined", term)
+			}
+			Self::UndefinedPrefix { term, prefix } => {
+				write!(f, "term `{}` uses undefined prefix `{}`", term, prefix)
+			}
+			Self::Compatibility(construct) => {
+				write!(f, "`{}` is not supported by JSON-LD 1.0", construct)
+			}
+		}
+	}
+}
+
+/// Issue located in the context document.
+#[derive(Clone, Debug)]
+pub struct Lint<M> {
+	/// Issue.
+	pub issue: Issue,
+
+	/// Metadata of the offending entry.
+	pub metadata: M,
+}
+
+/// Term definition, as seen by the linter.
+struct Definition<M> {
+	/// Raw IRI mapping, if any.
+	iri: Option<String>,
+
+	/// Raw type mapping, if any.
+	typ: Option<String>,
+
+	protected: bool,
+
+	metadata: M,
+}
+
+/// Analyzes the given context document.
+///
+/// The context may be a context definition or an array of context definitions.
+/// Remote contexts (strings) are ignored.
+/// Issues are returned in document order, except for unused prefixes
+/// and undefined prefixes that are reported at the end.
+pub fn lint<J: Json>(context: &J) -> Vec<Lint<J::MetaData>> {
+	let mut lints = Vec::new();
+	let mut definitions: HashMap<String, Definition<J::MetaData>> = HashMap::new();
+	let mut order = Vec::new();
+
+	let (contexts, _) = as_array(context);
+	for context in contexts {
+		match context.as_value_ref() {
+			ValueRef::Null => {
+				definitions.clear();
+				order.clear()
+			}
+			ValueRef::Object(context) => {
+				let protected = context
+					.get("@protected")
+					.and_then(|p| p.as_bool())
+					.unwrap_or(false);
+
+				for (key, value) in context.iter() {
+					let term: &str = key.as_ref();
+					match term {
+						"@propagate" | "@import" | "@version" | "@direction" | "@protected" => {
+							lints.push(Lint {
+								issue: Issue::Compatibility(term.to_string()),
+								metadata: key.metadata().clone(),
+							})
+						}
+						"@base" | "@language" | "@vocab" => (),
+						_ => {
+							if let Some(definition) = lint_definition::<J>(
+								term,
+								key.metadata(),
+								&*value,
+								protected,
+								&mut lints,
+							) {
+								if let Some(previous) = definitions.get(term) {
+									if previous.protected && previous.iri != definition.iri {
+										lints.push(Lint {
+											issue: Issue::ProtectedRedefinition(term.to_string()),
+											metadata: key.metadata().clone(),
+										})
+									}
+								}
+
+								if definitions.insert(term.to_string(), definition).is_none() {
+									order.push(term.to_string())
+								}
+							}
+						}
+					}
+				}
+			}
+			_ => (),
+		}
+	}
+
+	// Expand every IRI mapping using the defined prefixes.
+	let mut used_prefixes = HashSet::new();
+	let mut undefined = Vec::new();
+	let mut iris: HashMap<String, String> = HashMap::new();
+	for term in &order {
+		let definition = &definitions[term];
+
+		if let Some((prefix, _)) = split_compact_iri(term) {
+			if definitions.contains_key(prefix) {
+				used_prefixes.insert(prefix.to_string());
+			}
+		}
+
+		if let Some(typ) = &definition.typ {
+			check_prefix(
+				term,
+				typ,
+				&definitions,
+				&definition.metadata,
+				&mut used_prefixes,
+				&mut undefined,
+			);
+		}
+
+		if let Some(iri) = &definition.iri {
+			let expanded = check_prefix(
+				term,
+				iri,
+				&definitions,
+				&definition.metadata,
+				&mut used_prefixes,
+				&mut undefined,
+			);
+
+			match iris.get(&expanded) {
+				Some(other) => lints.push(Lint {
+					issue: Issue::DuplicateIri {
+						term: term.clone(),
+						other: other.clone(),
+						iri: expanded,
+					},
+					metadata: definition.metadata.clone(),
+				}),
+				None => {
+					iris.insert(expanded, term.clone());
+				}
+			}
+		}
+	}
+
+	for term in &order {
+		let definition = &definitions[term];
+		if let Some(iri) = &definition.iri {
+			let is_prefix = iri.ends_with('/') || iri.ends_with('#') || iri.ends_with(':');
+			if is_prefix && !used_prefixes.contains(term) {
+				lints.push(Lint {
+					issue: Issue::UnusedPrefix(term.clone()),
+					metadata: definition.metadata.clone(),
+				})
+			}
+		}
+	}
+
+	lints.extend(undefined);
+	lints
+}
+
+/// Lints a single term definition.
+fn lint_definition<J: Json>(
+	term: &str,
+	metadata: &J::MetaData,
+	value: &J,
+	protected: bool,
+	lints: &mut Vec<Lint<J::MetaData>>,
+) -> Option<Definition<J::MetaData>> {
+	if term == "@type" {
+		lints.push(Lint {
+			issue: Issue::Compatibility(term.to_string()),
+			metadata: metadata.clone(),
+		});
+		return None;
+	}
+
+	if is_keyword(term) || is_keyword_like(term) {
+		lints.push(Lint {
+			issue: Issue::KeywordShadowing(term.to_string()),
+			metadata: metadata.clone(),
+		});
+		return None;
+	}
+
+	let mut definition = Definition {
+		iri: None,
+		typ: None,
+		protected,
+		metadata: metadata.clone(),
+	};
+
+	match value.as_value_ref() {
+		ValueRef::String(iri) => definition.iri = Some(iri.as_ref().to_string()),
+		ValueRef::Object(value) => {
+			definition.iri = value
+				.get("@id")
+				.or_else(|| value.get("@reverse"))
+				.and_then(|iri| iri.as_str().map(ToString::to_string));
+			definition.typ = value
+				.get("@type")
+				.and_then(|typ| typ.as_str().map(ToString::to_string))
+				.filter(|typ| !is_keyword(typ));
+
+			if let Some(p) = value.get("@protected").and_then(|p| p.as_bool()) {
+				definition.protected = p
+			}
+
+			for (key, entry) in value.iter() {
+				let key: &str = key.as_ref();
+				match key {
+					"@context" | "@direction" | "@index" | "@nest" | "@prefix" | "@protected" => {
+						lints.push(Lint {
+							issue: Issue::Compatibility(key.to_string()),
+							metadata: entry.metadata().clone(),
+						})
+					}
+					"@container" => {
+						let compatible = match entry.as_str() {
+							Some(c) => !matches!(c, "@graph" | "@id" | "@type"),
+							None => false,
+						};
+
+						if !compatible {
+							lints.push(Lint {
+								issue: Issue::Compatibility("@container".to_string()),
+								metadata: entry.metadata().clone(),
+							})
+						}
+					}
+					"@type" => {
+						if matches!(entry.as_str(), Some("@json") | Some("@none")) {
+							lints.push(Lint {
+								issue: Issue::Compatibility(entry.as_str().unwrap().to_string()),
+								metadata: entry.metadata().clone(),
+							})
+						}
+					}
+					_ => (),
+				}
+			}
+		}
+		_ => (),
+	}
+
+	Some(definition)
+}
+
+/// Splits a compact IRI into its prefix and suffix.
+///
+/// Returns `None` for blank node identifiers and absolute IRIs with an authority.
+fn split_compact_iri(value: &str) -> Option<(&str, &str)> {
+	let i = value.find(':')?;
+	let (prefix, suffix) = (&value[..i], &value[(i + 1)..]);
+	if prefix == "_" || suffix.starts_with("//") {
+		None
+	} else {
+		Some((prefix, suffix))
+	}
+}
+
+/// Expands the given compact IRI `value` using the defined prefixes.
+///
+/// Records the used prefix, or an undefined prefix lint.
+fn check_prefix<M: Clone>(
+	term: &str,
+	value: &str,
+	definitions: &HashMap<String, Definition<M>>,
+	metadata: &M,
+	used_prefixes: &mut HashSet<String>,
+	undefined: &mut Vec<Lint<M>>,
+) -> String {
+	if let Some((prefix, suffix)) = split_compact_iri(value) {
+		match definitions.get(prefix).and_then(|d| d.iri.as_ref()) {
+			Some(prefix_iri) => {
+				used_prefixes.insert(prefix.to_string());
+				return prefix_iri.clone() + suffix;
+			}
+			None => {
+				if !KNOWN_SCHEMES.contains(&prefix) {
+					undefined.push(Lint {
+						issue: Issue::UndefinedPrefix {
+							term: term.to_string(),
+							prefix: prefix.to_string(),
+						},
+						metadata: metadata.clone(),
+					})
+				}
+			}
+		}
+	}
+
+	value.to_string()
+}
@@ -1,13 +1,21 @@
-use crate::{Error, ErrorCode, RemoteDocument};
-use futures::future::{BoxFuture, FutureExt};
+use crate::util::future::{BoxFuture, FutureExt};
+use crate::{Error, ErrorCode, ErrorDetails, RemoteDocument};
 use generic_json::Json;
-use iref::{Iri, IriBuf};
-use std::collections::HashMap;
+use iref::{Iri, IriBuf, IriRef};
+use std::collections::{HashMap, HashSet};
 use std::fs::File;
 use std::io::{BufReader, Read};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
 use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
 use std::{marker::PhantomData, str::FromStr};
 
+#[cfg(feature = "disk-cache")]
+mod disk_cache;
+
+#[cfg(feature = "disk-cache")]
+pub use disk_cache::*;
+
 /// Identifier reference.
 #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
 pub struct Id(usize);
@@ -28,6 +36,378 @@ impl From<Id> for usize {
 	}
 }
 
+/// Checks if the given media type is a JSON media type
+/// (`application/json` or `application/ld+json`).
+pub fn is_json_media_type(ty: &str) -> bool {
+	ty == "application/json" || ty == "application/ld+json"
+}
+
+/// Version information of a loaded document.
+///
+/// Captures the HTTP validators returned by the server along with the
+/// final URL of the document (after redirects), so that applications can
+/// record exactly which version of a remote document has been used.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Version {
+	/// Final URL of the document, after redirects.
+	pub url: IriBuf,
+
+	/// Value of the `ETag` header, if any.
+	pub etag: Option<String>,
+
+	/// Value of the `Last-Modified` header, if any.
+	pub last_modified: Option<String>,
+
+	/// Freshness lifetime of the document given by the `Cache-Control` header, if any
+	/// (see [`cache_max_age`]).
+	pub max_age: Option<Duration>,
+}
+
+impl Version {
+	/// Creates a new version information with no validator.
+	pub fn new(url: IriBuf) -> Self {
+		Self {
+			url,
+			etag: None,
+			last_modified: None,
+			max_age: None,
+		}
+	}
+}
+
+/// Returns the freshness lifetime given by the value of a `Cache-Control` header.
+///
+/// This is the `max-age` directive value, or zero if the response must not
+/// be reused without being validated (`no-cache` or `no-store` directive).
+///
+/// ```
+/// use json_ld::loader::cache_max_age;
+/// use std::time::Duration;
+///
+/// assert_eq!(cache_max_age("public, max-age=3600"), Some(Duration::from_secs(3600)));
+/// assert_eq!(cache_max_age("no-store"), Some(Duration::ZERO));
+/// assert_eq!(cache_max_age("public"), None);
+/// ```
+pub fn cache_max_age(cache_control: &str) -> Option<Duration> {
+	let mut max_age = None;
+	for directive in cache_control.split(',').map(str::trim) {
+		let (name, value) = directive.split_once('=').unwrap_or((directive, ""));
+		match name.trim().to_ascii_lowercase().as_str() {
+			"no-cache" | "no-store" => return Some(Duration::ZERO),
+			"max-age" => {
+				max_age = value
+					.trim()
+					.trim_matches('"')
+					.parse()
+					.ok()
+					.map(Duration::from_secs)
+			}
+			_ => (),
+		}
+	}
+
+	max_age
+}
+
+/// `http://www.w3.org/ns/json-ld#context` link relation.
+///
+/// Used in HTTP `Link` headers to associate a context to a JSON document.
+pub const CONTEXT_LINK_RELATION: &str = "http://www.w3.org/ns/json-ld#context";
+
+/// Information about a loaded document given along with its content,
+/// such as the HTTP headers of the response.
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct DocumentInfo {
+	/// Media type of the document, without parameters.
+	pub content_type: Option<String>,
+
+	/// Value of the `profile` parameter of the media type, if any.
+	pub profile: Option<String>,
+
+	/// URL of the context given by a `Link` header with the
+	/// [`CONTEXT_LINK_RELATION`] relation, if any.
+	pub context_url: Option<IriBuf>,
+}
+
+impl DocumentInfo {
+	/// Extracts the document information from the values of the `Content-Type`
+	/// and `Link` headers of an HTTP response.
+	///
+	/// As specified by the JSON-LD API, context links are ignored for
+	/// `application/ld+json` documents.
+	/// Relative link targets are resolved against `base`, the URL of the document.
+	/// Fails with a [`MultipleContextLinkHeaders`](ErrorCode::MultipleContextLinkHeaders)
+	/// error if more than one context link is found.
+	///
+	/// ```
+	/// use json_ld::loader::DocumentInfo;
+	/// use static_iref::iri;
+	///
+	/// let info = DocumentInfo::from_headers(
+	///   Some("application/json; charset=utf-8"),
+	///   vec!["<context.jsonld>; rel=\"http://www.w3.org/ns/json-ld#context\""],
+	///   iri!("https://example.com/data.json"),
+	/// )
+	/// .unwrap();
+	///
+	/// assert_eq!(info.content_type.as_deref(), Some("application/json"));
+	/// assert_eq!(
+	///   info.context_url.unwrap().as_str(),
+	///   "https://example.com/context.jsonld"
+	/// );
+	/// ```
+	pub fn from_headers<'h>(
+		content_type: Option<&str>,
+		links: impl IntoIterator<Item = &'h str>,
+		base: Iri,
+	) -> Result<Self, Error> {
+		let mut info = Self::default();
+
+		if let Some(content_type) = content_type {
+			let mut parts = content_type.split(';').map(str::trim);
+			info.content_type = parts.next().map(str::to_ascii_lowercase);
+			info.profile = parts.find_map(|param| {
+				let (name, value) = param.split_once('=')?;
+				if name.trim().eq_ignore_ascii_case("profile") {
+					Some(value.trim().trim_matches('"').to_string())
+				} else {
+					None
+				}
+			});
+		}
+
+		if info.content_type.as_deref() != Some("application/ld+json") {
+			let mut context_urls = links
+				.into_iter()
+				.flat_map(|value| value.split(','))
+				.filter_map(|link| context_link(link, base));
+
+			info.context_url = context_urls.next();
+			if context_urls.next().is_some() {
+				return Err(ErrorCode::MultipleContextLinkHeaders.into());
+			}
+		}
+
+		Ok(info)
+	}
+}
+
+/// Returns the target of the given link, if it has the [`CONTEXT_LINK_RELATION`] relation.
+fn context_link(link: &str, base: Iri) -> Option<IriBuf> {
+	let mut parts = link.split(';').map(str::trim);
+	let target = parts.next()?.strip_prefix('<')?.strip_suffix('>')?;
+
+	let is_context = parts.any(|param| match param.split_once('=') {
+		Some((name, value)) => {
+			name.trim().eq_ignore_ascii_case("rel")
+				&& value
+					.trim()
+					.trim_matches('"')
+					.split_whitespace()
+					.any(|rel| rel == CONTEXT_LINK_RELATION)
+		}
+		None => false,
+	});
+
+	if is_context {
+		IriRef::new(target).ok().map(|target| target.resolved(base))
+	} else {
+		None
+	}
+}
+
+/// Decoded `data:` URL, as defined by [RFC 2397](https://tools.ietf.org/html/rfc2397).
+///
+/// Such URLs embed a whole document,
+/// for instance `data:application/ld+json;base64,eyJAdm9jYWIiOiAiaHR0cDovL2V4YW1wbGUub3JnLyJ9`.
+///
+/// ```
+/// use json_ld::loader::DataUrl;
+///
+/// let url = "data:application/ld+json;base64,eyJAdm9jYWIiOiAiaHR0cDovL2V4YW1wbGUub3JnLyJ9";
+/// let url = DataUrl::parse(url).unwrap();
+/// assert!(url.is_json());
+/// assert_eq!(url.text(), Some(r#"{"@vocab": "http://example.org/"}"#));
+///
+/// let url = DataUrl::parse("data:application/json,%7B%7D").unwrap();
+/// assert_eq!(url.text(), Some("{}"));
+/// ```
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct DataUrl {
+	/// Media type, without parameters, in lowercase.
+	///
+	/// Defaults to `text/plain` if not specified.
+	pub media_type: String,
+
+	/// Parameters of the media type (such as `charset`), in order.
+	pub parameters: Vec<(String, String)>,
+
+	/// Decoded data.
+	pub data: Vec<u8>,
+}
+
+impl DataUrl {
+	/// Parses and decodes the given `data:` URL.
+	///
+	/// Returns `None` if the URL does not use the `data` scheme or is malformed.
+	pub fn parse(url: &str) -> Option<Self> {
+		let (scheme, rest) = url.split_once(':')?;
+		if !scheme.eq_ignore_ascii_case("data") {
+			return None;
+		}
+
+		let (header, data) = rest.split_once(',')?;
+		let header = String::from_utf8(percent_decode(header)?).ok()?;
+		let mut parts = header.split(';').map(str::trim);
+
+		let media_type = match parts.next() {
+			Some("") | None => "text/plain".to_string(),
+			Some(ty) => ty.to_ascii_lowercase(),
+		};
+
+		let mut parameters = Vec::new();
+		let mut base64 = false;
+		for part in parts {
+			match part.split_once('=') {
+				Some((name, value)) => {
+					parameters.push((name.trim().to_ascii_lowercase(), value.trim().to_string()))
+				}
+				None if part.eq_ignore_ascii_case("base64") => base64 = true,
+				None => return None,
+			}
+		}
+
+		let data = percent_decode(data)?;
+		let data = if base64 { base64_decode(&data)? } else { data };
+
+		Some(Self {
+			media_type,
+			parameters,
+			data,
+		})
+	}
+
+	/// Returns the value of the given media type parameter, if any.
+	pub fn parameter(&self, name: &str) -> Option<&str> {
+		self.parameters
+			.iter()
+			.find(|(n, _)| n.eq_ignore_ascii_case(name))
+			.map(|(_, value)| value.trim_matches('"'))
+	}
+
+	/// Checks if the media type is a JSON media type (see [`is_json_media_type`]).
+	pub fn is_json(&self) -> bool {
+		is_json_media_type(&self.media_type)
+	}
+
+	/// Returns the data as text, if it is valid UTF-8.
+	pub fn text(&self) -> Option<&str> {
+		std::str::from_utf8(&self.data).ok()
+	}
+
+	/// Returns the information about the embedded document
+	/// (its media type and profile).
+	pub fn info(&self) -> DocumentInfo {
+		DocumentInfo {
+			content_type: Some(self.media_type.clone()),
+			profile: self.parameter("profile").map(ToString::to_string),
+			context_url: None,
+		}
+	}
+}
+
+/// Checks if the given URL uses the `data` scheme.
+pub fn is_data_url(url: Iri) -> bool {
+	url.scheme().as_str().eq_ignore_ascii_case("data")
+}
+
+/// Parses the JSON document embedded in the given `data:` URL with the given parser.
+///
+/// Fails with a [`LoadingDocumentFailed`](ErrorCode::LoadingDocumentFailed) error
+/// if the URL is malformed, or if the embedded document is not a JSON document.
+pub fn load_data_url<J, P: ?Sized + FnMut(&str) -> Result<J, Error>>(
+	url: Iri,
+	parser: &mut P,
+) -> Result<(J, DocumentInfo), Error> {
+	let data_url = DataUrl::parse(url.as_str()).ok_or_else(|| {
+		Error::with_details(
+			ErrorCode::LoadingDocumentFailed,
+			ErrorDetails::Iri {
+				iri: url.as_str().to_string(),
+			},
+		)
+	})?;
+
+	if !data_url.is_json() {
+		return Err(Error::with_details(
+			ErrorCode::LoadingDocumentFailed,
+			ErrorDetails::UnexpectedValue {
+				expected: "a JSON media type".to_string(),
+				found: data_url.media_type,
+			},
+		));
+	}
+
+	let text = data_url
+		.text()
+		.ok_or_else(|| Error::from(ErrorCode::LoadingDocumentFailed))?;
+	let doc = (*parser)(text)?;
+	Ok((doc, data_url.info()))
+}
+
+fn percent_decode(s: &str) -> Option<Vec<u8>> {
+	let mut bytes = Vec::with_capacity(s.len());
+	let mut chars = s.bytes();
+	while let Some(b) = chars.next() {
+		if b == b'%' {
+			let hi = (chars.next()? as char).to_digit(16)?;
+			let lo = (chars.next()? as char).to_digit(16)?;
+			bytes.push((hi * 16 + lo) as u8)
+		} else {
+			bytes.push(b)
+		}
+	}
+
+	Some(bytes)
+}
+
+/// Decodes standard base64 data, ignoring whitespaces and allowing missing padding.
+fn base64_decode(data: &[u8]) -> Option<Vec<u8>> {
+	let mut bytes = Vec::with_capacity(data.len() * 3 / 4);
+	let mut buffer = 0u32;
+	let mut bits = 0;
+	let mut padding = false;
+	for &b in data {
+		let value = match b {
+			b'A'..=b'Z' => b - b'A',
+			b'a'..=b'z' => b - b'a' + 26,
+			b'0'..=b'9' => b - b'0' + 52,
+			b'+' => 62,
+			b'/' => 63,
+			b'=' => {
+				padding = true;
+				continue;
+			}
+			b if b.is_ascii_whitespace() => continue,
+			_ => return None,
+		};
+
+		if padding {
+			return None;
+		}
+
+		buffer = (buffer << 6) | value as u32;
+		bits += 6;
+		if bits >= 8 {
+			bits -= 8;
+			bytes.push((buffer >> bits) as u8);
+			buffer &= (1 << bits) - 1
+		}
+	}
+
+	Some(bytes)
+}
+
 /// JSON document loader.
 ///
 /// Each document is uniquely identified by the loader by a `u32`.
@@ -50,12 +430,580 @@ pub trait Loader {
 	fn iri(&self, id: Id) -> Option<Iri<'_>>;
 
 	/// Loads the document behind the given IRI.
+	///
+	/// Along with the document, the returned [`RemoteDocument`] may hold its
+	/// [version](RemoteDocument::version) and the [information](RemoteDocument::info)
+	/// given by the server, such as its media type or context URL.
+	///
+	/// The returned future is `Send`, unless the `local-futures` feature is enabled
+	/// (see [`util::future`](crate::util::future)).
 	fn load<'a>(
 		&'a mut self,
 		url: Iri<'_>,
 	) -> BoxFuture<'a, Result<RemoteDocument<Self::Document>, Error>>;
 }
 
+/// Negative results cache.
+///
+/// Remembers the URLs that failed to load so that a loader does not retry
+/// them for every document.
+/// A failing URL is not retried before its time-to-live expires.
+/// The time-to-live doubles with each consecutive failure,
+/// up to a maximum value.
+///
+/// Some URLs can be marked as fatal: once one of them failed,
+/// every subsequent load fails as well, aborting the whole batch.
+pub struct FailureCache {
+	ttl: Duration,
+	max_ttl: Duration,
+	fatal: HashSet<IriBuf>,
+	failures: HashMap<IriBuf, Failure>,
+	poisoned: Option<(IriBuf, ErrorCode)>,
+}
+
+/// Cached failure.
+struct Failure {
+	/// Number of consecutive failures.
+	attempts: u32,
+
+	/// Error code of the last failure.
+	code: ErrorCode,
+
+	/// Instant before which the URL must not be retried.
+	retry_at: Instant,
+}
+
+impl FailureCache {
+	/// Creates a new cache with the given initial time-to-live.
+	///
+	/// The maximum time-to-live defaults to 64 times the initial one.
+	pub fn new(ttl: Duration) -> Self {
+		Self {
+			ttl,
+			max_ttl: ttl * 64,
+			fatal: HashSet::new(),
+			failures: HashMap::new(),
+			poisoned: None,
+		}
+	}
+
+	/// Sets the maximum time-to-live reached through backoff.
+	#[must_use]
+	pub fn with_max_ttl(mut self, max_ttl: Duration) -> Self {
+		self.max_ttl = max_ttl;
+		self
+	}
+
+	/// Marks the given URL as fatal.
+	///
+	/// If it fails to load, every subsequent load will fail.
+	pub fn set_fatal(&mut self, url: Iri) {
+		self.fatal.insert(url.into());
+	}
+
+	/// Checks if the given URL is marked as fatal.
+	#[inline(always)]
+	pub fn is_fatal(&self, url: Iri) -> bool {
+		self.fatal.contains(&IriBuf::from(url))
+	}
+
+	/// Returns the fatal URL that failed, if any.
+	#[inline(always)]
+	pub fn poisoned(&self) -> Option<Iri> {
+		self.poisoned.as_ref().map(|(url, _)| url.as_iri())
+	}
+
+	/// Checks if the given URL can be loaded now.
+	///
+	/// Returns the cached error if a fatal URL failed,
+	/// or if the given URL failed and its time-to-live has not expired yet.
+	pub fn check(&self, url: Iri) -> Result<(), Error> {
+		if let Some((_, code)) = &self.poisoned {
+			return Err((*code).into());
+		}
+
+		match self.failures.get(&IriBuf::from(url)) {
+			Some(failure) if Instant::now() < failure.retry_at => Err(failure.code.into()),
+			_ => Ok(()),
+		}
+	}
+
+	/// Records a failure to load the given URL.
+	pub fn failed(&mut self, url: Iri, code: ErrorCode) {
+		let url = IriBuf::from(url);
+
+		if self.fatal.contains(&url) {
+			self.poisoned = Some((url.clone(), code))
+		}
+
+		let attempts = self.failures.get(&url).map(|f| f.attempts + 1).unwrap_or(1);
+		let ttl = self
+			.ttl
+			.checked_mul(1 << (attempts - 1).min(31))
+			.unwrap_or(self.max_ttl)
+			.min(self.max_ttl);
+
+		self.failures.insert(
+			url,
+			Failure {
+				attempts,
+				code,
+				retry_at: Instant::now() + ttl,
+			},
+		);
+	}
+
+	/// Records a successful load of the given URL, forgetting any previous failure.
+	pub fn succeeded(&mut self, url: Iri) {
+		self.failures.remove(&IriBuf::from(url));
+	}
+
+	/// Forgets every recorded failure, including fatal ones.
+	pub fn clear(&mut self) {
+		self.failures.clear();
+		self.poisoned = None
+	}
+}
+
+/// Security policy restricting the remote documents a loader may load.
+///
+/// Services processing untrusted JSON-LD documents should restrict the
+/// URLs of the remote documents and contexts they load,
+/// so that documents cannot make the service request arbitrary (internal) resources.
+///
+/// A URL is allowed if:
+///   - it uses the `https` scheme, when [`https_only`](LoadPolicy::https_only) is set;
+///   - it does not match any denied prefix;
+///   - it matches one of the allowed prefixes, unless no prefix is allowed explicitly;
+///   - the [filter](LoadPolicy::with_filter) function, if any, accepts it.
+///
+/// Prefixes are absolute IRIs matched component by component, not as strings:
+///   - the scheme and host must be equal, ignoring case.
+///     Hosts are compared after percent-decoding, and IPv4 and IPv6 addresses are compared
+///     as addresses, whatever their notation (`http://0x7f.1/` is `http://127.0.0.1/`).
+///     `localhost`, its subdomains and the loopback and unspecified addresses all match
+///     each other;
+///   - the port must be equal, the default port of the scheme being assumed when absent.
+///     A denied prefix without explicit port matches every port;
+///   - the path of the prefix must be a prefix of the path of the URL ending on a segment
+///     boundary (`/a` matches `/a` and `/a/b`, not `/ab`), after removing dot segments.
+///
+/// The user information, query and fragment of prefixes are ignored.
+/// Prefixes that are not absolute IRIs never match when allowed, and always match when
+/// denied.
+///
+/// Use [`PolicyLoader`] to enforce a policy on any loader.
+///
+/// ```
+/// use iref::Iri;
+/// use json_ld::loader::LoadPolicy;
+///
+/// let policy = LoadPolicy::new()
+///   .allow("https://example.org/contexts")
+///   .deny("https://example.org/contexts/private");
+///
+/// let allowed = |url: &str| policy.is_allowed(Iri::new(url).unwrap());
+/// assert!(allowed("https://EXAMPLE.org/contexts/v1"));
+/// assert!(!allowed("https://example.org/contexts-evil/v1"));
+/// assert!(!allowed("https://example.org.evil.com/contexts/v1"));
+/// assert!(!allowed("https://example.org/contexts/private/v1"));
+/// ```
+#[derive(Default)]
+pub struct LoadPolicy {
+	allowed: Vec<Option<UrlPrefix>>,
+	denied: Vec<Option<UrlPrefix>>,
+	https_only: bool,
+	filter: Option<Box<dyn 'static + Send + Sync + Fn(Iri) -> bool>>,
+}
+
+impl LoadPolicy {
+	/// Creates a new policy allowing every URL.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Allows the URLs matching the given prefix.
+	///
+	/// Once a prefix is allowed, URLs not matching any allowed prefix are rejected.
+	#[must_use]
+	pub fn allow(mut self, prefix: &str) -> Self {
+		self.allowed.push(UrlPrefix::parse(prefix, false));
+		self
+	}
+
+	/// Denies the URLs matching the given prefix.
+	///
+	/// Denied prefixes take precedence over allowed prefixes.
+	#[must_use]
+	pub fn deny(mut self, prefix: &str) -> Self {
+		self.denied.push(UrlPrefix::parse(prefix, true));
+		self
+	}
+
+	/// Only allows URLs with the `https` scheme.
+	#[must_use]
+	pub fn https_only(mut self) -> Self {
+		self.https_only = true;
+		self
+	}
+
+	/// Sets a function called before each load, that can veto it by returning `false`.
+	#[must_use]
+	pub fn with_filter(mut self, f: impl 'static + Send + Sync + Fn(Iri) -> bool) -> Self {
+		self.filter = Some(Box::new(f));
+		self
+	}
+
+	/// Checks if the given URL is allowed by the policy.
+	pub fn is_allowed(&self, url: Iri) -> bool {
+		let location = UrlPrefix::from_iri(url, false);
+		(!self.https_only || location.scheme == "https")
+			&& !self
+				.denied
+				.iter()
+				.any(|prefix| prefix.as_ref().map_or(true, |p| p.matches(&location)))
+			&& (self.allowed.is_empty()
+				|| self
+					.allowed
+					.iter()
+					.any(|prefix| prefix.as_ref().map_or(false, |p| p.matches(&location))))
+			&& self.filter.as_ref().map(|f| f(url)).unwrap_or(true)
+	}
+
+	/// Checks that the given URL is allowed by the policy.
+	///
+	/// Returns a `ForbiddenRemoteDocument` error otherwise.
+	pub fn check(&self, url: Iri) -> Result<(), Error> {
+		if self.is_allowed(url) {
+			Ok(())
+		} else {
+			Err(Error::with_details(
+				ErrorCode::ForbiddenRemoteDocument,
+				ErrorDetails::Iri {
+					iri: url.as_str().to_string(),
+				},
+			))
+		}
+	}
+}
+
+/// Normalized URL prefix of a [`LoadPolicy`] rule, or normalized URL.
+struct UrlPrefix {
+	/// Lowercase scheme.
+	scheme: String,
+
+	/// Host, if any.
+	host: Option<Host>,
+
+	/// Port, or `None` to match every port.
+	port: Option<u16>,
+
+	/// Path, without dot segments.
+	path: String,
+}
+
+impl UrlPrefix {
+	fn parse(prefix: &str, any_port: bool) -> Option<Self> {
+		Iri::new(prefix)
+			.ok()
+			.map(|iri| Self::from_iri(iri, any_port))
+	}
+
+	fn from_iri(iri: Iri, any_port: bool) -> Self {
+		let scheme = iri.scheme().as_str().to_ascii_lowercase();
+		let authority = iri.authority();
+		let explicit_port = authority
+			.as_ref()
+			.and_then(|a| a.port())
+			.and_then(|port| port.as_str().parse().ok());
+		let port = match explicit_port {
+			Some(port) => Some(port),
+			None if any_port => None,
+			None => default_port(&scheme),
+		};
+
+		Self {
+			host: authority.map(|a| Host::parse(a.host().as_str())),
+			port,
+			path: normalize_path(iri.path().as_str()),
+			scheme,
+		}
+	}
+
+	/// Checks if the given normalized URL matches this prefix.
+	fn matches(&self, url: &Self) -> bool {
+		let host_matches = match (&self.host, &url.host) {
+			(None, _) => true,
+			(Some(a), Some(b)) => a.matches(b),
+			(Some(_), None) => false,
+		};
+
+		self.scheme == url.scheme
+			&& host_matches
+			&& (self.port.is_none() || self.port == url.port)
+			&& path_matches(&self.path, &url.path)
+	}
+}
+
+fn default_port(scheme: &str) -> Option<u16> {
+	match scheme {
+		"http" | "ws" => Some(80),
+		"https" | "wss" => Some(443),
+		"ftp" => Some(21),
+		_ => None,
+	}
+}
+
+/// Normalized host.
+#[derive(PartialEq, Eq)]
+enum Host {
+	Name(String),
+	Ip(IpAddr),
+}
+
+impl Host {
+	fn parse(host: &str) -> Self {
+		let host = percent_decode(host).to_ascii_lowercase();
+		let host = host.strip_suffix('.').unwrap_or(&host);
+
+		if let Some(ip) = host.strip_prefix('[').and_then(|h| h.strip_suffix(']')) {
+			if let Ok(ip) = ip.parse::<Ipv6Addr>() {
+				return match ip.to_ipv4_mapped() {
+					Some(ip) => Self::Ip(IpAddr::V4(ip)),
+					None => Self::Ip(IpAddr::V6(ip)),
+				};
+			}
+		}
+
+		match parse_ipv4(host) {
+			Some(ip) => Self::Ip(IpAddr::V4(ip)),
+			None => Self::Name(host.to_string()),
+		}
+	}
+
+	fn is_local(&self) -> bool {
+		match self {
+			Self::Name(name) => name == "localhost" || name.ends_with(".localhost"),
+			Self::Ip(ip) => ip.is_loopback() || ip.is_unspecified(),
+		}
+	}
+
+	fn matches(&self, other: &Self) -> bool {
+		self == other || (self.is_local() && other.is_local())
+	}
+}
+
+/// Parses an IPv4 address the way web browsers do,
+/// accepting hexadecimal and octal parts, and less than four parts.
+fn parse_ipv4(host: &str) -> Option<Ipv4Addr> {
+	let mut numbers = Vec::new();
+	for part in host.split('.') {
+		let (digits, radix) = match part.strip_prefix("0x").or_else(|| part.strip_prefix("0X")) {
+			Some(hex) => (hex, 16),
+			None if part.len() > 1 && part.starts_with('0') => (&part[1..], 8),
+			None => (part, 10),
+		};
+
+		if (digits.is_empty() && radix != 16) || !digits.chars().all(|c| c.is_digit(radix)) {
+			return None;
+		}
+
+		let number = if digits.is_empty() {
+			0
+		} else {
+			u64::from_str_radix(digits, radix).ok()?
+		};
+
+		numbers.push(number);
+	}
+
+	let (last, init) = numbers.split_last()?;
+	if numbers.len() > 4
+		|| init.iter().any(|n| *n > 255)
+		|| *last >= 256u64.pow(5 - numbers.len() as u32)
+	{
+		return None;
+	}
+
+	let ip = init
+		.iter()
+		.enumerate()
+		.fold(*last, |ip, (i, n)| ip + (n << (8 * (3 - i))));
+	Some(Ipv4Addr::from(ip as u32))
+}
+
+fn percent_decode(s: &str) -> String {
+	let bytes = s.as_bytes();
+	let mut decoded = Vec::with_capacity(bytes.len());
+	let mut i = 0;
+	while i < bytes.len() {
+		let byte = match bytes.get(i + 1..i + 3) {
+			Some(hex) if bytes[i] == b'%' => std::str::from_utf8(hex)
+				.ok()
+				.and_then(|hex| u8::from_str_radix(hex, 16).ok()),
+			_ => None,
+		};
+
+		match byte {
+			Some(byte) => {
+				decoded.push(byte);
+				i += 3
+			}
+			None => {
+				decoded.push(bytes[i]);
+				i += 1
+			}
+		}
+	}
+
+	String::from_utf8_lossy(&decoded).into_owned()
+}
+
+/// Decodes the percent-encoded unreserved characters and removes the dot segments
+/// of the given path.
+fn normalize_path(path: &str) -> String {
+	let mut segments: Vec<String> = Vec::new();
+	let mut trailing_slash = false;
+	for segment in path.split('/') {
+		let segment = normalize_segment(segment);
+		trailing_slash = segment == "." || segment == "..";
+		match segment.as_str() {
+			"." => (),
+			".." => {
+				if segments.len() > 1 {
+					segments.pop();
+				}
+			}
+			_ => segments.push(segment),
+		}
+	}
+
+	if trailing_slash {
+		segments.push(String::new())
+	}
+
+	segments.join("/")
+}
+
+fn normalize_segment(segment: &str) -> String {
+	let mut result = String::with_capacity(segment.len());
+	let mut chars = segment.char_indices();
+	while let Some((i, c)) = chars.next() {
+		let unreserved = segment
+			.get(i + 1..i + 3)
+			.filter(|_| c == '%')
+			.and_then(|hex| u8::from_str_radix(hex, 16).ok())
+			.map(char::from)
+			.filter(|c| c.is_ascii_alphanumeric() || "-._~".contains(*c));
+
+		match unreserved {
+			Some(c) => {
+				result.push(c);
+				chars.nth(1);
+			}
+			None => result.push(c),
+		}
+	}
+
+	result
+}
+
+/// Checks if `prefix` is a prefix of `path` ending on a segment boundary.
+fn path_matches(prefix: &str, path: &str) -> bool {
+	match path.strip_prefix(prefix) {
+		Some(rest) => {
+			prefix.is_empty() || prefix.ends_with('/') || rest.is_empty() || rest.starts_with('/')
+		}
+		None => prefix == "/" && path.is_empty(),
+	}
+}
+
+/// Loader enforcing a [`LoadPolicy`].
+///
+/// Every load is checked against the policy before being delegated to the inner loader.
+/// If the inner loader reports the final URL of the document after redirections
+/// (see [`RemoteDocument::version`]), it is checked as well.
+/// Note however that the redirected request has already been performed at this point:
+/// the inner loader itself must be configured to restrict redirections
+/// if this is a concern.
+pub struct PolicyLoader<L> {
+	loader: L,
+	policy: LoadPolicy,
+}
+
+impl<L> PolicyLoader<L> {
+	/// Wraps the given loader to enforce the given policy.
+	pub fn new(loader: L, policy: LoadPolicy) -> Self {
+		Self { loader, policy }
+	}
+
+	/// Returns a reference to the policy.
+	pub fn policy(&self) -> &LoadPolicy {
+		&self.policy
+	}
+
+	/// Returns a mutable reference to the policy.
+	pub fn policy_mut(&mut self) -> &mut LoadPolicy {
+		&mut self.policy
+	}
+
+	/// Returns a reference to the inner loader.
+	pub fn inner(&self) -> &L {
+		&self.loader
+	}
+
+	/// Returns a mutable reference to the inner loader.
+	pub fn inner_mut(&mut self) -> &mut L {
+		&mut self.loader
+	}
+
+	/// Consumes the wrapper and returns the inner loader.
+	pub fn into_inner(self) -> L {
+		self.loader
+	}
+}
+
+impl<L: NonBlocking> NonBlocking for PolicyLoader<L> {}
+
+impl<L: Loader + Send> Loader for PolicyLoader<L> {
+	type Document = L::Document;
+
+	#[inline(always)]
+	fn id(&self, iri: Iri<'_>) -> Option<Id> {
+		self.loader.id(iri)
+	}
+
+	#[inline(always)]
+	fn iri(&self, id: Id) -> Option<Iri<'_>> {
+		self.loader.iri(id)
+	}
+
+	fn load<'a>(
+		&'a mut self,
+		url: Iri<'_>,
+	) -> BoxFuture<'a, Result<RemoteDocument<Self::Document>, Error>> {
+		let url = IriBuf::from(url);
+		async move {
+			self.policy.check(url.as_iri())?;
+			let doc = self.loader.load(url.as_iri()).await?;
+			if let Some(version) = doc.version() {
+				self.policy.check(version.url.as_iri())?
+			}
+
+			Ok(doc)
+		}
+		.boxed()
+	}
+}
+
+/// Marker trait for loaders that never wait on external events.
+///
+/// The futures returned by such loaders make progress every time they are polled,
+/// without relying on an asynchronous runtime (I/O reactor, timers, etc.).
+/// They can hence be driven by a minimal executor, which is what the blocking API
+/// (such as [`Document::expand_blocking`](crate::Document::expand_blocking)) does.
+pub trait NonBlocking {}
+
 /// Dummy loader.
 ///
 /// A dummy loader that does not load anything.
@@ -78,6 +1026,8 @@ impl<J> Default for NoLoader<J> {
 	}
 }
 
+impl<J> NonBlocking for NoLoader<J> {}
+
 impl<J: Json> Loader for NoLoader<J> {
 	type Document = J;
 
@@ -104,6 +1054,7 @@ impl<J: Json> Loader for NoLoader<J> {
 ///
 /// This is a special JSON-LD document loader that can load document from the file system by
 /// attaching a directory to specific URLs.
+/// It also loads the JSON documents embedded in `data:` URLs (see [`DataUrl`]).
 pub struct FsLoader<J> {
 	namespace: HashMap<IriBuf, Id>,
 	cache: Vec<(J, IriBuf)>,
@@ -149,6 +1100,9 @@ where
 	}
 }
 
+/// File system accesses are performed synchronously.
+impl<J> NonBlocking for FsLoader<J> {}
+
 impl<J: Json + Clone + Send> Loader for FsLoader<J> {
 	type Document = J;
 
@@ -171,6 +1125,11 @@ impl<J: Json + Clone + Send> Loader for FsLoader<J> {
 					url,
 					*id,
 				)),
+				None if is_data_url(url.as_iri()) => {
+					let (doc, info) = load_data_url(url.as_iri(), &mut *self.parser)?;
+					let id = self.allocate(url.clone(), doc.clone());
+					Ok(RemoteDocument::new(doc, url, id).with_info(info))
+				}
 				None => {
 					for (path, target_url) in &self.mount_points {
 						let url_ref = url.as_iri_ref();
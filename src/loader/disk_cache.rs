@@ -0,0 +1,440 @@
+use super::{is_data_url, DocumentInfo, Id, Loader, NonBlocking, Version};
+use crate::util::future::{BoxFuture, FutureExt};
+use crate::{Error, RemoteDocument};
+use iref::{Iri, IriBuf};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Loader wrapper caching the loaded documents on disk.
+///
+/// Documents loaded by the inner loader are written in the cache directory,
+/// so that subsequent runs do not load them again until they expire,
+/// and can work offline once the documents have been loaded.
+///
+/// The cache is content-addressed: the `documents` subdirectory holds one file
+/// per distinct document, named after the SHA-256 digest of its content,
+/// and the `index` subdirectory holds one entry per URL, named after the digest
+/// of the URL, recording the document digest, when it was loaded,
+/// and its [version](Version) and [information](DocumentInfo).
+///
+/// A cached document expires after the freshness lifetime given by the server
+/// (see [`Version::max_age`]) or, by default, after the time-to-live of the cache.
+/// Expired documents are loaded again, unless the cache is [offline](DiskCache::offline).
+/// If loading fails, the expired document is used anyway.
+/// Documents embedded in `data:` URLs are not cached.
+///
+/// Documents are written with their [`Display`](fmt::Display) implementation and
+/// read with their [`FromStr`] implementation: the metadata of a document read from
+/// the cache is given by the parser, and refers to the cached file.
+///
+/// ```
+/// use json_ld::{loader::DiskCache, FsLoader, Loader};
+/// use serde_json::Value;
+/// use static_iref::iri;
+/// use std::time::Duration;
+///
+/// let dir = std::env::temp_dir().join(format!("json-ld-disk-cache-{}", std::process::id()));
+/// std::fs::create_dir_all(dir.join("contexts")).unwrap();
+/// std::fs::write(
+///   dir.join("contexts/person.jsonld"),
+///   r#"{ "@context": { "name": "http://xmlns.com/foaf/0.1/name" } }"#,
+/// )
+/// .unwrap();
+///
+/// let url = iri!("https://example.com/person.jsonld");
+/// let ttl = Duration::from_secs(3600);
+///
+/// // First run: the document is loaded by the inner loader and cached.
+/// let mut inner = FsLoader::<Value>::default();
+/// inner.mount(iri!("https://example.com/"), dir.join("contexts"));
+/// let mut loader = DiskCache::new(inner, dir.join("cache"), ttl);
+/// let doc = async_std::task::block_on(loader.load(url)).unwrap();
+///
+/// // Next run: the document is read from the cache.
+/// std::fs::remove_dir_all(dir.join("contexts")).unwrap();
+/// let mut loader = DiskCache::new(FsLoader::<Value>::default(), dir.join("cache"), ttl);
+/// let cached = async_std::task::block_on(loader.load(url)).unwrap();
+/// assert_eq!(*cached, *doc);
+///
+/// loader.clear().unwrap();
+/// std::fs::remove_dir_all(dir).unwrap();
+/// ```
+pub struct DiskCache<L: Loader> {
+	loader: L,
+	directory: PathBuf,
+	ttl: Duration,
+	offline: bool,
+	namespace: HashMap<IriBuf, Id>,
+	documents: Vec<(IriBuf, RemoteDocument<L::Document>)>,
+}
+
+impl<L: Loader> DiskCache<L> {
+	/// Creates a new cache in the given directory, wrapping the given loader.
+	///
+	/// Documents expire after the given time-to-live, unless the server
+	/// specifies another freshness lifetime.
+	pub fn new(loader: L, directory: impl Into<PathBuf>, ttl: Duration) -> Self {
+		Self {
+			loader,
+			directory: directory.into(),
+			ttl,
+			offline: false,
+			namespace: HashMap::new(),
+			documents: Vec::new(),
+		}
+	}
+
+	/// Uses cached documents even when they are expired.
+	///
+	/// Documents not found in the cache are still loaded by the inner loader.
+	pub fn offline(mut self) -> Self {
+		self.offline = true;
+		self
+	}
+
+	/// Returns the cache directory.
+	pub fn directory(&self) -> &Path {
+		&self.directory
+	}
+
+	/// Returns the inner loader.
+	pub fn inner(&self) -> &L {
+		&self.loader
+	}
+
+	/// Returns a mutable reference to the inner loader.
+	pub fn inner_mut(&mut self) -> &mut L {
+		&mut self.loader
+	}
+
+	/// Returns the inner loader.
+	pub fn into_inner(self) -> L {
+		self.loader
+	}
+
+	/// Removes the cache entry of the given URL, if any.
+	///
+	/// The cached document itself is not removed, since it may be shared with other URLs.
+	pub fn remove(&self, url: Iri) -> io::Result<()> {
+		match fs::remove_file(self.entry_path(url)) {
+			Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+			_ => Ok(()),
+		}
+	}
+
+	/// Removes every cached document.
+	pub fn clear(&self) -> io::Result<()> {
+		for subdirectory in &["index", "documents"] {
+			match fs::remove_dir_all(self.directory.join(subdirectory)) {
+				Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(e),
+				_ => (),
+			}
+		}
+
+		Ok(())
+	}
+
+	fn entry_path(&self, url: Iri) -> PathBuf {
+		self.directory.join("index").join(digest(url.as_str()))
+	}
+
+	fn document_path(&self, digest: &str) -> PathBuf {
+		self.directory
+			.join("documents")
+			.join(format!("{}.json", digest))
+	}
+
+	/// Checks if the given entry has not expired.
+	fn is_fresh(&self, entry: &Entry) -> bool {
+		let lifetime = entry
+			.version
+			.as_ref()
+			.and_then(|version| version.max_age)
+			.unwrap_or(self.ttl);
+		now().saturating_sub(entry.loaded) < lifetime.as_secs()
+	}
+
+	/// Allocates an identifier to the given URL and document.
+	fn allocate(
+		&mut self,
+		url: IriBuf,
+		doc: RemoteDocument<L::Document>,
+	) -> RemoteDocument<L::Document>
+	where
+		L::Document: Clone,
+	{
+		let id = Id::new(self.documents.len());
+		let version = doc.version().cloned();
+		let info = doc.info().clone();
+		let (doc, _, base_url) = doc.into_parts();
+		let doc = RemoteDocument::new(doc, base_url, id)
+			.with_version(version)
+			.with_info(info);
+
+		self.namespace.insert(url.clone(), id);
+		self.documents.push((url, doc.clone()));
+		doc
+	}
+}
+
+impl<L: Loader> DiskCache<L>
+where
+	L::Document: fmt::Display + FromStr,
+{
+	/// Reads the cache entry of the given URL and its document.
+	fn read(&self, url: Iri) -> Option<(Entry, L::Document)> {
+		let entry: Entry = fs::read_to_string(self.entry_path(url))
+			.ok()?
+			.parse()
+			.ok()?;
+		if entry.url.as_iri() != url {
+			return None;
+		}
+
+		// The index may have been tampered with: only follow actual digests,
+		// so that the document path stays in the cache directory.
+		if !is_digest(&entry.document) {
+			log::warn!("invalid cache entry for `{}'", url);
+			return None;
+		}
+
+		let content = fs::read_to_string(self.document_path(&entry.document)).ok()?;
+		match content.parse() {
+			Ok(doc) => Some((entry, doc)),
+			Err(_) => {
+				log::warn!("invalid cached document for `{}'", url);
+				None
+			}
+		}
+	}
+
+	/// Writes the given document in the cache.
+	fn write(&self, url: Iri, doc: &RemoteDocument<L::Document>) -> io::Result<()> {
+		let content = (**doc).to_string();
+		let document = digest(&content);
+
+		let document_path = self.document_path(&document);
+		if !document_path.exists() {
+			write_atomically(&document_path, &content)?
+		}
+
+		let entry = Entry {
+			url: url.into(),
+			document,
+			loaded: now(),
+			base_url: doc.document_url().into(),
+			version: doc.version().cloned(),
+			info: doc.info().clone(),
+		};
+
+		write_atomically(&self.entry_path(url), &entry.to_string())
+	}
+}
+
+/// The cache directory is accessed synchronously.
+impl<L: Loader + NonBlocking> NonBlocking for DiskCache<L> {}
+
+impl<L: Loader + Send> Loader for DiskCache<L>
+where
+	L::Document: Clone + Send + fmt::Display + FromStr,
+{
+	type Document = L::Document;
+
+	#[inline(always)]
+	fn id(&self, iri: Iri<'_>) -> Option<Id> {
+		self.namespace.get(&IriBuf::from(iri)).cloned()
+	}
+
+	#[inline(always)]
+	fn iri(&self, id: Id) -> Option<Iri<'_>> {
+		self.documents.get(id.unwrap()).map(|(url, _)| url.as_iri())
+	}
+
+	fn load<'a>(
+		&'a mut self,
+		url: Iri<'_>,
+	) -> BoxFuture<'a, Result<RemoteDocument<Self::Document>, Error>> {
+		let url = IriBuf::from(url);
+		async move {
+			if let Some(id) = self.namespace.get(&url) {
+				return Ok(self.documents[id.unwrap()].1.clone());
+			}
+
+			let cacheable = !is_data_url(url.as_iri());
+			let cached = if cacheable {
+				self.read(url.as_iri())
+			} else {
+				None
+			};
+
+			let doc = match cached {
+				Some((entry, doc)) if self.offline || self.is_fresh(&entry) => {
+					entry.into_document(doc)
+				}
+				cached => match self.loader.load(url.as_iri()).await {
+					Ok(doc) => {
+						if cacheable {
+							if let Err(e) = self.write(url.as_iri(), &doc) {
+								log::warn!("unable to cache `{}': {}", url, e)
+							}
+						}
+
+						doc
+					}
+					Err(e) => match cached {
+						Some((entry, doc)) => {
+							log::warn!("unable to reload `{}', using the cached document", url);
+							entry.into_document(doc)
+						}
+						None => return Err(e),
+					},
+				},
+			};
+
+			Ok(self.allocate(url, doc))
+		}
+		.boxed()
+	}
+}
+
+/// Cache entry of a URL.
+///
+/// Entries are stored as lines of `name value` pairs.
+struct Entry {
+	url: IriBuf,
+	document: String,
+	loaded: u64,
+	base_url: IriBuf,
+	version: Option<Version>,
+	info: DocumentInfo,
+}
+
+impl Entry {
+	fn into_document<J>(self, doc: J) -> RemoteDocument<J> {
+		RemoteDocument::new(doc, self.base_url, Id::new(0))
+			.with_version(self.version)
+			.with_info(self.info)
+	}
+}
+
+impl fmt::Display for Entry {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		writeln!(f, "url {}", self.url)?;
+		writeln!(f, "document {}", self.document)?;
+		writeln!(f, "loaded {}", self.loaded)?;
+		writeln!(f, "base-url {}", self.base_url)?;
+
+		if let Some(version) = &self.version {
+			writeln!(f, "version-url {}", version.url)?;
+			if let Some(etag) = &version.etag {
+				writeln!(f, "etag {}", etag)?;
+			}
+			if let Some(last_modified) = &version.last_modified {
+				writeln!(f, "last-modified {}", last_modified)?;
+			}
+			if let Some(max_age) = version.max_age {
+				writeln!(f, "max-age {}", max_age.as_secs())?;
+			}
+		}
+
+		if let Some(content_type) = &self.info.content_type {
+			writeln!(f, "content-type {}", content_type)?;
+		}
+		if let Some(profile) = &self.info.profile {
+			writeln!(f, "profile {}", profile)?;
+		}
+		if let Some(context_url) = &self.info.context_url {
+			writeln!(f, "context-url {}", context_url)?;
+		}
+
+		Ok(())
+	}
+}
+
+/// Invalid cache entry.
+struct InvalidEntry;
+
+impl FromStr for Entry {
+	type Err = InvalidEntry;
+
+	fn from_str(s: &str) -> Result<Self, InvalidEntry> {
+		let mut fields = HashMap::new();
+		for line in s.lines() {
+			let (name, value) = line.split_once(' ').ok_or(InvalidEntry)?;
+			fields.insert(name, value);
+		}
+
+		let iri = |name: &str| match fields.get(name) {
+			Some(value) => IriBuf::new(value).map(Some).map_err(|_| InvalidEntry),
+			None => Ok(None),
+		};
+		let string = |name: &str| fields.get(name).map(|value| value.to_string());
+
+		let version = match iri("version-url")? {
+			Some(url) => Some(Version {
+				url,
+				etag: string("etag"),
+				last_modified: string("last-modified"),
+				max_age: match fields.get("max-age") {
+					Some(value) => Some(Duration::from_secs(
+						value.parse().map_err(|_| InvalidEntry)?,
+					)),
+					None => None,
+				},
+			}),
+			None => None,
+		};
+
+		Ok(Self {
+			url: iri("url")?.ok_or(InvalidEntry)?,
+			document: string("document").ok_or(InvalidEntry)?,
+			loaded: fields
+				.get("loaded")
+				.and_then(|value| value.parse().ok())
+				.ok_or(InvalidEntry)?,
+			base_url: iri("base-url")?.ok_or(InvalidEntry)?,
+			version,
+			info: DocumentInfo {
+				content_type: string("content-type"),
+				profile: string("profile"),
+				context_url: iri("context-url")?,
+			},
+		})
+	}
+}
+
+/// Returns the hexadecimal SHA-256 digest of the given string.
+fn digest(s: &str) -> String {
+	format!("{:x}", Sha256::digest(s.as_bytes()))
+}
+
+/// Checks that the given string is a SHA-256 digest as written by [`digest`]
+/// (64 lowercase hexadecimal digits).
+fn is_digest(s: &str) -> bool {
+	s.len() == 64 && s.bytes().all(|b| matches!(b, b'0'..=b'9' | b'a'..=b'f'))
+}
+
+/// Returns the current time, in seconds since the Unix epoch.
+fn now() -> u64 {
+	SystemTime::now()
+		.duration_since(UNIX_EPOCH)
+		.map_or(0, |d| d.as_secs())
+}
+
+/// Writes a file through a temporary file, so that concurrent readers never
+/// see a partially written file.
+fn write_atomically(path: &Path, content: &str) -> io::Result<()> {
+	if let Some(parent) = path.parent() {
+		fs::create_dir_all(parent)?
+	}
+
+	let tmp = path.with_extension(format!("{}.tmp", std::process::id()));
+	fs::write(&tmp, content)?;
+	fs::rename(&tmp, path)
+}
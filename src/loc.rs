@@ -1,4 +1,5 @@
 use crate::loader;
+use std::fmt;
 use std::ops::{Deref, DerefMut};
 
 /// Value located behind an IRI reference.
@@ -89,3 +90,31 @@ impl<T, M> AsMut<T> for Loc<T, M> {
 		&mut self.value
 	}
 }
+
+impl<T: fmt::Display, M> fmt::Display for Loc<T, M> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		self.value.fmt(f)
+	}
+}
+
+/// A located error is transparent: it displays as the inner error,
+/// and exposes the same source.
+impl<T: std::error::Error, M: fmt::Debug> std::error::Error for Loc<T, M> {
+	fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+		self.value.source()
+	}
+}
+
+/// Serializes the located value as an object with the `value`,
+/// the index of its `source` document (if any) and its `metadata`.
+#[cfg(feature = "serde")]
+impl<T: serde::Serialize, M: serde::Serialize> serde::Serialize for Loc<T, M> {
+	fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		use serde::ser::SerializeStruct;
+		let mut s = serializer.serialize_struct("Loc", 3)?;
+		s.serialize_field("value", &self.value)?;
+		s.serialize_field("source", &self.source.map(usize::from))?;
+		s.serialize_field("metadata", &self.metadata)?;
+		s.end()
+	}
+}
@@ -0,0 +1,150 @@
+use crate::{Error, ErrorCode};
+use cc_traits::MapIter;
+use generic_json::{Json, ValueRef};
+use std::fmt;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+/// Progress of a processing.
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub struct Progress {
+	/// Number of objects processed so far.
+	///
+	/// During expansion, this is the number of input JSON values.
+	/// During compaction, this is the number of node, value and list objects.
+	pub objects: usize,
+
+	/// Number of bytes of input consumed so far.
+	///
+	/// This is the length of the strings and object keys of the values
+	/// processed by the expansion algorithm, an approximation of the size of the input document
+	/// that can be compared to the size of its source.
+	/// Compaction does not consume bytes.
+	pub bytes: usize,
+}
+
+/// Progress monitor and cancellation token.
+///
+/// A monitor can be given to the expansion and compaction algorithms through
+/// their options (see [`expansion::Options::monitor`](crate::expansion::Options::monitor)
+/// and [`compaction::Options::monitor`](crate::compaction::Options::monitor)).
+/// It counts the processed objects, reports the progress to an optional callback,
+/// and is checked for cancellation at each object boundary.
+/// Once [`cancel`](Self::cancel) is called (possibly from another thread),
+/// the processing stops with a [`Cancelled`](ErrorCode::Cancelled) error.
+///
+/// The monitor is shared through an [`Arc`](std::sync::Arc),
+/// so that it can be kept by the caller to observe or cancel the processing,
+/// and reused by successive processings if [reset](Self::reset) in between.
+///
+/// ```
+/// use json_ld::Monitor;
+///
+/// let monitor = Monitor::with_callback(|progress| {
+///   println!("{} objects, {} bytes", progress.objects, progress.bytes)
+/// });
+///
+/// monitor.cancel();
+/// assert!(monitor.is_cancelled());
+/// ```
+#[derive(Default)]
+pub struct Monitor {
+	objects: AtomicUsize,
+	bytes: AtomicUsize,
+	cancelled: AtomicBool,
+	callback: Option<Box<dyn Fn(Progress) + Send + Sync>>,
+}
+
+impl Monitor {
+	/// Creates a new monitor without callback.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Creates a new monitor calling `callback` each time an object is processed.
+	///
+	/// The callback is called very often, and should be cheap.
+	pub fn with_callback(callback: impl Fn(Progress) + Send + Sync + 'static) -> Self {
+		Self {
+			callback: Some(Box::new(callback)),
+			..Self::default()
+		}
+	}
+
+	/// Returns the progress so far.
+	pub fn progress(&self) -> Progress {
+		Progress {
+			objects: self.objects.load(Ordering::Relaxed),
+			bytes: self.bytes.load(Ordering::Relaxed),
+		}
+	}
+
+	/// Requests the cancellation of the processing.
+	///
+	/// The processing stops at the next object boundary.
+	pub fn cancel(&self) {
+		self.cancelled.store(true, Ordering::Relaxed)
+	}
+
+	/// Checks if the cancellation has been requested.
+	pub fn is_cancelled(&self) -> bool {
+		self.cancelled.load(Ordering::Relaxed)
+	}
+
+	/// Resets the progress and cancellation state, so that the monitor can be reused.
+	pub fn reset(&self) {
+		self.objects.store(0, Ordering::Relaxed);
+		self.bytes.store(0, Ordering::Relaxed);
+		self.cancelled.store(false, Ordering::Relaxed)
+	}
+
+	/// Records the processing of one object consuming the given number of bytes.
+	///
+	/// Returns a `Cancelled` error if the cancellation has been requested.
+	pub(crate) fn advance(&self, bytes: usize) -> Result<(), Error> {
+		if self.is_cancelled() {
+			return Err(ErrorCode::Cancelled.into());
+		}
+
+		let progress = Progress {
+			objects: self.objects.fetch_add(1, Ordering::Relaxed) + 1,
+			bytes: self.bytes.fetch_add(bytes, Ordering::Relaxed) + bytes,
+		};
+
+		if let Some(callback) = &self.callback {
+			callback(progress)
+		}
+
+		Ok(())
+	}
+
+	/// Records the expansion of the given input value.
+	///
+	/// Nested values are recorded when they are expanded themselves.
+	pub(crate) fn advance_value<J: Json>(&self, value: &J) -> Result<(), Error> {
+		let bytes = match value.as_value_ref() {
+			ValueRef::String(s) => {
+				let s: &str = s.as_ref();
+				s.len()
+			}
+			ValueRef::Object(obj) => obj
+				.iter()
+				.map(|(key, _)| {
+					let key: &str = key.as_ref();
+					key.len()
+				})
+				.sum(),
+			_ => 0,
+		};
+
+		self.advance(bytes)
+	}
+}
+
+impl fmt::Debug for Monitor {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.debug_struct("Monitor")
+			.field("progress", &self.progress())
+			.field("cancelled", &self.is_cancelled())
+			.finish()
+	}
+}
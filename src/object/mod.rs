@@ -3,18 +3,23 @@
 pub mod node;
 pub mod value;
 
+#[cfg(not(feature = "std"))]
+use crate::prelude::*;
+use crate::util::collections::HashSet;
 use crate::{
 	lang::LenientLanguageTag,
 	syntax::Keyword,
 	util::{AsJson, JsonFrom},
 	Id, Indexed, Reference,
 };
-use generic_json::{JsonClone, JsonHash};
+use cc_traits::MapInsert;
+use core::hash::{Hash, Hasher};
+use generic_json::{Json, JsonClone, JsonHash};
 use iref::{Iri, IriBuf};
-use std::collections::HashSet;
-use std::hash::{Hash, Hasher};
 
-pub use node::{Node, Nodes};
+#[cfg(feature = "std")]
+pub use node::PlainValue;
+pub use node::{Graph, Node, Nodes};
 pub use value::{Literal, LiteralString, Value};
 
 pub trait Any<J: JsonHash, T: Id> {
@@ -79,20 +84,59 @@ pub enum Ref<'a, J: JsonHash, T: Id> {
 	List(&'a [Indexed<Object<J, T>>]),
 }
 
+/// Annotation of a property value (`@annotation` entry of the JSON-LD-star draft).
+///
+/// The annotation is stored beside the annotated object, in its [`Indexed`] wrapper
+/// (see [`Indexed::annotation`]).
+/// Its node objects describe the statement relating the annotated object
+/// to the node holding it as property value.
+///
+/// Embedded nodes used as `@id` (quoted triples) are not supported.
+pub type Annotation<J, T> = [Indexed<Object<J, T>>];
+
 /// Object.
 ///
 /// JSON-LD connects together multiple kinds of data objects.
 /// Objects may be nodes, values or lists of objects.
-#[derive(PartialEq, Eq)]
+///
+/// Value and list objects are given with the metadata of the JSON value they have been
+/// expanded from, if any (see [`Object::metadata`]).
+/// Node objects hold their own (see [`Node::metadata`]).
+/// Metadata is ignored when comparing or hashing objects.
 pub enum Object<J: JsonHash, T: Id = IriBuf> {
 	/// Value object.
-	Value(Value<J, T>),
+	Value(Value<J, T>, Option<J::MetaData>),
 
 	/// Node object.
 	Node(Node<J, T>),
 
 	/// List object.
-	List(Vec<Indexed<Self>>),
+	List(Vec<Indexed<Self>>, Option<J::MetaData>),
+}
+
+impl<J: JsonHash, T: Id> PartialEq for Object<J, T> {
+	#[inline]
+	fn eq(&self, other: &Self) -> bool {
+		match (self, other) {
+			(Self::Value(a, _), Self::Value(b, _)) => a == b,
+			(Self::Node(a), Self::Node(b)) => a == b,
+			(Self::List(a, _), Self::List(b, _)) => a == b,
+			_ => false,
+		}
+	}
+}
+
+impl<J: JsonHash, T: Id> Eq for Object<J, T> {}
+
+impl<J: JsonHash + JsonClone, T: Id> Clone for Object<J, T> {
+	#[inline(always)]
+	fn clone(&self) -> Self {
+		match self {
+			Self::Value(v, m) => Self::Value(v.clone(), m.clone()),
+			Self::Node(n) => Self::Node(n.clone()),
+			Self::List(l, m) => Self::List(l.clone(), m.clone()),
+		}
+	}
 }
 
 impl<J: JsonHash, T: Id> Object<J, T> {
@@ -120,14 +164,14 @@ impl<J: JsonHash, T: Id> Object<J, T> {
 	/// Tests if the object is a value.
 	#[inline(always)]
 	pub fn is_value(&self) -> bool {
-		matches!(self, Object::Value(_))
+		matches!(self, Object::Value(..))
 	}
 
 	/// Returns this object as a value, if it is one.
 	#[inline(always)]
 	pub fn as_value(&self) -> Option<&Value<J, T>> {
 		match self {
-			Self::Value(v) => Some(v),
+			Self::Value(v, _) => Some(v),
 			_ => None,
 		}
 	}
@@ -136,7 +180,7 @@ impl<J: JsonHash, T: Id> Object<J, T> {
 	#[inline(always)]
 	pub fn into_value(self) -> Option<Value<J, T>> {
 		match self {
-			Self::Value(v) => Some(v),
+			Self::Value(v, _) => Some(v),
 			_ => None,
 		}
 	}
@@ -165,6 +209,37 @@ impl<J: JsonHash, T: Id> Object<J, T> {
 		}
 	}
 
+	/// Returns the metadata of the JSON value this object has been expanded from, if known.
+	///
+	/// Objects synthesized by the processing algorithms (or built programmatically)
+	/// return `None`.
+	/// The metadata of objects expanded from a scalar value (such as the values of a
+	/// language map, or a string with a `@id` type mapping) is the metadata of this scalar.
+	#[inline(always)]
+	pub fn metadata(&self) -> Option<&J::MetaData> {
+		match self {
+			Self::Value(_, m) => m.as_ref(),
+			Self::Node(n) => n.metadata(),
+			Self::List(_, m) => m.as_ref(),
+		}
+	}
+
+	/// Sets the metadata of the JSON value this object comes from.
+	#[inline(always)]
+	pub fn set_metadata(&mut self, metadata: Option<J::MetaData>) {
+		match self {
+			Self::Value(_, m) => *m = metadata,
+			Self::Node(n) => n.set_metadata(metadata),
+			Self::List(_, m) => *m = metadata,
+		}
+	}
+
+	/// Checks if this object has been synthesized, meaning it has no source metadata.
+	#[inline(always)]
+	pub fn is_synthetic(&self) -> bool {
+		self.metadata().is_none()
+	}
+
 	/// Tests if the object is a graph object (a node with a `@graph` field).
 	#[inline(always)]
 	pub fn is_graph(&self) -> bool {
@@ -177,14 +252,23 @@ impl<J: JsonHash, T: Id> Object<J, T> {
 	/// Tests if the object is a list.
 	#[inline(always)]
 	pub fn is_list(&self) -> bool {
-		matches!(self, Object::List(_))
+		matches!(self, Object::List(..))
 	}
 
 	/// Returns this object as a list, if it is one.
 	#[inline(always)]
 	pub fn as_list(&self) -> Option<&[Indexed<Self>]> {
 		match self {
-			Self::List(l) => Some(l.as_slice()),
+			Self::List(l, _) => Some(l.as_slice()),
+			_ => None,
+		}
+	}
+
+	/// Returns this object as a mutable list, if it is one.
+	#[inline(always)]
+	pub fn as_list_mut(&mut self) -> Option<&mut Vec<Indexed<Self>>> {
+		match self {
+			Self::List(l, _) => Some(l),
 			_ => None,
 		}
 	}
@@ -193,11 +277,79 @@ impl<J: JsonHash, T: Id> Object<J, T> {
 	#[inline(always)]
 	pub fn into_list(self) -> Option<Vec<Indexed<Self>>> {
 		match self {
-			Self::List(l) => Some(l),
+			Self::List(l, _) => Some(l),
 			_ => None,
 		}
 	}
 
+	/// Returns the length of this list, or `None` if this object is not a list.
+	#[inline(always)]
+	pub fn list_len(&self) -> Option<usize> {
+		self.as_list().map(<[_]>::len)
+	}
+
+	/// Returns the item of this list at the given position.
+	///
+	/// Returns `None` if this object is not a list, or if `i` is out of bounds.
+	#[inline(always)]
+	pub fn list_item(&self, i: usize) -> Option<&Indexed<Self>> {
+		self.as_list().and_then(|items| items.get(i))
+	}
+
+	/// Maps the items of this list with the given function.
+	///
+	/// Nested lists are items like any other: they are not traversed.
+	/// If this object is not a list, it is returned unchanged.
+	pub fn map_list_items<F: FnMut(Indexed<Self>) -> Indexed<Self>>(self, f: F) -> Self {
+		match self {
+			Self::List(items, m) => Self::List(items.into_iter().map(f).collect(), m),
+			other => other,
+		}
+	}
+
+	/// Returns the values of this list, if it is a list of values only.
+	///
+	/// Returns `None` if this object is not a list, or if one of its items is
+	/// a node or a (nested) list.
+	///
+	/// ```
+	/// # use iref::IriBuf;
+	/// # use json_ld::{context, Document, NoLoader, Reference};
+	/// # use serde_json::Value;
+	/// let doc: Value = serde_json::from_str(r#"{
+	///   "http://example.org/p": { "@list": [ "a", "b", { "@list": [] } ] }
+	/// }"#).unwrap();
+	///
+	/// let mut loader = NoLoader::<Value>::new();
+	/// let expanded =
+	///   async_std::task::block_on(doc.expand::<context::Json<Value>, _>(&mut loader)).unwrap();
+	///
+	/// let node = expanded.main_node(None).unwrap();
+	/// let p = Reference::Id(IriBuf::new("http://example.org/p").unwrap());
+	/// let list = node.get_any(&p).unwrap();
+	/// assert_eq!(list.list_len(), Some(3));
+	/// assert_eq!(list.list_item(1).unwrap().as_str(), Some("b"));
+	///
+	/// // The last item is a nested list.
+	/// assert!(list.list_item(2).unwrap().is_list());
+	/// assert!(list.as_list_values().is_none());
+	/// ```
+	pub fn as_list_values(&self) -> Option<Vec<&Value<J, T>>> {
+		self.as_list()?.iter().map(|item| item.as_value()).collect()
+	}
+
+	/// Converts this list into its values, if it is a list of values only.
+	///
+	/// The indexes and annotations of the items are dropped.
+	/// Returns `None` if this object is not a list, or if one of its items is
+	/// a node or a (nested) list.
+	pub fn into_list_values(self) -> Option<Vec<Value<J, T>>> {
+		self.into_list()?
+			.into_iter()
+			.map(|item| item.into_inner().into_value())
+			.collect()
+	}
+
 	/// Get the object as a string.
 	///
 	/// If the object is a value that is a string, returns this string.
@@ -206,7 +358,7 @@ impl<J: JsonHash, T: Id> Object<J, T> {
 	#[inline(always)]
 	pub fn as_str(&self) -> Option<&str> {
 		match self {
-			Object::Value(value) => value.as_str(),
+			Object::Value(value, _) => value.as_str(),
 			Object::Node(node) => node.as_str(),
 			_ => None,
 		}
@@ -216,7 +368,7 @@ impl<J: JsonHash, T: Id> Object<J, T> {
 	#[inline(always)]
 	pub fn as_bool(&self) -> Option<bool> {
 		match self {
-			Object::Value(value) => value.as_bool(),
+			Object::Value(value, _) => value.as_bool(),
 			_ => None,
 		}
 	}
@@ -225,7 +377,7 @@ impl<J: JsonHash, T: Id> Object<J, T> {
 	#[inline(always)]
 	pub fn as_number(&self) -> Option<&J::Number> {
 		match self {
-			Object::Value(value) => value.as_number(),
+			Object::Value(value, _) => value.as_number(),
 			_ => None,
 		}
 	}
@@ -235,19 +387,121 @@ impl<J: JsonHash, T: Id> Object<J, T> {
 	#[inline(always)]
 	pub fn language(&self) -> Option<LenientLanguageTag> {
 		match self {
-			Object::Value(value) => value.language(),
+			Object::Value(value, _) => value.language(),
 			_ => None,
 		}
 	}
+
+	/// Calls `f` on every node reference appearing in the object, recursively.
+	///
+	/// See [`Node::for_each_reference`] for the list of visited references.
+	pub fn for_each_reference<F: FnMut(&Reference<T>)>(&self, f: &mut F) {
+		match self {
+			Object::Value(..) => (),
+			Object::Node(node) => node.for_each_reference(f),
+			Object::List(items, _) => {
+				for item in items {
+					item.for_each_reference(f)
+				}
+			}
+		}
+	}
+
+	/// Rebuilds the object by mapping every node reference with `f`, recursively.
+	///
+	/// See [`Node::for_each_reference`] for the list of visited references.
+	pub fn map_references<F: FnMut(Reference<T>) -> Reference<T>>(self, f: &mut F) -> Self {
+		self.map_with(&mut MapReferences(f))
+	}
+
+	/// Rebuilds the object by mapping every IRI with `f`, recursively.
+	///
+	/// Unlike [`map_references`](Self::map_references), the datatypes of typed literals
+	/// are also mapped. Blank node identifiers are left untouched.
+	pub fn map_iris<F: FnMut(T) -> T>(self, f: &mut F) -> Self {
+		self.map_with(&mut MapIris(f))
+	}
+
+	pub(crate) fn map_with<M: Mapping<T>>(self, m: &mut M) -> Self {
+		match self {
+			Object::Value(value, meta) => {
+				Object::Value(value.map_datatype(|ty| m.map_datatype(ty)), meta)
+			}
+			Object::Node(node) => Object::Node(node.map_with(m)),
+			Object::List(items, meta) => Object::List(
+				items
+					.into_iter()
+					.map(|item| item.map_inner(|item| item.map_with(m)))
+					.collect(),
+				meta,
+			),
+		}
+	}
+
+	/// Returns the normalized form of this object, recursively.
+	///
+	/// See [`Value::normalized`] and [`Node::normalized`].
+	pub fn normalized(self) -> Self {
+		match self {
+			Object::Value(value, meta) => Object::Value(value.normalized(), meta),
+			Object::Node(node) => Object::Node(node.normalized()),
+			Object::List(items, meta) => Object::List(
+				items
+					.into_iter()
+					.map(|item| item.map_inner(Object::normalized))
+					.collect(),
+				meta,
+			),
+		}
+	}
+
+	/// Converts this object into an object of another JSON type `K`,
+	/// using the given `meta` function to convert `J::MetaData` into `K::MetaData`.
+	///
+	/// This can be used to combine objects expanded from JSON documents
+	/// with different metadata types (see also [`Object::strip`]).
+	pub fn map_metadata<K: JsonFrom<J> + JsonHash>(
+		self,
+		meta: impl Clone + Fn(Option<&J::MetaData>) -> K::MetaData,
+	) -> Object<K, T>
+	where
+		J: JsonClone,
+	{
+		match self {
+			Object::Value(value, m) => {
+				let m = m.as_ref().map(|m| meta(Some(m)));
+				Object::Value(value.map_metadata(meta), m)
+			}
+			Object::Node(node) => Object::Node(node.map_metadata(meta)),
+			Object::List(items, m) => Object::List(
+				items
+					.into_iter()
+					.map(|item| item.map_inner(|item| item.map_metadata(meta.clone())))
+					.collect(),
+				m.as_ref().map(|m| meta(Some(m))),
+			),
+		}
+	}
+
+	/// Converts this object into an object of another JSON type `K`
+	/// without metadata.
+	#[inline(always)]
+	pub fn strip<K: JsonFrom<J> + JsonHash>(self) -> Object<K, T>
+	where
+		J: JsonClone,
+		K: Json<MetaData = ()>,
+	{
+		self.map_metadata(|_| ())
+	}
 }
 
 impl<J: JsonHash, T: Id> Hash for Object<J, T> {
 	#[inline]
 	fn hash<H: Hasher>(&self, h: &mut H) {
 		match self {
-			Self::Value(v) => v.hash(h),
+			Self::Value(v, _) => v.hash(h),
 			Self::Node(n) => n.hash(h),
-			Self::List(l) => l.hash(h),
+			Self::List(l, _) => l.hash(h),
 		}
 	}
 }
@@ -291,9 +545,9 @@ impl<J: JsonHash, T: Id> Any<J, T> for Object<J, T> {
 	#[inline(always)]
 	fn as_ref(&self) -> Ref<J, T> {
 		match self {
-			Object::Value(value) => Ref::Value(value),
+			Object::Value(value, _) => Ref::Value(value),
 			Object::Node(node) => Ref::Node(node),
-			Object::List(list) => Ref::List(list.as_ref()),
+			Object::List(list, _) => Ref::List(list.as_ref()),
 		}
 	}
 }
@@ -301,7 +555,7 @@ impl<J: JsonHash, T: Id> Any<J, T> for Object<J, T> {
 impl<J: JsonHash, T: Id> From<Value<J, T>> for Object<J, T> {
 	#[inline(always)]
 	fn from(value: Value<J, T>) -> Self {
-		Self::Value(value)
+		Self::Value(value, None)
 	}
 }
 
@@ -315,15 +569,15 @@ impl<J: JsonHash, T: Id> From<Node<J, T>> for Object<J, T> {
 impl<J: JsonHash + JsonClone, K: JsonFrom<J>, T: Id> AsJson<J, K> for Object<J, T> {
 	fn as_json_with(&self, meta: impl Clone + Fn(Option<&J::MetaData>) -> K::MetaData) -> K {
 		match self {
-			Object::Value(v) => v.as_json_with(meta),
+			Object::Value(v, m) => v.as_json_with_metadata(m.as_ref(), meta),
 			Object::Node(n) => n.as_json_with(meta),
-			Object::List(items) => {
+			Object::List(items, m) => {
 				let mut obj = K::Object::default();
 				obj.insert(
 					K::new_key(Keyword::List.into_str(), meta(None)),
 					items.as_json_with(meta.clone()),
 				);
-				K::object(obj, meta(None))
+				K::object(obj, meta(m.as_ref()))
 			}
 		}
 	}
@@ -342,12 +596,50 @@ impl<J: JsonHash + JsonClone, K: JsonFrom<J>, T: Id> AsJson<J, K>
 	}
 }
 
+/// Mapping of the node references and datatypes of an object.
+///
+/// See [`Object::map_references`] and [`Object::map_iris`].
+pub(crate) trait Mapping<T> {
+	fn map_reference(&mut self, r: Reference<T>) -> Reference<T>;
+
+	fn map_datatype(&mut self, ty: T) -> T;
+}
+
+/// Mapping of the node references only.
+pub(crate) struct MapReferences<'a, F>(pub &'a mut F);
+
+impl<'a, T, F: FnMut(Reference<T>) -> Reference<T>> Mapping<T> for MapReferences<'a, F> {
+	fn map_reference(&mut self, r: Reference<T>) -> Reference<T> {
+		(self.0)(r)
+	}
+
+	fn map_datatype(&mut self, ty: T) -> T {
+		ty
+	}
+}
+
+/// Mapping of every IRI, node references and datatypes.
+pub(crate) struct MapIris<'a, F>(pub &'a mut F);
+
+impl<'a, T, F: FnMut(T) -> T> Mapping<T> for MapIris<'a, F> {
+	fn map_reference(&mut self, r: Reference<T>) -> Reference<T> {
+		match r {
+			Reference::Id(id) => Reference::Id((self.0)(id)),
+			r => r,
+		}
+	}
+
+	fn map_datatype(&mut self, ty: T) -> T {
+		(self.0)(ty)
+	}
+}
+
 /// Iterator through indexed objects.
-pub struct Objects<'a, J: JsonHash, T: Id>(Option<std::slice::Iter<'a, Indexed<Object<J, T>>>>);
+pub struct Objects<'a, J: JsonHash, T: Id>(Option<core::slice::Iter<'a, Indexed<Object<J, T>>>>);
 
 impl<'a, J: JsonHash, T: Id> Objects<'a, J, T> {
 	#[inline(always)]
-	pub(crate) fn new(inner: Option<std::slice::Iter<'a, Indexed<Object<J, T>>>>) -> Self {
+	pub(crate) fn new(inner: Option<core::slice::Iter<'a, Indexed<Object<J, T>>>>) -> Self {
 		Self(inner)
 	}
 }
@@ -363,3 +655,71 @@ impl<'a, J: JsonHash, T: Id> Iterator for Objects<'a, J, T> {
 		}
 	}
 }
+
+/// Iterator over the node objects reachable from a set of objects.
+///
+/// Nested nodes are visited as well, in depth-first order:
+/// property values, list items, graphs, included nodes, annotations and reverse properties.
+pub struct Traverse<'a, J: JsonHash, T: Id> {
+	stack: Vec<Ref<'a, J, T>>,
+}
+
+impl<'a, J: JsonHash, T: Id> Traverse<'a, J, T> {
+	/// Creates a new iterator visiting the given objects and their nested nodes.
+	#[inline(always)]
+	pub fn new<I: IntoIterator<Item = &'a Indexed<Object<J, T>>>>(objects: I) -> Self {
+		Self {
+			stack: objects
+				.into_iter()
+				.map(|object| Any::as_ref(object.inner()))
+				.collect(),
+		}
+	}
+}
+
+impl<'a, J: JsonHash, T: Id> Iterator for Traverse<'a, J, T> {
+	type Item = &'a Node<J, T>;
+
+	fn next(&mut self) -> Option<&'a Node<J, T>> {
+		while let Some(object) = self.stack.pop() {
+			match object {
+				Ref::Value(_) => (),
+				Ref::List(items) => self
+					.stack
+					.extend(items.iter().rev().map(|item| Any::as_ref(item.inner()))),
+				Ref::Node(node) => {
+					if let Some(graph) = node.graph() {
+						self.stack
+							.extend(graph.iter().map(|item| Any::as_ref(item.inner())))
+					}
+
+					if let Some(included) = node.included() {
+						self.stack
+							.extend(included.iter().map(|item| Ref::Node(item.inner())))
+					}
+
+					for (_, objects) in node.properties() {
+						for item in objects {
+							if let Some(annotation) = item.annotation() {
+								self.stack
+									.extend(annotation.iter().map(|item| Any::as_ref(item.inner())))
+							}
+						}
+
+						self.stack
+							.extend(objects.iter().map(|item| Any::as_ref(item.inner())))
+					}
+
+					for (_, nodes) in node.reverse_properties() {
+						self.stack
+							.extend(nodes.iter().map(|item| Ref::Node(item.inner())))
+					}
+
+					return Some(node);
+				}
+			}
+		}
+
+		None
+	}
+}
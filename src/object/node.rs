@@ -1,21 +1,34 @@
+#[cfg(not(feature = "std"))]
+use crate::prelude::*;
+use crate::util::collections::{HashMap, HashSet};
 use crate::{
+	lang::LenientLanguageTag,
 	object,
 	syntax::{Keyword, Term},
-	util, Id, Indexed, Object, Objects, Reference, ToReference,
+	util, Id, Indexed, Object, Objects, Reference, ToReference, Value,
 };
 use cc_traits::MapInsert;
+use core::borrow::Borrow;
+use core::convert::TryFrom;
+use core::hash::{Hash, Hasher};
 use generic_json::{JsonClone, JsonHash};
 use iref::{Iri, IriBuf};
-use std::collections::HashSet;
-use std::convert::TryFrom;
-use std::hash::{Hash, Hasher};
 
+#[cfg(feature = "std")]
+pub mod coercion;
 pub mod properties;
 pub mod reverse_properties;
 
+#[cfg(feature = "std")]
+pub use coercion::PlainValue;
 pub use properties::Properties;
 pub use reverse_properties::ReverseProperties;
 
+/// Graph of a node object.
+///
+/// This is the value of the `@graph` entry of a node object.
+pub type Graph<J, T> = HashSet<Indexed<Object<J, T>>>;
+
 /// Node object.
 ///
 /// A node object represents zero or more properties of a node in the graph serialized by a JSON-LD document.
@@ -24,7 +37,6 @@ pub use reverse_properties::ReverseProperties;
 /// (`@included` field).
 // NOTE it may be better to use BTreeSet instead of HashSet to have some ordering?
 //      in which case the Json bound should be lifted.
-#[derive(PartialEq, Eq)]
 pub struct Node<J: JsonHash, T: Id = IriBuf> {
 	/// Identifier.
 	///
@@ -39,7 +51,7 @@ pub struct Node<J: JsonHash, T: Id = IriBuf> {
 	/// Associated graph.
 	///
 	/// This is the `@graph` field.
-	pub(crate) graph: Option<HashSet<Indexed<Object<J, T>>>>,
+	pub(crate) graph: Option<Graph<J, T>>,
 
 	/// Included nodes.
 	///
@@ -55,6 +67,47 @@ pub struct Node<J: JsonHash, T: Id = IriBuf> {
 	///
 	/// This is the `@reverse` field.
 	pub(crate) reverse_properties: ReverseProperties<J, T>,
+
+	/// Metadata of the JSON value this node has been expanded from.
+	///
+	/// Ignored when comparing or hashing nodes.
+	pub(crate) metadata: Option<J::MetaData>,
+
+	/// Custom keyword entries, kept by the
+	/// [keyword extension](crate::expansion::KeywordExtension) during expansion.
+	///
+	/// Ignored when comparing or hashing nodes.
+	pub(crate) custom_keywords: Vec<(String, J)>,
+}
+
+impl<J: JsonHash, T: Id> PartialEq for Node<J, T> {
+	#[inline]
+	fn eq(&self, other: &Self) -> bool {
+		self.id == other.id
+			&& self.types == other.types
+			&& self.graph == other.graph
+			&& self.included == other.included
+			&& self.properties == other.properties
+			&& self.reverse_properties == other.reverse_properties
+	}
+}
+
+impl<J: JsonHash, T: Id> Eq for Node<J, T> {}
+
+impl<J: JsonHash + JsonClone, T: Id> Clone for Node<J, T> {
+	#[inline(always)]
+	fn clone(&self) -> Self {
+		Self {
+			id: self.id.clone(),
+			types: self.types.clone(),
+			graph: self.graph.clone(),
+			included: self.included.clone(),
+			properties: self.properties.clone(),
+			reverse_properties: self.reverse_properties.clone(),
+			metadata: self.metadata.clone(),
+			custom_keywords: self.custom_keywords.clone(),
+		}
+	}
 }
 
 impl<J: JsonHash, T: Id> Default for Node<J, T> {
@@ -75,6 +128,8 @@ impl<J: JsonHash, T: Id> Node<J, T> {
 			included: None,
 			properties: Properties::new(),
 			reverse_properties: ReverseProperties::new(),
+			metadata: None,
+			custom_keywords: Vec::new(),
 		}
 	}
 
@@ -88,6 +143,34 @@ impl<J: JsonHash, T: Id> Node<J, T> {
 			included: None,
 			properties: Properties::new(),
 			reverse_properties: ReverseProperties::new(),
+			metadata: None,
+			custom_keywords: Vec::new(),
+		}
+	}
+
+	/// Creates a new graph object with the given id and graph.
+	///
+	/// ```
+	/// # use json_ld::{Indexed, Node, Object, Reference};
+	/// # use iref::IriBuf;
+	/// let id = Reference::Id(IriBuf::new("https://example.com/graph").unwrap());
+	/// let mut graph: Node<serde_json::Value> = Node::new_graph(Some(id), Default::default());
+	/// assert!(graph.is_graph());
+	///
+	/// let alice = Reference::Id(IriBuf::new("https://example.com/alice").unwrap());
+	/// graph.insert_in_graph(Indexed::new(Object::Node(Node::with_id(alice)), None));
+	/// assert_eq!(graph.graph().unwrap().len(), 1);
+	///
+	/// let (id, objects) = graph.into_graph().ok().unwrap();
+	/// assert!(id.is_some());
+	/// assert_eq!(objects.len(), 1);
+	/// ```
+	#[inline(always)]
+	pub fn new_graph(id: Option<Reference<T>>, graph: Graph<J, T>) -> Self {
+		Self {
+			id,
+			graph: Some(graph),
+			..Self::new()
 		}
 	}
 
@@ -116,6 +199,56 @@ impl<J: JsonHash, T: Id> Node<J, T> {
 		}
 	}
 
+	/// Returns the metadata of the JSON value this node has been expanded from.
+	///
+	/// Returns `None` if the node has been synthesized by the processing algorithms
+	/// (or built programmatically) rather than expanded from an input document.
+	/// The metadata of nodes expanded from a string value (with a `@id` or `@vocab` type
+	/// mapping) is the metadata of this string.
+	#[inline(always)]
+	pub fn metadata(&self) -> Option<&J::MetaData> {
+		self.metadata.as_ref()
+	}
+
+	/// Sets the metadata of the JSON value this node comes from.
+	#[inline(always)]
+	pub fn set_metadata(&mut self, metadata: Option<J::MetaData>) {
+		self.metadata = metadata
+	}
+
+	/// Returns the custom keyword entries of this node, with their value.
+	///
+	/// Those are the entries kept by the
+	/// [keyword extension](crate::expansion::KeywordExtension) during expansion,
+	/// in the order they appear in the input document.
+	/// They are not part of the graph: they are ignored when comparing nodes,
+	/// and dropped by the node map generation and the RDF serialization.
+	#[inline(always)]
+	pub fn custom_keywords(&self) -> &[(String, J)] {
+		&self.custom_keywords
+	}
+
+	/// Returns the value of the given custom keyword entry, if any.
+	#[inline(always)]
+	pub fn custom_keyword(&self, keyword: &str) -> Option<&J> {
+		self.custom_keywords
+			.iter()
+			.find(|(k, _)| k == keyword)
+			.map(|(_, value)| value)
+	}
+
+	/// Adds a custom keyword entry to this node.
+	#[inline(always)]
+	pub fn insert_custom_keyword(&mut self, keyword: String, value: J) {
+		self.custom_keywords.push((keyword, value))
+	}
+
+	/// Checks if this node has been synthesized, meaning it has no source metadata.
+	#[inline(always)]
+	pub fn is_synthetic(&self) -> bool {
+		self.metadata.is_none()
+	}
+
 	/// Get the identifier of the node.
 	///
 	/// This correspond to the `@id` field of the JSON object.
@@ -168,6 +301,23 @@ impl<J: JsonHash, T: Id> Node<J, T> {
 		false
 	}
 
+	/// Checks if the node has one of the given types.
+	#[inline]
+	pub fn has_type_in(&self, types: &HashSet<Reference<T>>) -> bool {
+		self.types.iter().any(|ty| types.contains(ty))
+	}
+
+	/// Checks if the node has the given type or one of its subclasses,
+	/// according to the given type hierarchy.
+	#[inline]
+	pub fn has_type_transitive<H: TypeHierarchy<T>>(
+		&self,
+		ty: &Reference<T>,
+		hierarchy: &H,
+	) -> bool {
+		self.has_type_in(&hierarchy.sub_classes_transitive(ty))
+	}
+
 	/// Tests if the node is empty.
 	///
 	/// It is empty is every field other than `@id` is empty.
@@ -200,22 +350,56 @@ impl<J: JsonHash, T: Id> Node<J, T> {
 
 	/// If the node is a graph object, get the graph.
 	#[inline(always)]
-	pub fn graph(&self) -> Option<&HashSet<Indexed<Object<J, T>>>> {
+	pub fn graph(&self) -> Option<&Graph<J, T>> {
 		self.graph.as_ref()
 	}
 
 	/// If the node is a graph object, get the mutable graph.
 	#[inline(always)]
-	pub fn graph_mut(&mut self) -> Option<&mut HashSet<Indexed<Object<J, T>>>> {
+	pub fn graph_mut(&mut self) -> Option<&mut Graph<J, T>> {
 		self.graph.as_mut()
 	}
 
+	/// Get the mutable graph, adding an empty `@graph` entry to the node first
+	/// if it has none.
+	#[inline(always)]
+	pub fn get_or_insert_graph(&mut self) -> &mut Graph<J, T> {
+		self.graph.get_or_insert_with(HashSet::new)
+	}
+
 	/// Set the graph.
 	#[inline(always)]
-	pub fn set_graph(&mut self, graph: Option<HashSet<Indexed<Object<J, T>>>>) {
+	pub fn set_graph(&mut self, graph: Option<Graph<J, T>>) {
 		self.graph = graph
 	}
 
+	/// Removes the `@graph` entry of the node, and returns the graph.
+	#[inline(always)]
+	pub fn take_graph(&mut self) -> Option<Graph<J, T>> {
+		self.graph.take()
+	}
+
+	/// Adds an object to the graph of the node,
+	/// adding an empty `@graph` entry to the node first if it has none.
+	///
+	/// Returns `false` if the graph already contained this object.
+	#[inline(always)]
+	pub fn insert_in_graph(&mut self, object: Indexed<Object<J, T>>) -> bool {
+		self.get_or_insert_graph().insert(object)
+	}
+
+	/// Removes an object from the graph of the node.
+	///
+	/// Returns `false` if the graph did not contain this object.
+	/// The `@graph` entry is kept, even if the graph becomes empty.
+	#[inline(always)]
+	pub fn remove_from_graph(&mut self, object: &Indexed<Object<J, T>>) -> bool {
+		match &mut self.graph {
+			Some(graph) => graph.remove(object),
+			None => false,
+		}
+	}
+
 	/// Get the set of nodes included by this node.
 	///
 	/// This correspond to the `@included` field in the JSON representation.
@@ -244,12 +428,48 @@ impl<J: JsonHash, T: Id> Node<J, T> {
 		&self.properties
 	}
 
+	/// Returns a mutable reference to the properties of the node.
+	#[inline(always)]
+	pub fn properties_mut(&mut self) -> &mut Properties<J, T> {
+		&mut self.properties
+	}
+
 	/// Returns a reference to the reverse properties of the node.
+	///
+	/// This is the `@reverse` field: each reverse property is associated to the nodes
+	/// having this node as value of the property.
 	#[inline(always)]
 	pub fn reverse_properties(&self) -> &ReverseProperties<J, T> {
 		&self.reverse_properties
 	}
 
+	/// Returns a mutable reference to the reverse properties of the node.
+	#[inline(always)]
+	pub fn reverse_properties_mut(&mut self) -> &mut ReverseProperties<J, T> {
+		&mut self.reverse_properties
+	}
+
+	/// Get all the nodes associated to the node with the given reverse property.
+	#[inline(always)]
+	pub fn get_reverse<'a, Q: ToReference<T>>(&self, prop: Q) -> Nodes<J, T>
+	where
+		T: 'a,
+	{
+		self.reverse_properties.get(prop)
+	}
+
+	/// Get one of the nodes associated to the node with the given reverse property.
+	///
+	/// If multiple nodes are attached to the node with this reverse property,
+	/// there are no guaranties on which node will be returned.
+	#[inline(always)]
+	pub fn get_any_reverse<'a, Q: ToReference<T>>(&self, prop: Q) -> Option<&Indexed<Self>>
+	where
+		T: 'a,
+	{
+		self.reverse_properties.get_any(prop)
+	}
+
 	/// Get all the objects associated to the node with the given property.
 	#[inline(always)]
 	pub fn get<'a, Q: ToReference<T>>(&self, prop: Q) -> Objects<J, T>
@@ -271,6 +491,171 @@ impl<J: JsonHash, T: Id> Node<J, T> {
 		self.properties.get_any(prop)
 	}
 
+	/// Get the strings associated to the node with the given property,
+	/// grouped by language tag.
+	///
+	/// This is the expanded counterpart of a property using a `@language` container.
+	/// Strings without language tag are grouped under the `None` key.
+	/// Other objects are ignored.
+	pub fn get_language_map<'a, Q: ToReference<T>>(
+		&'a self,
+		prop: Q,
+	) -> HashMap<Option<LenientLanguageTag<'a>>, Vec<&'a str>>
+	where
+		T: 'a,
+	{
+		let mut map: HashMap<_, Vec<_>> = HashMap::new();
+
+		for object in self.get(prop) {
+			match object.as_value() {
+				Some(Value::LangString(s)) => map.entry(s.language()).or_default().push(s.as_str()),
+				Some(value @ Value::Literal(_, None)) => {
+					if let Some(s) = value.as_str() {
+						map.entry(None).or_default().push(s)
+					}
+				}
+				_ => (),
+			}
+		}
+
+		map
+	}
+
+	/// Get the objects associated to the node with the given property,
+	/// grouped by `@index` value.
+	///
+	/// This is the expanded counterpart of a property using an `@index` container.
+	/// Objects without index are ignored.
+	pub fn get_index_map<'a, Q: ToReference<T>>(
+		&'a self,
+		prop: Q,
+	) -> HashMap<&'a str, Vec<&'a Indexed<Object<J, T>>>>
+	where
+		T: 'a,
+	{
+		let mut map: HashMap<_, Vec<_>> = HashMap::new();
+
+		for object in self.get(prop) {
+			if let Some(index) = object.index() {
+				map.entry(index).or_default().push(object)
+			}
+		}
+
+		map
+	}
+
+	/// Get the objects associated to the node with any property,
+	/// grouped by `@index` value.
+	///
+	/// Each object is given with its property.
+	/// Objects without index are ignored.
+	pub fn entries_by_index(&self) -> HashMap<&str, Vec<(&Reference<T>, &Indexed<Object<J, T>>)>> {
+		let mut map: HashMap<_, Vec<_>> = HashMap::new();
+
+		for (prop, objects) in self.properties.iter() {
+			for object in objects {
+				if let Some(index) = object.index() {
+					map.entry(index).or_default().push((prop, object))
+				}
+			}
+		}
+
+		map
+	}
+
+	/// Get the strings associated to the node with the given property
+	/// in the given language.
+	///
+	/// Language tags are matched using the BCP47 lookup scheme (RFC 4647):
+	/// tags are compared case-insensitively, and if no string is found
+	/// for the requested tag, its last subtag is removed and the lookup is
+	/// repeated (`en-US` falls back to `en`).
+	/// Returns an empty list if no string matches.
+	pub fn get_in_language<'a, Q: ToReference<T>>(&'a self, prop: Q, lang: &str) -> Vec<&'a str>
+	where
+		T: 'a,
+	{
+		let map = self.get_language_map(prop);
+		let mut range = lang;
+
+		loop {
+			let strings: Vec<&'a str> = map
+				.iter()
+				.filter(|(tag, _)| match tag {
+					Some(tag) => tag.as_str().eq_ignore_ascii_case(range),
+					None => false,
+				})
+				.flat_map(|(_, strings)| strings.iter().cloned())
+				.collect();
+
+			if !strings.is_empty() {
+				return strings;
+			}
+
+			match range.rfind('-') {
+				Some(i) => {
+					range = &range[..i];
+
+					// Single character subtags cannot end a range.
+					if range.len() > 1 && range.as_bytes()[range.len() - 2] == b'-' {
+						range = &range[..(range.len() - 2)]
+					}
+				}
+				None => return strings,
+			}
+		}
+	}
+
+	/// Selects the objects reached from this node by following the given property path.
+	///
+	/// Each step follows a property of the nodes selected by the previous step.
+	/// Nested nodes are walked through, but node references are not resolved.
+	/// The items of lists are selected in place of the lists themselves.
+	/// Objects are returned in order, without removing duplicates.
+	///
+	/// ```
+	/// use async_std::task;
+	/// use iref::IriBuf;
+	/// use json_ld::{context, Document, NoLoader, Reference};
+	/// use serde_json::Value;
+	///
+	/// let doc: Value = serde_json::from_str(r#"
+	///   {
+	///     "@context": { "@vocab": "https://schema.org/" },
+	///     "@id": "https://example.com/book",
+	///     "author": { "name": "Jane" }
+	///   }
+	/// "#).unwrap();
+	///
+	/// let mut loader = NoLoader::<Value>::new();
+	/// let expanded_doc = task::block_on(doc.expand::<context::Json<Value>, _>(&mut loader)).unwrap();
+	/// let book = expanded_doc.iter().next().unwrap().as_node().unwrap();
+	///
+	/// let author = Reference::Id(IriBuf::new("https://schema.org/author").unwrap());
+	/// let name = Reference::Id(IriBuf::new("https://schema.org/name").unwrap());
+	/// let names = book.select(&[&author, &name]);
+	/// assert_eq!(names[0].as_str(), Some("Jane"))
+	/// ```
+	pub fn select<'a, Q: ToReference<T>>(&'a self, path: &[Q]) -> Vec<&'a Indexed<Object<J, T>>> {
+		let mut selection = Vec::new();
+		self.select_into(path, &mut selection);
+		selection
+	}
+
+	fn select_into<'a, Q: ToReference<T>>(
+		&'a self,
+		path: &[Q],
+		selection: &mut Vec<&'a Indexed<Object<J, T>>>,
+	) {
+		if let Some((step, rest)) = path.split_first() {
+			let step = step.to_ref();
+			let step: &Reference<T> = step.borrow();
+			for object in self.get(step) {
+				select(object, rest, selection)
+			}
+		}
+	}
+
 	/// Associates the given object to the node through the given property.
 	#[inline(always)]
 	pub fn insert(&mut self, prop: Reference<T>, value: Indexed<Object<J, T>>) {
@@ -329,13 +714,220 @@ impl<J: JsonHash, T: Id> Node<J, T> {
 	/// The unnamed graph is returned as a set of indexed objects.
 	/// Fails and returns itself if the node is *not* an unnamed graph.
 	#[inline(always)]
-	pub fn into_unnamed_graph(self) -> Result<HashSet<Indexed<Object<J, T>>>, Self> {
+	pub fn into_unnamed_graph(self) -> Result<Graph<J, T>, Self> {
 		if self.is_unnamed_graph() {
 			Ok(self.graph.unwrap())
 		} else {
 			Err(self)
 		}
 	}
+
+	/// Returns the identifier and graph of the node, if it is a graph object
+	/// (see [`is_graph`](Self::is_graph)).
+	///
+	/// Fails and returns itself if the node is *not* a graph object.
+	#[inline(always)]
+	pub fn into_graph(self) -> Result<(Option<Reference<T>>, Graph<J, T>), Self> {
+		if self.is_graph() {
+			Ok((self.id, self.graph.unwrap()))
+		} else {
+			Err(self)
+		}
+	}
+
+	/// Calls `f` on every node reference appearing in this node, recursively.
+	///
+	/// This includes the node identifier, types, properties, reverse properties
+	/// and the references appearing in the graph, included nodes, property values
+	/// and their annotations.
+	pub fn for_each_reference<F: FnMut(&Reference<T>)>(&self, f: &mut F) {
+		if let Some(id) = &self.id {
+			f(id)
+		}
+
+		for ty in &self.types {
+			f(ty)
+		}
+
+		if let Some(graph) = &self.graph {
+			for object in graph {
+				object.for_each_reference(f)
+			}
+		}
+
+		if let Some(included) = &self.included {
+			for node in included {
+				node.for_each_reference(f)
+			}
+		}
+
+		for (prop, objects) in &self.properties {
+			f(prop);
+			for object in objects {
+				object.for_each_reference(f);
+				for node in object.annotation().into_iter().flatten() {
+					node.for_each_reference(f)
+				}
+			}
+		}
+
+		for (prop, nodes) in &self.reverse_properties {
+			f(prop);
+			for node in nodes {
+				node.for_each_reference(f)
+			}
+		}
+	}
+
+	/// Rebuilds this node by mapping every node reference with `f`, recursively.
+	///
+	/// See [`Node::for_each_reference`] for the list of visited references.
+	pub fn map_references<F: FnMut(Reference<T>) -> Reference<T>>(self, f: &mut F) -> Self {
+		self.map_with(&mut object::MapReferences(f))
+	}
+
+	/// Rebuilds this node by mapping every IRI with `f`, recursively.
+	///
+	/// See [`Object::map_iris`].
+	pub fn map_iris<F: FnMut(T) -> T>(self, f: &mut F) -> Self {
+		self.map_with(&mut object::MapIris(f))
+	}
+
+	pub(crate) fn map_with<M: object::Mapping<T>>(self, m: &mut M) -> Self {
+		let mut result = Node::new();
+		result.metadata = self.metadata;
+		result.custom_keywords = self.custom_keywords;
+		result.id = self.id.map(|id| m.map_reference(id));
+		result.types = self
+			.types
+			.into_iter()
+			.map(|ty| m.map_reference(ty))
+			.collect();
+		result.graph = self.graph.map(|graph| {
+			graph
+				.into_iter()
+				.map(|object| object.map_inner(|object| object.map_with(m)))
+				.collect()
+		});
+		result.included = self.included.map(|included| {
+			included
+				.into_iter()
+				.map(|node| node.map_inner(|node| node.map_with(m)))
+				.collect()
+		});
+
+		for (prop, objects) in self.properties {
+			let prop = m.map_reference(prop);
+			let objects: Vec<_> = objects
+				.into_iter()
+				.map(|object| object.map_inner(|object| object.map_with(m)))
+				.collect();
+			result.properties.insert_all(prop, objects.into_iter())
+		}
+
+		for (prop, nodes) in self.reverse_properties {
+			let prop = m.map_reference(prop);
+			let nodes: Vec<_> = nodes
+				.into_iter()
+				.map(|node| node.map_inner(|node| node.map_with(m)))
+				.collect();
+			result
+				.reverse_properties
+				.insert_all(prop, nodes.into_iter())
+		}
+
+		result
+	}
+
+	/// Returns the normalized form of this node, recursively.
+	///
+	/// Every value of the node is normalized (see [`Value::normalized`]),
+	/// after which values of a property that have become equal are collapsed into one.
+	pub fn normalized(self) -> Self {
+		let mut result = Node::new();
+		result.metadata = self.metadata;
+		result.custom_keywords = self.custom_keywords;
+		result.id = self.id;
+		result.types = self.types;
+		result.graph = self.graph.map(|graph| {
+			graph
+				.into_iter()
+				.map(|object| object.map_inner(Object::normalized))
+				.collect()
+		});
+		result.included = self.included.map(|included| {
+			included
+				.into_iter()
+				.map(|node| node.map_inner(Node::normalized))
+				.collect()
+		});
+
+		for (prop, objects) in self.properties {
+			let mut normalized: Vec<Indexed<Object<J, T>>> = Vec::with_capacity(objects.len());
+			for object in objects {
+				let object = object.map_inner(Object::normalized);
+				if object.is_list() || !normalized.contains(&object) {
+					normalized.push(object)
+				}
+			}
+
+			result.properties.insert_all(prop, normalized.into_iter())
+		}
+
+		for (prop, nodes) in self.reverse_properties {
+			let nodes: Vec<_> = nodes
+				.into_iter()
+				.map(|node| node.map_inner(Node::normalized))
+				.collect();
+			result
+				.reverse_properties
+				.insert_all(prop, nodes.into_iter())
+		}
+
+		result
+	}
+
+	/// Converts this node into a node of another JSON type `K`,
+	/// using the given `meta` function to convert `J::MetaData` into `K::MetaData`.
+	///
+	/// See [`Object::map_metadata`].
+	pub fn map_metadata<K: util::JsonFrom<J> + JsonHash>(
+		self,
+		meta: impl Clone + Fn(Option<&J::MetaData>) -> K::MetaData,
+	) -> Node<K, T>
+	where
+		J: JsonClone,
+	{
+		Node {
+			id: self.id,
+			types: self.types,
+			graph: self.graph.map(|graph| {
+				graph
+					.into_iter()
+					.map(|object| object.map_inner(|object| object.map_metadata(meta.clone())))
+					.collect()
+			}),
+			included: self.included.map(|included| {
+				included
+					.into_iter()
+					.map(|node| node.map_inner(|node| node.map_metadata(meta.clone())))
+					.collect()
+			}),
+			properties: self.properties.map_metadata(meta.clone()),
+			reverse_properties: self.reverse_properties.map_metadata(meta.clone()),
+			metadata: self.metadata.as_ref().map(|m| meta(Some(m))),
+			custom_keywords: self
+				.custom_keywords
+				.iter()
+				.map(|(keyword, value)| {
+					(
+						keyword.clone(),
+						util::AsJson::<J, K>::as_json_with(value, meta.clone()),
+					)
+				})
+				.collect(),
+		}
+	}
 }
 
 impl<J: JsonHash, T: Id> object::Any<J, T> for Node<J, T> {
@@ -423,7 +1015,7 @@ impl<J: JsonHash + JsonClone, K: util::JsonFrom<J>, T: Id> util::AsJson<J, K> fo
 			);
 		}
 
-		K::object(obj, meta(None))
+		K::object(obj, meta(self.metadata.as_ref()))
 	}
 }
 
@@ -441,11 +1033,11 @@ impl<J: JsonHash + JsonClone, K: util::JsonFrom<J>, T: Id> util::AsJson<J, K>
 }
 
 /// Iterator through indexed nodes.
-pub struct Nodes<'a, J: JsonHash, T: Id>(Option<std::slice::Iter<'a, Indexed<Node<J, T>>>>);
+pub struct Nodes<'a, J: JsonHash, T: Id>(Option<core::slice::Iter<'a, Indexed<Node<J, T>>>>);
 
 impl<'a, J: JsonHash, T: Id> Nodes<'a, J, T> {
 	#[inline(always)]
-	pub(crate) fn new(inner: Option<std::slice::Iter<'a, Indexed<Node<J, T>>>>) -> Self {
+	pub(crate) fn new(inner: Option<core::slice::Iter<'a, Indexed<Node<J, T>>>>) -> Self {
 		Self(inner)
 	}
 }
@@ -461,3 +1053,57 @@ impl<'a, J: JsonHash, T: Id> Iterator for Nodes<'a, J, T> {
 		}
 	}
 }
+
+/// Subclass relation between node types.
+///
+/// This is used to walk typed nodes with some external knowledge of the
+/// vocabulary (e.g. RDFS or OWL), without reimplementing the traversal.
+/// It is implemented by any function returning the direct subclasses of a type.
+pub trait TypeHierarchy<T: Id> {
+	/// Returns the direct subclasses of the given type.
+	fn sub_classes(&self, ty: &Reference<T>) -> Vec<Reference<T>>;
+
+	/// Returns the given type along with all its subclasses, transitively.
+	///
+	/// Cycles in the subclass relation are allowed.
+	fn sub_classes_transitive(&self, ty: &Reference<T>) -> HashSet<Reference<T>> {
+		let mut result = HashSet::new();
+		let mut stack = vec![ty.clone()];
+
+		while let Some(ty) = stack.pop() {
+			if !result.contains(&ty) {
+				stack.extend(self.sub_classes(&ty));
+				result.insert(ty);
+			}
+		}
+
+		result
+	}
+}
+
+impl<T: Id, F: Fn(&Reference<T>) -> Vec<Reference<T>>> TypeHierarchy<T> for F {
+	#[inline(always)]
+	fn sub_classes(&self, ty: &Reference<T>) -> Vec<Reference<T>> {
+		self(ty)
+	}
+}
+
+/// Selects the objects reached from the given object by following the given property path.
+///
+/// See [`Node::select`].
+pub(crate) fn select<'a, J: JsonHash, T: Id, Q: ToReference<T>>(
+	object: &'a Indexed<Object<J, T>>,
+	path: &[Q],
+	selection: &mut Vec<&'a Indexed<Object<J, T>>>,
+) {
+	match object.inner() {
+		Object::List(items, _) => {
+			for item in items {
+				select(item, path, selection)
+			}
+		}
+		_ if path.is_empty() => selection.push(object),
+		Object::Node(node) => node.select_into(path, selection),
+		_ => (),
+	}
+}
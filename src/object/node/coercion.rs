@@ -0,0 +1,331 @@
+//! Property value updates from plain Rust values.
+use super::Node;
+use crate::{
+	context::{Context, TermDefinition},
+	expansion::expand_iri,
+	object::{Literal, LiteralString},
+	syntax::{ContainerType, Term, Type},
+	ErrorCode, Id, Indexed, LangString, Object, Reference, Value,
+};
+use generic_json::JsonHash;
+use iref::IriBuf;
+
+/// Plain property value.
+///
+/// Values of this type are converted into objects by [`Node::set`] and [`Node::update`],
+/// or coerced using the type coercion rules of a context by [`Node::set_with`]
+/// and [`Node::update_with`].
+pub enum PlainValue<J: JsonHash, T: Id = IriBuf> {
+	/// String, coerced according to the definition of the property.
+	String(String),
+
+	/// Other literal value.
+	Literal(Literal<J>),
+
+	/// Node reference.
+	Reference(Reference<T>),
+
+	/// Object, inserted as is.
+	Object(Indexed<Object<J, T>>),
+}
+
+impl<J: JsonHash, T: Id> PlainValue<J, T> {
+	/// Converts this value into an object, without coercion.
+	///
+	/// Strings are converted into plain string literals.
+	pub fn into_object(self) -> Indexed<Object<J, T>> {
+		match self {
+			Self::String(s) => literal(Literal::String(LiteralString::Inferred(s)), None),
+			Self::Literal(l) => literal(l, None),
+			Self::Reference(r) => Object::Node(Node::with_id(r)).into(),
+			Self::Object(object) => object,
+		}
+	}
+
+	/// Converts this value into an object, following the type coercion rules of the given
+	/// context for a property with the given definition, as the expansion algorithm would.
+	///
+	/// - If the property has an `@id` or `@vocab` type mapping, strings are expanded into
+	///   node references.
+	/// - If the property has a datatype type mapping, strings and other literals are typed
+	///   with it.
+	/// - Otherwise, strings are tagged with the language and direction of the property,
+	///   or the default language and base direction of the context.
+	///
+	/// The `@json` type mapping is not applied: strings are not JSON literals.
+	/// References and objects are left untouched.
+	pub fn coerce<C: Context<T>>(
+		self,
+		context: &C,
+		definition: Option<&TermDefinition<T, C>>,
+	) -> Indexed<Object<J, T>> {
+		let typ = definition.and_then(|d| d.typ.as_ref());
+		match self {
+			Self::String(s) => match typ {
+				Some(Type::Id) | Some(Type::Vocab) => {
+					let vocab = matches!(typ, Some(Type::Vocab));
+					let id = match expand_iri(None, context, &s, &(), true, vocab, &mut Vec::new())
+					{
+						Term::Ref(id) => id,
+						_ => Reference::Invalid(s),
+					};
+
+					Object::Node(Node::with_id(id)).into()
+				}
+				Some(Type::Ref(ty)) => literal(
+					Literal::String(LiteralString::Inferred(s)),
+					Some(ty.clone()),
+				),
+				_ => {
+					let language = match definition.and_then(|d| d.language.as_ref()) {
+						Some(language) => language.as_ref().cloned().option(),
+						None => context.default_language().map(|lang| lang.cloned()),
+					};
+
+					let direction = match definition.and_then(|d| d.direction) {
+						Some(direction) => direction.option(),
+						None => context.default_base_direction(),
+					};
+
+					match LangString::new(LiteralString::Inferred(s), language, direction) {
+						Ok(s) => Object::from(Value::LangString(s)).into(),
+						Err(s) => literal(Literal::String(s), None),
+					}
+				}
+			},
+			Self::Literal(l) => match typ {
+				Some(Type::Ref(ty)) => literal(l, Some(ty.clone())),
+				_ => literal(l, None),
+			},
+			value => value.into_object(),
+		}
+	}
+}
+
+fn literal<J: JsonHash, T: Id>(literal: Literal<J>, ty: Option<T>) -> Indexed<Object<J, T>> {
+	Object::from(Value::Literal(literal, ty)).into()
+}
+
+impl<'a, J: JsonHash, T: Id> From<&'a str> for PlainValue<J, T> {
+	#[inline(always)]
+	fn from(s: &'a str) -> Self {
+		Self::String(s.to_string())
+	}
+}
+
+impl<J: JsonHash, T: Id> From<String> for PlainValue<J, T> {
+	#[inline(always)]
+	fn from(s: String) -> Self {
+		Self::String(s)
+	}
+}
+
+impl<J: JsonHash, T: Id> From<bool> for PlainValue<J, T> {
+	#[inline(always)]
+	fn from(b: bool) -> Self {
+		Self::Literal(Literal::Boolean(b))
+	}
+}
+
+impl<J: JsonHash, T: Id> From<i64> for PlainValue<J, T>
+where
+	J::Number: From<i64>,
+{
+	#[inline(always)]
+	fn from(n: i64) -> Self {
+		Self::Literal(Literal::Number(n.into()))
+	}
+}
+
+impl<J: JsonHash, T: Id> From<Literal<J>> for PlainValue<J, T> {
+	#[inline(always)]
+	fn from(l: Literal<J>) -> Self {
+		Self::Literal(l)
+	}
+}
+
+impl<J: JsonHash, T: Id> From<IriBuf> for PlainValue<J, T> {
+	#[inline(always)]
+	fn from(iri: IriBuf) -> Self {
+		Self::Reference(Reference::Id(T::from_iri(iri.as_iri())))
+	}
+}
+
+impl<J: JsonHash, T: Id> From<Reference<T>> for PlainValue<J, T> {
+	#[inline(always)]
+	fn from(r: Reference<T>) -> Self {
+		Self::Reference(r)
+	}
+}
+
+impl<J: JsonHash, T: Id> From<Node<J, T>> for PlainValue<J, T> {
+	#[inline(always)]
+	fn from(node: Node<J, T>) -> Self {
+		Self::Object(Object::Node(node).into())
+	}
+}
+
+impl<J: JsonHash, T: Id> From<Object<J, T>> for PlainValue<J, T> {
+	#[inline(always)]
+	fn from(object: Object<J, T>) -> Self {
+		Self::Object(object.into())
+	}
+}
+
+impl<J: JsonHash, T: Id> From<Indexed<Object<J, T>>> for PlainValue<J, T> {
+	#[inline(always)]
+	fn from(object: Indexed<Object<J, T>>) -> Self {
+		Self::Object(object)
+	}
+}
+
+/// Expands the given term, compact IRI or IRI into a property.
+fn property<T: Id, C: Context<T>>(context: &C, term: &str) -> Result<Reference<T>, ErrorCode> {
+	match expand_iri(None, context, term, &(), false, true, &mut Vec::new()) {
+		Term::Ref(prop) if prop.is_valid() => Ok(prop),
+		_ => Err(ErrorCode::InvalidIriMapping),
+	}
+}
+
+impl<J: JsonHash, T: Id> Node<J, T> {
+	/// Replaces the values of the given property, returning the previous values, if any.
+	///
+	/// Values are converted into objects without coercion
+	/// (see [`PlainValue::into_object`]).
+	/// The property is removed if there are no values.
+	pub fn set<V: Into<PlainValue<J, T>>, I: IntoIterator<Item = V>>(
+		&mut self,
+		prop: Reference<T>,
+		values: I,
+	) -> Option<Vec<Indexed<Object<J, T>>>> {
+		let previous_values = self.properties.remove(&prop);
+		let mut values = values
+			.into_iter()
+			.map(|v| v.into().into_object())
+			.peekable();
+		if values.peek().is_some() {
+			self.properties.insert_all(prop, values)
+		}
+
+		previous_values
+	}
+
+	/// Replaces the values of the given property by the result of `f`,
+	/// called with the current values.
+	///
+	/// See [`set`](Self::set) for the details.
+	pub fn update<V, I, F>(&mut self, prop: Reference<T>, f: F)
+	where
+		V: Into<PlainValue<J, T>>,
+		I: IntoIterator<Item = V>,
+		F: FnOnce(Vec<Indexed<Object<J, T>>>) -> I,
+	{
+		let values = self.properties.remove(&prop).unwrap_or_default();
+		self.set(prop, f(values));
+	}
+
+	/// Replaces the values of the given property using the given context,
+	/// returning the previous values, if any.
+	///
+	/// The property is a term, compact IRI or IRI expanded with the context.
+	/// Values are coerced according to the definition of the property in the context
+	/// (see [`PlainValue::coerce`]), and wrapped in a single list if the property has a
+	/// `@list` container mapping.
+	/// The property is removed if there are no values.
+	///
+	/// Fails with [`ErrorCode::InvalidIriMapping`] if the property does not expand into
+	/// an IRI or blank node identifier.
+	///
+	/// ```
+	/// use iref::IriBuf;
+	/// use json_ld::{context::{self, Local}, Node, NoLoader, Reference};
+	/// use serde_json::{json, Value};
+	///
+	/// # async_std::task::block_on(async {
+	/// let mut loader = NoLoader::<Value>::new();
+	/// let context = json!({
+	///   "@language": "en",
+	///   "foaf": "http://xmlns.com/foaf/0.1/",
+	///   "name": "foaf:name",
+	///   "knows": { "@id": "foaf:knows", "@type": "@id" },
+	///   "age": { "@id": "foaf:age", "@type": "http://www.w3.org/2001/XMLSchema#integer" }
+	/// })
+	/// .process::<context::Json<Value, IriBuf>, _>(&mut loader, None)
+	/// .await
+	/// .unwrap()
+	/// .into_inner();
+	///
+	/// let mut node: Node<Value> = Node::new();
+	/// node.set_with(&context, "name", vec!["Alice"]).unwrap();
+	/// node.set_with(&context, "knows", vec!["http://example.org/bob"]).unwrap();
+	/// node.set_with(&context, "age", vec![42i64]).unwrap();
+	///
+	/// let prop = |iri: &str| Reference::Id(IriBuf::new(iri).unwrap());
+	/// let name = node.get(&prop("http://xmlns.com/foaf/0.1/name")).next().unwrap();
+	/// assert_eq!(name.language().unwrap().as_str(), "en");
+	/// let knows = node.get(&prop("http://xmlns.com/foaf/0.1/knows")).next().unwrap();
+	/// assert_eq!(knows.as_iri().unwrap(), "http://example.org/bob");
+	/// let age = node.get(&prop("http://xmlns.com/foaf/0.1/age")).next().unwrap();
+	/// assert!(age.as_value().unwrap().typ().is_some());
+	///
+	/// node.update_with(&context, "age", |_| vec![43i64]).unwrap();
+	/// assert!(node.set_with(&context, "@undefined", vec!["?"]).is_err());
+	/// # })
+	/// ```
+	pub fn set_with<C, V, I>(
+		&mut self,
+		context: &C,
+		prop: &str,
+		values: I,
+	) -> Result<Option<Vec<Indexed<Object<J, T>>>>, ErrorCode>
+	where
+		C: Context<T>,
+		V: Into<PlainValue<J, T>>,
+		I: IntoIterator<Item = V>,
+	{
+		let definition = context.get(prop);
+		let prop = property(context, prop)?;
+		let values = values
+			.into_iter()
+			.map(|v| v.into().coerce(context, definition));
+
+		let previous_values = self.properties.remove(&prop);
+		if definition.map_or(false, |d| d.container.contains(ContainerType::List)) {
+			let items: Vec<_> = values.collect();
+			if !items.is_empty() {
+				self.properties
+					.insert(prop, Object::List(items, None).into())
+			}
+		} else {
+			let mut values = values.peekable();
+			if values.peek().is_some() {
+				self.properties.insert_all(prop, values)
+			}
+		}
+
+		Ok(previous_values)
+	}
+
+	/// Replaces the values of the given property by the result of `f`,
+	/// called with the current values, using the given context.
+	///
+	/// See [`set_with`](Self::set_with) for the details.
+	pub fn update_with<C, V, I, F>(
+		&mut self,
+		context: &C,
+		prop: &str,
+		f: F,
+	) -> Result<(), ErrorCode>
+	where
+		C: Context<T>,
+		V: Into<PlainValue<J, T>>,
+		I: IntoIterator<Item = V>,
+		F: FnOnce(Vec<Indexed<Object<J, T>>>) -> I,
+	{
+		let values = self
+			.properties
+			.remove(&property(context, prop)?)
+			.unwrap_or_default();
+		self.set_with(context, prop, f(values)).map(|_| ())
+	}
+}
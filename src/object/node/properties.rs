@@ -1,14 +1,22 @@
 use super::Objects;
-use crate::{Id, Indexed, Object, Reference, ToReference};
-use generic_json::JsonHash;
-use std::{
+#[cfg(not(feature = "std"))]
+use crate::prelude::*;
+use crate::{
+	util::{small_map, JsonFrom, SmallMap},
+	Id, Indexed, Object, Reference, ToReference,
+};
+use core::{
 	borrow::Borrow,
-	collections::HashMap,
 	hash::{Hash, Hasher},
 };
+use generic_json::{JsonClone, JsonHash};
 
 /// Properties of a node object, and their associated objects.
 ///
+/// Since most nodes only have a few properties, they are stored inline
+/// (and iterated in insertion order) until there are too many of them,
+/// at which point they are moved to a hash map.
+///
 /// ## Example
 ///
 /// ```rust
@@ -42,13 +50,94 @@ use std::{
 ///   }
 /// }
 /// ```
-#[derive(PartialEq, Eq)]
-pub struct Properties<J: JsonHash, T: Id>(HashMap<Reference<T>, Vec<Indexed<Object<J, T>>>>);
+///
+/// The metadata of the entry defining each property in the source document
+/// is kept aside, and ignored by comparison and hashing.
+pub struct Properties<J: JsonHash, T: Id>(
+	SmallMap<Reference<T>, Vec<Indexed<Object<J, T>>>>,
+	SmallMap<Reference<T>, J::MetaData>,
+);
+
+impl<J: JsonHash + JsonClone, T: Id> Clone for Properties<J, T> {
+	#[inline(always)]
+	fn clone(&self) -> Self {
+		Self(self.0.clone(), self.1.clone())
+	}
+}
+
+impl<J: JsonHash, T: Id> PartialEq for Properties<J, T> {
+	#[inline(always)]
+	fn eq(&self, other: &Self) -> bool {
+		self.0 == other.0
+	}
+}
+
+impl<J: JsonHash, T: Id> Eq for Properties<J, T> {}
 
 impl<J: JsonHash, T: Id> Properties<J, T> {
 	/// Creates an empty map.
 	pub(crate) fn new() -> Self {
-		Self(HashMap::new())
+		Self(SmallMap::new(), SmallMap::new())
+	}
+
+	/// Returns the metadata of the entry defining the given property
+	/// in the source document, if any.
+	///
+	/// If the property is defined by more than one entry
+	/// (for instance through a term and its IRI), this is the metadata of the first one.
+	#[inline(always)]
+	pub fn entry_metadata<Q: ToReference<T>>(&self, prop: Q) -> Option<&J::MetaData> {
+		let prop = prop.to_ref();
+		if self.0.get(prop.borrow()).is_some() {
+			self.1.get(prop.borrow())
+		} else {
+			None
+		}
+	}
+
+	/// Sets the metadata of the entry defining the given property,
+	/// unless it is already set.
+	///
+	/// Nothing is recorded if the property is not associated to any object.
+	pub(crate) fn set_entry_metadata(&mut self, prop: &Reference<T>, metadata: &J::MetaData) {
+		if self.0.get(prop).is_some() && self.1.get(prop).is_none() {
+			self.1.insert(prop.clone(), metadata.clone());
+		}
+	}
+
+	/// Forgets the entry metadata of the properties that have been removed.
+	fn prune_entry_metadata(&mut self) {
+		let objects = &self.0;
+		self.1.retain(|prop, _| objects.get(prop).is_some())
+	}
+
+	/// Converts the associated objects into objects of another JSON type `K`,
+	/// using the given `meta` function to convert `J::MetaData` into `K::MetaData`.
+	///
+	/// See [`Object::map_metadata`].
+	pub fn map_metadata<K: JsonFrom<J> + JsonHash>(
+		self,
+		meta: impl Clone + Fn(Option<&J::MetaData>) -> K::MetaData,
+	) -> Properties<K, T>
+	where
+		J: JsonClone,
+	{
+		Properties(
+			self.0
+				.into_iter()
+				.map(|(prop, objects)| {
+					let objects = objects
+						.into_iter()
+						.map(|object| object.map_inner(|object| object.map_metadata(meta.clone())))
+						.collect();
+					(prop, objects)
+				})
+				.collect(),
+			self.1
+				.into_iter()
+				.map(|(prop, metadata)| (prop, meta(Some(&metadata))))
+				.collect(),
+		)
 	}
 
 	/// Returns the number of properties.
@@ -106,6 +195,13 @@ impl<J: JsonHash, T: Id> Properties<J, T> {
 		}
 	}
 
+	/// Associate the given object to the node through the given property,
+	/// before any other object already associated to this property.
+	#[inline(always)]
+	pub(crate) fn insert_front(&mut self, prop: Reference<T>, value: Indexed<Object<J, T>>) {
+		self.0.get_or_insert_with(prop, Vec::new).insert(0, value)
+	}
+
 	/// Associate all the given objects to the node through the given property.
 	#[inline(always)]
 	pub fn insert_all<Objects: Iterator<Item = Indexed<Object<J, T>>>>(
@@ -120,6 +216,121 @@ impl<J: JsonHash, T: Id> Properties<J, T> {
 		}
 	}
 
+	/// Removes the given property, returning its associated objects, if any.
+	#[inline(always)]
+	pub fn remove<Q: ToReference<T>>(&mut self, prop: Q) -> Option<Vec<Indexed<Object<J, T>>>> {
+		let prop = prop.to_ref();
+		self.1.remove(prop.borrow());
+		self.0.remove(prop.borrow())
+	}
+
+	/// Removes one occurrence of the given object from the objects associated to the
+	/// given property, and returns it.
+	///
+	/// The property is removed if it is not associated to any object anymore.
+	pub fn remove_object<Q: ToReference<T>>(
+		&mut self,
+		prop: Q,
+		object: &Indexed<Object<J, T>>,
+	) -> Option<Indexed<Object<J, T>>> {
+		let prop = prop.to_ref();
+		let objects = self.0.get_mut(prop.borrow())?;
+		let i = objects.iter().position(|o| o == object)?;
+		let removed = objects.remove(i);
+
+		if objects.is_empty() {
+			self.0.remove(prop.borrow());
+			self.1.remove(prop.borrow());
+		}
+
+		Some(removed)
+	}
+
+	/// Retains only the objects for which `f` returns `true`.
+	///
+	/// Properties that are not associated to any object anymore are removed.
+	pub fn retain(&mut self, mut f: impl FnMut(&Reference<T>, &Indexed<Object<J, T>>) -> bool) {
+		self.0.retain(|prop, objects| {
+			objects.retain(|object| f(prop, object));
+			!objects.is_empty()
+		});
+		self.prune_entry_metadata()
+	}
+
+	/// Removes the duplicate objects of each property, keeping their first occurrence.
+	pub fn dedup(&mut self) {
+		for (_, objects) in self.0.iter_mut() {
+			let mut i = 1;
+			while i < objects.len() {
+				if objects[..i].contains(&objects[i]) {
+					objects.remove(i);
+				} else {
+					i += 1
+				}
+			}
+		}
+	}
+
+	/// Adds the objects of `other` to the objects associated to the same property,
+	/// unless they are already there.
+	///
+	/// Objects associated to a property are considered as a multiset:
+	/// an object appearing twice in `other` and once in `self` is added once.
+	pub fn union_with(&mut self, other: Self) {
+		let Properties(other, other_metadata) = other;
+		for (prop, others) in other {
+			if others.is_empty() {
+				continue;
+			}
+
+			let objects = self.0.get_or_insert_with(prop, Vec::new);
+			let mut matched = vec![false; objects.len()];
+			for object in others {
+				match (0..matched.len()).find(|&i| !matched[i] && objects[i] == object) {
+					Some(i) => matched[i] = true,
+					None => objects.push(object),
+				}
+			}
+		}
+
+		for (prop, metadata) in other_metadata {
+			self.set_entry_metadata(&prop, &metadata)
+		}
+	}
+
+	/// Only keeps the objects that are also associated to the same property in `other`.
+	///
+	/// Objects associated to a property are considered as a multiset:
+	/// an object appearing twice in `self` and once in `other` is kept once.
+	/// Properties that are not associated to any object anymore are removed.
+	pub fn intersect_with(&mut self, other: &Self) {
+		self.0.retain(|prop, objects| {
+			let others = other.0.get(prop).map(Vec::as_slice).unwrap_or(&[]);
+			let mut matched = vec![false; others.len()];
+			objects.retain(|object| {
+				match (0..others.len()).find(|&i| !matched[i] && others[i] == *object) {
+					Some(i) => {
+						matched[i] = true;
+						true
+					}
+					None => false,
+				}
+			});
+
+			!objects.is_empty()
+		});
+		self.prune_entry_metadata()
+	}
+
+	/// Removes every property, returning them with their associated objects.
+	#[inline(always)]
+	pub fn drain(&mut self) -> IntoIter<J, T> {
+		self.1 = SmallMap::new();
+		IntoIter {
+			inner: core::mem::take(&mut self.0).into_iter(),
+		}
+	}
+
 	/// Returns an iterator over the properties and their associated objects.
 	#[inline(always)]
 	pub fn iter(&self) -> Iter<'_, J, T> {
@@ -128,6 +339,17 @@ impl<J: JsonHash, T: Id> Properties<J, T> {
 		}
 	}
 
+	/// Returns an iterator over the properties and their associated objects,
+	/// sorted by property IRI.
+	///
+	/// Unlike [`iter`](Self::iter), the order does not depend on the insertion order
+	/// nor on the hash of the properties.
+	pub fn iter_sorted(&self) -> alloc::vec::IntoIter<BindingRef<'_, J, T>> {
+		let mut bindings: Vec<_> = self.iter().collect();
+		bindings.sort_by(|(a, _), (b, _)| a.as_str().cmp(b.as_str()));
+		bindings.into_iter()
+	}
+
 	/// Returns an iterator over the properties with a mutable reference to their associated objects.
 	#[inline(always)]
 	pub fn iter_mut(&mut self) -> IterMut<'_, J, T> {
@@ -140,7 +362,7 @@ impl<J: JsonHash, T: Id> Properties<J, T> {
 impl<J: JsonHash, T: Id> Hash for Properties<J, T> {
 	#[inline(always)]
 	fn hash<H: Hasher>(&self, h: &mut H) {
-		crate::util::hash_map(&self.0, h)
+		self.0.hash(h)
 	}
 }
 
@@ -152,6 +374,18 @@ pub type BindingRef<'a, J, T> = (&'a Reference<T>, &'a [Indexed<Object<J, T>>]);
 /// associating a property to some objects, with a mutable access to the objects.
 pub type BindingMut<'a, J, T> = (&'a Reference<T>, &'a mut Vec<Indexed<Object<J, T>>>);
 
+impl<J: JsonHash, T: Id> IntoIterator for Properties<J, T> {
+	type Item = (Reference<T>, Vec<Indexed<Object<J, T>>>);
+	type IntoIter = IntoIter<J, T>;
+
+	#[inline(always)]
+	fn into_iter(self) -> Self::IntoIter {
+		IntoIter {
+			inner: self.0.into_iter(),
+		}
+	}
+}
+
 impl<'a, J: JsonHash, T: Id> IntoIterator for &'a Properties<J, T> {
 	type Item = BindingRef<'a, J, T>;
 	type IntoIter = Iter<'a, J, T>;
@@ -176,7 +410,7 @@ impl<'a, J: JsonHash, T: Id> IntoIterator for &'a mut Properties<J, T> {
 ///
 /// It is created by the [`Properties::iter`] function.
 pub struct Iter<'a, J: JsonHash, T: Id> {
-	inner: std::collections::hash_map::Iter<'a, Reference<T>, Vec<Indexed<Object<J, T>>>>,
+	inner: small_map::Iter<'a, Reference<T>, Vec<Indexed<Object<J, T>>>>,
 }
 
 impl<'a, J: JsonHash, T: Id> Iterator for Iter<'a, J, T> {
@@ -197,14 +431,14 @@ impl<'a, J: JsonHash, T: Id> Iterator for Iter<'a, J, T> {
 
 impl<'a, J: JsonHash, T: Id> ExactSizeIterator for Iter<'a, J, T> {}
 
-impl<'a, J: JsonHash, T: Id> std::iter::FusedIterator for Iter<'a, J, T> {}
+impl<'a, J: JsonHash, T: Id> core::iter::FusedIterator for Iter<'a, J, T> {}
 
 /// Iterator over the properties of a node, giving a mutable reference
 /// to the associated objects.
 ///
 /// It is created by the [`Properties::iter_mut`] function.
 pub struct IterMut<'a, J: JsonHash, T: Id> {
-	inner: std::collections::hash_map::IterMut<'a, Reference<T>, Vec<Indexed<Object<J, T>>>>,
+	inner: small_map::IterMut<'a, Reference<T>, Vec<Indexed<Object<J, T>>>>,
 }
 
 impl<'a, J: JsonHash, T: Id> Iterator for IterMut<'a, J, T> {
@@ -223,4 +457,29 @@ impl<'a, J: JsonHash, T: Id> Iterator for IterMut<'a, J, T> {
 
 impl<'a, J: JsonHash, T: Id> ExactSizeIterator for IterMut<'a, J, T> {}
 
-impl<'a, J: JsonHash, T: Id> std::iter::FusedIterator for IterMut<'a, J, T> {}
+impl<'a, J: JsonHash, T: Id> core::iter::FusedIterator for IterMut<'a, J, T> {}
+
+/// Iterator over the properties of a node, and their associated objects.
+///
+/// It is created by the [`IntoIterator`] implementation of [`Properties`].
+pub struct IntoIter<J: JsonHash, T: Id> {
+	inner: small_map::IntoIter<Reference<T>, Vec<Indexed<Object<J, T>>>>,
+}
+
+impl<J: JsonHash, T: Id> Iterator for IntoIter<J, T> {
+	type Item = (Reference<T>, Vec<Indexed<Object<J, T>>>);
+
+	#[inline(always)]
+	fn size_hint(&self) -> (usize, Option<usize>) {
+		self.inner.size_hint()
+	}
+
+	#[inline(always)]
+	fn next(&mut self) -> Option<Self::Item> {
+		self.inner.next()
+	}
+}
+
+impl<J: JsonHash, T: Id> ExactSizeIterator for IntoIter<J, T> {}
+
+impl<J: JsonHash, T: Id> core::iter::FusedIterator for IntoIter<J, T> {}
@@ -1,20 +1,56 @@
 use super::{Node, Nodes};
-use crate::{Id, Indexed, Reference, ToReference};
-use generic_json::JsonHash;
-use std::{
+#[cfg(not(feature = "std"))]
+use crate::prelude::*;
+use crate::{
+	util::{small_map, JsonFrom, SmallMap},
+	Id, Indexed, Reference, ToReference,
+};
+use core::{
 	borrow::Borrow,
-	collections::HashMap,
 	hash::{Hash, Hasher},
 };
+use generic_json::{JsonClone, JsonHash};
 
 /// Reverse properties of a node object, and their associated nodes.
 #[derive(PartialEq, Eq)]
-pub struct ReverseProperties<J: JsonHash, T: Id>(HashMap<Reference<T>, Vec<Indexed<Node<J, T>>>>);
+pub struct ReverseProperties<J: JsonHash, T: Id>(SmallMap<Reference<T>, Vec<Indexed<Node<J, T>>>>);
+
+impl<J: JsonHash + JsonClone, T: Id> Clone for ReverseProperties<J, T> {
+	#[inline(always)]
+	fn clone(&self) -> Self {
+		Self(self.0.clone())
+	}
+}
 
 impl<J: JsonHash, T: Id> ReverseProperties<J, T> {
 	/// Creates an empty map.
 	pub(crate) fn new() -> Self {
-		Self(HashMap::new())
+		Self(SmallMap::new())
+	}
+
+	/// Converts the associated nodes into nodes of another JSON type `K`,
+	/// using the given `meta` function to convert `J::MetaData` into `K::MetaData`.
+	///
+	/// See [`Node::map_metadata`].
+	pub fn map_metadata<K: JsonFrom<J> + JsonHash>(
+		self,
+		meta: impl Clone + Fn(Option<&J::MetaData>) -> K::MetaData,
+	) -> ReverseProperties<K, T>
+	where
+		J: JsonClone,
+	{
+		ReverseProperties(
+			self.0
+				.into_iter()
+				.map(|(prop, nodes)| {
+					let nodes = nodes
+						.into_iter()
+						.map(|node| node.map_inner(|node| node.map_metadata(meta.clone())))
+						.collect();
+					(prop, nodes)
+				})
+				.collect(),
+		)
 	}
 
 	/// Returns the number of reverse properties.
@@ -94,6 +130,17 @@ impl<J: JsonHash, T: Id> ReverseProperties<J, T> {
 		}
 	}
 
+	/// Returns an iterator over the reverse properties and their associated nodes,
+	/// sorted by property IRI.
+	///
+	/// Unlike [`iter`](Self::iter), the order does not depend on the insertion order
+	/// nor on the hash of the properties.
+	pub fn iter_sorted(&self) -> alloc::vec::IntoIter<ReverseBindingRef<'_, J, T>> {
+		let mut bindings: Vec<_> = self.iter().collect();
+		bindings.sort_by(|(a, _), (b, _)| a.as_str().cmp(b.as_str()));
+		bindings.into_iter()
+	}
+
 	/// Returns an iterator over the reverse properties with a mutable reference to their associated nodes.
 	#[inline(always)]
 	pub fn iter_mut(&mut self) -> IterMut<'_, J, T> {
@@ -106,7 +153,7 @@ impl<J: JsonHash, T: Id> ReverseProperties<J, T> {
 impl<J: JsonHash, T: Id> Hash for ReverseProperties<J, T> {
 	#[inline(always)]
 	fn hash<H: Hasher>(&self, h: &mut H) {
-		crate::util::hash_map(&self.0, h)
+		self.0.hash(h)
 	}
 }
 
@@ -118,6 +165,18 @@ pub type ReverseBindingRef<'a, J, T> = (&'a Reference<T>, &'a [Indexed<Node<J, T
 /// associating a reverse property to some nodes, with a mutable access to the nodes.
 pub type ReverseBindingMut<'a, J, T> = (&'a Reference<T>, &'a mut Vec<Indexed<Node<J, T>>>);
 
+impl<J: JsonHash, T: Id> IntoIterator for ReverseProperties<J, T> {
+	type Item = (Reference<T>, Vec<Indexed<Node<J, T>>>);
+	type IntoIter = IntoIter<J, T>;
+
+	#[inline(always)]
+	fn into_iter(self) -> Self::IntoIter {
+		IntoIter {
+			inner: self.0.into_iter(),
+		}
+	}
+}
+
 impl<'a, J: JsonHash, T: Id> IntoIterator for &'a ReverseProperties<J, T> {
 	type Item = ReverseBindingRef<'a, J, T>;
 	type IntoIter = Iter<'a, J, T>;
@@ -142,7 +201,7 @@ impl<'a, J: JsonHash, T: Id> IntoIterator for &'a mut ReverseProperties<J, T> {
 ///
 /// It is created by the [`ReverseProperties::iter`] function.
 pub struct Iter<'a, J: JsonHash, T: Id> {
-	inner: std::collections::hash_map::Iter<'a, Reference<T>, Vec<Indexed<Node<J, T>>>>,
+	inner: small_map::Iter<'a, Reference<T>, Vec<Indexed<Node<J, T>>>>,
 }
 
 impl<'a, J: JsonHash, T: Id> Iterator for Iter<'a, J, T> {
@@ -163,14 +222,14 @@ impl<'a, J: JsonHash, T: Id> Iterator for Iter<'a, J, T> {
 
 impl<'a, J: JsonHash, T: Id> ExactSizeIterator for Iter<'a, J, T> {}
 
-impl<'a, J: JsonHash, T: Id> std::iter::FusedIterator for Iter<'a, J, T> {}
+impl<'a, J: JsonHash, T: Id> core::iter::FusedIterator for Iter<'a, J, T> {}
 
 /// Iterator over the reverse properties of a node, giving a mutable reference
 /// to the associated nodes.
 ///
 /// It is created by the [`ReverseProperties::iter_mut`] function.
 pub struct IterMut<'a, J: JsonHash, T: Id> {
-	inner: std::collections::hash_map::IterMut<'a, Reference<T>, Vec<Indexed<Node<J, T>>>>,
+	inner: small_map::IterMut<'a, Reference<T>, Vec<Indexed<Node<J, T>>>>,
 }
 
 impl<'a, J: JsonHash, T: Id> Iterator for IterMut<'a, J, T> {
@@ -189,4 +248,29 @@ impl<'a, J: JsonHash, T: Id> Iterator for IterMut<'a, J, T> {
 
 impl<'a, J: JsonHash, T: Id> ExactSizeIterator for IterMut<'a, J, T> {}
 
-impl<'a, J: JsonHash, T: Id> std::iter::FusedIterator for IterMut<'a, J, T> {}
+impl<'a, J: JsonHash, T: Id> core::iter::FusedIterator for IterMut<'a, J, T> {}
+
+/// Iterator over the reverse properties of a node, and their associated nodes.
+///
+/// It is created by the [`IntoIterator`] implementation of [`ReverseProperties`].
+pub struct IntoIter<J: JsonHash, T: Id> {
+	inner: small_map::IntoIter<Reference<T>, Vec<Indexed<Node<J, T>>>>,
+}
+
+impl<J: JsonHash, T: Id> Iterator for IntoIter<J, T> {
+	type Item = (Reference<T>, Vec<Indexed<Node<J, T>>>);
+
+	#[inline(always)]
+	fn size_hint(&self) -> (usize, Option<usize>) {
+		self.inner.size_hint()
+	}
+
+	#[inline(always)]
+	fn next(&mut self) -> Option<Self::Item> {
+		self.inner.next()
+	}
+}
+
+impl<J: JsonHash, T: Id> ExactSizeIterator for IntoIter<J, T> {}
+
+impl<J: JsonHash, T: Id> core::iter::FusedIterator for IntoIter<J, T> {}
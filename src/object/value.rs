@@ -1,3 +1,5 @@
+#[cfg(not(feature = "std"))]
+use crate::prelude::*;
 use crate::{
 	lang::LenientLanguageTag,
 	object,
@@ -6,13 +8,15 @@ use crate::{
 	Direction, Id, LangString,
 };
 use cc_traits::MapInsert;
-use derivative::Derivative;
-use generic_json::{Json, JsonClone, JsonHash};
-use iref::IriBuf;
-use std::{
+use core::{
 	fmt,
 	hash::{Hash, Hasher},
 };
+use derivative::Derivative;
+use generic_json::{Json, JsonClone, JsonHash, Number};
+use iref::{AsIri, IriBuf};
+
+pub mod xsd;
 
 #[derive(Derivative)]
 #[derivative(Clone(bound = "J::String: Clone"))]
@@ -32,6 +36,18 @@ impl<J: Json> LiteralString<J> {
 			Self::Inferred(s) => s.as_str(),
 		}
 	}
+
+	/// Converts this string into a string of another JSON type.
+	#[inline(always)]
+	pub fn cast<K: util::JsonFrom<J>>(self) -> LiteralString<K> {
+		match self {
+			Self::Expanded(s) => {
+				let s: &str = s.as_ref();
+				LiteralString::Expanded(s.into())
+			}
+			Self::Inferred(s) => LiteralString::Inferred(s),
+		}
+	}
 }
 
 impl<J: Json> AsRef<str> for LiteralString<J> {
@@ -41,14 +57,14 @@ impl<J: Json> AsRef<str> for LiteralString<J> {
 	}
 }
 
-impl<J: Json> std::borrow::Borrow<str> for LiteralString<J> {
+impl<J: Json> core::borrow::Borrow<str> for LiteralString<J> {
 	#[inline(always)]
 	fn borrow(&self) -> &str {
 		self.as_str()
 	}
 }
 
-impl<J: Json> std::ops::Deref for LiteralString<J> {
+impl<J: Json> core::ops::Deref for LiteralString<J> {
 	type Target = str;
 
 	#[inline(always)]
@@ -126,6 +142,17 @@ impl<J: JsonHash> Hash for Literal<J> {
 }
 
 impl<J: Json> Literal<J> {
+	/// Converts this literal into a literal of another JSON type.
+	#[inline(always)]
+	pub fn cast<K: util::JsonFrom<J>>(self) -> Literal<K> {
+		match self {
+			Self::Null => Literal::Null,
+			Self::Boolean(b) => Literal::Boolean(b),
+			Self::Number(n) => Literal::Number(n.into()),
+			Self::String(s) => Literal::String(s.cast()),
+		}
+	}
+
 	/// Returns this value as a string if it is one.
 	#[inline(always)]
 	pub fn as_str(&self) -> Option<&str> {
@@ -190,9 +217,16 @@ impl<J: Json, T: Id> Value<J, T> {
 		}
 	}
 
+	/// Returns this value as a boolean if it is one.
+	///
+	/// Accepts native booleans and `xsd:boolean` typed string literals.
 	#[inline(always)]
 	pub fn as_bool(&self) -> Option<bool> {
 		match self {
+			Value::Literal(Literal::String(s), Some(_)) => match self.xsd_datatype() {
+				Some(xsd::Datatype::Boolean) => xsd::parse_boolean(s.as_str()),
+				_ => None,
+			},
 			Value::Literal(lit, _) => lit.as_bool(),
 			_ => None,
 		}
@@ -206,6 +240,152 @@ impl<J: Json, T: Id> Value<J, T> {
 		}
 	}
 
+	/// Returns the XSD datatype of this value, if it is a literal typed
+	/// with a known XSD datatype.
+	pub fn xsd_datatype(&self) -> Option<xsd::Datatype> {
+		match self {
+			Value::Literal(_, Some(ty)) => xsd::Datatype::from_iri(AsIri::as_iri(ty).as_str()),
+			_ => None,
+		}
+	}
+
+	/// Returns this value as an integer.
+	///
+	/// Accepts native numbers with no fractional part, and string literals
+	/// typed with `xsd:integer` or one of its derived datatypes.
+	pub fn as_i64(&self) -> Option<i64> {
+		match self {
+			Value::Literal(Literal::Number(n), _) => n.as_i64(),
+			Value::Literal(Literal::String(s), Some(_)) => match self.xsd_datatype() {
+				Some(ty) if ty.is_integer() => {
+					let s = s.as_str();
+					s.strip_prefix('+').unwrap_or(s).parse().ok()
+				}
+				_ => None,
+			},
+			_ => None,
+		}
+	}
+
+	/// Returns this value as a floating point number.
+	///
+	/// Accepts native numbers, and string literals typed with a numeric XSD datatype
+	/// (including the special `INF`, `-INF` and `NaN` lexical forms).
+	pub fn as_f64(&self) -> Option<f64> {
+		match self {
+			Value::Literal(Literal::Number(n), _) => n.as_f64(),
+			Value::Literal(Literal::String(s), Some(_)) => match self.xsd_datatype() {
+				Some(ty) if ty.is_numeric() => xsd::parse_double(s.as_str()),
+				_ => None,
+			},
+			_ => None,
+		}
+	}
+
+	/// Returns this value as a date if it is a string literal typed with `xsd:date`.
+	pub fn as_date(&self) -> Option<xsd::Date> {
+		match self {
+			Value::Literal(Literal::String(s), Some(_)) => match self.xsd_datatype() {
+				Some(xsd::Datatype::Date) => xsd::Date::parse(s.as_str()),
+				_ => None,
+			},
+			_ => None,
+		}
+	}
+
+	/// Returns this value as a date time if it is a string literal typed with `xsd:dateTime`.
+	pub fn as_datetime(&self) -> Option<xsd::DateTime> {
+		match self {
+			Value::Literal(Literal::String(s), Some(_)) => match self.xsd_datatype() {
+				Some(xsd::Datatype::DateTime) => xsd::DateTime::parse(s.as_str()),
+				_ => None,
+			},
+			_ => None,
+		}
+	}
+
+	/// Returns this value as a duration if it is a string literal typed with `xsd:duration`.
+	pub fn as_duration(&self) -> Option<xsd::Duration> {
+		match self {
+			Value::Literal(Literal::String(s), Some(_)) => match self.xsd_datatype() {
+				Some(xsd::Datatype::Duration) => xsd::Duration::parse(s.as_str()),
+				_ => None,
+			},
+			_ => None,
+		}
+	}
+
+	/// Creates a typed literal value from the given value.
+	///
+	/// The literal is a string holding the canonical lexical form of the value,
+	/// typed with the corresponding XSD datatype.
+	///
+	/// ```
+	/// use json_ld::object::value::xsd;
+	/// use serde_json::Value;
+	///
+	/// let value: json_ld::object::Value<Value> = json_ld::object::Value::typed_literal(1.5f64);
+	/// assert_eq!(value.as_str(), Some("1.5E0"));
+	/// assert_eq!(value.xsd_datatype(), Some(xsd::Datatype::Double));
+	/// assert_eq!(value.as_f64(), Some(1.5));
+	/// ```
+	pub fn typed_literal<V: xsd::Literal>(value: V) -> Self {
+		let ty = value.datatype().iri();
+		Value::Literal(
+			Literal::String(LiteralString::Inferred(value.canonical_form())),
+			Some(T::from_iri(iref::Iri::new(&ty).unwrap())),
+		)
+	}
+
+	/// Returns the normalized form of this value.
+	///
+	/// Normalization canonicalizes literals so that equivalent values are equal:
+	///   - language tags are lowercased;
+	///   - `xsd:string` typed literals become untyped string literals;
+	///   - string literals typed with `xsd:integer` (or a derived datatype) and `xsd:double`
+	///     are rewritten in their canonical lexical form.
+	///
+	/// Invalid lexical forms and other values are left untouched.
+	///
+	/// ```
+	/// use iref::IriBuf;
+	/// use json_ld::object::{value::xsd, Literal, LiteralString, Value};
+	///
+	/// let ty = IriBuf::new(&xsd::Datatype::Integer.iri()).unwrap();
+	/// let value: Value<serde_json::Value> = Value::Literal(
+	///   Literal::String(LiteralString::Inferred("+0042".to_string())),
+	///   Some(ty),
+	/// );
+	/// assert_eq!(value.normalized().as_str(), Some("42"));
+	/// ```
+	pub fn normalized(self) -> Self {
+		match self {
+			Value::LangString(mut s) => {
+				s.normalize_language();
+				Value::LangString(s)
+			}
+			Value::Literal(Literal::String(s), Some(ty)) => {
+				let canonical = match xsd::Datatype::from_iri(AsIri::as_iri(&ty).as_str()) {
+					Some(xsd::Datatype::String) => return Value::Literal(Literal::String(s), None),
+					Some(xsd::Datatype::Double) => {
+						xsd::parse_double(s.as_str()).map(xsd::canonical_double)
+					}
+					Some(dt) if dt.is_integer() => xsd::canonical_integer(s.as_str()),
+					_ => None,
+				};
+
+				match canonical {
+					Some(canonical) => Value::Literal(
+						Literal::String(LiteralString::Inferred(canonical)),
+						Some(ty),
+					),
+					None => Value::Literal(Literal::String(s), Some(ty)),
+				}
+			}
+			value => value,
+		}
+	}
+
 	/// Return the type of the value if any.
 	///
 	/// This will return `Some(Type::Json)` for JSON literal values.
@@ -217,6 +397,31 @@ impl<J: Json, T: Id> Value<J, T> {
 		}
 	}
 
+	/// Maps the datatype of this value with `f`, if it is a typed literal.
+	#[inline(always)]
+	pub fn map_datatype<F: FnOnce(T) -> T>(self, f: F) -> Self {
+		match self {
+			Value::Literal(lit, Some(ty)) => Value::Literal(lit, Some(f(ty))),
+			value => value,
+		}
+	}
+
+	/// Converts this value into a value of another JSON type `K`,
+	/// using the given `meta` function to convert `J::MetaData` into `K::MetaData`.
+	pub fn map_metadata<K: util::JsonFrom<J>>(
+		self,
+		meta: impl Clone + Fn(Option<&J::MetaData>) -> K::MetaData,
+	) -> Value<K, T>
+	where
+		J: JsonClone,
+	{
+		match self {
+			Value::Literal(lit, ty) => Value::Literal(lit.cast(), ty),
+			Value::LangString(s) => Value::LangString(s.cast()),
+			Value::Json(json) => Value::Json(util::AsJson::<J, K>::as_json_with(&json, meta)),
+		}
+	}
+
 	/// If the value is a language tagged string, return its associated language if any.
 	///
 	/// Returns `None` if the value is not a language tagged string.
@@ -261,8 +466,14 @@ impl<J: JsonHash, T: Id> Hash for Value<J, T> {
 	}
 }
 
-impl<J: JsonClone, K: util::JsonFrom<J>, T: Id> util::AsJson<J, K> for Value<J, T> {
-	fn as_json_with(&self, meta: impl Clone + Fn(Option<&J::MetaData>) -> K::MetaData) -> K {
+impl<J: JsonClone, T: Id> Value<J, T> {
+	/// Converts this value into a value object,
+	/// attached to the given metadata (see [`Object::metadata`](crate::Object::metadata)).
+	pub(crate) fn as_json_with_metadata<K: util::JsonFrom<J>>(
+		&self,
+		metadata: Option<&J::MetaData>,
+		meta: impl Clone + Fn(Option<&J::MetaData>) -> K::MetaData,
+	) -> K {
 		let mut obj = K::Object::default();
 
 		match self {
@@ -325,6 +536,13 @@ impl<J: JsonClone, K: util::JsonFrom<J>, T: Id> util::AsJson<J, K> for Value<J,
 			}
 		}
 
-		K::object(obj, meta(None))
+		K::object(obj, meta(metadata))
+	}
+}
+
+impl<J: JsonClone, K: util::JsonFrom<J>, T: Id> util::AsJson<J, K> for Value<J, T> {
+	#[inline(always)]
+	fn as_json_with(&self, meta: impl Clone + Fn(Option<&J::MetaData>) -> K::MetaData) -> K {
+		self.as_json_with_metadata(None, meta)
 	}
 }
@@ -0,0 +1,698 @@
+//! XSD datatypes support for typed literals.
+#[cfg(not(feature = "std"))]
+use crate::prelude::*;
+use core::convert::TryFrom;
+use core::fmt;
+
+#[cfg(feature = "chrono")]
+mod chrono;
+
+/// `xsd` namespace IRI.
+pub const NAMESPACE: &str = "http://www.w3.org/2001/XMLSchema#";
+
+/// XSD datatype known by the typed literal accessors.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Datatype {
+	Boolean,
+	Integer,
+	Long,
+	Int,
+	Short,
+	Byte,
+	NonNegativeInteger,
+	PositiveInteger,
+	NonPositiveInteger,
+	NegativeInteger,
+	UnsignedLong,
+	UnsignedInt,
+	UnsignedShort,
+	UnsignedByte,
+	Decimal,
+	Double,
+	Float,
+	String,
+	Date,
+	DateTime,
+	Duration,
+}
+
+impl Datatype {
+	/// Returns the datatype identified by the given IRI, if it is known.
+	pub fn from_iri(iri: &str) -> Option<Datatype> {
+		use Datatype::*;
+		let name = iri.strip_prefix(NAMESPACE)?;
+		match name {
+			"boolean" => Some(Boolean),
+			"integer" => Some(Integer),
+			"long" => Some(Long),
+			"int" => Some(Int),
+			"short" => Some(Short),
+			"byte" => Some(Byte),
+			"nonNegativeInteger" => Some(NonNegativeInteger),
+			"positiveInteger" => Some(PositiveInteger),
+			"nonPositiveInteger" => Some(NonPositiveInteger),
+			"negativeInteger" => Some(NegativeInteger),
+			"unsignedLong" => Some(UnsignedLong),
+			"unsignedInt" => Some(UnsignedInt),
+			"unsignedShort" => Some(UnsignedShort),
+			"unsignedByte" => Some(UnsignedByte),
+			"decimal" => Some(Decimal),
+			"double" => Some(Double),
+			"float" => Some(Float),
+			"string" => Some(String),
+			"date" => Some(Date),
+			"dateTime" => Some(DateTime),
+			"duration" => Some(Duration),
+			_ => None,
+		}
+	}
+
+	/// Local name of the datatype in the `xsd` namespace.
+	pub fn name(&self) -> &'static str {
+		use Datatype::*;
+		match self {
+			Boolean => "boolean",
+			Integer => "integer",
+			Long => "long",
+			Int => "int",
+			Short => "short",
+			Byte => "byte",
+			NonNegativeInteger => "nonNegativeInteger",
+			PositiveInteger => "positiveInteger",
+			NonPositiveInteger => "nonPositiveInteger",
+			NegativeInteger => "negativeInteger",
+			UnsignedLong => "unsignedLong",
+			UnsignedInt => "unsignedInt",
+			UnsignedShort => "unsignedShort",
+			UnsignedByte => "unsignedByte",
+			Decimal => "decimal",
+			Double => "double",
+			Float => "float",
+			String => "string",
+			Date => "date",
+			DateTime => "dateTime",
+			Duration => "duration",
+		}
+	}
+
+	/// Full IRI of the datatype.
+	pub fn iri(&self) -> alloc::string::String {
+		NAMESPACE.to_string() + self.name()
+	}
+
+	/// Checks if this is `xsd:integer` or one of its derived datatypes.
+	pub fn is_integer(&self) -> bool {
+		use Datatype::*;
+		!matches!(
+			self,
+			Boolean | Decimal | Double | Float | String | Date | DateTime | Duration
+		)
+	}
+
+	/// Checks if this is a numeric datatype.
+	pub fn is_numeric(&self) -> bool {
+		self.is_integer() || matches!(self, Datatype::Decimal | Datatype::Double | Datatype::Float)
+	}
+}
+
+/// Parses an `xsd:boolean` lexical form.
+pub fn parse_boolean(s: &str) -> Option<bool> {
+	match s {
+		"true" | "1" => Some(true),
+		"false" | "0" => Some(false),
+		_ => None,
+	}
+}
+
+/// Parses an `xsd:double` (or `xsd:float`) lexical form,
+/// including the special `INF`, `-INF` and `NaN` forms.
+pub fn parse_double(s: &str) -> Option<f64> {
+	match s {
+		"INF" | "+INF" => Some(f64::INFINITY),
+		"-INF" => Some(f64::NEG_INFINITY),
+		"NaN" => Some(f64::NAN),
+		s if s
+			.bytes()
+			.all(|b| b.is_ascii_digit() || matches!(b, b'+' | b'-' | b'.' | b'e' | b'E')) =>
+		{
+			s.parse().ok()
+		}
+		_ => None,
+	}
+}
+
+/// Canonical `xsd:integer` lexical form of the given lexical form,
+/// without sign `+` or leading zeros.
+///
+/// Returns `None` if the input is not a valid `xsd:integer` lexical form.
+/// Integers of any size are supported.
+pub fn canonical_integer(s: &str) -> Option<String> {
+	let (negative, digits) = match s.as_bytes().first() {
+		Some(b'-') => (true, &s[1..]),
+		Some(b'+') => (false, &s[1..]),
+		_ => (false, s),
+	};
+
+	if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+		return None;
+	}
+
+	let digits = digits.trim_start_matches('0');
+	if digits.is_empty() {
+		Some("0".to_string())
+	} else if negative {
+		Some(format!("-{}", digits))
+	} else {
+		Some(digits.to_string())
+	}
+}
+
+/// Time zone offset, in minutes.
+pub type Offset = i16;
+
+/// `xsd:date` value.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct Date {
+	pub year: i32,
+	pub month: u8,
+	pub day: u8,
+
+	/// Time zone offset in minutes, if any.
+	pub offset: Option<Offset>,
+}
+
+/// `xsd:dateTime` value.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct DateTime {
+	pub year: i32,
+	pub month: u8,
+	pub day: u8,
+	pub hour: u8,
+	pub minute: u8,
+	pub second: u8,
+	pub nanosecond: u32,
+
+	/// Time zone offset in minutes, if any.
+	pub offset: Option<Offset>,
+}
+
+fn digits(s: &str, n: usize) -> Option<(u32, &str)> {
+	if s.len() >= n && s.as_bytes()[..n].iter().all(u8::is_ascii_digit) {
+		Some((s[..n].parse().ok()?, &s[n..]))
+	} else {
+		None
+	}
+}
+
+fn expect(s: &str, c: char) -> Option<&str> {
+	s.strip_prefix(c)
+}
+
+fn parse_date_part(s: &str) -> Option<(i32, u8, u8, &str)> {
+	let (negative, s) = match s.strip_prefix('-') {
+		Some(rest) => (true, rest),
+		None => (false, s),
+	};
+
+	let len = s.find('-')?;
+	if len < 4 {
+		return None;
+	}
+	let (year, s) = digits(s, len)?;
+	let s = expect(s, '-')?;
+	let (month, s) = digits(s, 2)?;
+	let s = expect(s, '-')?;
+	let (day, s) = digits(s, 2)?;
+
+	let year = i32::try_from(year).ok()?;
+	let year = if negative { -year } else { year };
+	if !(1..=12).contains(&month) || day < 1 || day > days_in_month(year, month) {
+		return None;
+	}
+
+	Some((year, month as u8, day as u8, s))
+}
+
+/// Number of days of the given month (from 1 to 12), in the proleptic Gregorian calendar.
+fn days_in_month(year: i32, month: u32) -> u32 {
+	match month {
+		2 => {
+			let leap =
+				year.rem_euclid(4) == 0 && (year.rem_euclid(100) != 0 || year.rem_euclid(400) == 0);
+			if leap {
+				29
+			} else {
+				28
+			}
+		}
+		4 | 6 | 9 | 11 => 30,
+		_ => 31,
+	}
+}
+
+fn parse_offset(s: &str) -> Option<Option<Offset>> {
+	match s {
+		"" => Some(None),
+		"Z" => Some(Some(0)),
+		_ => {
+			let (sign, s) = match s.as_bytes()[0] {
+				b'+' => (1, &s[1..]),
+				b'-' => (-1, &s[1..]),
+				_ => return None,
+			};
+			let (hours, s) = digits(s, 2)?;
+			let s = expect(s, ':')?;
+			let (minutes, s) = digits(s, 2)?;
+			if !s.is_empty() || hours > 14 || minutes > 59 || (hours == 14 && minutes > 0) {
+				return None;
+			}
+
+			Some(Some(sign * (hours * 60 + minutes) as Offset))
+		}
+	}
+}
+
+fn fmt_offset(offset: Option<Offset>, f: &mut fmt::Formatter) -> fmt::Result {
+	match offset {
+		None => Ok(()),
+		Some(0) => write!(f, "Z"),
+		Some(offset) => {
+			let sign = if offset < 0 { '-' } else { '+' };
+			let offset = offset.abs();
+			write!(f, "{}{:02}:{:02}", sign, offset / 60, offset % 60)
+		}
+	}
+}
+
+fn fmt_year(year: i32, f: &mut fmt::Formatter) -> fmt::Result {
+	if year < 0 {
+		write!(f, "-{:04}", -year)
+	} else {
+		write!(f, "{:04}", year)
+	}
+}
+
+impl Date {
+	/// Parses an `xsd:date` lexical form.
+	///
+	/// The day must exist in the given month and year,
+	/// the year must fit in an `i32`, and the timezone offset must be between
+	/// `-14:00` and `+14:00`.
+	///
+	/// ```
+	/// use json_ld::object::value::xsd::Date;
+	///
+	/// assert!(Date::parse("2020-02-29").is_some());
+	/// assert!(Date::parse("2021-02-29").is_none());
+	/// assert!(Date::parse("2021-04-31").is_none());
+	/// assert!(Date::parse("3000000000-01-01").is_none());
+	/// assert!(Date::parse("2021-01-01+14:00").is_some());
+	/// assert!(Date::parse("2021-01-01+14:59").is_none());
+	/// ```
+	pub fn parse(s: &str) -> Option<Date> {
+		let (year, month, day, s) = parse_date_part(s)?;
+		Some(Date {
+			year,
+			month,
+			day,
+			offset: parse_offset(s)?,
+		})
+	}
+}
+
+impl fmt::Display for Date {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		fmt_year(self.year, f)?;
+		write!(f, "-{:02}-{:02}", self.month, self.day)?;
+		fmt_offset(self.offset, f)
+	}
+}
+
+impl DateTime {
+	/// Parses an `xsd:dateTime` lexical form.
+	///
+	/// The day must exist in the given month and year,
+	/// and the hour may only be 24 at the end of the day (`24:00:00`).
+	///
+	/// ```
+	/// use json_ld::object::value::xsd::DateTime;
+	///
+	/// assert!(DateTime::parse("2000-02-29T24:00:00Z").is_some());
+	/// assert!(DateTime::parse("2021-02-31T12:00:00").is_none());
+	/// assert!(DateTime::parse("1900-02-29T12:00:00").is_none());
+	/// assert!(DateTime::parse("2021-01-01T24:00:01").is_none());
+	/// assert!(DateTime::parse("2021-01-01T24:30:00").is_none());
+	/// assert!(DateTime::parse("2021-01-01T24:00:00.5").is_none());
+	/// assert!(DateTime::parse("2021-01-01T25:00:00").is_none());
+	/// assert!(DateTime::parse("-3000000000-01-01T12:00:00").is_none());
+	/// assert!(DateTime::parse("2021-01-01T12:00:00-14:30").is_none());
+	/// ```
+	pub fn parse(s: &str) -> Option<DateTime> {
+		let (year, month, day, s) = parse_date_part(s)?;
+		let s = expect(s, 'T')?;
+		let (hour, s) = digits(s, 2)?;
+		let s = expect(s, ':')?;
+		let (minute, s) = digits(s, 2)?;
+		let s = expect(s, ':')?;
+		let (second, mut s) = digits(s, 2)?;
+
+		let mut nanosecond = 0;
+		let mut zero_fraction = true;
+		if let Some(rest) = s.strip_prefix('.') {
+			let len = rest.bytes().take_while(u8::is_ascii_digit).count();
+			if len == 0 {
+				return None;
+			}
+
+			zero_fraction = rest[..len].bytes().all(|b| b == b'0');
+			let fraction = &rest[..len.min(9)];
+			nanosecond = fraction.parse::<u32>().ok()? * 10u32.pow(9 - fraction.len() as u32);
+			s = &rest[len..];
+		}
+
+		if hour > 24 || minute > 59 || second > 59 {
+			return None;
+		}
+
+		// `24:00:00` is the only valid time with hour 24.
+		if hour == 24 && (minute > 0 || second > 0 || !zero_fraction) {
+			return None;
+		}
+
+		Some(DateTime {
+			year,
+			month,
+			day,
+			hour: hour as u8,
+			minute: minute as u8,
+			second: second as u8,
+			nanosecond,
+			offset: parse_offset(s)?,
+		})
+	}
+
+	/// Date part of this date time.
+	pub fn date(&self) -> Date {
+		Date {
+			year: self.year,
+			month: self.month,
+			day: self.day,
+			offset: self.offset,
+		}
+	}
+}
+
+impl fmt::Display for DateTime {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		fmt_year(self.year, f)?;
+		write!(
+			f,
+			"-{:02}-{:02}T{:02}:{:02}:{:02}",
+			self.month, self.day, self.hour, self.minute, self.second
+		)?;
+
+		if self.nanosecond > 0 {
+			let fraction = format!("{:09}", self.nanosecond);
+			write!(f, ".{}", fraction.trim_end_matches('0'))?;
+		}
+
+		fmt_offset(self.offset, f)
+	}
+}
+
+/// `xsd:duration` value.
+///
+/// Following XSD 1.1, a duration is made of a number of months and a number of seconds,
+/// so that `P1Y` and `P12M` (or `P1D` and `PT24H`) are the same duration.
+///
+/// ```
+/// use json_ld::object::value::xsd::Duration;
+///
+/// let duration = Duration::parse("P1Y14M2DT25H").unwrap();
+/// assert_eq!(duration.to_string(), "P2Y2M3DT1H");
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+pub struct Duration {
+	/// Whether the duration is negative. Always `false` for the zero duration.
+	pub negative: bool,
+
+	/// Months component (years are counted as 12 months).
+	pub months: u64,
+
+	/// Seconds component (days, hours and minutes are converted into seconds).
+	pub seconds: u64,
+
+	/// Fractional part of the seconds component, in nanoseconds.
+	pub nanoseconds: u32,
+}
+
+impl Duration {
+	/// Parses an `xsd:duration` lexical form, such as `P1Y2M3DT4H5M6.7S`.
+	///
+	/// Fractional seconds beyond the nanosecond are truncated.
+	pub fn parse(s: &str) -> Option<Duration> {
+		let (negative, s) = match s.strip_prefix('-') {
+			Some(rest) => (true, rest),
+			None => (false, s),
+		};
+
+		let mut s = expect(s, 'P')?;
+		let mut duration = Duration::default();
+		let mut time = false;
+
+		// Index of the last component, in the order `Y`, `M`, `D`, `H`, `M`, `S`.
+		let mut last: Option<usize> = None;
+
+		while !s.is_empty() {
+			if let Some(rest) = s.strip_prefix('T') {
+				if time {
+					return None;
+				}
+
+				time = true;
+				s = rest;
+				continue;
+			}
+
+			let len = s.bytes().take_while(u8::is_ascii_digit).count();
+			if len == 0 {
+				return None;
+			}
+			let value: u64 = s[..len].parse().ok()?;
+			s = &s[len..];
+
+			let mut nanoseconds = None;
+			if let Some(rest) = s.strip_prefix('.') {
+				let len = rest.bytes().take_while(u8::is_ascii_digit).count();
+				if len == 0 {
+					return None;
+				}
+
+				let fraction = &rest[..len.min(9)];
+				nanoseconds =
+					Some(fraction.parse::<u32>().ok()? * 10u32.pow(9 - fraction.len() as u32));
+				s = &rest[len..];
+			}
+
+			let index = match (time, s.chars().next()?) {
+				(false, 'Y') => 0,
+				(false, 'M') => 1,
+				(false, 'D') => 2,
+				(true, 'H') => 3,
+				(true, 'M') => 4,
+				(true, 'S') => 5,
+				_ => return None,
+			};
+			s = &s[1..];
+
+			if last.map(|last| index <= last).unwrap_or(false)
+				|| (nanoseconds.is_some() && index != 5)
+			{
+				return None;
+			}
+			last = Some(index);
+
+			let (months, seconds) = match index {
+				0 => (value.checked_mul(12)?, 0),
+				1 => (value, 0),
+				2 => (0, value.checked_mul(86400)?),
+				3 => (0, value.checked_mul(3600)?),
+				4 => (0, value.checked_mul(60)?),
+				_ => (0, value),
+			};
+
+			duration.months = duration.months.checked_add(months)?;
+			duration.seconds = duration.seconds.checked_add(seconds)?;
+			duration.nanoseconds = nanoseconds.unwrap_or(0);
+		}
+
+		// At least one component is required, and at least one time component after `T`.
+		match last {
+			Some(last) if !time || last >= 3 => (),
+			_ => return None,
+		}
+
+		duration.negative = negative && !duration.is_zero();
+		Some(duration)
+	}
+
+	/// Checks if this is the zero duration.
+	pub fn is_zero(&self) -> bool {
+		self.months == 0 && self.seconds == 0 && self.nanoseconds == 0
+	}
+}
+
+/// Formats the duration using its canonical lexical form.
+impl fmt::Display for Duration {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		if self.negative {
+			write!(f, "-")?;
+		}
+
+		write!(f, "P")?;
+
+		if self.is_zero() {
+			return write!(f, "T0S");
+		}
+
+		let (years, months) = (self.months / 12, self.months % 12);
+		if years > 0 {
+			write!(f, "{}Y", years)?;
+		}
+		if months > 0 {
+			write!(f, "{}M", months)?;
+		}
+
+		let days = self.seconds / 86400;
+		let hours = self.seconds % 86400 / 3600;
+		let minutes = self.seconds % 3600 / 60;
+		let seconds = self.seconds % 60;
+		if days > 0 {
+			write!(f, "{}D", days)?;
+		}
+
+		if hours > 0 || minutes > 0 || seconds > 0 || self.nanoseconds > 0 {
+			write!(f, "T")?;
+			if hours > 0 {
+				write!(f, "{}H", hours)?;
+			}
+			if minutes > 0 {
+				write!(f, "{}M", minutes)?;
+			}
+			if seconds > 0 || self.nanoseconds > 0 {
+				write!(f, "{}", seconds)?;
+				if self.nanoseconds > 0 {
+					let fraction = format!("{:09}", self.nanoseconds);
+					write!(f, ".{}", fraction.trim_end_matches('0'))?;
+				}
+				write!(f, "S")?;
+			}
+		}
+
+		Ok(())
+	}
+}
+
+/// Canonical `xsd:double` lexical form of the given number.
+pub fn canonical_double(d: f64) -> String {
+	if d.is_nan() {
+		"NaN".to_string()
+	} else if d.is_infinite() {
+		if d > 0.0 {
+			"INF".to_string()
+		} else {
+			"-INF".to_string()
+		}
+	} else {
+		let s = format!("{:E}", d);
+		match s.find('E') {
+			Some(i) if !s[..i].contains('.') => format!("{}.0{}", &s[..i], &s[i..]),
+			_ => s,
+		}
+	}
+}
+
+/// Canonical `xsd:decimal` lexical form of the given finite number.
+///
+/// The decimal point is always present, followed by at least one digit.
+pub fn canonical_decimal(d: f64) -> String {
+	if d == 0.0 {
+		"0.0".to_string()
+	} else {
+		let s = d.to_string();
+		if s.contains('.') {
+			s
+		} else {
+			format!("{}.0", s)
+		}
+	}
+}
+
+/// Value that can be represented as an XSD typed literal.
+pub trait Literal {
+	/// Datatype of the literal.
+	fn datatype(&self) -> Datatype;
+
+	/// Canonical lexical form of the value.
+	fn canonical_form(&self) -> String;
+}
+
+impl Literal for bool {
+	fn datatype(&self) -> Datatype {
+		Datatype::Boolean
+	}
+
+	fn canonical_form(&self) -> String {
+		self.to_string()
+	}
+}
+
+impl Literal for i64 {
+	fn datatype(&self) -> Datatype {
+		Datatype::Integer
+	}
+
+	fn canonical_form(&self) -> String {
+		self.to_string()
+	}
+}
+
+impl Literal for f64 {
+	fn datatype(&self) -> Datatype {
+		Datatype::Double
+	}
+
+	fn canonical_form(&self) -> String {
+		canonical_double(*self)
+	}
+}
+
+impl Literal for Date {
+	fn datatype(&self) -> Datatype {
+		Datatype::Date
+	}
+
+	fn canonical_form(&self) -> String {
+		self.to_string()
+	}
+}
+
+impl Literal for DateTime {
+	fn datatype(&self) -> Datatype {
+		Datatype::DateTime
+	}
+
+	fn canonical_form(&self) -> String {
+		self.to_string()
+	}
+}
+
+impl Literal for Duration {
+	fn datatype(&self) -> Datatype {
+		Datatype::Duration
+	}
+
+	fn canonical_form(&self) -> String {
+		self.to_string()
+	}
+}
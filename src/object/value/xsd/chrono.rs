@@ -0,0 +1,177 @@
+//! Conversions between XSD temporal values and [`chrono`](::chrono) types.
+use super::{Datatype, Date, DateTime, Duration, Literal, Offset};
+use ::chrono::{
+	Datelike, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime, Offset as _, TimeZone, Timelike,
+};
+use core::convert::TryFrom;
+
+fn fixed_offset(offset: Offset) -> Option<FixedOffset> {
+	FixedOffset::east_opt(offset as i32 * 60)
+}
+
+impl Date {
+	/// Converts this date into a `chrono` naive date, ignoring its time zone offset.
+	///
+	/// Returns `None` if the date does not exist (such as `2021-02-30`)
+	/// or is out of the range supported by `chrono`.
+	pub fn to_naive_date(&self) -> Option<NaiveDate> {
+		NaiveDate::from_ymd_opt(self.year, self.month as u32, self.day as u32)
+	}
+}
+
+impl From<NaiveDate> for Date {
+	fn from(date: NaiveDate) -> Self {
+		Date {
+			year: date.year(),
+			month: date.month() as u8,
+			day: date.day() as u8,
+			offset: None,
+		}
+	}
+}
+
+impl DateTime {
+	/// Converts this date time into a `chrono` naive date time, ignoring its time zone offset.
+	///
+	/// The `24:00:00` time is converted into the first instant of the next day.
+	/// Returns `None` if the date time does not exist or is out of the range supported by `chrono`.
+	pub fn to_naive_datetime(&self) -> Option<NaiveDateTime> {
+		let date = self.date().to_naive_date()?;
+		if self.hour == 24 {
+			if self.minute != 0 || self.second != 0 || self.nanosecond != 0 {
+				return None;
+			}
+
+			Some(NaiveDateTime::new(
+				date.succ_opt()?,
+				NaiveTime::from_hms_opt(0, 0, 0)?,
+			))
+		} else {
+			let time = NaiveTime::from_hms_nano_opt(
+				self.hour as u32,
+				self.minute as u32,
+				self.second as u32,
+				self.nanosecond,
+			)?;
+
+			Some(NaiveDateTime::new(date, time))
+		}
+	}
+
+	/// Converts this date time into a `chrono` date time with a fixed offset.
+	///
+	/// Returns `None` if this date time has no time zone offset,
+	/// or if it cannot be represented by `chrono`.
+	pub fn to_chrono(&self) -> Option<::chrono::DateTime<FixedOffset>> {
+		let offset = fixed_offset(self.offset?)?;
+		offset
+			.from_local_datetime(&self.to_naive_datetime()?)
+			.single()
+	}
+}
+
+impl From<NaiveDateTime> for DateTime {
+	fn from(datetime: NaiveDateTime) -> Self {
+		DateTime {
+			year: datetime.year(),
+			month: datetime.month() as u8,
+			day: datetime.day() as u8,
+			hour: datetime.hour() as u8,
+			minute: datetime.minute() as u8,
+			second: datetime.second() as u8,
+			// Leap seconds are represented by a nanosecond count above one second.
+			nanosecond: datetime.nanosecond().min(999_999_999),
+			offset: None,
+		}
+	}
+}
+
+/// Keeps the offset of the date time, truncated to the minute.
+impl<Tz: TimeZone> From<::chrono::DateTime<Tz>> for DateTime {
+	fn from(datetime: ::chrono::DateTime<Tz>) -> Self {
+		let offset = datetime.offset().fix().local_minus_utc() / 60;
+		DateTime {
+			offset: Some(offset as Offset),
+			..DateTime::from(datetime.naive_local())
+		}
+	}
+}
+
+impl Duration {
+	/// Converts this duration into a `chrono` duration.
+	///
+	/// Returns `None` if the duration has a months component, whose length in seconds
+	/// is not fixed, or if it is out of the range supported by `chrono`.
+	pub fn to_chrono(&self) -> Option<::chrono::Duration> {
+		if self.months != 0 || self.seconds > i64::MAX as u64 / 1000 {
+			return None;
+		}
+
+		let duration = ::chrono::Duration::seconds(i64::try_from(self.seconds).ok()?)
+			.checked_add(&::chrono::Duration::nanoseconds(self.nanoseconds as i64))?;
+
+		if self.negative {
+			Some(-duration)
+		} else {
+			Some(duration)
+		}
+	}
+}
+
+impl From<::chrono::Duration> for Duration {
+	fn from(duration: ::chrono::Duration) -> Self {
+		let negative = duration < ::chrono::Duration::zero();
+		let duration = if negative { -duration } else { duration };
+		let seconds = duration.num_seconds();
+		let nanoseconds = (duration - ::chrono::Duration::seconds(seconds))
+			.num_nanoseconds()
+			.unwrap_or(0);
+
+		Duration {
+			negative,
+			months: 0,
+			seconds: seconds as u64,
+			nanoseconds: nanoseconds as u32,
+		}
+	}
+}
+
+impl Literal for NaiveDate {
+	fn datatype(&self) -> Datatype {
+		Datatype::Date
+	}
+
+	fn canonical_form(&self) -> String {
+		Date::from(*self).to_string()
+	}
+}
+
+impl Literal for NaiveDateTime {
+	fn datatype(&self) -> Datatype {
+		Datatype::DateTime
+	}
+
+	fn canonical_form(&self) -> String {
+		DateTime::from(*self).to_string()
+	}
+}
+
+impl<Tz: TimeZone> Literal for ::chrono::DateTime<Tz> {
+	fn datatype(&self) -> Datatype {
+		Datatype::DateTime
+	}
+
+	fn canonical_form(&self) -> String {
+		DateTime::from(self.clone()).to_string()
+	}
+}
+
+impl Literal for ::chrono::Duration {
+	fn datatype(&self) -> Datatype {
+		Datatype::Duration
+	}
+
+	fn canonical_form(&self) -> String {
+		Duration::from(*self).to_string()
+	}
+}
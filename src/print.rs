@@ -0,0 +1,564 @@
+//! Pretty-printing of expanded documents.
+//!
+//! Expanded documents, objects and node objects can be printed as compact JSON,
+//! as indented JSON, or as an indented tree meant to be read by humans,
+//! where IRIs are not quoted and can be abbreviated using a prefix map
+//! (see [`Options`]).
+//! Their [`Display`](fmt::Display) implementation prints compact JSON,
+//! or indented JSON with the alternate flag (`{:#}`).
+//!
+//! Since expanded documents and some of their entries (such as `@graph`) are sets,
+//! their items are printed in the order of their compact JSON representation,
+//! so that the output is reproducible.
+//!
+//! ```
+//! use iref::IriBuf;
+//! use json_ld::{context, print, Document, NoLoader};
+//! use serde_json::{json, Value};
+//!
+//! # async_std::task::block_on(async {
+//! let doc = json!({
+//!   "@id": "http://example.org/alice",
+//!   "@type": "http://xmlns.com/foaf/0.1/Person",
+//!   "http://xmlns.com/foaf/0.1/name": "Alice"
+//! });
+//!
+//! let mut loader = NoLoader::<Value>::new();
+//! let expanded = doc.expand::<context::Json<Value, IriBuf>, _>(&mut loader).await.unwrap();
+//!
+//! let prefixes = [("ex", "http://example.org/"), ("foaf", "http://xmlns.com/foaf/0.1/")];
+//! let options = print::Options {
+//!   style: print::Style::Tree,
+//!   prefixes: &prefixes,
+//!   ..print::Options::default()
+//! };
+//!
+//! assert_eq!(
+//!   expanded.pretty(options).to_string(),
+//!   "- @id: ex:alice\n  @type:\n    - foaf:Person\n  foaf:name:\n    - @value: \"Alice\""
+//! );
+//!
+//! assert!(expanded.to_string().starts_with(r#"[{"@id":"http://example.org/alice","#));
+//! # })
+//! ```
+use crate::{
+	object::{Literal, Value},
+	rdf::write_json_string,
+	ExpandedDocument, Id, Indexed, Node, Object, Reference,
+};
+use cc_traits::{Iter, MapIter};
+use generic_json::{Json, JsonHash, Number, ValueRef};
+use iref::AsIri;
+use std::fmt::{self, Write};
+
+/// Printing style.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Style {
+	/// JSON without any whitespace.
+	Compact,
+
+	/// Indented JSON.
+	Indented,
+
+	/// Indented tree, similar to YAML.
+	///
+	/// IRIs and blank node identifiers are not quoted, and IRIs are abbreviated
+	/// using the [prefixes](Options::prefixes) of the options.
+	/// Strings are quoted.
+	Tree,
+}
+
+/// Printing options.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Options<'a> {
+	/// Printing style.
+	///
+	/// Default is [`Style::Indented`].
+	pub style: Style,
+
+	/// Number of spaces per indentation level.
+	///
+	/// Default is 2.
+	pub indent: usize,
+
+	/// Prefixes used to abbreviate IRIs in the [tree](Style::Tree) style,
+	/// as `(prefix, namespace)` pairs.
+	///
+	/// An IRI starting with a namespace is abbreviated into a compact IRI,
+	/// using the longest matching namespace.
+	/// IRIs are never abbreviated in JSON, so that the output is still
+	/// a valid expanded document.
+	pub prefixes: &'a [(&'a str, &'a str)],
+}
+
+impl<'a> Default for Options<'a> {
+	fn default() -> Self {
+		Self {
+			style: Style::Indented,
+			indent: 2,
+			prefixes: &[],
+		}
+	}
+}
+
+impl<'a> Options<'a> {
+	/// Abbreviates the given IRI using the prefixes.
+	fn abbreviate(&self, iri: &str) -> String {
+		let prefix = self
+			.prefixes
+			.iter()
+			.filter(|(_, namespace)| !namespace.is_empty() && iri.starts_with(namespace))
+			.max_by_key(|(_, namespace)| namespace.len());
+
+		match prefix {
+			Some((prefix, namespace)) => format!("{}:{}", prefix, &iri[namespace.len()..]),
+			None => iri.to_string(),
+		}
+	}
+}
+
+/// Printable value, with its printing options.
+///
+/// See the [module documentation](self) for the details.
+pub struct Pretty<'a> {
+	value: &'a dyn ToItem,
+	options: Options<'a>,
+}
+
+impl<'a> fmt::Display for Pretty<'a> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		let item = self.value.to_item();
+		match self.options.style {
+			Style::Compact => write_json(&item, None, 0, f),
+			Style::Indented => write_json(&item, Some(self.options.indent), 0, f),
+			Style::Tree => {
+				let mut out = String::new();
+				write_tree(&item, &self.options, 0, false, &mut out)?;
+				f.write_str(out.trim_end_matches('\n'))
+			}
+		}
+	}
+}
+
+impl<J: JsonHash, T: Id> ExpandedDocument<J, T> {
+	/// Returns a printable version of the document,
+	/// using the given options.
+	pub fn pretty<'a>(&'a self, options: Options<'a>) -> Pretty<'a> {
+		Pretty {
+			value: self,
+			options,
+		}
+	}
+}
+
+impl<J: JsonHash, T: Id> Object<J, T> {
+	/// Returns a printable version of the object,
+	/// using the given options.
+	pub fn pretty<'a>(&'a self, options: Options<'a>) -> Pretty<'a> {
+		Pretty {
+			value: self,
+			options,
+		}
+	}
+}
+
+impl<J: JsonHash, T: Id> Node<J, T> {
+	/// Returns a printable version of the node,
+	/// using the given options.
+	pub fn pretty<'a>(&'a self, options: Options<'a>) -> Pretty<'a> {
+		Pretty {
+			value: self,
+			options,
+		}
+	}
+}
+
+/// Options of the `Display` implementations.
+fn display_options(f: &fmt::Formatter) -> Options<'static> {
+	Options {
+		style: if f.alternate() {
+			Style::Indented
+		} else {
+			Style::Compact
+		},
+		..Options::default()
+	}
+}
+
+/// Prints the document as JSON, compact unless the alternate flag is used.
+impl<J: JsonHash, T: Id> fmt::Display for ExpandedDocument<J, T> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		self.pretty(display_options(f)).fmt(f)
+	}
+}
+
+/// Prints the object as JSON, compact unless the alternate flag is used.
+impl<J: JsonHash, T: Id> fmt::Display for Object<J, T> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		self.pretty(display_options(f)).fmt(f)
+	}
+}
+
+/// Prints the node as JSON, compact unless the alternate flag is used.
+impl<J: JsonHash, T: Id> fmt::Display for Node<J, T> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		self.pretty(display_options(f)).fmt(f)
+	}
+}
+
+/// Printed tree.
+enum Item {
+	/// JSON-encoded scalar value.
+	Literal(String),
+
+	/// IRI, blank node identifier or keyword value.
+	Reference(String),
+
+	/// Array, or set.
+	Array(Vec<Item>),
+
+	/// Object entries, in order.
+	Object(Vec<(Key, Item)>),
+}
+
+/// Object entry key.
+enum Key {
+	/// Keyword.
+	Keyword(&'static str),
+
+	/// Property IRI or blank node identifier.
+	Reference(String),
+
+	/// Key of a JSON literal.
+	String(String),
+}
+
+impl Key {
+	fn as_str(&self) -> &str {
+		match self {
+			Self::Keyword(k) => k,
+			Self::Reference(r) => r,
+			Self::String(s) => s,
+		}
+	}
+}
+
+/// Values that can be printed.
+trait ToItem {
+	fn to_item(&self) -> Item;
+}
+
+fn json_string(s: &str) -> String {
+	let mut json = String::new();
+	write_json_string(s, &mut json);
+	json
+}
+
+fn string(s: &str) -> Item {
+	Item::Literal(json_string(s))
+}
+
+fn reference<T: AsIri>(r: &Reference<T>) -> Item {
+	Item::Reference(r.to_string())
+}
+
+/// Builds the item of a set, sorting the items by their compact JSON representation.
+fn set(items: impl Iterator<Item = Item>) -> Item {
+	let mut items: Vec<_> = items
+		.map(|item| {
+			let mut json = String::new();
+			write_json(&item, None, 0, &mut json).unwrap();
+			(json, item)
+		})
+		.collect();
+	items.sort_by(|(a, _), (b, _)| a.cmp(b));
+	Item::Array(items.into_iter().map(|(_, item)| item).collect())
+}
+
+fn indexed<X: ToItem>(value: &Indexed<X>) -> Item {
+	let mut item = value.inner().to_item();
+	if let Item::Object(entries) = &mut item {
+		if let Some(index) = value.index() {
+			entries.push((Key::Keyword("@index"), string(index)))
+		}
+
+		if let Some(annotation) = value.annotation() {
+			entries.push((
+				Key::Keyword("@annotation"),
+				set(annotation.iter().map(indexed)),
+			))
+		}
+	}
+
+	item
+}
+
+fn json<J: Json>(value: &J) -> Item {
+	match value.as_value_ref() {
+		ValueRef::Null => Item::Literal("null".to_string()),
+		ValueRef::Boolean(b) => Item::Literal(b.to_string()),
+		ValueRef::Number(n) => number(n),
+		ValueRef::String(s) => string(s.as_ref()),
+		ValueRef::Array(items) => Item::Array(items.iter().map(|item| json(&*item)).collect()),
+		ValueRef::Object(entries) => Item::Object(
+			entries
+				.iter()
+				.map(|(key, value)| {
+					let key: &str = key.as_ref();
+					(Key::String(key.to_string()), json(&*value))
+				})
+				.collect(),
+		),
+	}
+}
+
+fn number<N: Number>(n: &N) -> Item {
+	let s = match (n.as_i64(), n.as_u64(), n.as_f64()) {
+		(Some(i), _, _) => i.to_string(),
+		(None, Some(u), _) => u.to_string(),
+		(None, None, Some(d)) if d.is_finite() => d.to_string(),
+		_ => "null".to_string(),
+	};
+
+	Item::Literal(s)
+}
+
+impl<J: JsonHash, T: Id> ToItem for ExpandedDocument<J, T> {
+	fn to_item(&self) -> Item {
+		set(self.iter().map(indexed))
+	}
+}
+
+impl<J: JsonHash, T: Id> ToItem for Object<J, T> {
+	fn to_item(&self) -> Item {
+		match self {
+			Object::Value(value, _) => value.to_item(),
+			Object::Node(node) => node.to_item(),
+			Object::List(items, _) => Item::Object(vec![(
+				Key::Keyword("@list"),
+				Item::Array(items.iter().map(indexed).collect()),
+			)]),
+		}
+	}
+}
+
+impl<J: JsonHash, T: Id> ToItem for Value<J, T> {
+	fn to_item(&self) -> Item {
+		let mut entries = Vec::new();
+		match self {
+			Value::Literal(literal, ty) => {
+				let value = match literal {
+					Literal::Null => Item::Literal("null".to_string()),
+					Literal::Boolean(b) => Item::Literal(b.to_string()),
+					Literal::Number(n) => number(n),
+					Literal::String(s) => string(s.as_str()),
+				};
+
+				entries.push((Key::Keyword("@value"), value));
+				if let Some(ty) = ty {
+					let ty = Item::Reference(ty.as_iri().to_string());
+					entries.push((Key::Keyword("@type"), ty))
+				}
+			}
+			Value::LangString(s) => {
+				entries.push((Key::Keyword("@value"), string(s.as_str())));
+				if let Some(language) = s.language() {
+					entries.push((Key::Keyword("@language"), string(&language.to_string())))
+				}
+				if let Some(direction) = s.direction() {
+					entries.push((Key::Keyword("@direction"), string(&direction.to_string())))
+				}
+			}
+			Value::Json(value) => {
+				entries.push((Key::Keyword("@value"), json(value)));
+				entries.push((Key::Keyword("@type"), Item::Reference("@json".to_string())))
+			}
+		}
+
+		Item::Object(entries)
+	}
+}
+
+impl<J: JsonHash, T: Id> ToItem for Node<J, T> {
+	fn to_item(&self) -> Item {
+		let mut entries = Vec::new();
+
+		if let Some(id) = self.id() {
+			entries.push((Key::Keyword("@id"), reference(id)))
+		}
+
+		if !self.types().is_empty() {
+			let types = self.types().iter().map(reference).collect();
+			entries.push((Key::Keyword("@type"), Item::Array(types)))
+		}
+
+		if let Some(graph) = self.graph() {
+			entries.push((Key::Keyword("@graph"), set(graph.iter().map(indexed))))
+		}
+
+		if let Some(included) = self.included() {
+			entries.push((Key::Keyword("@included"), set(included.iter().map(indexed))))
+		}
+
+		for (property, objects) in self.properties().iter() {
+			let objects = Item::Array(objects.iter().map(indexed).collect());
+			entries.push((Key::Reference(property.to_string()), objects))
+		}
+
+		if !self.reverse_properties().is_empty() {
+			let reverse = self
+				.reverse_properties()
+				.iter()
+				.map(|(property, nodes)| {
+					let nodes = Item::Array(nodes.iter().map(indexed).collect());
+					(Key::Reference(property.to_string()), nodes)
+				})
+				.collect();
+			entries.push((Key::Keyword("@reverse"), Item::Object(reverse)))
+		}
+
+		Item::Object(entries)
+	}
+}
+
+fn write_indent<W: Write>(indent: Option<usize>, level: usize, out: &mut W) -> fmt::Result {
+	match indent {
+		Some(indent) => write!(out, "\n{:1$}", "", indent * level),
+		None => Ok(()),
+	}
+}
+
+fn write_json<W: Write>(
+	item: &Item,
+	indent: Option<usize>,
+	level: usize,
+	out: &mut W,
+) -> fmt::Result {
+	match item {
+		Item::Literal(s) => out.write_str(s),
+		Item::Reference(r) => out.write_str(&json_string(r)),
+		Item::Array(items) if items.is_empty() => out.write_str("[]"),
+		Item::Array(items) => {
+			out.write_char('[')?;
+			for (i, item) in items.iter().enumerate() {
+				if i > 0 {
+					out.write_char(',')?
+				}
+				write_indent(indent, level + 1, out)?;
+				write_json(item, indent, level + 1, out)?
+			}
+			write_indent(indent, level, out)?;
+			out.write_char(']')
+		}
+		Item::Object(entries) if entries.is_empty() => out.write_str("{}"),
+		Item::Object(entries) => {
+			out.write_char('{')?;
+			for (i, (key, value)) in entries.iter().enumerate() {
+				if i > 0 {
+					out.write_char(',')?
+				}
+				write_indent(indent, level + 1, out)?;
+				out.write_str(&json_string(key.as_str()))?;
+				out.write_str(if indent.is_some() { ": " } else { ":" })?;
+				write_json(value, indent, level + 1, out)?
+			}
+			write_indent(indent, level, out)?;
+			out.write_char('}')
+		}
+	}
+}
+
+impl Item {
+	/// Checks if the item is printed on a single line in the tree style.
+	fn is_inline(&self) -> bool {
+		match self {
+			Self::Array(items) => items.is_empty(),
+			Self::Object(entries) => entries.is_empty(),
+			_ => true,
+		}
+	}
+}
+
+/// Writes an item on a single line, in the tree style.
+fn write_tree_inline<W: Write>(item: &Item, options: &Options, out: &mut W) -> fmt::Result {
+	match item {
+		Item::Literal(s) => out.write_str(s),
+		Item::Reference(r) => out.write_str(&options.abbreviate(r)),
+		Item::Array(_) => out.write_str("[]"),
+		Item::Object(_) => out.write_str("{}"),
+	}
+}
+
+/// Writes an item in the tree style, each line starting at the given indentation level.
+///
+/// If `inline_first` is set, the first line is not indented
+/// (it follows a `- ` array item marker).
+fn write_tree<W: Write>(
+	item: &Item,
+	options: &Options,
+	level: usize,
+	inline_first: bool,
+	out: &mut W,
+) -> fmt::Result {
+	let indent = |out: &mut W| write!(out, "{:1$}", "", options.indent * level);
+
+	match item {
+		Item::Object(entries) if !entries.is_empty() => {
+			for (i, (key, value)) in entries.iter().enumerate() {
+				if i > 0 || !inline_first {
+					indent(out)?
+				}
+
+				match key {
+					Key::Reference(r) => out.write_str(&options.abbreviate(r))?,
+					key => out.write_str(key.as_str())?,
+				}
+				out.write_char(':')?;
+
+				if value.is_inline() {
+					out.write_char(' ')?;
+					write_tree_inline(value, options, out)?;
+					out.write_char('\n')?
+				} else {
+					out.write_char('\n')?;
+					write_tree(value, options, level + 1, false, out)?
+				}
+			}
+
+			Ok(())
+		}
+		Item::Array(items) if !items.is_empty() => {
+			for (i, item) in items.iter().enumerate() {
+				if i > 0 || !inline_first {
+					indent(out)?
+				}
+
+				match item {
+					Item::Object(_) if !item.is_inline() => {
+						out.write_str("- ")?;
+						write_tree(item, options, level + 1, true, out)?
+					}
+					Item::Array(_) if !item.is_inline() => {
+						out.write_str("-\n")?;
+						write_tree(item, options, level + 1, false, out)?
+					}
+					item => {
+						out.write_str("- ")?;
+						write_tree_inline(item, options, out)?;
+						out.write_char('\n')?
+					}
+				}
+			}
+
+			Ok(())
+		}
+		item => {
+			if !inline_first {
+				indent(out)?
+			}
+
+			write_tree_inline(item, options, out)?;
+			out.write_char('\n')
+		}
+	}
+}
@@ -0,0 +1,391 @@
+//! Conversion of JSON-LD documents into RDF.
+//!
+//! This implements the
+//! [Object to RDF Conversion](https://www.w3.org/TR/json-ld11-api/#object-to-rdf-conversion)
+//! algorithm for values, and the conversion of whole documents into [`Quad`]s
+//! (see [`ExpandedDocument::to_rdf`](crate::ExpandedDocument::to_rdf)).
+//! Domain-specific datatypes can be canonicalized during the conversion by
+//! registering [`DatatypeHandlers`].
+//! Datasets can be canonicalized with [`canonicalize`].
+//!
+//! Quads can be converted back into a JSON-LD document with
+//! [`ExpandedDocument::from_rdf`](crate::ExpandedDocument::from_rdf).
+//! With the `rdf-types` feature, terms and quads can be converted from and into the types
+//! of the [`rdf-types`](https://crates.io/crates/rdf-types) crate, so that documents
+//! can be converted from and into any dataset implementation built upon them.
+use crate::util::collections::HashMap;
+use crate::{
+	object::{value::xsd, Literal as JsonLdLiteral},
+	ErrorCode, Id, LenientLanguageTagBuf, RdfDirection, Value,
+};
+use cc_traits::{Iter, MapIter};
+use generic_json::{Json, Number, ValueRef};
+use iref::{AsIri, Iri, IriBuf};
+use std::fmt;
+
+mod canonical;
+mod from_rdf;
+mod quad;
+
+#[cfg(feature = "rdf-types")]
+mod interop;
+
+pub use canonical::*;
+pub use from_rdf::*;
+pub use quad::*;
+
+/// `rdf:JSON` datatype IRI.
+pub const RDF_JSON: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#JSON";
+
+/// RDF literal.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub enum Literal {
+	/// Typed literal, with its lexical form and datatype IRI.
+	Typed(String, IriBuf),
+
+	/// Language-tagged string.
+	LangString(String, LenientLanguageTagBuf),
+}
+
+impl Literal {
+	/// Lexical form of the literal.
+	#[inline(always)]
+	pub fn lexical_form(&self) -> &str {
+		match self {
+			Self::Typed(s, _) => s.as_str(),
+			Self::LangString(s, _) => s.as_str(),
+		}
+	}
+
+	/// Datatype of the literal, if it is a typed literal.
+	#[inline(always)]
+	pub fn datatype(&self) -> Option<Iri> {
+		match self {
+			Self::Typed(_, ty) => Some(ty.as_iri()),
+			Self::LangString(_, _) => None,
+		}
+	}
+}
+
+/// Formats the literal using the N-Quads syntax.
+///
+/// `xsd:string` literals are written as simple literals, without datatype.
+impl fmt::Display for Literal {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "\"")?;
+		for c in self.lexical_form().chars() {
+			match c {
+				'"' => write!(f, "\\\"")?,
+				'\\' => write!(f, "\\\\")?,
+				'\n' => write!(f, "\\n")?,
+				'\r' => write!(f, "\\r")?,
+				c => c.fmt(f)?,
+			}
+		}
+		write!(f, "\"")?;
+
+		match self {
+			Self::Typed(_, ty) if ty.as_str() == xsd::Datatype::String.iri() => Ok(()),
+			Self::Typed(_, ty) => write!(f, "^^<{}>", ty),
+			Self::LangString(_, lang) => write!(f, "@{}", lang.as_str()),
+		}
+	}
+}
+
+/// Datatype handler, returning the lexical form of the given typed literal.
+pub type DatatypeHandler = dyn Send + Sync + Fn(&Literal) -> String;
+
+/// Datatype handlers.
+///
+/// Associates datatype IRIs to functions computing the lexical form of the literals
+/// with this datatype, such as a canonicalization function.
+/// Handlers are applied on typed literals after the standard conversion.
+///
+/// ```
+/// use iref::IriBuf;
+/// use json_ld::rdf::DatatypeHandlers;
+///
+/// let wkt = IriBuf::new("http://www.opengis.net/ont/geosparql#wktLiteral").unwrap();
+/// let handlers = DatatypeHandlers::new()
+///   .with(wkt, |literal| literal.lexical_form().trim().to_uppercase());
+/// ```
+#[derive(Default)]
+pub struct DatatypeHandlers {
+	handlers: HashMap<IriBuf, Box<DatatypeHandler>>,
+}
+
+impl DatatypeHandlers {
+	/// Creates an empty set of handlers.
+	#[inline(always)]
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Registers the handler of the given datatype, replacing any previous handler.
+	#[inline(always)]
+	pub fn insert(
+		&mut self,
+		datatype: IriBuf,
+		handler: impl 'static + Send + Sync + Fn(&Literal) -> String,
+	) {
+		self.handlers.insert(datatype, Box::new(handler));
+	}
+
+	/// Registers the handler of the given datatype.
+	#[must_use]
+	#[inline(always)]
+	pub fn with(
+		mut self,
+		datatype: IriBuf,
+		handler: impl 'static + Send + Sync + Fn(&Literal) -> String,
+	) -> Self {
+		self.insert(datatype, handler);
+		self
+	}
+
+	/// Removes the handler of the given datatype.
+	#[inline(always)]
+	pub fn remove(&mut self, datatype: Iri) -> bool {
+		self.handlers.remove(&IriBuf::from(datatype)).is_some()
+	}
+
+	/// Checks if a handler is registered for the given datatype.
+	#[inline(always)]
+	pub fn contains(&self, datatype: Iri) -> bool {
+		self.handlers.contains_key(&IriBuf::from(datatype))
+	}
+
+	/// Applies the handler associated to the datatype of the given literal, if any.
+	pub fn apply(&self, literal: Literal) -> Literal {
+		match &literal {
+			Literal::Typed(_, ty) => match self.handlers.get(ty) {
+				Some(handler) => Literal::Typed(handler(&literal), ty.clone()),
+				None => literal,
+			},
+			Literal::LangString(_, _) => literal,
+		}
+	}
+}
+
+/// Options of the conversion into RDF literals.
+#[derive(Default)]
+pub struct Options {
+	/// Base direction encoding strategy.
+	///
+	/// If `None`, the direction of strings is dropped.
+	pub rdf_direction: Option<RdfDirection>,
+
+	/// Datatype handlers applied on typed literals.
+	pub datatypes: DatatypeHandlers,
+
+	/// Produces generalized RDF.
+	///
+	/// If set to `true`, statements with a blank node identifier as predicate are kept,
+	/// producing [generalized RDF](https://www.w3.org/TR/rdf11-concepts/#section-generalized-rdf).
+	/// Otherwise they are dropped, with a
+	/// [`BlankNodePredicate`](crate::Warning::BlankNodePredicate) warning.
+	/// This is the `produceGeneralizedRdf` flag of the JSON-LD 1.1 API.
+	///
+	/// Default is `false`.
+	pub produce_generalized_rdf: bool,
+
+	/// Conversion of JSON numbers.
+	pub numbers: NumberOptions,
+}
+
+/// Options of the conversion of JSON numbers into RDF literals.
+///
+/// By default, numbers follow the mapping of the JSON-LD 1.1 API:
+/// integers are converted into `xsd:integer` literals and other numbers into
+/// `xsd:double` literals, in canonical form.
+/// Numbers that cannot be represented by a 64-bit float are dropped.
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub struct NumberOptions {
+	/// Converts non-integer numbers into `xsd:decimal` literals instead of `xsd:double`.
+	///
+	/// Numbers explicitly typed with `xsd:double` or `xsd:float` are not affected.
+	///
+	/// Default is `false`.
+	pub decimal: bool,
+
+	/// Preserves the lexical form of non-integer numbers.
+	///
+	/// The decimal representation of the number, as written in JSON,
+	/// is used instead of the canonical form of the datatype
+	/// (for instance `1.5` instead of `1.5E0`).
+	/// Since JSON numbers are parsed before the conversion, this is the shortest
+	/// representation of the parsed value, which may differ from the original text
+	/// if it has more significant digits than a 64-bit float.
+	///
+	/// Default is `false`.
+	pub preserve_lexical_form: bool,
+
+	/// Lossless mode.
+	///
+	/// If set to `true`, the conversion fails with an
+	/// [`InexactNumber`](crate::ErrorCode::InexactNumber) error when a number cannot be
+	/// represented exactly, instead of dropping it.
+	///
+	/// Default is `false`.
+	pub lossless: bool,
+}
+
+impl<J: Json, T: Id> Value<J, T> {
+	/// Converts this value into an RDF literal.
+	///
+	/// Native booleans and numbers are converted to `xsd:boolean`, `xsd:integer` or
+	/// `xsd:double` literals (in canonical form) unless the value has an explicit type,
+	/// and JSON literals are serialized (with sorted keys and no whitespace)
+	/// into `rdf:JSON` literals.
+	///
+	/// Returns `None` for `null` literals, for strings with a base direction
+	/// when `rdf_direction` is [`RdfDirection::CompoundLiteral`], since they
+	/// cannot be represented by a single literal,
+	/// and for numbers that cannot be represented exactly
+	/// (see [`try_to_rdf_literal`](Self::try_to_rdf_literal) for the lossless mode).
+	#[inline(always)]
+	pub fn to_rdf_literal(&self, options: &Options) -> Option<Literal> {
+		self.try_to_rdf_literal(options).unwrap_or(None)
+	}
+
+	/// Converts this value into an RDF literal, following the [`NumberOptions`].
+	///
+	/// See [`to_rdf_literal`](Self::to_rdf_literal) for the details.
+	/// Fails with an [`InexactNumber`](ErrorCode::InexactNumber) error if the value is a number
+	/// that cannot be represented exactly in the [lossless](NumberOptions::lossless) mode.
+	pub fn try_to_rdf_literal(&self, options: &Options) -> Result<Option<Literal>, ErrorCode> {
+		let typed = |s: String, ty: &Option<T>, default: xsd::Datatype| {
+			let ty = match ty {
+				Some(ty) => AsIri::as_iri(ty).into(),
+				None => IriBuf::new(&default.iri()).unwrap(),
+			};
+
+			Literal::Typed(s, ty)
+		};
+
+		let literal = match self {
+			Value::Literal(JsonLdLiteral::Null, _) => return Ok(None),
+			Value::Literal(JsonLdLiteral::Boolean(b), ty) => {
+				typed(b.to_string(), ty, xsd::Datatype::Boolean)
+			}
+			Value::Literal(JsonLdLiteral::Number(n), ty) => {
+				let numbers = &options.numbers;
+				let datatype = self.xsd_datatype();
+				let is_double = datatype == Some(xsd::Datatype::Double);
+				let integer = match n.as_i64() {
+					Some(i) => Some(i.to_string()),
+					None => n.as_u64().map(|u| u.to_string()),
+				};
+
+				match integer {
+					Some(i) if !is_double => typed(i, ty, xsd::Datatype::Integer),
+					_ => {
+						let d = match n.as_f64() {
+							Some(d) => d,
+							None if numbers.lossless => return Err(ErrorCode::InexactNumber),
+							None => return Ok(None),
+						};
+
+						let is_float = is_double || datatype == Some(xsd::Datatype::Float);
+						if numbers.decimal && !is_float && d.is_finite() {
+							let s = if numbers.preserve_lexical_form {
+								d.to_string()
+							} else {
+								xsd::canonical_decimal(d)
+							};
+
+							typed(s, ty, xsd::Datatype::Decimal)
+						} else if numbers.preserve_lexical_form && d.is_finite() {
+							typed(d.to_string(), ty, xsd::Datatype::Double)
+						} else {
+							typed(xsd::canonical_double(d), ty, xsd::Datatype::Double)
+						}
+					}
+				}
+			}
+			Value::Literal(JsonLdLiteral::String(s), ty) => {
+				typed(s.as_str().to_string(), ty, xsd::Datatype::String)
+			}
+			Value::LangString(s) => match (s.direction(), options.rdf_direction) {
+				(Some(_), Some(RdfDirection::I18nDatatype)) => {
+					Literal::Typed(s.as_str().to_string(), s.i18n_datatype().unwrap())
+				}
+				(Some(_), Some(RdfDirection::CompoundLiteral)) => return Ok(None),
+				_ => match s.language() {
+					Some(language) => {
+						Literal::LangString(s.as_str().to_string(), language.cloned())
+					}
+					None => typed(s.as_str().to_string(), &None, xsd::Datatype::String),
+				},
+			},
+			Value::Json(json) => {
+				let mut s = String::new();
+				write_canonical_json(json, &mut s);
+				Literal::Typed(s, IriBuf::new(RDF_JSON).unwrap())
+			}
+		};
+
+		Ok(Some(options.datatypes.apply(literal)))
+	}
+}
+
+/// Writes the given JSON value with sorted object keys and without whitespace.
+fn write_canonical_json<J: Json>(json: &J, out: &mut String) {
+	match json.as_value_ref() {
+		ValueRef::Null => out.push_str("null"),
+		ValueRef::Boolean(b) => out.push_str(if b { "true" } else { "false" }),
+		ValueRef::Number(n) => match (n.as_i64(), n.as_f64()) {
+			(Some(i), _) => out.push_str(&i.to_string()),
+			(None, Some(d)) => out.push_str(&d.to_string()),
+			(None, None) => out.push_str("null"),
+		},
+		ValueRef::String(s) => write_json_string(s.as_ref(), out),
+		ValueRef::Array(items) => {
+			out.push('[');
+			for (i, item) in items.iter().enumerate() {
+				if i > 0 {
+					out.push(',')
+				}
+				write_canonical_json(&*item, out)
+			}
+			out.push(']')
+		}
+		ValueRef::Object(entries) => {
+			let mut entries: Vec<_> = entries.iter().collect();
+			entries.sort_by(|(a, _), (b, _)| {
+				let a: &str = a.as_ref();
+				let b: &str = b.as_ref();
+				a.encode_utf16().cmp(b.encode_utf16())
+			});
+
+			out.push('{');
+			for (i, (key, value)) in entries.into_iter().enumerate() {
+				if i > 0 {
+					out.push(',')
+				}
+				write_json_string(key.as_ref(), out);
+				out.push(':');
+				write_canonical_json(&*value, out)
+			}
+			out.push('}')
+		}
+	}
+}
+
+pub(crate) fn write_json_string(s: &str, out: &mut String) {
+	out.push('"');
+	for c in s.chars() {
+		match c {
+			'"' => out.push_str("\\\""),
+			'\\' => out.push_str("\\\\"),
+			'\u{08}' => out.push_str("\\b"),
+			'\u{0c}' => out.push_str("\\f"),
+			'\n' => out.push_str("\\n"),
+			'\r' => out.push_str("\\r"),
+			'\t' => out.push_str("\\t"),
+			c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+			c => out.push(c),
+		}
+	}
+	out.push('"');
+}
@@ -0,0 +1,411 @@
+use super::{Options, Quad, Term};
+use crate::util::collections::HashMap;
+use crate::{BlankId, BlankIdSequence, Error, ExpandedDocument, Id};
+use generic_json::{JsonClone, JsonHash};
+use std::collections::BTreeMap;
+use std::marker::PhantomData;
+
+/// Message digest algorithm, such as SHA-256.
+///
+/// Used to canonicalize RDF datasets (see [`canonicalize`]) and to hash documents
+/// (see [`ExpandedDocument::semantic_hash`]).
+/// The canonicalization algorithm creates a new digest with [`Default`] each time it needs
+/// to hash a value.
+pub trait Digest: Default {
+	/// Feeds the given data into the digest.
+	fn update(&mut self, data: &[u8]);
+
+	/// Consumes the digest and returns the hash value.
+	fn finalize(self) -> Vec<u8>;
+}
+
+/// Hashes the given string and returns the hash value in lowercase hexadecimal.
+fn hash<D: Digest>(data: &str) -> String {
+	let mut digest = D::default();
+	digest.update(data.as_bytes());
+	digest
+		.finalize()
+		.iter()
+		.map(|byte| format!("{:02x}", byte))
+		.collect()
+}
+
+/// Relabels the blank nodes of the given dataset with canonical identifiers.
+///
+/// This implements the
+/// [URDNA2015](https://www.w3.org/TR/rdf-canon/) RDF dataset canonicalization algorithm,
+/// using the digest `D` as hash algorithm.
+/// Blank nodes are relabeled `_:c14n0`, `_:c14n1`, etc.
+/// such that isomorphic datasets are relabeled identically.
+/// Blank nodes occurring in [quoted triples](Term::Triple) are not relabeled.
+///
+/// The algorithm is exponential in the worst case
+/// (when many blank nodes cannot be distinguished by their neighborhood).
+pub fn canonicalize<D: Digest>(quads: &[Quad]) -> Vec<Quad> {
+	let issuer = Canonicalizer::<D>::new(quads).run();
+	let relabel = |term: &Term| match term {
+		Term::Blank(id) => Term::Blank(issuer.get(id).unwrap().clone()),
+		term => term.clone(),
+	};
+
+	quads
+		.iter()
+		.map(|quad| Quad {
+			subject: relabel(&quad.subject),
+			predicate: quad.predicate.clone(),
+			object: relabel(&quad.object),
+			graph: quad.graph.as_ref().map(relabel),
+		})
+		.collect()
+}
+
+/// Serializes the given dataset into canonical N-Quads.
+///
+/// The dataset is canonicalized with [`canonicalize`],
+/// and its statements are sorted in code point order, without duplicates.
+/// Every statement is terminated by a line break.
+pub fn canonical_nquads<D: Digest>(quads: &[Quad]) -> String {
+	let mut lines: Vec<_> = canonicalize::<D>(quads)
+		.iter()
+		.map(|quad| format!("{}\n", quad))
+		.collect();
+	lines.sort();
+	lines.dedup();
+	lines.concat()
+}
+
+impl<J: JsonHash + JsonClone, T: Id> ExpandedDocument<J, T> {
+	/// Computes a hash of the semantics of this document.
+	///
+	/// The document is converted into RDF (see [`to_rdf`](Self::to_rdf)),
+	/// serialized into canonical N-Quads (see [`canonical_nquads`]), which are fed to `hasher`.
+	/// Documents describing isomorphic datasets hence have the same hash,
+	/// regardless of their blank node identifiers, the order of their values,
+	/// or the way their nodes are nested.
+	///
+	/// The base direction of strings is dropped during the conversion.
+	///
+	/// ```
+	/// # use json_ld::{context, Document, ExpandedDocument, NoLoader, rdf::Digest};
+	/// # use iref::IriBuf;
+	/// # use serde_json::Value;
+	/// # fn expand(json: &str) -> ExpandedDocument<Value, IriBuf> {
+	/// #   let doc: Value = serde_json::from_str(json).unwrap();
+	/// #   let mut loader = NoLoader::<Value>::new();
+	/// #   async_std::task::block_on(doc.expand::<context::Json<Value>, _>(&mut loader)).unwrap()
+	/// # }
+	/// // Identity "digest" returning the canonical N-Quads.
+	/// #[derive(Default)]
+	/// struct Identity(Vec<u8>);
+	///
+	/// impl Digest for Identity {
+	///   fn update(&mut self, data: &[u8]) {
+	///     self.0.extend_from_slice(data)
+	///   }
+	///
+	///   fn finalize(self) -> Vec<u8> {
+	///     self.0
+	///   }
+	/// }
+	///
+	/// let a = expand(r#"{
+	///   "@id": "_:alice",
+	///   "http://xmlns.com/foaf/0.1/knows": { "@id": "_:bob" }
+	/// }"#);
+	/// let b = expand(r#"[
+	///   { "@id": "_:x", "http://xmlns.com/foaf/0.1/knows": { "@id": "_:y" } },
+	///   { "@id": "_:y" }
+	/// ]"#);
+	///
+	/// let hash = a.semantic_hash(Identity::default()).unwrap();
+	/// assert_eq!(hash, b.semantic_hash(Identity::default()).unwrap());
+	/// assert_eq!(
+	///   String::from_utf8(hash).unwrap(),
+	///   "_:c14n0 <http://xmlns.com/foaf/0.1/knows> _:c14n1 .\n"
+	/// );
+	/// ```
+	pub fn semantic_hash<D: Digest>(&self, mut hasher: D) -> Result<Vec<u8>, Error> {
+		let quads = self.to_rdf(&mut BlankIdSequence::default(), &Options::default())?;
+		hasher.update(canonical_nquads::<D>(&quads).as_bytes());
+		Ok(hasher.finalize())
+	}
+}
+
+/// Blank node identifier issuer.
+#[derive(Clone)]
+struct IdentifierIssuer {
+	prefix: &'static str,
+
+	/// Issued identifiers, in order.
+	issued: Vec<BlankId>,
+
+	/// Issued identifiers, by original identifier.
+	map: HashMap<BlankId, BlankId>,
+}
+
+impl IdentifierIssuer {
+	fn new(prefix: &'static str) -> Self {
+		Self {
+			prefix,
+			issued: Vec::new(),
+			map: HashMap::new(),
+		}
+	}
+
+	fn get(&self, id: &BlankId) -> Option<&BlankId> {
+		self.map.get(id)
+	}
+
+	/// Issues a new identifier for `id`, unless it already has one.
+	fn issue(&mut self, id: &BlankId) -> &BlankId {
+		if !self.map.contains_key(id) {
+			let new_id = BlankId::new(&format!("{}{}", self.prefix, self.issued.len()));
+			self.issued.push(id.clone());
+			self.map.insert(id.clone(), new_id);
+		}
+
+		&self.map[id]
+	}
+}
+
+struct Canonicalizer<'q, D> {
+	/// Quads mentioning each blank node.
+	blank_quads: HashMap<&'q BlankId, Vec<&'q Quad>>,
+
+	/// First degree hash of each blank node.
+	first_degree: HashMap<&'q BlankId, String>,
+
+	/// Canonical identifier issuer.
+	canonical: IdentifierIssuer,
+
+	digest: PhantomData<D>,
+}
+
+impl<'q, D: Digest> Canonicalizer<'q, D> {
+	fn new(quads: &'q [Quad]) -> Self {
+		let mut blank_quads: HashMap<_, Vec<_>> = HashMap::new();
+		for quad in quads {
+			let mut ids: Vec<&BlankId> = Vec::new();
+			for term in [Some(&quad.subject), Some(&quad.object), quad.graph.as_ref()].iter() {
+				if let Some(id) = term.and_then(Term::as_blank) {
+					if !ids.contains(&id) {
+						ids.push(id)
+					}
+				}
+			}
+
+			for id in ids {
+				blank_quads.entry(id).or_default().push(quad)
+			}
+		}
+
+		let mut result = Self {
+			blank_quads,
+			first_degree: HashMap::new(),
+			canonical: IdentifierIssuer::new("c14n"),
+			digest: PhantomData,
+		};
+
+		let first_degree = result
+			.blank_quads
+			.keys()
+			.map(|id| (*id, result.hash_first_degree(id)))
+			.collect();
+		result.first_degree = first_degree;
+		result
+	}
+
+	/// Issues the canonical identifiers of every blank node.
+	fn run(mut self) -> IdentifierIssuer {
+		let mut hash_to_blank_ids: BTreeMap<&str, Vec<&BlankId>> = BTreeMap::new();
+		for (id, hash) in &self.first_degree {
+			hash_to_blank_ids.entry(hash).or_default().push(id)
+		}
+
+		// Blank nodes with a unique first degree hash.
+		for ids in hash_to_blank_ids.values() {
+			if ids.len() == 1 {
+				self.canonical.issue(ids[0]);
+			}
+		}
+
+		for ids in hash_to_blank_ids.values() {
+			if ids.len() == 1 {
+				continue;
+			}
+
+			let mut results = Vec::new();
+			for id in ids {
+				if self.canonical.get(id).is_none() {
+					let mut issuer = IdentifierIssuer::new("b");
+					issuer.issue(id);
+					results.push(self.hash_n_degree(id, issuer))
+				}
+			}
+
+			results.sort_by(|(a, _), (b, _)| a.cmp(b));
+			for (_, issuer) in results {
+				for id in &issuer.issued {
+					self.canonical.issue(id);
+				}
+			}
+		}
+
+		self.canonical
+	}
+
+	/// Hash First Degree Quads algorithm.
+	fn hash_first_degree(&self, id: &BlankId) -> String {
+		let reference = BlankId::new("a");
+		let other = BlankId::new("z");
+		let relabel = |term: &Term| match term {
+			Term::Blank(b) if b == id => Term::Blank(reference.clone()),
+			Term::Blank(_) => Term::Blank(other.clone()),
+			term => term.clone(),
+		};
+
+		let mut lines: Vec<_> = self.blank_quads[id]
+			.iter()
+			.map(|quad| {
+				let quad = Quad {
+					subject: relabel(&quad.subject),
+					predicate: quad.predicate.clone(),
+					object: relabel(&quad.object),
+					graph: quad.graph.as_ref().map(relabel),
+				};
+
+				format!("{}\n", quad)
+			})
+			.collect();
+		lines.sort();
+
+		hash::<D>(&lines.concat())
+	}
+
+	/// Hash Related Blank Node algorithm.
+	fn hash_related(
+		&self,
+		related: &BlankId,
+		quad: &Quad,
+		issuer: &IdentifierIssuer,
+		position: char,
+	) -> String {
+		let id = match self.canonical.get(related).or_else(|| issuer.get(related)) {
+			Some(id) => id.to_string(),
+			None => self.first_degree[related].clone(),
+		};
+
+		let mut input = position.to_string();
+		if position != 'g' {
+			input.push_str(&quad.predicate.to_string())
+		}
+		input.push_str(&id);
+
+		hash::<D>(&input)
+	}
+
+	/// Hash N-Degree Quads algorithm.
+	fn hash_n_degree(
+		&self,
+		id: &BlankId,
+		mut issuer: IdentifierIssuer,
+	) -> (String, IdentifierIssuer) {
+		let mut related_blank_ids: BTreeMap<String, Vec<&BlankId>> = BTreeMap::new();
+		for quad in &self.blank_quads[id] {
+			let components = [
+				(Some(&quad.subject), 's'),
+				(Some(&quad.object), 'o'),
+				(quad.graph.as_ref(), 'g'),
+			];
+
+			for (term, position) in components.iter() {
+				if let Some(related) = term.and_then(Term::as_blank) {
+					if related != id {
+						let hash = self.hash_related(related, quad, &issuer, *position);
+						related_blank_ids.entry(hash).or_default().push(related)
+					}
+				}
+			}
+		}
+
+		let mut data = String::new();
+		for (related_hash, blank_ids) in related_blank_ids {
+			data.push_str(&related_hash);
+
+			let mut chosen_path = String::new();
+			let mut chosen_issuer = None;
+
+			'permutations: for permutation in permutations(&blank_ids) {
+				let mut issuer_copy = issuer.clone();
+				let mut path = String::new();
+				let mut recursion_list = Vec::new();
+
+				for related in permutation.iter().copied() {
+					match self.canonical.get(related) {
+						Some(id) => path.push_str(id.as_str()),
+						None => {
+							if issuer_copy.get(related).is_none() {
+								recursion_list.push(related)
+							}
+
+							path.push_str(issuer_copy.issue(related).as_str())
+						}
+					}
+
+					if is_worse(&path, &chosen_path) {
+						continue 'permutations;
+					}
+				}
+
+				for related in recursion_list {
+					let (hash, result_issuer) = self.hash_n_degree(related, issuer_copy.clone());
+					path.push_str(issuer_copy.issue(related).as_str());
+					path.push('<');
+					path.push_str(&hash);
+					path.push('>');
+					issuer_copy = result_issuer;
+
+					if is_worse(&path, &chosen_path) {
+						continue 'permutations;
+					}
+				}
+
+				if chosen_path.is_empty() || path < chosen_path {
+					chosen_path = path;
+					chosen_issuer = Some(issuer_copy);
+				}
+			}
+
+			data.push_str(&chosen_path);
+			if let Some(chosen_issuer) = chosen_issuer {
+				issuer = chosen_issuer
+			}
+		}
+
+		(hash::<D>(&data), issuer)
+	}
+}
+
+/// Checks if the given path cannot be chosen over the currently chosen path.
+fn is_worse(path: &str, chosen_path: &str) -> bool {
+	!chosen_path.is_empty() && path.len() >= chosen_path.len() && path > chosen_path
+}
+
+/// Returns every permutation of the given items.
+fn permutations<'a, T>(items: &[&'a T]) -> Vec<Vec<&'a T>> {
+	if items.len() <= 1 {
+		return vec![items.to_vec()];
+	}
+
+	let mut result = Vec::new();
+	for i in 0..items.len() {
+		let mut rest = items.to_vec();
+		let item = rest.remove(i);
+		for mut permutation in permutations(&rest) {
+			permutation.insert(0, item);
+			result.push(permutation)
+		}
+	}
+
+	result
+}
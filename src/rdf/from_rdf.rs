@@ -0,0 +1,283 @@
+use super::{Literal, Quad, Term, RDF_FIRST, RDF_NIL, RDF_REST, RDF_TYPE};
+use crate::util::collections::{HashMap, HashSet};
+use crate::{
+	object::{value::xsd, Literal as JsonLdLiteral, LiteralString},
+	BlankId, ExpandedDocument, Id, Indexed, LangString, Node, Object, RdfDirection, Reference,
+	Value,
+};
+use generic_json::JsonHash;
+use iref::AsIri;
+
+/// `rdf:List` IRI.
+const RDF_LIST: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#List";
+
+/// Options of the conversion of RDF datasets into JSON-LD documents.
+#[derive(Clone, Copy, Default)]
+pub struct FromRdfOptions {
+	/// Keep `rdf:type` statements as regular properties instead of `@type` entries.
+	pub use_rdf_type: bool,
+
+	/// Base direction encoding strategy used by the dataset.
+	///
+	/// Only the `i18n-datatype` strategy is decoded.
+	pub rdf_direction: Option<RdfDirection>,
+}
+
+impl<J: JsonHash, T: Id> ExpandedDocument<J, T> {
+	/// Builds a document from RDF quads.
+	///
+	/// This follows the
+	/// [Serialize RDF as JSON-LD algorithm](https://www.w3.org/TR/json-ld11-api/#serialize-rdf-as-json-ld-algorithm)
+	/// of the JSON-LD 1.1 API, without native types conversion:
+	/// literals are kept as strings typed with their datatype (except for `xsd:string`),
+	/// and `rdf:JSON` literals are not parsed.
+	/// Well-formed RDF collections are converted into list objects,
+	/// and named graphs into graph objects.
+	///
+	/// Statements about or involving quoted triples, or with a literal subject or predicate,
+	/// are ignored.
+	///
+	/// ```
+	/// use json_ld::{rdf::{FromRdfOptions, Literal, Quad, Term}, ExpandedDocument, Reference};
+	/// use iref::IriBuf;
+	///
+	/// let iri = |s: &str| Term::Iri(IriBuf::new(s).unwrap());
+	/// let quads = vec![Quad {
+	///   subject: iri("http://example.org/alice"),
+	///   predicate: iri("http://xmlns.com/foaf/0.1/name"),
+	///   object: Term::Literal(Literal::Typed(
+	///     "Alice".to_string(),
+	///     IriBuf::new("http://www.w3.org/2001/XMLSchema#string").unwrap(),
+	///   )),
+	///   graph: None,
+	/// }];
+	///
+	/// let doc: ExpandedDocument<serde_json::Value> =
+	///   ExpandedDocument::from_rdf(quads, &FromRdfOptions::default());
+	/// let alice = doc.iter().next().unwrap().as_node().unwrap();
+	/// let name = Reference::Id(IriBuf::new("http://xmlns.com/foaf/0.1/name").unwrap());
+	/// assert_eq!(alice.get_any(&name).unwrap().as_str(), Some("Alice"));
+	/// ```
+	pub fn from_rdf<Q: Into<Quad>, I: IntoIterator<Item = Q>>(
+		quads: I,
+		options: &FromRdfOptions,
+	) -> Self {
+		let mut graphs: HashMap<Option<Term>, Graph> = HashMap::new();
+		for quad in quads {
+			let quad = quad.into();
+			let valid = matches!(quad.subject, Term::Iri(_) | Term::Blank(_))
+				&& matches!(quad.predicate, Term::Iri(_) | Term::Blank(_))
+				&& !matches!(quad.object, Term::Triple(_))
+				&& matches!(quad.graph, None | Some(Term::Iri(_)) | Some(Term::Blank(_)));
+
+			if valid {
+				graphs.entry(quad.graph).or_default().insert(
+					quad.subject,
+					quad.predicate,
+					quad.object,
+				)
+			}
+		}
+
+		let mut default_graph = graphs
+			.remove(&None)
+			.map(|graph| graph.into_nodes(options))
+			.unwrap_or_default();
+
+		for (name, graph) in graphs {
+			if let Some(name) = name {
+				let nodes = graph.into_nodes(options);
+				let node = default_graph
+					.entry(name.clone())
+					.or_insert_with(|| Node::with_id(reference(&name)));
+
+				node.set_graph(Some(
+					nodes
+						.into_iter()
+						.map(|(_, node)| Indexed::new(Object::Node(node), None))
+						.collect(),
+				))
+			}
+		}
+
+		let objects = default_graph
+			.into_iter()
+			.map(|(_, node)| Indexed::new(Object::Node(node), None))
+			.collect();
+
+		Self::new(objects, Vec::new())
+	}
+}
+
+/// Statements of a graph, grouped by subject.
+#[derive(Default)]
+struct Graph {
+	statements: HashMap<Term, Vec<(Term, Term)>>,
+
+	/// Number of statements using each blank node as object.
+	usages: HashMap<BlankId, usize>,
+}
+
+impl Graph {
+	fn insert(&mut self, subject: Term, predicate: Term, object: Term) {
+		let statements = self.statements.entry(subject).or_default();
+		if !statements
+			.iter()
+			.any(|(p, o)| *p == predicate && *o == object)
+		{
+			if let Term::Blank(id) = &object {
+				*self.usages.entry(id.clone()).or_default() += 1
+			}
+
+			statements.push((predicate, object))
+		}
+	}
+
+	/// Checks if the given blank node is a list node:
+	/// it is only used once, and only has one `rdf:first` and one `rdf:rest` property
+	/// (and optionally a `rdf:List` type).
+	fn is_list_node(&self, id: &BlankId) -> bool {
+		if self.usages.get(id).cloned() != Some(1) {
+			return false;
+		}
+
+		let statements = match self.statements.get(&Term::Blank(id.clone())) {
+			Some(statements) => statements,
+			None => return false,
+		};
+
+		let mut first = 0;
+		let mut rest = 0;
+		for (predicate, object) in statements {
+			match predicate {
+				Term::Iri(p) if p.as_str() == RDF_FIRST => first += 1,
+				Term::Iri(p) if p.as_str() == RDF_REST => rest += 1,
+				Term::Iri(p) if p.as_str() == RDF_TYPE => match object {
+					Term::Iri(ty) if ty.as_str() == RDF_LIST => (),
+					_ => return false,
+				},
+				_ => return false,
+			}
+		}
+
+		first == 1 && rest == 1
+	}
+
+	/// Returns the items of the well-formed list starting at the given blank node, if any.
+	fn list(&self, head: &BlankId) -> Option<Vec<&Term>> {
+		let mut items = Vec::new();
+		let mut visited = HashSet::new();
+		let mut current = head;
+
+		loop {
+			if !visited.insert(current) || !self.is_list_node(current) {
+				return None;
+			}
+
+			let statements = &self.statements[&Term::Blank(current.clone())];
+			let property = |name: &str| {
+				statements
+					.iter()
+					.find(|(p, _)| matches!(p, Term::Iri(iri) if iri.as_str() == name))
+					.map(|(_, o)| o)
+			};
+
+			items.push(property(RDF_FIRST)?);
+			match property(RDF_REST)? {
+				Term::Iri(iri) if iri.as_str() == RDF_NIL => break Some(items),
+				Term::Blank(next) => current = next,
+				_ => return None,
+			}
+		}
+	}
+
+	fn into_nodes<J: JsonHash, T: Id>(self, options: &FromRdfOptions) -> HashMap<Term, Node<J, T>> {
+		let mut nodes = HashMap::new();
+		for (subject, statements) in &self.statements {
+			if let Term::Blank(id) = subject {
+				if self.list(id).is_some() {
+					// The node is converted into (part of) a list object.
+					continue;
+				}
+			}
+
+			let mut node = Node::with_id(reference(subject));
+			for (predicate, object) in statements {
+				let is_type = !options.use_rdf_type
+					&& matches!(predicate, Term::Iri(p) if p.as_str() == RDF_TYPE)
+					&& matches!(object, Term::Iri(_) | Term::Blank(_));
+
+				if is_type {
+					let ty = reference(object);
+					if !node.types.contains(&ty) {
+						node.types.push(ty)
+					}
+				} else {
+					let object = Indexed::new(self.object(object, options), None);
+					node.properties.insert(reference(predicate), object)
+				}
+			}
+
+			nodes.insert(subject.clone(), node);
+		}
+
+		nodes
+	}
+
+	fn object<J: JsonHash, T: Id>(&self, object: &Term, options: &FromRdfOptions) -> Object<J, T> {
+		match object {
+			Term::Iri(iri) if iri.as_str() == RDF_NIL => Object::List(Vec::new(), None),
+			Term::Blank(id) => match self.list(id) {
+				Some(items) => Object::List(
+					items
+						.into_iter()
+						.map(|item| Indexed::new(self.object(item, options), None))
+						.collect(),
+					None,
+				),
+				None => Object::Node(Node::with_id(reference(object))),
+			},
+			Term::Literal(literal) => Object::Value(value(literal, options), None),
+			_ => Object::Node(Node::with_id(reference(object))),
+		}
+	}
+}
+
+/// Converts the given IRI or blank node term into a node reference.
+fn reference<T: Id>(term: &Term) -> Reference<T> {
+	match term {
+		Term::Iri(iri) => Reference::Id(T::from_iri(iri.as_iri())),
+		Term::Blank(id) => Reference::Blank(id.clone()),
+		_ => panic!("not a node reference"),
+	}
+}
+
+/// Converts the given literal into a value.
+fn value<J: JsonHash, T: Id>(literal: &Literal, options: &FromRdfOptions) -> Value<J, T> {
+	match literal {
+		Literal::LangString(s, language) => {
+			let s = LiteralString::Inferred(s.clone());
+			match LangString::new(s, Some(language.clone()), None) {
+				Ok(s) => Value::LangString(s),
+				Err(s) => Value::Literal(JsonLdLiteral::String(s), None),
+			}
+		}
+		Literal::Typed(s, ty) => {
+			let s = LiteralString::Inferred(s.clone());
+			let s = if options.rdf_direction == Some(RdfDirection::I18nDatatype) {
+				match LangString::from_i18n_datatype(s, ty.as_str()) {
+					Ok(s) => return Value::LangString(s),
+					Err(s) => s,
+				}
+			} else {
+				s
+			};
+
+			if ty.as_str() == xsd::Datatype::String.iri() {
+				Value::Literal(JsonLdLiteral::String(s), None)
+			} else {
+				Value::Literal(JsonLdLiteral::String(s), Some(T::from_iri(ty.as_iri())))
+			}
+		}
+	}
+}
@@ -0,0 +1,165 @@
+//! Conversions from and into the types of the `rdf-types` crate.
+use super::{FromRdfOptions, Literal, Options, Quad, Term};
+use crate::{
+	object::value::xsd, BlankId, BlankIdGenerator, Error, ExpandedDocument, Id,
+	LenientLanguageTagBuf,
+};
+use generic_json::{JsonClone, JsonHash};
+use iref::IriBuf;
+use std::convert::TryFrom;
+use std::iter::FromIterator;
+
+fn blank_id(id: &rdf_types::BlankIdBuf) -> BlankId {
+	match BlankId::try_from(id.as_str()) {
+		Ok(id) => id,
+		Err(_) => BlankId::new(id.as_str()),
+	}
+}
+
+/// Fails if the language tag is not well-formed.
+impl TryFrom<Literal> for rdf_types::Literal {
+	type Error = Literal;
+
+	fn try_from(literal: Literal) -> Result<Self, Literal> {
+		match literal {
+			Literal::Typed(s, ty) => Ok(Self::TypedString(s.into(), ty)),
+			Literal::LangString(s, LenientLanguageTagBuf::WellFormed(tag)) => {
+				Ok(Self::LangString(s.into(), tag))
+			}
+			literal => Err(literal),
+		}
+	}
+}
+
+/// Simple literals are converted into `xsd:string` literals.
+impl From<rdf_types::Literal> for Literal {
+	fn from(literal: rdf_types::Literal) -> Self {
+		match literal {
+			rdf_types::Literal::String(s) => Self::Typed(
+				s.as_str().to_string(),
+				IriBuf::new(&xsd::Datatype::String.iri()).unwrap(),
+			),
+			rdf_types::Literal::TypedString(s, ty) => Self::Typed(s.as_str().to_string(), ty),
+			rdf_types::Literal::LangString(s, tag) => {
+				Self::LangString(s.as_str().to_string(), tag.into())
+			}
+		}
+	}
+}
+
+/// Fails on quoted triples, literals with a malformed language tag,
+/// and blank node identifiers not valid for `rdf-types`.
+impl TryFrom<Term> for rdf_types::Term {
+	type Error = Term;
+
+	fn try_from(term: Term) -> Result<Self, Term> {
+		match term {
+			Term::Iri(iri) => Ok(Self::Iri(iri)),
+			Term::Blank(id) => match rdf_types::BlankIdBuf::new(id.as_str().to_string()) {
+				Ok(blank) => Ok(Self::Blank(blank)),
+				Err(_) => Err(Term::Blank(id)),
+			},
+			Term::Literal(literal) => rdf_types::Literal::try_from(literal)
+				.map(Self::Literal)
+				.map_err(Term::Literal),
+			Term::Triple(_) => Err(term),
+		}
+	}
+}
+
+impl From<rdf_types::Term> for Term {
+	fn from(term: rdf_types::Term) -> Self {
+		match term {
+			rdf_types::Term::Iri(iri) => Self::Iri(iri),
+			rdf_types::Term::Blank(id) => Self::Blank(blank_id(&id)),
+			rdf_types::Term::Literal(literal) => Self::Literal(literal.into()),
+		}
+	}
+}
+
+impl From<rdf_types::Subject> for Term {
+	fn from(subject: rdf_types::Subject) -> Self {
+		match subject {
+			rdf_types::Subject::Iri(iri) => Self::Iri(iri),
+			rdf_types::Subject::Blank(id) => Self::Blank(blank_id(&id)),
+		}
+	}
+}
+
+fn subject(term: Term) -> Option<rdf_types::Subject> {
+	match rdf_types::Term::try_from(term).ok()? {
+		rdf_types::Term::Iri(iri) => Some(rdf_types::Subject::Iri(iri)),
+		rdf_types::Term::Blank(id) => Some(rdf_types::Subject::Blank(id)),
+		rdf_types::Term::Literal(_) => None,
+	}
+}
+
+fn quad(quad: &Quad) -> Option<rdf_types::Quad> {
+	let subject = subject(quad.subject.clone())?;
+
+	let predicate = match &quad.predicate {
+		Term::Iri(iri) => iri.clone(),
+		_ => return None,
+	};
+
+	let object = rdf_types::Term::try_from(quad.object.clone()).ok()?;
+
+	let graph = match &quad.graph {
+		Some(graph) => Some(self::subject(graph.clone())?),
+		None => None,
+	};
+
+	Some(rdf_types::Quad(subject, predicate, object, graph))
+}
+
+/// Fails if a term of the quad cannot be represented with `rdf-types`
+/// (see the conversion of [`Term`]s), if the subject or graph name is a literal,
+/// or if the predicate is not an IRI.
+impl TryFrom<Quad> for rdf_types::Quad {
+	type Error = Quad;
+
+	#[inline(always)]
+	fn try_from(q: Quad) -> Result<Self, Quad> {
+		quad(&q).ok_or(q)
+	}
+}
+
+impl From<rdf_types::Quad> for Quad {
+	fn from(rdf_types::Quad(s, p, o, g): rdf_types::Quad) -> Self {
+		Self {
+			subject: s.into(),
+			predicate: Term::Iri(p),
+			object: o.into(),
+			graph: g.map(Into::into),
+		}
+	}
+}
+
+impl<J: JsonHash + JsonClone, T: Id> ExpandedDocument<J, T> {
+	/// Converts this document into a collection of `rdf-types` quads,
+	/// such as an RDF dataset implementation.
+	///
+	/// See [`to_rdf`](Self::to_rdf) for the details.
+	/// Statements that cannot be represented with `rdf-types`,
+	/// such as statements about quoted triples, are dropped.
+	pub fn to_rdf_dataset<D: FromIterator<rdf_types::Quad>, G: BlankIdGenerator>(
+		&self,
+		generator: &mut G,
+		options: &Options,
+	) -> Result<D, Error> {
+		Ok(self
+			.to_rdf(generator, options)?
+			.into_iter()
+			.filter_map(|quad| rdf_types::Quad::try_from(quad).ok())
+			.collect())
+	}
+}
+
+/// Builds a document from `rdf-types` quads, using the default options.
+///
+/// See [`ExpandedDocument::from_rdf`] for the details.
+impl<J: JsonHash, T: Id> FromIterator<rdf_types::Quad> for ExpandedDocument<J, T> {
+	fn from_iter<I: IntoIterator<Item = rdf_types::Quad>>(quads: I) -> Self {
+		Self::from_rdf(quads, &FromRdfOptions::default())
+	}
+}
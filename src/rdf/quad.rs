@@ -0,0 +1,354 @@
+use super::{Literal, Options};
+use crate::{
+	object::{value::xsd, Annotation},
+	BlankId, BlankIdGenerator, Error, ExpandedDocument, Id, Indexed, Loc, Node, Object,
+	RdfDirection, Reference, Value, Warning,
+};
+use generic_json::{JsonClone, JsonHash};
+use iref::{AsIri, IriBuf};
+use std::fmt;
+
+/// `rdf:type` IRI.
+pub const RDF_TYPE: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#type";
+
+/// `rdf:first` IRI.
+pub const RDF_FIRST: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#first";
+
+/// `rdf:rest` IRI.
+pub const RDF_REST: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#rest";
+
+/// `rdf:nil` IRI.
+pub const RDF_NIL: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#nil";
+
+/// `rdf:value` IRI.
+pub const RDF_VALUE: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#value";
+
+/// `rdf:language` IRI.
+pub const RDF_LANGUAGE: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#language";
+
+/// `rdf:direction` IRI.
+pub const RDF_DIRECTION: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#direction";
+
+/// RDF term.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub enum Term {
+	/// IRI.
+	Iri(IriBuf),
+
+	/// Blank node.
+	Blank(BlankId),
+
+	/// Literal.
+	Literal(Literal),
+
+	/// Quoted triple `(subject, predicate, object)`, from RDF-star.
+	///
+	/// Quoted triples are the subject of the statements produced by
+	/// [JSON-LD-star](https://json-ld.github.io/json-ld-star/) annotations.
+	Triple(Box<(Term, Term, Term)>),
+}
+
+impl Term {
+	fn iri(iri: &str) -> Self {
+		Self::Iri(IriBuf::new(iri).unwrap())
+	}
+
+	/// Converts a node reference into a term, unless it is invalid.
+	fn from_reference<T: Id>(r: &Reference<T>) -> Option<Self> {
+		match r {
+			Reference::Id(id) => Some(Self::Iri(AsIri::as_iri(id).into())),
+			Reference::Blank(id) => Some(Self::Blank(id.clone())),
+			Reference::Invalid(_) => None,
+		}
+	}
+
+	/// Returns the blank node identifier of this term, if it is a blank node.
+	#[inline(always)]
+	pub fn as_blank(&self) -> Option<&BlankId> {
+		match self {
+			Self::Blank(id) => Some(id),
+			_ => None,
+		}
+	}
+}
+
+/// Formats the term using the N-Quads syntax.
+impl fmt::Display for Term {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Self::Iri(iri) => write!(f, "<{}>", iri),
+			Self::Blank(id) => id.fmt(f),
+			Self::Literal(literal) => literal.fmt(f),
+			Self::Triple(triple) => write!(f, "<< {} {} {} >>", triple.0, triple.1, triple.2),
+		}
+	}
+}
+
+/// RDF quad.
+///
+/// A quad without graph belongs to the default graph.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct Quad {
+	/// Subject.
+	pub subject: Term,
+
+	/// Predicate.
+	pub predicate: Term,
+
+	/// Object.
+	pub object: Term,
+
+	/// Graph name.
+	pub graph: Option<Term>,
+}
+
+/// Formats the quad as an N-Quads statement, without the final line break.
+impl fmt::Display for Quad {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "{} {} {} ", self.subject, self.predicate, self.object)?;
+		if let Some(graph) = &self.graph {
+			write!(f, "{} ", graph)?;
+		}
+		write!(f, ".")
+	}
+}
+
+impl<J: JsonHash + JsonClone, T: Id> ExpandedDocument<J, T> {
+	/// Converts this document into RDF quads.
+	///
+	/// This follows the
+	/// [Deserialize JSON-LD to RDF algorithm](https://www.w3.org/TR/json-ld11-api/#deserialize-json-ld-to-rdf-algorithm)
+	/// of the JSON-LD 1.1 API, using the node map of the document
+	/// (see [`generate_node_map`](Self::generate_node_map)).
+	/// Blank node identifiers, including the ones needed to represent lists,
+	/// are provided by `generator`.
+	/// Statements with an invalid node reference are dropped.
+	/// Statements with a blank node identifier as predicate are dropped as well,
+	/// unless [generalized RDF](Options::produce_generalized_rdf) is produced.
+	///
+	/// The annotations of the document (see [`Indexed::annotation`]) are converted into
+	/// statements about [quoted triples](Term::Triple), following RDF-star.
+	///
+	/// Numbers are converted following the [`NumberOptions`](super::NumberOptions):
+	/// in lossless mode, this fails with an [`InexactNumber`](crate::ErrorCode::InexactNumber)
+	/// error if a number cannot be represented exactly.
+	///
+	/// The quads are returned in no particular order.
+	/// Use [`to_rdf_with_warnings`](Self::to_rdf_with_warnings) to be notified
+	/// of the dropped statements.
+	#[inline(always)]
+	pub fn to_rdf<G: BlankIdGenerator>(
+		&self,
+		generator: &mut G,
+		options: &Options,
+	) -> Result<Vec<Quad>, Error> {
+		self.to_rdf_with_warnings(generator, options, &mut Vec::new())
+	}
+
+	/// Converts this document into RDF quads, reporting the dropped statements.
+	///
+	/// See [`to_rdf`](Self::to_rdf) for the details.
+	/// A warning is pushed in `warnings` for every statement dropped because its predicate
+	/// is a blank node identifier, located by the metadata of its subject node, if any.
+	pub fn to_rdf_with_warnings<G: BlankIdGenerator>(
+		&self,
+		generator: &mut G,
+		options: &Options,
+		warnings: &mut Vec<Loc<Warning, Option<J::MetaData>>>,
+	) -> Result<Vec<Quad>, Error> {
+		let node_map = self.clone().generate_node_map(generator)?;
+
+		let mut quads = Vec::new();
+		for (graph, nodes) in node_map.graphs() {
+			let graph = match graph {
+				Some(graph) => match Term::from_reference(graph) {
+					Some(graph) => Some(graph),
+					None => continue,
+				},
+				None => None,
+			};
+
+			let mut converter = Converter {
+				graph,
+				generator: &mut *generator,
+				options,
+				quads: &mut quads,
+				warnings: &mut *warnings,
+			};
+
+			for (subject, node) in nodes.iter() {
+				if let Some(subject) = Term::from_reference(subject) {
+					converter.node(subject, node)?
+				}
+			}
+		}
+
+		Ok(quads)
+	}
+}
+
+fn xsd_string() -> IriBuf {
+	IriBuf::new(&xsd::Datatype::String.iri()).unwrap()
+}
+
+struct Converter<'a, G, M> {
+	graph: Option<Term>,
+	generator: &'a mut G,
+	options: &'a Options,
+	quads: &'a mut Vec<Quad>,
+	warnings: &'a mut Vec<Loc<Warning, Option<M>>>,
+}
+
+impl<'a, G: BlankIdGenerator, M: Clone> Converter<'a, G, M> {
+	fn push(&mut self, subject: Term, predicate: Term, object: Term) {
+		self.quads.push(Quad {
+			subject,
+			predicate,
+			object,
+			graph: self.graph.clone(),
+		})
+	}
+
+	/// Adds the statements about the given node, with the given subject.
+	fn node<J: JsonHash<MetaData = M>, T: Id>(
+		&mut self,
+		subject: Term,
+		node: &Node<J, T>,
+	) -> Result<(), Error> {
+		for ty in node.types() {
+			if let Some(ty) = Term::from_reference(ty) {
+				self.push(subject.clone(), Term::iri(RDF_TYPE), ty)
+			}
+		}
+
+		for (prop, objects) in node.properties() {
+			let predicate = match prop {
+				Reference::Id(_) => Term::from_reference(prop).unwrap(),
+				Reference::Blank(id) if self.options.produce_generalized_rdf => {
+					Term::Blank(id.clone())
+				}
+				Reference::Blank(id) => {
+					self.warnings.push(Loc::new(
+						Warning::BlankNodePredicate(id.clone()),
+						None,
+						node.metadata().cloned(),
+					));
+					continue;
+				}
+				Reference::Invalid(_) => continue,
+			};
+
+			for object in objects {
+				if let Some(term) = self.object(object)? {
+					self.push(subject.clone(), predicate.clone(), term.clone());
+
+					if let Some(annotation) = object.annotation() {
+						let triple =
+							Term::Triple(Box::new((subject.clone(), predicate.clone(), term)));
+						self.annotation(triple, annotation)?
+					}
+				}
+			}
+		}
+
+		Ok(())
+	}
+
+	/// Adds the statements of the given annotation, about the given quoted triple.
+	fn annotation<J: JsonHash<MetaData = M>, T: Id>(
+		&mut self,
+		triple: Term,
+		annotation: &Annotation<J, T>,
+	) -> Result<(), Error> {
+		for object in annotation {
+			if let Object::Node(node) = object.inner() {
+				self.node(triple.clone(), node)?
+			}
+		}
+
+		Ok(())
+	}
+
+	/// Converts the given object into an RDF term, adding the quads needed to
+	/// represent lists and compound literals.
+	///
+	/// Nodes are flattened by the node map, except the ones embedded in annotations,
+	/// whose statements are also added here.
+	fn object<J: JsonHash<MetaData = M>, T: Id>(
+		&mut self,
+		object: &Indexed<Object<J, T>>,
+	) -> Result<Option<Term>, Error> {
+		match object.inner() {
+			Object::Node(node) => {
+				let term = match node.id() {
+					Some(id) => match Term::from_reference(id) {
+						Some(term) => term,
+						None => return Ok(None),
+					},
+					None => Term::Blank(self.generator.next_blank_id()),
+				};
+
+				self.node(term.clone(), node)?;
+				Ok(Some(term))
+			}
+			Object::Value(value, _) => self.value(value),
+			Object::List(items, _) => self.list(items).map(Some),
+		}
+	}
+
+	fn value<J: JsonHash, T: Id>(&mut self, value: &Value<J, T>) -> Result<Option<Term>, Error> {
+		match value {
+			Value::LangString(s)
+				if s.direction().is_some()
+					&& self.options.rdf_direction == Some(RdfDirection::CompoundLiteral) =>
+			{
+				let node = Term::Blank(self.generator.next_blank_id());
+				let string = |s: &str| Term::Literal(Literal::Typed(s.to_string(), xsd_string()));
+
+				self.push(node.clone(), Term::iri(RDF_VALUE), string(s.as_str()));
+
+				if let Some(language) = s.language() {
+					self.push(
+						node.clone(),
+						Term::iri(RDF_LANGUAGE),
+						string(language.as_str()),
+					)
+				}
+
+				let direction = s.direction().unwrap().to_string();
+				self.push(node.clone(), Term::iri(RDF_DIRECTION), string(&direction));
+
+				Ok(Some(node))
+			}
+			value => Ok(value.try_to_rdf_literal(self.options)?.map(Term::Literal)),
+		}
+	}
+
+	fn list<J: JsonHash<MetaData = M>, T: Id>(
+		&mut self,
+		items: &[Indexed<Object<J, T>>],
+	) -> Result<Term, Error> {
+		let nodes: Vec<_> = items
+			.iter()
+			.map(|_| Term::Blank(self.generator.next_blank_id()))
+			.collect();
+
+		for (i, item) in items.iter().enumerate() {
+			let node = nodes[i].clone();
+			if let Some(object) = self.object(item)? {
+				self.push(node.clone(), Term::iri(RDF_FIRST), object)
+			}
+
+			let rest = match nodes.get(i + 1) {
+				Some(next) => next.clone(),
+				None => Term::iri(RDF_NIL),
+			};
+			self.push(node, Term::iri(RDF_REST), rest)
+		}
+
+		match nodes.into_iter().next() {
+			Some(head) => Ok(head),
+			None => Ok(Term::iri(RDF_NIL)),
+		}
+	}
+}
@@ -1,13 +1,15 @@
+#[cfg(not(feature = "std"))]
+use crate::prelude::*;
 use crate::{
 	syntax::{Term, TermLike},
 	util::{self, AsAnyJson},
 	BlankId, Id,
 };
+use core::borrow::Borrow;
+use core::convert::TryFrom;
+use core::fmt;
 use generic_json::JsonClone;
 use iref::{AsIri, Iri, IriBuf};
-use std::borrow::Borrow;
-use std::convert::TryFrom;
-use std::fmt;
 
 /// Node reference.
 ///
@@ -1,19 +1,68 @@
 //! Simple document and context loader based on [`reqwest`](https://crates.io/crates/reqwest)
 
-use crate::{loader, Error, ErrorCode, RemoteDocument};
-use futures::future::{BoxFuture, FutureExt};
+use crate::util::future::{BoxFuture, FutureExt};
+use crate::{
+	context::{self, RemoteContext},
+	loader, Error, ErrorCode, ErrorDetails, RemoteDocument,
+};
 use generic_json::Json;
-use iref::{Iri, IriBuf};
+use iref::{Iri, IriBuf, IriRef};
 use std::collections::HashMap;
 
-pub fn is_json_media_type(ty: &str) -> bool {
-	ty == "application/json" || ty == "application/ld+json"
-}
+pub use loader::is_json_media_type;
+
+/// JSON-LD profile identifying context documents.
+pub const CONTEXT_PROFILE: &str = "http://www.w3.org/ns/json-ld#context";
+
+/// `Accept` header of context requests.
+///
+/// Context documents are preferred, but any JSON-LD or JSON document is accepted.
+const CONTEXT_ACCEPT: &str = concat!(
+	"application/ld+json;profile=\"http://www.w3.org/ns/json-ld#context\", ",
+	"application/ld+json;q=0.9, application/json;q=0.8"
+);
 
 pub async fn load_remote_json_ld_document<J, P>(url: Iri<'_>, parser: &mut P) -> Result<J, Error>
 where
 	P: Send + Sync + FnMut(&str) -> Result<J, Error>,
 {
+	load_remote_json_ld_document_with_version(url, parser)
+		.await
+		.map(|(doc, _)| doc)
+}
+
+/// Loads the remote JSON-LD document at the given URL,
+/// along with its version information.
+pub async fn load_remote_json_ld_document_with_version<J, P>(
+	url: Iri<'_>,
+	parser: &mut P,
+) -> Result<(J, loader::Version), Error>
+where
+	P: Send + Sync + FnMut(&str) -> Result<J, Error>,
+{
+	load_remote_document(url, parser)
+		.await
+		.map(|(doc, version, _)| (doc, version))
+}
+
+/// Loads the remote JSON-LD document at the given URL,
+/// along with its version information and the information given by the
+/// HTTP headers of the response (see [`loader::DocumentInfo::from_headers`]).
+///
+/// Documents embedded in `data:` URLs are decoded without any request
+/// (see [`loader::DataUrl`]).
+pub async fn load_remote_document<J, P>(
+	url: Iri<'_>,
+	parser: &mut P,
+) -> Result<(J, loader::Version, loader::DocumentInfo), Error>
+where
+	P: Send + Sync + FnMut(&str) -> Result<J, Error>,
+{
+	if loader::is_data_url(url) {
+		let (doc, info) = loader::load_data_url(url, parser)?;
+		return Ok((doc, loader::Version::new(url.into()), info));
+	}
+
 	log::info!("loading remote document `{}'", url);
 	use reqwest::header::*;
 
@@ -23,28 +72,63 @@ where
 		.header(ACCEPT, "application/ld+json, application/json");
 	let response = request.send().await?;
 
-	if response
+	let content_type = response
 		.headers()
-		.get_all(CONTENT_TYPE)
-		.iter()
-		.any(|value| {
-			if let Ok(value) = value.to_str() {
-				is_json_media_type(value)
-			} else {
-				false
-			}
-		}) {
+		.get(CONTENT_TYPE)
+		.and_then(|value| value.to_str().ok())
+		.map(ToString::to_string);
+
+	// The media type may have parameters, such as a `profile`.
+	let is_json = content_type.as_deref().map_or(false, |value| {
+		is_json_media_type(value.split(';').next().unwrap().trim())
+	});
+
+	if is_json {
+		let header = |name: HeaderName| {
+			response
+				.headers()
+				.get(name)
+				.and_then(|value| value.to_str().ok())
+				.map(ToString::to_string)
+		};
+
+		let version = loader::Version {
+			url: IriBuf::new(response.url().as_str()).unwrap_or_else(|_| url.into()),
+			etag: header(ETAG),
+			last_modified: header(LAST_MODIFIED),
+			max_age: header(CACHE_CONTROL)
+				.as_deref()
+				.and_then(loader::cache_max_age),
+		};
+
+		let info = loader::DocumentInfo::from_headers(
+			content_type.as_deref(),
+			response
+				.headers()
+				.get_all(LINK)
+				.iter()
+				.filter_map(|value| value.to_str().ok()),
+			version.url.as_iri(),
+		)?;
+
 		let body = response.text().await?;
 		let doc = (*parser)(body.as_str())?;
-		Ok(doc)
+		Ok((doc, version, info))
 	} else {
-		Err(ErrorCode::LoadingDocumentFailed.into())
+		Err(Error::with_details(
+			ErrorCode::LoadingDocumentFailed,
+			ErrorDetails::UnexpectedValue {
+				expected: "a JSON media type".to_string(),
+				found: content_type.unwrap_or_else(|| "no content type".to_string()),
+			},
+		))
 	}
 }
 
 pub struct Loader<J> {
 	namespace: HashMap<IriBuf, loader::Id>,
-	cache: Vec<(J, IriBuf)>,
+	cache: Vec<(J, IriBuf, Option<loader::Version>, loader::DocumentInfo)>,
+	failures: Option<loader::FailureCache>,
 	parser: Box<dyn 'static + Send + Sync + FnMut(&str) -> Result<J, Error>>,
 }
 
@@ -55,32 +139,85 @@ impl<J: Clone + Send> Loader<J> {
 		Self {
 			namespace: HashMap::new(),
 			cache: Vec::new(),
+			failures: None,
 			parser: Box::new(move |s| {
 				parser(s).map_err(|e| Error::with_source(ErrorCode::LoadingDocumentFailed, e))
 			}),
 		}
 	}
 
+	/// Enables negative results caching with the given cache.
+	///
+	/// URLs that failed to load are not requested again before their
+	/// time-to-live expires.
+	pub fn set_failure_cache(&mut self, failures: loader::FailureCache) {
+		self.failures = Some(failures)
+	}
+
+	/// Returns a mutable reference to the negative results cache, if any.
+	pub fn failure_cache_mut(&mut self) -> Option<&mut loader::FailureCache> {
+		self.failures.as_mut()
+	}
+
 	/// Allocate a identifier to the given IRI.
-	fn allocate(&mut self, iri: IriBuf, doc: J) -> loader::Id {
+	fn allocate(
+		&mut self,
+		iri: IriBuf,
+		doc: J,
+		version: Option<loader::Version>,
+		info: loader::DocumentInfo,
+	) -> loader::Id {
 		let id = loader::Id::new(self.cache.len());
 		self.namespace.insert(iri.clone(), id);
-		self.cache.push((doc, iri));
+		self.cache.push((doc, iri, version, info));
 		id
 	}
 
+	/// Returns the version information of the document with the given identifier, if any.
+	pub fn version(&self, id: loader::Id) -> Option<&loader::Version> {
+		self.cache
+			.get(id.unwrap())
+			.and_then(|(_, _, version, _)| version.as_ref())
+	}
+
 	pub async fn load(&mut self, url: Iri<'_>) -> Result<RemoteDocument<J>, Error> {
 		let url = IriBuf::from(url);
 		match self.namespace.get(&url) {
-			Some(id) => Ok(RemoteDocument::new(
-				self.cache[id.unwrap()].0.clone(),
-				url,
-				*id,
-			)),
+			Some(id) => {
+				let (doc, _, version, info) = &self.cache[id.unwrap()];
+				Ok(RemoteDocument::new(doc.clone(), url, *id)
+					.with_version(version.clone())
+					.with_info(info.clone()))
+			}
 			None => {
-				let doc = load_remote_json_ld_document(url.as_iri(), &mut self.parser).await?;
-				let id = self.allocate(url.clone(), doc.clone());
-				Ok(RemoteDocument::new(doc, url, id))
+				if let Some(failures) = &self.failures {
+					failures.check(url.as_iri())?
+				}
+
+				match load_remote_document(url.as_iri(), &mut self.parser).await {
+					Ok((doc, version, info)) => {
+						if let Some(failures) = &mut self.failures {
+							failures.succeeded(url.as_iri())
+						}
+
+						let id = self.allocate(
+							url.clone(),
+							doc.clone(),
+							Some(version.clone()),
+							info.clone(),
+						);
+						Ok(RemoteDocument::new(doc, url, id)
+							.with_version(Some(version))
+							.with_info(info))
+					}
+					Err(e) => {
+						if let Some(failures) = &mut self.failures {
+							failures.failed(url.as_iri(), e.code())
+						}
+
+						Err(e)
+					}
+				}
 			}
 		}
 	}
@@ -96,26 +233,239 @@ impl<J: Json + Clone + Send + Sync> crate::Loader for Loader<J> {
 
 	#[inline(always)]
 	fn iri(&self, id: loader::Id) -> Option<Iri<'_>> {
-		self.cache.get(id.unwrap()).map(|(_, iri)| iri.as_iri())
+		self.cache
+			.get(id.unwrap())
+			.map(|(_, iri, _, _)| iri.as_iri())
 	}
 
 	fn load<'a>(&'a mut self, url: Iri<'_>) -> BoxFuture<'a, Result<RemoteDocument<J>, Error>> {
 		let url: IriBuf = url.into();
-		async move {
-			match self.namespace.get(&url) {
-				Some(id) => Ok(RemoteDocument::new(
-					self.cache[id.unwrap()].0.clone(),
-					url,
-					*id,
-				)),
-				None => {
-					let doc = load_remote_json_ld_document(url.as_iri(), &mut self.parser).await?;
-					let id = self.allocate(url.clone(), doc.clone());
-					Ok(RemoteDocument::new(doc, url, id))
+		async move { Loader::load(self, url.as_iri()).await }.boxed()
+	}
+}
+
+/// Loads the context of the remote JSON-LD document at the given URL,
+/// along with the version information of the loaded document.
+///
+/// The request asks for the [`CONTEXT_PROFILE`] profile,
+/// so that servers able to serve the context of a document separately
+/// can answer with this context instead of the whole document.
+/// If the response is not a JSON document but has an alternate JSON-LD representation,
+/// advertised by a `Link` header with `rel="alternate"` and `type="application/ld+json"`,
+/// this representation is loaded instead.
+/// Documents embedded in `data:` URLs are decoded without any request.
+///
+/// In any case, the value of the `@context` entry of the loaded document is returned.
+/// Fails with an [`InvalidRemoteContext`](ErrorCode::InvalidRemoteContext) error if there
+/// is no such entry.
+pub async fn load_remote_context<J: Json, P>(
+	url: Iri<'_>,
+	parser: &mut P,
+) -> Result<(J, loader::Version), Error>
+where
+	J::Object: IntoIterator,
+	P: Send + Sync + FnMut(&str) -> Result<J, Error>,
+{
+	if loader::is_data_url(url) {
+		let (doc, _) = loader::load_data_url(url, parser)?;
+		return Ok((context_entry(doc)?, loader::Version::new(url.into())));
+	}
+
+	log::info!("loading remote context `{}'", url);
+	use reqwest::header::*;
+
+	let client = reqwest::Client::new();
+	let mut url = IriBuf::from(url);
+	let mut alternate_followed = false;
+	loop {
+		let request = client.get(url.as_str()).header(ACCEPT, CONTEXT_ACCEPT);
+		let response = request.send().await?;
+
+		let content_type = response
+			.headers()
+			.get(CONTENT_TYPE)
+			.and_then(|value| value.to_str().ok())
+			.unwrap_or("no content type")
+			.to_string();
+
+		// The media type may have parameters, such as a `profile`.
+		let essence = content_type.split(';').next().unwrap().trim();
+		if is_json_media_type(essence) {
+			let header = |name: HeaderName| {
+				response
+					.headers()
+					.get(name)
+					.and_then(|value| value.to_str().ok())
+					.map(ToString::to_string)
+			};
+
+			let version = loader::Version {
+				url: IriBuf::new(response.url().as_str()).unwrap_or_else(|_| url.clone()),
+				etag: header(ETAG),
+				last_modified: header(LAST_MODIFIED),
+				max_age: header(CACHE_CONTROL)
+					.as_deref()
+					.and_then(loader::cache_max_age),
+			};
+
+			let body = response.text().await?;
+			let doc = (*parser)(body.as_str())?;
+			return Ok((context_entry(doc)?, version));
+		}
+
+		if !alternate_followed {
+			if let Some(alternate) = alternate_link(response.headers(), url.as_iri()) {
+				alternate_followed = true;
+				url = alternate;
+				continue;
+			}
+		}
+
+		return Err(Error::with_details(
+			ErrorCode::LoadingDocumentFailed,
+			ErrorDetails::UnexpectedValue {
+				expected: "a JSON media type".to_string(),
+				found: content_type,
+			},
+		));
+	}
+}
+
+/// Returns the value of the `@context` entry of the given document.
+fn context_entry<J: Json>(doc: J) -> Result<J, Error>
+where
+	J::Object: IntoIterator,
+{
+	if let generic_json::Value::Object(obj) = doc.into() {
+		for (key, value) in obj {
+			if &*key == "@context" {
+				return Ok(value);
+			}
+		}
+	}
+
+	Err(ErrorCode::InvalidRemoteContext.into())
+}
+
+/// Finds the target of the alternate JSON-LD representation
+/// advertised by the `Link` headers, if any.
+///
+/// Relative targets are resolved against `base`.
+fn alternate_link(headers: &reqwest::header::HeaderMap, base: Iri) -> Option<IriBuf> {
+	headers
+		.get_all(reqwest::header::LINK)
+		.iter()
+		.filter_map(|value| value.to_str().ok())
+		.flat_map(|value| value.split(','))
+		.find_map(|link| {
+			let mut parts = link.split(';').map(str::trim);
+			let target = parts.next()?.strip_prefix('<')?.strip_suffix('>')?;
+
+			let mut is_alternate = false;
+			let mut is_json_ld = false;
+			for param in parts {
+				if let Some((name, value)) = param.split_once('=') {
+					let value = value.trim().trim_matches('"');
+					match name.trim().to_ascii_lowercase().as_str() {
+						"rel" => is_alternate = value.split_whitespace().any(|r| r == "alternate"),
+						"type" => is_json_ld = value == "application/ld+json",
+						_ => (),
+					}
 				}
 			}
+
+			if is_alternate && is_json_ld {
+				IriRef::new(target).ok().map(|target| target.resolved(base))
+			} else {
+				None
+			}
+		})
+}
+
+/// Context loader that only fetches the context of remote documents, when possible.
+///
+/// Contexts are loaded with [`load_remote_context`]:
+/// the context profile is requested so that servers supporting it
+/// answer with the context only, instead of the whole document.
+/// Otherwise the context is extracted from the whole document.
+///
+/// Unlike [`Loader`], this loader cannot load documents:
+/// it only implements [`context::Loader`].
+pub struct ContextLoader<J> {
+	namespace: HashMap<IriBuf, loader::Id>,
+	cache: Vec<(J, IriBuf, Option<loader::Version>)>,
+	parser: Box<dyn 'static + Send + Sync + FnMut(&str) -> Result<J, Error>>,
+}
+
+impl<J: Json + Clone + Send> ContextLoader<J>
+where
+	J::Object: IntoIterator,
+{
+	pub fn new<E: 'static + std::error::Error>(
+		mut parser: impl 'static + Send + Sync + FnMut(&str) -> Result<J, E>,
+	) -> Self {
+		Self {
+			namespace: HashMap::new(),
+			cache: Vec::new(),
+			parser: Box::new(move |s| {
+				parser(s).map_err(|e| Error::with_source(ErrorCode::LoadingDocumentFailed, e))
+			}),
+		}
+	}
+
+	/// Returns the version information of the context document with the given identifier,
+	/// if any.
+	pub fn version(&self, id: loader::Id) -> Option<&loader::Version> {
+		self.cache
+			.get(id.unwrap())
+			.and_then(|(_, _, version)| version.as_ref())
+	}
+
+	pub async fn load_context(&mut self, url: Iri<'_>) -> Result<RemoteContext<J>, Error> {
+		let url = IriBuf::from(url);
+		if let Some(id) = self.namespace.get(&url) {
+			let (context, _, version) = &self.cache[id.unwrap()];
+			let context = RemoteContext::from_parts(url, *id, context.clone());
+			return Ok(context.with_version(version.clone()));
 		}
-		.boxed()
+
+		match load_remote_context(url.as_iri(), &mut self.parser).await {
+			Ok((context, version)) => {
+				let id = loader::Id::new(self.cache.len());
+				self.namespace.insert(url.clone(), id);
+				self.cache
+					.push((context.clone(), url.clone(), Some(version.clone())));
+
+				Ok(RemoteContext::from_parts(url, id, context).with_version(Some(version)))
+			}
+			Err(e) if e.code() == ErrorCode::InvalidRemoteContext => Err(e),
+			Err(e) => Err(Error::with_source(ErrorCode::LoadingRemoteContextFailed, e)
+				.set_details(ErrorDetails::Iri {
+					iri: url.as_str().to_string(),
+				})),
+		}
+	}
+}
+
+impl<J: Json + Clone + Send + Sync> context::Loader for ContextLoader<J>
+where
+	J::Object: IntoIterator,
+{
+	type Output = J;
+
+	#[inline(always)]
+	fn id(&self, iri: Iri<'_>) -> Option<loader::Id> {
+		self.namespace.get(&IriBuf::from(iri)).cloned()
+	}
+
+	#[inline(always)]
+	fn iri(&self, id: loader::Id) -> Option<Iri<'_>> {
+		self.cache.get(id.unwrap()).map(|(_, iri, _)| iri.as_iri())
+	}
+
+	fn load_context<'a>(&'a mut self, url: Iri) -> BoxFuture<'a, Result<RemoteContext<J>, Error>> {
+		let url: IriBuf = url.into();
+		async move { ContextLoader::load_context(self, url.as_iri()).await }.boxed()
 	}
 }
 
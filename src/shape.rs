@@ -0,0 +1,370 @@
+//! Lightweight shape validation.
+//!
+//! A [`Shape`] describes the properties expected on the nodes of a given type:
+//! how many values each property must have, and what kind of values are accepted.
+//! This is far less expressive than [SHACL](https://www.w3.org/TR/shacl/),
+//! but allows rejecting malformed data early using the expanded object model directly.
+//!
+//! ```
+//! use iref::IriBuf;
+//! use json_ld::{context, Document, NoLoader, Reference};
+//! use json_ld::shape::{Issue, PropertyShape, Shape, ValueKind};
+//! use json_ld::object::value::xsd;
+//! use serde_json::Value;
+//!
+//! # async_std::task::block_on(async {
+//! let doc: Value = serde_json::from_str(r#"
+//!   {
+//!     "@id": "http://example.org/alice",
+//!     "@type": "http://xmlns.com/foaf/0.1/Person",
+//!     "http://xmlns.com/foaf/0.1/age": "unknown"
+//!   }
+//! "#).unwrap();
+//!
+//! let mut loader = NoLoader::<Value>::new();
+//! let expanded = doc.expand::<context::Json<Value, IriBuf>, _>(&mut loader).await.unwrap();
+//!
+//! let iri = |s: &str| IriBuf::new(s).unwrap();
+//! let person = Shape::new(Reference::Id(iri("http://xmlns.com/foaf/0.1/Person")))
+//!   .with_property(
+//!     PropertyShape::new(Reference::Id(iri("http://xmlns.com/foaf/0.1/name"))).required()
+//!   )
+//!   .with_property(
+//!     PropertyShape::new(Reference::Id(iri("http://xmlns.com/foaf/0.1/age")))
+//!       .with_max_count(1)
+//!       .with_kind(ValueKind::Xsd(xsd::Datatype::Integer))
+//!   );
+//!
+//! let violations = expanded.validate(&[person]);
+//! assert_eq!(violations.len(), 2);
+//! assert!(violations.iter().any(|v| v.issue == Issue::MinCount { min: 1, count: 0 }));
+//! assert!(violations.iter().any(|v| v.issue == Issue::UnexpectedValue));
+//! # })
+//! ```
+use crate::{
+	object::value::{xsd, Literal},
+	Id, Node, Object, Reference, Value,
+};
+use generic_json::JsonHash;
+use std::fmt;
+
+/// Kind of value accepted by a property.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub enum ValueKind<T: Id> {
+	/// Node object, or node reference.
+	Node,
+
+	/// Node object having the given type.
+	NodeOfType(Reference<T>),
+
+	/// Literal typed with the given datatype.
+	Typed(T),
+
+	/// Literal whose value is valid for the given XSD datatype.
+	///
+	/// The literal may be typed with this datatype, in which case its lexical form
+	/// must be valid, or be a native JSON value compatible with the datatype
+	/// (a boolean, a number, or an untyped string for `xsd:string`).
+	Xsd(xsd::Datatype),
+
+	/// Language tagged string.
+	LangString,
+
+	/// JSON literal.
+	Json,
+}
+
+impl<T: Id> ValueKind<T> {
+	/// Checks if the given object is of this kind.
+	///
+	/// List objects are never of any kind: their items are checked instead.
+	pub fn matches<J: JsonHash>(&self, object: &Object<J, T>) -> bool {
+		match (self, object) {
+			(Self::Node, Object::Node(_)) => true,
+			(Self::NodeOfType(ty), Object::Node(node)) => node.has_type(ty),
+			(Self::Typed(ty), Object::Value(Value::Literal(_, Some(t)), _)) => t == ty,
+			(Self::Xsd(ty), Object::Value(value, _)) => xsd_matches(*ty, value),
+			(Self::LangString, Object::Value(Value::LangString(_), _)) => true,
+			(Self::Json, Object::Value(Value::Json(_), _)) => true,
+			_ => false,
+		}
+	}
+}
+
+fn xsd_matches<J: JsonHash, T: Id>(ty: xsd::Datatype, value: &Value<J, T>) -> bool {
+	match value {
+		Value::Literal(_, Some(_)) if value.xsd_datatype() != Some(ty) => false,
+		Value::Literal(Literal::Null, _) | Value::LangString(_) | Value::Json(_) => false,
+		Value::Literal(Literal::String(_), None) => ty == xsd::Datatype::String,
+		_ => match ty {
+			xsd::Datatype::Boolean => value.as_bool().is_some(),
+			xsd::Datatype::Date => value.as_date().is_some(),
+			xsd::Datatype::DateTime => value.as_datetime().is_some(),
+			xsd::Datatype::Duration => value.as_duration().is_some(),
+			xsd::Datatype::String => value.as_str().is_some(),
+			ty if ty.is_integer() => value.as_i64().is_some(),
+			_ => value.as_f64().is_some(),
+		},
+	}
+}
+
+/// Constraints on the values of a property.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct PropertyShape<T: Id> {
+	property: Reference<T>,
+	min_count: usize,
+	max_count: Option<usize>,
+	kinds: Vec<ValueKind<T>>,
+}
+
+impl<T: Id> PropertyShape<T> {
+	/// Creates a new shape for the given property, accepting any number of values of any kind.
+	pub fn new(property: Reference<T>) -> Self {
+		Self {
+			property,
+			min_count: 0,
+			max_count: None,
+			kinds: Vec::new(),
+		}
+	}
+
+	/// Constrained property.
+	#[inline(always)]
+	pub fn property(&self) -> &Reference<T> {
+		&self.property
+	}
+
+	/// Minimum number of values.
+	#[inline(always)]
+	pub fn min_count(&self) -> usize {
+		self.min_count
+	}
+
+	/// Maximum number of values, if any.
+	#[inline(always)]
+	pub fn max_count(&self) -> Option<usize> {
+		self.max_count
+	}
+
+	/// Accepted kinds of values.
+	///
+	/// If empty, any value is accepted.
+	#[inline(always)]
+	pub fn kinds(&self) -> &[ValueKind<T>] {
+		&self.kinds
+	}
+
+	/// Sets the minimum number of values.
+	#[inline(always)]
+	pub fn with_min_count(mut self, min: usize) -> Self {
+		self.min_count = min;
+		self
+	}
+
+	/// Sets the maximum number of values.
+	#[inline(always)]
+	pub fn with_max_count(mut self, max: usize) -> Self {
+		self.max_count = Some(max);
+		self
+	}
+
+	/// Requires at least one value.
+	#[inline(always)]
+	pub fn required(self) -> Self {
+		self.with_min_count(1)
+	}
+
+	/// Adds the given kind to the accepted kinds of values.
+	#[inline(always)]
+	pub fn with_kind(mut self, kind: ValueKind<T>) -> Self {
+		self.kinds.push(kind);
+		self
+	}
+
+	/// Checks if the given value is accepted.
+	///
+	/// Lists are accepted if all their items are.
+	pub fn accepts<J: JsonHash>(&self, object: &Object<J, T>) -> bool {
+		match object {
+			Object::List(items, _) => items.iter().all(|item| self.accepts(item.inner())),
+			object => self.kinds.is_empty() || self.kinds.iter().any(|k| k.matches(object)),
+		}
+	}
+}
+
+/// Expected shape of the nodes of a given type.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Shape<T: Id> {
+	ty: Reference<T>,
+	properties: Vec<PropertyShape<T>>,
+}
+
+impl<T: Id> Shape<T> {
+	/// Creates a new shape for the nodes of the given type, without any constraint.
+	pub fn new(ty: Reference<T>) -> Self {
+		Self {
+			ty,
+			properties: Vec::new(),
+		}
+	}
+
+	/// Type of the nodes constrained by this shape.
+	#[inline(always)]
+	pub fn ty(&self) -> &Reference<T> {
+		&self.ty
+	}
+
+	/// Property constraints.
+	#[inline(always)]
+	pub fn properties(&self) -> &[PropertyShape<T>] {
+		&self.properties
+	}
+
+	/// Adds a property constraint.
+	#[inline(always)]
+	pub fn with_property(mut self, property: PropertyShape<T>) -> Self {
+		self.properties.push(property);
+		self
+	}
+
+	/// Checks if the given node is targeted by this shape.
+	#[inline(always)]
+	pub fn targets<J: JsonHash>(&self, node: &Node<J, T>) -> bool {
+		node.has_type(&self.ty)
+	}
+
+	/// Validates the given node against this shape, whatever its type.
+	///
+	/// Violations are appended to `violations`.
+	pub fn validate_node<J: JsonHash>(
+		&self,
+		node: &Node<J, T>,
+		violations: &mut Vec<Violation<T, J::MetaData>>,
+	) {
+		for property in &self.properties {
+			let values: Vec<_> = node.get(property.property()).collect();
+			let count = values.len();
+
+			if count < property.min_count {
+				violations.push(Violation::new(
+					node,
+					property,
+					Issue::MinCount {
+						min: property.min_count,
+						count,
+					},
+					node.metadata(),
+				))
+			}
+
+			if let Some(max) = property.max_count {
+				if count > max {
+					violations.push(Violation::new(
+						node,
+						property,
+						Issue::MaxCount { max, count },
+						node.metadata(),
+					))
+				}
+			}
+
+			for value in values {
+				if !property.accepts(value.inner()) {
+					violations.push(Violation::new(
+						node,
+						property,
+						Issue::UnexpectedValue,
+						value.metadata().or_else(|| node.metadata()),
+					))
+				}
+			}
+		}
+	}
+}
+
+/// Shape violation.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Issue {
+	/// The property has less values than required.
+	MinCount { min: usize, count: usize },
+
+	/// The property has more values than allowed.
+	MaxCount { max: usize, count: usize },
+
+	/// The value is not of any accepted kind.
+	UnexpectedValue,
+}
+
+impl fmt::Display for Issue {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Self::MinCount { min, count } => {
+				write!(f, "expected at least {} value(s), found {}", min, count)
+			}
+			Self::MaxCount { max, count } => {
+				write!(f, "expected at most {} value(s), found {}", max, count)
+			}
+			Self::UnexpectedValue => write!(f, "unexpected value"),
+		}
+	}
+}
+
+/// Shape violation located in the document.
+#[derive(Clone, Debug)]
+pub struct Violation<T: Id, M> {
+	/// Issue.
+	pub issue: Issue,
+
+	/// Identifier of the offending node, if any.
+	pub node: Option<Reference<T>>,
+
+	/// Offending property.
+	pub property: Reference<T>,
+
+	/// Metadata of the offending value, or of the offending node if unknown.
+	pub metadata: Option<M>,
+}
+
+impl<T: Id, M: Clone> Violation<T, M> {
+	fn new<J: JsonHash<MetaData = M>>(
+		node: &Node<J, T>,
+		property: &PropertyShape<T>,
+		issue: Issue,
+		metadata: Option<&M>,
+	) -> Self {
+		Self {
+			issue,
+			node: node.id().cloned(),
+			property: property.property().clone(),
+			metadata: metadata.cloned(),
+		}
+	}
+}
+
+impl<T: Id, M> fmt::Display for Violation<T, M> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match &self.node {
+			Some(node) => write!(f, "{}: `{}` of `{}`", self.issue, self.property, node),
+			None => write!(f, "{}: `{}`", self.issue, self.property),
+		}
+	}
+}
+
+/// Validates the given nodes against the given shapes.
+///
+/// Each node is validated against every shape targeting one of its types.
+pub fn validate<'a, J: 'a + JsonHash, T: 'a + Id>(
+	nodes: impl IntoIterator<Item = &'a Node<J, T>>,
+	shapes: &[Shape<T>],
+) -> Vec<Violation<T, J::MetaData>> {
+	let mut violations = Vec::new();
+	for node in nodes {
+		for shape in shapes {
+			if shape.targets(node) {
+				shape.validate_node(node, &mut violations)
+			}
+		}
+	}
+
+	violations
+}
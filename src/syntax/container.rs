@@ -1,5 +1,5 @@
 use super::Keyword;
-use std::convert::TryFrom;
+use core::convert::TryFrom;
 
 #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
 pub enum ContainerType {
@@ -0,0 +1,544 @@
+use super::{ContainerType, Keyword};
+#[cfg(not(feature = "std"))]
+use crate::prelude::*;
+use crate::{util::AsAnyJson, Direction};
+use core::fmt;
+use core::str::FromStr;
+use generic_json::JsonBuild;
+
+/// Context definition.
+///
+/// Programmatic representation of a JSON-LD context definition (a local context object),
+/// that can be built term by term, merged with other definitions and serialized into JSON
+/// using the [`AsAnyJson`] trait.
+/// Terms are serialized in the order they have been added.
+///
+/// ```
+/// use json_ld::{syntax::{ContextDefinition, ExpandedTermDefinition}, util::AsAnyJson};
+/// use serde_json::Value;
+///
+/// let context = ContextDefinition::new()
+///   .with_vocab("https://schema.org/")
+///   .with_prefix("foaf", "http://xmlns.com/foaf/0.1/")
+///   .with_term("name", ExpandedTermDefinition::new("foaf:name"));
+///
+/// let json: Value = context.as_json();
+/// assert_eq!(json["name"], "foaf:name");
+/// ```
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct ContextDefinition {
+	/// `@base` entry. `Some(None)` stands for `null`.
+	pub base: Option<Option<String>>,
+
+	/// `@vocab` entry. `Some(None)` stands for `null`.
+	pub vocab: Option<Option<String>>,
+
+	/// `@language` entry. `Some(None)` stands for `null`.
+	pub language: Option<Option<String>>,
+
+	/// `@direction` entry. `Some(None)` stands for `null`.
+	pub direction: Option<Option<Direction>>,
+
+	/// `@propagate` entry.
+	pub propagate: Option<bool>,
+
+	/// `@protected` entry.
+	pub protected: Option<bool>,
+
+	/// Term definitions, in order. `None` stands for a `null` definition.
+	terms: Vec<(String, Option<ExpandedTermDefinition>)>,
+}
+
+/// Checks if the given IRI ends with a gen-delim character,
+/// making the term mapped to it a prefix by default.
+pub(crate) fn ends_with_gen_delim(iri: &str) -> bool {
+	iri.ends_with(|c| matches!(c, ':' | '/' | '?' | '#' | '[' | ']' | '@'))
+}
+
+/// Conflicting entry found while merging context definitions.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct MergeConflict {
+	/// Conflicting term or keyword.
+	pub key: String,
+}
+
+impl fmt::Display for MergeConflict {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "conflicting definitions for `{}`", self.key)
+	}
+}
+
+impl ContextDefinition {
+	/// Creates a new empty context definition.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Sets the `@base` entry.
+	#[must_use]
+	pub fn with_base(mut self, base: impl Into<String>) -> Self {
+		self.base = Some(Some(base.into()));
+		self
+	}
+
+	/// Sets the `@vocab` entry.
+	#[must_use]
+	pub fn with_vocab(mut self, vocab: impl Into<String>) -> Self {
+		self.vocab = Some(Some(vocab.into()));
+		self
+	}
+
+	/// Sets the `@language` entry.
+	#[must_use]
+	pub fn with_language(mut self, language: impl Into<String>) -> Self {
+		self.language = Some(Some(language.into()));
+		self
+	}
+
+	/// Sets the `@direction` entry.
+	#[must_use]
+	pub fn with_direction(mut self, direction: Direction) -> Self {
+		self.direction = Some(Some(direction));
+		self
+	}
+
+	/// Sets the `@protected` entry.
+	#[must_use]
+	pub fn with_protected(mut self, protected: bool) -> Self {
+		self.protected = Some(protected);
+		self
+	}
+
+	/// Sets the `@propagate` entry.
+	#[must_use]
+	pub fn with_propagate(mut self, propagate: bool) -> Self {
+		self.propagate = Some(propagate);
+		self
+	}
+
+	/// Adds (or replaces) the definition of the given term.
+	#[must_use]
+	pub fn with_term(
+		mut self,
+		term: impl Into<String>,
+		definition: ExpandedTermDefinition,
+	) -> Self {
+		self.insert(term.into(), Some(definition));
+		self
+	}
+
+	/// Adds (or replaces) a `null` definition for the given term,
+	/// preventing it from being expanded.
+	#[must_use]
+	pub fn with_null_term(mut self, term: impl Into<String>) -> Self {
+		self.insert(term.into(), None);
+		self
+	}
+
+	/// Adds (or replaces) a prefix definition.
+	///
+	/// If the IRI does not end with a gen-delim character, the definition
+	/// is explicitly marked as a prefix with `@prefix`.
+	#[must_use]
+	pub fn with_prefix(self, prefix: impl Into<String>, iri: impl Into<String>) -> Self {
+		let iri = iri.into();
+		let mut definition = ExpandedTermDefinition::new(iri.as_str());
+		if !ends_with_gen_delim(&iri) {
+			definition.prefix = Some(true)
+		}
+
+		self.with_term(prefix, definition)
+	}
+
+	/// Defines a nest group.
+	///
+	/// The `nest` term is defined as an alias of `@nest`,
+	/// and the given terms are nested under it (with a `@nest` entry),
+	/// so that the compaction algorithm groups their values into a `nest` object.
+	/// Terms that are not defined yet are defined as the given IRI, compact IRI or term.
+	#[must_use]
+	pub fn with_nest_group<'t>(
+		mut self,
+		nest: impl Into<String>,
+		terms: impl IntoIterator<Item = &'t str>,
+	) -> Self {
+		let nest = nest.into();
+
+		for term in terms {
+			let mut definition = match self.get(term) {
+				Some(Some(definition)) => definition.clone(),
+				_ => ExpandedTermDefinition::new(term),
+			};
+
+			definition.nest = Some(nest.clone());
+			self.insert(term.to_string(), Some(definition))
+		}
+
+		self.insert(nest, Some(ExpandedTermDefinition::new("@nest")));
+		self
+	}
+
+	/// Inserts the given term definition, replacing any previous definition
+	/// while keeping its position.
+	pub fn insert(&mut self, term: String, definition: Option<ExpandedTermDefinition>) {
+		match self.terms.iter_mut().find(|(t, _)| *t == term) {
+			Some((_, d)) => *d = definition,
+			None => self.terms.push((term, definition)),
+		}
+	}
+
+	/// Removes the definition of the given term.
+	pub fn remove(&mut self, term: &str) -> Option<Option<ExpandedTermDefinition>> {
+		let i = self.terms.iter().position(|(t, _)| t == term)?;
+		Some(self.terms.remove(i).1)
+	}
+
+	/// Returns the definition of the given term, if any.
+	///
+	/// Returns `Some(None)` if the term has a `null` definition.
+	pub fn get(&self, term: &str) -> Option<Option<&ExpandedTermDefinition>> {
+		self.terms
+			.iter()
+			.find(|(t, _)| t == term)
+			.map(|(_, d)| d.as_ref())
+	}
+
+	/// Returns an iterator over the term definitions, in order.
+	pub fn terms(&self) -> impl Iterator<Item = (&str, Option<&ExpandedTermDefinition>)> {
+		self.terms.iter().map(|(t, d)| (t.as_str(), d.as_ref()))
+	}
+
+	/// Merges the given context definition into this one.
+	///
+	/// Entries and terms defined by both definitions must be equal,
+	/// otherwise every conflicting key is returned and this definition is left unchanged.
+	/// New terms are appended after the existing ones.
+	pub fn merge(&mut self, other: ContextDefinition) -> Result<(), Vec<MergeConflict>> {
+		let mut conflicts = Vec::new();
+
+		fn check<T: PartialEq>(
+			conflicts: &mut Vec<MergeConflict>,
+			key: Keyword,
+			a: &Option<T>,
+			b: &Option<T>,
+		) {
+			if let (Some(a), Some(b)) = (a, b) {
+				if a != b {
+					conflicts.push(MergeConflict {
+						key: key.into_str().to_string(),
+					})
+				}
+			}
+		}
+
+		check(&mut conflicts, Keyword::Base, &self.base, &other.base);
+		check(&mut conflicts, Keyword::Vocab, &self.vocab, &other.vocab);
+		check(
+			&mut conflicts,
+			Keyword::Language,
+			&self.language,
+			&other.language,
+		);
+		check(
+			&mut conflicts,
+			Keyword::Direction,
+			&self.direction,
+			&other.direction,
+		);
+		check(
+			&mut conflicts,
+			Keyword::Propagate,
+			&self.propagate,
+			&other.propagate,
+		);
+		check(
+			&mut conflicts,
+			Keyword::Protected,
+			&self.protected,
+			&other.protected,
+		);
+
+		for (term, definition) in &other.terms {
+			if let Some(current) = self.get(term) {
+				if current != definition.as_ref() {
+					conflicts.push(MergeConflict { key: term.clone() })
+				}
+			}
+		}
+
+		if !conflicts.is_empty() {
+			return Err(conflicts);
+		}
+
+		self.base = self.base.take().or(other.base);
+		self.vocab = self.vocab.take().or(other.vocab);
+		self.language = self.language.take().or(other.language);
+		self.direction = self.direction.or(other.direction);
+		self.propagate = self.propagate.or(other.propagate);
+		self.protected = self.protected.or(other.protected);
+
+		for (term, definition) in other.terms {
+			self.insert(term, definition)
+		}
+
+		Ok(())
+	}
+}
+
+/// Policy deciding if a `"@version": 1.1` entry is added to a serialized context definition.
+///
+/// See [`ContextDefinition::as_json_with_version`].
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum VersionEntry {
+	/// No `@version` entry.
+	Omit,
+
+	/// Always add a `@version` entry.
+	Always,
+
+	/// Add a `@version` entry only if the context uses JSON-LD 1.1 features
+	/// (see [`ContextDefinition::features`]).
+	Auto,
+}
+
+impl ContextDefinition {
+	/// Converts this context definition into JSON, with a `"@version": 1.1` entry
+	/// depending on the given policy.
+	///
+	/// This is the same as [`AsAnyJson::as_json_with`] (that never adds a `@version` entry),
+	/// except that the version number must be parsed into a `K::Number`.
+	/// Contexts targeting both JSON-LD 1.0 and 1.1 consumers should use [`VersionEntry::Auto`],
+	/// so that JSON-LD 1.0 processors reject contexts they would misinterpret.
+	///
+	/// ```
+	/// use json_ld::syntax::{
+	///   ContainerType, ContextDefinition, ExpandedTermDefinition, VersionEntry,
+	/// };
+	/// use serde_json::{json, Value};
+	///
+	/// let mut tags = ExpandedTermDefinition::new("http://example.org/tag");
+	/// tags.container = vec![ContainerType::Id];
+	///
+	/// let context = ContextDefinition::new().with_term("tags", tags);
+	/// let json: Value = context.as_json_with_version(VersionEntry::Auto, ());
+	/// assert_eq!(json["@version"], json!(1.1));
+	/// ```
+	pub fn as_json_with_version<K: JsonBuild>(&self, version: VersionEntry, meta: K::MetaData) -> K
+	where
+		K::Number: FromStr,
+	{
+		let mut entries = Vec::new();
+
+		let versioned = match version {
+			VersionEntry::Omit => false,
+			VersionEntry::Always => true,
+			VersionEntry::Auto => self.requires_json_ld_1_1(),
+		};
+
+		if versioned {
+			if let Ok(number) = "1.1".parse() {
+				entries.push((
+					K::new_key(Keyword::Version.into_str(), meta.clone()),
+					K::number(number, meta.clone()),
+				))
+			}
+		}
+
+		entries.extend(self.entries::<K>(&meta));
+		K::object(entries.into_iter().collect(), meta)
+	}
+
+	fn entries<K: JsonBuild>(&self, meta: &K::MetaData) -> Vec<(K::Key, K)> {
+		let mut entries = Vec::new();
+
+		fn nullable<K: JsonBuild, T: AsAnyJson<K>>(value: &Option<T>, meta: K::MetaData) -> K {
+			match value {
+				Some(value) => value.as_json_with(meta),
+				None => K::null(meta),
+			}
+		}
+
+		let mut entry = |key: Keyword, value: K| {
+			entries.push((K::new_key(key.into_str(), meta.clone()), value))
+		};
+
+		if let Some(base) = &self.base {
+			entry(Keyword::Base, nullable(base, meta.clone()))
+		}
+
+		if let Some(vocab) = &self.vocab {
+			entry(Keyword::Vocab, nullable(vocab, meta.clone()))
+		}
+
+		if let Some(language) = &self.language {
+			entry(Keyword::Language, nullable(language, meta.clone()))
+		}
+
+		if let Some(direction) = &self.direction {
+			entry(Keyword::Direction, nullable(direction, meta.clone()))
+		}
+
+		if let Some(propagate) = self.propagate {
+			entry(Keyword::Propagate, propagate.as_json_with(meta.clone()))
+		}
+
+		if let Some(protected) = self.protected {
+			entry(Keyword::Protected, protected.as_json_with(meta.clone()))
+		}
+
+		for (term, definition) in &self.terms {
+			entries.push((
+				K::new_key(term, meta.clone()),
+				nullable(definition, meta.clone()),
+			))
+		}
+
+		entries
+	}
+}
+
+impl<K: JsonBuild> AsAnyJson<K> for ContextDefinition {
+	fn as_json_with(&self, meta: K::MetaData) -> K {
+		K::object(self.entries::<K>(&meta).into_iter().collect(), meta)
+	}
+}
+
+/// Expanded term definition.
+///
+/// A definition with only an `@id` entry is serialized as a simple term definition.
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct ExpandedTermDefinition {
+	/// `@id` entry.
+	pub id: Option<String>,
+
+	/// `@reverse` entry.
+	pub reverse: Option<String>,
+
+	/// `@type` entry.
+	pub type_: Option<String>,
+
+	/// `@language` entry. `Some(None)` stands for `null`.
+	pub language: Option<Option<String>>,
+
+	/// `@direction` entry. `Some(None)` stands for `null`.
+	pub direction: Option<Option<Direction>>,
+
+	/// `@container` entry.
+	pub container: Vec<ContainerType>,
+
+	/// `@context` entry (scoped context).
+	pub context: Option<Box<ContextDefinition>>,
+
+	/// `@index` entry.
+	pub index: Option<String>,
+
+	/// `@nest` entry.
+	pub nest: Option<String>,
+
+	/// `@prefix` entry.
+	pub prefix: Option<bool>,
+
+	/// `@protected` entry.
+	pub protected: Option<bool>,
+}
+
+impl ExpandedTermDefinition {
+	/// Creates a new term definition mapped to the given IRI, compact IRI, term or keyword.
+	pub fn new(id: impl Into<String>) -> Self {
+		Self {
+			id: Some(id.into()),
+			..Default::default()
+		}
+	}
+
+	/// Checks if this definition is a simple term definition (it only has an `@id` entry).
+	pub fn is_simple(&self) -> bool {
+		self.id.is_some()
+			&& *self
+				== Self {
+					id: self.id.clone(),
+					..Default::default()
+				}
+	}
+}
+
+impl<K: JsonBuild> AsAnyJson<K> for ExpandedTermDefinition {
+	fn as_json_with(&self, meta: K::MetaData) -> K {
+		if self.is_simple() {
+			return self.id.as_ref().unwrap().as_json_with(meta);
+		}
+
+		let mut entries = Vec::new();
+		let mut entry = |key: Keyword, value: K| {
+			entries.push((K::new_key(key.into_str(), meta.clone()), value))
+		};
+
+		if let Some(id) = &self.id {
+			entry(Keyword::Id, id.as_json_with(meta.clone()))
+		}
+
+		if let Some(reverse) = &self.reverse {
+			entry(Keyword::Reverse, reverse.as_json_with(meta.clone()))
+		}
+
+		if let Some(ty) = &self.type_ {
+			entry(Keyword::Type, ty.as_json_with(meta.clone()))
+		}
+
+		if let Some(language) = &self.language {
+			let value = match language {
+				Some(language) => language.as_json_with(meta.clone()),
+				None => K::null(meta.clone()),
+			};
+			entry(Keyword::Language, value)
+		}
+
+		if let Some(direction) = &self.direction {
+			let value = match direction {
+				Some(direction) => direction.as_json_with(meta.clone()),
+				None => K::null(meta.clone()),
+			};
+			entry(Keyword::Direction, value)
+		}
+
+		match self.container.as_slice() {
+			[] => (),
+			[c] => entry(
+				Keyword::Container,
+				Keyword::from(*c).into_str().as_json_with(meta.clone()),
+			),
+			containers => entry(
+				Keyword::Container,
+				K::array(
+					containers
+						.iter()
+						.map(|c| Keyword::from(*c).into_str().as_json_with(meta.clone()))
+						.collect(),
+					meta.clone(),
+				),
+			),
+		}
+
+		if let Some(context) = &self.context {
+			entry(Keyword::Context, context.as_json_with(meta.clone()))
+		}
+
+		if let Some(index) = &self.index {
+			entry(Keyword::Index, index.as_json_with(meta.clone()))
+		}
+
+		if let Some(nest) = &self.nest {
+			entry(Keyword::Nest, nest.as_json_with(meta.clone()))
+		}
+
+		if let Some(prefix) = self.prefix {
+			entry(Keyword::Prefix, prefix.as_json_with(meta.clone()))
+		}
+
+		if let Some(protected) = self.protected {
+			entry(Keyword::Protected, protected.as_json_with(meta.clone()))
+		}
+
+		K::object(entries.into_iter().collect(), meta)
+	}
+}
@@ -0,0 +1,333 @@
+//! Detection of the JSON-LD 1.1 features used by contexts and documents.
+use super::{ContainerType, ContextDefinition, ExpandedTermDefinition};
+use alloc::collections::BTreeSet;
+use cc_traits::{Iter, MapIter};
+use core::fmt;
+use generic_json::{Json, ValueRef};
+
+/// JSON-LD 1.1 feature, unknown to JSON-LD 1.0 processors.
+///
+/// A context relying on any of those features should contain a `"@version": 1.1` entry,
+/// so that JSON-LD 1.0 processors reject it instead of silently misinterpreting it.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub enum Feature {
+	/// Base direction (`@direction`).
+	Direction,
+
+	/// Context propagation (`@propagate`).
+	Propagate,
+
+	/// Protected term definitions (`@protected`).
+	Protected,
+
+	/// Imported contexts (`@import`).
+	Import,
+
+	/// Scoped contexts (`@context` in a term definition).
+	ScopedContext,
+
+	/// Nested properties (`@nest`).
+	Nest,
+
+	/// Explicit prefix flag (`@prefix`).
+	Prefix,
+
+	/// Property-based data indexing (`@index` in a term definition).
+	PropertyIndex,
+
+	/// Node identifier maps (`@id` container).
+	IdMap,
+
+	/// Node type maps (`@type` container).
+	TypeMap,
+
+	/// Graph containers (`@graph` container).
+	GraphContainer,
+
+	/// Container mappings given as an array.
+	ContainerArray,
+
+	/// JSON literals (`@json` type).
+	JsonLiteral,
+
+	/// Included blocks (`@included`).
+	Included,
+}
+
+impl Feature {
+	/// Returns a short description of the feature.
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			Self::Direction => "base direction (`@direction`)",
+			Self::Propagate => "context propagation (`@propagate`)",
+			Self::Protected => "protected term definitions (`@protected`)",
+			Self::Import => "imported contexts (`@import`)",
+			Self::ScopedContext => "scoped contexts",
+			Self::Nest => "nested properties (`@nest`)",
+			Self::Prefix => "prefix flag (`@prefix`)",
+			Self::PropertyIndex => "property-based data indexing",
+			Self::IdMap => "node identifier maps",
+			Self::TypeMap => "node type maps",
+			Self::GraphContainer => "graph containers",
+			Self::ContainerArray => "container mapping arrays",
+			Self::JsonLiteral => "JSON literals (`@json`)",
+			Self::Included => "included blocks (`@included`)",
+		}
+	}
+}
+
+impl fmt::Display for Feature {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		self.as_str().fmt(f)
+	}
+}
+
+/// Returns the JSON-LD 1.1 features used by the given JSON-LD document.
+///
+/// This includes the features used by the embedded contexts (`@context` entries),
+/// but not the ones of the remote contexts they refer to.
+/// The document can also be a context document, or a local context itself
+/// (see [`context_features`]).
+///
+/// ```
+/// use json_ld::syntax::{document_features, Feature};
+/// use serde_json::json;
+///
+/// let doc = json!({
+///   "@context": { "tags": { "@id": "http://example.org/tag", "@container": "@id" } },
+///   "@included": []
+/// });
+///
+/// let features = document_features(&doc);
+/// assert!(features.contains(&Feature::IdMap));
+/// assert!(features.contains(&Feature::Included));
+/// ```
+pub fn document_features<J: Json>(json: &J) -> BTreeSet<Feature> {
+	let mut features = BTreeSet::new();
+	document(json, &mut features);
+	features
+}
+
+/// Returns the JSON-LD 1.1 features used by the given local context.
+///
+/// Remote contexts are not loaded.
+pub fn context_features<J: Json>(json: &J) -> BTreeSet<Feature> {
+	let mut features = BTreeSet::new();
+	context(json, &mut features);
+	features
+}
+
+fn document<J: Json>(json: &J, features: &mut BTreeSet<Feature>) {
+	match json.as_value_ref() {
+		ValueRef::Array(ary) => {
+			for item in ary.iter() {
+				document(&*item, features)
+			}
+		}
+		ValueRef::Object(obj) => {
+			for (key, value) in obj.iter() {
+				match &**key {
+					"@context" => context(&*value, features),
+					"@direction" => {
+						features.insert(Feature::Direction);
+					}
+					"@type" => {
+						if value.as_str() == Some("@json") {
+							// The value is a JSON literal, that must not be analyzed.
+							features.insert(Feature::JsonLiteral);
+							return;
+						}
+					}
+					key => {
+						match key {
+							"@included" => {
+								features.insert(Feature::Included);
+							}
+							"@nest" => {
+								features.insert(Feature::Nest);
+							}
+							_ => (),
+						}
+
+						document(&*value, features)
+					}
+				}
+			}
+		}
+		_ => (),
+	}
+}
+
+fn context<J: Json>(json: &J, features: &mut BTreeSet<Feature>) {
+	match json.as_value_ref() {
+		ValueRef::Array(ary) => {
+			for item in ary.iter() {
+				context(&*item, features)
+			}
+		}
+		ValueRef::Object(obj) => {
+			for (key, value) in obj.iter() {
+				match &**key {
+					"@direction" => {
+						features.insert(Feature::Direction);
+					}
+					"@propagate" => {
+						features.insert(Feature::Propagate);
+					}
+					"@protected" => {
+						features.insert(Feature::Protected);
+					}
+					"@import" => {
+						features.insert(Feature::Import);
+					}
+					"@base" | "@vocab" | "@language" | "@version" => (),
+					_ => term_definition(&*value, features),
+				}
+			}
+		}
+		_ => (),
+	}
+}
+
+fn term_definition<J: Json>(json: &J, features: &mut BTreeSet<Feature>) {
+	if let ValueRef::Object(obj) = json.as_value_ref() {
+		for (key, value) in obj.iter() {
+			match &**key {
+				"@context" => {
+					features.insert(Feature::ScopedContext);
+					context(&*value, features)
+				}
+				"@nest" => {
+					features.insert(Feature::Nest);
+				}
+				"@prefix" => {
+					features.insert(Feature::Prefix);
+				}
+				"@index" => {
+					features.insert(Feature::PropertyIndex);
+				}
+				"@protected" => {
+					features.insert(Feature::Protected);
+				}
+				"@direction" => {
+					features.insert(Feature::Direction);
+				}
+				"@type" => {
+					if value.as_str() == Some("@json") {
+						features.insert(Feature::JsonLiteral);
+					}
+				}
+				"@container" => match value.as_value_ref() {
+					ValueRef::Array(ary) => {
+						features.insert(Feature::ContainerArray);
+						for item in ary.iter() {
+							container(item.as_str(), features)
+						}
+					}
+					_ => container(value.as_str(), features),
+				},
+				_ => (),
+			}
+		}
+	}
+}
+
+fn container(container: Option<&str>, features: &mut BTreeSet<Feature>) {
+	let container = match container {
+		Some("@id") => ContainerType::Id,
+		Some("@type") => ContainerType::Type,
+		Some("@graph") => ContainerType::Graph,
+		_ => return,
+	};
+
+	container_type(container, features)
+}
+
+fn container_type(container: ContainerType, features: &mut BTreeSet<Feature>) {
+	match container {
+		ContainerType::Id => {
+			features.insert(Feature::IdMap);
+		}
+		ContainerType::Type => {
+			features.insert(Feature::TypeMap);
+		}
+		ContainerType::Graph => {
+			features.insert(Feature::GraphContainer);
+		}
+		_ => (),
+	}
+}
+
+impl ContextDefinition {
+	/// Returns the JSON-LD 1.1 features used by this context definition.
+	pub fn features(&self) -> BTreeSet<Feature> {
+		let mut features = BTreeSet::new();
+		self.collect_features(&mut features);
+		features
+	}
+
+	/// Checks if this context definition uses JSON-LD 1.1 features,
+	/// and should be serialized with a `@version` entry.
+	pub fn requires_json_ld_1_1(&self) -> bool {
+		!self.features().is_empty()
+	}
+
+	fn collect_features(&self, features: &mut BTreeSet<Feature>) {
+		if self.direction.is_some() {
+			features.insert(Feature::Direction);
+		}
+
+		if self.propagate.is_some() {
+			features.insert(Feature::Propagate);
+		}
+
+		if self.protected.is_some() {
+			features.insert(Feature::Protected);
+		}
+
+		for definition in self.terms().filter_map(|(_, definition)| definition) {
+			definition.collect_features(features)
+		}
+	}
+}
+
+impl ExpandedTermDefinition {
+	fn collect_features(&self, features: &mut BTreeSet<Feature>) {
+		if let Some(context) = &self.context {
+			features.insert(Feature::ScopedContext);
+			context.collect_features(features)
+		}
+
+		if self.nest.is_some() {
+			features.insert(Feature::Nest);
+		}
+
+		if self.prefix.is_some() {
+			features.insert(Feature::Prefix);
+		}
+
+		if self.index.is_some() {
+			features.insert(Feature::PropertyIndex);
+		}
+
+		if self.protected.is_some() {
+			features.insert(Feature::Protected);
+		}
+
+		if self.direction.is_some() {
+			features.insert(Feature::Direction);
+		}
+
+		if self.type_.as_deref() == Some("@json") {
+			features.insert(Feature::JsonLiteral);
+		}
+
+		if self.container.len() > 1 {
+			features.insert(Feature::ContainerArray);
+		}
+
+		for container in &self.container {
+			container_type(*container, features)
+		}
+	}
+}
@@ -0,0 +1,408 @@
+use super::{ContextDefinition, Keyword};
+#[cfg(not(feature = "std"))]
+use crate::prelude::*;
+use crate::util::AsAnyJson;
+use core::fmt;
+use generic_json::JsonBuild;
+
+/// Frame builder.
+///
+/// Programmatic representation of a
+/// [JSON-LD frame](https://www.w3.org/TR/json-ld11-framing/#frame-matching),
+/// that can be serialized into a frame document using the [`AsAnyJson`] trait.
+/// This crate does not implement the framing algorithm itself,
+/// but the resulting document can be given to any external framer.
+///
+/// Frames are built by matching on identifiers and types, and on the presence of properties,
+/// each property being matched against its own (nested) frame.
+/// The embedding policy and flags of a nested frame only apply to the values of its property.
+/// Properties are serialized in the order they have been added.
+/// The content of named graphs is matched by a `@graph` frame
+/// (see [`FrameBuilder::frame_graph`]).
+///
+/// ```
+/// use json_ld::{syntax::{Embed, FrameBuilder}, util::AsAnyJson};
+/// use serde_json::Value;
+///
+/// let frame = FrameBuilder::new()
+///   .with_type("http://schema.org/Book")
+///   .with_property(
+///     "http://schema.org/author",
+///     FrameBuilder::new().with_embed(Embed::Never),
+///   )
+///   .with_absent_property("http://schema.org/isbn");
+///
+/// let json: Value = frame.as_json();
+/// assert_eq!(json["@type"], "http://schema.org/Book");
+/// assert_eq!(json["http://schema.org/author"]["@embed"], "@never");
+/// assert_eq!(json["http://schema.org/isbn"], Value::Array(Vec::new()));
+/// ```
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct FrameBuilder {
+	/// `@context` entry.
+	pub context: Option<ContextDefinition>,
+
+	/// `@id` entry.
+	pub id: Option<FrameMatch>,
+
+	/// `@type` entry.
+	pub type_: Option<FrameMatch>,
+
+	/// `@embed` entry.
+	pub embed: Option<Embed>,
+
+	/// `@explicit` entry.
+	pub explicit: Option<bool>,
+
+	/// `@omitDefault` entry.
+	pub omit_default: Option<bool>,
+
+	/// `@requireAll` entry.
+	pub require_all: Option<bool>,
+
+	/// Property frames, in order. `None` stands for the match none pattern `[]`.
+	properties: Vec<(String, Option<FrameBuilder>)>,
+
+	/// Reverse property frames, in order. `None` stands for the match none pattern `[]`.
+	reverse_properties: Vec<(String, Option<FrameBuilder>)>,
+
+	/// `@graph` entry, matching the content of named graphs.
+	graph: Option<Box<FrameBuilder>>,
+}
+
+/// Pattern of the `@id` or `@type` entry of a frame.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum FrameMatch {
+	/// Wildcard `{}`, matching any value, as long as there is one.
+	Wildcard,
+
+	/// Match none `[]`, matching the absence of value.
+	None,
+
+	/// Matches any of the given IRIs (or compact IRIs, terms, blank node identifiers).
+	Values(Vec<String>),
+}
+
+impl FrameMatch {
+	fn push(&mut self, value: String) {
+		match self {
+			Self::Values(values) => values.push(value),
+			_ => *self = Self::Values(vec![value]),
+		}
+	}
+}
+
+impl<K: JsonBuild> AsAnyJson<K> for FrameMatch {
+	fn as_json_with(&self, meta: K::MetaData) -> K {
+		match self {
+			Self::Wildcard => K::object(core::iter::empty().collect(), meta),
+			Self::None => K::array(core::iter::empty().collect(), meta),
+			Self::Values(values) => match values.as_slice() {
+				[value] => value.as_json_with(meta),
+				values => K::array(
+					values
+						.iter()
+						.map(|value| value.as_json_with(meta.clone()))
+						.collect(),
+					meta,
+				),
+			},
+		}
+	}
+}
+
+/// Embedding policy (value of the `@embed` frame entry).
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Embed {
+	/// `@always`: always embed node objects as property values,
+	/// unless this would cause a circular reference.
+	Always,
+
+	/// `@once`: embed node objects the first time they are referenced,
+	/// using node references afterwards.
+	Once,
+
+	/// `@never`: always use node references.
+	Never,
+}
+
+impl Embed {
+	/// Returns the keyword representing this policy.
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			Self::Always => "@always",
+			Self::Once => "@once",
+			Self::Never => "@never",
+		}
+	}
+}
+
+impl fmt::Display for Embed {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		self.as_str().fmt(f)
+	}
+}
+
+impl<K: JsonBuild> AsAnyJson<K> for Embed {
+	fn as_json_with(&self, meta: K::MetaData) -> K {
+		self.as_str().as_json_with(meta)
+	}
+}
+
+impl FrameBuilder {
+	/// Creates a new empty frame, matching every node object.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Creates a frame matching the named graph with the given identifier,
+	/// whose content is framed with the given frame.
+	///
+	/// This frames the content of each named graph of a dataset independently,
+	/// for instance when named graphs are used to record provenance.
+	/// The named graph itself can also be extracted beforehand with
+	/// [`ExpandedDocument::graph`](crate::ExpandedDocument::graph)
+	/// and framed as a default graph.
+	///
+	/// ```
+	/// use json_ld::{syntax::FrameBuilder, util::AsAnyJson};
+	/// use serde_json::{json, Value};
+	///
+	/// let frame = FrameBuilder::frame_graph(
+	///   "http://example.org/graphs/1",
+	///   FrameBuilder::new().with_type("http://schema.org/Person"),
+	/// );
+	///
+	/// let json: Value = frame.as_json();
+	/// assert_eq!(json, json!({
+	///   "@id": "http://example.org/graphs/1",
+	///   "@graph": { "@type": "http://schema.org/Person" }
+	/// }));
+	/// ```
+	pub fn frame_graph(graph: impl Into<String>, frame: FrameBuilder) -> Self {
+		Self::new().with_id(graph).with_graph(frame)
+	}
+
+	/// Sets the `@context` entry.
+	#[must_use]
+	pub fn with_context(mut self, context: ContextDefinition) -> Self {
+		self.context = Some(context);
+		self
+	}
+
+	/// Adds the given identifier to the ones matched by the frame.
+	#[must_use]
+	pub fn with_id(mut self, id: impl Into<String>) -> Self {
+		self.id.get_or_insert(FrameMatch::Wildcard).push(id.into());
+		self
+	}
+
+	/// Adds the given type to the ones matched by the frame.
+	///
+	/// A node object matches if it has any of the types.
+	#[must_use]
+	pub fn with_type(mut self, ty: impl Into<String>) -> Self {
+		self.type_
+			.get_or_insert(FrameMatch::Wildcard)
+			.push(ty.into());
+		self
+	}
+
+	/// Only matches node objects having at least one type.
+	#[must_use]
+	pub fn with_any_type(mut self) -> Self {
+		self.type_ = Some(FrameMatch::Wildcard);
+		self
+	}
+
+	/// Only matches node objects without type.
+	#[must_use]
+	pub fn with_no_type(mut self) -> Self {
+		self.type_ = Some(FrameMatch::None);
+		self
+	}
+
+	/// Sets the `@embed` entry.
+	#[must_use]
+	pub fn with_embed(mut self, embed: Embed) -> Self {
+		self.embed = Some(embed);
+		self
+	}
+
+	/// Sets the `@explicit` entry.
+	#[must_use]
+	pub fn with_explicit(mut self, explicit: bool) -> Self {
+		self.explicit = Some(explicit);
+		self
+	}
+
+	/// Sets the `@omitDefault` entry.
+	#[must_use]
+	pub fn with_omit_default(mut self, omit_default: bool) -> Self {
+		self.omit_default = Some(omit_default);
+		self
+	}
+
+	/// Sets the `@requireAll` entry.
+	#[must_use]
+	pub fn with_require_all(mut self, require_all: bool) -> Self {
+		self.require_all = Some(require_all);
+		self
+	}
+
+	/// Adds (or replaces) the frame of the given property.
+	#[must_use]
+	pub fn with_property(mut self, property: impl Into<String>, frame: FrameBuilder) -> Self {
+		insert(&mut self.properties, property.into(), Some(frame));
+		self
+	}
+
+	/// Requires the given property to be present, with any value.
+	///
+	/// This is a shortcut for a property matched by an empty frame (wildcard).
+	#[must_use]
+	pub fn with_required_property(self, property: impl Into<String>) -> Self {
+		self.with_property(property, FrameBuilder::new())
+	}
+
+	/// Requires the given property to be absent.
+	#[must_use]
+	pub fn with_absent_property(mut self, property: impl Into<String>) -> Self {
+		insert(&mut self.properties, property.into(), None);
+		self
+	}
+
+	/// Adds (or replaces) the frame of the given reverse property.
+	#[must_use]
+	pub fn with_reverse_property(
+		mut self,
+		property: impl Into<String>,
+		frame: FrameBuilder,
+	) -> Self {
+		insert(&mut self.reverse_properties, property.into(), Some(frame));
+		self
+	}
+
+	/// Sets the `@graph` entry, matching the content of named graphs.
+	///
+	/// Only node objects that are named graphs and whose content
+	/// matches the given frame are matched.
+	#[must_use]
+	pub fn with_graph(mut self, frame: FrameBuilder) -> Self {
+		self.graph = Some(Box::new(frame));
+		self
+	}
+
+	/// Returns the frame of the `@graph` entry, if any.
+	pub fn graph(&self) -> Option<&FrameBuilder> {
+		self.graph.as_deref()
+	}
+
+	/// Returns the frame of the given property, if any.
+	///
+	/// Returns `Some(None)` if the property is required to be absent.
+	pub fn property(&self, property: &str) -> Option<Option<&FrameBuilder>> {
+		self.properties
+			.iter()
+			.find(|(p, _)| p == property)
+			.map(|(_, frame)| frame.as_ref())
+	}
+
+	/// Returns an iterator over the properties of the frame and their frame.
+	///
+	/// A property without frame is required to be absent.
+	pub fn properties(&self) -> impl Iterator<Item = (&str, Option<&FrameBuilder>)> {
+		self.properties
+			.iter()
+			.map(|(p, frame)| (p.as_str(), frame.as_ref()))
+	}
+
+	/// Returns an iterator over the reverse properties of the frame and their frame.
+	pub fn reverse_properties(&self) -> impl Iterator<Item = (&str, Option<&FrameBuilder>)> {
+		self.reverse_properties
+			.iter()
+			.map(|(p, frame)| (p.as_str(), frame.as_ref()))
+	}
+}
+
+fn insert(
+	properties: &mut Vec<(String, Option<FrameBuilder>)>,
+	property: String,
+	frame: Option<FrameBuilder>,
+) {
+	match properties.iter_mut().find(|(p, _)| *p == property) {
+		Some((_, f)) => *f = frame,
+		None => properties.push((property, frame)),
+	}
+}
+
+fn properties_as_json<K: JsonBuild>(
+	properties: &[(String, Option<FrameBuilder>)],
+	meta: &K::MetaData,
+) -> Vec<(K::Key, K)> {
+	properties
+		.iter()
+		.map(|(property, frame)| {
+			let value = match frame {
+				Some(frame) => frame.as_json_with(meta.clone()),
+				None => K::array(core::iter::empty().collect(), meta.clone()),
+			};
+
+			(K::new_key(property, meta.clone()), value)
+		})
+		.collect()
+}
+
+impl<K: JsonBuild> AsAnyJson<K> for FrameBuilder {
+	fn as_json_with(&self, meta: K::MetaData) -> K {
+		let mut entries = Vec::new();
+		let mut entry = |key: &str, value: K| entries.push((K::new_key(key, meta.clone()), value));
+
+		if let Some(context) = &self.context {
+			entry(
+				Keyword::Context.into_str(),
+				context.as_json_with(meta.clone()),
+			)
+		}
+
+		if let Some(id) = &self.id {
+			entry(Keyword::Id.into_str(), id.as_json_with(meta.clone()))
+		}
+
+		if let Some(ty) = &self.type_ {
+			entry(Keyword::Type.into_str(), ty.as_json_with(meta.clone()))
+		}
+
+		if let Some(embed) = self.embed {
+			entry("@embed", embed.as_json_with(meta.clone()))
+		}
+
+		if let Some(explicit) = self.explicit {
+			entry("@explicit", explicit.as_json_with(meta.clone()))
+		}
+
+		if let Some(omit_default) = self.omit_default {
+			entry("@omitDefault", omit_default.as_json_with(meta.clone()))
+		}
+
+		if let Some(require_all) = self.require_all {
+			entry("@requireAll", require_all.as_json_with(meta.clone()))
+		}
+
+		if let Some(graph) = &self.graph {
+			entry(Keyword::Graph.into_str(), graph.as_json_with(meta.clone()))
+		}
+
+		entries.extend(properties_as_json(&self.properties, &meta));
+
+		if !self.reverse_properties.is_empty() {
+			let reverse = properties_as_json(&self.reverse_properties, &meta);
+			entries.push((
+				K::new_key(Keyword::Reverse.into_str(), meta.clone()),
+				K::object(reverse.into_iter().collect(), meta.clone()),
+			))
+		}
+
+		K::object(entries.into_iter().collect(), meta)
+	}
+}
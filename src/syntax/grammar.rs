@@ -0,0 +1,226 @@
+use super::{is_keyword, is_keyword_like, Container, ContainerType};
+#[cfg(not(feature = "std"))]
+use crate::prelude::*;
+use crate::{util::as_array, ErrorCode};
+use cc_traits::{Get, MapIter};
+use core::convert::TryFrom;
+use generic_json::{Json, Key, ValueRef};
+
+/// Term definition grammar violation.
+///
+/// Produced by [`check_term_definitions`] for each structurally invalid
+/// entry of a local context.
+#[derive(Clone, Debug)]
+pub struct Violation<M> {
+	/// Defined term.
+	pub term: String,
+
+	/// Error that the create term definition algorithm would raise.
+	pub code: ErrorCode,
+
+	/// Metadata of the offending value.
+	pub metadata: M,
+}
+
+/// Checks every term definition of the given local context against the
+/// JSON-LD 1.1 term definition grammar.
+///
+/// This does not resolve any IRI nor load any remote context: only the
+/// structure of the definitions is checked.
+/// All the violations are collected in one pass, in document order.
+/// For each term, the first violation is the one that the create term definition
+/// algorithm would raise.
+pub fn check_term_definitions<J: Json>(local_context: &J) -> Vec<Violation<J::MetaData>> {
+	let mut violations = Vec::new();
+
+	let (local_context, _) = as_array(local_context);
+	for context in local_context {
+		if let ValueRef::Object(context) = context.as_value_ref() {
+			check_context_definition::<J>(context, &mut violations)
+		}
+	}
+
+	violations
+}
+
+/// Checks the term definitions of a single context definition.
+pub(crate) fn check_context_definition<J: Json>(
+	context: &J::Object,
+	violations: &mut Vec<Violation<J::MetaData>>,
+) {
+	for (key, value) in context.iter() {
+		let key_metadata = key.metadata();
+		let term: &str = key.as_ref();
+		match term {
+			"@base" | "@direction" | "@import" | "@language" | "@propagate" | "@protected"
+			| "@version" | "@vocab" => (),
+			_ => check_term_definition::<J>(term, key_metadata, &*value, violations),
+		}
+	}
+}
+
+fn check_term_definition<J: Json>(
+	term: &str,
+	term_metadata: &J::MetaData,
+	value: &J,
+	violations: &mut Vec<Violation<J::MetaData>>,
+) {
+	let mut violation = |code, metadata: &J::MetaData| {
+		violations.push(Violation {
+			term: term.to_string(),
+			code,
+			metadata: metadata.clone(),
+		})
+	};
+
+	if term.is_empty() {
+		violation(ErrorCode::InvalidTermDefinition, term_metadata);
+		return;
+	}
+
+	// Keywords and keyword-like terms are handled by the processing algorithm.
+	if is_keyword(term) || is_keyword_like(term) {
+		return;
+	}
+
+	let value = match value.as_value_ref() {
+		ValueRef::Null | ValueRef::String(_) => return,
+		ValueRef::Object(value) => value,
+		_ => {
+			violation(ErrorCode::InvalidTermDefinition, value.metadata());
+			return;
+		}
+	};
+
+	if let Some(protected_value) = value.get("@protected") {
+		if protected_value.as_bool().is_none() {
+			violation(ErrorCode::InvalidProtectedValue, protected_value.metadata())
+		}
+	}
+
+	if let Some(type_value) = value.get("@type") {
+		if type_value.as_str().is_none() {
+			violation(ErrorCode::InvalidTypeMapping, type_value.metadata())
+		}
+	}
+
+	if let Some(reverse_value) = value.get("@reverse") {
+		if value.get("@id").is_some() || value.get("@nest").is_some() {
+			violation(ErrorCode::InvalidReverseProperty, reverse_value.metadata());
+			return;
+		}
+
+		match reverse_value.as_str() {
+			Some(reverse) if is_keyword_like(reverse) => (),
+			Some(_) => {
+				if let Some(container_value) = value.get("@container") {
+					let valid = match container_value.as_value_ref() {
+						ValueRef::Null => true,
+						ValueRef::String(c) => matches!(
+							ContainerType::try_from(c.as_ref()),
+							Ok(ContainerType::Set) | Ok(ContainerType::Index)
+						),
+						_ => false,
+					};
+
+					if !valid {
+						violation(
+							ErrorCode::InvalidReverseProperty,
+							container_value.metadata(),
+						)
+					}
+				}
+			}
+			None => violation(ErrorCode::InvalidIriMapping, reverse_value.metadata()),
+		}
+
+		// Reverse term definitions ignore any other entry.
+		return;
+	}
+
+	if let Some(id_value) = value.get("@id") {
+		match id_value.as_value_ref() {
+			ValueRef::Null => (),
+			ValueRef::String(id) => {
+				let id: &str = id.as_ref();
+				if is_keyword_like(id) && !is_keyword(id) {
+					// Ignored by the processing algorithm, with a warning.
+					return;
+				}
+			}
+			_ => violation(ErrorCode::InvalidIriMapping, id_value.metadata()),
+		}
+	}
+
+	let mut container = Container::None;
+	if let Some(container_value) = value.get("@container") {
+		let (entries, _) = as_array(&*container_value);
+		for entry in entries {
+			let valid = match entry.as_str() {
+				Some(entry) => match ContainerType::try_from(entry) {
+					Ok(c) => container.add(c),
+					Err(_) => false,
+				},
+				None => false,
+			};
+
+			if !valid {
+				violation(ErrorCode::InvalidContainerMapping, entry.metadata());
+				break;
+			}
+		}
+	}
+
+	if let Some(index_value) = value.get("@index") {
+		if !container.contains(ContainerType::Index) || index_value.as_str().is_none() {
+			violation(ErrorCode::InvalidTermDefinition, index_value.metadata())
+		}
+	}
+
+	if let Some(context_value) = value.get("@context") {
+		match context_value.as_value_ref() {
+			ValueRef::Null | ValueRef::String(_) | ValueRef::Object(_) | ValueRef::Array(_) => (),
+			_ => violation(ErrorCode::InvalidScopedContext, context_value.metadata()),
+		}
+	}
+
+	if value.get("@type").is_none() {
+		if let Some(language_value) = value.get("@language") {
+			match language_value.as_value_ref() {
+				ValueRef::Null | ValueRef::String(_) => (),
+				_ => violation(ErrorCode::InvalidLanguageMapping, language_value.metadata()),
+			}
+		}
+
+		if let Some(direction_value) = value.get("@direction") {
+			match direction_value.as_value_ref() {
+				ValueRef::Null => (),
+				ValueRef::String(dir) if dir.as_ref() == "ltr" || dir.as_ref() == "rtl" => (),
+				_ => violation(ErrorCode::InvalidBaseDirection, direction_value.metadata()),
+			}
+		}
+	}
+
+	if let Some(nest_value) = value.get("@nest") {
+		match nest_value.as_str() {
+			Some(nest) if !is_keyword(nest) || nest == "@nest" => (),
+			_ => violation(ErrorCode::InvalidNestValue, nest_value.metadata()),
+		}
+	}
+
+	if let Some(prefix_value) = value.get("@prefix") {
+		if term.contains(':') || term.contains('/') {
+			violation(ErrorCode::InvalidTermDefinition, term_metadata)
+		} else if prefix_value.as_bool().is_none() {
+			violation(ErrorCode::InvalidPrefixValue, prefix_value.metadata())
+		}
+	}
+
+	for (key, _) in value.iter() {
+		match key.as_ref() {
+			"@id" | "@reverse" | "@container" | "@context" | "@direction" | "@index"
+			| "@language" | "@nest" | "@prefix" | "@protected" | "@type" => (),
+			_ => violation(ErrorCode::InvalidTermDefinition, key.metadata()),
+		}
+	}
+}
@@ -0,0 +1,406 @@
+//! Migration of JSON-LD 1.0 contexts to JSON-LD 1.1.
+use super::{
+	definition::ends_with_gen_delim, is_keyword, is_keyword_like, ContainerType, ContextDefinition,
+	ExpandedTermDefinition,
+};
+#[cfg(not(feature = "std"))]
+use crate::prelude::*;
+use crate::util::collections::HashSet;
+use crate::{util::as_array, Direction};
+use cc_traits::MapIter;
+use core::convert::TryFrom;
+use core::fmt;
+use generic_json::{Json, Key, ValueRef};
+
+/// Migration options.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct MigrationOptions {
+	/// Protects every term definition of the migrated context with `@protected`.
+	///
+	/// Default is `false`.
+	pub protected: bool,
+}
+
+/// Construct that could not be migrated.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum MigrationIssue {
+	/// Remote context reference. It is dropped and must be migrated separately.
+	RemoteContext(String),
+
+	/// Entry or value that cannot be represented by a [`ContextDefinition`]. It is dropped.
+	Unsupported(String),
+
+	/// The term is a keyword or has the form of a keyword.
+	///
+	/// JSON-LD 1.1 processors ignore (or reject) such terms. It is dropped.
+	KeywordTerm(String),
+
+	/// The term definition is invalid. It is dropped.
+	InvalidDefinition(String),
+
+	/// The term is used as a prefix but cannot be flagged with `@prefix`
+	/// since it contains a `:` or a `/`, or maps to a keyword.
+	///
+	/// JSON-LD 1.1 processors will not use it as a prefix anymore.
+	IllegalPrefix(String),
+
+	/// The term is a compact IRI or an IRI that does not expand to its own IRI mapping,
+	/// which is forbidden by JSON-LD 1.1.
+	CompactIriMismatch { term: String, iri: String },
+}
+
+impl fmt::Display for MigrationIssue {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Self::RemoteContext(iri) => write!(f, "remote context `{}` is not migrated", iri),
+			Self::Unsupported(key) => write!(f, "`{}` cannot be migrated", key),
+			Self::KeywordTerm(term) => {
+				write!(f, "term `{}` is or has the form of a keyword", term)
+			}
+			Self::InvalidDefinition(term) => write!(f, "invalid definition for term `{}`", term),
+			Self::IllegalPrefix(term) => write!(f, "term `{}` cannot be used as a prefix", term),
+			Self::CompactIriMismatch { term, iri } => {
+				write!(f, "term `{}` does not expand to `{}`", term, iri)
+			}
+		}
+	}
+}
+
+/// Migration issue located in the context document.
+#[derive(Clone, Debug)]
+pub struct Unmigrated<M> {
+	/// Issue.
+	pub issue: MigrationIssue,
+
+	/// Metadata of the offending entry.
+	pub metadata: M,
+}
+
+/// Migrated context.
+#[derive(Clone, Debug)]
+pub struct Migration<M> {
+	/// JSON-LD 1.1 context definition.
+	pub context: ContextDefinition,
+
+	/// Constructs that could not be migrated, in document order.
+	pub issues: Vec<Unmigrated<M>>,
+}
+
+/// Rewrites a JSON-LD 1.0 context into an equivalent JSON-LD 1.1 context definition.
+///
+/// The context may be a context definition or an array of context definitions,
+/// merged in order.
+/// Terms used as prefixes that would no longer be considered as prefixes by a
+/// JSON-LD 1.1 processor (because their IRI does not end with a gen-delim character, or
+/// because they have an expanded definition) are flagged with `@prefix`.
+/// With the [`protected`](MigrationOptions::protected) option, the context is protected.
+///
+/// Constructs that cannot be migrated are dropped and reported.
+/// Issues related to prefixes are located at the context document itself.
+///
+/// ```
+/// use json_ld::syntax::{migrate, MigrationOptions};
+/// use serde_json::Value;
+///
+/// let context: Value = serde_json::from_str(r#"
+///   {
+///     "ex": "http://example.com/vocab#term-",
+///     "name": "ex:name"
+///   }
+/// "#).unwrap();
+///
+/// let migration = migrate(&context, MigrationOptions::default());
+/// assert!(migration.issues.is_empty());
+/// assert_eq!(migration.context.get("ex").unwrap().unwrap().prefix, Some(true))
+/// ```
+pub fn migrate<J: Json>(context: &J, options: MigrationOptions) -> Migration<J::MetaData> {
+	let mut migration = Migration {
+		context: ContextDefinition::new(),
+		issues: Vec::new(),
+	};
+
+	let (contexts, _) = as_array(context);
+	for context in contexts {
+		let definition = parse_context(&*context, &mut migration.issues);
+		migration.context.base = definition.base.or(migration.context.base.take());
+		migration.context.vocab = definition.vocab.or(migration.context.vocab.take());
+		migration.context.language = definition.language.or(migration.context.language.take());
+		migration.context.direction = definition.direction.or(migration.context.direction);
+		migration.context.propagate = definition.propagate.or(migration.context.propagate);
+		migration.context.protected = definition.protected.or(migration.context.protected);
+		for (term, definition) in definition.terms() {
+			migration
+				.context
+				.insert(term.to_string(), definition.cloned())
+		}
+	}
+
+	flag_prefixes(&mut migration, context.metadata());
+
+	if options.protected {
+		migration.context.protected = Some(true)
+	}
+
+	migration
+}
+
+/// Reports an issue.
+fn report<M: Clone>(issues: &mut Vec<Unmigrated<M>>, issue: MigrationIssue, metadata: &M) {
+	issues.push(Unmigrated {
+		issue,
+		metadata: metadata.clone(),
+	})
+}
+
+/// Parses a context definition.
+fn parse_context<J: Json>(
+	context: &J,
+	issues: &mut Vec<Unmigrated<J::MetaData>>,
+) -> ContextDefinition {
+	let mut result = ContextDefinition::new();
+
+	match context.as_value_ref() {
+		ValueRef::Null => report(
+			issues,
+			MigrationIssue::Unsupported("null".to_string()),
+			context.metadata(),
+		),
+		ValueRef::String(iri) => report(
+			issues,
+			MigrationIssue::RemoteContext(iri.as_ref().to_string()),
+			context.metadata(),
+		),
+		ValueRef::Object(context) => {
+			for (key, value) in context.iter() {
+				let key_str: &str = key.as_ref();
+				let value = &*value;
+				let invalid = || MigrationIssue::Unsupported(key_str.to_string());
+				match key_str {
+					"@base" => match nullable_str(value) {
+						Some(base) => result.base = Some(base),
+						None => report(issues, invalid(), key.metadata()),
+					},
+					"@vocab" => match nullable_str(value) {
+						Some(vocab) => result.vocab = Some(vocab),
+						None => report(issues, invalid(), key.metadata()),
+					},
+					"@language" => match nullable_str(value) {
+						Some(language) => result.language = Some(language),
+						None => report(issues, invalid(), key.metadata()),
+					},
+					"@direction" => match nullable_direction(value) {
+						Some(direction) => result.direction = Some(direction),
+						None => report(issues, invalid(), key.metadata()),
+					},
+					"@propagate" => match value.as_bool() {
+						Some(propagate) => result.propagate = Some(propagate),
+						None => report(issues, invalid(), key.metadata()),
+					},
+					"@protected" => match value.as_bool() {
+						Some(protected) => result.protected = Some(protected),
+						None => report(issues, invalid(), key.metadata()),
+					},
+					"@version" => (),
+					_ if is_keyword(key_str) || is_keyword_like(key_str) => {
+						let issue = if key_str == "@import" {
+							invalid()
+						} else {
+							MigrationIssue::KeywordTerm(key_str.to_string())
+						};
+
+						report(issues, issue, key.metadata())
+					}
+					term => match parse_definition(value, issues) {
+						Ok(definition) => result.insert(term.to_string(), definition),
+						Err(()) => report(
+							issues,
+							MigrationIssue::InvalidDefinition(term.to_string()),
+							key.metadata(),
+						),
+					},
+				}
+			}
+		}
+		_ => report(
+			issues,
+			MigrationIssue::Unsupported("context".to_string()),
+			context.metadata(),
+		),
+	}
+
+	result
+}
+
+/// Parses a term definition.
+///
+/// A definition without IRI mapping (`{ "@id": null }`) is returned as a `null` definition.
+fn parse_definition<J: Json>(
+	value: &J,
+	issues: &mut Vec<Unmigrated<J::MetaData>>,
+) -> Result<Option<ExpandedTermDefinition>, ()> {
+	match value.as_value_ref() {
+		ValueRef::Null => Ok(None),
+		ValueRef::String(id) => {
+			let id: &str = id.as_ref();
+			Ok(Some(ExpandedTermDefinition::new(id)))
+		}
+		ValueRef::Object(entries) => {
+			let mut definition = ExpandedTermDefinition::default();
+
+			for (key, value) in entries.iter() {
+				let value = &*value;
+				let key: &str = key.as_ref();
+				let string = || value.as_str().map(ToString::to_string).ok_or(());
+				match key {
+					"@id" => match value.as_value_ref() {
+						ValueRef::Null => return Ok(None),
+						_ => definition.id = Some(string()?),
+					},
+					"@reverse" => definition.reverse = Some(string()?),
+					"@type" => definition.type_ = Some(string()?),
+					"@language" => definition.language = Some(nullable_str(value).ok_or(())?),
+					"@direction" => {
+						definition.direction = Some(nullable_direction(value).ok_or(())?)
+					}
+					"@container" => {
+						let (containers, _) = as_array(value);
+						for container in containers {
+							let container = container.as_str().ok_or(())?;
+							definition
+								.container
+								.push(ContainerType::try_from(container).map_err(|_| ())?)
+						}
+					}
+					"@context" => definition.context = Some(Box::new(parse_context(value, issues))),
+					"@index" => definition.index = Some(string()?),
+					"@nest" => definition.nest = Some(string()?),
+					"@prefix" => definition.prefix = Some(value.as_bool().ok_or(())?),
+					"@protected" => definition.protected = Some(value.as_bool().ok_or(())?),
+					_ => return Err(()),
+				}
+			}
+
+			Ok(Some(definition))
+		}
+		_ => Err(()),
+	}
+}
+
+/// Parses a string or `null`.
+fn nullable_str<J: Json>(value: &J) -> Option<Option<String>> {
+	match value.as_value_ref() {
+		ValueRef::Null => Some(None),
+		ValueRef::String(s) => Some(Some(s.as_ref().to_string())),
+		_ => None,
+	}
+}
+
+/// Parses a direction or `null`.
+fn nullable_direction<J: Json>(value: &J) -> Option<Option<Direction>> {
+	match value.as_value_ref() {
+		ValueRef::Null => Some(None),
+		ValueRef::String(s) => {
+			let s: &str = s.as_ref();
+			Direction::try_from(s).ok().map(Some)
+		}
+		_ => None,
+	}
+}
+
+/// Splits a compact IRI into its prefix and suffix.
+///
+/// Returns `None` for blank node identifiers and absolute IRIs with an authority.
+fn split_compact_iri(value: &str) -> Option<(&str, &str)> {
+	let i = value.find(':')?;
+	let (prefix, suffix) = (&value[..i], &value[(i + 1)..]);
+	if prefix == "_" || suffix.starts_with("//") {
+		None
+	} else {
+		Some((prefix, suffix))
+	}
+}
+
+/// Flags the terms used as prefixes with `@prefix`,
+/// and reports the terms whose expansion would differ from their IRI mapping.
+fn flag_prefixes<M: Clone>(migration: &mut Migration<M>, metadata: &M) {
+	let context = &migration.context;
+	let prefix_iri = |prefix: &str| match context.get(prefix) {
+		Some(Some(definition)) => definition.id.as_deref(),
+		_ => None,
+	};
+
+	let mut prefixes = HashSet::new();
+	let mut mismatches = Vec::new();
+	for (term, definition) in context.terms() {
+		let definition = match definition {
+			Some(definition) => definition,
+			None => continue,
+		};
+
+		let values = [
+			definition.id.as_deref(),
+			definition.reverse.as_deref(),
+			definition.type_.as_deref(),
+		];
+		for value in values.iter().flatten() {
+			if let Some((prefix, _)) = split_compact_iri(value) {
+				if prefix != term && prefix_iri(prefix).is_some() {
+					prefixes.insert(prefix.to_string());
+				}
+			}
+		}
+
+		if let Some((prefix, suffix)) = split_compact_iri(term) {
+			if let (Some(prefix_iri), Some(id)) = (prefix_iri(prefix), &definition.id) {
+				prefixes.insert(prefix.to_string());
+
+				let expected = format!("{}{}", prefix_iri, suffix);
+				let iri = match split_compact_iri(id) {
+					Some((p, s)) if p != term => match context.get(p) {
+						Some(Some(ExpandedTermDefinition {
+							id: Some(p_iri), ..
+						})) => {
+							format!("{}{}", p_iri, s)
+						}
+						_ => id.clone(),
+					},
+					_ => id.clone(),
+				};
+
+				if iri != expected {
+					mismatches.push(MigrationIssue::CompactIriMismatch {
+						term: term.to_string(),
+						iri: expected,
+					})
+				}
+			}
+		}
+	}
+
+	for issue in mismatches {
+		report(&mut migration.issues, issue, metadata)
+	}
+
+	let mut prefixes: Vec<_> = prefixes.into_iter().collect();
+	prefixes.sort_unstable();
+	for prefix in prefixes {
+		if let Some(Some(definition)) = migration.context.get(&prefix) {
+			let id = definition.id.as_deref().unwrap_or_default();
+			if (definition.is_simple() && ends_with_gen_delim(id)) || definition.prefix.is_some() {
+				continue;
+			}
+
+			let mut definition = definition.clone();
+			if prefix.contains(|c| c == ':' || c == '/') || is_keyword(id) {
+				report(
+					&mut migration.issues,
+					MigrationIssue::IllegalPrefix(prefix),
+					metadata,
+				)
+			} else {
+				definition.prefix = Some(true);
+				migration.context.insert(prefix, Some(definition))
+			}
+		}
+	}
+}
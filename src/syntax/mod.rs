@@ -1,11 +1,21 @@
 //! Syntax elements.
 
 mod container;
+mod definition;
+mod features;
+mod frame;
+mod grammar;
 mod keyword;
+mod migration;
 mod term;
 mod typ;
 
 pub use container::*;
+pub use definition::*;
+pub use features::*;
+pub use frame::*;
+pub use grammar::*;
 pub use keyword::*;
+pub use migration::*;
 pub use term::*;
 pub use typ::*;
@@ -1,8 +1,8 @@
 use super::Keyword;
 use crate::{util::AsAnyJson, BlankId, Reference};
+use core::fmt;
 use generic_json::JsonBuild;
 use iref::{AsIri, Iri};
-use std::fmt;
 
 pub trait TermLike {
 	fn as_iri(&self) -> Option<Iri>;
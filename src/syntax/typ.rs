@@ -1,9 +1,9 @@
 use super::{Keyword, Term, TermLike};
 use crate::{util, Id, Reference};
+use core::convert::TryFrom;
+use core::fmt;
 use generic_json::JsonBuild;
 use iref::Iri;
-use std::convert::TryFrom;
-use std::fmt;
 
 #[derive(Clone, PartialEq, Eq, Hash)]
 pub enum Type<T> {
@@ -0,0 +1,46 @@
+//! Hash-based collections.
+//!
+//! These are the collections of the standard library,
+//! or the ones of [`hashbrown`](https://crates.io/crates/hashbrown)
+//! if the `hashbrown` feature is enabled (required without the `std` feature).
+//! Every hash set and map of the object model (graphs, included nodes, node maps, etc.)
+//! uses these types.
+#[cfg(feature = "hashbrown")]
+pub use hashbrown::{hash_map, hash_set, HashMap, HashSet};
+#[cfg(not(feature = "hashbrown"))]
+pub use std::collections::{hash_map, hash_set, HashMap, HashSet};
+
+#[cfg(feature = "std")]
+pub(crate) use std::collections::hash_map::DefaultHasher;
+
+/// Deterministic hasher used to combine the hashes of unordered collections
+/// when the standard library is not available.
+#[cfg(not(feature = "std"))]
+#[allow(deprecated)]
+pub(crate) struct DefaultHasher(core::hash::SipHasher);
+
+#[cfg(not(feature = "std"))]
+impl DefaultHasher {
+	#[allow(deprecated)]
+	pub fn new() -> Self {
+		Self(core::hash::SipHasher::new())
+	}
+}
+
+#[cfg(not(feature = "std"))]
+impl Default for DefaultHasher {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+#[cfg(not(feature = "std"))]
+impl core::hash::Hasher for DefaultHasher {
+	fn write(&mut self, bytes: &[u8]) {
+		self.0.write(bytes)
+	}
+
+	fn finish(&self) -> u64 {
+		self.0.finish()
+	}
+}
@@ -0,0 +1,50 @@
+//! Boxed futures.
+//!
+//! The processing algorithms and loaders return boxed futures.
+//! These futures are `Send`, unless the `local-futures` feature is enabled
+//! (it is enabled by the `wasm` feature).
+//! In this case they are not required to be `Send`, so that loaders can await
+//! non-`Send` futures (such as JavaScript promises),
+//! and must be polled on the thread they were created on.
+//!
+//! Custom [`Loader`](crate::Loader) implementations should use these types
+//! to work with and without the `local-futures` feature.
+use std::future::Future;
+
+#[cfg(not(feature = "local-futures"))]
+pub use futures::future::BoxFuture;
+
+#[cfg(feature = "local-futures")]
+pub use futures::future::LocalBoxFuture as BoxFuture;
+
+/// Extension trait to turn futures into [`BoxFuture`]s.
+pub trait FutureExt: Future {
+	/// Wraps the future in a box, pinning it.
+	///
+	/// Without the `local-futures` feature, the future must be `Send`.
+	fn boxed<'a>(self) -> BoxFuture<'a, Self::Output>
+	where
+		Self: Sized + 'a;
+}
+
+#[cfg(not(feature = "local-futures"))]
+impl<F: Future + Send> FutureExt for F {
+	#[inline(always)]
+	fn boxed<'a>(self) -> BoxFuture<'a, F::Output>
+	where
+		Self: 'a,
+	{
+		Box::pin(self)
+	}
+}
+
+#[cfg(feature = "local-futures")]
+impl<F: Future> FutureExt for F {
+	#[inline(always)]
+	fn boxed<'a>(self) -> BoxFuture<'a, F::Output>
+	where
+		Self: 'a,
+	{
+		Box::pin(self)
+	}
+}
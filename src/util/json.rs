@@ -1,9 +1,11 @@
-use generic_json::{Json, JsonHash, ValueRef};
-use std::{
-	collections::hash_map::DefaultHasher,
+use super::collections::DefaultHasher;
+#[cfg(not(feature = "std"))]
+use crate::prelude::*;
+use core::{
 	hash::{Hash, Hasher},
 	ops::Deref,
 };
+use generic_json::{Json, JsonHash, ValueRef};
 
 mod build;
 
@@ -32,7 +34,7 @@ impl<'a, J: Json> Deref for AsArrayItem<'a, J> {
 /// converted into an array.
 ///
 /// Each item is referenced through the [`AsArrayItem`] type
-/// that [`Deref`](std::ops::Deref) into `J`.
+/// that [`Deref`](core::ops::Deref) into `J`.
 pub enum AsArray<'a, J: Json> {
 	/// The value is not an array,
 	/// and is hence interpreted as an array with one single element.
@@ -1,3 +1,5 @@
+#[cfg(not(feature = "std"))]
+use crate::prelude::*;
 use cc_traits::{Get, Iter, Len, MapIter};
 use generic_json::{
 	Json, JsonBuild, JsonClone, JsonIntoMut, JsonMutSendSync, Key, Value, ValueRef,
@@ -5,8 +7,11 @@ use generic_json::{
 use langtag::{LanguageTag, LanguageTagBuf};
 
 /// JSON value that can be converted from a `J` value.
+///
+/// Integers can also be converted into `Self` numbers,
+/// which is required to compact typed literals into native numbers.
 pub trait JsonFrom<J: Json> = JsonMutSendSync + JsonBuild + JsonIntoMut
-where <Self as Json>::Number: From<<J as Json>::Number>;
+where <Self as Json>::Number: From<<J as Json>::Number> + From<i64>;
 
 /// Type composed of `J` JSON values that can be converted
 /// into a `K` JSON value.
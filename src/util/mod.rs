@@ -1,10 +1,17 @@
 //! Utility functions.
-use std::collections::{hash_map::DefaultHasher, HashMap, HashSet};
-use std::hash::{Hash, Hasher};
+use core::hash::{Hash, Hasher};
 
+pub mod collections;
+#[cfg(feature = "std")]
+pub mod future;
 mod json;
 pub use self::json::*;
 
+use self::collections::{DefaultHasher, HashMap, HashSet};
+
+pub(crate) mod small_map;
+pub(crate) use self::small_map::SmallMap;
+
 /// Hash a [`HashSet`].
 ///
 /// The standard library does not provide (yet) a `Hash` implementation
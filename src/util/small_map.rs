@@ -0,0 +1,367 @@
+#[cfg(not(feature = "std"))]
+use crate::prelude::*;
+use crate::util::collections::DefaultHasher;
+use core::hash::{Hash, Hasher};
+use core::iter::FromIterator;
+
+#[cfg(not(feature = "ordered"))]
+use crate::util::collections::{hash_map as map, HashMap as Map};
+#[cfg(feature = "ordered")]
+use indexmap::{map, IndexMap as Map};
+
+/// Maximum number of entries stored inline by a [`SmallMap`].
+const INLINE_CAPACITY: usize = 8;
+
+/// Map storing its entries inline, in a vector, while it is small,
+/// and switching to a hash map once it grows over [`INLINE_CAPACITY`] entries.
+///
+/// Most node objects only have a handful of properties,
+/// for which a linear search in a single allocation is cheaper than hashing.
+/// Inline entries are kept in insertion order.
+/// With the `ordered` feature, the hash map is an `IndexMap`,
+/// so that the insertion order is always preserved.
+#[derive(Clone)]
+pub(crate) enum SmallMap<K, V> {
+	Inline(Vec<(K, V)>),
+	Hashed(Map<K, V>),
+}
+
+impl<K, V> Default for SmallMap<K, V> {
+	#[inline(always)]
+	fn default() -> Self {
+		Self::Inline(Vec::new())
+	}
+}
+
+impl<K: Eq + Hash, V> SmallMap<K, V> {
+	/// Creates an empty map.
+	#[inline(always)]
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	#[inline(always)]
+	pub fn len(&self) -> usize {
+		match self {
+			Self::Inline(entries) => entries.len(),
+			Self::Hashed(map) => map.len(),
+		}
+	}
+
+	#[inline(always)]
+	pub fn is_empty(&self) -> bool {
+		self.len() == 0
+	}
+
+	pub fn get(&self, key: &K) -> Option<&V> {
+		match self {
+			Self::Inline(entries) => entries.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+			Self::Hashed(map) => map.get(key),
+		}
+	}
+
+	pub fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+		match self {
+			Self::Inline(entries) => entries.iter_mut().find(|(k, _)| k == key).map(|(_, v)| v),
+			Self::Hashed(map) => map.get_mut(key),
+		}
+	}
+
+	/// Switches to the hashed representation if inserting `key`
+	/// would exceed the inline capacity.
+	fn reserve(&mut self, key: &K) {
+		if let Self::Inline(entries) = self {
+			if entries.len() >= INLINE_CAPACITY && !entries.iter().any(|(k, _)| k == key) {
+				let map = core::mem::take(entries).into_iter().collect();
+				*self = Self::Hashed(map)
+			}
+		}
+	}
+
+	/// Returns a mutable reference to the value associated to the given key,
+	/// inserting the value returned by `f` first if there is none.
+	pub fn get_or_insert_with(&mut self, key: K, f: impl FnOnce() -> V) -> &mut V {
+		self.reserve(&key);
+		match self {
+			Self::Inline(entries) => {
+				let i = match entries.iter().position(|(k, _)| *k == key) {
+					Some(i) => i,
+					None => {
+						entries.push((key, f()));
+						entries.len() - 1
+					}
+				};
+
+				&mut entries[i].1
+			}
+			Self::Hashed(map) => map.entry(key).or_insert_with(f),
+		}
+	}
+
+	/// Inserts the given entry, returning the value previously associated to the key, if any.
+	pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+		match self.get_mut(&key) {
+			Some(v) => Some(core::mem::replace(v, value)),
+			None => {
+				self.reserve(&key);
+				match self {
+					Self::Inline(entries) => entries.push((key, value)),
+					Self::Hashed(map) => {
+						map.insert(key, value);
+					}
+				}
+
+				None
+			}
+		}
+	}
+
+	/// Removes the given key, returning its associated value, if any.
+	///
+	/// The order of the remaining entries is preserved.
+	pub fn remove(&mut self, key: &K) -> Option<V> {
+		match self {
+			Self::Inline(entries) => {
+				let i = entries.iter().position(|(k, _)| k == key)?;
+				Some(entries.remove(i).1)
+			}
+			#[cfg(feature = "ordered")]
+			Self::Hashed(map) => map.shift_remove(key),
+			#[cfg(not(feature = "ordered"))]
+			Self::Hashed(map) => map.remove(key),
+		}
+	}
+
+	/// Retains only the entries for which `f` returns `true`.
+	pub fn retain(&mut self, mut f: impl FnMut(&K, &mut V) -> bool) {
+		match self {
+			Self::Inline(entries) => {
+				let mut i = 0;
+				while i < entries.len() {
+					let (k, v) = &mut entries[i];
+					if f(k, v) {
+						i += 1
+					} else {
+						entries.remove(i);
+					}
+				}
+			}
+			Self::Hashed(map) => map.retain(f),
+		}
+	}
+
+	#[inline(always)]
+	pub fn iter(&self) -> Iter<K, V> {
+		match self {
+			Self::Inline(entries) => Iter::Inline(entries.iter()),
+			Self::Hashed(map) => Iter::Hashed(map.iter()),
+		}
+	}
+
+	#[inline(always)]
+	pub fn iter_mut(&mut self) -> IterMut<K, V> {
+		match self {
+			Self::Inline(entries) => IterMut::Inline(entries.iter_mut()),
+			Self::Hashed(map) => IterMut::Hashed(map.iter_mut()),
+		}
+	}
+}
+
+impl<K: Eq + Hash, V: PartialEq> PartialEq for SmallMap<K, V> {
+	fn eq(&self, other: &Self) -> bool {
+		self.len() == other.len() && self.iter().all(|(k, v)| other.get(k) == Some(v))
+	}
+}
+
+impl<K: Eq + Hash, V: Eq> Eq for SmallMap<K, V> {}
+
+impl<K: Eq + Hash, V: Hash> Hash for SmallMap<K, V> {
+	fn hash<H: Hasher>(&self, hasher: &mut H) {
+		// Same as `util::hash_map`: the hash must not depend on the order of the entries.
+		let mut hash = 0;
+		for entry in self.iter() {
+			let mut h = DefaultHasher::new();
+			entry.hash(&mut h);
+			hash = u64::wrapping_add(hash, h.finish());
+		}
+
+		hasher.write_u64(hash);
+	}
+}
+
+impl<K: Eq + Hash, V> FromIterator<(K, V)> for SmallMap<K, V> {
+	fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+		let mut map = Self::new();
+		for (key, value) in iter {
+			map.insert(key, value);
+		}
+
+		map
+	}
+}
+
+impl<K, V> IntoIterator for SmallMap<K, V> {
+	type Item = (K, V);
+	type IntoIter = IntoIter<K, V>;
+
+	#[inline(always)]
+	fn into_iter(self) -> Self::IntoIter {
+		match self {
+			Self::Inline(entries) => IntoIter::Inline(entries.into_iter()),
+			Self::Hashed(map) => IntoIter::Hashed(map.into_iter()),
+		}
+	}
+}
+
+pub(crate) enum Iter<'a, K, V> {
+	Inline(core::slice::Iter<'a, (K, V)>),
+	Hashed(map::Iter<'a, K, V>),
+}
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+	type Item = (&'a K, &'a V);
+
+	#[inline(always)]
+	fn size_hint(&self) -> (usize, Option<usize>) {
+		match self {
+			Self::Inline(inner) => inner.size_hint(),
+			Self::Hashed(inner) => inner.size_hint(),
+		}
+	}
+
+	#[inline(always)]
+	fn next(&mut self) -> Option<Self::Item> {
+		match self {
+			Self::Inline(inner) => inner.next().map(|(k, v)| (k, v)),
+			Self::Hashed(inner) => inner.next(),
+		}
+	}
+}
+
+impl<'a, K, V> ExactSizeIterator for Iter<'a, K, V> {}
+
+impl<'a, K, V> core::iter::FusedIterator for Iter<'a, K, V> {}
+
+pub(crate) enum IterMut<'a, K, V> {
+	Inline(core::slice::IterMut<'a, (K, V)>),
+	Hashed(map::IterMut<'a, K, V>),
+}
+
+impl<'a, K, V> Iterator for IterMut<'a, K, V> {
+	type Item = (&'a K, &'a mut V);
+
+	#[inline(always)]
+	fn size_hint(&self) -> (usize, Option<usize>) {
+		match self {
+			Self::Inline(inner) => inner.size_hint(),
+			Self::Hashed(inner) => inner.size_hint(),
+		}
+	}
+
+	#[inline(always)]
+	fn next(&mut self) -> Option<Self::Item> {
+		match self {
+			Self::Inline(inner) => inner.next().map(|(k, v)| (&*k, v)),
+			Self::Hashed(inner) => inner.next(),
+		}
+	}
+}
+
+impl<'a, K, V> ExactSizeIterator for IterMut<'a, K, V> {}
+
+impl<'a, K, V> core::iter::FusedIterator for IterMut<'a, K, V> {}
+
+pub(crate) enum IntoIter<K, V> {
+	Inline(alloc::vec::IntoIter<(K, V)>),
+	Hashed(map::IntoIter<K, V>),
+}
+
+impl<K, V> Iterator for IntoIter<K, V> {
+	type Item = (K, V);
+
+	#[inline(always)]
+	fn size_hint(&self) -> (usize, Option<usize>) {
+		match self {
+			Self::Inline(inner) => inner.size_hint(),
+			Self::Hashed(inner) => inner.size_hint(),
+		}
+	}
+
+	#[inline(always)]
+	fn next(&mut self) -> Option<Self::Item> {
+		match self {
+			Self::Inline(inner) => inner.next(),
+			Self::Hashed(inner) => inner.next(),
+		}
+	}
+}
+
+impl<K, V> ExactSizeIterator for IntoIter<K, V> {}
+
+impl<K, V> core::iter::FusedIterator for IntoIter<K, V> {}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn keys(map: &SmallMap<usize, usize>) -> Vec<usize> {
+		map.iter().map(|(k, _)| *k).collect()
+	}
+
+	#[test]
+	fn inline_until_capacity() {
+		let mut map: SmallMap<usize, usize> = (0..INLINE_CAPACITY).rev().map(|i| (i, i)).collect();
+		assert!(matches!(map, SmallMap::Inline(_)));
+		assert_eq!(keys(&map), (0..INLINE_CAPACITY).rev().collect::<Vec<_>>());
+
+		// Replacing or updating an existing entry does not spill.
+		assert_eq!(map.insert(0, 1), Some(0));
+		*map.get_or_insert_with(1, || 0) += 1;
+		assert!(matches!(map, SmallMap::Inline(_)));
+		assert_eq!(map.get(&0), Some(&1));
+		assert_eq!(map.get(&1), Some(&2));
+
+		// Removing keeps the order of the other entries.
+		assert_eq!(map.remove(&3), Some(3));
+		let mut expected: Vec<_> = (0..INLINE_CAPACITY).rev().collect();
+		expected.retain(|&k| k != 3);
+		assert_eq!(keys(&map), expected);
+	}
+
+	#[test]
+	fn spill_over_capacity() {
+		let mut map: SmallMap<usize, usize> = (0..INLINE_CAPACITY).map(|i| (i, i)).collect();
+		assert_eq!(map.insert(INLINE_CAPACITY, INLINE_CAPACITY), None);
+		assert!(matches!(map, SmallMap::Hashed(_)));
+		assert_eq!(map.len(), INLINE_CAPACITY + 1);
+		assert!((0..=INLINE_CAPACITY).all(|i| map.get(&i) == Some(&i)));
+
+		let mut map: SmallMap<usize, usize> = (0..INLINE_CAPACITY).map(|i| (i, i)).collect();
+		*map.get_or_insert_with(INLINE_CAPACITY, || 0) += 1;
+		assert!(matches!(map, SmallMap::Hashed(_)));
+		assert_eq!(map.get(&INLINE_CAPACITY), Some(&1));
+
+		#[cfg(feature = "ordered")]
+		{
+			assert_eq!(keys(&map), (0..=INLINE_CAPACITY).collect::<Vec<_>>());
+			assert_eq!(map.remove(&2), Some(2));
+			let mut expected: Vec<_> = (0..=INLINE_CAPACITY).collect();
+			expected.remove(2);
+			assert_eq!(keys(&map), expected);
+		}
+	}
+
+	#[test]
+	fn representation_independent_eq() {
+		let hashed: SmallMap<usize, usize> = (0..=INLINE_CAPACITY).map(|i| (i, i)).collect();
+		// Shrinking does not move the entries back inline.
+		let mut shrunk = hashed.clone();
+		shrunk.retain(|k, _| *k < INLINE_CAPACITY);
+		assert!(matches!(shrunk, SmallMap::Hashed(_)));
+
+		let small: SmallMap<usize, usize> = (0..INLINE_CAPACITY).rev().map(|i| (i, i)).collect();
+		assert!(matches!(small, SmallMap::Inline(_)));
+		assert!(shrunk == small);
+		assert!(hashed != small);
+	}
+}
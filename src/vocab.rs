@@ -1,8 +1,8 @@
 use crate::{Id, Reference, ToReference};
+use core::convert::TryFrom;
+use core::fmt;
+use core::hash::Hash;
 use iref::{AsIri, Iri, IriBuf};
-use std::convert::TryFrom;
-use std::fmt;
-use std::hash::Hash;
 
 /// Vocabulary type.
 ///
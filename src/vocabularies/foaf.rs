@@ -0,0 +1,138 @@
+use iref_enum::IriEnum;
+
+/// [FOAF](http://xmlns.com/foaf/spec/) vocabulary (`foaf:` prefix).
+#[derive(IriEnum, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[iri_prefix("foaf" = "http://xmlns.com/foaf/0.1/")]
+pub enum Foaf {
+	#[iri("foaf:Agent")]
+	Agent,
+
+	#[iri("foaf:Person")]
+	Person,
+
+	#[iri("foaf:Organization")]
+	Organization,
+
+	#[iri("foaf:Group")]
+	Group,
+
+	#[iri("foaf:Document")]
+	Document,
+
+	#[iri("foaf:Image")]
+	Image,
+
+	#[iri("foaf:OnlineAccount")]
+	OnlineAccount,
+
+	#[iri("foaf:Project")]
+	Project,
+
+	#[iri("foaf:name")]
+	Name,
+
+	#[iri("foaf:title")]
+	Title,
+
+	#[iri("foaf:nick")]
+	Nick,
+
+	#[iri("foaf:givenName")]
+	GivenName,
+
+	#[iri("foaf:familyName")]
+	FamilyName,
+
+	#[iri("foaf:firstName")]
+	FirstName,
+
+	#[iri("foaf:lastName")]
+	LastName,
+
+	#[iri("foaf:mbox")]
+	Mbox,
+
+	#[iri("foaf:mbox_sha1sum")]
+	MboxSha1Sum,
+
+	#[iri("foaf:homepage")]
+	Homepage,
+
+	#[iri("foaf:weblog")]
+	Weblog,
+
+	#[iri("foaf:img")]
+	Img,
+
+	#[iri("foaf:depiction")]
+	Depiction,
+
+	#[iri("foaf:depicts")]
+	Depicts,
+
+	#[iri("foaf:knows")]
+	Knows,
+
+	#[iri("foaf:member")]
+	Member,
+
+	#[iri("foaf:account")]
+	Account,
+
+	#[iri("foaf:accountName")]
+	AccountName,
+
+	#[iri("foaf:accountServiceHomepage")]
+	AccountServiceHomepage,
+
+	#[iri("foaf:age")]
+	Age,
+
+	#[iri("foaf:birthday")]
+	Birthday,
+
+	#[iri("foaf:gender")]
+	Gender,
+
+	#[iri("foaf:based_near")]
+	BasedNear,
+
+	#[iri("foaf:topic")]
+	Topic,
+
+	#[iri("foaf:primaryTopic")]
+	PrimaryTopic,
+
+	#[iri("foaf:isPrimaryTopicOf")]
+	IsPrimaryTopicOf,
+
+	#[iri("foaf:maker")]
+	Maker,
+
+	#[iri("foaf:made")]
+	Made,
+
+	#[iri("foaf:logo")]
+	Logo,
+
+	#[iri("foaf:page")]
+	Page,
+
+	#[iri("foaf:interest")]
+	Interest,
+
+	#[iri("foaf:topic_interest")]
+	TopicInterest,
+
+	#[iri("foaf:currentProject")]
+	CurrentProject,
+
+	#[iri("foaf:pastProject")]
+	PastProject,
+
+	#[iri("foaf:phone")]
+	Phone,
+
+	#[iri("foaf:thumbnail")]
+	Thumbnail,
+}
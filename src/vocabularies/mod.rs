@@ -0,0 +1,182 @@
+//! Common vocabularies.
+//!
+//! This module, enabled by the `vocabularies` feature, provides [`Vocab`](crate::Vocab)
+//! types for the terms of well-known vocabularies,
+//! so that they do not have to be written by hand to build a [`Lexicon`].
+//! Each vocabulary can be used on its own, or through the [`Common`] vocabulary
+//! gathering all of them.
+//! The terms of each vocabulary can directly be used as [`CommonId`] references.
+//!
+//! ```
+//! use json_ld::vocabularies::{CommonId, Schema};
+//! # use serde_json::Value;
+//!
+//! fn person_name(node: &json_ld::Node<Value, CommonId>) -> Option<&str> {
+//!   node.get_any(Schema::Name)?.as_str()
+//! }
+//! ```
+use crate::{Lexicon, Reference, ToReference};
+use iref::{AsIri, Iri};
+use std::convert::TryFrom;
+
+mod foaf;
+mod rdf;
+mod rdfs;
+mod schema;
+mod skos;
+mod xsd;
+
+pub use foaf::*;
+pub use rdf::*;
+pub use rdfs::*;
+pub use schema::*;
+pub use skos::*;
+pub use xsd::*;
+
+/// Union of the vocabularies of this module.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Common {
+	Rdf(Rdf),
+	Rdfs(Rdfs),
+	Xsd(Xsd),
+	Skos(Skos),
+	Foaf(Foaf),
+	Schema(Schema),
+}
+
+/// Identifier type built from the [`Common`] vocabulary.
+pub type CommonId = Lexicon<Common>;
+
+impl AsIri for Common {
+	fn as_iri(&self) -> Iri {
+		match self {
+			Self::Rdf(t) => t.as_iri(),
+			Self::Rdfs(t) => t.as_iri(),
+			Self::Xsd(t) => t.as_iri(),
+			Self::Skos(t) => t.as_iri(),
+			Self::Foaf(t) => t.as_iri(),
+			Self::Schema(t) => t.as_iri(),
+		}
+	}
+}
+
+impl<'a> TryFrom<Iri<'a>> for Common {
+	type Error = Iri<'a>;
+
+	fn try_from(iri: Iri<'a>) -> Result<Self, Iri<'a>> {
+		if let Ok(t) = Rdf::try_from(iri) {
+			return Ok(Self::Rdf(t));
+		}
+
+		if let Ok(t) = Rdfs::try_from(iri) {
+			return Ok(Self::Rdfs(t));
+		}
+
+		if let Ok(t) = Xsd::try_from(iri) {
+			return Ok(Self::Xsd(t));
+		}
+
+		if let Ok(t) = Skos::try_from(iri) {
+			return Ok(Self::Skos(t));
+		}
+
+		if let Ok(t) = Foaf::try_from(iri) {
+			return Ok(Self::Foaf(t));
+		}
+
+		match Schema::try_from(iri) {
+			Ok(t) => Ok(Self::Schema(t)),
+			Err(_) => Err(iri),
+		}
+	}
+}
+
+impl From<Rdf> for Common {
+	fn from(t: Rdf) -> Self {
+		Self::Rdf(t)
+	}
+}
+
+impl From<Rdfs> for Common {
+	fn from(t: Rdfs) -> Self {
+		Self::Rdfs(t)
+	}
+}
+
+impl From<Xsd> for Common {
+	fn from(t: Xsd) -> Self {
+		Self::Xsd(t)
+	}
+}
+
+impl From<Skos> for Common {
+	fn from(t: Skos) -> Self {
+		Self::Skos(t)
+	}
+}
+
+impl From<Foaf> for Common {
+	fn from(t: Foaf) -> Self {
+		Self::Foaf(t)
+	}
+}
+
+impl From<Schema> for Common {
+	fn from(t: Schema) -> Self {
+		Self::Schema(t)
+	}
+}
+
+impl ToReference<CommonId> for Rdf {
+	type Reference = Reference<CommonId>;
+
+	#[inline(always)]
+	fn to_ref(&self) -> Self::Reference {
+		Reference::Id(Lexicon::Id(Common::Rdf(*self)))
+	}
+}
+
+impl ToReference<CommonId> for Rdfs {
+	type Reference = Reference<CommonId>;
+
+	#[inline(always)]
+	fn to_ref(&self) -> Self::Reference {
+		Reference::Id(Lexicon::Id(Common::Rdfs(*self)))
+	}
+}
+
+impl ToReference<CommonId> for Xsd {
+	type Reference = Reference<CommonId>;
+
+	#[inline(always)]
+	fn to_ref(&self) -> Self::Reference {
+		Reference::Id(Lexicon::Id(Common::Xsd(*self)))
+	}
+}
+
+impl ToReference<CommonId> for Skos {
+	type Reference = Reference<CommonId>;
+
+	#[inline(always)]
+	fn to_ref(&self) -> Self::Reference {
+		Reference::Id(Lexicon::Id(Common::Skos(*self)))
+	}
+}
+
+impl ToReference<CommonId> for Foaf {
+	type Reference = Reference<CommonId>;
+
+	#[inline(always)]
+	fn to_ref(&self) -> Self::Reference {
+		Reference::Id(Lexicon::Id(Common::Foaf(*self)))
+	}
+}
+
+impl ToReference<CommonId> for Schema {
+	type Reference = Reference<CommonId>;
+
+	#[inline(always)]
+	fn to_ref(&self) -> Self::Reference {
+		Reference::Id(Lexicon::Id(Common::Schema(*self)))
+	}
+}
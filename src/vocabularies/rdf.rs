@@ -0,0 +1,69 @@
+use iref_enum::IriEnum;
+
+/// [RDF](https://www.w3.org/TR/rdf11-schema/) vocabulary (`rdf:` prefix).
+#[derive(IriEnum, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[iri_prefix("rdf" = "http://www.w3.org/1999/02/22-rdf-syntax-ns#")]
+pub enum Rdf {
+	#[iri("rdf:type")]
+	Type,
+
+	#[iri("rdf:Property")]
+	Property,
+
+	#[iri("rdf:Statement")]
+	Statement,
+
+	#[iri("rdf:subject")]
+	Subject,
+
+	#[iri("rdf:predicate")]
+	Predicate,
+
+	#[iri("rdf:object")]
+	Object,
+
+	#[iri("rdf:value")]
+	Value,
+
+	#[iri("rdf:List")]
+	List,
+
+	#[iri("rdf:first")]
+	First,
+
+	#[iri("rdf:rest")]
+	Rest,
+
+	#[iri("rdf:nil")]
+	Nil,
+
+	#[iri("rdf:langString")]
+	LangString,
+
+	#[iri("rdf:HTML")]
+	Html,
+
+	#[iri("rdf:XMLLiteral")]
+	XmlLiteral,
+
+	#[iri("rdf:JSON")]
+	Json,
+
+	#[iri("rdf:CompoundLiteral")]
+	CompoundLiteral,
+
+	#[iri("rdf:language")]
+	Language,
+
+	#[iri("rdf:direction")]
+	Direction,
+
+	#[iri("rdf:Alt")]
+	Alt,
+
+	#[iri("rdf:Bag")]
+	Bag,
+
+	#[iri("rdf:Seq")]
+	Seq,
+}
@@ -0,0 +1,51 @@
+use iref_enum::IriEnum;
+
+/// [RDF Schema](https://www.w3.org/TR/rdf11-schema/) vocabulary (`rdfs:` prefix).
+#[derive(IriEnum, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[iri_prefix("rdfs" = "http://www.w3.org/2000/01/rdf-schema#")]
+pub enum Rdfs {
+	#[iri("rdfs:Resource")]
+	Resource,
+
+	#[iri("rdfs:Class")]
+	Class,
+
+	#[iri("rdfs:Literal")]
+	Literal,
+
+	#[iri("rdfs:Datatype")]
+	Datatype,
+
+	#[iri("rdfs:Container")]
+	Container,
+
+	#[iri("rdfs:ContainerMembershipProperty")]
+	ContainerMembershipProperty,
+
+	#[iri("rdfs:subClassOf")]
+	SubClassOf,
+
+	#[iri("rdfs:subPropertyOf")]
+	SubPropertyOf,
+
+	#[iri("rdfs:domain")]
+	Domain,
+
+	#[iri("rdfs:range")]
+	Range,
+
+	#[iri("rdfs:label")]
+	Label,
+
+	#[iri("rdfs:comment")]
+	Comment,
+
+	#[iri("rdfs:member")]
+	Member,
+
+	#[iri("rdfs:seeAlso")]
+	SeeAlso,
+
+	#[iri("rdfs:isDefinedBy")]
+	IsDefinedBy,
+}
@@ -0,0 +1,411 @@
+use iref_enum::IriEnum;
+
+/// [schema.org](https://schema.org/) vocabulary (`schema:` prefix).
+///
+/// Properties sharing their name with a type are suffixed with `Property`
+/// (e.g. [`BrandProperty`](Schema::BrandProperty) for `schema:brand`).
+#[derive(IriEnum, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[iri_prefix("schema" = "http://schema.org/")]
+pub enum Schema {
+	#[iri("schema:Thing")]
+	Thing,
+
+	#[iri("schema:Person")]
+	Person,
+
+	#[iri("schema:Organization")]
+	Organization,
+
+	#[iri("schema:Corporation")]
+	Corporation,
+
+	#[iri("schema:LocalBusiness")]
+	LocalBusiness,
+
+	#[iri("schema:Restaurant")]
+	Restaurant,
+
+	#[iri("schema:Place")]
+	Place,
+
+	#[iri("schema:PostalAddress")]
+	PostalAddress,
+
+	#[iri("schema:GeoCoordinates")]
+	GeoCoordinates,
+
+	#[iri("schema:Country")]
+	Country,
+
+	#[iri("schema:City")]
+	City,
+
+	#[iri("schema:Event")]
+	Event,
+
+	#[iri("schema:CreativeWork")]
+	CreativeWork,
+
+	#[iri("schema:Article")]
+	Article,
+
+	#[iri("schema:NewsArticle")]
+	NewsArticle,
+
+	#[iri("schema:BlogPosting")]
+	BlogPosting,
+
+	#[iri("schema:Book")]
+	Book,
+
+	#[iri("schema:WebPage")]
+	WebPage,
+
+	#[iri("schema:WebSite")]
+	WebSite,
+
+	#[iri("schema:ImageObject")]
+	ImageObject,
+
+	#[iri("schema:VideoObject")]
+	VideoObject,
+
+	#[iri("schema:AudioObject")]
+	AudioObject,
+
+	#[iri("schema:MediaObject")]
+	MediaObject,
+
+	#[iri("schema:Dataset")]
+	Dataset,
+
+	#[iri("schema:SoftwareApplication")]
+	SoftwareApplication,
+
+	#[iri("schema:Movie")]
+	Movie,
+
+	#[iri("schema:MusicRecording")]
+	MusicRecording,
+
+	#[iri("schema:Recipe")]
+	Recipe,
+
+	#[iri("schema:Product")]
+	Product,
+
+	#[iri("schema:Offer")]
+	Offer,
+
+	#[iri("schema:Brand")]
+	Brand,
+
+	#[iri("schema:Review")]
+	Review,
+
+	#[iri("schema:Rating")]
+	Rating,
+
+	#[iri("schema:AggregateRating")]
+	AggregateRating,
+
+	#[iri("schema:ContactPoint")]
+	ContactPoint,
+
+	#[iri("schema:ItemList")]
+	ItemList,
+
+	#[iri("schema:ListItem")]
+	ListItem,
+
+	#[iri("schema:BreadcrumbList")]
+	BreadcrumbList,
+
+	#[iri("schema:Text")]
+	Text,
+
+	#[iri("schema:Number")]
+	Number,
+
+	#[iri("schema:Integer")]
+	Integer,
+
+	#[iri("schema:Boolean")]
+	Boolean,
+
+	#[iri("schema:Date")]
+	Date,
+
+	#[iri("schema:DateTime")]
+	DateTime,
+
+	#[iri("schema:Time")]
+	Time,
+
+	#[iri("schema:URL")]
+	Url,
+
+	#[iri("schema:name")]
+	Name,
+
+	#[iri("schema:alternateName")]
+	AlternateName,
+
+	#[iri("schema:description")]
+	Description,
+
+	#[iri("schema:disambiguatingDescription")]
+	DisambiguatingDescription,
+
+	#[iri("schema:url")]
+	Url,
+
+	#[iri("schema:image")]
+	Image,
+
+	#[iri("schema:identifier")]
+	Identifier,
+
+	#[iri("schema:sameAs")]
+	SameAs,
+
+	#[iri("schema:additionalType")]
+	AdditionalType,
+
+	#[iri("schema:author")]
+	Author,
+
+	#[iri("schema:creator")]
+	Creator,
+
+	#[iri("schema:contributor")]
+	Contributor,
+
+	#[iri("schema:publisher")]
+	Publisher,
+
+	#[iri("schema:editor")]
+	Editor,
+
+	#[iri("schema:datePublished")]
+	DatePublished,
+
+	#[iri("schema:dateCreated")]
+	DateCreated,
+
+	#[iri("schema:dateModified")]
+	DateModified,
+
+	#[iri("schema:headline")]
+	Headline,
+
+	#[iri("schema:keywords")]
+	Keywords,
+
+	#[iri("schema:about")]
+	About,
+
+	#[iri("schema:mainEntity")]
+	MainEntity,
+
+	#[iri("schema:mainEntityOfPage")]
+	MainEntityOfPage,
+
+	#[iri("schema:inLanguage")]
+	InLanguage,
+
+	#[iri("schema:license")]
+	License,
+
+	#[iri("schema:isPartOf")]
+	IsPartOf,
+
+	#[iri("schema:hasPart")]
+	HasPart,
+
+	#[iri("schema:text")]
+	TextProperty,
+
+	#[iri("schema:articleBody")]
+	ArticleBody,
+
+	#[iri("schema:email")]
+	Email,
+
+	#[iri("schema:telephone")]
+	Telephone,
+
+	#[iri("schema:faxNumber")]
+	FaxNumber,
+
+	#[iri("schema:address")]
+	Address,
+
+	#[iri("schema:streetAddress")]
+	StreetAddress,
+
+	#[iri("schema:addressLocality")]
+	AddressLocality,
+
+	#[iri("schema:addressRegion")]
+	AddressRegion,
+
+	#[iri("schema:postalCode")]
+	PostalCode,
+
+	#[iri("schema:addressCountry")]
+	AddressCountry,
+
+	#[iri("schema:givenName")]
+	GivenName,
+
+	#[iri("schema:familyName")]
+	FamilyName,
+
+	#[iri("schema:additionalName")]
+	AdditionalName,
+
+	#[iri("schema:birthDate")]
+	BirthDate,
+
+	#[iri("schema:deathDate")]
+	DeathDate,
+
+	#[iri("schema:gender")]
+	Gender,
+
+	#[iri("schema:jobTitle")]
+	JobTitle,
+
+	#[iri("schema:worksFor")]
+	WorksFor,
+
+	#[iri("schema:memberOf")]
+	MemberOf,
+
+	#[iri("schema:member")]
+	Member,
+
+	#[iri("schema:knows")]
+	Knows,
+
+	#[iri("schema:nationality")]
+	Nationality,
+
+	#[iri("schema:location")]
+	Location,
+
+	#[iri("schema:geo")]
+	Geo,
+
+	#[iri("schema:latitude")]
+	Latitude,
+
+	#[iri("schema:longitude")]
+	Longitude,
+
+	#[iri("schema:startDate")]
+	StartDate,
+
+	#[iri("schema:endDate")]
+	EndDate,
+
+	#[iri("schema:organizer")]
+	Organizer,
+
+	#[iri("schema:performer")]
+	Performer,
+
+	#[iri("schema:attendee")]
+	Attendee,
+
+	#[iri("schema:price")]
+	Price,
+
+	#[iri("schema:priceCurrency")]
+	PriceCurrency,
+
+	#[iri("schema:availability")]
+	Availability,
+
+	#[iri("schema:offers")]
+	Offers,
+
+	#[iri("schema:brand")]
+	BrandProperty,
+
+	#[iri("schema:sku")]
+	Sku,
+
+	#[iri("schema:gtin")]
+	Gtin,
+
+	#[iri("schema:review")]
+	ReviewProperty,
+
+	#[iri("schema:reviewRating")]
+	ReviewRating,
+
+	#[iri("schema:reviewBody")]
+	ReviewBody,
+
+	#[iri("schema:ratingValue")]
+	RatingValue,
+
+	#[iri("schema:bestRating")]
+	BestRating,
+
+	#[iri("schema:worstRating")]
+	WorstRating,
+
+	#[iri("schema:aggregateRating")]
+	AggregateRatingProperty,
+
+	#[iri("schema:reviewCount")]
+	ReviewCount,
+
+	#[iri("schema:logo")]
+	Logo,
+
+	#[iri("schema:contactPoint")]
+	ContactPointProperty,
+
+	#[iri("schema:contactType")]
+	ContactType,
+
+	#[iri("schema:encodingFormat")]
+	EncodingFormat,
+
+	#[iri("schema:contentUrl")]
+	ContentUrl,
+
+	#[iri("schema:embedUrl")]
+	EmbedUrl,
+
+	#[iri("schema:thumbnailUrl")]
+	ThumbnailUrl,
+
+	#[iri("schema:width")]
+	Width,
+
+	#[iri("schema:height")]
+	Height,
+
+	#[iri("schema:duration")]
+	Duration,
+
+	#[iri("schema:uploadDate")]
+	UploadDate,
+
+	#[iri("schema:itemListElement")]
+	ItemListElement,
+
+	#[iri("schema:position")]
+	Position,
+
+	#[iri("schema:item")]
+	Item,
+
+	#[iri("schema:numberOfItems")]
+	NumberOfItems,
+}
@@ -0,0 +1,102 @@
+use iref_enum::IriEnum;
+
+/// [SKOS](https://www.w3.org/TR/skos-reference/) vocabulary (`skos:` prefix).
+#[derive(IriEnum, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[iri_prefix("skos" = "http://www.w3.org/2004/02/skos/core#")]
+pub enum Skos {
+	#[iri("skos:Concept")]
+	Concept,
+
+	#[iri("skos:ConceptScheme")]
+	ConceptScheme,
+
+	#[iri("skos:Collection")]
+	Collection,
+
+	#[iri("skos:OrderedCollection")]
+	OrderedCollection,
+
+	#[iri("skos:inScheme")]
+	InScheme,
+
+	#[iri("skos:hasTopConcept")]
+	HasTopConcept,
+
+	#[iri("skos:topConceptOf")]
+	TopConceptOf,
+
+	#[iri("skos:prefLabel")]
+	PrefLabel,
+
+	#[iri("skos:altLabel")]
+	AltLabel,
+
+	#[iri("skos:hiddenLabel")]
+	HiddenLabel,
+
+	#[iri("skos:notation")]
+	Notation,
+
+	#[iri("skos:note")]
+	Note,
+
+	#[iri("skos:changeNote")]
+	ChangeNote,
+
+	#[iri("skos:definition")]
+	Definition,
+
+	#[iri("skos:editorialNote")]
+	EditorialNote,
+
+	#[iri("skos:example")]
+	Example,
+
+	#[iri("skos:historyNote")]
+	HistoryNote,
+
+	#[iri("skos:scopeNote")]
+	ScopeNote,
+
+	#[iri("skos:semanticRelation")]
+	SemanticRelation,
+
+	#[iri("skos:broader")]
+	Broader,
+
+	#[iri("skos:narrower")]
+	Narrower,
+
+	#[iri("skos:related")]
+	Related,
+
+	#[iri("skos:broaderTransitive")]
+	BroaderTransitive,
+
+	#[iri("skos:narrowerTransitive")]
+	NarrowerTransitive,
+
+	#[iri("skos:member")]
+	Member,
+
+	#[iri("skos:memberList")]
+	MemberList,
+
+	#[iri("skos:mappingRelation")]
+	MappingRelation,
+
+	#[iri("skos:closeMatch")]
+	CloseMatch,
+
+	#[iri("skos:exactMatch")]
+	ExactMatch,
+
+	#[iri("skos:broadMatch")]
+	BroadMatch,
+
+	#[iri("skos:narrowMatch")]
+	NarrowMatch,
+
+	#[iri("skos:relatedMatch")]
+	RelatedMatch,
+}
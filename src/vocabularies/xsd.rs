@@ -0,0 +1,152 @@
+use crate::object::value::xsd::Datatype;
+use iref_enum::IriEnum;
+
+/// [XML Schema datatypes](https://www.w3.org/TR/xmlschema11-2/) vocabulary (`xsd:` prefix).
+#[derive(IriEnum, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[iri_prefix("xsd" = "http://www.w3.org/2001/XMLSchema#")]
+pub enum Xsd {
+	#[iri("xsd:string")]
+	String,
+
+	#[iri("xsd:boolean")]
+	Boolean,
+
+	#[iri("xsd:decimal")]
+	Decimal,
+
+	#[iri("xsd:integer")]
+	Integer,
+
+	#[iri("xsd:double")]
+	Double,
+
+	#[iri("xsd:float")]
+	Float,
+
+	#[iri("xsd:date")]
+	Date,
+
+	#[iri("xsd:time")]
+	Time,
+
+	#[iri("xsd:dateTime")]
+	DateTime,
+
+	#[iri("xsd:dateTimeStamp")]
+	DateTimeStamp,
+
+	#[iri("xsd:duration")]
+	Duration,
+
+	#[iri("xsd:dayTimeDuration")]
+	DayTimeDuration,
+
+	#[iri("xsd:yearMonthDuration")]
+	YearMonthDuration,
+
+	#[iri("xsd:gYear")]
+	GYear,
+
+	#[iri("xsd:gYearMonth")]
+	GYearMonth,
+
+	#[iri("xsd:gMonth")]
+	GMonth,
+
+	#[iri("xsd:gMonthDay")]
+	GMonthDay,
+
+	#[iri("xsd:gDay")]
+	GDay,
+
+	#[iri("xsd:hexBinary")]
+	HexBinary,
+
+	#[iri("xsd:base64Binary")]
+	Base64Binary,
+
+	#[iri("xsd:anyURI")]
+	AnyUri,
+
+	#[iri("xsd:language")]
+	Language,
+
+	#[iri("xsd:normalizedString")]
+	NormalizedString,
+
+	#[iri("xsd:token")]
+	Token,
+
+	#[iri("xsd:NMTOKEN")]
+	Nmtoken,
+
+	#[iri("xsd:Name")]
+	Name,
+
+	#[iri("xsd:NCName")]
+	NcName,
+
+	#[iri("xsd:long")]
+	Long,
+
+	#[iri("xsd:int")]
+	Int,
+
+	#[iri("xsd:short")]
+	Short,
+
+	#[iri("xsd:byte")]
+	Byte,
+
+	#[iri("xsd:nonNegativeInteger")]
+	NonNegativeInteger,
+
+	#[iri("xsd:positiveInteger")]
+	PositiveInteger,
+
+	#[iri("xsd:nonPositiveInteger")]
+	NonPositiveInteger,
+
+	#[iri("xsd:negativeInteger")]
+	NegativeInteger,
+
+	#[iri("xsd:unsignedLong")]
+	UnsignedLong,
+
+	#[iri("xsd:unsignedInt")]
+	UnsignedInt,
+
+	#[iri("xsd:unsignedShort")]
+	UnsignedShort,
+
+	#[iri("xsd:unsignedByte")]
+	UnsignedByte,
+}
+
+impl From<Datatype> for Xsd {
+	fn from(datatype: Datatype) -> Self {
+		match datatype {
+			Datatype::Boolean => Self::Boolean,
+			Datatype::Integer => Self::Integer,
+			Datatype::Long => Self::Long,
+			Datatype::Int => Self::Int,
+			Datatype::Short => Self::Short,
+			Datatype::Byte => Self::Byte,
+			Datatype::NonNegativeInteger => Self::NonNegativeInteger,
+			Datatype::PositiveInteger => Self::PositiveInteger,
+			Datatype::NonPositiveInteger => Self::NonPositiveInteger,
+			Datatype::NegativeInteger => Self::NegativeInteger,
+			Datatype::UnsignedLong => Self::UnsignedLong,
+			Datatype::UnsignedInt => Self::UnsignedInt,
+			Datatype::UnsignedShort => Self::UnsignedShort,
+			Datatype::UnsignedByte => Self::UnsignedByte,
+			Datatype::Decimal => Self::Decimal,
+			Datatype::Double => Self::Double,
+			Datatype::Float => Self::Float,
+			Datatype::String => Self::String,
+			Datatype::Date => Self::Date,
+			Datatype::DateTime => Self::DateTime,
+			Datatype::Duration => Self::Duration,
+		}
+	}
+}
@@ -1,4 +1,5 @@
-use crate::BlankId;
+use crate::{BlankId, ErrorCode};
+use std::convert::TryFrom;
 use std::fmt;
 
 /// Warning that can occur during JSON-LD documents processing.
@@ -27,6 +28,34 @@ pub enum Warning {
 
 	/// String literal is not an IRI.
 	MalformedIri(String),
+
+	/// Entry dropped because of a recoverable error.
+	///
+	/// Only emitted by the expansion algorithm in lenient mode.
+	DroppedEntry(ErrorCode),
+
+	/// Statement dropped during the conversion into RDF
+	/// because its predicate is a blank node identifier.
+	///
+	/// Only emitted when generalized RDF is not produced
+	/// (see [`rdf::Options`](crate::rdf::Options)).
+	BlankNodePredicate(BlankId),
+}
+
+impl Warning {
+	/// Returns the code of this warning.
+	pub fn code(&self) -> WarningCode {
+		match self {
+			Self::EmptyTerm => WarningCode::EmptyTerm,
+			Self::BlankNodeIdProperty(_) => WarningCode::BlankNodeIdProperty,
+			Self::KeywordLikeTerm(_) => WarningCode::KeywordLikeTerm,
+			Self::KeywordLikeValue(_) => WarningCode::KeywordLikeValue,
+			Self::MalformedLanguageTag(_, _) => WarningCode::MalformedLanguageTag,
+			Self::MalformedIri(_) => WarningCode::MalformedIri,
+			Self::DroppedEntry(_) => WarningCode::DroppedEntry,
+			Self::BlankNodePredicate(_) => WarningCode::BlankNodePredicate,
+		}
+	}
 }
 
 impl fmt::Display for Warning {
@@ -44,6 +73,107 @@ impl fmt::Display for Warning {
 				write!(f, "malformed language tag `{}`: {}", tag, e)
 			}
 			Self::MalformedIri(value) => write!(f, "invalid IRI `{}`", value),
+			Self::DroppedEntry(code) => write!(f, "dropped entry: {}", code.as_str()),
+			Self::BlankNodePredicate(id) => {
+				write!(f, "dropped statement with blank node predicate `{}`", id)
+			}
 		}
 	}
 }
+
+/// Serializes the warning as an object with its `code` and `message`.
+#[cfg(feature = "serde")]
+impl serde::Serialize for Warning {
+	fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		use serde::ser::SerializeStruct;
+		let mut s = serializer.serialize_struct("Warning", 2)?;
+		s.serialize_field("code", &self.code())?;
+		s.serialize_field("message", &self.to_string())?;
+		s.end()
+	}
+}
+
+/// Warning code.
+///
+/// Unlike the warnings themselves, codes do not carry any data,
+/// and their textual representation (see [`WarningCode::as_str`]) is stable,
+/// so that they can be matched by external tools.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Debug)]
+pub enum WarningCode {
+	/// See [`Warning::EmptyTerm`].
+	EmptyTerm,
+
+	/// See [`Warning::BlankNodeIdProperty`].
+	BlankNodeIdProperty,
+
+	/// See [`Warning::KeywordLikeTerm`].
+	KeywordLikeTerm,
+
+	/// See [`Warning::KeywordLikeValue`].
+	KeywordLikeValue,
+
+	/// See [`Warning::MalformedLanguageTag`].
+	MalformedLanguageTag,
+
+	/// See [`Warning::MalformedIri`].
+	MalformedIri,
+
+	/// See [`Warning::DroppedEntry`].
+	DroppedEntry,
+
+	/// See [`Warning::BlankNodePredicate`].
+	BlankNodePredicate,
+}
+
+impl WarningCode {
+	/// Get the name of the warning code.
+	pub fn as_str(&self) -> &'static str {
+		use WarningCode::*;
+
+		match self {
+			EmptyTerm => "empty term",
+			BlankNodeIdProperty => "blank node identifier property",
+			KeywordLikeTerm => "keyword-like term",
+			KeywordLikeValue => "keyword-like value",
+			MalformedLanguageTag => "malformed language tag",
+			MalformedIri => "malformed IRI",
+			DroppedEntry => "dropped entry",
+			BlankNodePredicate => "blank node predicate",
+		}
+	}
+}
+
+impl<'a> TryFrom<&'a str> for WarningCode {
+	type Error = ();
+
+	fn try_from(name: &'a str) -> Result<WarningCode, ()> {
+		use WarningCode::*;
+		match name {
+			"empty term" => Ok(EmptyTerm),
+			"blank node identifier property" => Ok(BlankNodeIdProperty),
+			"keyword-like term" => Ok(KeywordLikeTerm),
+			"keyword-like value" => Ok(KeywordLikeValue),
+			"malformed language tag" => Ok(MalformedLanguageTag),
+			"malformed IRI" => Ok(MalformedIri),
+			"dropped entry" => Ok(DroppedEntry),
+			"blank node predicate" => Ok(BlankNodePredicate),
+			_ => Err(()),
+		}
+	}
+}
+
+impl fmt::Display for WarningCode {
+	#[inline(always)]
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "{}", self.as_str())
+	}
+}
+
+/// Serializes the warning code as its name (see [`WarningCode::as_str`]).
+#[cfg(feature = "serde")]
+impl serde::Serialize for WarningCode {
+	#[inline(always)]
+	fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		serializer.serialize_str(self.as_str())
+	}
+}
@@ -9,17 +9,21 @@ extern crate json_ld;
 use async_std::task;
 use iref::{Iri, IriBuf};
 use json_ld::{
-	context::{self, Loader as ContextLoader, Local, ProcessingOptions},
-	expansion,
+	context::{self, Context, ContextMut, Local, ProcessingOptions},
+	expansion::{self, ExpandContext, LanguageOptions},
+	syntax::ContextDefinition,
 	util::{json_ld_eq, AsJson},
-	Document, FsLoader, Loader, ProcessingMode,
+	BlankIdSequence, Document, ErrorCode, ErrorDetails, ExpandedDocument, FsLoader, Limits, Loader,
+	Monitor, NoLoader, ProcessingMode, Reference,
 };
 use serde_json::Value;
+use std::sync::Arc;
 
 #[derive(Clone, Copy)]
 struct Options<'a> {
 	processing_mode: ProcessingMode,
 	context: Option<Iri<'a>>,
+	lenient: bool,
 }
 
 impl<'a> From<Options<'a>> for expansion::Options {
@@ -27,6 +31,7 @@ impl<'a> From<Options<'a>> for expansion::Options {
 		expansion::Options {
 			processing_mode: options.processing_mode,
 			ordered: false,
+			lenient: options.lenient,
 			..expansion::Options::default()
 		}
 	}
@@ -50,17 +55,16 @@ fn positive_test(options: Options, input_url: Iri, base_url: Iri, output_url: Ir
 	let mut input_context: context::Json<Value, IriBuf> = context::Json::new(Some(base_url));
 
 	if let Some(context_url) = options.context {
-		let local_context = task::block_on(loader.load_context(context_url))
-			.unwrap()
-			.into_context();
-		input_context = task::block_on(local_context.process_with(
-			&input_context,
-			&mut loader,
-			Some(base_url),
-			options.into(),
-		))
-		.unwrap()
-		.into_inner();
+		input_context = task::block_on(
+			ExpandContext::Iri(context_url.into()).process::<Value, _, _, _>(
+				&input_context,
+				&mut loader,
+				Some(base_url),
+				(),
+				options.into(),
+			),
+		)
+		.unwrap();
 	}
 
 	let result = task::block_on(input.expand_with(
@@ -102,7 +106,8 @@ fn positive_test(options: Options, input_url: Iri, base_url: Iri, output_url: Ir
 // 	positive_test(
 // 		Options {
 // 			processing_mode: ProcessingMode::JsonLd1_1,
-// 			context: None
+// 			context: None,
+// 			lenient: false
 // 		},
 // 		input_url,
 // 		base_url,
@@ -119,6 +124,7 @@ fn custom_e111() {
 		Options {
 			processing_mode: ProcessingMode::JsonLd1_1,
 			context: None,
+			lenient: false,
 		},
 		input_url,
 		base_url,
@@ -135,6 +141,7 @@ fn custom_e112() {
 		Options {
 			processing_mode: ProcessingMode::JsonLd1_1,
 			context: None,
+			lenient: false,
 		},
 		input_url,
 		base_url,
@@ -151,7 +158,8 @@ fn custom_e112() {
 // 	positive_test(
 // 		Options {
 // 			processing_mode: ProcessingMode::JsonLd1_1,
-// 			context: None
+// 			context: None,
+// 			lenient: false
 // 		},
 // 		input_url,
 // 		base_url,
@@ -168,6 +176,7 @@ fn custom_c037() {
 		Options {
 			processing_mode: ProcessingMode::JsonLd1_1,
 			context: None,
+			lenient: false,
 		},
 		input_url,
 		base_url,
@@ -184,6 +193,7 @@ fn custom_c038() {
 		Options {
 			processing_mode: ProcessingMode::JsonLd1_1,
 			context: None,
+			lenient: false,
 		},
 		input_url,
 		base_url,
@@ -200,6 +210,7 @@ fn custom_0120() {
 		Options {
 			processing_mode: ProcessingMode::JsonLd1_1,
 			context: None,
+			lenient: false,
 		},
 		input_url,
 		base_url,
@@ -216,6 +227,7 @@ fn custom_0122() {
 		Options {
 			processing_mode: ProcessingMode::JsonLd1_1,
 			context: None,
+			lenient: false,
 		},
 		input_url,
 		base_url,
@@ -232,6 +244,7 @@ fn custom_0123() {
 		Options {
 			processing_mode: ProcessingMode::JsonLd1_1,
 			context: None,
+			lenient: false,
 		},
 		input_url,
 		base_url,
@@ -248,6 +261,7 @@ fn custom_0124() {
 		Options {
 			processing_mode: ProcessingMode::JsonLd1_1,
 			context: None,
+			lenient: false,
 		},
 		input_url,
 		base_url,
@@ -264,9 +278,835 @@ fn custom_0125() {
 		Options {
 			processing_mode: ProcessingMode::JsonLd1_1,
 			context: None,
+			lenient: false,
+		},
+		input_url,
+		base_url,
+		output_url,
+	)
+}
+
+#[test]
+fn custom_lenient() {
+	let input_url = iri!("file://crate/tests/custom/lenient-in.jsonld");
+	let base_url = iri!("file://crate/tests/custom/lenient-in.jsonld");
+	let output_url = iri!("file://crate/tests/custom/lenient-out.jsonld");
+	positive_test(
+		Options {
+			processing_mode: ProcessingMode::JsonLd1_1,
+			context: None,
+			lenient: true,
+		},
+		input_url,
+		base_url,
+		output_url,
+	)
+}
+
+#[test]
+fn custom_lenient_index() {
+	let input_url = iri!("file://crate/tests/custom/lenient-index-in.jsonld");
+	let base_url = iri!("file://crate/tests/custom/lenient-index-in.jsonld");
+	let output_url = iri!("file://crate/tests/custom/lenient-index-out.jsonld");
+	positive_test(
+		Options {
+			processing_mode: ProcessingMode::JsonLd1_1,
+			context: None,
+			lenient: true,
 		},
 		input_url,
 		base_url,
 		output_url,
 	)
 }
+
+#[test]
+fn custom_json_ld_1_0() {
+	let input_url = iri!("file://crate/tests/custom/json-ld-1.0-in.jsonld");
+	let base_url = iri!("file://crate/tests/custom/json-ld-1.0-in.jsonld");
+	let output_url = iri!("file://crate/tests/custom/json-ld-1.0-out.jsonld");
+	positive_test(
+		Options {
+			processing_mode: ProcessingMode::JsonLd1_0,
+			context: None,
+			lenient: false,
+		},
+		input_url,
+		base_url,
+		output_url,
+	)
+}
+
+#[test]
+fn custom_reverse() {
+	let input_url = iri!("file://crate/tests/custom/reverse-in.jsonld");
+	let base_url = iri!("file://crate/tests/custom/reverse-in.jsonld");
+	let output_url = iri!("file://crate/tests/custom/reverse-out.jsonld");
+	positive_test(
+		Options {
+			processing_mode: ProcessingMode::JsonLd1_1,
+			context: None,
+			lenient: false,
+		},
+		input_url,
+		base_url,
+		output_url,
+	)
+}
+
+#[test]
+fn custom_property_index() {
+	let input_url = iri!("file://crate/tests/custom/property-index-in.jsonld");
+	let base_url = iri!("file://crate/tests/custom/property-index-in.jsonld");
+	let output_url = iri!("file://crate/tests/custom/property-index-out.jsonld");
+	positive_test(
+		Options {
+			processing_mode: ProcessingMode::JsonLd1_1,
+			context: None,
+			lenient: false,
+		},
+		input_url,
+		base_url,
+		output_url,
+	)
+}
+
+#[test]
+fn custom_scoped_context_cache() {
+	let local_context: Value = serde_json::from_str(
+		r#"{
+			"@vocab": "http://schema.org/",
+			"Person": { "@context": { "name": "http://xmlns.com/foaf/0.1/name" } }
+		}"#,
+	)
+	.unwrap();
+	let doc: Value = serde_json::from_str(
+		r#"[
+			{ "@type": "Person", "name": "Alice" },
+			{ "@type": "Person", "name": "Bob" }
+		]"#,
+	)
+	.unwrap();
+
+	let mut loader = NoLoader::<Value>::new();
+	let active_context =
+		task::block_on(local_context.process::<context::Json<Value, IriBuf>, _>(&mut loader, None))
+			.unwrap()
+			.into_inner();
+	let expanded = task::block_on(doc.expand_with(
+		None,
+		&active_context,
+		&mut loader,
+		expansion::Options::default(),
+	))
+	.unwrap();
+	assert_eq!(expanded.len(), 2);
+
+	// The type-scoped context has been processed once for both nodes.
+	let cache = &active_context.get("Person").unwrap().scoped_contexts;
+	assert_eq!(cache.len(), 1);
+
+	let entry = &cache.entries()[0];
+	assert!(!entry.options.propagate);
+	assert_eq!(Some(entry.active_context), active_context.generation());
+	assert!(entry.processed.get("name").is_some());
+
+	// Clones share the cached contexts, until they are modified.
+	let mut cloned = active_context.clone();
+	assert_eq!(cloned.generation(), active_context.generation());
+	assert_eq!(cloned.get("Person").unwrap().scoped_contexts.len(), 1);
+	cloned.set_vocabulary(None);
+	assert_ne!(cloned.generation(), active_context.generation())
+}
+
+#[test]
+fn custom_expand_context() {
+	let expand_context = ContextDefinition::new().with_vocab("http://schema.org/");
+	let doc: Value =
+		serde_json::from_str(r#"{ "@context": { "id": "@id" }, "name": "Alice" }"#).unwrap();
+
+	let mut loader = NoLoader::<Value>::new();
+	let context: context::Json<Value, IriBuf> = task::block_on(
+		ExpandContext::Definition(expand_context).process::<Value, _, _, _>(
+			&context::Json::new(None),
+			&mut loader,
+			None,
+			(),
+			ProcessingOptions::default(),
+		),
+	)
+	.unwrap();
+
+	let expanded =
+		task::block_on(doc.expand_with(None, &context, &mut loader, expansion::Options::default()))
+			.unwrap();
+	let output: Value = expanded.as_json();
+
+	let expected: Value =
+		serde_json::from_str(r#"[{ "http://schema.org/name": [{ "@value": "Alice" }] }]"#).unwrap();
+	assert!(json_ld_eq(&output, &expected))
+}
+
+#[test]
+fn custom_index_map() {
+	let doc: Value = serde_json::from_str(
+		r#"{
+			"@context": {
+				"@vocab": "http://example.org/",
+				"post": { "@container": "@index" }
+			},
+			"@id": "http://example.org/blog",
+			"post": {
+				"en": { "@id": "http://example.org/p1", "title": "Hello" },
+				"fr": "Bonjour"
+			}
+		}"#,
+	)
+	.unwrap();
+
+	let mut loader = NoLoader::<Value>::new();
+	let expanded =
+		task::block_on(doc.expand::<context::Json<Value, IriBuf>, _>(&mut loader)).unwrap();
+
+	let post = Reference::Id(IriBuf::new("http://example.org/post").unwrap());
+	let blog = expanded.main_node(None).unwrap();
+	let map = blog.get_index_map(&post);
+	assert_eq!(map.len(), 2);
+	assert_eq!(map["fr"][0].as_str(), Some("Bonjour"));
+	assert_eq!(blog.entries_by_index()["en"][0].0, &post);
+
+	// The index survives the node map generation.
+	let node_map = expanded
+		.generate_node_map(&mut BlankIdSequence::default())
+		.unwrap();
+	let graph = node_map.default_graph();
+	let blog = graph
+		.get(&Reference::Id(
+			IriBuf::new("http://example.org/blog").unwrap(),
+		))
+		.unwrap();
+	let reference = blog.get_index_map(&post)["en"][0];
+	assert_eq!(
+		reference.as_node().unwrap().as_iri().unwrap().as_str(),
+		"http://example.org/p1"
+	);
+
+	let indexed: Vec<_> = graph.nodes_with_index("en").collect();
+	assert_eq!(indexed.len(), 1);
+	assert_eq!(
+		indexed[0].as_iri().unwrap().as_str(),
+		"http://example.org/p1"
+	)
+}
+
+#[test]
+fn custom_conflicting_indexes() {
+	let doc: Value = serde_json::from_str(
+		r#"[
+			{ "@id": "http://example.org/a", "@index": "x" },
+			{ "@id": "http://example.org/a", "@index": "y" }
+		]"#,
+	)
+	.unwrap();
+
+	let mut loader = NoLoader::<Value>::new();
+	let expanded =
+		task::block_on(doc.expand::<context::Json<Value, IriBuf>, _>(&mut loader)).unwrap();
+
+	let error = expanded
+		.clone()
+		.generate_node_map(&mut BlankIdSequence::default())
+		.err()
+		.unwrap();
+	assert_eq!(error.code(), ErrorCode::ConflictingIndexes);
+
+	let mut warnings = Vec::new();
+	let node_map = expanded
+		.generate_node_map_with_warnings(&mut BlankIdSequence::default(), &mut warnings)
+		.unwrap();
+	assert_eq!(warnings.len(), 1);
+
+	let a = Reference::Id(IriBuf::new("http://example.org/a").unwrap());
+	let index = node_map.default_graph().get(&a).unwrap().index();
+	assert!(index == Some("x") || index == Some("y"))
+}
+
+#[test]
+fn custom_monitor() {
+	let doc: Value = serde_json::from_str(
+		r#"{ "@id": "http://example.org/a", "http://example.org/p": [ "x", "y" ] }"#,
+	)
+	.unwrap();
+
+	let monitor = Arc::new(Monitor::new());
+	let options = expansion::Options {
+		monitor: Some(monitor.clone()),
+		..expansion::Options::default()
+	};
+
+	let mut loader = NoLoader::<Value>::new();
+	task::block_on(doc.expand_with_base::<context::Json<Value, IriBuf>, _>(
+		None,
+		&mut loader,
+		options.clone(),
+	))
+	.unwrap();
+
+	// The node object, the array and its two strings (`@id` is not expanded as an element).
+	let progress = monitor.progress();
+	assert_eq!(progress.objects, 4);
+	assert_eq!(
+		progress.bytes,
+		"@id".len() + "http://example.org/p".len() + 2
+	);
+
+	monitor.reset();
+	monitor.cancel();
+	let result = task::block_on(doc.expand_with_base::<context::Json<Value, IriBuf>, _>(
+		None,
+		&mut loader,
+		options,
+	));
+	assert_eq!(result.err().unwrap().code(), ErrorCode::Cancelled)
+}
+
+#[test]
+fn custom_properties_editing() {
+	let doc: Value = serde_json::from_str(
+		r#"{ "@id": "http://example.org/a", "http://example.org/p": [ "x", "y", "x" ] }"#,
+	)
+	.unwrap();
+
+	let mut loader = NoLoader::<Value>::new();
+	let expanded =
+		task::block_on(doc.expand::<context::Json<Value, IriBuf>, _>(&mut loader)).unwrap();
+	let mut node = expanded
+		.into_iter()
+		.next()
+		.unwrap()
+		.into_indexed_node()
+		.unwrap();
+
+	let p = Reference::Id(IriBuf::new("http://example.org/p").unwrap());
+	let x = node.get_any(&p).unwrap().clone();
+	let properties = node.properties_mut();
+
+	properties.dedup();
+	assert_eq!(properties.get(&p).count(), 2);
+
+	let other = properties.clone();
+	assert!(properties.remove_object(&p, &x).is_some());
+	assert!(properties.remove_object(&p, &x).is_none());
+	assert_eq!(properties.get(&p).count(), 1);
+
+	properties.union_with(other.clone());
+	assert_eq!(properties.get(&p).count(), 2);
+
+	properties.retain(|_, object| object.as_str() != Some("y"));
+	properties.intersect_with(&other);
+	assert_eq!(properties.get(&p).count(), 1);
+
+	let drained: Vec<_> = properties.drain().collect();
+	assert_eq!(drained.len(), 1);
+	assert!(properties.is_empty())
+}
+
+#[test]
+fn custom_graphs() {
+	let doc: Value = serde_json::from_str(
+		r#"[
+			{
+				"@id": "http://example.org/g1",
+				"@graph": { "@id": "http://example.org/a", "http://example.org/p": "x" }
+			},
+			{
+				"@id": "http://example.org/g2",
+				"http://example.org/label": "G2",
+				"@graph": { "@id": "http://example.org/b", "http://example.org/p": "y" }
+			}
+		]"#,
+	)
+	.unwrap();
+
+	let mut loader = NoLoader::<Value>::new();
+	let expanded =
+		task::block_on(doc.expand::<context::Json<Value, IriBuf>, _>(&mut loader)).unwrap();
+
+	let g1 = Reference::Id(IriBuf::new("http://example.org/g1").unwrap());
+	let g2 = Reference::Id(IriBuf::new("http://example.org/g2").unwrap());
+	assert_eq!(expanded.graph_names().len(), 2);
+	assert_eq!(expanded.graph(&g1).unwrap().len(), 1);
+
+	let mut moved = expanded.clone();
+	assert!(moved.move_graph(&g1, Some(&g2)));
+	assert!(!moved.move_graph(&g1, None));
+	assert_eq!(moved.len(), 1);
+	assert_eq!(moved.graph(&g2).unwrap().len(), 2);
+
+	// The `g1` node only has an `@id` once its graph is removed.
+	let (default_graph, graphs) = expanded.into_graphs();
+	assert_eq!(default_graph.len(), 1);
+	assert_eq!(graphs.len(), 2);
+	assert_eq!(graphs[&g2].len(), 1)
+}
+
+#[test]
+fn custom_language_options() {
+	let doc: Value = serde_json::from_str(
+		r#"{
+			"@context": {
+				"@vocab": "http://example.org/",
+				"label": { "@container": "@language" }
+			},
+			"label": { "EN-us": "Hello", "fr": "Bonjour", "de": "Hallo" },
+			"name": "Alice",
+			"age": { "@value": "42", "@type": "http://www.w3.org/2001/XMLSchema#integer" }
+		}"#,
+	)
+	.unwrap();
+
+	let options = expansion::Options {
+		language: LanguageOptions {
+			lowercase: true,
+			allowed: Some(&["en"]),
+			default: Some("en"),
+		},
+		..expansion::Options::default()
+	};
+
+	let mut loader = NoLoader::<Value>::new();
+	let expanded = task::block_on(doc.expand_with_base::<context::Json<Value, IriBuf>, _>(
+		None,
+		&mut loader,
+		options,
+	))
+	.unwrap();
+	let output: Value = expanded.as_json();
+
+	let expected: Value = serde_json::from_str(
+		r#"[{
+			"http://example.org/label": [{ "@value": "Hello", "@language": "en-us" }],
+			"http://example.org/name": [{ "@value": "Alice", "@language": "en" }],
+			"http://example.org/age": [{
+				"@value": "42",
+				"@type": "http://www.w3.org/2001/XMLSchema#integer"
+			}]
+		}]"#,
+	)
+	.unwrap();
+	assert!(json_ld_eq(&output, &expected))
+}
+
+#[test]
+fn custom_data_url_context() {
+	// The first context is `{"@context": {"name": "http://xmlns.com/foaf/0.1/name"}}`.
+	let doc: Value = serde_json::from_str(
+		r#"{
+			"@context": [
+				"data:application/ld+json;base64,eyJAY29udGV4dCI6IHsibmFtZSI6ICJodHRwOi8veG1sbnMuY29tL2ZvYWYvMC4xL25hbWUifX0=",
+				"data:application/json,%7B%22%40context%22%3A%7B%22knows%22%3A%22http%3A%2F%2Fxmlns.com%2Ffoaf%2F0.1%2Fknows%22%7D%7D"
+			],
+			"name": "Alice",
+			"knows": "Bob"
+		}"#,
+	)
+	.unwrap();
+
+	let mut loader = FsLoader::<Value>::default();
+	let expanded =
+		task::block_on(doc.expand::<context::Json<Value, IriBuf>, _>(&mut loader)).unwrap();
+	let output: Value = expanded.as_json();
+
+	let expected: Value = serde_json::from_str(
+		r#"[{
+			"http://xmlns.com/foaf/0.1/name": [{ "@value": "Alice" }],
+			"http://xmlns.com/foaf/0.1/knows": [{ "@value": "Bob" }]
+		}]"#,
+	)
+	.unwrap();
+	assert!(json_ld_eq(&output, &expected))
+}
+
+#[test]
+fn custom_error_trace() {
+	// The document loads `A`, which loads `B`, which has an invalid term definition.
+	let b = "data:application/ld+json;base64,eyJAY29udGV4dCI6IHsiYmFkIjogeyJAaWQiOiB0cnVlfX19";
+	let a = "data:application/ld+json;base64,eyJAY29udGV4dCI6ICJkYXRhOmFwcGxpY2F0aW9uL2xkK2pzb247YmFzZTY0LGV5SkFZMjl1ZEdWNGRDSTZJSHNpWW1Ga0lqb2dleUpBYVdRaU9pQjBjblZsZlgxOSJ9";
+	let doc = serde_json::json!({ "@context": a, "bad": "value" });
+
+	let mut loader = FsLoader::<Value>::default();
+	let error =
+		task::block_on(doc.expand::<context::Json<Value, IriBuf>, _>(&mut loader)).unwrap_err();
+
+	let trace: Vec<_> = error.trace().iter().map(|i| i.url.as_str()).collect();
+	assert_eq!(trace, vec![b, a]);
+	assert_eq!(error.trace()[0].source, loader.id(Iri::new(a).unwrap()));
+	assert_eq!(error.trace()[1].source, None);
+	assert_eq!(
+		error.display_trace(&loader).to_string(),
+		format!(
+			"{}\n  in `{}`, loaded by `{}`\n  in `{}`, loaded by the document",
+			*error, b, a, a
+		)
+	);
+}
+
+#[test]
+fn custom_expand_stream() {
+	use expansion::Event;
+
+	// Each item is generated only when the expansion asks for it.
+	const LEN: usize = 10_000;
+	let items = (0..LEN).flat_map(|i| {
+		vec![
+			Event::BeginObject(()),
+			Event::Key("@id".to_string(), ()),
+			Event::String(format!("http://example.org/{}", i), ()),
+			Event::Key("http://example.org/index".to_string(), ()),
+			Event::Number((i as u64).into(), ()),
+			Event::EndObject,
+		]
+	});
+	let mut events = std::iter::once(Event::BeginArray(()))
+		.chain(items)
+		.chain(std::iter::once(Event::EndArray))
+		.map(Ok::<Event<Value>, ()>);
+
+	let context: context::Json<Value, IriBuf> = context::Json::new(None);
+	let mut loader = NoLoader::<Value>::new();
+	let mut warnings = Vec::new();
+	let expanded = task::block_on(expansion::expand_stream(
+		&context,
+		&mut events,
+		None,
+		&mut loader,
+		expansion::Options::default(),
+		&mut warnings,
+	))
+	.unwrap();
+
+	assert_eq!(expanded.len(), LEN);
+	assert!(warnings.is_empty());
+	assert!(events.next().is_none());
+
+	let index = Reference::Id(IriBuf::new("http://example.org/index").unwrap());
+	for object in &expanded {
+		let node = object.as_node().unwrap();
+		let id = node.id().unwrap().as_str();
+		let i: u64 = id["http://example.org/".len()..].parse().unwrap();
+		let value = node.get_any(&index).unwrap().as_value().unwrap();
+		assert!(matches!(
+			value,
+			json_ld::Value::Literal(json_ld::object::Literal::Number(n), None) if n.as_u64() == Some(i)
+		));
+	}
+}
+
+#[test]
+fn custom_limits() {
+	fn nested(depth: usize) -> Value {
+		let mut value = Value::Null;
+		for _ in 0..depth {
+			value = Value::Array(vec![value])
+		}
+		value
+	}
+
+	fn exceeded(limits: Limits, value: &Value) -> Option<(String, usize)> {
+		let error = limits.check(value, None).err()?;
+		assert_eq!(error.code(), ErrorCode::LimitExceeded);
+		match error.details() {
+			Some(ErrorDetails::Limit { name, max }) => Some((name.clone(), *max)),
+			_ => panic!("missing limit details"),
+		}
+	}
+
+	let depth = Limits {
+		max_depth: Some(3),
+		..Limits::none()
+	};
+	assert_eq!(exceeded(depth, &nested(3)), None);
+	assert_eq!(
+		exceeded(depth, &nested(4)),
+		Some(("max_depth".to_string(), 3))
+	);
+
+	// `[1, 2, 3]` has four values.
+	let values = Limits {
+		max_values: Some(4),
+		..Limits::none()
+	};
+	let array = serde_json::json!([1, 2, 3]);
+	assert_eq!(exceeded(values, &array), None);
+	assert_eq!(
+		exceeded(
+			Limits {
+				max_values: Some(3),
+				..values
+			},
+			&array
+		),
+		Some(("max_values".to_string(), 3))
+	);
+
+	let strings = Limits {
+		max_string_length: Some(3),
+		..Limits::none()
+	};
+	assert_eq!(
+		exceeded(strings, &serde_json::json!({ "abc": "def" })),
+		None
+	);
+	assert_eq!(
+		exceeded(strings, &serde_json::json!({ "abcd": "def" })),
+		Some(("max_string_length".to_string(), 3))
+	);
+	assert_eq!(
+		exceeded(strings, &serde_json::json!({ "abc": ["defg"] })),
+		Some(("max_string_length".to_string(), 3))
+	);
+
+	// The depth is always bounded once a limit is set.
+	let unbounded_depth = Limits {
+		max_values: Some(usize::MAX),
+		..Limits::none()
+	};
+	let max_depth = ("max_depth".to_string(), Limits::MAX_DEPTH);
+	assert_eq!(exceeded(unbounded_depth, &nested(Limits::MAX_DEPTH)), None);
+	assert_eq!(
+		exceeded(unbounded_depth, &nested(Limits::MAX_DEPTH + 1)),
+		Some(max_depth.clone())
+	);
+	assert_eq!(
+		exceeded(
+			Limits {
+				max_depth: Some(usize::MAX),
+				..Limits::none()
+			},
+			&nested(Limits::MAX_DEPTH + 1)
+		),
+		Some(max_depth)
+	);
+	assert_eq!(
+		exceeded(Limits::none(), &nested(Limits::MAX_DEPTH + 1)),
+		None
+	);
+}
+
+fn node_map_test(input_url: Iri, output_url: Iri) {
+	let mut loader = FsLoader::<Value>::new(|s| serde_json::from_str(s));
+	loader.mount(iri!("file://crate/tests"), "tests");
+
+	let input = task::block_on(loader.load(input_url)).unwrap();
+	let output = task::block_on(loader.load(output_url)).unwrap();
+	let input_context: context::Json<Value, IriBuf> = context::Json::new(Some(input_url));
+
+	let node_map = task::block_on(input.expand_with(
+		Some(input_url),
+		&input_context,
+		&mut loader,
+		expansion::Options::default(),
+	))
+	.unwrap()
+	.generate_node_map(&mut BlankIdSequence::default())
+	.unwrap();
+
+	// Each graph name (or `@default`) is mapped to the object mapping each node
+	// identifier to its node.
+	let result_json: Value = node_map
+		.graphs()
+		.map(|(name, graph)| {
+			let nodes = graph
+				.iter()
+				.map(|(id, node)| {
+					let node: Value = node.as_json();
+					(id.as_str().to_string(), node)
+				})
+				.collect();
+			let name = name.map_or("@default", |name| name.as_str());
+			(name.to_string(), Value::Object(nodes))
+		})
+		.collect::<serde_json::Map<_, _>>()
+		.into();
+	let success = json_ld_eq(&result_json, &*output);
+
+	if success {
+		println!(
+			"output=\n{}",
+			serde_json::to_string_pretty(&result_json).unwrap()
+		);
+	} else {
+		println!(
+			"output=\n{}",
+			serde_json::to_string_pretty(&result_json).unwrap()
+		);
+		println!(
+			"\nexpected=\n{}",
+			serde_json::to_string_pretty(&*output).unwrap()
+		);
+	}
+
+	assert!(success)
+}
+
+#[test]
+fn custom_node_map() {
+	// The input has a single blank node, so that its new identifier is known.
+	let input_url = iri!("file://crate/tests/custom/node-map-in.jsonld");
+	let output_url = iri!("file://crate/tests/custom/node-map-out.jsonld");
+	node_map_test(input_url, output_url)
+}
+
+fn merge_test(input_urls: &[Iri], output_url: Iri) {
+	let mut loader = FsLoader::<Value>::new(|s| serde_json::from_str(s));
+	loader.mount(iri!("file://crate/tests"), "tests");
+
+	let output = task::block_on(loader.load(output_url)).unwrap();
+	let mut docs: Vec<ExpandedDocument<Value, IriBuf>> = Vec::new();
+	for &input_url in input_urls {
+		let input = task::block_on(loader.load(input_url)).unwrap();
+		let input_context: context::Json<Value, IriBuf> = context::Json::new(Some(input_url));
+		docs.push(
+			task::block_on(input.expand_with(
+				Some(input_url),
+				&input_context,
+				&mut loader,
+				expansion::Options::default(),
+			))
+			.unwrap(),
+		)
+	}
+
+	// Merging with `FromIterator` and `ExpandedDocument::merge` gives the same result.
+	let collected: ExpandedDocument<Value, IriBuf> = docs.clone().into_iter().collect();
+	let mut docs = docs.into_iter();
+	let mut merged = docs.next().unwrap();
+	for doc in docs {
+		merged.merge(doc)
+	}
+
+	for result in vec![collected, merged] {
+		let result_json: Value = result.as_json();
+		let success = json_ld_eq(&result_json, &*output);
+
+		if success {
+			println!(
+				"output=\n{}",
+				serde_json::to_string_pretty(&result_json).unwrap()
+			);
+		} else {
+			println!(
+				"output=\n{}",
+				serde_json::to_string_pretty(&result_json).unwrap()
+			);
+			println!(
+				"\nexpected=\n{}",
+				serde_json::to_string_pretty(&*output).unwrap()
+			);
+		}
+
+		assert!(success)
+	}
+}
+
+#[test]
+fn custom_merge() {
+	// Nested nodes are not merged with top-level nodes sharing their identifier.
+	let input_urls = [
+		iri!("file://crate/tests/custom/merge-1.jsonld"),
+		iri!("file://crate/tests/custom/merge-2.jsonld"),
+	];
+	let output_url = iri!("file://crate/tests/custom/merge-out.jsonld");
+	merge_test(&input_urls, output_url);
+
+	// Blank node identifiers are local to each document.
+	let mut loader = NoLoader::<Value>::new();
+	let mut blank = |name: &str| {
+		let doc = serde_json::json!({ "@id": "_:x", "http://example.org/name": name });
+		task::block_on(doc.expand::<context::Json<Value, IriBuf>, _>(&mut loader)).unwrap()
+	};
+	let mut doc = blank("X1");
+	doc.merge(blank("X2"));
+	assert_eq!(doc.len(), 2);
+	assert_eq!(
+		doc.blank_ids()
+			.collect::<std::collections::HashSet<_>>()
+			.len(),
+		2
+	);
+}
+
+fn sorted_iteration_test(input_url: Iri, main: Iri, output_url: Iri) {
+	let mut loader = FsLoader::<Value>::new(|s| serde_json::from_str(s));
+	loader.mount(iri!("file://crate/tests"), "tests");
+
+	let input = task::block_on(loader.load(input_url)).unwrap();
+	let output = task::block_on(loader.load(output_url)).unwrap();
+	let input_context: context::Json<Value, IriBuf> = context::Json::new(Some(input_url));
+
+	let expanded = task::block_on(input.expand_with(
+		Some(input_url),
+		&input_context,
+		&mut loader,
+		expansion::Options::default(),
+	))
+	.unwrap();
+	let main = Reference::Id(main.into());
+	let node = expanded
+		.nodes()
+		.find(|node| node.id() == Some(&main))
+		.unwrap();
+	let iris = |iris: Vec<&Reference<IriBuf>>| -> Value {
+		iris.into_iter()
+			.map(|iri| Value::from(iri.as_str()))
+			.collect()
+	};
+
+	let node_map = expanded
+		.clone()
+		.generate_node_map(&mut BlankIdSequence::default())
+		.unwrap();
+	let graphs: Value = node_map
+		.graphs_sorted()
+		.into_iter()
+		.map(|(name, graph)| {
+			let name = Value::from(name.map_or("@default", |name| name.as_str()));
+			serde_json::json!([name, iris(graph.iter_sorted().map(|(id, _)| id).collect())])
+		})
+		.collect();
+
+	let result_json = serde_json::json!({
+		"properties": iris(node.properties().iter_sorted().map(|(p, _)| p).collect()),
+		"reverseProperties": iris(node.reverse_properties().iter_sorted().map(|(p, _)| p).collect()),
+		"nodeMap": graphs
+	});
+
+	// Arrays are compared in order.
+	let success = result_json == *output;
+
+	if success {
+		println!(
+			"output=\n{}",
+			serde_json::to_string_pretty(&result_json).unwrap()
+		);
+	} else {
+		println!(
+			"output=\n{}",
+			serde_json::to_string_pretty(&result_json).unwrap()
+		);
+		println!(
+			"\nexpected=\n{}",
+			serde_json::to_string_pretty(&*output).unwrap()
+		);
+	}
+
+	assert!(success)
+}
+
+#[test]
+fn custom_sorted_iteration() {
+	let input_url = iri!("file://crate/tests/custom/ordered-in.jsonld");
+	let main = iri!("http://example.org/main");
+	let output_url = iri!("file://crate/tests/custom/ordered-out.jsonld");
+	sorted_iteration_test(input_url, main, output_url)
+}
@@ -0,0 +1,294 @@
+#![feature(proc_macro_hygiene)]
+
+extern crate async_std;
+extern crate iref;
+#[macro_use]
+extern crate static_iref;
+extern crate json_ld;
+
+use async_std::task;
+use iref::{Iri, IriBuf};
+use json_ld::{
+	compaction,
+	context::{self, Local, ProcessedOwned, ProcessingOptions},
+	syntax::{Container, Term},
+	util::{json_ld_eq, AsJson},
+	Document, FsLoader, Loader, NoLoader, ProcessingMode, Reference,
+};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+fn no_metadata<M>(_: Option<&M>) {}
+
+/// Expands the given document and compacts it back using its own context,
+/// checking that the output is the input document.
+fn round_trip_test(input_url: Iri) {
+	let mut loader = FsLoader::<Value>::new(|s| serde_json::from_str(s));
+	loader.mount(iri!("file://crate/tests"), "tests");
+
+	let input = task::block_on(loader.load(input_url)).unwrap();
+	let local_context = input.get("@context").unwrap().clone();
+	let context: ProcessedOwned<Value, context::Json<Value, IriBuf>> =
+		task::block_on(local_context.process_with(
+			&context::Json::new(Some(input_url)),
+			&mut loader,
+			Some(input_url),
+			ProcessingOptions::default(),
+		))
+		.unwrap()
+		.owned();
+
+	let options = compaction::Options {
+		processing_mode: ProcessingMode::JsonLd1_1,
+		..compaction::Options::default()
+	};
+
+	let output: Value = task::block_on(input.compact_with(
+		Some(input_url),
+		&context,
+		&mut loader,
+		options,
+		no_metadata,
+		no_metadata,
+	))
+	.unwrap();
+
+	let success = json_ld_eq(&output, &*input);
+	if !success {
+		println!(
+			"output=\n{}",
+			serde_json::to_string_pretty(&output).unwrap()
+		);
+		println!(
+			"\nexpected=\n{}",
+			serde_json::to_string_pretty(&*input).unwrap()
+		);
+	}
+
+	assert!(success)
+}
+
+#[test]
+fn custom_compact_graph_id() {
+	round_trip_test(iri!("file://crate/tests/custom/graph-id.jsonld"))
+}
+
+#[test]
+fn custom_compact_graph_index() {
+	round_trip_test(iri!("file://crate/tests/custom/graph-index.jsonld"))
+}
+
+#[test]
+fn custom_compact_graph() {
+	round_trip_test(iri!("file://crate/tests/custom/graph.jsonld"))
+}
+
+#[test]
+fn custom_compact_nested_list() {
+	round_trip_test(iri!("file://crate/tests/custom/nested-list.jsonld"))
+}
+
+#[test]
+fn custom_compact_prefixes() {
+	let input: Value = serde_json::from_str(
+		r#"{
+			"@id": "urn:example:alice",
+			"@type": "http://xmlns.com/foaf/0.1/Person",
+			"http://xmlns.com/foaf/0.1/name": "Alice",
+			"http://xmlns.com/foaf/0.1/knows": [
+				{ "@id": "urn:bob" },
+				{ "@id": "http://xmlns.com/foaf/0.1/" }
+			]
+		}"#,
+	)
+	.unwrap();
+
+	let mut loader = NoLoader::<Value>::new();
+	let expanded =
+		task::block_on(input.expand::<context::Json<Value, IriBuf>, _>(&mut loader)).unwrap();
+
+	let mut prefixes = HashMap::new();
+	prefixes.insert(
+		"foaf".to_string(),
+		IriBuf::new("http://xmlns.com/foaf/0.1/").unwrap(),
+	);
+	// `urn:bob` cannot be compacted, so the `urn` prefix must not be used.
+	prefixes.insert("urn".to_string(), IriBuf::new("urn:example:").unwrap());
+
+	let output: Value = expanded.compact_with_prefixes(&prefixes, no_metadata);
+	assert_eq!(
+		output["@context"],
+		json!({ "foaf": "http://xmlns.com/foaf/0.1/" })
+	);
+	assert_eq!(output["@graph"][0]["@type"], json!(["foaf:Person"]));
+
+	let reexpanded =
+		task::block_on(output.expand::<context::Json<Value, IriBuf>, _>(&mut loader)).unwrap();
+	let expected: Value = expanded.as_json();
+	let actual: Value = reexpanded.as_json();
+	assert!(json_ld_eq(&actual, &expected))
+}
+
+#[test]
+fn custom_compact_select_property() {
+	let mut loader = NoLoader::<Value>::new();
+
+	let local_context = json!({
+		"tag": "http://example.org/tag",
+		"tags": { "@id": "http://example.org/tag", "@container": "@language" }
+	});
+	let context: context::Json<Value, IriBuf> = task::block_on(local_context.process_with(
+		&context::Json::new(None),
+		&mut loader,
+		None,
+		ProcessingOptions::default(),
+	))
+	.unwrap()
+	.into_inner();
+
+	let property = IriBuf::new("http://example.org/tag").unwrap();
+	let mut select = |value: Value| {
+		let input = json!({ "http://example.org/tag": value });
+		let expanded =
+			task::block_on(input.expand::<context::Json<Value, IriBuf>, _>(&mut loader)).unwrap();
+		let node = expanded.iter().next().unwrap().as_node().unwrap();
+		let value = node.get_any(&Reference::Id(property.clone())).unwrap();
+
+		compaction::select_property(
+			&context,
+			&Term::Ref(Reference::Id(property.clone())),
+			value,
+			false,
+			compaction::Options::default(),
+		)
+		.unwrap()
+		.unwrap()
+	};
+
+	let selection = select(json!({ "@value": "hello", "@language": "en" }));
+	assert_eq!(selection.key, "tags");
+	assert_eq!(selection.term.unwrap().container, Container::Language);
+
+	let selection = select(json!("hello"));
+	assert_eq!(selection.key, "tag");
+	assert_eq!(selection.term.unwrap().container, Container::None);
+	assert_eq!(selection.preferred_containers[0], Container::Set);
+}
+
+#[test]
+fn custom_compact_array_options() {
+	let base_url = iri!("http://example.org/doc/");
+	let input = json!({
+		"@id": "http://example.org/doc/alice",
+		"@type": "http://example.org/Person",
+		"http://example.org/name": "Alice"
+	});
+
+	let mut loader = NoLoader::<Value>::new();
+	let local_context = json!({
+		"@version": 1.1,
+		"@vocab": "http://example.org/",
+		"@type": { "@container": "@set" }
+	});
+	let context: ProcessedOwned<Value, context::Json<Value, IriBuf>> =
+		task::block_on(local_context.process_with(
+			&context::Json::new(Some(base_url)),
+			&mut loader,
+			Some(base_url),
+			ProcessingOptions::default(),
+		))
+		.unwrap()
+		.owned();
+
+	let mut compact = |options: compaction::Options| -> Value {
+		task::block_on(input.compact_with(
+			Some(base_url),
+			&context,
+			&mut loader,
+			options,
+			no_metadata,
+			no_metadata,
+		))
+		.unwrap()
+	};
+
+	// The `@type` entry remains an array because of its `@set` container.
+	let output = compact(compaction::Options::default());
+	assert_eq!(output["@id"], json!("alice"));
+	assert_eq!(output["@type"], json!(["Person"]));
+	assert_eq!(output["name"], json!("Alice"));
+
+	let output = compact(compaction::Options {
+		compact_to_relative: false,
+		compact_arrays: false,
+		..compaction::Options::default()
+	});
+	assert_eq!(output["@id"], json!("http://example.org/doc/alice"));
+	assert_eq!(output["@type"], json!(["Person"]));
+	assert_eq!(output["name"], json!(["Alice"]))
+}
+
+#[test]
+fn custom_compact_json_ld_1_0() {
+	let base_url = iri!("http://example.org/doc/");
+	let input = json!({
+		"@id": "http://example.org/x",
+		"http://example.org/n": "nested value",
+		"http://example.org/s": { "http://example.org/v": "scoped" },
+		"http://example.org/g": { "@graph": { "http://example.org/v": "in graph" } }
+	});
+
+	let mut loader = NoLoader::<Value>::new();
+	let local_context = json!({
+		"meta": "@nest",
+		"nested": { "@id": "http://example.org/n", "@nest": "meta" },
+		"scoped": { "@id": "http://example.org/s", "@context": { "v": "http://example.org/v" } },
+		"graph": { "@id": "http://example.org/g", "@container": "@graph" }
+	});
+	let context: ProcessedOwned<Value, context::Json<Value, IriBuf>> =
+		task::block_on(local_context.process_with(
+			&context::Json::new(Some(base_url)),
+			&mut loader,
+			Some(base_url),
+			ProcessingOptions::default(),
+		))
+		.unwrap()
+		.owned();
+
+	let mut compact = |input: &Value, processing_mode: ProcessingMode| {
+		task::block_on(input.compact_with::<Value, _, _, _, _>(
+			Some(base_url),
+			&context,
+			&mut loader,
+			compaction::Options {
+				processing_mode,
+				..compaction::Options::default()
+			},
+			no_metadata,
+			no_metadata,
+		))
+	};
+
+	let output = compact(&input, ProcessingMode::JsonLd1_1).unwrap();
+	assert_eq!(output["meta"]["nested"], json!("nested value"));
+	assert_eq!(output["scoped"], json!({ "v": "scoped" }));
+	assert!(output.get("graph").is_some());
+
+	// Nesting, scoped contexts and graph containers are ignored in json-ld-1.0.
+	let output = compact(&input, ProcessingMode::JsonLd1_0).unwrap();
+	assert!(output.get("meta").is_none());
+	assert_eq!(output["nested"], json!("nested value"));
+	assert_eq!(
+		output["scoped"],
+		json!({ "http://example.org/v": "scoped" })
+	);
+	assert!(output.get("graph").is_none());
+	assert!(output.get("http://example.org/g").is_some());
+
+	// JSON literals are rejected in json-ld-1.0.
+	let input = json!({
+		"http://example.org/j": { "@value": { "a": 1 }, "@type": "@json" }
+	});
+	assert!(compact(&input, ProcessingMode::JsonLd1_1).is_ok());
+	assert!(compact(&input, ProcessingMode::JsonLd1_0).is_err())
+}
@@ -0,0 +1,75 @@
+extern crate iref;
+extern crate json_ld;
+
+use iref::Iri;
+use json_ld::loader::LoadPolicy;
+
+fn allowed(policy: &LoadPolicy, url: &str) -> bool {
+	policy.is_allowed(Iri::new(url).unwrap())
+}
+
+#[test]
+fn policy_allow_host() {
+	let policy = LoadPolicy::new().allow("https://example.org");
+	assert!(allowed(&policy, "https://example.org/context.jsonld"));
+	assert!(allowed(&policy, "HTTPS://Example.ORG/context.jsonld"));
+	assert!(allowed(&policy, "https://example.org:443/context.jsonld"));
+	assert!(!allowed(&policy, "https://example.org.evil.com/"));
+	assert!(!allowed(&policy, "https://example.org@evil.com/"));
+	assert!(!allowed(&policy, "https://example.org:8443/"));
+	assert!(!allowed(&policy, "http://example.org/"));
+}
+
+#[test]
+fn policy_allow_path() {
+	let policy = LoadPolicy::new().allow("https://example.org/contexts");
+	assert!(allowed(&policy, "https://example.org/contexts"));
+	assert!(allowed(&policy, "https://example.org/contexts/v1.jsonld"));
+	assert!(!allowed(
+		&policy,
+		"https://example.org/contexts-evil/v1.jsonld"
+	));
+	assert!(!allowed(&policy, "https://example.org/contexts/../private"));
+	assert!(!allowed(
+		&policy,
+		"https://example.org/%63ontexts/../private"
+	));
+}
+
+#[test]
+fn policy_deny_localhost() {
+	let policy = LoadPolicy::new().deny("http://localhost");
+	assert!(!allowed(&policy, "http://localhost/"));
+	assert!(!allowed(&policy, "HTTP://LOCALHOST/"));
+	assert!(!allowed(&policy, "http://localhost:8080/"));
+	assert!(!allowed(&policy, "http://localhost./"));
+	assert!(!allowed(&policy, "http://127.0.0.1/"));
+	assert!(!allowed(&policy, "http://127.1/"));
+	assert!(!allowed(&policy, "http://0x7f000001/"));
+	assert!(!allowed(&policy, "http://2130706433/"));
+	assert!(!allowed(&policy, "http://0.0.0.0/"));
+	assert!(!allowed(&policy, "http://[::1]/"));
+	assert!(!allowed(&policy, "http://[::ffff:127.0.0.1]/"));
+	assert!(allowed(&policy, "http://example.org/"));
+	assert!(allowed(&policy, "https://localhost/"));
+}
+
+#[test]
+fn policy_deny_port() {
+	let policy = LoadPolicy::new().deny("http://example.org:8080");
+	assert!(!allowed(&policy, "http://example.org:8080/"));
+	assert!(allowed(&policy, "http://example.org/"));
+	assert!(allowed(&policy, "http://example.org:8081/"));
+}
+
+#[test]
+fn policy_invalid_prefix() {
+	assert!(!allowed(
+		&LoadPolicy::new().allow("example.org"),
+		"https://example.org/"
+	));
+	assert!(!allowed(
+		&LoadPolicy::new().deny("example.org"),
+		"https://example.com/"
+	));
+}
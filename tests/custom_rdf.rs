@@ -0,0 +1,268 @@
+extern crate async_std;
+extern crate iref;
+extern crate json_ld;
+
+use async_std::task;
+use iref::IriBuf;
+use json_ld::{
+	context, expansion,
+	rdf::{self, Digest},
+	BlankIdSequence, Document, ExpandedDocument, NoLoader, WarningCode,
+};
+use serde_json::Value;
+
+/// Identity digest, so that the canonical form can be checked.
+#[derive(Default)]
+struct Identity(Vec<u8>);
+
+impl Digest for Identity {
+	fn update(&mut self, data: &[u8]) {
+		self.0.extend_from_slice(data)
+	}
+
+	fn finalize(self) -> Vec<u8> {
+		self.0
+	}
+}
+
+fn expand(json: &str) -> ExpandedDocument<Value, IriBuf> {
+	let doc: Value = serde_json::from_str(json).unwrap();
+	let mut loader = NoLoader::<Value>::new();
+	task::block_on(doc.expand::<context::Json<Value, IriBuf>, _>(&mut loader)).unwrap()
+}
+
+fn canonical_nquads(doc: &ExpandedDocument<Value, IriBuf>) -> String {
+	let quads = doc
+		.to_rdf(&mut BlankIdSequence::default(), &rdf::Options::default())
+		.unwrap();
+	rdf::canonical_nquads::<Identity>(&quads)
+}
+
+#[test]
+fn custom_rdf_list() {
+	let doc = expand(
+		r#"{
+			"@id": "http://example.org/s",
+			"@type": "http://example.org/T",
+			"http://example.org/p": { "@list": [ "a", 1 ] }
+		}"#,
+	);
+
+	let expected = "\
+		<http://example.org/s> <http://example.org/p> _:c14n0 .\n\
+		<http://example.org/s> <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> <http://example.org/T> .\n\
+		_:c14n0 <http://www.w3.org/1999/02/22-rdf-syntax-ns#first> \"a\" .\n\
+		_:c14n0 <http://www.w3.org/1999/02/22-rdf-syntax-ns#rest> _:c14n1 .\n\
+		_:c14n1 <http://www.w3.org/1999/02/22-rdf-syntax-ns#first> \"1\"^^<http://www.w3.org/2001/XMLSchema#integer> .\n\
+		_:c14n1 <http://www.w3.org/1999/02/22-rdf-syntax-ns#rest> <http://www.w3.org/1999/02/22-rdf-syntax-ns#nil> .\n";
+
+	assert_eq!(canonical_nquads(&doc), expected)
+}
+
+#[test]
+fn custom_rdf_nested_list() {
+	let nested = expand(
+		r#"{
+			"@id": "http://example.org/s",
+			"http://example.org/p": { "@list": [ { "@list": [ "a" ] }, "b", { "@list": [] } ] }
+		}"#,
+	);
+
+	let chains = expand(
+		r#"{
+			"@context": {
+				"rdf": "http://www.w3.org/1999/02/22-rdf-syntax-ns#",
+				"first": "rdf:first",
+				"rest": { "@id": "rdf:rest", "@type": "@vocab" },
+				"nil": "rdf:nil"
+			},
+			"@id": "http://example.org/s",
+			"http://example.org/p": {
+				"first": { "first": "a", "rest": "nil" },
+				"rest": {
+					"first": "b",
+					"rest": { "first": { "@id": "rdf:nil" }, "rest": "nil" }
+				}
+			}
+		}"#,
+	);
+
+	assert_eq!(canonical_nquads(&nested), canonical_nquads(&chains))
+}
+
+#[test]
+fn custom_rdf_named_graph() {
+	let a = expand(
+		r#"{
+			"@id": "http://example.org/g",
+			"@graph": [
+				{ "@id": "_:x", "http://example.org/p": { "@id": "_:y" } },
+				{ "@id": "_:y", "http://example.org/p": { "@id": "_:x" } }
+			]
+		}"#,
+	);
+
+	let b = expand(
+		r#"{
+			"@id": "http://example.org/g",
+			"@graph": {
+				"@id": "_:n1",
+				"http://example.org/p": {
+					"@id": "_:n2",
+					"http://example.org/p": { "@id": "_:n1" }
+				}
+			}
+		}"#,
+	);
+
+	assert_eq!(canonical_nquads(&a), canonical_nquads(&b));
+	assert_eq!(
+		a.semantic_hash(Identity::default()).unwrap(),
+		canonical_nquads(&a).into_bytes()
+	)
+}
+
+#[test]
+fn custom_rdf_annotation() {
+	let doc: Value = serde_json::from_str(
+		r#"{
+			"@id": "http://example.org/bob",
+			"http://example.org/age": {
+				"@value": 23,
+				"@annotation": { "http://example.org/certainty": "high" }
+			}
+		}"#,
+	)
+	.unwrap();
+
+	let options = expansion::Options {
+		annotation: true,
+		..expansion::Options::default()
+	};
+
+	let mut loader = NoLoader::<Value>::new();
+	let doc = task::block_on(doc.expand_with_base::<context::Json<Value, IriBuf>, _>(
+		None,
+		&mut loader,
+		options,
+	))
+	.unwrap();
+
+	let expected = "\
+		<< <http://example.org/bob> <http://example.org/age> \"23\"^^<http://www.w3.org/2001/XMLSchema#integer> >> <http://example.org/certainty> \"high\" .\n\
+		<http://example.org/bob> <http://example.org/age> \"23\"^^<http://www.w3.org/2001/XMLSchema#integer> .\n";
+
+	assert_eq!(canonical_nquads(&doc), expected)
+}
+
+#[test]
+fn custom_rdf_node_annotation() {
+	let doc: Value = serde_json::from_str(
+		r#"{
+			"@id": "http://example.org/bob",
+			"http://example.org/knows": {
+				"@id": "http://example.org/alice",
+				"@annotation": { "http://example.org/since": "2020" }
+			}
+		}"#,
+	)
+	.unwrap();
+
+	let options = expansion::Options {
+		annotation: true,
+		..expansion::Options::default()
+	};
+
+	let mut loader = NoLoader::<Value>::new();
+	let doc = task::block_on(doc.expand_with_base::<context::Json<Value, IriBuf>, _>(
+		None,
+		&mut loader,
+		options,
+	))
+	.unwrap();
+
+	let expected = "\
+		<< <http://example.org/bob> <http://example.org/knows> <http://example.org/alice> >> <http://example.org/since> \"2020\" .\n\
+		<http://example.org/bob> <http://example.org/knows> <http://example.org/alice> .\n";
+
+	assert_eq!(canonical_nquads(&doc), expected)
+}
+
+#[test]
+fn custom_rdf_generalized() {
+	let doc = expand(r#"{ "@id": "http://example.org/s", "_:p": "o" }"#);
+
+	let mut warnings = Vec::new();
+	let quads = doc
+		.to_rdf_with_warnings(
+			&mut BlankIdSequence::default(),
+			&rdf::Options::default(),
+			&mut warnings,
+		)
+		.unwrap();
+	assert!(quads.is_empty());
+	assert_eq!(warnings.len(), 1);
+	assert_eq!(warnings[0].code(), WarningCode::BlankNodePredicate);
+
+	let options = rdf::Options {
+		produce_generalized_rdf: true,
+		..rdf::Options::default()
+	};
+	let quads = doc
+		.to_rdf(&mut BlankIdSequence::default(), &options)
+		.unwrap();
+	assert_eq!(quads.len(), 1);
+	assert_eq!(quads[0].to_string(), "<http://example.org/s> _:p \"o\" .")
+}
+
+#[test]
+fn custom_rdf_numbers() {
+	let doc = expand(
+		r#"{ "@id": "http://example.org/s", "http://example.org/p": [ 1.5, 2, 18446744073709551615 ] }"#,
+	);
+
+	let literals = |numbers: rdf::NumberOptions| {
+		let options = rdf::Options {
+			numbers,
+			..rdf::Options::default()
+		};
+		let mut literals: Vec<_> = doc
+			.to_rdf(&mut BlankIdSequence::default(), &options)
+			.unwrap()
+			.into_iter()
+			.map(|quad| quad.object.to_string())
+			.collect();
+		literals.sort();
+		literals
+	};
+
+	let xsd = |s: &str, ty: &str| format!("\"{}\"^^<http://www.w3.org/2001/XMLSchema#{}>", s, ty);
+
+	assert_eq!(
+		literals(rdf::NumberOptions::default()),
+		vec![
+			xsd("1.5E0", "double"),
+			xsd("18446744073709551615", "integer"),
+			xsd("2", "integer")
+		]
+	);
+
+	let decimal = rdf::NumberOptions {
+		decimal: true,
+		..rdf::NumberOptions::default()
+	};
+	assert_eq!(
+		literals(decimal),
+		vec![
+			xsd("1.5", "decimal"),
+			xsd("18446744073709551615", "integer"),
+			xsd("2", "integer")
+		]
+	);
+
+	let preserve = rdf::NumberOptions {
+		preserve_lexical_form: true,
+		..rdf::NumberOptions::default()
+	};
+	assert_eq!(literals(preserve)[0], xsd("1.5", "double"))
+}
@@ -9,8 +9,8 @@ extern crate json_ld;
 use async_std::task;
 use iref::{{Iri, IriBuf}};
 use json_ld::{{
-	context::{{self, Loader as ContextLoader, Local, ProcessingOptions}},
-	expansion,
+	context::{{self, ProcessingOptions}},
+	expansion::{{self, ExpandContext}},
 	util::{{json_ld_eq, AsJson}},
 	Document, ErrorCode, FsLoader, Loader, ProcessingMode,
 }};
@@ -50,17 +50,16 @@ fn positive_test(options: Options, input_url: Iri, base_url: Iri, output_url: Ir
 	let mut input_context: context::Json<Value, IriBuf> = context::Json::new(Some(base_url));
 
 	if let Some(context_url) = options.context {{
-		let local_context = task::block_on(loader.load_context(context_url))
-			.unwrap()
-			.into_context();
-		input_context = task::block_on(local_context.process_with(
-			&input_context,
-			&mut loader,
-			Some(base_url),
-			options.into(),
-		))
-		.unwrap()
-		.into_inner();
+		input_context = task::block_on(
+			ExpandContext::Iri(context_url.into()).process::<Value, _, _, _>(
+				&input_context,
+				&mut loader,
+				Some(base_url),
+				(),
+				options.into(),
+			),
+		)
+		.unwrap();
 	}}
 
 	let output = task::block_on(input.expand_with(
@@ -96,17 +95,16 @@ fn negative_test(options: Options, input_url: Iri, base_url: Iri, error_code: Er
 	let mut input_context: context::Json<Value, IriBuf> = context::Json::new(Some(base_url));
 
 	if let Some(context_url) = options.context {{
-		let local_context = task::block_on(loader.load_context(context_url))
-			.unwrap()
-			.into_context();
-		input_context = task::block_on(local_context.process_with(
-			&input_context,
-			&mut loader,
-			Some(base_url),
-			options.into(),
-		))
-		.unwrap()
-		.into_inner();
+		input_context = task::block_on(
+			ExpandContext::Iri(context_url.into()).process::<Value, _, _, _>(
+				&input_context,
+				&mut loader,
+				Some(base_url),
+				(),
+				options.into(),
+			),
+		)
+		.unwrap();
 	}}
 
 	let result = task::block_on(input.expand_with(